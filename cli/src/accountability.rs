@@ -0,0 +1,106 @@
+//! Break skip penalty
+//!
+//! With `[accountability] skip_penalty_minutes` set, every break skipped
+//! beyond the daily allowance takes minutes off the day's "quality focus":
+//! the focus time that was followed by real rest. Recorded focus time and
+//! goals are never changed.
+
+use anyhow::Result;
+
+use crate::config::AccountabilityConfig;
+use crate::db::Database;
+
+/// Skipped breaks on one day and what they cost
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SkipPenalty {
+    pub skipped: i32,
+    /// Skips beyond the free allowance
+    pub penalized: i32,
+    pub penalty_seconds: i32,
+}
+
+impl SkipPenalty {
+    pub fn new(config: &AccountabilityConfig, skipped: i32) -> Self {
+        let penalized = (skipped - config.free_skips as i32).max(0);
+        Self {
+            skipped,
+            penalized,
+            penalty_seconds: penalized * config.skip_penalty_minutes as i32 * 60,
+        }
+    }
+
+    /// Focus time minus the penalty, never below zero
+    pub fn quality_focus(&self, focus_seconds: i32) -> i32 {
+        (focus_seconds - self.penalty_seconds).max(0)
+    }
+
+    /// "3 breaks skipped today", or None when no break was skipped
+    pub fn warning(&self) -> Option<String> {
+        match self.skipped {
+            0 => None,
+            1 => Some("1 break skipped today".to_string()),
+            n => Some(format!("{} breaks skipped today", n)),
+        }
+    }
+}
+
+/// Today's skip penalty, or None when the rule is off
+pub fn today(db: &Database, config: &AccountabilityConfig) -> Result<Option<SkipPenalty>> {
+    if !config.enabled() {
+        return Ok(None);
+    }
+    let today = db.today()?.format("%Y-%m-%d").to_string();
+    let skipped = db.count_skipped_breaks(&today)?;
+    Ok(Some(SkipPenalty::new(config, skipped)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::SessionType;
+
+    fn config(minutes: u32, free_skips: u32) -> AccountabilityConfig {
+        AccountabilityConfig {
+            skip_penalty_minutes: minutes,
+            free_skips,
+        }
+    }
+
+    #[test]
+    fn test_penalty_after_free_skips() {
+        let penalty = SkipPenalty::new(&config(5, 1), 3);
+        assert_eq!(penalty.penalized, 2);
+        assert_eq!(penalty.penalty_seconds, 600);
+        assert_eq!(penalty.quality_focus(3600), 3000);
+        assert_eq!(penalty.quality_focus(300), 0);
+        assert_eq!(penalty.warning().as_deref(), Some("3 breaks skipped today"));
+
+        let free = SkipPenalty::new(&config(5, 2), 1);
+        assert_eq!(free.penalty_seconds, 0);
+        assert_eq!(free.warning().as_deref(), Some("1 break skipped today"));
+        assert_eq!(SkipPenalty::new(&config(5, 0), 0).warning(), None);
+    }
+
+    #[test]
+    fn test_today_counts_skipped_breaks_only() {
+        let db = Database::open_in_memory().unwrap();
+        let skip = |session_type| {
+            let id = db
+                .start_session_with_tag(session_type, None, Some(300))
+                .unwrap();
+            db.log_session_event(id, "skipped", None).unwrap();
+        };
+        skip(SessionType::ShortBreak);
+        skip(SessionType::LongBreak);
+        skip(SessionType::Work);
+        let rested = db
+            .start_session_with_tag(SessionType::ShortBreak, None, Some(300))
+            .unwrap();
+        db.complete_session(rested, 300).unwrap();
+
+        assert_eq!(today(&db, &config(0, 0)).unwrap(), None);
+        let penalty = today(&db, &config(10, 0)).unwrap().unwrap();
+        assert_eq!(penalty.skipped, 2);
+        assert_eq!(penalty.penalty_seconds, 1200);
+    }
+}
@@ -0,0 +1,220 @@
+//! Ambient sound under work sessions
+//!
+//! With `[ambience] sound` set, a few seconds of ticking, rain or white noise
+//! are synthesized to a WAV file and played over and over by the platform's
+//! audio player (see audio.rs) while a work session runs. Pausing, a break
+//! or quitting stops it; the run loop calls `Ambience::update` with what
+//! should be playing, like the tmux focus lock.
+
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+use crate::audio::{self, SAMPLE_RATE};
+use crate::config::AmbientSound;
+
+/// Length of the loop; each repeat starts a new player, so longer loops
+/// mean fewer seams
+const LOOP_SECS: u32 = 10;
+
+/// How often the player thread checks whether it should stop
+const POLL: Duration = Duration::from_millis(50);
+
+/// Small xorshift generator; the same noise every run is fine
+struct Noise(u32);
+
+impl Noise {
+    fn new() -> Self {
+        Self(0x9E37_79B9)
+    }
+
+    /// Uniform in -1.0..1.0
+    fn next(&mut self) -> f32 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 17;
+        self.0 ^= self.0 << 5;
+        self.0 as f32 / u32::MAX as f32 * 2.0 - 1.0
+    }
+}
+
+/// One loop of `sound` at `volume` (0–100) as 16-bit mono PCM
+pub fn synthesize(sound: AmbientSound, volume: u32) -> Vec<i16> {
+    let len = (SAMPLE_RATE * LOOP_SECS) as usize;
+    let gain = volume.min(100) as f32 / 100.0;
+    let mut noise = Noise::new();
+    let mut samples = vec![0.0f32; len];
+
+    match sound {
+        AmbientSound::Off => return Vec::new(),
+        AmbientSound::Ticking => {
+            // A short noise burst each second, "tick" and a duller "tock"
+            let click = (SAMPLE_RATE / 100) as usize;
+            for second in 0..LOOP_SECS as usize {
+                let start = second * SAMPLE_RATE as usize;
+                let level = if second % 2 == 0 { 0.8 } else { 0.5 };
+                for n in 0..click {
+                    let decay = (-8.0 * n as f32 / click as f32).exp();
+                    samples[start + n] = noise.next() * decay * level;
+                }
+            }
+        }
+        AmbientSound::Rain => {
+            // Low-passed noise for the hiss, with scattered drops on top
+            let mut low = 0.0;
+            for sample in samples.iter_mut() {
+                low += (noise.next() - low) * 0.15;
+                *sample = low * 0.9;
+            }
+            let drop = (SAMPLE_RATE / 200) as usize;
+            for _ in 0..LOOP_SECS * 12 {
+                let start = ((noise.next() + 1.0) / 2.0 * (len - drop) as f32) as usize;
+                let level = 0.2 + (noise.next() + 1.0) * 0.15;
+                for n in 0..drop {
+                    let decay = (-6.0 * n as f32 / drop as f32).exp();
+                    samples[start + n] += noise.next() * decay * level;
+                }
+            }
+        }
+        AmbientSound::Noise => {
+            for sample in samples.iter_mut() {
+                *sample = noise.next() * 0.4;
+            }
+        }
+    }
+
+    samples
+        .into_iter()
+        .map(|s| ((s * gain).clamp(-1.0, 1.0) * i16::MAX as f32) as i16)
+        .collect()
+}
+
+/// Write one loop to a temporary WAV file
+fn write_loop(sound: AmbientSound, volume: u32) -> std::io::Result<PathBuf> {
+    let path = std::env::temp_dir().join(format!("sandoro-ambience-{:?}.wav", sound));
+    std::fs::write(&path, audio::encode_wav(&synthesize(sound, volume)))?;
+    Ok(path)
+}
+
+/// A loop playing on a background thread
+struct Playing {
+    sound: AmbientSound,
+    volume: u32,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Playing {
+    fn start(sound: AmbientSound, volume: u32) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let thread = write_loop(sound, volume).ok().map(|path| {
+            let stop = stop.clone();
+            std::thread::spawn(move || play_until(&path, &stop))
+        });
+        Self {
+            sound,
+            volume,
+            stop,
+            thread,
+        }
+    }
+}
+
+impl Drop for Playing {
+    /// Stop the player before returning, so quitting doesn't leave it running
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Play `path` again each time it ends, until `stop` is set. Gives up when
+/// there's no player, or the player fails straight away.
+fn play_until(path: &std::path::Path, stop: &AtomicBool) {
+    while !stop.load(Ordering::Relaxed) {
+        let Some(mut cmd) = audio::player_command(path) else {
+            return;
+        };
+        let Ok(mut child) = cmd
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        else {
+            return;
+        };
+        loop {
+            if stop.load(Ordering::Relaxed) {
+                let _ = child.kill();
+                let _ = child.wait();
+                return;
+            }
+            match child.try_wait() {
+                Ok(Some(status)) if status.success() => break,
+                Ok(None) => std::thread::sleep(POLL),
+                _ => return,
+            }
+        }
+    }
+}
+
+/// Plays the ambient loop while the timer wants it
+#[derive(Default)]
+pub struct Ambience {
+    playing: Option<Playing>,
+}
+
+impl Ambience {
+    /// Call on every loop with the sound and volume that should be playing
+    /// (None for silence). Starts, stops or restarts the loop when that
+    /// changes.
+    pub fn update(&mut self, wanted: Option<(AmbientSound, u32)>) {
+        let current = self.playing.as_ref().map(|p| (p.sound, p.volume));
+        if current == wanted {
+            return;
+        }
+        // Stop the old loop before the new one starts
+        self.playing = None;
+        self.playing = wanted
+            .filter(|(sound, _)| *sound != AmbientSound::Off)
+            .map(|(sound, volume)| Playing::start(sound, volume));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn peak(samples: &[i16]) -> u16 {
+        samples.iter().map(|s| s.unsigned_abs()).max().unwrap_or(0)
+    }
+
+    #[test]
+    fn test_loops_are_full_length_and_follow_volume() {
+        for sound in [
+            AmbientSound::Ticking,
+            AmbientSound::Rain,
+            AmbientSound::Noise,
+        ] {
+            let loud = synthesize(sound, 100);
+            assert_eq!(loud.len(), (SAMPLE_RATE * LOOP_SECS) as usize);
+            assert!(peak(&loud) > 0);
+            assert!(peak(&synthesize(sound, 20)) < peak(&loud));
+            assert_eq!(peak(&synthesize(sound, 0)), 0);
+        }
+        assert!(synthesize(AmbientSound::Off, 100).is_empty());
+    }
+
+    #[test]
+    fn test_ticking_is_quiet_between_ticks() {
+        let samples = synthesize(AmbientSound::Ticking, 100);
+        let half_second = SAMPLE_RATE as usize / 2;
+        assert!(samples[half_second..half_second + 100]
+            .iter()
+            .all(|s| *s == 0));
+    }
+}
@@ -0,0 +1,301 @@
+//! Statistical helpers for stats output
+//!
+//! Rolling averages, linear-regression trends and sparkline charts
+
+use serde::Serialize;
+
+use crate::i18n;
+
+/// Direction of a trend over a window of daily values
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Trend {
+    Improving,
+    Flat,
+    Declining,
+}
+
+impl Trend {
+    /// Classify a regression slope relative to the window mean.
+    ///
+    /// A change of less than 1% of the mean per day is considered flat.
+    pub fn from_slope(slope: f64, mean: f64) -> Self {
+        if mean <= 0.0 {
+            return if slope > 0.0 {
+                Trend::Improving
+            } else {
+                Trend::Flat
+            };
+        }
+        let relative = slope / mean;
+        if relative > 0.01 {
+            Trend::Improving
+        } else if relative < -0.01 {
+            Trend::Declining
+        } else {
+            Trend::Flat
+        }
+    }
+
+    pub fn arrow(&self) -> &'static str {
+        match self {
+            Trend::Improving => "↑",
+            Trend::Flat => "→",
+            Trend::Declining => "↓",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Trend::Improving => i18n::t("improving"),
+            Trend::Flat => i18n::t("flat"),
+            Trend::Declining => i18n::t("declining"),
+        }
+    }
+}
+
+/// Arithmetic mean (0.0 for an empty slice)
+pub fn mean(values: &[f64]) -> f64 {
+    if values.is_empty() {
+        return 0.0;
+    }
+    values.iter().sum::<f64>() / values.len() as f64
+}
+
+/// Trailing rolling average; element `i` averages `values[i + 1 - window ..= i]`.
+/// The first elements average over whatever history is available.
+pub fn rolling_average(values: &[f64], window: usize) -> Vec<f64> {
+    if window == 0 {
+        return vec![0.0; values.len()];
+    }
+    (0..values.len())
+        .map(|i| {
+            let start = (i + 1).saturating_sub(window);
+            mean(&values[start..=i])
+        })
+        .collect()
+}
+
+/// Least-squares slope of `values` against their index (units per day)
+pub fn linear_regression_slope(values: &[f64]) -> f64 {
+    let n = values.len();
+    if n < 2 {
+        return 0.0;
+    }
+    let x_mean = (n - 1) as f64 / 2.0;
+    let y_mean = mean(values);
+    let mut numerator = 0.0;
+    let mut denominator = 0.0;
+    for (i, y) in values.iter().enumerate() {
+        let dx = i as f64 - x_mean;
+        numerator += dx * (y - y_mean);
+        denominator += dx * dx;
+    }
+    if denominator == 0.0 {
+        0.0
+    } else {
+        numerator / denominator
+    }
+}
+
+/// Trend of the last `window` values
+pub fn trend(values: &[f64], window: usize) -> Trend {
+    let start = values.len().saturating_sub(window);
+    let tail = &values[start..];
+    Trend::from_slope(linear_regression_slope(tail), mean(tail))
+}
+
+/// 7- and 28-day averages of daily values with their trends
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct TrendSummary {
+    pub avg_7d: f64,
+    pub avg_28d: f64,
+    pub trend_7d: Trend,
+    pub trend_28d: Trend,
+}
+
+/// Summarize daily values (oldest first)
+pub fn summarize_trend(values: &[f64]) -> TrendSummary {
+    let tail_mean = |window: usize| mean(&values[values.len().saturating_sub(window)..]);
+    TrendSummary {
+        avg_7d: tail_mean(7),
+        avg_28d: tail_mean(28),
+        trend_7d: trend(values, 7),
+        trend_28d: trend(values, 28),
+    }
+}
+
+/// Render values as a one-line sparkline chart
+pub fn sparkline(values: &[f64]) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let max = values.iter().cloned().fold(0.0_f64, f64::max);
+    values
+        .iter()
+        .map(|v| {
+            if max <= 0.0 || *v <= 0.0 {
+                ' '
+            } else {
+                let idx = ((v / max) * (BARS.len() - 1) as f64).round() as usize;
+                BARS[idx.min(BARS.len() - 1)]
+            }
+        })
+        .collect()
+}
+
+/// Projection of a weekly goal based on progress so far and weekday habits
+#[derive(Debug, Clone, PartialEq)]
+pub struct GoalForecast {
+    /// Expected total by the end of the week
+    pub projected: f64,
+    /// Day index (0 = Monday) on which the goal is expected to be reached
+    pub hit_day: Option<usize>,
+    /// Average needed per remaining day (including today) to reach the goal
+    pub required_daily: f64,
+}
+
+/// Forecast a weekly goal.
+///
+/// `today` is the day index within the week (0 = Monday), `progress` is the
+/// total so far including `today_progress`, and `weekday_averages` holds the
+/// historical average for each weekday starting from Monday.
+pub fn forecast_weekly_goal(
+    goal: f64,
+    progress: f64,
+    today: usize,
+    today_progress: f64,
+    weekday_averages: &[f64; 7],
+) -> GoalForecast {
+    let today = today.min(6);
+    let days_left = (7 - today) as f64;
+    let required_daily = ((goal - progress) / days_left).max(0.0);
+
+    let mut projected = progress;
+    let mut hit_day = (projected >= goal).then_some(today);
+
+    // Whatever is usually left of today, then the usual amount for each remaining day
+    projected += (weekday_averages[today] - today_progress).max(0.0);
+    if hit_day.is_none() && projected >= goal {
+        hit_day = Some(today);
+    }
+    for (day, avg) in weekday_averages.iter().enumerate().skip(today + 1) {
+        projected += avg;
+        if hit_day.is_none() && projected >= goal {
+            hit_day = Some(day);
+        }
+    }
+
+    GoalForecast {
+        projected,
+        hit_day,
+        required_daily,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mean() {
+        assert_eq!(mean(&[]), 0.0);
+        assert_eq!(mean(&[1.0, 2.0, 3.0]), 2.0);
+    }
+
+    #[test]
+    fn test_rolling_average() {
+        let avg = rolling_average(&[2.0, 4.0, 6.0, 8.0], 2);
+        assert_eq!(avg, vec![2.0, 3.0, 5.0, 7.0]);
+    }
+
+    #[test]
+    fn test_rolling_average_window_larger_than_data() {
+        let avg = rolling_average(&[3.0, 6.0], 7);
+        assert_eq!(avg, vec![3.0, 4.5]);
+    }
+
+    #[test]
+    fn test_slope_of_line() {
+        let slope = linear_regression_slope(&[1.0, 3.0, 5.0, 7.0]);
+        assert!((slope - 2.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_slope_of_constant() {
+        assert_eq!(linear_regression_slope(&[5.0, 5.0, 5.0]), 0.0);
+        assert_eq!(linear_regression_slope(&[5.0]), 0.0);
+    }
+
+    #[test]
+    fn test_trend_classification() {
+        assert_eq!(trend(&[10.0, 20.0, 30.0, 40.0], 4), Trend::Improving);
+        assert_eq!(trend(&[40.0, 30.0, 20.0, 10.0], 4), Trend::Declining);
+        assert_eq!(trend(&[30.0, 30.0, 30.0, 30.0], 4), Trend::Flat);
+        assert_eq!(trend(&[0.0, 0.0, 0.0], 3), Trend::Flat);
+    }
+
+    #[test]
+    fn test_trend_uses_tail_window() {
+        // Declining overall, but the last 3 values are rising
+        let values = [90.0, 80.0, 70.0, 10.0, 20.0, 30.0];
+        assert_eq!(trend(&values, 3), Trend::Improving);
+        assert_eq!(trend(&values, 6), Trend::Declining);
+    }
+
+    #[test]
+    fn test_summarize_trend() {
+        let mut values = vec![0.0; 21];
+        values.extend([10.0, 20.0, 30.0, 40.0, 50.0, 60.0, 70.0]);
+        let summary = summarize_trend(&values);
+        assert_eq!(summary.avg_7d, 40.0);
+        assert_eq!(summary.avg_28d, 10.0);
+        assert_eq!(summary.trend_7d, Trend::Improving);
+    }
+
+    #[test]
+    fn test_sparkline() {
+        let line = sparkline(&[0.0, 1.0, 4.0, 8.0]);
+        assert_eq!(line.chars().count(), 4);
+        assert!(line.starts_with(' '));
+        assert!(line.ends_with('█'));
+    }
+
+    #[test]
+    fn test_sparkline_all_zero() {
+        assert_eq!(sparkline(&[0.0, 0.0]), "  ");
+    }
+
+    #[test]
+    fn test_forecast_already_met() {
+        let f = forecast_weekly_goal(100.0, 120.0, 2, 10.0, &[10.0; 7]);
+        assert_eq!(f.hit_day, Some(2));
+        assert_eq!(f.required_daily, 0.0);
+    }
+
+    #[test]
+    fn test_forecast_hits_later_in_week() {
+        // Monday: 20 done, usual pace 20/day -> 140 by Sunday, 100 reached on Friday
+        let f = forecast_weekly_goal(100.0, 20.0, 0, 20.0, &[20.0; 7]);
+        assert_eq!(f.hit_day, Some(4));
+        assert!((f.projected - 140.0).abs() < 1e-9);
+        assert!((f.required_daily - 80.0 / 7.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_forecast_counts_rest_of_today() {
+        // Thursday with 10 of the usual 30 done so far
+        let f = forecast_weekly_goal(200.0, 100.0, 3, 10.0, &[30.0; 7]);
+        assert!((f.projected - 210.0).abs() < 1e-9);
+        assert_eq!(f.hit_day, Some(6));
+    }
+
+    #[test]
+    fn test_forecast_behind() {
+        let mut averages = [30.0; 7];
+        averages[5] = 0.0;
+        averages[6] = 0.0;
+        let f = forecast_weekly_goal(300.0, 60.0, 4, 0.0, &averages);
+        assert_eq!(f.hit_day, None);
+        assert!((f.projected - 90.0).abs() < 1e-9);
+        assert!((f.required_daily - 80.0).abs() < 1e-9);
+    }
+}
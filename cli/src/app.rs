@@ -1,23 +1,86 @@
 //! Application state management and main TUI loop
 
 use anyhow::Result;
+use chrono::{DateTime, Local, Utc};
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode},
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyModifiers,
+    },
     execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    terminal::{
+        disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen, SetTitle,
+    },
 };
 use ratatui::{backend::CrosstermBackend, Terminal};
-use std::io;
-use std::time::Duration;
-
-use crate::config::{Config, FocusMode};
-use crate::db::{Database, Session, SessionType, Tag};
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use crate::accountability::{self, SkipPenalty};
+use crate::ambience::Ambience;
+use crate::board::{self, Board};
+use crate::break_activity::{self, Heading, SnakeGame, SNAKE_STEP_TICKS};
+use crate::budget;
+use crate::capacity;
+use crate::clock;
+use crate::config::{
+    AmbientSound, BreakActivity, Config, FocusMode, LowPowerMode, NotificationRoutes, NotifyEvent,
+    ProjectConfig, StrictMode, DEFAULT_PROGRESS_CUES,
+};
+use crate::control;
+use crate::db::{
+    Database, DbTarget, SavedSession, Session, SessionMode, SessionType, Tag, TagDefaults,
+    TaskStatus,
+};
+use crate::demo::{self, Demo};
+use crate::escalation::{self, Escalation, Push, QuietHours};
+use crate::focus_dnd::FocusDnd;
+use crate::goal_reminder::{self, Reminder};
+use crate::goals;
+use crate::handoff::{self, Adoption, Beat};
+use crate::i18n;
 use crate::icons::IconType;
+use crate::insights;
 use crate::notification;
+use crate::plan::{self, WeekPlan};
+use crate::power;
+use crate::privacy::{self, Unlocker};
+use crate::recovery::{self, OpenSession, RecoveryAction};
+use crate::retention;
+use crate::routine;
+use crate::session_hooks;
+use crate::settings;
+use crate::status::{self, LiveStatus};
+use crate::storage::{self, Storage};
 use crate::sync;
-use crate::theme::Theme;
+use crate::terminal;
+use crate::theme::{Rainbow, Theme, CUSTOM_RAINBOW, RAINBOW_PALETTES, RAINBOW_SPEEDS};
+use crate::time_tracking;
 use crate::timer::{Timer, TimerState};
+use crate::tmux;
 use crate::ui;
+use crate::workspace::{self, DetectedWorkspace};
+use crate::xp;
+
+/// What a note being typed is for
+#[derive(Debug, Clone, PartialEq)]
+pub enum NoteTarget {
+    /// A session's note, by session id
+    Session(i64),
+    /// The journal note for a date (YYYY-MM-DD)
+    Day(String),
+}
+
+/// A note being typed for a session or a day
+#[derive(Debug, Clone, PartialEq)]
+pub struct NoteDraft {
+    pub target: NoteTarget,
+    /// What the note is for as shown above the input, e.g.
+    /// "10/17 09:00 · 25m · writing"
+    pub label: String,
+    pub text: String,
+}
 
 /// Current view/screen
 #[derive(Debug, Clone, PartialEq)]
@@ -32,14 +95,26 @@ pub enum SettingsItem {
     Theme,
     AccentColor,
     Icon,
+    AnimationSpeed,
+    RainbowPalette,
+    RainbowSpeed,
     WorkDuration,
     ShortBreak,
     LongBreak,
     AutoStart,
+    NotePrompt,
     FocusMode,
+    NamedTimer,
     BreakSnooze,
+    BreakActivity,
     SoundEnabled,
+    SoundChoice,
+    Ambience,
+    AmbienceVolume,
+    ProgressCues,
     DesktopNotification,
+    NotificationRouting,
+    DoNotDisturb,
     DailySessionsGoal,
     DailyMinutesGoal,
     WeeklySessionsGoal,
@@ -47,8 +122,15 @@ pub enum SettingsItem {
     TagsHeader,
     AddTag,
     DeleteTag,
+    TagDefaults,
+    TagWork,
+    TagBreak,
+    TagIcon,
+    TagColor,
     SessionsHeader,
     EditSessionTag,
+    EditSessionNote,
+    MergeSession,
     DeleteSession,
     Back,
 }
@@ -59,14 +141,26 @@ impl SettingsItem {
             Self::Theme,
             Self::AccentColor,
             Self::Icon,
+            Self::AnimationSpeed,
+            Self::RainbowPalette,
+            Self::RainbowSpeed,
             Self::WorkDuration,
             Self::ShortBreak,
             Self::LongBreak,
             Self::AutoStart,
+            Self::NotePrompt,
             Self::FocusMode,
+            Self::NamedTimer,
             Self::BreakSnooze,
+            Self::BreakActivity,
             Self::SoundEnabled,
+            Self::SoundChoice,
+            Self::Ambience,
+            Self::AmbienceVolume,
+            Self::ProgressCues,
             Self::DesktopNotification,
+            Self::NotificationRouting,
+            Self::DoNotDisturb,
             Self::DailySessionsGoal,
             Self::DailyMinutesGoal,
             Self::WeeklySessionsGoal,
@@ -74,8 +168,15 @@ impl SettingsItem {
             Self::TagsHeader,
             Self::AddTag,
             Self::DeleteTag,
+            Self::TagDefaults,
+            Self::TagWork,
+            Self::TagBreak,
+            Self::TagIcon,
+            Self::TagColor,
             Self::SessionsHeader,
             Self::EditSessionTag,
+            Self::EditSessionNote,
+            Self::MergeSession,
             Self::DeleteSession,
             Self::Back,
         ]
@@ -87,163 +188,50 @@ impl SettingsItem {
     }
 
     pub fn label_with_lang(&self, lang: &str) -> &'static str {
-        let is_ja = lang == "ja";
-        match self {
-            Self::Theme => {
-                if is_ja {
-                    "テーマ"
-                } else {
-                    "Theme"
-                }
-            }
-            Self::AccentColor => {
-                if is_ja {
-                    "アクセントカラー"
-                } else {
-                    "Accent Color"
-                }
-            }
-            Self::Icon => {
-                if is_ja {
-                    "アイコン"
-                } else {
-                    "Icon"
-                }
-            }
-            Self::WorkDuration => {
-                if is_ja {
-                    "作業時間"
-                } else {
-                    "Work Duration"
-                }
-            }
-            Self::ShortBreak => {
-                if is_ja {
-                    "短い休憩"
-                } else {
-                    "Short Break"
-                }
-            }
-            Self::LongBreak => {
-                if is_ja {
-                    "長い休憩"
-                } else {
-                    "Long Break"
-                }
-            }
-            Self::AutoStart => {
-                if is_ja {
-                    "自動開始"
-                } else {
-                    "Auto Start"
-                }
-            }
-            Self::FocusMode => {
-                if is_ja {
-                    "フォーカスモード"
-                } else {
-                    "Focus Mode"
-                }
-            }
-            Self::BreakSnooze => {
-                if is_ja {
-                    "休憩延長"
-                } else {
-                    "Break Snooze"
-                }
-            }
-            Self::SoundEnabled => {
-                if is_ja {
-                    "サウンド"
-                } else {
-                    "Sound"
-                }
-            }
-            Self::DesktopNotification => {
-                if is_ja {
-                    "デスクトップ通知"
-                } else {
-                    "Desktop Notification"
-                }
-            }
-            Self::DailySessionsGoal => {
-                if is_ja {
-                    "1日のセッション目標"
-                } else {
-                    "Daily Sessions Goal"
-                }
-            }
-            Self::DailyMinutesGoal => {
-                if is_ja {
-                    "1日の作業時間目標"
-                } else {
-                    "Daily Minutes Goal"
-                }
-            }
-            Self::WeeklySessionsGoal => {
-                if is_ja {
-                    "週間セッション目標"
-                } else {
-                    "Weekly Sessions Goal"
-                }
-            }
-            Self::WeeklyMinutesGoal => {
-                if is_ja {
-                    "週間作業時間目標"
-                } else {
-                    "Weekly Minutes Goal"
-                }
-            }
-            Self::TagsHeader => {
-                if is_ja {
-                    "── タグ ──"
-                } else {
-                    "── Tags ──"
-                }
-            }
-            Self::AddTag => {
-                if is_ja {
-                    "新しいタグを追加"
-                } else {
-                    "Add New Tag"
-                }
-            }
-            Self::DeleteTag => {
-                if is_ja {
-                    "タグを削除"
-                } else {
-                    "Delete Tag"
-                }
-            }
-            Self::SessionsHeader => {
-                if is_ja {
-                    "── セッション履歴 ──"
-                } else {
-                    "── Session History ──"
-                }
-            }
-            Self::EditSessionTag => {
-                if is_ja {
-                    "セッションのタグを変更"
-                } else {
-                    "Change Session Tag"
-                }
-            }
-            Self::DeleteSession => {
-                if is_ja {
-                    "セッションを削除"
-                } else {
-                    "Delete Session"
-                }
-            }
-            Self::Back => {
-                if is_ja {
-                    "← タイマーに戻る"
-                } else {
-                    "← Back to Timer"
-                }
-            }
-        }
+        let label = match self {
+            Self::Theme => "Theme",
+            Self::AccentColor => "Accent Color",
+            Self::Icon => "Icon",
+            Self::AnimationSpeed => "Animation Speed",
+            Self::RainbowPalette => "Rainbow Palette",
+            Self::RainbowSpeed => "Rainbow Speed",
+            Self::WorkDuration => "Work Duration",
+            Self::ShortBreak => "Short Break",
+            Self::LongBreak => "Long Break",
+            Self::AutoStart => "Auto Start",
+            Self::NotePrompt => "Session Notes",
+            Self::FocusMode => "Focus Mode",
+            Self::NamedTimer => "Timer",
+            Self::BreakSnooze => "Break Snooze",
+            Self::BreakActivity => "Break Activity",
+            Self::SoundEnabled => "Sound",
+            Self::SoundChoice => "Notification Sound",
+            Self::Ambience => "Ambience",
+            Self::AmbienceVolume => "Ambience Volume",
+            Self::ProgressCues => "Progress Cues",
+            Self::DesktopNotification => "Desktop Notification",
+            Self::NotificationRouting => "Notification Routing",
+            Self::DoNotDisturb => "Do Not Disturb",
+            Self::DailySessionsGoal => "Daily Sessions Goal",
+            Self::DailyMinutesGoal => "Daily Minutes Goal",
+            Self::WeeklySessionsGoal => "Weekly Sessions Goal",
+            Self::WeeklyMinutesGoal => "Weekly Minutes Goal",
+            Self::TagsHeader => "── Tags ──",
+            Self::AddTag => "Add New Tag",
+            Self::DeleteTag => "Delete Tag",
+            Self::TagDefaults => "Tag Defaults",
+            Self::TagWork => "Tag Work",
+            Self::TagBreak => "Tag Break",
+            Self::TagIcon => "Tag Icon",
+            Self::TagColor => "Tag Color",
+            Self::SessionsHeader => "── Session History ──",
+            Self::EditSessionTag => "Change Session Tag",
+            Self::EditSessionNote => "Edit Session Note",
+            Self::MergeSession => "Merge With Previous",
+            Self::DeleteSession => "Delete Session",
+            Self::Back => "← Back to Timer",
+        };
+        i18n::t_in(lang, label)
     }
 
     /// Returns true if this item is a header/separator (not selectable for editing)
@@ -266,6 +254,8 @@ pub struct App {
     pub theme: Theme,
     /// Configuration
     pub config: Config,
+    /// Config as last loaded or saved; saves write what changed since
+    saved_config: Config,
     /// Theme selection index (when editing theme)
     pub theme_index: usize,
     /// Available themes
@@ -286,22 +276,49 @@ pub struct App {
     pub animation_frame: u8,
     /// Tick counter for animation timing (animate every N ticks)
     animation_tick: u8,
-    /// Rainbow color frame counter (0-6 for 7 colors)
+    /// Rainbow color frame counter, one step per palette color
     pub rainbow_frame: u8,
     /// Rainbow animation tick counter
     rainbow_tick: u8,
-    /// Database for session recording
-    db: Option<Database>,
+    /// Storage for session recording
+    db: Option<Box<dyn Storage>>,
     /// Current session ID being recorded
     current_session_id: Option<i64>,
     /// Today's total work time in seconds
     pub today_work_seconds: i32,
     /// Today's completed sessions count
     pub today_sessions: i32,
+    /// Today's completed work by tag, for the goal bar
+    pub today_by_tag: Vec<(Option<Tag>, i32)>,
+    /// Breaks skipped today and their cost, when the skip penalty is on
+    pub skip_penalty: Option<SkipPenalty>,
+    /// Suggested work length, shown until the next work session starts
+    pub work_length_tip: Option<String>,
+    /// Today's goal is beyond what's been done on this weekday
+    pub capacity_warning: Option<String>,
+    /// Why the timer stopped, after another device took the session over
+    pub handoff_notice: Option<String>,
+    /// The last tmux focus-lock command that failed
+    pub tmux_error: Option<String>,
+    /// Why do not disturb couldn't be switched, the last time it failed
+    pub dnd_error: Option<String>,
+    /// Why the last thing asked for from a popup failed, e.g. saving the
+    /// journal; shown under the timer until the next work session
+    pub action_error: Option<String>,
+    /// Buttons clicked on session-complete notifications: the sending end
+    /// goes out with each notification, the run loop drains the other
+    clicks: (
+        mpsc::Sender<notification::Clicked>,
+        mpsc::Receiver<notification::Clicked>,
+    ),
     /// Current streak (consecutive days)
     pub current_streak: i32,
     /// Longest streak ever
     pub longest_streak: i32,
+    /// Total XP, when `[xp]` is enabled and sessions are kept in SQLite
+    pub xp: Option<i64>,
+    /// This week's plan, when there is one
+    pub week_plan: Option<WeekPlan>,
     /// Yesterday's work seconds
     pub yesterday_seconds: i32,
     /// Weekly average work seconds
@@ -312,6 +329,18 @@ pub struct App {
     pub available_tags: Vec<Tag>,
     /// Currently selected tag index (None = no tag)
     pub selected_tag_index: Option<usize>,
+    /// Routine being walked through, block by block
+    pub routine: Option<routine::Run>,
+    /// Selected row of the routines picker (`o`), while it's open
+    pub routine_picker: Option<usize>,
+    /// Event selected in the notification routing editor, while it's open
+    pub routes_editor: Option<usize>,
+    /// Storage and stats still being loaded at startup
+    pub startup: Option<mpsc::Receiver<Startup>>,
+    /// Keys pressed while storage was still loading, applied once it's in
+    pending_keys: Vec<KeyEvent>,
+    /// Last status to look for crashed sessions with, once storage is open
+    recover_from: Option<Option<LiveStatus>>,
     /// Settings list scroll offset for visible items
     pub settings_scroll_offset: usize,
     /// Input buffer for adding new tag
@@ -320,16 +349,256 @@ pub struct App {
     pub tag_input_mode: bool,
     /// Index for selecting tag to delete (cycles through available tags)
     pub delete_tag_index: usize,
+    /// Tag whose defaults are edited in settings
+    pub tag_defaults_index: usize,
+    /// Defaults of the tag at tag_defaults_index
+    pub tag_settings: TagDefaults,
+    /// Defaults of the selected tag, in effect on the timer
+    pub tag_defaults: TagDefaults,
     /// Recent sessions (with tags) for editing
     pub recent_sessions: Vec<(Session, Option<Tag>)>,
     /// Index for selecting session to edit/delete
     pub session_edit_index: usize,
+    /// Filter for the session history (searches tags and notes)
+    pub session_search: String,
+    /// Whether we're typing into the session history filter
+    pub session_search_mode: bool,
     /// Index for selecting tag when editing session tag
     pub session_tag_edit_index: Option<usize>,
+    /// Note being typed, after a work session or from the history
+    pub note_draft: Option<NoteDraft>,
+    /// When `i` was pressed during work, while waiting for the category key
+    pub interruption_at: Option<DateTime<Utc>>,
+    /// Interruptions noted in the current work session
+    pub interruptions: u32,
+    /// Interruptions per session in the history, for sessions that have any
+    pub history_interruptions: HashMap<i64, u32>,
+    /// Workspace detected from the current directory or tmux session
+    pub workspace: Option<DetectedWorkspace>,
+    /// Ticks elapsed in the current break (drives break activities)
+    pub break_ticks: u32,
+    /// Snake game for the current break
+    pub snake: Option<SnakeGame>,
+    /// Which progress cue thresholds have fired this work session
+    cues_fired: Vec<bool>,
+    /// Tags already warned about today for going over budget
+    budget_alerts: budget::Alerts,
+    /// Last snapshot written to the live status file
+    published_status: Option<LiveStatus>,
+    /// Low-power mode is active: one tick per second, no rainbow cycling
+    pub low_power: bool,
+    /// Low-power mode was requested with --low-power
+    force_low_power: bool,
+    /// When the power source was last checked
+    power_checked_at: Option<Instant>,
+    /// When the lock file was last checked while locked
+    lock_checked_at: Option<Instant>,
+    /// Save config changes and sync sessions (off in tests)
+    persist: bool,
+    /// Running on an in-memory database with --ephemeral; nothing is saved
+    pub ephemeral: bool,
+    /// Showcase run from `sandoro demo` (also ephemeral)
+    pub demo: Option<Demo>,
+    /// Break that ended on its own and can still be snoozed: (state,
+    /// session count during it). Cleared once work starts.
+    ended_break: Option<(TimerState, u32)>,
+    /// Phone pushes for that break while work isn't started
+    escalation: Option<Escalation>,
+    /// End-of-day reminder of a daily goal not met yet
+    goal_reminder: Reminder,
+    /// The once-a-day journal prompt, at the goal reminder's time
+    journal_reminder: Reminder,
+    /// Three-line timer requested with --compact (it's also used whenever
+    /// the terminal is too short for the full one)
+    pub compact: bool,
+    /// Sessions left open by a crashed run, awaiting a decision
+    pub recovery: Vec<OpenSession>,
+    /// Session the last run was quit in the middle of, offered for resuming
+    pub saved_session: Option<SavedSession>,
+    /// Whether the "today" board pane is shown (and takes board keys)
+    pub board_open: bool,
+    /// Tasks and cursor for the board pane
+    pub board: Board,
+    /// Task that work sessions are recorded against
+    pub bound_task: Option<i64>,
+    /// Private mode: tags, notes and history are hidden until unlocked
+    pub locked: bool,
+    /// Keys typed towards the unlock sequence
+    pub unlocker: Unlocker,
+    /// `.sandoro.toml` laid over config.toml for this run
+    pub project: Option<ProjectConfig>,
+    /// Key held back by strict mode and how many times in a row it's been
+    /// pressed
+    pub strict_key: Option<(char, u32)>,
+}
+
+/// Normal tick interval; break activities and animations count in these
+const TICK: Duration = Duration::from_millis(100);
+/// Tick interval in low-power mode
+const LOW_POWER_TICK: Duration = Duration::from_secs(1);
+/// How often to re-check the power source in auto mode
+const POWER_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+/// How often a locked timer looks for `sandoro unlock` from another pane
+const LOCK_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Metadata key holding the id of the last selected tag
+const LAST_TAG_KEY: &str = "last_tag_id";
+/// Metadata key holding the session quit mid-way, as JSON
+const SAVED_SESSION_KEY: &str = "saved_session";
+/// Longest note typed in the TUI
+const NOTE_MAX_CHARS: usize = 200;
+/// Presses in a row that get a key through strict mode's `confirm`
+pub const STRICT_PRESSES: u32 = 3;
+/// Most keys held back while storage loads; more are dropped
+const PENDING_KEYS_MAX: usize = 16;
+
+/// What's on screen, used to skip redraws in low-power mode
+#[derive(Debug, PartialEq)]
+struct FrameKey {
+    display_time: String,
+    state: TimerState,
+    paused: bool,
+    animation_frame: u8,
+    break_ticks: u32,
+    clock: String,
+    loading: bool,
 }
 
 impl App {
     pub fn new(config: Config) -> Self {
+        // Storage, stats and the pending sync come in the background; the
+        // timer draws right away with zeros until they arrive
+        let startup = Startup::spawn(&config);
+        // Retry time entries that couldn't be sent last time
+        time_tracking::flush_in_background(&config);
+
+        let mut app = Self::with_database(config, None, true);
+        app.startup = Some(startup);
+        // Another timer that's still running owns its open session. Checked
+        // now, before this timer's own status is published.
+        if status::read().ok().flatten().is_none() {
+            app.recover_from = Some(status::read_last().ok().flatten());
+        }
+        app
+    }
+
+    /// Take in what the startup thread loaded, if it's there yet. With
+    /// `wait`, block until it is: for anything that needs storage or tags.
+    pub fn finish_startup(&mut self, wait: bool) {
+        let Some(receiver) = &self.startup else {
+            return;
+        };
+        let received = if wait {
+            receiver.recv().ok()
+        } else {
+            match receiver.try_recv() {
+                Ok(startup) => Some(startup),
+                Err(mpsc::TryRecvError::Empty) => return,
+                Err(mpsc::TryRecvError::Disconnected) => None,
+            }
+        };
+        self.startup = None;
+        if let Some(Startup { db, loaded }) = received {
+            self.db = db;
+            self.apply_loaded(loaded);
+            if let Some(last_status) = self.recover_from.take() {
+                self.load_recovery(last_status.as_ref());
+                self.load_saved_session();
+            }
+        }
+        for key in std::mem::take(&mut self.pending_keys) {
+            self.handle_key(key);
+        }
+    }
+
+    /// Still waiting on the startup thread; stats show as placeholders
+    pub fn is_loading(&self) -> bool {
+        self.startup.is_some()
+    }
+
+    fn apply_loaded(&mut self, loaded: Loaded) {
+        if self.selected_tag_index.is_none() {
+            self.selected_tag_index = loaded.last_tag_index(&self.config);
+        }
+        self.apply_tag_defaults();
+        self.load_tag_settings();
+        self.today_work_seconds = loaded.today_work_seconds;
+        self.today_sessions = loaded.today_sessions;
+        self.today_by_tag = loaded.today_by_tag;
+        self.skip_penalty = loaded.skip_penalty;
+        self.work_length_tip = loaded.work_length_tip;
+        self.capacity_warning = loaded.capacity_warning;
+        self.current_streak = loaded.current_streak;
+        self.longest_streak = loaded.longest_streak;
+        self.xp = loaded.xp;
+        self.week_plan = loaded.week_plan;
+        self.yesterday_seconds = loaded.yesterday_seconds;
+        self.week_avg_seconds = loaded.week_avg_seconds;
+        self.total_sessions = loaded.total_sessions;
+        self.available_tags = loaded.available_tags;
+        self.recent_sessions = loaded.recent_sessions;
+        self.count_history_interruptions();
+    }
+
+    /// App on a throwaway in-memory database for --ephemeral: no sync,
+    /// recovery, config saves or status file, so no files are touched
+    pub fn ephemeral(config: Config) -> Result<Self> {
+        let db = Database::open_target(DbTarget::Memory)?;
+        Ok(Self::in_memory(config, db))
+    }
+
+    /// Ephemeral app on made-up history with a sped-up, already running
+    /// timer, for `sandoro demo`
+    pub fn demo(mut config: Config, demo: Demo) -> Result<Self> {
+        let db = Database::open_target(DbTarget::Memory)?;
+        demo::seed(&db, clock::now().date_naive())?;
+        config.timer.auto_start = true;
+        // Nothing should pop up or chime over a recording
+        config.notifications.sound = false;
+        config.notifications.desktop = false;
+
+        let mut app = Self::in_memory(config, db);
+        app.timer.speed = demo.speed;
+        app.demo = Some(demo);
+        app.toggle_pause();
+        Ok(app)
+    }
+
+    fn in_memory(config: Config, db: Database) -> Self {
+        let mut app = Self::with_database(config, Some(Box::new(db)), false);
+        app.ephemeral = true;
+        app
+    }
+
+    /// App backed by the given database that never writes config.toml or
+    /// syncs, for driving the TUI in tests
+    #[cfg(test)]
+    pub fn headless(config: Config, db: Database) -> Self {
+        Self::with_database(config, Some(Box::new(db)), false)
+    }
+
+    /// App whose storage is still on its way, as right after launch; send
+    /// the startup to finish loading
+    #[cfg(test)]
+    pub fn deferred(config: Config) -> (Self, mpsc::Sender<Startup>) {
+        let (tx, rx) = mpsc::channel();
+        let mut app = Self::with_database(config, None, false);
+        app.startup = Some(rx);
+        (app, tx)
+    }
+
+    /// Where notifications send their button clicks, for tests to click
+    #[cfg(test)]
+    pub fn notification_clicks(&self) -> mpsc::Sender<notification::Clicked> {
+        self.clicks.0.clone()
+    }
+
+    /// The SQLite database, for features other backends don't have
+    pub fn database(&self) -> Option<&Database> {
+        self.db.as_deref().and_then(|d| d.sqlite())
+    }
+
+    fn with_database(config: Config, db: Option<Box<dyn Storage>>, persist: bool) -> Self {
         use crate::theme::available_accent_colors;
 
         // Apply accent color to theme
@@ -360,67 +629,11 @@ impl App {
         let focus_mode_index = match config.focus.mode {
             FocusMode::Classic => 0,
             FocusMode::Flowtime => 1,
+            FocusMode::Timer => 2,
         };
 
-        // Open database and get stats
-        let db = Database::open().ok();
-
-        // Try to sync any pending sessions from previous offline usage
-        if let Some(ref d) = db {
-            let _ = sync::try_sync_pending(d.connection());
-        }
-
-        let (today_work_seconds, today_sessions) = db
-            .as_ref()
-            .and_then(|d| d.get_today_stats().ok())
-            .map(|s| (s.total_work_seconds, s.sessions_completed))
-            .unwrap_or((0, 0));
-
-        // Get streak info
-        let (current_streak, longest_streak) = db
-            .as_ref()
-            .and_then(|d| d.get_streak().ok())
-            .map(|s| (s.current, s.longest))
-            .unwrap_or((0, 0));
-
-        // Get week stats for average
-        let week_avg_seconds = db
-            .as_ref()
-            .and_then(|d| d.get_week_stats().ok())
-            .map(|s| s.total_work_seconds / 7)
-            .unwrap_or(0);
-
-        // Get yesterday's stats
-        let yesterday_seconds = db
-            .as_ref()
-            .and_then(|d| {
-                use chrono::{Duration, Local};
-                let yesterday = (Local::now() - Duration::days(1))
-                    .format("%Y-%m-%d")
-                    .to_string();
-                d.get_date_stats(&yesterday).ok()
-            })
-            .map(|s| s.total_work_seconds)
-            .unwrap_or(0);
-
-        // Get total sessions count
-        let total_sessions = db
-            .as_ref()
-            .and_then(|d| d.get_month_stats().ok())
-            .map(|s| s.sessions_completed)
-            .unwrap_or(0);
-
-        // Load available tags
-        let available_tags = db
-            .as_ref()
-            .and_then(|d| d.get_all_tags().ok())
-            .unwrap_or_default();
-
-        // Load recent sessions for editing
-        let recent_sessions = db
-            .as_ref()
-            .and_then(|d| d.get_recent_sessions(20).ok())
-            .unwrap_or_default();
+        let loaded = Loaded::load(db.as_deref(), &config);
+        let selected_tag_index = loaded.last_tag_index(&config);
 
         // Create timer and set flowtime mode
         let mut timer = Timer::with_sessions(
@@ -430,13 +643,15 @@ impl App {
             config.timer.sessions_until_long,
         );
         timer.set_flowtime(config.focus.mode == FocusMode::Flowtime);
+        timer.set_named_timer(config.focus.timer_minutes());
 
-        Self {
+        let mut app = Self {
             timer,
             should_quit: false,
             view: AppView::Timer,
             settings_index: 0,
             theme,
+            saved_config: config.clone(),
             config,
             theme_index,
             available_themes,
@@ -452,92 +667,392 @@ impl App {
             rainbow_tick: 0,
             db,
             current_session_id: None,
-            today_work_seconds,
-            today_sessions,
-            current_streak,
-            longest_streak,
-            yesterday_seconds,
-            week_avg_seconds,
-            total_sessions,
-            available_tags,
-            selected_tag_index: None,
+            today_work_seconds: loaded.today_work_seconds,
+            today_sessions: loaded.today_sessions,
+            today_by_tag: loaded.today_by_tag,
+            skip_penalty: loaded.skip_penalty,
+            work_length_tip: loaded.work_length_tip,
+            capacity_warning: loaded.capacity_warning,
+            handoff_notice: None,
+            tmux_error: None,
+            dnd_error: None,
+            action_error: None,
+            clicks: mpsc::channel(),
+            current_streak: loaded.current_streak,
+            longest_streak: loaded.longest_streak,
+            xp: loaded.xp,
+            week_plan: loaded.week_plan,
+            yesterday_seconds: loaded.yesterday_seconds,
+            week_avg_seconds: loaded.week_avg_seconds,
+            total_sessions: loaded.total_sessions,
+            available_tags: loaded.available_tags,
+            selected_tag_index,
+            routine: None,
+            routine_picker: None,
+            routes_editor: None,
+            startup: None,
+            pending_keys: Vec::new(),
+            recover_from: None,
             settings_scroll_offset: 0,
             tag_input: String::new(),
             tag_input_mode: false,
+            session_search: String::new(),
+            session_search_mode: false,
             delete_tag_index: 0,
-            recent_sessions,
+            tag_defaults_index: 0,
+            tag_settings: TagDefaults::default(),
+            tag_defaults: TagDefaults::default(),
+            recent_sessions: loaded.recent_sessions,
             session_edit_index: 0,
             session_tag_edit_index: None,
-        }
+            note_draft: None,
+            interruption_at: None,
+            interruptions: 0,
+            history_interruptions: HashMap::new(),
+            workspace: None,
+            break_ticks: 0,
+            snake: None,
+            cues_fired: Vec::new(),
+            budget_alerts: budget::Alerts::default(),
+            published_status: None,
+            low_power: false,
+            force_low_power: false,
+            power_checked_at: None,
+            lock_checked_at: None,
+            persist,
+            ephemeral: false,
+            demo: None,
+            ended_break: None,
+            escalation: None,
+            goal_reminder: Reminder::default(),
+            journal_reminder: Reminder::default(),
+            compact: false,
+            recovery: Vec::new(),
+            saved_session: None,
+            board_open: false,
+            board: Board::default(),
+            bound_task: None,
+            // Tests and ephemeral runs don't share the lock on disk
+            locked: persist && privacy::is_locked(),
+            unlocker: Unlocker::default(),
+            project: None,
+            strict_key: None,
+        };
+        app.apply_tag_defaults();
+        app.load_tag_settings();
+        app.count_history_interruptions();
+        app
     }
 
     pub fn tick(&mut self) {
-        // Rainbow animation runs in both Timer and Settings views
-        self.rainbow_tick = (self.rainbow_tick + 1) % 5;
-        if self.rainbow_tick == 0 {
-            // Cycle through 7 rainbow colors
-            self.rainbow_frame = (self.rainbow_frame + 1) % 7;
+        self.finish_startup(false);
+        self.check_escalation(clock::now());
+        self.check_goal_reminder(clock::now());
+        self.check_journal_prompt(clock::now());
+
+        // Rainbow animation runs in both Timer and Settings views; it's held
+        // still in low-power mode
+        if !self.low_power {
+            let rainbow = self.rainbow();
+            self.rainbow_tick += 1;
+            if self.rainbow_tick >= rainbow.ticks_per_color {
+                // Step to the palette's next color
+                self.rainbow_tick = 0;
+                self.rainbow_frame = (self.rainbow_frame + 1) % rainbow.colors.len() as u8;
+            }
         }
 
         if self.view == AppView::Timer {
             let was_running = !self.timer.is_paused;
             let old_state = self.timer.state;
+            let old_count = self.timer.session_count;
+            let phase_seconds = self.timer.phase_seconds();
 
             self.timer.tick();
 
-            // Check if timer completed and transitioned
-            if was_running && self.timer.is_paused && self.timer.state != old_state {
+            // Check if timer completed and transitioned; a named timer
+            // starts over in the same state
+            let ended = self.timer.state != old_state || old_state == TimerState::Timer;
+            if was_running && self.timer.is_paused && ended {
+                if !old_state.is_focus() {
+                    self.ended_break = Some((old_state, old_count));
+                    if self.config.escalation.enabled().is_some() {
+                        self.escalation = Some(Escalation::new(clock::now()));
+                    }
+                    // A break earns XP only when it runs to the end; skipped
+                    // breaks are recorded as sessions too but don't get here
+                    let awarded = match (self.xp.is_some(), self.database()) {
+                        (true, Some(db)) => xp::award_break(db).ok(),
+                        _ => None,
+                    };
+                    if let Some(total) = awarded {
+                        self.set_xp(total);
+                    }
+                }
                 // Record session completion
-                self.record_session_complete(old_state, true);
+                self.record_session_complete(old_state, true, phase_seconds);
+                let hook = if old_state.is_focus() {
+                    session_hooks::Event::WorkComplete
+                } else {
+                    session_hooks::Event::BreakComplete
+                };
+                self.fire_hook(hook, old_state, phase_seconds);
 
                 // Send notification
+                let event = if old_state.is_focus() {
+                    NotifyEvent::WorkEnd
+                } else {
+                    NotifyEvent::BreakEnd
+                };
+                let continues = self.routine.is_some()
+                    || (self.config.timer.auto_start && old_state != TimerState::Timer);
+                let actions = notification::Actions {
+                    buttons: notification::session_actions(old_state, continues),
+                    phase: self.timer.state,
+                    sender: self.clicks.0.clone(),
+                };
                 notification::notify_session_complete(
                     old_state,
-                    self.config.notifications.sound,
-                    self.config.notifications.desktop,
+                    &self.config.notifications.sound_name,
+                    self.delivery(event),
+                    self.locked,
+                    Some(actions),
                 );
 
-                // Auto-start if enabled
-                if self.config.timer.auto_start {
+                // A routine moves on by itself; otherwise auto-start if enabled
+                if self.routine.is_some() {
+                    self.next_routine_block(true);
+                } else if self.config.timer.auto_start && old_state != TimerState::Timer {
                     self.timer.toggle_pause();
                     // Start recording new session if transitioning to Work
                     if self.timer.state == TimerState::Work {
                         self.start_session_recording();
+                    } else {
+                        self.fire_hook(
+                            session_hooks::Event::BreakStart,
+                            self.timer.state,
+                            self.timer.phase_seconds(),
+                        );
                     }
                 }
             }
 
-            // Advance animation frame every 5 ticks (500ms at 100ms tick rate)
-            self.animation_tick = (self.animation_tick + 1) % 5;
+            self.tick_break_activity();
+            self.tick_progress_cues();
+            self.tick_budget();
+            if self.demo.as_mut().is_some_and(|demo| demo.cycle_due()) {
+                self.next_demo_look();
+            }
+
+            // Advance animation frame every few ticks (500ms at normal speed),
+            // or on every tick when ticks are slower than that. With animation
+            // off the icon holds its first frame and only shows progress.
+            let Some(per_frame) = self.config.appearance.animation_speed.ticks_per_frame() else {
+                self.animation_tick = 0;
+                self.animation_frame = 0;
+                return;
+            };
+            self.animation_tick =
+                (self.animation_tick + self.tick_step() as u8).min(per_frame) % per_frame;
             if self.animation_tick == 0 && !self.timer.is_paused {
-                // Get max frames for current icon
-                let max_frames = match self.current_icon() {
-                    IconType::Hourglass => 4,
-                    IconType::Coffee => 4,
-                    IconType::Tomato => 2,
-                    IconType::Progress => 2,
-                    _ => 1,
-                };
+                let max_frames = self.current_icon().frame_count();
                 self.animation_frame = (self.animation_frame + 1) % max_frames;
             }
         }
     }
 
+    /// Move the demo on to the next theme and icon, skipping the blank icon
+    fn next_demo_look(&mut self) {
+        self.theme_index = (self.theme_index + 1) % self.available_themes.len();
+        self.icon_index = (self.icon_index + 1) % self.available_icons.len();
+        if self.available_icons[self.icon_index] == IconType::None {
+            self.icon_index = (self.icon_index + 1) % self.available_icons.len();
+        }
+        self.apply_settings();
+    }
+
+    fn is_break(&self) -> bool {
+        matches!(
+            self.timer.state,
+            TimerState::ShortBreak | TimerState::LongBreak
+        )
+    }
+
+    /// Advance the break activity while a break is running
+    fn tick_break_activity(&mut self) {
+        if !self.is_break() {
+            self.break_ticks = 0;
+            self.snake = None;
+            return;
+        }
+        if self.config.focus.break_activity == BreakActivity::Snake {
+            if self.snake.is_none() {
+                self.snake = Some(SnakeGame::new(snake_seed()));
+            }
+        } else {
+            self.snake = None;
+        }
+        if !self.timer.is_paused {
+            self.break_ticks = self.break_ticks.wrapping_add(self.tick_step());
+            if let Some(game) = self.snake.as_mut() {
+                if self.break_ticks.is_multiple_of(SNAKE_STEP_TICKS) {
+                    game.step();
+                }
+            }
+        }
+    }
+
+    /// Today's time on the selected tag against its budget, counting the
+    /// work session in progress
+    pub fn budget_usage(&self) -> Option<budget::Usage> {
+        let tag = self.selected_tag()?;
+        let budget_minutes = budget::budget_for(&self.config.budgets, &tag.name)?;
+        let running = match self.current_session_id {
+            Some(_) if self.timer.state.is_focus() => self.timer.seconds_done(),
+            _ => 0,
+        };
+        Some(budget::Usage {
+            tag: tag.name.clone(),
+            seconds: budget::seconds_for(&self.today_by_tag, &tag.name) + running as i32,
+            budget_minutes,
+        })
+    }
+
+    /// Warn once a day when running work takes its tag over budget
+    fn tick_budget(&mut self) {
+        if self.timer.is_paused || !self.timer.state.is_focus() {
+            return;
+        }
+        let Some(usage) = self.budget_usage().filter(|u| u.is_over()) else {
+            return;
+        };
+        let today = self
+            .db
+            .as_ref()
+            .and_then(|db| db.today().ok())
+            .unwrap_or_else(|| clock::now().date_naive());
+        if self.budget_alerts.first_today(today, &usage.tag) {
+            notification::notify_over_budget(
+                &usage.tag,
+                &crate::format_duration(usage.budget_minutes as i32 * 60),
+                self.delivery(NotifyEvent::OverBudget),
+                self.locked,
+            );
+        }
+    }
+
+    /// How `event` is delivered; a run that isn't saved leaves no log
+    fn delivery(&self, event: NotifyEvent) -> notification::Delivery {
+        let mut delivery = notification::delivery(&self.config.notifications, event);
+        delivery.log &= self.persist;
+        delivery
+    }
+
+    /// Take a new XP total, announcing a level reached on the way
+    fn set_xp(&mut self, total: i64) {
+        if let Some(before) = self.xp {
+            let level = xp::level_for(total);
+            if level.level > xp::level_for(before).level {
+                notification::notify_level_up(
+                    &level.badge(),
+                    self.delivery(NotifyEvent::Achievement),
+                );
+            }
+        }
+        self.xp = Some(total);
+    }
+
+    /// Play the cue for a progress threshold the work phase just crossed.
+    /// Returns its step (0 for the lowest threshold), also when it stays
+    /// quiet because the timer is paused or sounds are off.
+    pub fn tick_progress_cues(&mut self) -> Option<usize> {
+        let thresholds = &self.config.notifications.progress_cues;
+        if self.cues_fired.len() != thresholds.len() {
+            self.cues_fired = vec![false; thresholds.len()];
+        }
+        if self.timer.state != TimerState::Work || self.timer.is_flowtime {
+            self.cues_fired.fill(false);
+            return None;
+        }
+
+        let progress = self.timer.progress_percent();
+        // Only the highest newly crossed threshold plays, e.g. after a snooze
+        let mut cue = None;
+        for (i, &threshold) in thresholds.iter().enumerate() {
+            if progress < threshold as f32 {
+                // Timer was reset
+                self.cues_fired[i] = false;
+            } else if !self.cues_fired[i] {
+                self.cues_fired[i] = true;
+                let step = thresholds.iter().filter(|&&t| t < threshold).count();
+                cue = cue.max(Some(step));
+            }
+        }
+
+        if let Some(step) = cue {
+            if self.config.notifications.sound && !self.timer.is_paused {
+                notification::play_progress_cue(step);
+            }
+        }
+        cue
+    }
+
+    /// Lines to draw instead of the icon, when a break activity is active
+    pub fn break_activity_lines(&self) -> Option<Vec<String>> {
+        if !self.is_break() {
+            return None;
+        }
+        let lang = &self.config.appearance.language;
+        match self.config.focus.break_activity {
+            BreakActivity::Icon => None,
+            BreakActivity::Breathing => {
+                Some(break_activity::breathing_lines(self.break_ticks, lang))
+            }
+            BreakActivity::Snake => self.snake.as_ref().map(|game| game.lines(lang)),
+        }
+    }
+
+    /// Whether direction keys currently steer the snake
+    pub fn snake_active(&self) -> bool {
+        self.is_break() && self.snake.is_some()
+    }
+
+    /// Steer the snake, or start a new game after a game over
+    pub fn snake_turn(&mut self, heading: Heading) {
+        if let Some(game) = self.snake.as_mut() {
+            if game.alive {
+                game.turn(heading);
+            } else {
+                *game = SnakeGame::new(snake_seed());
+            }
+        }
+    }
+
     pub fn toggle_pause(&mut self) {
         let was_paused = self.timer.is_paused;
         self.timer.toggle_pause();
+        self.record_pause();
 
-        // Start recording session when timer starts (only for Work sessions)
-        if was_paused && !self.timer.is_paused && self.timer.state == TimerState::Work {
+        // Start recording session when timer starts (only for Work sessions
+        // and named timers)
+        if was_paused && !self.timer.is_paused && self.timer.state.is_focus() {
             // Only start new recording if there's no current session
             if self.current_session_id.is_none() {
                 self.start_session_recording();
             }
+        } else if was_paused && !self.timer.is_paused && self.timer.seconds_done() == 0 {
+            self.fire_hook(
+                session_hooks::Event::BreakStart,
+                self.timer.state,
+                self.timer.phase_seconds(),
+            );
         }
     }
 
     pub fn reset(&mut self) {
         // Cancel current session if running
+        self.log_current_session_event("reset");
+        self.ended_break = None;
         self.current_session_id = None;
         self.timer.reset();
     }
@@ -545,52 +1060,410 @@ impl App {
     /// Full reset - back to session 1 and Work state
     pub fn full_reset(&mut self) {
         // Cancel current session if running
+        self.log_current_session_event("reset");
+        self.ended_break = None;
         self.current_session_id = None;
+        self.end_routine();
         self.timer.full_reset();
     }
 
-    pub fn skip(&mut self) {
-        let old_state = self.timer.state;
-        self.timer.skip();
-        // Record skipped session (not completed)
-        self.record_session_complete(old_state, false);
+    /// Whether strict mode holds back `q`, `s` and `r`: a work session has
+    /// been started and hasn't ended, paused or not
+    pub fn strict_active(&self) -> bool {
+        self.config.focus.strict != StrictMode::Off
+            && self.timer.state.is_focus()
+            && (!self.timer.is_paused || self.timer.seconds_done() > 0)
     }
 
-    /// Cycle through available tags (None -> tag1 -> tag2 -> ... -> None)
-    pub fn cycle_tag(&mut self) {
-        if self.available_tags.is_empty() {
-            return;
+    /// Whether a press of `key` goes through strict mode: never with `lock`,
+    /// on the third press in a row with `confirm`
+    fn strict_allows(&mut self, key: char) -> bool {
+        if !self.strict_active() {
+            return true;
         }
-        match self.selected_tag_index {
-            None => self.selected_tag_index = Some(0),
-            Some(idx) => {
-                if idx + 1 < self.available_tags.len() {
-                    self.selected_tag_index = Some(idx + 1);
-                } else {
-                    self.selected_tag_index = None;
-                }
-            }
+        let presses = match self.strict_key {
+            Some((held, count)) if held == key => count + 1,
+            _ => 1,
+        };
+        if self.config.focus.strict == StrictMode::Confirm && presses >= STRICT_PRESSES {
+            self.strict_key = None;
+            return true;
         }
+        self.strict_key = Some((key, presses));
+        false
     }
 
-    /// Get the currently selected tag
-    pub fn selected_tag(&self) -> Option<&Tag> {
-        self.selected_tag_index
+    /// What the last key held back by strict mode needs, shown under the timer
+    pub fn strict_notice(&self) -> Option<String> {
+        let (key, presses) = self.strict_key?;
+        if !self.strict_active() {
+            return None;
+        }
+        let action = match key {
+            'q' => "quit",
+            's' => "skip",
+            _ => "reset",
+        };
+        Some(match self.config.focus.strict {
+            StrictMode::Confirm => {
+                let left = STRICT_PRESSES - presses;
+                let times = if left == 1 {
+                    "once more".to_string()
+                } else {
+                    format!("{} more times", left)
+                };
+                format!("Strict mode: press {} {} to {}", key, times, action)
+            }
+            _ => format!("Strict mode: {} waits for the break", action),
+        })
+    }
+
+    /// Start the routine `name` at its first block. Whatever the timer was
+    /// doing is dropped, as with a reset.
+    pub fn start_routine(&mut self, name: &str) -> Result<()> {
+        let run = routine::Run::start(&self.config, name)?;
+        self.log_current_session_event("reset");
+        self.ended_break = None;
+        self.current_session_id = None;
+        // Blocks have fixed lengths, so work counts down even in Flowtime
+        self.timer.set_flowtime(false);
+        self.timer.set_named_timer(None);
+        self.routine = Some(run);
+        self.enter_routine_block(true);
+        Ok(())
+    }
+
+    /// Set the timer to the routine's current block, with its tag
+    fn enter_routine_block(&mut self, start: bool) {
+        let Some(block) = self.routine.as_ref().map(|run| run.current().clone()) else {
+            return;
+        };
+        if let Some(tag) = &block.tag {
+            self.select_tag_by_name(tag);
+        }
+        self.timer.start_block(block.state, block.minutes);
+        if start {
+            self.toggle_pause();
+        }
+    }
+
+    /// Go on to the routine's next block, or back to the usual timer after
+    /// the last one
+    fn next_routine_block(&mut self, start: bool) {
+        let Some(run) = self.routine.as_mut() else {
+            return;
+        };
+        if run.advance().is_some() {
+            self.enter_routine_block(start);
+        } else {
+            self.end_routine();
+        }
+    }
+
+    /// Leave the routine: lengths and focus mode go back to the settings
+    fn end_routine(&mut self) {
+        if self.routine.take().is_none() {
+            return;
+        }
+        let speed = self.timer.speed;
+        self.timer = Timer::with_sessions(
+            self.work_minutes(),
+            self.short_break_minutes(),
+            self.config.timer.long_break,
+            self.config.timer.sessions_until_long,
+        );
+        self.timer.speed = speed;
+        self.timer
+            .set_flowtime(self.config.focus.mode == FocusMode::Flowtime);
+        self.timer
+            .set_named_timer(self.config.focus.timer_minutes());
+    }
+
+    /// Open the routines picker, when any routines are set up
+    pub fn open_routine_picker(&mut self) {
+        if !self.config.routines.is_empty() {
+            self.routine_picker = Some(0);
+        }
+    }
+
+    fn handle_routine_picker_key(&mut self, key: KeyEvent) {
+        let names = routine::names(&self.config);
+        let Some(index) = self.routine_picker.filter(|_| !names.is_empty()) else {
+            self.routine_picker = None;
+            return;
+        };
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.routine_picker = Some((index + names.len() - 1) % names.len())
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                self.routine_picker = Some((index + 1) % names.len())
+            }
+            KeyCode::Enter => {
+                self.routine_picker = None;
+                if let Err(err) = self.start_routine(&names[index]) {
+                    self.action_error = Some(err.to_string());
+                }
+            }
+            KeyCode::Esc | KeyCode::Char('o') => self.routine_picker = None,
+            KeyCode::Char('q') => self.should_quit = true,
+            _ => {}
+        }
+    }
+
+    /// Pick an event and cycle where its notification goes
+    fn handle_routes_editor_key(&mut self, key: KeyEvent) {
+        let Some(index) = self.routes_editor else {
+            return;
+        };
+        let count = NotifyEvent::ALL.len();
+        match key.code {
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.routes_editor = Some((index + count - 1) % count)
+            }
+            KeyCode::Down | KeyCode::Char('j') => self.routes_editor = Some((index + 1) % count),
+            KeyCode::Enter | KeyCode::Char(' ') | KeyCode::Right | KeyCode::Char('l') => {
+                let route = self
+                    .config
+                    .notifications
+                    .routes
+                    .get_mut(NotifyEvent::ALL[index]);
+                *route = route.next();
+                self.apply_settings();
+            }
+            KeyCode::Esc => self.routes_editor = None,
+            KeyCode::Char('q') => self.should_quit = true,
+            _ => {}
+        }
+    }
+
+    /// Note a pause or its end on the session being recorded, so its time
+    /// paused is kept apart from its duration
+    fn record_pause(&self) {
+        let (Some(db), Some(session_id)) = (
+            self.db.as_ref().and_then(|db| db.sqlite()),
+            self.current_session_id,
+        ) else {
+            return;
+        };
+        let _ = if self.timer.is_paused {
+            db.pause_session(session_id, clock::now_utc())
+        } else {
+            db.unpause_session(session_id, clock::now_utc())
+        };
+    }
+
+    /// Append an event to the trail of the session being recorded (if any)
+    fn log_current_session_event(&self, event: &str) {
+        if let (Some(ref db), Some(session_id)) = (&self.db, self.current_session_id) {
+            let _ = db.log_session_event(session_id, event, None);
+        }
+    }
+
+    pub fn skip(&mut self) {
+        let old_state = self.timer.state;
+        let skipping_break = !old_state.is_focus();
+        // A break skipped before it started still counts toward the skip penalty
+        if skipping_break
+            && self.config.accountability.enabled()
+            && self.current_session_id.is_none()
+        {
+            self.start_session_recording();
+        }
+        // Flowtime work and named timers end rather than being skipped, and count
+        let ends = old_state == TimerState::Timer || self.timer.is_counting_up();
+        let seconds = self.timer.seconds_done();
+        self.timer.skip();
+        self.record_session_complete(old_state, ends, seconds);
+        let hook = if ends {
+            session_hooks::Event::WorkComplete
+        } else {
+            session_hooks::Event::Skip
+        };
+        self.fire_hook(hook, old_state, seconds);
+        self.next_routine_block(false);
+        if skipping_break {
+            self.refresh_skip_penalty();
+        }
+    }
+
+    fn refresh_skip_penalty(&mut self) {
+        if let Some(db) = self.database() {
+            self.skip_penalty = accountability::today(db, &self.config.accountability)
+                .ok()
+                .flatten();
+        }
+    }
+
+    /// Cycle through available tags (None -> tag1 -> tag2 -> ... -> None)
+    pub fn cycle_tag(&mut self) {
+        if self.available_tags.is_empty() {
+            return;
+        }
+        match self.selected_tag_index {
+            None => self.selected_tag_index = Some(0),
+            Some(idx) => {
+                if idx + 1 < self.available_tags.len() {
+                    self.selected_tag_index = Some(idx + 1);
+                } else {
+                    self.selected_tag_index = None;
+                }
+            }
+        }
+        self.remember_tag();
+        self.apply_tag_defaults();
+    }
+
+    /// Work length in minutes: the selected tag's, or the setting
+    fn work_minutes(&self) -> u32 {
+        self.tag_defaults
+            .work_minutes
+            .unwrap_or(self.config.timer.work_duration)
+    }
+
+    /// Short break length in minutes: the selected tag's, or the setting
+    fn short_break_minutes(&self) -> u32 {
+        self.tag_defaults
+            .break_minutes
+            .unwrap_or(self.config.timer.short_break)
+    }
+
+    /// Take on the selected tag's durations and icon. Lengths change for
+    /// phases that haven't started yet; a routine keeps its own.
+    fn apply_tag_defaults(&mut self) {
+        let tag_id = self.selected_tag().map(|t| t.id);
+        let sqlite = self.db.as_deref().and_then(|d| d.sqlite());
+        self.tag_defaults = match (tag_id, sqlite) {
+            (Some(id), Some(db)) => db.get_tag_defaults(id).unwrap_or_default(),
+            _ => TagDefaults::default(),
+        };
+        if self.routine.is_none() {
+            self.timer
+                .set_lengths(self.work_minutes(), self.short_break_minutes());
+        }
+    }
+
+    /// Load the defaults of the tag being edited in settings
+    fn load_tag_settings(&mut self) {
+        let tag_id = self
+            .available_tags
+            .get(self.tag_defaults_index)
+            .map(|t| t.id);
+        let sqlite = self.db.as_deref().and_then(|d| d.sqlite());
+        self.tag_settings = match (tag_id, sqlite) {
+            (Some(id), Some(db)) => db.get_tag_defaults(id).unwrap_or_default(),
+            _ => TagDefaults::default(),
+        };
+    }
+
+    /// Store the edited tag defaults, and apply them when it's the
+    /// selected tag
+    fn save_tag_settings(&mut self) {
+        let Some(tag_id) = self
+            .available_tags
+            .get(self.tag_defaults_index)
+            .map(|t| t.id)
+        else {
+            return;
+        };
+        if let Some(db) = self.db.as_deref().and_then(|d| d.sqlite()) {
+            let _ = db.set_tag_defaults(tag_id, &self.tag_settings);
+        }
+        if self.selected_tag().map(|t| t.id) == Some(tag_id) {
+            self.apply_tag_defaults();
+        }
+    }
+
+    /// Give the tag in the tag defaults editor the next accent color, then
+    /// none again (a color picked by id)
+    fn cycle_tag_color(&mut self) {
+        let Some(tag) = self.available_tags.get(self.tag_defaults_index) else {
+            return;
+        };
+        let Some(db) = self.db.as_deref().and_then(|d| d.sqlite()) else {
+            return;
+        };
+        let colors: Vec<&str> = crate::theme::available_accent_colors()
+            .into_iter()
+            .filter(|c| *c != "rainbow")
+            .collect();
+        let current = tag
+            .color
+            .as_deref()
+            .and_then(|c| colors.iter().position(|name| *name == c));
+        let color = match (current, &tag.color) {
+            (Some(i), _) => colors.get(i + 1).copied(),
+            // A "#rrggbb" set elsewhere goes back to none first
+            (None, Some(_)) => None,
+            (None, None) => colors.first().copied(),
+        };
+        let (id, name) = (tag.id, tag.name.clone());
+        if db.update_tag(id, &name, color).is_err() {
+            return;
+        }
+        let color = color.map(str::to_string);
+        for tag in self.available_tags.iter_mut().filter(|t| t.id == id) {
+            tag.color = color.clone();
+        }
+        for tag in self
+            .recent_sessions
+            .iter_mut()
+            .filter_map(|(_, tag)| tag.as_mut())
+            .filter(|t| t.id == id)
+        {
+            tag.color = color.clone();
+        }
+    }
+
+    /// Move the tag defaults editor to the next (or previous) tag
+    fn cycle_tag_settings(&mut self, forward: bool) {
+        let len = self.available_tags.len();
+        if len == 0 {
+            return;
+        }
+        self.tag_defaults_index = if forward {
+            (self.tag_defaults_index + 1) % len
+        } else {
+            (self.tag_defaults_index + len - 1) % len
+        };
+        self.load_tag_settings();
+    }
+
+    /// Store the selected tag so the next launch starts with it
+    fn remember_tag(&self) {
+        if let Some(ref db) = self.db {
+            if self.config.focus.remember_tag {
+                let id = self.selected_tag().map(|t| t.id.to_string());
+                let _ = db.set_metadata(LAST_TAG_KEY, id.as_deref().unwrap_or(""));
+            }
+        }
+    }
+
+    /// Get the currently selected tag
+    pub fn selected_tag(&self) -> Option<&Tag> {
+        self.selected_tag_index
             .and_then(|idx| self.available_tags.get(idx))
     }
 
-    /// Cycle through focus modes (Classic -> Flowtime -> Classic)
+    /// Cycle through focus modes (Classic -> Flowtime -> Timer -> Classic)
     pub fn cycle_focus_mode(&mut self) {
-        self.focus_mode_index = (self.focus_mode_index + 1) % 2;
+        self.focus_mode_index = (self.focus_mode_index + 1) % 3;
+        self.apply_focus_mode();
+        // Save config
+        let _ = self.save_config();
+    }
+
+    /// Set the focus mode from `focus_mode_index` and switch the timer over
+    fn apply_focus_mode(&mut self) {
         self.config.focus.mode = match self.focus_mode_index {
             0 => FocusMode::Classic,
-            _ => FocusMode::Flowtime,
+            1 => FocusMode::Flowtime,
+            _ => FocusMode::Timer,
         };
-        // Update timer flowtime mode
         self.timer
             .set_flowtime(self.config.focus.mode == FocusMode::Flowtime);
-        // Save config
-        let _ = self.config.save();
+        self.timer
+            .set_named_timer(self.config.focus.timer_minutes());
     }
 
     /// Snooze break - add current break duration to timer
@@ -608,12 +1481,501 @@ impl App {
                 let snooze_seconds = self.config.timer.long_break * 60;
                 self.timer.add_time(snooze_seconds);
             }
-            TimerState::Work => {
+            TimerState::Work | TimerState::Timer => {
                 // Do nothing during work
             }
         }
     }
 
+    /// Snooze asked for from outside the TUI (`sandoro snooze`, notification
+    /// actions): extend the running break by `minutes`, or its full length
+    /// when None, or bring back a break that just ended. Unlike the z key
+    /// this doesn't need `break_snooze_enabled`; asking is explicit.
+    pub fn snooze_break_by(&mut self, minutes: Option<u32>) -> Result<String> {
+        let break_minutes = |state| match state {
+            TimerState::LongBreak => self.config.timer.long_break,
+            _ => self.config.timer.short_break,
+        };
+        match self.timer.state {
+            TimerState::ShortBreak | TimerState::LongBreak => {
+                let minutes = minutes.unwrap_or_else(|| break_minutes(self.timer.state));
+                self.timer.add_time(minutes * 60);
+                Ok(format!(
+                    "Break extended by {}m, {} left",
+                    minutes,
+                    self.timer.formatted_display_time()
+                ))
+            }
+            TimerState::Work => {
+                let Some((state, count)) = self
+                    .ended_break
+                    .filter(|_| self.timer.is_paused && self.current_session_id.is_none())
+                else {
+                    anyhow::bail!("Not on a break; work is under way.");
+                };
+                let minutes = minutes.unwrap_or_else(|| break_minutes(state));
+                self.timer.reopen_break(state, count, minutes * 60);
+                self.ended_break = None;
+                Ok(format!("Break resumed for {}m", minutes))
+            }
+            TimerState::Timer => anyhow::bail!("Timer mode has no breaks."),
+        }
+    }
+
+    /// Push to the phone when a finished break has been left alone for the
+    /// next step of the ladder. Returns the push that was due.
+    pub fn check_escalation(&mut self, now: chrono::DateTime<chrono::Local>) -> Option<Push> {
+        let waiting = self.ended_break.is_some()
+            && self.timer.state == TimerState::Work
+            && self.timer.is_paused;
+        if !waiting {
+            self.escalation = None;
+            return None;
+        }
+        let config = &self.config.escalation;
+        let provider = config.enabled()?;
+        let quiet = QuietHours::parse(&config.quiet_hours).ok();
+        let push = self.escalation.as_mut()?.due(&config.ladder, quiet, now)?;
+        if self.persist {
+            escalation::send_in_background(config, provider, push.clone());
+        }
+        Some(push)
+    }
+
+    /// Notify once a day, at the reminder time, when the daily goal isn't
+    /// met yet. Returns what was left of it.
+    pub fn check_goal_reminder(&mut self, now: chrono::DateTime<chrono::Local>) -> Option<String> {
+        if !self.goal_reminder.due(&self.config.goals, now) {
+            return None;
+        }
+        let today = self.db.as_ref()?.get_today_stats().ok()?;
+        let left = goal_reminder::shortfall(
+            &self.config.goals,
+            today.sessions_completed,
+            today.total_work_seconds,
+        )?;
+        notification::notify_goal_reminder(&left, self.delivery(NotifyEvent::GoalReminder));
+        Some(left)
+    }
+
+    /// With `[goals] journal_prompt`, ask for today's journal note once a day
+    /// at the reminder time, unless it has one already. Returns whether the
+    /// prompt opened.
+    pub fn check_journal_prompt(&mut self, now: chrono::DateTime<chrono::Local>) -> bool {
+        if !self.config.goals.journal_prompt
+            || self.locked
+            || self.note_draft.is_some()
+            || self.database().is_none()
+        {
+            return false;
+        }
+        if !self
+            .journal_reminder
+            .due_at(&self.config.goals.reminder_time, now)
+        {
+            return false;
+        }
+        let Some(db) = self.database() else {
+            return false;
+        };
+        let Ok(stats) = db.get_today_stats() else {
+            return false;
+        };
+        if !matches!(db.get_journal(&stats.date), Ok(None)) {
+            return false;
+        }
+        self.note_draft = Some(NoteDraft {
+            label: format!(
+                "{} · {}",
+                stats.date,
+                crate::format_duration(stats.total_work_seconds)
+            ),
+            target: NoteTarget::Day(stats.date),
+            text: String::new(),
+        });
+        true
+    }
+
+    /// Carry out a request from the control socket
+    pub fn handle_control(&mut self, command: control::Command) -> Result<String> {
+        self.finish_startup(true);
+        // There's no pressing three times from outside, so strict mode holds
+        // these back until the break in either setting
+        if self.strict_active() {
+            match command {
+                control::Command::Skip => anyhow::bail!("Strict mode: skip waits for the break"),
+                control::Command::BreakNow => {
+                    anyhow::bail!("Strict mode: the break waits for the work to end")
+                }
+                _ => {}
+            }
+        }
+        match command {
+            control::Command::Snooze(minutes) => self.snooze_break_by(minutes),
+            control::Command::Lock => {
+                self.lock();
+                Ok("Private mode on".to_string())
+            }
+            control::Command::BreakNow => self.break_now(),
+            control::Command::WorkNow => self.work_now(),
+            control::Command::Start => {
+                if !self.timer.is_paused {
+                    anyhow::bail!("Already running.");
+                }
+                self.toggle_pause();
+                Ok(format!(
+                    "Started, {} left",
+                    self.timer.formatted_display_time()
+                ))
+            }
+            control::Command::Pause => {
+                if self.timer.is_paused {
+                    anyhow::bail!("Already paused.");
+                }
+                self.toggle_pause();
+                Ok("Paused".to_string())
+            }
+            control::Command::Skip => {
+                self.skip();
+                Ok(format!(
+                    "Skipped to {}",
+                    self.timer.state.label().to_lowercase()
+                ))
+            }
+        }
+    }
+
+    /// Carry out buttons clicked on notifications. A click is dropped when
+    /// the timer has moved on to another phase since the notification went
+    /// out. True if any click was handled.
+    pub fn handle_notification_clicks(&mut self) -> bool {
+        let clicks: Vec<notification::Clicked> = self.clicks.1.try_iter().collect();
+        for click in &clicks {
+            if click.phase == self.timer.state {
+                let _ = self.handle_control(click.command);
+            }
+        }
+        !clicks.is_empty()
+    }
+
+    /// Start the break right away (`sandoro break now`). The work block under
+    /// way is closed as interrupted with the time it ran, and a running
+    /// routine is left, since its next block may not be a break.
+    pub fn break_now(&mut self) -> Result<String> {
+        match self.timer.state {
+            TimerState::Work => {}
+            TimerState::Timer => anyhow::bail!("Timer mode has no breaks."),
+            TimerState::ShortBreak | TimerState::LongBreak => anyhow::bail!("Already on a break."),
+        }
+        self.log_current_session_event("break now");
+        self.interrupt_current_session();
+        self.end_routine();
+        self.ended_break = None;
+        // Flowtime works out the break from the time worked, as with Enter
+        self.timer.skip();
+        self.timer.toggle_pause();
+        let kind = match self.timer.state {
+            TimerState::LongBreak => "Long break",
+            _ => "Break",
+        };
+        Ok(format!(
+            "{} started, {} left",
+            kind,
+            self.timer.formatted_display_time()
+        ))
+    }
+
+    /// Go back to work right away (`sandoro work now`): a break is ended like
+    /// the skip key ends it, so it counts toward the skip penalty, and the
+    /// work block starts running
+    pub fn work_now(&mut self) -> Result<String> {
+        if self.timer.state.is_focus() {
+            if !self.timer.is_paused {
+                anyhow::bail!("Already working.");
+            }
+        } else {
+            self.skip();
+            self.end_routine();
+        }
+        self.ended_break = None;
+        self.toggle_pause();
+        Ok(format!(
+            "Work started, {}",
+            if self.timer.is_counting_up() {
+                "counting up".to_string()
+            } else {
+                format!("{} left", self.timer.formatted_display_time())
+            }
+        ))
+    }
+
+    /// Select the tag mapped to a detected workspace, creating it if needed
+    pub fn apply_workspace(&mut self, workspace: DetectedWorkspace) {
+        self.select_tag_by_name(&workspace.tag);
+        self.workspace = Some(workspace);
+    }
+
+    /// Snapshot for the live status file
+    pub fn live_status(&self) -> LiveStatus {
+        let is_work = self.timer.state.is_focus();
+        LiveStatus {
+            pid: std::process::id(),
+            state: session_type(self.timer.state).as_str().to_string(),
+            paused: self.timer.is_paused,
+            remaining_seconds: self.timer.remaining_seconds,
+            elapsed_seconds: self.timer.elapsed_seconds,
+            session_active: is_work && self.current_session_id.is_some(),
+            session_number: self.today_sessions.max(0) as u32 + u32::from(is_work),
+            cycle_position: self.timer.cycle_position(),
+            cycle_length: self.timer.cycle_length(),
+            tag: self
+                .selected_tag()
+                .map(|t| privacy::mask(&t.name, self.locked)),
+            session_id: self.current_session_id,
+            updated_at: clock::now_utc(),
+        }
+    }
+
+    /// Rewrite the live status file when the state changed or a refresh is due
+    pub fn publish_status(&mut self) {
+        if self.ephemeral {
+            return;
+        }
+        let current = self.live_status();
+        let due = self.published_status.as_ref().is_none_or(|last| {
+            last.differs_from(&current)
+                || current.updated_at - last.updated_at
+                    >= chrono::Duration::seconds(status::REFRESH_SECS)
+        });
+        if due && status::write(&current).is_ok() {
+            self.published_status = Some(current);
+        }
+    }
+
+    /// The running work session for the handoff heartbeat; None between
+    /// sessions. Flowtime work has no countdown to hand over.
+    pub fn handoff_beat(&self) -> Option<Beat> {
+        let running = self.timer.state == TimerState::Work
+            && !self.timer.is_flowtime
+            && self.current_session_id.is_some();
+        running.then(|| Beat {
+            paused: self.timer.is_paused,
+            remaining_seconds: self.timer.remaining_seconds,
+            work_minutes: self.timer.work_duration,
+            tag: self.selected_tag().map(|t| t.name.clone()),
+        })
+    }
+
+    /// Continue a work session taken over from another device
+    pub fn adopt_remote(&mut self, adoption: &Adoption) {
+        self.apply_overrides(&SessionOverrides {
+            tag: adoption.tag.clone(),
+            work: Some(adoption.work_minutes),
+            ..Default::default()
+        });
+        self.timer.state = TimerState::Work;
+        self.timer.remaining_seconds = adoption.remaining_seconds;
+        if self.timer.is_paused {
+            self.toggle_pause();
+        }
+        let detail = format!("from {}", adoption.from_device.name);
+        if let (Some(ref db), Some(session_id)) = (&self.db, self.current_session_id) {
+            let _ = db.log_session_event(session_id, "resumed_remote", Some(&detail));
+        }
+    }
+
+    /// Whether the tmux focus lock should be on: a work session is being
+    /// recorded, paused or not
+    pub fn tmux_focus(&self) -> (bool, tmux::Vars) {
+        let working = self.timer.state.is_focus() && self.current_session_id.is_some();
+        let vars = tmux::Vars {
+            tag: self
+                .selected_tag()
+                .map(|t| privacy::mask(&t.name, self.locked)),
+            minutes: self.timer.work_duration,
+        };
+        (working, vars)
+    }
+
+    /// Whether the system's do not disturb should be on: a work session is
+    /// being recorded, paused or not, and it's enabled
+    pub fn dnd_focus(&self) -> bool {
+        self.config.dnd.enabled && self.timer.state.is_focus() && self.current_session_id.is_some()
+    }
+
+    /// The ambient loop that should be playing: only while a work session
+    /// is running, with sound on
+    pub fn ambience(&self) -> Option<(AmbientSound, u32)> {
+        let ambience = &self.config.ambience;
+        let playing = self.config.notifications.sound
+            && ambience.sound != AmbientSound::Off
+            && self.timer.state.is_focus()
+            && !self.timer.is_paused
+            && self.current_session_id.is_some();
+        playing.then_some((ambience.sound, ambience.volume))
+    }
+
+    /// Another device took the session over: drop it here unrecorded, so
+    /// it only counts where it finishes
+    pub fn handed_off(&mut self, to: &str) {
+        if let (Some(ref db), Some(session_id)) = (&self.db, self.current_session_id.take()) {
+            let seconds = self.timer.seconds_done();
+            let _ = db.hand_off_session(session_id, clock::now_utc(), seconds as i32, to);
+        }
+        self.timer.reset();
+        self.handoff_notice = Some(format!("Continued on {}", to));
+    }
+
+    /// Time between ticks. The snake needs the normal rate to be playable.
+    pub fn tick_rate(&self) -> Duration {
+        if self.low_power && !self.snake_active() {
+            LOW_POWER_TICK
+        } else {
+            TICK
+        }
+    }
+
+    /// Number of normal ticks one tick stands for
+    fn tick_step(&self) -> u32 {
+        (self.tick_rate().as_millis() / TICK.as_millis()) as u32
+    }
+
+    /// Turn low-power mode on or off from the flag, config and power source.
+    /// The power source is re-checked at most once a minute.
+    pub fn refresh_power(&mut self) {
+        self.low_power = match self.config.power.low_power {
+            _ if self.force_low_power => true,
+            LowPowerMode::On => true,
+            LowPowerMode::Off => false,
+            LowPowerMode::Auto => {
+                if self
+                    .power_checked_at
+                    .is_some_and(|at| at.elapsed() < POWER_CHECK_INTERVAL)
+                {
+                    return;
+                }
+                self.power_checked_at = Some(Instant::now());
+                power::on_battery().unwrap_or(false)
+            }
+        };
+    }
+
+    /// Terminal title with `[appearance] terminal_title`: the state and the
+    /// cycle's session dots (a routine's blocks don't follow the cycle), and
+    /// with `title_time` the time shown on the timer in front
+    pub fn terminal_title(&self) -> Option<String> {
+        if !self.config.appearance.terminal_title {
+            return None;
+        }
+        let state = self
+            .timer
+            .state
+            .label_with_lang(&self.config.appearance.language);
+        let title = match self.routine {
+            Some(_) => format!("{} · sandoro", state),
+            None => format!("{} {} · sandoro", self.timer.cycle_dots(), state),
+        };
+        if !self.config.appearance.title_time {
+            return Some(title);
+        }
+        let paused = if self.timer.is_paused { "⏸ " } else { "" };
+        Some(format!(
+            "{}{} · {}",
+            paused,
+            self.timer.formatted_display_time(),
+            title
+        ))
+    }
+
+    /// Progress for the terminal's tab or taskbar with `[appearance]
+    /// taskbar_progress`, once the phase has started
+    pub fn taskbar_progress(&self) -> Option<terminal::Progress> {
+        if !self.config.appearance.taskbar_progress || self.locked {
+            return None;
+        }
+        let started = !self.timer.is_paused || self.timer.seconds_done() > 0;
+        if !started {
+            return None;
+        }
+        if self.timer.is_counting_up() {
+            return Some(terminal::Progress::Open);
+        }
+        let percent = self.timer.progress_percent().floor() as u8;
+        Some(if self.timer.is_paused {
+            terminal::Progress::Paused(percent)
+        } else {
+            terminal::Progress::Running(percent)
+        })
+    }
+
+    fn frame_key(&self) -> FrameKey {
+        FrameKey {
+            display_time: self.timer.formatted_display_time(),
+            state: self.timer.state,
+            paused: self.timer.is_paused,
+            animation_frame: self.animation_frame,
+            break_ticks: self.break_ticks,
+            // The header clock shows minutes only in low-power mode
+            clock: clock::now().format("%H:%M").to_string(),
+            // Stats fill in once startup finishes loading them
+            loading: self.is_loading(),
+        }
+    }
+
+    /// Select a tag by name, creating it if needed
+    fn select_tag_by_name(&mut self, name: &str) {
+        // Tags come with the rest of storage
+        self.finish_startup(true);
+        let find = |tags: &[Tag]| tags.iter().position(|t| t.name == name.trim());
+        if find(&self.available_tags).is_none() {
+            self.add_tag(name);
+        }
+        if let Some(idx) = find(&self.available_tags) {
+            self.selected_tag_index = Some(idx);
+        }
+        self.apply_tag_defaults();
+    }
+
+    /// Apply one-off session settings (e.g. from a sandoro:// URL).
+    ///
+    /// Durations only change the running timer, not config.toml.
+    pub fn apply_overrides(&mut self, overrides: &SessionOverrides) {
+        if let Some(tag) = &overrides.tag {
+            self.select_tag_by_name(tag);
+        }
+        if overrides.work.is_some()
+            || overrides.short_break.is_some()
+            || overrides.long_break.is_some()
+        {
+            let (is_flowtime, speed) = (self.timer.is_flowtime, self.timer.speed);
+            let timer_minutes = self.timer.timer_minutes;
+            self.timer = Timer::with_sessions(
+                overrides.work.unwrap_or(self.timer.work_duration),
+                overrides
+                    .short_break
+                    .unwrap_or(self.timer.short_break_duration),
+                overrides
+                    .long_break
+                    .unwrap_or(self.timer.long_break_duration),
+                self.timer.sessions_until_long_break,
+            );
+            self.timer.set_flowtime(is_flowtime);
+            self.timer.set_named_timer(timer_minutes);
+            self.timer.speed = speed;
+        }
+        if overrides.autostart && self.timer.is_paused {
+            self.toggle_pause();
+        }
+    }
+
+    /// Whether the selected tag was changed away from the detected workspace
+    pub fn workspace_overridden(&self) -> bool {
+        match &self.workspace {
+            Some(ws) => self.selected_tag().map(|t| t.name.as_str()) != Some(ws.tag.as_str()),
+            None => false,
+        }
+    }
+
     /// Add a new tag
     pub fn add_tag(&mut self, name: &str) {
         if name.trim().is_empty() {
@@ -627,6 +1989,9 @@ impl App {
                     color: None,
                 };
                 self.available_tags.push(tag);
+                // Tag Defaults and Tag Color go on to the new tag
+                self.tag_defaults_index = self.available_tags.len() - 1;
+                self.load_tag_settings();
             }
         }
     }
@@ -657,6 +2022,9 @@ impl App {
                         self.selected_tag_index = Some(idx - 1);
                     }
                 }
+                self.tag_defaults_index = 0;
+                self.load_tag_settings();
+                self.apply_tag_defaults();
             }
         }
     }
@@ -669,18 +2037,165 @@ impl App {
         self.delete_tag_index = (self.delete_tag_index + 1) % self.available_tags.len();
     }
 
-    /// Refresh recent sessions from database
+    /// Refresh recent sessions from database, applying the history filter
     pub fn refresh_recent_sessions(&mut self) {
+        let search = self.session_search.trim();
         self.recent_sessions = self
             .db
             .as_ref()
-            .and_then(|d| d.get_recent_sessions(20).ok())
+            .and_then(|d| {
+                if search.is_empty() {
+                    d.get_recent_sessions(20).ok()
+                } else {
+                    d.sqlite()
+                        .and_then(|d| d.search_sessions(search, 20).ok())
+                        .map(|hits| hits.into_iter().map(|hit| (hit.session, hit.tag)).collect())
+                }
+            })
             .unwrap_or_default();
         // Adjust index if needed
         if self.session_edit_index >= self.recent_sessions.len() && !self.recent_sessions.is_empty()
         {
             self.session_edit_index = self.recent_sessions.len() - 1;
         }
+        self.count_history_interruptions();
+    }
+
+    /// Look up how often each session in the history was interrupted
+    fn count_history_interruptions(&mut self) {
+        let ids: Vec<i64> = self.recent_sessions.iter().map(|(s, _)| s.id).collect();
+        self.history_interruptions = self
+            .database()
+            .and_then(|db| db.count_interruptions(&ids).ok())
+            .unwrap_or_default();
+    }
+
+    /// Whether the selected settings item works on the session history
+    pub fn session_history_selected(&self) -> bool {
+        matches!(
+            SettingsItem::all()[self.settings_index],
+            SettingsItem::EditSessionTag
+                | SettingsItem::EditSessionNote
+                | SettingsItem::MergeSession
+                | SettingsItem::DeleteSession
+        )
+    }
+
+    fn set_session_search(&mut self, search: String) {
+        self.session_search = search;
+        self.session_edit_index = 0;
+        self.session_tag_edit_index = None;
+        self.refresh_recent_sessions();
+    }
+
+    fn handle_session_search_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Enter => self.session_search_mode = false,
+            KeyCode::Esc => {
+                self.session_search_mode = false;
+                self.set_session_search(String::new());
+            }
+            KeyCode::Backspace => {
+                let mut search = self.session_search.clone();
+                search.pop();
+                self.set_session_search(search);
+            }
+            KeyCode::Char(c) if self.session_search.chars().count() < 40 => {
+                let search = format!("{}{}", self.session_search, c);
+                self.set_session_search(search);
+            }
+            _ => {}
+        }
+    }
+
+    /// Start typing a note for the session at session_edit_index, starting
+    /// from the note it has
+    fn edit_current_session_note(&mut self) {
+        let Some((session, tag)) = self.recent_sessions.get(self.session_edit_index) else {
+            return;
+        };
+        self.note_draft = Some(NoteDraft {
+            target: NoteTarget::Session(session.id),
+            label: session_label(
+                session.started_at.with_timezone(&Local),
+                session.duration_seconds.unwrap_or(0).max(0) as u32,
+                tag.as_ref(),
+            ),
+            text: session.notes.clone().unwrap_or_default(),
+        });
+    }
+
+    /// Typing a note: Enter saves it, Esc leaves the session or day as it was
+    fn handle_note_key(&mut self, key: KeyEvent) {
+        let Some(draft) = self.note_draft.as_mut() else {
+            return;
+        };
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.should_quit = true
+            }
+            KeyCode::Enter => {
+                let draft = self.note_draft.take();
+                if let (Some(draft), Some(db)) = (draft, self.database()) {
+                    match draft.target {
+                        NoteTarget::Session(id) => {
+                            let _ = db.set_session_notes(id, &draft.text);
+                        }
+                        NoteTarget::Day(_) if draft.text.trim().is_empty() => {}
+                        NoteTarget::Day(date) => {
+                            if let Err(err) = db.set_journal(&date, draft.text.trim()) {
+                                self.action_error = Some(err.to_string());
+                            }
+                        }
+                    }
+                }
+                self.refresh_recent_sessions();
+            }
+            KeyCode::Esc => self.note_draft = None,
+            KeyCode::Backspace => {
+                draft.text.pop();
+            }
+            KeyCode::Char(c) if draft.text.chars().count() < NOTE_MAX_CHARS => {
+                draft.text.push(c);
+            }
+            _ => {}
+        }
+    }
+
+    /// `i` during a work session: note the time and wait for a category
+    fn start_interruption(&mut self) {
+        if self.timer.state.is_focus()
+            && self.current_session_id.is_some()
+            && self.database().is_some()
+        {
+            self.interruption_at = Some(clock::now_utc());
+        }
+    }
+
+    /// The key after `i`: a character is the category, Enter records it
+    /// without one, Esc drops it
+    fn handle_interruption_key(&mut self, key: KeyEvent) {
+        let category = match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.should_quit = true;
+                return;
+            }
+            KeyCode::Esc => {
+                self.interruption_at = None;
+                return;
+            }
+            KeyCode::Enter => None,
+            KeyCode::Char(c) if !c.is_whitespace() => Some(c),
+            _ => return,
+        };
+        let at = self.interruption_at.take();
+        let recorded = match (at, self.current_session_id, self.database()) {
+            (Some(at), Some(id), Some(db)) => db.add_interruption(id, category, at).is_ok(),
+            _ => false,
+        };
+        if recorded {
+            self.interruptions += 1;
+        }
     }
 
     /// Delete the session at session_edit_index
@@ -699,12 +2214,38 @@ impl App {
             .unwrap_or(false);
         if deleted {
             self.refresh_recent_sessions();
-            // Update stats
-            if let Some(ref db) = self.db {
-                if let Ok(stats) = db.get_today_stats() {
-                    self.today_work_seconds = stats.total_work_seconds;
-                    self.today_sessions = stats.sessions_completed;
-                }
+            self.refresh_today_stats();
+        }
+    }
+
+    /// Merge the session at session_edit_index with the one listed below it,
+    /// i.e. the one before it: a block split by a crash or a double start
+    pub fn merge_current_session(&mut self) {
+        let index = self.session_edit_index;
+        let (Some((newer, _)), Some((older, _))) = (
+            self.recent_sessions.get(index),
+            self.recent_sessions.get(index + 1),
+        ) else {
+            return;
+        };
+        let merged = self
+            .database()
+            .map(|db| db.merge_sessions(older.id, newer.id).is_ok())
+            .unwrap_or(false);
+        if merged {
+            self.refresh_recent_sessions();
+            // The merged session takes the older one's place in the list
+            self.session_edit_index = index.min(self.recent_sessions.len().saturating_sub(1));
+            self.refresh_today_stats();
+        }
+    }
+
+    /// Re-read today's totals after the history was changed
+    fn refresh_today_stats(&mut self) {
+        if let Some(ref db) = self.db {
+            if let Ok(stats) = db.get_today_stats() {
+                self.today_work_seconds = stats.total_work_seconds;
+                self.today_sessions = stats.sessions_completed;
             }
         }
     }
@@ -738,63 +2279,630 @@ impl App {
         if self.recent_sessions.is_empty() {
             return;
         }
-        if self.session_edit_index > 0 {
-            self.session_edit_index -= 1;
-        } else {
-            self.session_edit_index = self.recent_sessions.len() - 1;
+        if self.session_edit_index > 0 {
+            self.session_edit_index -= 1;
+        } else {
+            self.session_edit_index = self.recent_sessions.len() - 1;
+        }
+    }
+
+    /// Start recording a new session
+    fn start_session_recording(&mut self) {
+        self.finish_startup(true);
+        if self.timer.state == TimerState::Work {
+            self.ended_break = None;
+        }
+        if let Some(ref db) = self.db {
+            let session_type = session_type(self.timer.state);
+            // Flowtime work and stopwatches are open-ended, so there is no
+            // planned duration
+            let counting_up = self.timer.is_counting_up();
+            let mode = if counting_up && session_type == SessionType::Work {
+                SessionMode::Flowtime
+            } else {
+                SessionMode::Classic
+            };
+            let planned_seconds = (!counting_up).then(|| self.timer.phase_seconds() as i32);
+            // Start session with tag if selected
+            let tag_id = self.selected_tag().map(|t| t.id);
+            let result = db.start_session_in_mode(session_type, tag_id, planned_seconds, mode);
+            if let Ok(id) = result {
+                self.current_session_id = Some(id);
+                if self.timer.state.is_focus() {
+                    self.interruptions = 0;
+                    self.work_length_tip = None;
+                    self.capacity_warning = None;
+                    self.handoff_notice = None;
+                    self.tmux_error = None;
+                    self.dnd_error = None;
+                    self.action_error = None;
+                    if let (Some(task), Some(db)) = (self.bound_task, db.sqlite()) {
+                        let _ = db.bind_session_task(id, task);
+                    }
+                }
+            }
+        }
+        if self.bound_task.is_some() {
+            self.reload_board(self.bound_task);
+        }
+        if self.timer.state.is_focus() {
+            let planned = self.timer.phase_seconds();
+            self.fire_hook(session_hooks::Event::WorkStart, self.timer.state, planned);
+        }
+    }
+
+    /// Run the `[hooks]` command for `event` about a `state` phase; never in
+    /// tests, demos or ephemeral runs
+    fn fire_hook(&self, event: session_hooks::Event, state: TimerState, duration: u32) {
+        if !self.persist {
+            return;
+        }
+        let context = session_hooks::Context {
+            state: session_type(state).as_str().to_string(),
+            duration,
+            tag: self
+                .selected_tag()
+                .map(|t| privacy::mask(&t.name, self.locked)),
+        };
+        session_hooks::fire(&self.config.hooks, event, &context);
+    }
+
+    /// Record the end of a `state` session that ran for `duration` seconds
+    fn record_session_complete(&mut self, state: TimerState, completed: bool, duration: u32) {
+        let mut reached_goal = false;
+        let mut awarded = None;
+        if let (Some(ref db), Some(session_id)) = (&self.db, self.current_session_id) {
+            if completed {
+                let _ = db.complete_session(session_id, duration as i32);
+
+                // Try to sync to cloud (silently fails if offline or not logged in)
+                if let (true, Some(db)) = (self.persist, db.sqlite()) {
+                    let _ = sync::try_sync_session(db.connection(), session_id);
+                }
+
+                // Update today's stats for work sessions and named timers
+                if state.is_focus() {
+                    let sqlite = db.sqlite();
+                    if let Some(Ok(true)) =
+                        sqlite.map(|db| time_tracking::enqueue(db, &self.config, session_id))
+                    {
+                        if self.persist {
+                            time_tracking::flush_in_background(&self.config);
+                        }
+                    }
+                    let goal_met_before = xp::daily_goal_met(
+                        &self.config,
+                        self.today_sessions,
+                        self.today_work_seconds,
+                    );
+                    self.today_work_seconds += duration as i32;
+                    self.today_sessions += 1;
+                    self.total_sessions += 1;
+                    if let Ok(by_tag) = db.get_today_stats_by_tag() {
+                        self.today_by_tag = by_tag;
+                    }
+
+                    // Refresh streak info (may have started a new streak today)
+                    if let Ok(streak) = db.get_streak() {
+                        self.current_streak = streak.current;
+                        self.longest_streak = streak.longest;
+                    }
+                    if let Some(db) = sqlite {
+                        let _ = db
+                            .today()
+                            .and_then(|today| goals::record_week(db, &self.config, today));
+                    }
+                    if let Some(db) = sqlite {
+                        self.week_plan = load_week_plan(db, &self.config);
+                    }
+                    reached_goal = !goal_met_before
+                        && xp::daily_goal_met(
+                            &self.config,
+                            self.today_sessions,
+                            self.today_work_seconds,
+                        );
+                    if let (true, Some(db)) = (self.xp.is_some(), sqlite) {
+                        awarded = xp::award_work(
+                            db,
+                            session_id,
+                            duration / 60,
+                            self.current_streak,
+                            reached_goal,
+                        )
+                        .ok();
+                    }
+
+                    if let (true, Some(_)) = (self.config.timer.note_prompt && !self.locked, sqlite)
+                    {
+                        self.note_draft = Some(NoteDraft {
+                            target: NoteTarget::Session(session_id),
+                            label: session_label(
+                                clock::now() - chrono::Duration::seconds(i64::from(duration)),
+                                duration,
+                                self.selected_tag(),
+                            ),
+                            text: String::new(),
+                        });
+                    }
+
+                    // A task that reaches its estimate is done and unbound
+                    if let (Some(task), Some(db)) = (self.bound_task, sqlite) {
+                        if let Ok(TaskStatus::Done) = db.record_task_session(task) {
+                            self.bound_task = None;
+                        }
+                    }
+                }
+            } else {
+                let _ = db.log_session_event(session_id, "skipped", None);
+            }
+        }
+        self.current_session_id = None;
+        if reached_goal {
+            notification::notify_goal_reached(self.delivery(NotifyEvent::GoalReached));
+        }
+        if let Some(total) = awarded {
+            self.set_xp(total);
+        }
+        if completed && state.is_focus() {
+            self.reload_board(None);
+        }
+    }
+
+    /// Show or hide the "today" board pane
+    pub fn toggle_board(&mut self) {
+        self.board_open = !self.board_open;
+        self.board.input = None;
+        if self.board_open {
+            self.reload_board(None);
+        }
+    }
+
+    /// Reload the board's tasks, keeping the cursor on `focus` if given
+    fn reload_board(&mut self, focus: Option<i64>) {
+        let tasks = self
+            .database()
+            .and_then(|db| db.get_board_tasks().ok())
+            .unwrap_or_default();
+        self.board.load(tasks, focus);
+    }
+
+    /// Apply a key to the board pane; returns false for keys it doesn't use
+    fn handle_board_key(&mut self, key: KeyEvent) -> bool {
+        if let Some(input) = self.board.input.as_mut() {
+            match key.code {
+                KeyCode::Enter => {
+                    let title = input.trim().to_string();
+                    self.board.input = None;
+                    if let (false, Some(db)) = (title.is_empty(), self.database()) {
+                        if let Ok(id) = db.create_task(&title) {
+                            self.reload_board(Some(id));
+                        }
+                    }
+                }
+                KeyCode::Esc => self.board.input = None,
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Char(c) if input.chars().count() < board::MAX_TITLE_LEN => input.push(c),
+                _ => {}
+            }
+            return true;
+        }
+
+        let selected = self.board.selected().cloned();
+        match key.code {
+            KeyCode::Left | KeyCode::Char('h') => self.board.left(),
+            KeyCode::Right | KeyCode::Char('l') => self.board.right(),
+            KeyCode::Up | KeyCode::Char('k') => self.board.up(),
+            KeyCode::Down | KeyCode::Char('j') => self.board.down(),
+            KeyCode::Char('a') => self.board.input = Some(String::new()),
+            KeyCode::Esc => self.toggle_board(),
+            KeyCode::Enter => {
+                // Bind the task to the timer, or unbind it
+                if let Some(task) = selected {
+                    self.bound_task = (self.bound_task != Some(task.id)).then_some(task.id);
+                }
+            }
+            KeyCode::Char(c @ ('>' | '<')) => {
+                if let (Some(task), Some(db)) = (selected, self.database()) {
+                    let status = board::shift(task.status, c == '>');
+                    let _ = db.set_task_status(task.id, status);
+                    if status == TaskStatus::Done && self.bound_task == Some(task.id) {
+                        self.bound_task = None;
+                    }
+                    self.reload_board(Some(task.id));
+                }
+            }
+            KeyCode::Char(c @ ('+' | '-')) => {
+                if let (Some(task), Some(db)) = (selected, self.database()) {
+                    let estimate = match (task.estimate, c) {
+                        (None, '+') => Some(1),
+                        (Some(n), '+') => Some(n + 1),
+                        (Some(n), _) if n > 1 => Some(n - 1),
+                        _ => None,
+                    };
+                    let _ = db.set_task_estimate(task.id, estimate);
+                    self.reload_board(Some(task.id));
+                }
+            }
+            KeyCode::Char('x') => {
+                if let (Some(task), Some(db)) = (selected, self.database()) {
+                    let _ = db.delete_task(task.id);
+                    if self.bound_task == Some(task.id) {
+                        self.bound_task = None;
+                    }
+                    self.reload_board(None);
+                }
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// The task work sessions are recorded against
+    pub fn bound_task(&self) -> Option<&crate::db::Task> {
+        let id = self.bound_task?;
+        self.board.tasks.iter().find(|t| t.id == id)
+    }
+
+    /// Look for sessions a previous run left open
+    pub fn load_recovery(&mut self, last_status: Option<&LiveStatus>) {
+        self.recovery = self
+            .database()
+            .and_then(|db| recovery::find(db, last_status).ok())
+            .unwrap_or_default();
+    }
+
+    /// Resolve the first open session, or all of them
+    pub fn resolve_recovery(&mut self, action: RecoveryAction, all: bool) {
+        let Some(db) = self.db.as_deref().and_then(|d| d.sqlite()) else {
+            self.recovery.clear();
+            return;
+        };
+        let count = if all { self.recovery.len() } else { 1 };
+        for open in self.recovery.drain(..count.min(self.recovery.len())) {
+            // Without an estimate there's nothing to complete with
+            let action = match action {
+                RecoveryAction::Complete if open.estimate.is_none() => RecoveryAction::Interrupt,
+                other => other,
+            };
+            if recovery::resolve(db, &open, action).is_ok() && action == RecoveryAction::Complete {
+                let _ = time_tracking::enqueue(db, &self.config, open.detail.session.id);
+            }
+        }
+        if self.persist {
+            time_tracking::flush_in_background(&self.config);
+        }
+        if let Ok(stats) = db.get_today_stats() {
+            self.today_work_seconds = stats.total_work_seconds;
+            self.today_sessions = stats.sessions_completed;
+        }
+        if let Ok(streak) = db.get_streak() {
+            self.current_streak = streak.current;
+            self.longest_streak = streak.longest;
+        }
+        self.refresh_recent_sessions();
+    }
+
+    fn handle_recovery_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Char('c') => self.resolve_recovery(RecoveryAction::Complete, false),
+            KeyCode::Char('i') => self.resolve_recovery(RecoveryAction::Interrupt, false),
+            KeyCode::Char('d') => self.resolve_recovery(RecoveryAction::Discard, false),
+            KeyCode::Char('C') => self.resolve_recovery(RecoveryAction::Complete, true),
+            KeyCode::Char('I') => self.resolve_recovery(RecoveryAction::Interrupt, true),
+            KeyCode::Char('D') => self.resolve_recovery(RecoveryAction::Discard, true),
+            // Decide next time
+            KeyCode::Esc => self.recovery.clear(),
+            KeyCode::Char('q') => self.should_quit = true,
+            _ => {}
+        }
+    }
+
+    /// Remember a session quit in the middle of, to offer it next launch,
+    /// then close it as interrupted
+    pub fn save_session_on_exit(&mut self) {
+        let started = self.current_session_id.is_some() || self.timer.seconds_done() > 0;
+        if let Some(db) = self.db.as_deref().filter(|_| started) {
+            let saved = SavedSession {
+                state: self.live_status().state,
+                remaining_seconds: self.timer.remaining_seconds,
+                elapsed_seconds: self.timer.elapsed_seconds,
+                session_count: self.timer.session_count,
+                tag_id: self.selected_tag().map(|t| t.id),
+                session_id: self.current_session_id,
+                saved_at: clock::now_utc(),
+            };
+            if let Ok(json) = serde_json::to_string(&saved) {
+                let _ = db.set_metadata(SAVED_SESSION_KEY, &json);
+            }
+        }
+        self.interrupt_current_session();
+    }
+
+    /// Pick up the session the last run saved on quitting; it's offered
+    /// once, whatever the answer
+    pub fn load_saved_session(&mut self) {
+        let Some(db) = self.db.as_deref() else {
+            return;
+        };
+        let saved = db.get_metadata(SAVED_SESSION_KEY).ok().flatten();
+        if saved.as_deref().is_some_and(|json| !json.is_empty()) {
+            let _ = db.set_metadata(SAVED_SESSION_KEY, "");
+        }
+        self.saved_session = saved.and_then(|json| serde_json::from_str(&json).ok());
+    }
+
+    /// Put the timer back where the saved session left off, paused, and
+    /// carry on recording it when the storage allows
+    pub fn resume_saved_session(&mut self) {
+        let Some(saved) = self.saved_session.take() else {
+            return;
+        };
+        self.timer.state = match saved.state.as_str() {
+            "short_break" => TimerState::ShortBreak,
+            "long_break" => TimerState::LongBreak,
+            // Only while timer mode is still on
+            "timer" if self.timer.timer_minutes.is_some() => TimerState::Timer,
+            _ => TimerState::Work,
+        };
+        self.timer.remaining_seconds = saved.remaining_seconds;
+        self.timer.elapsed_seconds = saved.elapsed_seconds;
+        self.timer.session_count = saved.session_count;
+        self.timer.is_paused = true;
+        if let Some(idx) = saved
+            .tag_id
+            .and_then(|id| self.available_tags.iter().position(|t| t.id == id))
+        {
+            self.selected_tag_index = Some(idx);
+        }
+        let sqlite = self.db.as_deref().and_then(|d| d.sqlite());
+        if let (Some(id), Some(db)) = (saved.session_id, sqlite) {
+            if db.reopen_session(id).unwrap_or(false) {
+                self.current_session_id = Some(id);
+            }
+        }
+    }
+
+    fn handle_saved_session_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Char('r') | KeyCode::Enter => self.resume_saved_session(),
+            KeyCode::Char('n') | KeyCode::Esc => self.saved_session = None,
+            KeyCode::Char('q') => self.should_quit = true,
+            _ => {}
+        }
+    }
+
+    /// Close the session being recorded when quitting, so it isn't mistaken
+    /// for one left open by a crash
+    pub fn interrupt_current_session(&mut self) {
+        let (Some(ref db), Some(session_id)) = (&self.db, self.current_session_id) else {
+            return;
+        };
+        let seconds = self.timer.seconds_done();
+        let _ = db.interrupt_session(session_id, clock::now_utc(), seconds as i32);
+        self.current_session_id = None;
+    }
+
+    /// Turn on private mode; everything else on screen stays as it was
+    pub fn lock(&mut self) {
+        self.locked = true;
+        self.unlocker.clear();
+        self.view = AppView::Timer;
+        if self.persist {
+            let _ = privacy::set_locked(true);
+        }
+    }
+
+    pub fn unlock(&mut self) {
+        self.locked = false;
+        self.unlocker.clear();
+        if self.persist {
+            let _ = privacy::set_locked(false);
+        }
+    }
+
+    /// Drop the lock once `sandoro unlock` has removed the lock file; only
+    /// it and the typed sequence unlock, never the control socket. True
+    /// when the lock came off.
+    pub fn refresh_lock(&mut self) -> bool {
+        if !self.locked || !self.persist {
+            return false;
+        }
+        if self
+            .lock_checked_at
+            .is_some_and(|at| at.elapsed() < LOCK_CHECK_INTERVAL)
+        {
+            return false;
+        }
+        self.lock_checked_at = Some(Instant::now());
+        if privacy::is_locked() {
+            return false;
+        }
+        self.locked = false;
+        self.unlocker.clear();
+        true
+    }
+
+    /// While locked, keys spell out the unlock sequence. Space still
+    /// pauses and resumes, and Ctrl+C quits.
+    fn handle_locked_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.should_quit = true
+            }
+            KeyCode::Char(' ') => self.toggle_pause(),
+            KeyCode::Esc => self.unlocker.clear(),
+            KeyCode::Char(c) => {
+                let sequence = privacy::unlock_sequence(&self.config);
+                let typed_in_full = self.unlocker.push(c, sequence);
+                if typed_in_full {
+                    self.unlock();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Apply a key press to the current view
+    pub fn handle_key(&mut self, key: KeyEvent) {
+        // Keys act on stats, tags and sessions, so they wait for storage.
+        // The loop keeps drawing meanwhile; only Ctrl+C can't wait.
+        self.finish_startup(false);
+        if self.is_loading() {
+            if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+                self.should_quit = true;
+            } else if self.pending_keys.len() < PENDING_KEYS_MAX {
+                self.pending_keys.push(key);
+            }
+            return;
+        }
+        if !self.recovery.is_empty() {
+            self.handle_recovery_key(key);
+            return;
+        }
+        if self.saved_session.is_some() {
+            self.handle_saved_session_key(key);
+            return;
+        }
+        if self.locked {
+            self.handle_locked_key(key);
+            return;
+        }
+        if self.routine_picker.is_some() {
+            self.handle_routine_picker_key(key);
+            return;
+        }
+        if self.routes_editor.is_some() {
+            self.handle_routes_editor_key(key);
+            return;
+        }
+        if self.note_draft.is_some() {
+            self.handle_note_key(key);
+            return;
         }
-    }
-
-    /// Start recording a new session
-    fn start_session_recording(&mut self) {
-        if let Some(ref db) = self.db {
-            let session_type = match self.timer.state {
-                TimerState::Work => SessionType::Work,
-                TimerState::ShortBreak => SessionType::ShortBreak,
-                TimerState::LongBreak => SessionType::LongBreak,
-            };
-            // Start session with tag if selected
-            let result = if let Some(tag) = self.selected_tag() {
-                db.start_session_with_tag(session_type, Some(tag.id))
-            } else {
-                db.start_session(session_type)
-            };
-            if let Ok(id) = result {
-                self.current_session_id = Some(id);
+        if self.interruption_at.is_some() {
+            self.handle_interruption_key(key);
+            return;
+        }
+        // A held-back key has to be pressed again in a row
+        if self
+            .strict_key
+            .is_some_and(|(held, _)| key.code != KeyCode::Char(held))
+        {
+            self.strict_key = None;
+        }
+        // The board pane takes its keys first, except while the snake game
+        // is steering with the arrow keys
+        if self.view == AppView::Timer
+            && self.board_open
+            && !self.snake_active()
+            && self.handle_board_key(key)
+        {
+            return;
+        }
+        match self.view {
+            AppView::Timer => match key.code {
+                KeyCode::Up | KeyCode::Char('k') if self.snake_active() => {
+                    self.snake_turn(Heading::Up)
+                }
+                KeyCode::Down | KeyCode::Char('j') if self.snake_active() => {
+                    self.snake_turn(Heading::Down)
+                }
+                KeyCode::Left | KeyCode::Char('h') if self.snake_active() => {
+                    self.snake_turn(Heading::Left)
+                }
+                KeyCode::Right | KeyCode::Char('l') if self.snake_active() => {
+                    self.snake_turn(Heading::Right)
+                }
+                KeyCode::Char(c @ ('q' | 'r' | 'R' | 's')) if !self.strict_allows(c) => {}
+                KeyCode::Char('q') => self.should_quit = true,
+                KeyCode::Char(' ') => self.toggle_pause(),
+                KeyCode::Char('r') => self.reset(),
+                KeyCode::Char('R') => self.full_reset(),
+                KeyCode::Char('s') => self.skip(),
+                KeyCode::Char('t') => self.cycle_tag(),
+                KeyCode::Char('m') => self.cycle_focus_mode(),
+                KeyCode::Char('z') => self.snooze_break(),
+                KeyCode::Char('b') => self.toggle_board(),
+                KeyCode::Char('o') => self.open_routine_picker(),
+                KeyCode::Char('p') => self.lock(),
+                KeyCode::Char('i') => self.start_interruption(),
+                KeyCode::Tab => self.toggle_settings(),
+                _ => {}
+            },
+            AppView::Settings => {
+                // Handle tag input mode separately
+                if self.session_search_mode {
+                    self.handle_session_search_key(key);
+                } else if self.tag_input_mode {
+                    match key.code {
+                        KeyCode::Enter => {
+                            // Confirm tag input
+                            if !self.tag_input.is_empty() {
+                                let name = self.tag_input.clone();
+                                self.add_tag(&name);
+                                self.tag_input.clear();
+                            }
+                            self.tag_input_mode = false;
+                        }
+                        KeyCode::Esc => {
+                            // Cancel tag input
+                            self.tag_input.clear();
+                            self.tag_input_mode = false;
+                        }
+                        KeyCode::Backspace => {
+                            self.tag_input.pop();
+                        }
+                        KeyCode::Char(c) if self.tag_input.len() < 30 => {
+                            // Add character to input (limit length)
+                            self.tag_input.push(c);
+                        }
+                        _ => {}
+                    }
+                } else {
+                    match key.code {
+                        KeyCode::Char('q') if !self.editing => {
+                            // Strict mode holds it back here too
+                            self.should_quit = self.strict_allows('q');
+                        }
+                        KeyCode::Tab | KeyCode::Esc => {
+                            if self.editing {
+                                self.editing = false;
+                            } else {
+                                self.toggle_settings();
+                            }
+                        }
+                        KeyCode::Char('/') if self.session_history_selected() => {
+                            self.editing = false;
+                            self.session_search_mode = true;
+                        }
+                        KeyCode::Up | KeyCode::Char('k') => self.settings_up(),
+                        KeyCode::Down | KeyCode::Char('j') => self.settings_down(),
+                        KeyCode::Enter | KeyCode::Char(' ') => self.settings_select(),
+                        _ => {}
+                    }
+                }
             }
         }
     }
 
-    /// Record session completion
-    fn record_session_complete(&mut self, state: TimerState, completed: bool) {
-        if let (Some(ref db), Some(session_id)) = (&self.db, self.current_session_id) {
-            let duration = match state {
-                TimerState::Work => self.config.timer.work_duration * 60,
-                TimerState::ShortBreak => self.config.timer.short_break * 60,
-                TimerState::LongBreak => self.config.timer.long_break * 60,
+    fn save_config(&mut self) -> Result<()> {
+        if self.persist {
+            // Picks up what other frontends saved in the meantime too
+            let saved = settings::save(&self.saved_config, &self.config)?;
+            self.config = match &self.project {
+                // The project's keys stay on top, under what was just changed
+                Some(project) => settings::apply_changes(
+                    &self.saved_config,
+                    &self.config,
+                    &project.apply(&saved)?,
+                )?,
+                None => saved,
             };
-
-            if completed {
-                let _ = db.complete_session(session_id, duration as i32);
-
-                // Try to sync to cloud (silently fails if offline or not logged in)
-                let _ = sync::try_sync_session(db.connection(), session_id);
-
-                // Update today's stats for work sessions
-                if state == TimerState::Work {
-                    self.today_work_seconds += duration as i32;
-                    self.today_sessions += 1;
-                    self.total_sessions += 1;
-
-                    // Refresh streak info (may have started a new streak today)
-                    if let Ok(streak) = db.get_streak() {
-                        self.current_streak = streak.current;
-                        self.longest_streak = streak.longest;
-                    }
-                }
-            }
+            self.saved_config = self.config.clone();
         }
-        self.current_session_id = None;
+        Ok(())
     }
 
     pub fn toggle_settings(&mut self) {
@@ -829,37 +2937,28 @@ impl App {
                         self.accent_index = self.available_accents.len() - 1;
                     }
                 }
-                SettingsItem::WorkDuration => {
-                    if self.config.timer.work_duration < 60 {
-                        self.config.timer.work_duration += 5;
-                    }
+                SettingsItem::WorkDuration if self.config.timer.work_duration < 60 => {
+                    self.config.timer.work_duration += 5;
                 }
-                SettingsItem::ShortBreak => {
-                    if self.config.timer.short_break < 30 {
-                        self.config.timer.short_break += 1;
-                    }
+                SettingsItem::ShortBreak if self.config.timer.short_break < 30 => {
+                    self.config.timer.short_break += 1;
                 }
-                SettingsItem::LongBreak => {
-                    if self.config.timer.long_break < 60 {
-                        self.config.timer.long_break += 5;
-                    }
+                SettingsItem::LongBreak if self.config.timer.long_break < 60 => {
+                    self.config.timer.long_break += 5;
+                }
+                SettingsItem::AmbienceVolume if self.config.ambience.volume < 100 => {
+                    self.config.ambience.volume = (self.config.ambience.volume + 10).min(100);
                 }
                 SettingsItem::AutoStart => {
                     self.config.timer.auto_start = !self.config.timer.auto_start;
                 }
+                SettingsItem::NotePrompt => {
+                    self.config.timer.note_prompt = !self.config.timer.note_prompt;
+                }
                 SettingsItem::FocusMode => {
-                    if self.focus_mode_index > 0 {
-                        self.focus_mode_index -= 1;
-                    } else {
-                        self.focus_mode_index = 1; // Cycle: Classic(0) <-> Flowtime(1)
-                    }
-                    self.config.focus.mode = match self.focus_mode_index {
-                        0 => FocusMode::Classic,
-                        _ => FocusMode::Flowtime,
-                    };
-                    // Update timer flowtime mode
-                    self.timer
-                        .set_flowtime(self.config.focus.mode == FocusMode::Flowtime);
+                    // Cycle: Classic(0) -> Timer(2) -> Flowtime(1) -> Classic(0)
+                    self.focus_mode_index = (self.focus_mode_index + 2) % 3;
+                    self.apply_focus_mode();
                 }
                 SettingsItem::BreakSnooze => {
                     self.config.focus.break_snooze_enabled =
@@ -871,41 +2970,47 @@ impl App {
                 SettingsItem::DesktopNotification => {
                     self.config.notifications.desktop = !self.config.notifications.desktop;
                 }
-                SettingsItem::DailySessionsGoal => {
-                    if self.config.goals.daily_sessions < 20 {
-                        self.config.goals.daily_sessions += 1;
-                        // Auto-calculate weekly = daily * 7
-                        self.config.goals.weekly_sessions = self.config.goals.daily_sessions * 7;
-                    }
+                SettingsItem::DailySessionsGoal if self.config.goals.daily_sessions < 20 => {
+                    self.config.goals.daily_sessions += 1;
+                    // Auto-calculate weekly = daily * 7
+                    self.config.goals.weekly_sessions = self.config.goals.daily_sessions * 7;
                 }
-                SettingsItem::DailyMinutesGoal => {
-                    if self.config.goals.daily_minutes < 480 {
-                        self.config.goals.daily_minutes += 30;
-                        // Auto-calculate weekly = daily * 7
-                        self.config.goals.weekly_minutes = self.config.goals.daily_minutes * 7;
-                    }
+                SettingsItem::DailyMinutesGoal if self.config.goals.daily_minutes < 480 => {
+                    self.config.goals.daily_minutes += 30;
+                    // Auto-calculate weekly = daily * 7
+                    self.config.goals.weekly_minutes = self.config.goals.daily_minutes * 7;
                 }
-                SettingsItem::WeeklySessionsGoal => {
-                    if self.config.goals.weekly_sessions < 100 {
-                        self.config.goals.weekly_sessions += 5;
-                    }
+                SettingsItem::WeeklySessionsGoal if self.config.goals.weekly_sessions < 100 => {
+                    self.config.goals.weekly_sessions += 5;
                 }
-                SettingsItem::WeeklyMinutesGoal => {
-                    if self.config.goals.weekly_minutes < 2400 {
-                        self.config.goals.weekly_minutes += 60;
-                    }
+                SettingsItem::WeeklyMinutesGoal if self.config.goals.weekly_minutes < 2400 => {
+                    self.config.goals.weekly_minutes += 60;
                 }
-                SettingsItem::DeleteTag => {
+                SettingsItem::DeleteTag if !self.available_tags.is_empty() => {
                     // Cycle to previous tag for deletion
-                    if !self.available_tags.is_empty() {
-                        if self.delete_tag_index > 0 {
-                            self.delete_tag_index -= 1;
-                        } else {
-                            self.delete_tag_index = self.available_tags.len() - 1;
-                        }
+                    if self.delete_tag_index > 0 {
+                        self.delete_tag_index -= 1;
+                    } else {
+                        self.delete_tag_index = self.available_tags.len() - 1;
                     }
                 }
-                SettingsItem::EditSessionTag | SettingsItem::DeleteSession => {
+                SettingsItem::TagDefaults => self.cycle_tag_settings(false),
+                SettingsItem::TagWork => {
+                    // From the setting, then up in steps of 5 to two hours
+                    let work = &mut self.tag_settings.work_minutes;
+                    *work =
+                        Some(work.map_or(self.config.timer.work_duration, |m| (m + 5).min(120)));
+                    self.save_tag_settings();
+                }
+                SettingsItem::TagBreak => {
+                    let short = &mut self.tag_settings.break_minutes;
+                    *short = Some(short.map_or(self.config.timer.short_break, |m| (m + 1).min(30)));
+                    self.save_tag_settings();
+                }
+                SettingsItem::EditSessionTag
+                | SettingsItem::EditSessionNote
+                | SettingsItem::MergeSession
+                | SettingsItem::DeleteSession => {
                     // Cycle to previous session
                     self.cycle_session_edit_back();
                 }
@@ -945,37 +3050,28 @@ impl App {
                         self.accent_index = 0;
                     }
                 }
-                SettingsItem::WorkDuration => {
-                    if self.config.timer.work_duration > 5 {
-                        self.config.timer.work_duration -= 5;
-                    }
+                SettingsItem::WorkDuration if self.config.timer.work_duration > 5 => {
+                    self.config.timer.work_duration -= 5;
                 }
-                SettingsItem::ShortBreak => {
-                    if self.config.timer.short_break > 1 {
-                        self.config.timer.short_break -= 1;
-                    }
+                SettingsItem::ShortBreak if self.config.timer.short_break > 1 => {
+                    self.config.timer.short_break -= 1;
                 }
-                SettingsItem::LongBreak => {
-                    if self.config.timer.long_break > 5 {
-                        self.config.timer.long_break -= 5;
-                    }
+                SettingsItem::LongBreak if self.config.timer.long_break > 5 => {
+                    self.config.timer.long_break -= 5;
+                }
+                SettingsItem::AmbienceVolume => {
+                    self.config.ambience.volume = self.config.ambience.volume.saturating_sub(10);
                 }
                 SettingsItem::AutoStart => {
                     self.config.timer.auto_start = !self.config.timer.auto_start;
                 }
+                SettingsItem::NotePrompt => {
+                    self.config.timer.note_prompt = !self.config.timer.note_prompt;
+                }
                 SettingsItem::FocusMode => {
-                    if self.focus_mode_index < 1 {
-                        self.focus_mode_index += 1;
-                    } else {
-                        self.focus_mode_index = 0; // Cycle: Flowtime(1) -> Classic(0)
-                    }
-                    self.config.focus.mode = match self.focus_mode_index {
-                        0 => FocusMode::Classic,
-                        _ => FocusMode::Flowtime,
-                    };
-                    // Update timer flowtime mode
-                    self.timer
-                        .set_flowtime(self.config.focus.mode == FocusMode::Flowtime);
+                    // Cycle: Classic(0) -> Flowtime(1) -> Timer(2) -> Classic(0)
+                    self.focus_mode_index = (self.focus_mode_index + 1) % 3;
+                    self.apply_focus_mode();
                 }
                 SettingsItem::BreakSnooze => {
                     self.config.focus.break_snooze_enabled =
@@ -987,12 +3083,10 @@ impl App {
                 SettingsItem::DesktopNotification => {
                     self.config.notifications.desktop = !self.config.notifications.desktop;
                 }
-                SettingsItem::DailySessionsGoal => {
-                    if self.config.goals.daily_sessions > 0 {
-                        self.config.goals.daily_sessions -= 1;
-                        // Auto-calculate weekly = daily * 7
-                        self.config.goals.weekly_sessions = self.config.goals.daily_sessions * 7;
-                    }
+                SettingsItem::DailySessionsGoal if self.config.goals.daily_sessions > 0 => {
+                    self.config.goals.daily_sessions -= 1;
+                    // Auto-calculate weekly = daily * 7
+                    self.config.goals.weekly_sessions = self.config.goals.daily_sessions * 7;
                 }
                 SettingsItem::DailyMinutesGoal => {
                     if self.config.goals.daily_minutes >= 30 {
@@ -1021,7 +3115,22 @@ impl App {
                     // Cycle to next tag for deletion
                     self.cycle_delete_tag();
                 }
-                SettingsItem::EditSessionTag | SettingsItem::DeleteSession => {
+                SettingsItem::TagDefaults => self.cycle_tag_settings(true),
+                SettingsItem::TagWork => {
+                    // Below 5 minutes goes back to the setting
+                    let work = &mut self.tag_settings.work_minutes;
+                    *work = work.filter(|m| *m > 5).map(|m| m - 5);
+                    self.save_tag_settings();
+                }
+                SettingsItem::TagBreak => {
+                    let short = &mut self.tag_settings.break_minutes;
+                    *short = short.filter(|m| *m > 1).map(|m| m - 1);
+                    self.save_tag_settings();
+                }
+                SettingsItem::EditSessionTag
+                | SettingsItem::EditSessionNote
+                | SettingsItem::MergeSession
+                | SettingsItem::DeleteSession => {
                     // Cycle to next session
                     self.cycle_session_edit();
                 }
@@ -1067,7 +3176,8 @@ impl App {
             | SettingsItem::Icon
             | SettingsItem::WorkDuration
             | SettingsItem::ShortBreak
-            | SettingsItem::LongBreak => {
+            | SettingsItem::LongBreak
+            | SettingsItem::AmbienceVolume => {
                 if self.editing {
                     // Apply changes
                     self.editing = false;
@@ -1081,6 +3191,10 @@ impl App {
                 self.config.timer.auto_start = !self.config.timer.auto_start;
                 self.apply_settings();
             }
+            SettingsItem::NotePrompt => {
+                self.config.timer.note_prompt = !self.config.timer.note_prompt;
+                self.apply_settings();
+            }
             SettingsItem::FocusMode => {
                 if self.editing {
                     // Apply changes
@@ -1095,16 +3209,93 @@ impl App {
                 self.config.focus.break_snooze_enabled = !self.config.focus.break_snooze_enabled;
                 self.apply_settings();
             }
+            SettingsItem::AnimationSpeed => {
+                // Cycle animation speed directly; it takes effect on the next tick
+                self.config.appearance.animation_speed =
+                    self.config.appearance.animation_speed.next();
+                self.apply_settings();
+            }
+            SettingsItem::RainbowPalette => {
+                // Cycle palettes directly so the accent previews each one
+                let rainbow = &mut self.config.appearance.rainbow;
+                let mut names: Vec<&str> = RAINBOW_PALETTES.iter().map(|(n, _)| *n).collect();
+                if !rainbow.colors.is_empty() {
+                    names.push(CUSTOM_RAINBOW);
+                }
+                let current = names.iter().position(|n| *n == rainbow.palette);
+                let next = current.map_or(0, |i| (i + 1) % names.len());
+                rainbow.palette = names[next].to_string();
+                self.rainbow_frame = 0;
+                self.apply_settings();
+            }
+            SettingsItem::RainbowSpeed => {
+                // The next slower speed, wrapping to the fastest
+                let rainbow = &mut self.config.appearance.rainbow;
+                rainbow.speed_ms = RAINBOW_SPEEDS
+                    .into_iter()
+                    .find(|ms| *ms > rainbow.speed_ms)
+                    .unwrap_or(RAINBOW_SPEEDS[0]);
+                self.rainbow_tick = 0;
+                self.apply_settings();
+            }
+            SettingsItem::NamedTimer => {
+                // Cycle the named timers; in timer mode the timer switches
+                // over, paused at the start
+                self.config.focus.next_timer();
+                let minutes = self.config.focus.timer_minutes();
+                if minutes.is_some() && minutes != self.timer.timer_minutes {
+                    self.interrupt_current_session();
+                    self.timer.set_named_timer(minutes);
+                }
+                self.apply_settings();
+            }
+            SettingsItem::BreakActivity => {
+                // Cycle break activity directly
+                self.config.focus.break_activity = self.config.focus.break_activity.next();
+                self.apply_settings();
+            }
             SettingsItem::SoundEnabled => {
                 // Toggle sound directly
                 self.config.notifications.sound = !self.config.notifications.sound;
                 self.apply_settings();
             }
+            SettingsItem::SoundChoice => {
+                // Cycle the built-in sounds, playing each one as a preview
+                let notifications = &mut self.config.notifications;
+                notifications.sound_name = notifications.sound_name.next();
+                if notifications.sound {
+                    notification::play_sound(TimerState::Work, &notifications.sound_name, 0.5);
+                }
+                self.apply_settings();
+            }
+            SettingsItem::Ambience => {
+                // Cycle ambient sounds directly; it plays from the next work session
+                self.config.ambience.sound = self.config.ambience.sound.next();
+                self.apply_settings();
+            }
+            SettingsItem::ProgressCues => {
+                // Toggle between the default thresholds and off
+                if self.config.notifications.progress_cues.is_empty() {
+                    self.config.notifications.progress_cues = DEFAULT_PROGRESS_CUES.to_vec();
+                } else {
+                    self.config.notifications.progress_cues.clear();
+                }
+                self.apply_settings();
+            }
             SettingsItem::DesktopNotification => {
                 // Toggle desktop notification directly
                 self.config.notifications.desktop = !self.config.notifications.desktop;
                 self.apply_settings();
             }
+            SettingsItem::NotificationRouting => {
+                // Edited in its own overlay, one event per row
+                self.routes_editor = Some(0);
+            }
+            SettingsItem::DoNotDisturb => {
+                // Toggle directly; the run loop switches it from the next work session
+                self.config.dnd.enabled = !self.config.dnd.enabled;
+                self.apply_settings();
+            }
             SettingsItem::DailySessionsGoal
             | SettingsItem::DailyMinutesGoal
             | SettingsItem::WeeklySessionsGoal
@@ -1144,6 +3335,28 @@ impl App {
                     self.editing = true;
                 }
             }
+            SettingsItem::TagDefaults | SettingsItem::TagWork | SettingsItem::TagBreak => {
+                // Changes are stored as they're made
+                self.editing = !self.editing && !self.available_tags.is_empty();
+            }
+            SettingsItem::TagIcon if !self.available_tags.is_empty() => {
+                // Cycle: the icon setting, then each icon
+                let icons = &self.available_icons;
+                let current = self
+                    .tag_settings
+                    .icon
+                    .as_deref()
+                    .and_then(IconType::from_str)
+                    .and_then(|icon| icons.iter().position(|i| *i == icon));
+                self.tag_settings.icon = match current {
+                    None => icons.first(),
+                    Some(i) => icons.get(i + 1),
+                }
+                .map(|icon| icon.to_string());
+                self.save_tag_settings();
+            }
+            SettingsItem::TagIcon => {}
+            SettingsItem::TagColor => self.cycle_tag_color(),
             SettingsItem::SessionsHeader => {
                 // Header is not selectable, skip to next item
             }
@@ -1168,6 +3381,26 @@ impl App {
                     }
                 }
             }
+            SettingsItem::EditSessionNote => {
+                if self.editing {
+                    // Confirm: type the selected session's note
+                    self.editing = false;
+                    self.edit_current_session_note();
+                } else if !self.recent_sessions.is_empty() {
+                    // Enter edit mode to select the session
+                    self.editing = true;
+                }
+            }
+            SettingsItem::MergeSession => {
+                if self.editing {
+                    // Confirm: merge the selected session with the one before it
+                    self.merge_current_session();
+                    self.editing = false;
+                } else if self.recent_sessions.len() > 1 {
+                    // Enter edit mode to select which session to merge
+                    self.editing = true;
+                }
+            }
             SettingsItem::DeleteSession => {
                 if self.editing {
                     // Confirm: delete the selected session
@@ -1191,25 +3424,36 @@ impl App {
         // Apply icon
         self.config.appearance.icon = self.available_icons[self.icon_index].to_string();
 
-        // Only recreate timer if duration settings changed
-        let duration_changed = self.timer.work_duration != self.config.timer.work_duration
-            || self.timer.short_break_duration != self.config.timer.short_break
-            || self.timer.long_break_duration != self.config.timer.long_break
-            || self.timer.sessions_until_long_break != self.config.timer.sessions_until_long;
+        // Only recreate timer if duration settings changed; a routine sets
+        // its own lengths until it ends
+        let duration_changed = self.routine.is_none()
+            && (self.timer.work_duration != self.work_minutes()
+                || self.timer.short_break_duration != self.short_break_minutes()
+                || self.timer.long_break_duration != self.config.timer.long_break
+                || self.timer.sessions_until_long_break != self.config.timer.sessions_until_long);
 
         if duration_changed {
+            let speed = self.timer.speed;
             self.timer = Timer::with_sessions(
-                self.config.timer.work_duration,
-                self.config.timer.short_break,
+                self.work_minutes(),
+                self.short_break_minutes(),
                 self.config.timer.long_break,
                 self.config.timer.sessions_until_long,
             );
+            self.timer.speed = speed;
+            self.timer
+                .set_named_timer(self.config.focus.timer_minutes());
         }
 
         // Save config
-        if let Err(e) = self.config.save() {
+        if let Err(e) = self.save_config() {
             eprintln!("Failed to save config: {}", e);
         }
+        if let Some(db) = self.database() {
+            let _ = db
+                .today()
+                .and_then(|today| goals::record_week(db, &self.config, today));
+        }
     }
 
     #[allow(dead_code)]
@@ -1231,6 +3475,13 @@ impl App {
                     "OFF".to_string()
                 }
             }
+            SettingsItem::NotePrompt => {
+                if self.config.timer.note_prompt {
+                    "ON".to_string()
+                } else {
+                    "OFF".to_string()
+                }
+            }
             SettingsItem::SoundEnabled => {
                 if self.config.notifications.sound {
                     "ON".to_string()
@@ -1238,6 +3489,21 @@ impl App {
                     "OFF".to_string()
                 }
             }
+            SettingsItem::SoundChoice => self
+                .config
+                .notifications
+                .sound_name
+                .label_with_lang(&self.config.appearance.language),
+            SettingsItem::Ambience => self
+                .config
+                .ambience
+                .sound
+                .label_with_lang(&self.config.appearance.language)
+                .to_string(),
+            SettingsItem::AmbienceVolume => format!("{}%", self.config.ambience.volume),
+            SettingsItem::ProgressCues => {
+                format_progress_cues(&self.config.notifications.progress_cues)
+            }
             SettingsItem::DesktopNotification => {
                 if self.config.notifications.desktop {
                     "ON".to_string()
@@ -1245,6 +3511,16 @@ impl App {
                     "OFF".to_string()
                 }
             }
+            SettingsItem::NotificationRouting => {
+                routes_summary(&self.config.notifications.routes).to_string()
+            }
+            SettingsItem::DoNotDisturb => {
+                if self.config.dnd.enabled {
+                    "ON".to_string()
+                } else {
+                    "OFF".to_string()
+                }
+            }
             SettingsItem::DailySessionsGoal => {
                 if self.config.goals.daily_sessions == 0 {
                     "Not set".to_string()
@@ -1274,7 +3550,7 @@ impl App {
                 }
             }
             SettingsItem::FocusMode => {
-                let modes = ["🍅 Classic", "🌊 Flowtime"];
+                let modes = ["🍅 Classic", "🌊 Flowtime", "⏱ Timer"];
                 modes[self.focus_mode_index].to_string()
             }
             SettingsItem::BreakSnooze => {
@@ -1284,19 +3560,72 @@ impl App {
                     "OFF".to_string()
                 }
             }
+            SettingsItem::AnimationSpeed => self
+                .config
+                .appearance
+                .animation_speed
+                .label_with_lang(&self.config.appearance.language)
+                .to_string(),
+            SettingsItem::RainbowPalette => self.rainbow().summary(),
+            SettingsItem::RainbowSpeed => {
+                format!("{} ms", self.config.appearance.rainbow.speed_ms)
+            }
+            SettingsItem::NamedTimer => self
+                .config
+                .focus
+                .named_timer()
+                .map(|t| t.summary(&self.config.appearance.language))
+                .unwrap_or_default(),
+            SettingsItem::BreakActivity => self
+                .config
+                .focus
+                .break_activity
+                .label_with_lang(&self.config.appearance.language)
+                .to_string(),
             SettingsItem::TagsHeader | SettingsItem::AddTag | SettingsItem::DeleteTag => {
                 String::new()
             }
+            ref item @ (SettingsItem::TagDefaults
+            | SettingsItem::TagWork
+            | SettingsItem::TagBreak
+            | SettingsItem::TagIcon
+            | SettingsItem::TagColor) => self.tag_setting_value(item),
             SettingsItem::SessionsHeader
             | SettingsItem::EditSessionTag
+            | SettingsItem::EditSessionNote
+            | SettingsItem::MergeSession
             | SettingsItem::DeleteSession => String::new(),
             SettingsItem::Back => String::new(),
         }
     }
 
+    /// Value shown for the tag defaults items; "-" keeps the setting
+    pub fn tag_setting_value(&self, item: &SettingsItem) -> String {
+        let Some(tag) = self.available_tags.get(self.tag_defaults_index) else {
+            return "(no tags)".to_string();
+        };
+        let minutes = |m: Option<u32>| m.map_or("-".to_string(), |m| format!("{} min", m));
+        match item {
+            SettingsItem::TagDefaults => tag.name.clone(),
+            SettingsItem::TagWork => minutes(self.tag_settings.work_minutes),
+            SettingsItem::TagBreak => minutes(self.tag_settings.break_minutes),
+            SettingsItem::TagIcon => self
+                .tag_settings
+                .icon
+                .clone()
+                .unwrap_or_else(|| "-".to_string()),
+            SettingsItem::TagColor => tag.color.clone().unwrap_or_else(|| "-".to_string()),
+            _ => String::new(),
+        }
+    }
+
     /// Get the current icon type
     pub fn current_icon(&self) -> IconType {
-        self.available_icons[self.icon_index]
+        self.tag_defaults
+            .icon
+            .as_deref()
+            .and_then(IconType::from_str)
+            .unwrap_or(self.available_icons[self.icon_index])
     }
 
     /// Get the current accent color name
@@ -1308,12 +3637,322 @@ impl App {
     pub fn is_rainbow_mode(&self) -> bool {
         self.current_accent() == "rainbow"
     }
+
+    /// The rainbow accent's palette and speed from config
+    pub fn rainbow(&self) -> Rainbow {
+        Rainbow::from_config(&self.config.appearance.rainbow)
+    }
+
+    /// Accent for the current timer state, falling back to the global accent
+    /// when no valid per-state accent is configured
+    pub fn state_accent(&self) -> &str {
+        let accents = &self.config.appearance.state_accents;
+        let configured = match self.timer.state {
+            TimerState::Work | TimerState::Timer => &accents.work,
+            TimerState::ShortBreak => &accents.short_break,
+            TimerState::LongBreak => &accents.long_break,
+        };
+        configured
+            .as_deref()
+            .filter(|name| self.available_accents.iter().any(|a| a == name))
+            .unwrap_or_else(|| self.current_accent())
+    }
+}
+
+/// Progress cue thresholds as shown in Settings, e.g. "50%, 90%"
+pub fn format_progress_cues(thresholds: &[u32]) -> String {
+    if thresholds.is_empty() {
+        "OFF".to_string()
+    } else {
+        thresholds
+            .iter()
+            .map(|t| format!("{}%", t))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// Stats and lists the header, goal bar and settings read from storage
+pub struct Loaded {
+    today_work_seconds: i32,
+    today_sessions: i32,
+    today_by_tag: Vec<(Option<Tag>, i32)>,
+    skip_penalty: Option<SkipPenalty>,
+    work_length_tip: Option<String>,
+    capacity_warning: Option<String>,
+    current_streak: i32,
+    longest_streak: i32,
+    xp: Option<i64>,
+    week_plan: Option<WeekPlan>,
+    yesterday_seconds: i32,
+    week_avg_seconds: i32,
+    total_sessions: i32,
+    available_tags: Vec<Tag>,
+    last_tag_id: Option<i64>,
+    recent_sessions: Vec<(Session, Option<Tag>)>,
+}
+
+impl Loaded {
+    /// Read everything at once; zeros and empty lists without storage
+    pub fn load(db: Option<&dyn Storage>, config: &Config) -> Self {
+        let (today_work_seconds, today_sessions) = db
+            .and_then(|d| d.get_today_stats().ok())
+            .map(|s| (s.total_work_seconds, s.sessions_completed))
+            .unwrap_or((0, 0));
+        let sqlite = db.and_then(|d| d.sqlite());
+
+        // Get streak info
+        let (current_streak, longest_streak) = db
+            .and_then(|d| d.get_streak().ok())
+            .map(|s| (s.current, s.longest))
+            .unwrap_or((0, 0));
+
+        // Get week stats for average
+        let week_avg_seconds = db
+            .and_then(|d| d.get_week_stats().ok())
+            .map(|s| s.total_work_seconds / 7)
+            .unwrap_or(0);
+
+        // Get yesterday's stats
+        let yesterday_seconds = db
+            .and_then(|d| {
+                use chrono::Duration;
+                let yesterday = (clock::now() - Duration::days(1))
+                    .format("%Y-%m-%d")
+                    .to_string();
+                d.get_date_stats(&yesterday).ok()
+            })
+            .map(|s| s.total_work_seconds)
+            .unwrap_or(0);
+
+        if let Some(d) = sqlite {
+            let _ = d
+                .today()
+                .and_then(|today| goals::record_week(d, config, today));
+        }
+
+        Self {
+            today_work_seconds,
+            today_sessions,
+            today_by_tag: db
+                .and_then(|d| d.get_today_stats_by_tag().ok())
+                .unwrap_or_default(),
+            skip_penalty: sqlite
+                .and_then(|d| accountability::today(d, &config.accountability).ok())
+                .flatten(),
+            work_length_tip: sqlite
+                .and_then(|d| insights::daily_tip(d, config).ok())
+                .flatten(),
+            capacity_warning: sqlite
+                .and_then(|d| capacity::daily_warning(d, config).ok())
+                .flatten(),
+            current_streak,
+            longest_streak,
+            xp: sqlite
+                .filter(|_| config.xp.enabled)
+                .and_then(|d| d.total_xp().ok()),
+            week_plan: sqlite.and_then(|d| load_week_plan(d, config)),
+            yesterday_seconds,
+            week_avg_seconds,
+            // Get total sessions count
+            total_sessions: db
+                .and_then(|d| d.get_month_stats().ok())
+                .map(|s| s.sessions_completed)
+                .unwrap_or(0),
+            available_tags: db.and_then(|d| d.get_all_tags().ok()).unwrap_or_default(),
+            last_tag_id: db
+                .filter(|_| config.focus.remember_tag)
+                .and_then(|d| d.get_metadata(LAST_TAG_KEY).ok().flatten())
+                .and_then(|id| id.parse::<i64>().ok()),
+            // Load recent sessions for editing
+            recent_sessions: db
+                .and_then(|d| d.get_recent_sessions(20).ok())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Position of the tag selected when sandoro last ran
+    fn last_tag_index(&self, config: &Config) -> Option<usize> {
+        let id = self.last_tag_id.filter(|_| config.focus.remember_tag)?;
+        self.available_tags.iter().position(|t| t.id == id)
+    }
+}
+
+/// Storage and stats opened on a background thread at startup
+pub struct Startup {
+    pub db: Option<Box<dyn Storage>>,
+    pub loaded: Loaded,
+}
+
+impl Startup {
+    /// Open storage and read the stats on another thread, so the first
+    /// frame doesn't wait for them (seconds on a network-mounted home)
+    fn spawn(config: &Config) -> mpsc::Receiver<Startup> {
+        let (tx, rx) = mpsc::channel();
+        let config = config.clone();
+        std::thread::spawn(move || {
+            let db = storage::open(&config).ok();
+            let sqlite = db.as_deref().and_then(|d| d.sqlite());
+            if let Some(d) = sqlite {
+                // Apply the retention policy (at most once a day)
+                let _ = retention::run_scheduled(d, &config);
+            }
+            let is_sqlite = sqlite.is_some();
+            let loaded = Loaded::load(db.as_deref(), &config);
+            let _ = tx.send(Startup { db, loaded });
+
+            // Sync sessions left from offline use last, on a connection of
+            // its own, since it waits on the network
+            if is_sqlite {
+                if let Ok(d) = Database::open() {
+                    let _ = sync::try_sync_pending(d.connection());
+                }
+            }
+        });
+        rx
+    }
+}
+
+/// Notification routes as shown in Settings
+pub fn routes_summary(routes: &NotificationRoutes) -> &'static str {
+    if *routes == NotificationRoutes::default() {
+        "Default"
+    } else {
+        "Custom"
+    }
+}
+
+/// This week's plan, or None when nothing is planned
+fn load_week_plan(db: &Database, config: &Config) -> Option<WeekPlan> {
+    let start = plan::week_of(config, db.today().ok()?, 0);
+    plan::load(db, start).ok().filter(|p| !p.is_empty())
+}
+
+/// The session type recorded for a timer state
+fn session_type(state: TimerState) -> SessionType {
+    match state {
+        TimerState::Work => SessionType::Work,
+        TimerState::ShortBreak => SessionType::ShortBreak,
+        TimerState::LongBreak => SessionType::LongBreak,
+        TimerState::Timer => SessionType::Timer,
+    }
+}
+
+/// How a session is shown above its note: "10/17 09:00 · 25m · writing"
+fn session_label(started_at: DateTime<Local>, seconds: u32, tag: Option<&Tag>) -> String {
+    let mut label = format!("{} · {}m", started_at.format("%m/%d %H:%M"), seconds / 60);
+    if let Some(tag) = tag {
+        label.push_str(" · ");
+        label.push_str(&tag.name);
+    }
+    label
+}
+
+/// Seed for a new snake game
+fn snake_seed() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(1)
+}
+
+/// One-off settings for the first session of a run
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SessionOverrides {
+    pub tag: Option<String>,
+    /// Durations in minutes
+    pub work: Option<u32>,
+    pub short_break: Option<u32>,
+    pub long_break: Option<u32>,
+    /// Start the timer immediately
+    pub autostart: bool,
+}
+
+/// Options for a TUI run, set from the command line
+#[derive(Debug, Default)]
+pub struct RunOptions {
+    /// Skip workspace detection
+    pub no_workspace: bool,
+    /// Force low-power mode regardless of config and power source
+    pub low_power: bool,
+    /// Keep everything in memory and save nothing
+    pub ephemeral: bool,
+    /// Run `sandoro demo` instead of the normal timer
+    pub demo: Option<Demo>,
+    /// Always use the three-line timer
+    pub compact: bool,
+    /// Take over the work session running on another device
+    pub resume_remote: bool,
+    /// Start this routine straight away
+    pub routine: Option<String>,
+    pub overrides: SessionOverrides,
 }
 
 /// Run the TUI application
-pub fn run() -> Result<()> {
-    // Load config
-    let config = Config::load().unwrap_or_default();
+pub fn run(options: RunOptions) -> Result<()> {
+    // Load config, with the project's .sandoro.toml over it. A broken one
+    // stops here, before the screen changes.
+    let project = match std::env::current_dir() {
+        Ok(dir) if !options.no_workspace => ProjectConfig::find(&dir)?,
+        _ => None,
+    };
+    if let Some(project) = &project {
+        for key in &project.ignored {
+            eprintln!(
+                "Ignoring {} in {}: a project file may only set tag, {}",
+                key,
+                project.path.display(),
+                crate::config::PROJECT_TABLES.join(", ")
+            );
+        }
+    }
+    let config = Config::load_with(project.as_ref())?;
+    let workspace = if options.no_workspace {
+        None
+    } else {
+        project
+            .as_ref()
+            .and_then(workspace::from_project)
+            .or_else(|| workspace::detect(&config.workspaces))
+    };
+
+    // Create app state
+    let mut app = if let Some(demo) = options.demo {
+        App::demo(config, demo)?
+    } else if options.ephemeral {
+        App::ephemeral(config)?
+    } else {
+        App::new(config)
+    };
+
+    app.project = project;
+    if let Some(ws) = workspace {
+        app.apply_workspace(ws);
+    }
+    app.apply_overrides(&options.overrides);
+    // Before the screen changes, so an unknown name prints normally
+    if let Some(name) = &options.routine {
+        app.start_routine(name)?;
+    }
+    app.force_low_power = options.low_power;
+    app.compact = options.compact;
+
+    // Take over before the screen changes, so a refusal prints normally
+    let mut resumed_from = None;
+    if options.resume_remote {
+        if app.ephemeral {
+            anyhow::bail!("--resume-remote records the session, so it can't be ephemeral.");
+        }
+        let adoption = handoff::adopt()?;
+        app.adopt_remote(&adoption);
+        resumed_from = Some(adoption.from_device.id);
+    }
+    // Publish running work for other devices (when logged in)
+    let mut heartbeat = if app.ephemeral || !app.config.account.handoff {
+        None
+    } else {
+        handoff::Heartbeat::start(resumed_from).ok().flatten()
+    };
 
     // Setup terminal
     enable_raw_mode()?;
@@ -1322,94 +3961,122 @@ pub fn run() -> Result<()> {
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    // Create app state
-    let mut app = App::new(config);
+    // Let `sandoro snooze` and friends reach this timer. An ephemeral run
+    // touches no files, and a second timer leaves the socket to the first.
+    let control = if app.ephemeral {
+        None
+    } else {
+        control::Server::bind().ok().flatten()
+    };
 
     // Main loop
-    let tick_rate = Duration::from_millis(100);
+    let mut last_tick = Instant::now();
+    let mut last_frame: Option<FrameKey> = None;
+    let mut needs_redraw = true;
+    let mut title: Option<String> = None;
+    let mut progress: Option<terminal::Progress> = None;
+    let mut focus_lock = tmux::FocusLock::new(&app.config.tmux);
+    let mut ambience = Ambience::default();
+    let mut dnd = FocusDnd::new(&app.config.dnd);
     loop {
-        // Draw UI
-        terminal.draw(|f| ui::draw(f, &app))?;
+        app.refresh_power();
+        if app.refresh_lock() {
+            needs_redraw = true;
+        }
+        ambience.update(app.ambience());
+        if let Err(err) = dnd.update(app.dnd_focus()) {
+            app.dnd_error = Some(err);
+            needs_redraw = true;
+        }
+
+        if let Some(lock) = focus_lock.as_mut() {
+            let (working, vars) = app.tmux_focus();
+            if let Err(err) = lock.update(working, &vars) {
+                app.tmux_error = Some(err);
+                needs_redraw = true;
+            }
+        }
+
+        // Set when it changes: on transitions, or every second with the time
+        let wanted = app.terminal_title();
+        if wanted != title {
+            execute!(
+                terminal.backend_mut(),
+                SetTitle(wanted.as_deref().unwrap_or(""))
+            )?;
+            title = wanted;
+        }
+        let wanted = app.taskbar_progress();
+        if wanted != progress {
+            write!(terminal.backend_mut(), "{}", terminal::osc_progress(wanted))?;
+            terminal.backend_mut().flush()?;
+            progress = wanted;
+        }
+
+        // Draw UI. In low-power mode, only when something on screen changed.
+        let frame = app.frame_key();
+        if needs_redraw || !app.low_power || last_frame.as_ref() != Some(&frame) {
+            terminal.draw(|f| ui::draw(f, &app))?;
+            last_frame = Some(frame);
+            needs_redraw = false;
+        }
 
         // Handle input
-        if event::poll(tick_rate)? {
-            if let Event::Key(key) = event::read()? {
-                match app.view {
-                    AppView::Timer => match key.code {
-                        KeyCode::Char('q') => app.should_quit = true,
-                        KeyCode::Char(' ') => app.toggle_pause(),
-                        KeyCode::Char('r') => app.reset(),
-                        KeyCode::Char('R') => app.full_reset(),
-                        KeyCode::Char('s') => app.skip(),
-                        KeyCode::Char('t') => app.cycle_tag(),
-                        KeyCode::Char('m') => app.cycle_focus_mode(),
-                        KeyCode::Char('z') => app.snooze_break(),
-                        KeyCode::Tab => app.toggle_settings(),
-                        _ => {}
-                    },
-                    AppView::Settings => {
-                        // Handle tag input mode separately
-                        if app.tag_input_mode {
-                            match key.code {
-                                KeyCode::Enter => {
-                                    // Confirm tag input
-                                    if !app.tag_input.is_empty() {
-                                        let name = app.tag_input.clone();
-                                        app.add_tag(&name);
-                                        app.tag_input.clear();
-                                    }
-                                    app.tag_input_mode = false;
-                                }
-                                KeyCode::Esc => {
-                                    // Cancel tag input
-                                    app.tag_input.clear();
-                                    app.tag_input_mode = false;
-                                }
-                                KeyCode::Backspace => {
-                                    app.tag_input.pop();
-                                }
-                                KeyCode::Char(c) => {
-                                    // Add character to input (limit length)
-                                    if app.tag_input.len() < 30 {
-                                        app.tag_input.push(c);
-                                    }
-                                }
-                                _ => {}
-                            }
-                        } else {
-                            match key.code {
-                                KeyCode::Char('q') => {
-                                    if !app.editing {
-                                        app.should_quit = true;
-                                    }
-                                }
-                                KeyCode::Tab | KeyCode::Esc => {
-                                    if app.editing {
-                                        app.editing = false;
-                                    } else {
-                                        app.toggle_settings();
-                                    }
-                                }
-                                KeyCode::Up | KeyCode::Char('k') => app.settings_up(),
-                                KeyCode::Down | KeyCode::Char('j') => app.settings_down(),
-                                KeyCode::Enter | KeyCode::Char(' ') => app.settings_select(),
-                                _ => {}
-                            }
-                        }
-                    }
-                }
+        let timeout = app.tick_rate().saturating_sub(last_tick.elapsed());
+        if event::poll(timeout)? {
+            let event = event::read()?;
+            needs_redraw = true;
+            if let Event::Key(key) = event {
+                app.handle_key(key);
             }
         }
 
         // Update timer
-        app.tick();
+        if last_tick.elapsed() >= app.tick_rate() {
+            app.tick();
+            last_tick = Instant::now();
+        }
+        if let Some(server) = &control {
+            server.poll(|command| {
+                needs_redraw = true;
+                app.handle_control(command)
+            });
+        }
+        if app.handle_notification_clicks() {
+            needs_redraw = true;
+        }
+        app.publish_status();
+        if let Some(heartbeat) = heartbeat.as_mut() {
+            if let Some(to) = heartbeat.tick(app.handoff_beat()) {
+                app.handed_off(&to);
+                needs_redraw = true;
+            }
+        }
 
         if app.should_quit {
             break;
         }
     }
 
+    app.save_session_on_exit();
+    if let Some(lock) = focus_lock.as_mut() {
+        lock.release();
+    }
+    dnd.release();
+    if let Some(heartbeat) = heartbeat {
+        heartbeat.finish();
+    }
+    if !app.ephemeral {
+        let _ = status::clear();
+    }
+
     // Restore terminal
+    if title.is_some() {
+        execute!(terminal.backend_mut(), SetTitle(""))?;
+    }
+    if progress.is_some() {
+        write!(terminal.backend_mut(), "{}", terminal::osc_progress(None))?;
+    }
     disable_raw_mode()?;
     execute!(
         terminal.backend_mut(),
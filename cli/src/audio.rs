@@ -0,0 +1,209 @@
+//! Audio playback
+//!
+//! Synthesizes short tones as WAV and plays them with the platform's audio
+//! player (afplay, paplay/pw-play/aplay, PowerShell), so no native audio
+//! libraries are needed at build time.
+
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+pub const SAMPLE_RATE: u32 = 44_100;
+
+/// A single sine tone followed by an optional silence
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tone {
+    pub frequency: f32,
+    pub duration_ms: u32,
+    pub gap_ms: u32,
+}
+
+impl Tone {
+    pub const fn new(frequency: f32, duration_ms: u32, gap_ms: u32) -> Self {
+        Self {
+            frequency,
+            duration_ms,
+            gap_ms,
+        }
+    }
+}
+
+/// Render tones to 16-bit mono PCM with a short attack and exponential decay
+pub fn synthesize(tones: &[Tone], volume: f32) -> Vec<i16> {
+    let volume = volume.clamp(0.0, 1.0);
+    let attack = (SAMPLE_RATE / 200) as usize; // 5ms, avoids clicks
+    let mut samples = Vec::new();
+
+    for tone in tones {
+        let len = (SAMPLE_RATE as u64 * tone.duration_ms as u64 / 1000) as usize;
+        for n in 0..len {
+            let t = n as f32 / SAMPLE_RATE as f32;
+            let envelope = if n < attack {
+                n as f32 / attack as f32
+            } else {
+                (-4.0 * (n - attack) as f32 / len.max(1) as f32).exp()
+            };
+            let value = (2.0 * std::f32::consts::PI * tone.frequency * t).sin() * envelope * volume;
+            samples.push((value * i16::MAX as f32) as i16);
+        }
+        let gap = (SAMPLE_RATE as u64 * tone.gap_ms as u64 / 1000) as usize;
+        samples.extend(std::iter::repeat_n(0, gap));
+    }
+
+    samples
+}
+
+/// Encode 16-bit mono PCM as a WAV file
+pub fn encode_wav(samples: &[i16]) -> Vec<u8> {
+    let data_len = (samples.len() * 2) as u32;
+    let mut wav = Vec::with_capacity(44 + data_len as usize);
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data_len).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes()); // fmt chunk size
+    wav.extend_from_slice(&1u16.to_le_bytes()); // PCM
+    wav.extend_from_slice(&1u16.to_le_bytes()); // mono
+    wav.extend_from_slice(&SAMPLE_RATE.to_le_bytes());
+    wav.extend_from_slice(&(SAMPLE_RATE * 2).to_le_bytes()); // byte rate
+    wav.extend_from_slice(&2u16.to_le_bytes()); // block align
+    wav.extend_from_slice(&16u16.to_le_bytes()); // bits per sample
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&data_len.to_le_bytes());
+    for s in samples {
+        wav.extend_from_slice(&s.to_le_bytes());
+    }
+    wav
+}
+
+/// Whether an executable is on PATH
+fn on_path(program: &str) -> bool {
+    std::env::var_os("PATH")
+        .map(|paths| {
+            std::env::split_paths(&paths).any(|dir| {
+                let candidate = dir.join(program);
+                candidate.is_file() || candidate.with_extension("exe").is_file()
+            })
+        })
+        .unwrap_or(false)
+}
+
+/// A path for use inside a single-quoted PowerShell string, where the only
+/// special character is `'` (written twice). Temp dirs under profiles like
+/// `C:\Users\O'Brien` would otherwise end the string early.
+fn powershell_quoted(path: &Path) -> String {
+    path.display().to_string().replace('\'', "''")
+}
+
+/// Command that plays an audio file on this platform
+pub fn player_command(path: &Path) -> Option<Command> {
+    if cfg!(target_os = "macos") {
+        let mut cmd = Command::new("afplay");
+        cmd.arg(path);
+        return Some(cmd);
+    }
+    if cfg!(windows) {
+        let mut cmd = Command::new("powershell");
+        cmd.args([
+            "-NoProfile",
+            "-NonInteractive",
+            "-Command",
+            &format!(
+                "(New-Object Media.SoundPlayer '{}').PlaySync()",
+                powershell_quoted(path)
+            ),
+        ]);
+        return Some(cmd);
+    }
+    ["paplay", "pw-play", "aplay"]
+        .iter()
+        .find(|p| on_path(p))
+        .map(|p| {
+            let mut cmd = Command::new(p);
+            if *p == "aplay" {
+                cmd.arg("-q");
+            }
+            cmd.arg(path);
+            cmd
+        })
+}
+
+/// Play an audio file in the background. Returns false if no player is available.
+pub fn play_file(path: &Path) -> bool {
+    let Some(mut cmd) = player_command(path) else {
+        return false;
+    };
+    match cmd
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+    {
+        Ok(mut child) => {
+            // Reap the player when it finishes so it doesn't linger as a zombie
+            std::thread::spawn(move || {
+                let _ = child.wait();
+            });
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+/// Write synthesized tones to a temporary WAV file
+fn write_tones(name: &str, tones: &[Tone], volume: f32) -> io::Result<PathBuf> {
+    let path = std::env::temp_dir().join(format!("sandoro-{}.wav", name));
+    std::fs::write(&path, encode_wav(&synthesize(tones, volume)))?;
+    Ok(path)
+}
+
+/// Play tones in the background, falling back to the terminal bell
+pub fn play_tones(name: &str, tones: &[Tone], volume: f32) {
+    let played = write_tones(name, tones, volume)
+        .map(|path| play_file(&path))
+        .unwrap_or(false);
+    if !played {
+        print!("\x07");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_synthesize_length() {
+        let samples = synthesize(&[Tone::new(440.0, 100, 50)], 0.5);
+        assert_eq!(samples.len(), (SAMPLE_RATE as usize * 150) / 1000);
+        // Trailing gap is silent
+        assert!(samples[samples.len() - 10..].iter().all(|s| *s == 0));
+    }
+
+    #[test]
+    fn test_synthesize_respects_volume() {
+        let quiet = synthesize(&[Tone::new(440.0, 50, 0)], 0.1);
+        let peak = quiet.iter().map(|s| s.unsigned_abs()).max().unwrap();
+        assert!(peak <= (i16::MAX as f32 * 0.1) as u16 + 1);
+        assert!(synthesize(&[Tone::new(440.0, 50, 0)], 0.0)
+            .iter()
+            .all(|s| *s == 0));
+    }
+
+    #[test]
+    fn test_powershell_quoted() {
+        let path = Path::new(r"C:\Users\O'Brien\AppData\Local\Temp\sandoro-bell.wav");
+        assert_eq!(
+            powershell_quoted(path),
+            r"C:\Users\O''Brien\AppData\Local\Temp\sandoro-bell.wav"
+        );
+    }
+
+    #[test]
+    fn test_encode_wav_header() {
+        let wav = encode_wav(&[0, 1, -1]);
+        assert_eq!(&wav[0..4], b"RIFF");
+        assert_eq!(&wav[8..12], b"WAVE");
+        assert_eq!(u32::from_le_bytes(wav[4..8].try_into().unwrap()), 36 + 6);
+        assert_eq!(u32::from_le_bytes(wav[40..44].try_into().unwrap()), 6);
+        assert_eq!(wav.len(), 44 + 6);
+    }
+}
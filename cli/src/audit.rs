@@ -0,0 +1,185 @@
+//! Session audit for `sandoro db audit`
+//!
+//! A system clock that jumps (a manual change, an NTP correction, a laptop
+//! waking up with a stale clock) can leave sessions that end before they
+//! start, overlap each other or sit in the future. Stats already skip the
+//! worst of these; the audit lists them so they can be looked at or deleted.
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashSet;
+
+use crate::db::{Database, Session, CLOCK_SLACK_SECONDS};
+
+/// Slack for a duration longer than the session's wall time. Paused time
+/// only makes wall time longer, so more than this means the clock went back.
+const DURATION_SLACK_SECONDS: i64 = 60;
+
+/// What looks wrong about a session
+#[derive(Debug, Clone, PartialEq)]
+pub enum Problem {
+    NegativeDuration(i32),
+    EndsBeforeStart,
+    InFuture,
+    /// Starts before the given session ended
+    Overlaps(i64),
+    LongerThanWallTime {
+        duration: i32,
+        wall: i64,
+    },
+    /// Flagged by the clock guard when it was recorded
+    Flagged(String),
+}
+
+impl Problem {
+    pub fn describe(&self) -> String {
+        match self {
+            Problem::NegativeDuration(seconds) => format!("negative duration ({}s)", seconds),
+            Problem::EndsBeforeStart => "ends before it starts".to_string(),
+            Problem::InFuture => "starts in the future".to_string(),
+            Problem::Overlaps(other) => format!("overlaps session #{}", other),
+            Problem::LongerThanWallTime { duration, wall } => {
+                format!("lasted {}s in {}s of wall time", duration, wall)
+            }
+            Problem::Flagged(detail) => format!("flagged when recorded: {}", detail),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Anomaly {
+    pub session: Session,
+    pub problem: Problem,
+}
+
+/// Problems in `sessions`, which must be sorted by start time
+pub fn find(sessions: &[Session], now: DateTime<Utc>) -> Vec<Anomaly> {
+    let slack = Duration::seconds(CLOCK_SLACK_SECONDS);
+    let mut anomalies = Vec::new();
+    // Session with the latest end so far: (id, ended_at)
+    let mut latest: Option<(i64, DateTime<Utc>)> = None;
+
+    for session in sessions {
+        let mut problems = Vec::new();
+        if let Some(seconds) = session.duration_seconds.filter(|s| *s < 0) {
+            problems.push(Problem::NegativeDuration(seconds));
+        }
+        if session.started_at > now + slack {
+            problems.push(Problem::InFuture);
+        }
+        if let Some((other, ended_at)) = latest {
+            if ended_at > session.started_at + slack {
+                problems.push(Problem::Overlaps(other));
+            }
+        }
+        if let Some(ended_at) = session.ended_at {
+            let wall = (ended_at - session.started_at).num_seconds();
+            if wall < -CLOCK_SLACK_SECONDS {
+                problems.push(Problem::EndsBeforeStart);
+            } else {
+                let duration = session.duration_seconds.unwrap_or(0);
+                if duration as i64 > wall + DURATION_SLACK_SECONDS {
+                    problems.push(Problem::LongerThanWallTime { duration, wall });
+                }
+                if latest.is_none_or(|(_, end)| ended_at > end) {
+                    latest = Some((session.id, ended_at));
+                }
+            }
+        }
+        anomalies.extend(problems.into_iter().map(|problem| Anomaly {
+            session: session.clone(),
+            problem,
+        }));
+    }
+    anomalies
+}
+
+/// Audit every session, adding flags from the clock guard that the
+/// timestamps alone no longer show
+pub fn audit(db: &Database) -> Result<Vec<Anomaly>> {
+    let mut sessions = db.get_all_sessions()?;
+    // Stored timestamps don't all share one format; sort by the parsed time
+    sessions.sort_by_key(|s| (s.started_at, s.id));
+    let mut anomalies = find(&sessions, Utc::now());
+    let reported: HashSet<i64> = anomalies.iter().map(|a| a.session.id).collect();
+    for (id, detail) in db.get_clock_jump_flags()? {
+        if reported.contains(&id) {
+            continue;
+        }
+        if let Some(session) = sessions.iter().find(|s| s.id == id) {
+            anomalies.push(Anomaly {
+                session: session.clone(),
+                problem: Problem::Flagged(detail),
+            });
+        }
+    }
+    Ok(anomalies)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 2, hour, minute, 0).unwrap()
+    }
+
+    fn session(id: i64, start: DateTime<Utc>, end: DateTime<Utc>, duration: i32) -> Session {
+        Session {
+            id,
+            started_at: start,
+            ended_at: Some(end),
+            duration_seconds: Some(duration),
+            session_type: "work".to_string(),
+            completed: true,
+            notes: None,
+        }
+    }
+
+    fn problems(sessions: &[Session]) -> Vec<(i64, Problem)> {
+        find(sessions, at(23, 0))
+            .into_iter()
+            .map(|a| (a.session.id, a.problem))
+            .collect()
+    }
+
+    #[test]
+    fn test_clean_history_has_no_anomalies() {
+        let sessions = [
+            session(1, at(9, 0), at(9, 25), 1500),
+            // Break starts the moment work ends; paused time only adds wall time
+            session(2, at(9, 25), at(9, 30), 300),
+            session(3, at(9, 30), at(10, 30), 1500),
+        ];
+        assert!(problems(&sessions).is_empty());
+    }
+
+    #[test]
+    fn test_finds_clock_jumps() {
+        let sessions = [
+            session(1, at(9, 0), at(10, 0), 3600),
+            // Clock set back 30 minutes after session 1
+            session(2, at(9, 40), at(10, 5), 1500),
+            session(3, at(11, 0), at(10, 50), -600),
+            session(4, at(12, 0), at(12, 10), 1500),
+            session(5, at(23, 30), at(23, 55), 1500),
+        ];
+        assert_eq!(
+            problems(&sessions),
+            vec![
+                (2, Problem::Overlaps(1)),
+                (3, Problem::NegativeDuration(-600)),
+                (3, Problem::EndsBeforeStart),
+                (
+                    4,
+                    Problem::LongerThanWallTime {
+                        duration: 1500,
+                        wall: 600
+                    }
+                ),
+                (5, Problem::InFuture),
+            ]
+        );
+    }
+}
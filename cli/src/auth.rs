@@ -17,6 +17,9 @@ const SUPABASE_ANON_KEY: &str = "eyJhbGciOiJIUzI1NiIsInR5cCI6IkpXVCJ9.eyJpc3MiOi
 /// Local callback server port
 const CALLBACK_PORT: u16 = 54321;
 
+/// How long to wait for the browser to finish signing in
+const LOGIN_TIMEOUT_SECS: u64 = 300;
+
 /// Stored credentials
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Credentials {
@@ -80,18 +83,24 @@ fn save_credentials(creds: &Credentials) -> Result<()> {
     let path = get_credentials_path()?;
     let content = serde_json::to_string_pretty(creds)?;
 
+    // Restricted before the tokens go in
     let mut file = fs::File::create(&path)?;
+    restrict_to_owner(&file)?;
     file.write_all(content.as_bytes())?;
+    Ok(())
+}
 
-    // Set file permissions (Unix only)
-    #[cfg(unix)]
-    {
-        use std::os::unix::fs::PermissionsExt;
-        let mut perms = file.metadata()?.permissions();
-        perms.set_mode(0o600);
-        fs::set_permissions(&path, perms)?;
-    }
+/// Unix: make the file readable by its owner only (0600). Other platforms
+/// rely on the data dir's own access rules (a per-user profile on Windows).
+#[cfg(unix)]
+fn restrict_to_owner(file: &fs::File) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    file.set_permissions(fs::Permissions::from_mode(0o600))?;
+    Ok(())
+}
 
+#[cfg(not(unix))]
+fn restrict_to_owner(_file: &fs::File) -> Result<()> {
     Ok(())
 }
 
@@ -161,8 +170,13 @@ pub fn login(provider: &str) -> Result<Credentials> {
     println!("{}", auth_url);
 
     // Start local server before opening browser
-    let server = Server::http(format!("127.0.0.1:{}", CALLBACK_PORT))
-        .map_err(|e| anyhow::anyhow!("Failed to start callback server: {}", e))?;
+    let server = Server::http(format!("127.0.0.1:{}", CALLBACK_PORT)).map_err(|e| {
+        anyhow::anyhow!(
+            "Failed to start callback server on port {}: {} (is another 'sandoro login' running?)",
+            CALLBACK_PORT,
+            e
+        )
+    })?;
 
     // Open browser
     if let Err(e) = open::that(&auth_url) {
@@ -188,13 +202,16 @@ pub fn login(provider: &str) -> Result<Credentials> {
 
 /// Wait for OAuth callback and extract tokens
 fn wait_for_callback(server: &Server) -> Result<(String, String)> {
-    // Set timeout
-    let timeout = std::time::Duration::from_secs(300); // 5 minutes
+    let timeout = std::time::Duration::from_secs(LOGIN_TIMEOUT_SECS);
     let start = std::time::Instant::now();
 
     loop {
         if start.elapsed() > timeout {
-            anyhow::bail!("Authentication timed out");
+            anyhow::bail!(
+                "No response from the browser within {} minutes. Run 'sandoro login' again, \
+                 and if no browser opens, visit the URL printed above by hand",
+                LOGIN_TIMEOUT_SECS / 60
+            );
         }
 
         // Non-blocking receive with timeout
@@ -427,8 +444,9 @@ mod tests {
 
     #[test]
     fn test_credentials_path() {
+        // By component, so it holds with either separator
         let path = get_credentials_path().unwrap();
-        assert!(path.to_string_lossy().contains("sandoro"));
-        assert!(path.to_string_lossy().ends_with("credentials.json"));
+        assert_eq!(path.file_name().unwrap(), "credentials.json");
+        assert_eq!(path.parent().unwrap().file_name().unwrap(), "sandoro");
     }
 }
@@ -0,0 +1,155 @@
+//! "Today" board: Planned / Doing / Done columns in a side pane
+//!
+//! Holds the tasks shown and the cursor. The app does the database work
+//! and reloads the board afterwards.
+
+use crate::db::{Task, TaskStatus};
+
+/// Longest task title accepted from the add prompt
+pub const MAX_TITLE_LEN: usize = 60;
+
+#[derive(Debug, Clone, Default)]
+pub struct Board {
+    pub tasks: Vec<Task>,
+    /// Selected column, an index into `TaskStatus::ALL`
+    pub column: usize,
+    /// Selected row within the column
+    pub row: usize,
+    /// Title being typed for a new task
+    pub input: Option<String>,
+}
+
+impl Board {
+    /// Tasks in one column, in board order
+    pub fn column_tasks(&self, status: TaskStatus) -> Vec<&Task> {
+        self.tasks.iter().filter(|t| t.status == status).collect()
+    }
+
+    pub fn selected_status(&self) -> TaskStatus {
+        TaskStatus::ALL[self.column]
+    }
+
+    /// The task under the cursor
+    pub fn selected(&self) -> Option<&Task> {
+        self.column_tasks(self.selected_status())
+            .get(self.row)
+            .copied()
+    }
+
+    /// Replace the tasks, keeping the cursor on `focus` when given
+    pub fn load(&mut self, tasks: Vec<Task>, focus: Option<i64>) {
+        self.tasks = tasks;
+        if let Some(task) = focus.and_then(|id| self.tasks.iter().find(|t| t.id == id)) {
+            self.column = TaskStatus::ALL
+                .iter()
+                .position(|s| *s == task.status)
+                .unwrap_or(0);
+            let status = task.status;
+            let id = task.id;
+            self.row = self
+                .column_tasks(status)
+                .iter()
+                .position(|t| t.id == id)
+                .unwrap_or(0);
+        }
+        self.clamp_row();
+    }
+
+    pub fn left(&mut self) {
+        self.column = self.column.saturating_sub(1);
+        self.clamp_row();
+    }
+
+    pub fn right(&mut self) {
+        self.column = (self.column + 1).min(TaskStatus::ALL.len() - 1);
+        self.clamp_row();
+    }
+
+    pub fn up(&mut self) {
+        self.row = self.row.saturating_sub(1);
+    }
+
+    pub fn down(&mut self) {
+        self.row += 1;
+        self.clamp_row();
+    }
+
+    fn clamp_row(&mut self) {
+        let len = self.column_tasks(self.selected_status()).len();
+        self.row = self.row.min(len.saturating_sub(1));
+    }
+}
+
+/// Column a task moves to with `>` (forward) or `<`
+pub fn shift(status: TaskStatus, forward: bool) -> TaskStatus {
+    match (status, forward) {
+        (TaskStatus::Planned, true) | (TaskStatus::Done, false) => TaskStatus::Doing,
+        (TaskStatus::Doing, true) | (TaskStatus::Done, true) => TaskStatus::Done,
+        (TaskStatus::Doing, false) | (TaskStatus::Planned, false) => TaskStatus::Planned,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(id: i64, status: TaskStatus) -> Task {
+        Task {
+            id,
+            title: format!("task {}", id),
+            status,
+            estimate: None,
+            sessions_done: 0,
+        }
+    }
+
+    #[test]
+    fn test_cursor_stays_inside_columns() {
+        let mut board = Board::default();
+        board.load(
+            vec![
+                task(1, TaskStatus::Planned),
+                task(2, TaskStatus::Planned),
+                task(3, TaskStatus::Doing),
+            ],
+            None,
+        );
+        board.down();
+        board.down();
+        assert_eq!(board.selected().map(|t| t.id), Some(2));
+        board.right();
+        assert_eq!(board.selected().map(|t| t.id), Some(3));
+        board.right();
+        board.right();
+        assert_eq!(board.selected_status(), TaskStatus::Done);
+        assert_eq!(board.selected(), None);
+        board.left();
+        board.left();
+        board.left();
+        assert_eq!(board.column, 0);
+    }
+
+    #[test]
+    fn test_load_follows_moved_task() {
+        let mut board = Board::default();
+        board.load(
+            vec![task(1, TaskStatus::Planned), task(2, TaskStatus::Doing)],
+            None,
+        );
+        board.load(
+            vec![task(1, TaskStatus::Doing), task(2, TaskStatus::Doing)],
+            Some(1),
+        );
+        assert_eq!(board.selected_status(), TaskStatus::Doing);
+        assert_eq!(board.selected().map(|t| t.id), Some(1));
+    }
+
+    #[test]
+    fn test_shift() {
+        assert_eq!(shift(TaskStatus::Planned, true), TaskStatus::Doing);
+        assert_eq!(shift(TaskStatus::Doing, true), TaskStatus::Done);
+        assert_eq!(shift(TaskStatus::Done, true), TaskStatus::Done);
+        assert_eq!(shift(TaskStatus::Done, false), TaskStatus::Doing);
+        assert_eq!(shift(TaskStatus::Planned, false), TaskStatus::Planned);
+    }
+}
@@ -0,0 +1,312 @@
+//! Break activities
+//!
+//! Content shown in place of the icon during breaks: a box-breathing guide
+//! and a tiny snake game. Both are driven by the 100ms tick loop.
+
+use std::collections::VecDeque;
+
+use crate::i18n;
+
+/// Ticks per second of the main loop
+pub const TICKS_PER_SECOND: u32 = 10;
+
+/// Seconds per box-breathing phase (inhale, hold, exhale, hold)
+const BREATH_PHASE_SECS: u32 = 4;
+const BREATH_MIN_ROWS: u32 = 1;
+const BREATH_MAX_ROWS: u32 = 5;
+
+/// Render the box-breathing guide for the given number of ticks into the break.
+///
+/// The square grows while breathing in, stays full while holding, shrinks while
+/// breathing out and stays small during the final hold.
+pub fn breathing_lines(ticks: u32, lang: &str) -> Vec<String> {
+    let phase_ticks = BREATH_PHASE_SECS * TICKS_PER_SECOND;
+    let cycle_pos = ticks % (phase_ticks * 4);
+    let phase = cycle_pos / phase_ticks;
+    let in_phase = cycle_pos % phase_ticks;
+    let progress = in_phase as f64 / phase_ticks as f64;
+
+    let span = (BREATH_MAX_ROWS - BREATH_MIN_ROWS) as f64;
+    let rows = match phase {
+        0 => BREATH_MIN_ROWS as f64 + span * progress,
+        1 => BREATH_MAX_ROWS as f64,
+        2 => BREATH_MAX_ROWS as f64 - span * progress,
+        _ => BREATH_MIN_ROWS as f64,
+    }
+    .round() as u32;
+
+    let label = i18n::t_in(
+        lang,
+        match phase {
+            0 => "Breathe in",
+            2 => "Breathe out",
+            _ => "Hold",
+        },
+    );
+    let countdown = BREATH_PHASE_SECS - in_phase / TICKS_PER_SECOND;
+
+    // Pad vertically so the total height stays constant while the box changes size
+    let pad = BREATH_MAX_ROWS - rows;
+    let width = (rows * 4) as usize;
+    let mut lines = Vec::new();
+    lines.extend((0..pad / 2).map(|_| String::new()));
+    lines.push(format!("┌{}┐", "─".repeat(width)));
+    lines.extend((0..rows).map(|_| format!("│{}│", " ".repeat(width))));
+    lines.push(format!("└{}┘", "─".repeat(width)));
+    lines.extend((0..pad - pad / 2).map(|_| String::new()));
+    lines.push(String::new());
+    lines.push(format!("{}  {}", label, countdown));
+    lines
+}
+
+/// Direction the snake is heading
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Heading {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Heading {
+    fn delta(&self) -> (i32, i32) {
+        match self {
+            Heading::Up => (0, -1),
+            Heading::Down => (0, 1),
+            Heading::Left => (-1, 0),
+            Heading::Right => (1, 0),
+        }
+    }
+
+    fn opposite(&self) -> Self {
+        match self {
+            Heading::Up => Heading::Down,
+            Heading::Down => Heading::Up,
+            Heading::Left => Heading::Right,
+            Heading::Right => Heading::Left,
+        }
+    }
+}
+
+const SNAKE_WIDTH: i32 = 24;
+const SNAKE_HEIGHT: i32 = 8;
+/// Ticks between snake moves
+pub const SNAKE_STEP_TICKS: u32 = 2;
+
+/// A tiny snake game played during breaks
+#[derive(Debug, Clone)]
+pub struct SnakeGame {
+    body: VecDeque<(i32, i32)>,
+    heading: Heading,
+    next_heading: Heading,
+    food: (i32, i32),
+    rng: u64,
+    pub score: u32,
+    pub alive: bool,
+}
+
+impl SnakeGame {
+    pub fn new(seed: u64) -> Self {
+        let y = SNAKE_HEIGHT / 2;
+        let body = (0..3).map(|i| (SNAKE_WIDTH / 2 - i, y)).collect();
+        let mut game = Self {
+            body,
+            heading: Heading::Right,
+            next_heading: Heading::Right,
+            food: (0, 0),
+            // xorshift must not start at zero
+            rng: seed | 1,
+            score: 0,
+            alive: true,
+        };
+        game.place_food();
+        game
+    }
+
+    /// Change direction; reversing into the snake's own body is ignored
+    pub fn turn(&mut self, heading: Heading) {
+        if heading != self.heading.opposite() {
+            self.next_heading = heading;
+        }
+    }
+
+    /// Advance the snake by one cell
+    pub fn step(&mut self) {
+        if !self.alive {
+            return;
+        }
+        self.heading = self.next_heading;
+        let (dx, dy) = self.heading.delta();
+        let (hx, hy) = self.body[0];
+        let head = (hx + dx, hy + dy);
+
+        let eating = head == self.food;
+        // The tail moves away this step unless the snake grows
+        let body_len = if eating {
+            self.body.len()
+        } else {
+            self.body.len() - 1
+        };
+        let hits_wall = head.0 < 0 || head.0 >= SNAKE_WIDTH || head.1 < 0 || head.1 >= SNAKE_HEIGHT;
+        if hits_wall || self.body.iter().take(body_len).any(|&p| p == head) {
+            self.alive = false;
+            return;
+        }
+
+        self.body.push_front(head);
+        if eating {
+            self.score += 1;
+            self.place_food();
+        } else {
+            self.body.pop_back();
+        }
+    }
+
+    fn next_random(&mut self) -> u64 {
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 7;
+        self.rng ^= self.rng << 17;
+        self.rng
+    }
+
+    fn place_food(&mut self) {
+        let cells = (SNAKE_WIDTH * SNAKE_HEIGHT) as u64;
+        let start = self.next_random() % cells;
+        // Scan from a random cell to the first free one
+        for offset in 0..cells {
+            let idx = ((start + offset) % cells) as i32;
+            let cell = (idx % SNAKE_WIDTH, idx / SNAKE_WIDTH);
+            if !self.body.contains(&cell) {
+                self.food = cell;
+                return;
+            }
+        }
+    }
+
+    pub fn lines(&self, lang: &str) -> Vec<String> {
+        let mut lines = vec![format!("┌{}┐", "─".repeat(SNAKE_WIDTH as usize))];
+        for y in 0..SNAKE_HEIGHT {
+            let row: String = (0..SNAKE_WIDTH)
+                .map(|x| {
+                    if self.body[0] == (x, y) {
+                        '@'
+                    } else if self.body.contains(&(x, y)) {
+                        'o'
+                    } else if self.food == (x, y) {
+                        '*'
+                    } else {
+                        ' '
+                    }
+                })
+                .collect();
+            lines.push(format!("│{}│", row));
+        }
+        lines.push(format!("└{}┘", "─".repeat(SNAKE_WIDTH as usize)));
+        lines.push(if self.alive {
+            i18n::fill_in(lang, "Score: {0}  [←↑↓→/hjkl]", &[&self.score])
+        } else {
+            i18n::fill_in(
+                lang,
+                "Game over ({0})  Arrow key to restart",
+                &[&self.score],
+            )
+        });
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn box_rows(lines: &[String]) -> usize {
+        lines.iter().filter(|l| l.starts_with('│')).count()
+    }
+
+    #[test]
+    fn test_breathing_grows_and_shrinks() {
+        let phase = BREATH_PHASE_SECS * TICKS_PER_SECOND;
+        assert_eq!(
+            box_rows(&breathing_lines(0, "en")),
+            BREATH_MIN_ROWS as usize
+        );
+        assert_eq!(
+            box_rows(&breathing_lines(phase, "en")),
+            BREATH_MAX_ROWS as usize
+        );
+        assert_eq!(
+            box_rows(&breathing_lines(phase * 3, "en")),
+            BREATH_MIN_ROWS as usize
+        );
+        // Wraps around after a full cycle
+        assert_eq!(breathing_lines(phase * 4, "en"), breathing_lines(0, "en"));
+    }
+
+    #[test]
+    fn test_breathing_height_is_constant() {
+        let heights: Vec<usize> = (0..160).map(|t| breathing_lines(t, "en").len()).collect();
+        assert!(heights.iter().all(|h| *h == heights[0]));
+    }
+
+    #[test]
+    fn test_breathing_label() {
+        let phase = BREATH_PHASE_SECS * TICKS_PER_SECOND;
+        assert_eq!(breathing_lines(0, "en").last().unwrap(), "Breathe in  4");
+        assert_eq!(breathing_lines(phase + 35, "en").last().unwrap(), "Hold  1");
+        assert_eq!(
+            breathing_lines(phase * 2, "ja").last().unwrap(),
+            "吐いて  4"
+        );
+    }
+
+    #[test]
+    fn test_snake_moves_forward() {
+        let mut game = SnakeGame::new(42);
+        game.food = (0, 0);
+        let head = game.body[0];
+        game.step();
+        assert_eq!(game.body[0], (head.0 + 1, head.1));
+        assert_eq!(game.body.len(), 3);
+    }
+
+    #[test]
+    fn test_snake_grows_when_eating() {
+        let mut game = SnakeGame::new(42);
+        let head = game.body[0];
+        game.food = (head.0 + 1, head.1);
+        game.step();
+        assert_eq!(game.score, 1);
+        assert_eq!(game.body.len(), 4);
+        assert!(!game.body.contains(&game.food));
+    }
+
+    #[test]
+    fn test_snake_ignores_reversal() {
+        let mut game = SnakeGame::new(42);
+        game.food = (0, 0);
+        game.turn(Heading::Left);
+        game.step();
+        assert!(game.alive);
+        assert_eq!(game.heading, Heading::Right);
+    }
+
+    #[test]
+    fn test_snake_dies_at_wall() {
+        let mut game = SnakeGame::new(42);
+        game.food = (0, 0);
+        for _ in 0..SNAKE_WIDTH {
+            game.step();
+        }
+        assert!(!game.alive);
+    }
+
+    #[test]
+    fn test_snake_render_size() {
+        let game = SnakeGame::new(7);
+        let lines = game.lines("en");
+        assert_eq!(lines.len(), SNAKE_HEIGHT as usize + 3);
+        assert!(lines[1..=SNAKE_HEIGHT as usize]
+            .iter()
+            .all(|l| l.chars().count() == SNAKE_WIDTH as usize + 2));
+    }
+}
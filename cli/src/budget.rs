@@ -0,0 +1,115 @@
+//! Daily time budgets per tag, the inverse of goals
+//!
+//! `[budgets]` in config.toml caps the minutes a tag should get in a day
+//! (`"meetings-prep" = 60`). The timer warns once a day per tag as soon as
+//! the running session takes the tag past its budget, and `sandoro stats
+//! --by-tag` marks tags that are over today.
+
+use chrono::NaiveDate;
+use std::collections::{BTreeMap, HashSet};
+
+use crate::db::Tag;
+
+/// A tag's time today against its budget
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Usage {
+    pub tag: String,
+    pub seconds: i32,
+    pub budget_minutes: u32,
+}
+
+impl Usage {
+    pub fn is_over(&self) -> bool {
+        self.seconds > self.budget_minutes as i32 * 60
+    }
+}
+
+/// Budget of `tag`, if it has one (0 means none)
+pub fn budget_for(budgets: &BTreeMap<String, u32>, tag: &str) -> Option<u32> {
+    budgets.get(tag).copied().filter(|minutes| *minutes > 0)
+}
+
+/// Today's usage of every budgeted tag, from per-tag totals
+pub fn usage(budgets: &BTreeMap<String, u32>, by_tag: &[(Option<Tag>, i32)]) -> Vec<Usage> {
+    budgets
+        .iter()
+        .filter(|(_, minutes)| **minutes > 0)
+        .map(|(tag, minutes)| Usage {
+            tag: tag.clone(),
+            seconds: seconds_for(by_tag, tag),
+            budget_minutes: *minutes,
+        })
+        .collect()
+}
+
+/// Seconds recorded on `tag` in per-tag totals
+pub fn seconds_for(by_tag: &[(Option<Tag>, i32)], tag: &str) -> i32 {
+    by_tag
+        .iter()
+        .filter(|(t, _)| t.as_ref().is_some_and(|t| t.name == tag))
+        .map(|(_, seconds)| seconds)
+        .sum()
+}
+
+/// Which tags have been warned about today, so each warning comes once
+#[derive(Debug, Default)]
+pub struct Alerts {
+    day: Option<NaiveDate>,
+    warned: HashSet<String>,
+}
+
+impl Alerts {
+    /// True the first time `tag` is seen over budget on `today`
+    pub fn first_today(&mut self, today: NaiveDate, tag: &str) -> bool {
+        if self.day != Some(today) {
+            self.day = Some(today);
+            self.warned.clear();
+        }
+        self.warned.insert(tag.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tag(id: i64, name: &str) -> Option<Tag> {
+        Some(Tag {
+            id,
+            name: name.to_string(),
+            color: None,
+        })
+    }
+
+    #[test]
+    fn test_usage_of_budgeted_tags() {
+        let budgets = BTreeMap::from([
+            ("meetings-prep".to_string(), 60),
+            ("email".to_string(), 30),
+            ("off".to_string(), 0),
+        ]);
+        let by_tag = vec![
+            (tag(1, "meetings-prep"), 3900),
+            (tag(2, "writing"), 7200),
+            (None, 600),
+        ];
+        let usage = usage(&budgets, &by_tag);
+
+        assert_eq!(usage.len(), 2);
+        let email = &usage[0];
+        assert_eq!((email.tag.as_str(), email.seconds), ("email", 0));
+        assert!(!email.is_over());
+        assert!(usage[1].is_over());
+        assert_eq!(budget_for(&budgets, "off"), None);
+    }
+
+    #[test]
+    fn test_each_tag_is_warned_once_a_day() {
+        let mut alerts = Alerts::default();
+        let day = NaiveDate::from_ymd_opt(2026, 10, 17).unwrap();
+        assert!(alerts.first_today(day, "email"));
+        assert!(!alerts.first_today(day, "email"));
+        assert!(alerts.first_today(day, "meetings-prep"));
+        assert!(alerts.first_today(day.succ_opt().unwrap(), "email"));
+    }
+}
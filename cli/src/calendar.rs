@@ -0,0 +1,486 @@
+//! Month calendar of completed work for `sandoro stats --calendar`
+//!
+//! Each day shows its number of sessions and focus time. Arrow keys (or
+//! hjkl) move by day and week, `[` / `]` (PageUp / PageDown) by month, and
+//! Enter lists the selected day's sessions with their tags and lengths.
+
+use std::collections::HashMap;
+use std::io::{stdout, Write};
+
+use anyhow::Result;
+use chrono::{Datelike, Duration, Local, Months, NaiveDate};
+use crossterm::event::KeyCode;
+
+use crate::color;
+use crate::db::{DailyStats, Database, DaySession};
+use crate::i18n;
+use crate::privacy;
+use crate::theme;
+
+/// Width of a day cell, in columns
+const CELL: usize = 9;
+
+const WEEKDAYS: [&str; 7] = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
+
+/// ANSI codes the calendar is drawn with; empty strings draw it plain
+pub struct Style {
+    /// Color of a day for each activity level, 0 (none) to 4
+    pub levels: [String; 5],
+    /// The selected day
+    pub selected: String,
+    /// Headings and borders
+    pub accent: String,
+}
+
+impl Style {
+    #[cfg(test)]
+    fn plain() -> Self {
+        Self {
+            levels: Default::default(),
+            selected: String::new(),
+            accent: String::new(),
+        }
+    }
+}
+
+pub struct Calendar {
+    today: NaiveDate,
+    selected: NaiveDate,
+    /// First of the month the data was loaded for
+    loaded: Option<NaiveDate>,
+    days: HashMap<String, DailyStats>,
+    sessions: Vec<DaySession>,
+    /// Listing the selected day's sessions, scrolled down this many
+    list: Option<usize>,
+}
+
+impl Calendar {
+    pub fn new(today: NaiveDate) -> Self {
+        Self {
+            today,
+            selected: today,
+            loaded: None,
+            days: HashMap::new(),
+            sessions: Vec::new(),
+            list: None,
+        }
+    }
+
+    /// First of the month on screen
+    fn month(&self) -> NaiveDate {
+        self.selected.with_day(1).unwrap_or(self.selected)
+    }
+
+    /// Load the month on screen, if it isn't already
+    pub fn load(&mut self, db: &Database) -> Result<()> {
+        let month = self.month();
+        if self.loaded == Some(month) {
+            return Ok(());
+        }
+        let start = month.format("%Y-%m-%d").to_string();
+        let end = (month + Months::new(1)).format("%Y-%m-%d").to_string();
+        self.days = db
+            .get_range_days(&start, &end)?
+            .into_iter()
+            .map(|day| (day.date.clone(), day))
+            .collect();
+        self.sessions = db.get_sessions_in_range(&start, &end)?;
+        self.loaded = Some(month);
+        Ok(())
+    }
+
+    fn day(&self, date: NaiveDate) -> Option<&DailyStats> {
+        self.days.get(&date.format("%Y-%m-%d").to_string())
+    }
+
+    /// Select `date`, no later than today
+    fn select(&mut self, date: NaiveDate) {
+        self.selected = date.min(self.today);
+    }
+
+    fn move_days(&mut self, days: i64) {
+        self.select(self.selected + Duration::days(days));
+    }
+
+    /// The same day `months` months away, or the month's last day
+    fn move_months(&mut self, months: i32) {
+        let moved = if months >= 0 {
+            self.selected.checked_add_months(Months::new(months as u32))
+        } else {
+            self.selected
+                .checked_sub_months(Months::new(months.unsigned_abs()))
+        };
+        if let Some(date) = moved {
+            self.select(date);
+        }
+    }
+
+    /// Handle a key; true to quit
+    pub fn handle_key(&mut self, code: KeyCode) -> bool {
+        if let Some(offset) = self.list {
+            match code {
+                KeyCode::Char('q') => return true,
+                KeyCode::Esc | KeyCode::Backspace | KeyCode::Enter => self.list = None,
+                KeyCode::Up | KeyCode::Char('k') => self.list = Some(offset.saturating_sub(1)),
+                KeyCode::Down | KeyCode::Char('j') => {
+                    let count = self.day_sessions().count();
+                    self.list = Some((offset + 1).min(count.saturating_sub(1)));
+                }
+                _ => {}
+            }
+            return false;
+        }
+        match code {
+            KeyCode::Char('q') | KeyCode::Esc => return true,
+            KeyCode::Left | KeyCode::Char('h') => self.move_days(-1),
+            KeyCode::Right | KeyCode::Char('l') => self.move_days(1),
+            KeyCode::Up | KeyCode::Char('k') => self.move_days(-7),
+            KeyCode::Down | KeyCode::Char('j') => self.move_days(7),
+            KeyCode::Char('[') | KeyCode::PageUp => self.move_months(-1),
+            KeyCode::Char(']') | KeyCode::PageDown => self.move_months(1),
+            KeyCode::Char('t') => self.select(self.today),
+            KeyCode::Enter => self.list = Some(0),
+            _ => {}
+        }
+        false
+    }
+
+    /// Sessions of the selected day
+    fn day_sessions(&self) -> impl Iterator<Item = &DaySession> {
+        let date = self.selected.format("%Y-%m-%d").to_string();
+        self.sessions.iter().filter(move |s| s.date == date)
+    }
+
+    /// Weeks of the month on screen, Sunday first; days of other months are
+    /// None
+    fn weeks(&self) -> Vec<[Option<NaiveDate>; 7]> {
+        let first = self.month();
+        let start = first - Duration::days(i64::from(first.weekday().num_days_from_sunday()));
+        let mut weeks = Vec::new();
+        let mut day = start;
+        while day.month() == first.month() || day < first {
+            let mut week = [None; 7];
+            for slot in week.iter_mut() {
+                if day.month() == first.month() {
+                    *slot = Some(day);
+                }
+                day += Duration::days(1);
+            }
+            weeks.push(week);
+        }
+        weeks
+    }
+
+    /// The screen, one string per line; `rows` is the terminal height
+    pub fn render(&self, style: &Style, rows: usize) -> Vec<String> {
+        if let Some(offset) = self.list {
+            self.render_list(style, offset, rows)
+        } else {
+            self.render_month(style)
+        }
+    }
+
+    fn render_month(&self, style: &Style) -> Vec<String> {
+        let reset = color::reset();
+        let dim = color::dim();
+        let mut lines = vec![
+            String::new(),
+            format!(
+                "  {}{}{}{}",
+                style.accent,
+                color::bold(),
+                self.month().format("%B %Y"),
+                reset
+            ),
+            format!(
+                "  {}←↑↓→/hjkl: day  [/]: month  t: today  Enter: sessions  q: quit{}",
+                dim, reset
+            ),
+            String::new(),
+        ];
+
+        let header: String = WEEKDAYS
+            .iter()
+            .map(|d| format!("{:<width$}", format!(" {}", d), width = CELL))
+            .collect();
+        lines.push(format!("  {}{}{}", dim, header.trim_end(), reset));
+
+        for week in self.weeks() {
+            let mut numbers = String::from("  ");
+            let mut totals = String::from("  ");
+            for date in week {
+                let Some(date) = date else {
+                    numbers.push_str(&" ".repeat(CELL));
+                    totals.push_str(&" ".repeat(CELL));
+                    continue;
+                };
+                let day = self.day(date);
+                let number = format!("{:<width$}", format!(" {:>2}", date.day()), width = CELL);
+                let total = match day {
+                    Some(d) if d.sessions_completed > 0 => format!(
+                        " {}·{}",
+                        d.sessions_completed,
+                        short_duration(d.total_work_seconds)
+                    ),
+                    _ if date > self.today => String::new(),
+                    _ => " ·".to_string(),
+                };
+                let total = format!("{:<width$}", total, width = CELL);
+                let paint = if date == self.selected {
+                    &style.selected
+                } else {
+                    let level = theme::activity_level(day.map_or(0, |d| d.total_work_seconds));
+                    &style.levels[level]
+                };
+                numbers.push_str(&format!("{}{}{}", paint, number, reset));
+                totals.push_str(&format!("{}{}{}", paint, total, reset));
+            }
+            lines.push(numbers.trim_end().to_string());
+            lines.push(totals.trim_end().to_string());
+        }
+
+        // The month's totals and the selected day
+        let (sessions, seconds) = self.days.values().fold((0, 0), |(n, s), d| {
+            (n + d.sessions_completed, s + d.total_work_seconds)
+        });
+        lines.push(String::new());
+        lines.push(format!(
+            "  {}  {} · {}",
+            self.month().format("%B"),
+            i18n::sessions(sessions),
+            i18n::duration(seconds)
+        ));
+        let (day_sessions, day_seconds) = self
+            .day(self.selected)
+            .map_or((0, 0), |d| (d.sessions_completed, d.total_work_seconds));
+        lines.push(format!(
+            "  {}📅 {}{}  ⏱  {}  📊 {}",
+            style.accent,
+            self.selected.format("%Y-%m-%d (%a)"),
+            reset,
+            i18n::duration(day_seconds),
+            i18n::sessions(day_sessions)
+        ));
+        lines
+    }
+
+    fn render_list(&self, style: &Style, offset: usize, rows: usize) -> Vec<String> {
+        let reset = color::reset();
+        let dim = color::dim();
+        let sessions: Vec<&DaySession> = self.day_sessions().collect();
+        let (count, seconds) = self
+            .day(self.selected)
+            .map_or((0, 0), |d| (d.sessions_completed, d.total_work_seconds));
+        let mut lines = vec![
+            String::new(),
+            format!(
+                "  {}📅 {}{} · {} · {}",
+                style.accent,
+                self.selected.format("%Y-%m-%d (%a)"),
+                reset,
+                i18n::sessions(count),
+                i18n::duration(seconds)
+            ),
+            format!("  {}↑↓/jk: scroll  Esc: back  q: quit{}", dim, reset),
+            String::new(),
+        ];
+        if sessions.is_empty() {
+            let empty = if count > 0 {
+                i18n::t("Only the day's totals are kept; its sessions were pruned.")
+            } else {
+                i18n::t("No sessions.")
+            };
+            lines.push(format!("  {}{}{}", dim, empty, reset));
+            return lines;
+        }
+        let room = rows.saturating_sub(lines.len() + 1).max(1);
+        for entry in sessions.iter().skip(offset).take(room) {
+            let session = &entry.session;
+            let tag = entry
+                .tag
+                .as_ref()
+                .map(|t| privacy::hide(&t.name))
+                .unwrap_or_else(|| "-".to_string());
+            let kind = if session.session_type == "timer" {
+                " ⏱ timer"
+            } else {
+                ""
+            };
+            lines.push(format!(
+                "  {}  {:>7}  {}{}",
+                session.started_at.with_timezone(&Local).format("%H:%M"),
+                i18n::duration(session.duration_seconds.unwrap_or(0).max(0)),
+                tag,
+                kind
+            ));
+        }
+        if sessions.len() > offset + room {
+            lines.push(format!(
+                "  {}… {} more{}",
+                dim,
+                sessions.len() - offset - room,
+                reset
+            ));
+        }
+        lines
+    }
+}
+
+/// `1h15` or `45m`, short enough for a day cell
+fn short_duration(seconds: i32) -> String {
+    let minutes = seconds.max(0) / 60;
+    if minutes >= 60 {
+        format!("{}h{:02}", minutes / 60, minutes % 60)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+/// Run the calendar until q is pressed
+pub fn run(db: &Database, today: NaiveDate, style: &Style) -> Result<()> {
+    use crossterm::{
+        cursor,
+        event::{self, Event, KeyEvent},
+        execute,
+        terminal::{self, ClearType, EnterAlternateScreen, LeaveAlternateScreen},
+    };
+
+    let mut calendar = Calendar::new(today);
+    let mut stdout = stdout();
+    terminal::enable_raw_mode()?;
+    execute!(stdout, EnterAlternateScreen, cursor::Hide)?;
+
+    let result = (|| -> Result<()> {
+        loop {
+            calendar.load(db)?;
+            let (_, rows) = terminal::size().unwrap_or((80, 24));
+            execute!(
+                stdout,
+                cursor::MoveTo(0, 0),
+                terminal::Clear(ClearType::All)
+            )?;
+            for line in calendar.render(style, rows as usize) {
+                write!(stdout, "{}\r\n", line)?;
+            }
+            stdout.flush()?;
+            if let Event::Key(KeyEvent { code, .. }) = event::read()? {
+                if calendar.handle_key(code) {
+                    return Ok(());
+                }
+            }
+        }
+    })();
+
+    execute!(stdout, LeaveAlternateScreen, cursor::Show)?;
+    terminal::disable_raw_mode()?;
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::{Session, Tag};
+    use chrono::{TimeZone, Utc};
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn calendar() -> Calendar {
+        let mut calendar = Calendar::new(date("2026-10-17"));
+        calendar.days.insert(
+            "2026-10-02".to_string(),
+            DailyStats {
+                date: "2026-10-02".to_string(),
+                total_work_seconds: 4500,
+                sessions_completed: 3,
+                longest_streak: 0,
+            },
+        );
+        calendar.sessions.push(DaySession {
+            date: "2026-10-02".to_string(),
+            session: Session {
+                id: 1,
+                started_at: Utc.with_ymd_and_hms(2026, 10, 2, 9, 0, 0).unwrap(),
+                ended_at: None,
+                duration_seconds: Some(1500),
+                session_type: "work".to_string(),
+                completed: true,
+                notes: None,
+            },
+            tag: Some(Tag {
+                id: 1,
+                name: "writing".to_string(),
+                color: None,
+            }),
+        });
+        calendar.loaded = Some(date("2026-10-01"));
+        calendar
+    }
+
+    #[test]
+    fn test_weeks_of_a_month() {
+        let calendar = calendar();
+        let weeks = calendar.weeks();
+        // October 2026 starts on a Thursday and ends on a Saturday
+        assert_eq!(weeks.len(), 5);
+        assert_eq!(weeks[0][3], None);
+        assert_eq!(weeks[0][4], Some(date("2026-10-01")));
+        assert_eq!(weeks[4][6], Some(date("2026-10-31")));
+    }
+
+    #[test]
+    fn test_moves_by_day_week_and_month_but_not_past_today() {
+        let mut calendar = calendar();
+        calendar.handle_key(KeyCode::Right);
+        assert_eq!(calendar.selected, date("2026-10-17"));
+        calendar.handle_key(KeyCode::Up);
+        assert_eq!(calendar.selected, date("2026-10-10"));
+        calendar.handle_key(KeyCode::Char('['));
+        assert_eq!(calendar.selected, date("2026-09-10"));
+        calendar.handle_key(KeyCode::PageDown);
+        calendar.handle_key(KeyCode::PageDown);
+        assert_eq!(calendar.selected, date("2026-10-17"));
+
+        // The 31st a month on is the last of that month
+        calendar.select(date("2026-08-31"));
+        calendar.move_months(1);
+        assert_eq!(calendar.selected, date("2026-09-30"));
+        assert!(calendar.handle_key(KeyCode::Char('q')));
+    }
+
+    #[test]
+    fn test_month_shows_counts_and_time() {
+        let mut calendar = calendar();
+        calendar.select(date("2026-10-02"));
+        let screen = calendar.render(&Style::plain(), 40).join("\n");
+        assert!(screen.contains("October 2026"));
+        assert!(screen.contains("3·1h15"));
+        assert!(screen.contains("2026-10-02 (Fri)"));
+    }
+
+    #[test]
+    fn test_enter_lists_the_day() {
+        let mut calendar = calendar();
+        calendar.select(date("2026-10-02"));
+        calendar.handle_key(KeyCode::Enter);
+        let screen = calendar.render(&Style::plain(), 40).join("\n");
+        assert!(screen.contains("25m  writing"));
+        // Back to the month
+        assert!(!calendar.handle_key(KeyCode::Esc));
+        assert!(calendar.list.is_none());
+
+        // A day without sessions says so
+        calendar.select(date("2026-10-05"));
+        calendar.handle_key(KeyCode::Enter);
+        let screen = calendar.render(&Style::plain(), 40).join("\n");
+        assert!(screen.contains("No sessions."));
+    }
+
+    #[test]
+    fn test_short_duration() {
+        assert_eq!(short_duration(4500), "1h15");
+        assert_eq!(short_duration(1500), "25m");
+        assert_eq!(short_duration(-5), "0m");
+    }
+}
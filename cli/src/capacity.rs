@@ -0,0 +1,280 @@
+//! Busy-day capacity warnings
+//!
+//! Compares today's daily goal with what was done on the same weekday over
+//! the last `WINDOW_WEEKS` weeks. A goal beyond the best of those days gets
+//! a warning with the weekday's average as a target to aim for instead,
+//! e.g. "You've never done 10 sessions on a Friday — aim for 6?".
+
+use anyhow::Result;
+use chrono::{Datelike, NaiveDate, Weekday};
+
+use crate::config::{Config, GoalConfig};
+use crate::db::{DailyStats, Database};
+use crate::i18n;
+use crate::messages::Language;
+
+/// Weeks of history the warning is based on
+pub const WINDOW_WEEKS: i32 = 26;
+/// Days with focus on the weekday needed before its history is trusted
+pub const MIN_DAYS: i32 = 4;
+/// Minute targets are rounded to this
+const STEP_MINUTES: i32 = 5;
+/// Metadata key holding the local date the last warning was shown
+const WARNED_KEY: &str = "capacity_warning_on";
+
+/// What was done on one weekday, counting only days with any focus
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WeekdayHistory {
+    pub weekday: Weekday,
+    pub days: i32,
+    pub best_sessions: i32,
+    pub best_minutes: i32,
+    pub total_sessions: i32,
+    pub total_minutes: i32,
+}
+
+impl WeekdayHistory {
+    /// Days before `today` falling on `weekday`
+    pub fn from_days(days: &[DailyStats], weekday: Weekday, today: NaiveDate) -> Self {
+        let mut history = Self {
+            weekday,
+            days: 0,
+            best_sessions: 0,
+            best_minutes: 0,
+            total_sessions: 0,
+            total_minutes: 0,
+        };
+        for day in days {
+            let Ok(date) = NaiveDate::parse_from_str(&day.date, "%Y-%m-%d") else {
+                continue;
+            };
+            if date >= today || date.weekday() != weekday || day.sessions_completed == 0 {
+                continue;
+            }
+            let minutes = day.total_work_seconds / 60;
+            history.days += 1;
+            history.best_sessions = history.best_sessions.max(day.sessions_completed);
+            history.best_minutes = history.best_minutes.max(minutes);
+            history.total_sessions += day.sessions_completed;
+            history.total_minutes += minutes;
+        }
+        history
+    }
+
+    fn average_sessions(&self) -> i32 {
+        (self.total_sessions as f64 / self.days as f64)
+            .round()
+            .max(1.0) as i32
+    }
+
+    fn average_minutes(&self) -> i32 {
+        let steps = (self.total_minutes as f64 / self.days as f64 / STEP_MINUTES as f64).round();
+        (steps as i32 * STEP_MINUTES).max(STEP_MINUTES)
+    }
+}
+
+/// A daily goal beyond anything done on the weekday
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Warning {
+    Sessions {
+        weekday: Weekday,
+        goal: i32,
+        aim: i32,
+    },
+    Minutes {
+        weekday: Weekday,
+        goal: i32,
+        aim: i32,
+    },
+}
+
+impl Warning {
+    pub fn message(&self, lang: Language) -> String {
+        let weekday = |day| i18n::weekday_in(day, lang);
+        match *self {
+            Warning::Sessions {
+                weekday: day,
+                goal,
+                aim,
+            } => i18n::fill_in(
+                lang.code(),
+                "You've never done {0} sessions on a {1} — aim for {2}?",
+                &[&goal, &weekday(day), &aim],
+            ),
+            Warning::Minutes {
+                weekday: day,
+                goal,
+                aim,
+            } => i18n::fill_in(
+                lang.code(),
+                "You've never focused {0}m on a {1} — aim for {2}m?",
+                &[&goal, &weekday(day), &aim],
+            ),
+        }
+    }
+}
+
+/// Warn about the session goal first, then the minutes goal, when either
+/// is above the weekday's best
+pub fn check(goals: &GoalConfig, history: &WeekdayHistory) -> Option<Warning> {
+    if history.days < MIN_DAYS {
+        return None;
+    }
+    let sessions = goals.daily_sessions as i32;
+    let minutes = goals.daily_minutes as i32;
+    if sessions > history.best_sessions {
+        return Some(Warning::Sessions {
+            weekday: history.weekday,
+            goal: sessions,
+            aim: history.average_sessions(),
+        });
+    }
+    if minutes > history.best_minutes {
+        return Some(Warning::Minutes {
+            weekday: history.weekday,
+            goal: minutes,
+            aim: history.average_minutes(),
+        });
+    }
+    None
+}
+
+/// The warning for the timer screen, at most once a day and only with
+/// `[goals] capacity_warnings` on
+pub fn daily_warning(db: &Database, config: &Config) -> Result<Option<String>> {
+    let goals = &config.goals;
+    if !goals.capacity_warnings || (goals.daily_sessions == 0 && goals.daily_minutes == 0) {
+        return Ok(None);
+    }
+    let today = db.today()?;
+    let date = today.format("%Y-%m-%d").to_string();
+    if db.get_metadata(WARNED_KEY)?.as_deref() == Some(date.as_str()) {
+        return Ok(None);
+    }
+    let days = db.get_heatmap_data(WINDOW_WEEKS)?;
+    let history = WeekdayHistory::from_days(&days, today.weekday(), today);
+    let Some(warning) = check(goals, &history) else {
+        return Ok(None);
+    };
+    db.set_metadata(WARNED_KEY, &date)?;
+    let lang = Language::from_str(&config.appearance.language);
+    Ok(Some(warning.message(lang)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::SessionType;
+    use chrono::Duration;
+    use rusqlite::params;
+
+    fn stats(date: &str, sessions: i32, minutes: i32) -> DailyStats {
+        DailyStats {
+            date: date.to_string(),
+            total_work_seconds: minutes * 60,
+            sessions_completed: sessions,
+            longest_streak: 0,
+        }
+    }
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn goals(sessions: u32, minutes: u32) -> GoalConfig {
+        GoalConfig {
+            daily_sessions: sessions,
+            daily_minutes: minutes,
+            ..GoalConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_history_counts_the_weekday_before_today() {
+        // Fridays 2026-09-25 to 10-16; 10-17 is a Saturday
+        let days = [
+            stats("2026-09-25", 4, 100),
+            stats("2026-10-02", 0, 0),
+            stats("2026-10-09", 8, 150),
+            stats("2026-10-10", 12, 300),
+            stats("2026-10-16", 6, 200),
+        ];
+        let history = WeekdayHistory::from_days(&days, Weekday::Fri, date("2026-10-17"));
+        assert_eq!(history.days, 3);
+        assert_eq!(history.best_sessions, 8);
+        assert_eq!(history.best_minutes, 200);
+        assert_eq!(history.average_sessions(), 6);
+        assert_eq!(history.average_minutes(), 150);
+
+        // Today doesn't count yet
+        let history = WeekdayHistory::from_days(&days, Weekday::Fri, date("2026-10-16"));
+        assert_eq!(history.days, 2);
+    }
+
+    #[test]
+    fn test_check_against_the_best() {
+        let history = WeekdayHistory {
+            weekday: Weekday::Fri,
+            days: 4,
+            best_sessions: 8,
+            best_minutes: 200,
+            total_sessions: 24,
+            total_minutes: 480,
+        };
+        let warning = check(&goals(10, 0), &history).unwrap();
+        assert_eq!(
+            warning.message(Language::English),
+            "You've never done 10 sessions on a Friday — aim for 6?"
+        );
+        assert_eq!(
+            warning.message(Language::Japanese),
+            "金曜日に10セッションできた日はまだありません — 6セッションを目指しては？"
+        );
+        assert_eq!(
+            check(&goals(8, 240), &history)
+                .unwrap()
+                .message(Language::English),
+            "You've never focused 240m on a Friday — aim for 120m?"
+        );
+        assert_eq!(check(&goals(8, 200), &history), None);
+
+        // Not enough Fridays to go on
+        let history = WeekdayHistory { days: 3, ..history };
+        assert_eq!(check(&goals(10, 0), &history), None);
+    }
+
+    #[test]
+    fn test_daily_warning_once_a_day() {
+        let db = Database::open_in_memory().unwrap();
+        let today = db.today().unwrap();
+        for week in 1..=4 {
+            let day = (today - Duration::weeks(week))
+                .format("%Y-%m-%d")
+                .to_string();
+            let id = db
+                .start_session_with_tag(SessionType::Work, None, None)
+                .unwrap();
+            db.complete_session(id, 1500).unwrap();
+            db.connection()
+                .execute(
+                    "UPDATE sessions SET started_at = ?1, day = ?2 WHERE id = ?3",
+                    params![format!("{}T09:00:00+00:00", day), day, id],
+                )
+                .unwrap();
+        }
+        let mut config = Config::default();
+        config.appearance.language = "en".to_string();
+        assert_eq!(daily_warning(&db, &config).unwrap(), None);
+
+        config.goals.daily_sessions = 3;
+        let weekday = i18n::weekday_in(today.weekday(), Language::English);
+        assert_eq!(
+            daily_warning(&db, &config).unwrap(),
+            Some(format!(
+                "You've never done 3 sessions on a {} — aim for 1?",
+                weekday
+            ))
+        );
+        assert_eq!(daily_warning(&db, &config).unwrap(), None);
+    }
+}
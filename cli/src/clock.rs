@@ -0,0 +1,140 @@
+//! The time the timer runs on
+//!
+//! Normally the wall clock. The hidden `--time-scale N` flag runs it N times
+//! faster from the moment sandoro starts, so a 25-minute session takes
+//! 25/N minutes and the time of day (greetings, the header clock, today's
+//! date) moves on with it. That's for trying transitions, notifications,
+//! achievements and the end-of-day rollover without waiting for them.
+//!
+//! The timer holds its own `Clock`, so tests can give it a frozen one and
+//! move it by hand.
+
+use chrono::{DateTime, Local, Utc};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+static GLOBAL: OnceLock<Clock> = OnceLock::new();
+
+/// A clock started at some moment, running `scale` times real time
+#[derive(Debug, Clone)]
+pub struct Clock {
+    started: Instant,
+    wall: DateTime<Utc>,
+    /// 0 for a frozen clock that only moves with `advance`
+    scale: u32,
+    /// Added by `advance`, shared by every copy of the clock
+    offset: Arc<Mutex<Duration>>,
+}
+
+impl Clock {
+    /// Starting now, running `scale` times real time
+    pub fn scaled(scale: u32) -> Self {
+        Self {
+            started: Instant::now(),
+            wall: Utc::now(),
+            scale,
+            offset: Arc::default(),
+        }
+    }
+
+    /// A clock that stands still until `advance` moves it
+    #[allow(dead_code)]
+    pub fn frozen() -> Self {
+        Self::scaled(0)
+    }
+
+    /// Time passed on this clock since it started
+    pub fn elapsed(&self) -> Duration {
+        self.started.elapsed() * self.scale + *self.offset.lock().unwrap()
+    }
+
+    /// Move the clock forward
+    #[allow(dead_code)]
+    pub fn advance(&self, by: Duration) {
+        *self.offset.lock().unwrap() += by;
+    }
+
+    /// The wall clock for an unscaled clock nobody has moved: `Instant`
+    /// stands still while the machine sleeps, so counting from `started`
+    /// would fall behind after a suspend
+    pub fn now_utc(&self) -> DateTime<Utc> {
+        let offset = *self.offset.lock().unwrap();
+        if self.scale == 1 && offset.is_zero() {
+            return Utc::now();
+        }
+        self.wall + chrono::Duration::from_std(self.elapsed()).unwrap_or_default()
+    }
+
+    pub fn now(&self) -> DateTime<Local> {
+        self.now_utc().with_timezone(&Local)
+    }
+}
+
+/// Run the process's clock `scale` times real time. Call before anything
+/// reads the time; later calls are ignored.
+pub fn init(scale: u32) {
+    let _ = GLOBAL.set(Clock::scaled(scale.max(1)));
+}
+
+/// The process's clock: the wall clock unless `init` scaled it
+pub fn global() -> Clock {
+    GLOBAL.get_or_init(|| Clock::scaled(1)).clone()
+}
+
+/// Whether `--time-scale` is speeding things up
+pub fn is_scaled() -> bool {
+    GLOBAL.get().is_some_and(|clock| clock.scale > 1)
+}
+
+/// The local time on the process's clock
+pub fn now() -> DateTime<Local> {
+    match GLOBAL.get() {
+        Some(clock) if clock.scale != 1 => clock.now(),
+        _ => Local::now(),
+    }
+}
+
+/// The UTC time on the process's clock
+pub fn now_utc() -> DateTime<Utc> {
+    match GLOBAL.get() {
+        Some(clock) if clock.scale != 1 => clock.now_utc(),
+        _ => Utc::now(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frozen_clock_moves_only_when_advanced() {
+        let clock = Clock::frozen();
+        let start = clock.now_utc();
+        std::thread::sleep(Duration::from_millis(5));
+        assert_eq!(clock.now_utc(), start);
+
+        // Copies share the clock
+        clock.clone().advance(Duration::from_secs(90));
+        assert_eq!(clock.elapsed(), Duration::from_secs(90));
+        assert_eq!(clock.now_utc() - start, chrono::Duration::seconds(90));
+    }
+
+    #[test]
+    fn test_real_time_clock_reads_the_wall_clock() {
+        let clock = Clock::scaled(1);
+        // As if the machine had slept since the clock started
+        let clock = Clock {
+            wall: clock.wall - chrono::Duration::hours(1),
+            ..clock
+        };
+        assert!(Utc::now() - clock.now_utc() < chrono::Duration::seconds(1));
+    }
+
+    #[test]
+    fn test_scaled_clock_runs_fast() {
+        let clock = Clock::scaled(600);
+        std::thread::sleep(Duration::from_millis(10));
+        // 10ms real is at least 6s on the clock
+        assert!(clock.elapsed() >= Duration::from_secs(6));
+    }
+}
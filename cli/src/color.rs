@@ -0,0 +1,218 @@
+//! Colors for plain CLI output: stats, the heatmap, reports and errors
+//!
+//! Everything printed with `println!` takes its escape codes from here, so
+//! one decision covers all of it. Colors are on when the stream is a
+//! terminal, unless `NO_COLOR` is set (https://no-color.org) or
+//! `--color=always|never` says otherwise. The TUI draws through ratatui and
+//! isn't affected.
+//!
+//! Headings, bars and highlights take their colors from the configured theme
+//! and accent, so `sandoro stats` looks like the timer. Changes for the
+//! better are green and for the worse red, whatever the theme.
+
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::OnceLock;
+
+use crate::config::AppearanceConfig;
+use crate::theme::{self, Theme};
+
+/// `--color` on the command line
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ColorChoice {
+    /// Color when writing to a terminal and NO_COLOR isn't set
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+static STDOUT: AtomicBool = AtomicBool::new(false);
+static STDERR: AtomicBool = AtomicBool::new(false);
+static PALETTE: OnceLock<Palette> = OnceLock::new();
+
+const RESET: &str = "\x1b[0m";
+const UP: (u8, u8, u8) = (80, 200, 120);
+const DOWN: (u8, u8, u8) = (230, 90, 90);
+
+/// Theme colors used in command output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Palette {
+    heading: (u8, u8, u8),
+    accent: (u8, u8, u8),
+}
+
+impl Palette {
+    fn from_appearance(appearance: &AppearanceConfig) -> Self {
+        let accent = if appearance.accent == "rainbow" {
+            theme::Rainbow::from_config(&appearance.rainbow).heatmap(4)
+        } else {
+            theme::heatmap_accent_rgb(&appearance.accent)
+        };
+        Self {
+            heading: Theme::by_name(&appearance.theme).primary.to_rgb(),
+            accent,
+        }
+    }
+
+    fn get() -> Self {
+        *PALETTE.get_or_init(|| Self::from_appearance(&AppearanceConfig::default()))
+    }
+}
+
+/// Decide once, at startup, whether stdout and stderr get colors, and which
+pub fn init(choice: ColorChoice, appearance: &AppearanceConfig) {
+    let _ = PALETTE.set(Palette::from_appearance(appearance));
+    let no_color = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+    let dumb = std::env::var("TERM").is_ok_and(|t| t == "dumb");
+    let decide = |is_terminal: bool| match choice {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => is_terminal && !no_color && !dumb,
+    };
+    STDOUT.store(decide(std::io::stdout().is_terminal()), Ordering::Relaxed);
+    STDERR.store(decide(std::io::stderr().is_terminal()), Ordering::Relaxed);
+}
+
+/// Whether stdout output is colored
+pub fn enabled() -> bool {
+    STDOUT.load(Ordering::Relaxed)
+}
+
+fn code(on: bool, code: &str) -> String {
+    if on {
+        code.to_string()
+    } else {
+        String::new()
+    }
+}
+
+/// Foreground color escape, or nothing without colors
+pub fn fg((r, g, b): (u8, u8, u8)) -> String {
+    code(enabled(), &format!("\x1b[38;2;{};{};{}m", r, g, b))
+}
+
+/// Background color escape, or nothing without colors
+pub fn bg((r, g, b): (u8, u8, u8)) -> String {
+    code(enabled(), &format!("\x1b[48;2;{};{};{}m", r, g, b))
+}
+
+pub fn reset() -> &'static str {
+    if enabled() {
+        RESET
+    } else {
+        ""
+    }
+}
+
+pub fn dim() -> &'static str {
+    if enabled() {
+        "\x1b[2m"
+    } else {
+        ""
+    }
+}
+
+pub fn bold() -> &'static str {
+    if enabled() {
+        "\x1b[1m"
+    } else {
+        ""
+    }
+}
+
+/// `text` in the given color
+pub fn paint(rgb: (u8, u8, u8), text: &str) -> String {
+    format!("{}{}{}", fg(rgb), text, reset())
+}
+
+/// A section title, in the theme's primary color
+pub fn heading(text: &str) -> String {
+    format!("{}{}", bold(), paint(Palette::get().heading, text))
+}
+
+/// `text` in the accent color, e.g. the filled part of a bar
+pub fn accent(text: &str) -> String {
+    paint(Palette::get().accent, text)
+}
+
+/// A number worth noticing, like a running streak: bold, in the accent
+pub fn highlight(text: &str) -> String {
+    format!("{}{}", bold(), accent(text))
+}
+
+/// A change for the better
+pub fn up(text: &str) -> String {
+    paint(UP, text)
+}
+
+/// A change for the worse
+pub fn down(text: &str) -> String {
+    paint(DOWN, text)
+}
+
+/// Heatmap cell for an activity level (0-4). With colors every level is a
+/// full block in its own shade; without, the block itself shows the level.
+pub fn heatmap_glyph(level: usize) -> char {
+    if enabled() {
+        '█'
+    } else {
+        ['·', '░', '▒', '▓', '█'][level.min(4)]
+    }
+}
+
+/// The "Error:" prefix for errors on stderr
+pub fn error_label() -> String {
+    if STDERR.load(Ordering::Relaxed) {
+        format!("\x1b[1;31mError:{}", RESET)
+    } else {
+        "Error:".to_string()
+    }
+}
+
+#[cfg(test)]
+pub fn set_enabled(on: bool) {
+    STDOUT.store(on, Ordering::Relaxed);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // One test so the global switch isn't flipped by another test mid-way
+    #[test]
+    fn test_codes_follow_the_switch() {
+        set_enabled(true);
+        assert_eq!(paint((255, 0, 128), "x"), "\x1b[38;2;255;0;128mx\x1b[0m");
+        assert_eq!(heatmap_glyph(1), '█');
+
+        assert_eq!(accent("x"), paint(Palette::get().accent, "x"));
+        assert!(heading("Goals").starts_with("\x1b[1m\x1b[38;2;"));
+        assert_eq!(up("↑"), "\x1b[38;2;80;200;120m↑\x1b[0m");
+
+        set_enabled(false);
+        assert_eq!(paint((255, 0, 128), "x"), "x");
+        assert_eq!(heading("Goals"), "Goals");
+        assert_eq!(highlight("3 days"), "3 days");
+        assert_eq!(down("↓ -5%"), "↓ -5%");
+        assert_eq!(bg((1, 2, 3)), "");
+        assert_eq!(heatmap_glyph(0), '·');
+        assert_eq!(heatmap_glyph(9), '█');
+    }
+
+    #[test]
+    fn test_palette_follows_theme_and_accent() {
+        let mut appearance = AppearanceConfig {
+            theme: "nord".to_string(),
+            accent: "green".to_string(),
+            ..Default::default()
+        };
+        let palette = Palette::from_appearance(&appearance);
+        assert_eq!(palette.heading, (136, 192, 208));
+        assert_eq!(palette.accent, theme::heatmap_accent_rgb("green"));
+
+        appearance.accent = "rainbow".to_string();
+        let rainbow = Palette::from_appearance(&appearance);
+        assert_eq!(rainbow.accent, theme::rainbow_heatmap_rgb(4));
+    }
+}
@@ -0,0 +1,357 @@
+//! Period and tag comparisons for `stats --compare-with`
+//!
+//! A comparison has two sides, each a date range with an optional tag. The
+//! current side is always the period containing today; the other side is the
+//! period or tag named on the command line.
+
+use anyhow::{bail, Result};
+use chrono::{Datelike, Duration, NaiveDate};
+
+use crate::db::{DailyStats, Database};
+use crate::i18n;
+
+/// A date range, end exclusive
+#[derive(Debug, Clone, PartialEq)]
+pub struct Period {
+    pub label: String,
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+impl Period {
+    fn month(year: i32, month: u32) -> Option<Self> {
+        let start = NaiveDate::from_ymd_opt(year, month, 1)?;
+        let end = if month == 12 {
+            NaiveDate::from_ymd_opt(year + 1, 1, 1)?
+        } else {
+            NaiveDate::from_ymd_opt(year, month + 1, 1)?
+        };
+        Some(Self {
+            label: start.format("%b %Y").to_string(),
+            start,
+            end,
+        })
+    }
+
+    fn quarter(year: i32, quarter: u32) -> Option<Self> {
+        if !(1..=4).contains(&quarter) {
+            return None;
+        }
+        let first = Self::month(year, quarter * 3 - 2)?;
+        let last = Self::month(year, quarter * 3)?;
+        Some(Self {
+            label: format!("Q{} {}", quarter, year),
+            start: first.start,
+            end: last.end,
+        })
+    }
+
+    fn year(year: i32) -> Option<Self> {
+        Some(Self {
+            label: year.to_string(),
+            start: NaiveDate::from_ymd_opt(year, 1, 1)?,
+            end: NaiveDate::from_ymd_opt(year + 1, 1, 1)?,
+        })
+    }
+
+    fn days(&self) -> i64 {
+        (self.end - self.start).num_days()
+    }
+
+    /// Mark a period that is still running
+    fn so_far(mut self, today: NaiveDate) -> Self {
+        if today < self.end - Duration::days(1) {
+            self.label.push_str(" (to date)");
+        }
+        self
+    }
+}
+
+/// One side of a comparison
+#[derive(Debug, Clone, PartialEq)]
+pub struct Side {
+    pub period: Period,
+    /// Only count sessions with this tag
+    pub tag: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+    pub current: Side,
+    pub other: Side,
+}
+
+impl Comparison {
+    /// Heading naming both sides, e.g. "Oct 2026 vs Oct 2025 · writing"
+    pub fn title(&self) -> String {
+        let (a, b) = (&self.current, &self.other);
+        if a.period == b.period {
+            let tag = |side: &Side| {
+                side.tag
+                    .clone()
+                    .unwrap_or_else(|| i18n::t("All").to_string())
+            };
+            format!("{} vs {} · {}", tag(a), tag(b), a.period.label)
+        } else {
+            match &a.tag {
+                Some(tag) => format!("{} vs {} · {}", a.period.label, b.period.label, tag),
+                None => format!("{} vs {}", a.period.label, b.period.label),
+            }
+        }
+    }
+}
+
+fn parse_date(s: &str) -> Result<NaiveDate> {
+    NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .map_err(|_| anyhow::anyhow!("Invalid date '{}'. Use YYYY-MM-DD.", s))
+}
+
+fn this_month(today: NaiveDate) -> Period {
+    Period::month(today.year(), today.month()).expect("valid month")
+}
+
+fn this_quarter(today: NaiveDate) -> Period {
+    Period::quarter(today.year(), today.month0() / 3 + 1).expect("valid quarter")
+}
+
+fn this_year(today: NaiveDate) -> Period {
+    Period::year(today.year()).expect("valid year")
+}
+
+/// The period before `period` of the same kind
+fn previous(period: &Period, kind: fn(NaiveDate) -> Period) -> Period {
+    kind(period.start - Duration::days(1))
+}
+
+/// Parse a `--compare-with` spec into a comparison against today.
+///
+/// `tag` restricts the current side, and both sides of period comparisons.
+pub fn parse(spec: &str, tag: Option<&str>, today: NaiveDate) -> Result<Comparison> {
+    let spec = spec.trim();
+    let current_tag = tag.map(str::to_string);
+
+    if let Some(other_tag) = spec.strip_prefix("tag:") {
+        let other_tag = other_tag.trim();
+        if other_tag.is_empty() {
+            bail!("Missing tag name in 'tag:NAME'");
+        }
+        if tag == Some(other_tag) {
+            bail!("Can't compare tag '{}' with itself", other_tag);
+        }
+        let period = this_month(today).so_far(today);
+        return Ok(Comparison {
+            current: Side {
+                period: period.clone(),
+                tag: current_tag,
+            },
+            other: Side {
+                period,
+                tag: Some(other_tag.to_string()),
+            },
+        });
+    }
+
+    let (current, other) = match spec {
+        "last-month" => {
+            let current = this_month(today);
+            let other = previous(&current, this_month);
+            (current, other)
+        }
+        "last-quarter" => {
+            let current = this_quarter(today);
+            let other = previous(&current, this_quarter);
+            (current, other)
+        }
+        "last-year" => {
+            let current = this_year(today);
+            let other = previous(&current, this_year);
+            (current, other)
+        }
+        "same-month-last-year" => {
+            let current = this_month(today);
+            let other = Period::month(today.year() - 1, today.month()).expect("valid month");
+            (current, other)
+        }
+        _ => parse_explicit(spec, today)?,
+    };
+
+    Ok(Comparison {
+        current: Side {
+            period: current.so_far(today),
+            tag: current_tag.clone(),
+        },
+        other: Side {
+            period: other,
+            tag: current_tag,
+        },
+    })
+}
+
+/// `YYYY`, `YYYY-MM`, `YYYY-Qn` or `START..END`, each compared with the
+/// current period of the same length
+fn parse_explicit(spec: &str, today: NaiveDate) -> Result<(Period, Period)> {
+    let unknown = || {
+        anyhow::anyhow!(
+            "Unknown period '{}'. Use last-month, last-quarter, last-year, \
+             same-month-last-year, YYYY, YYYY-MM, YYYY-Qn, START..END or tag:NAME.",
+            spec
+        )
+    };
+
+    if let Some((start, end)) = spec.split_once("..") {
+        let start = parse_date(start)?;
+        let end = parse_date(end)?;
+        if end < start {
+            bail!("'{}' ends before it starts", spec);
+        }
+        let other = Period {
+            label: format!("{} – {}", start, end),
+            start,
+            end: end + Duration::days(1),
+        };
+        let days = other.days();
+        let current = Period {
+            label: format!("Last {} days", days),
+            start: today - Duration::days(days - 1),
+            end: today + Duration::days(1),
+        };
+        return Ok((current, other));
+    }
+
+    let parts: Vec<&str> = spec.split('-').collect();
+    let year = |s: &str| -> Option<i32> { (s.len() == 4).then(|| s.parse().ok()).flatten() };
+    match parts.as_slice() {
+        [y] => {
+            let other = year(y).and_then(Period::year).ok_or_else(unknown)?;
+            Ok((this_year(today), other))
+        }
+        [y, q] if q.starts_with(['Q', 'q']) => {
+            let other = year(y)
+                .zip(q[1..].parse().ok())
+                .and_then(|(y, q)| Period::quarter(y, q))
+                .ok_or_else(unknown)?;
+            Ok((this_quarter(today), other))
+        }
+        [y, m] => {
+            let other = year(y)
+                .zip(m.parse().ok())
+                .and_then(|(y, m)| Period::month(y, m))
+                .ok_or_else(unknown)?;
+            Ok((this_month(today), other))
+        }
+        _ => Err(unknown()),
+    }
+}
+
+/// Load the focus recorded for one side
+pub fn load(db: &Database, side: &Side) -> Result<DailyStats> {
+    let start = side.period.start.format("%Y-%m-%d").to_string();
+    let end = side.period.end.format("%Y-%m-%d").to_string();
+    match &side.tag {
+        Some(name) => match db.get_tag_by_name(name)? {
+            Some(tag) => db.get_range_stats_for_tag(&start, &end, tag.id),
+            None => bail!("No tag named '{}'", name),
+        },
+        None => db.get_range_stats(&start, &end),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::SessionType;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn range(c: &Side) -> (NaiveDate, NaiveDate) {
+        (c.period.start, c.period.end)
+    }
+
+    #[test]
+    fn test_parse_keywords() {
+        let today = date("2026-10-17");
+
+        let c = parse("same-month-last-year", None, today).unwrap();
+        assert_eq!(range(&c.current), (date("2026-10-01"), date("2026-11-01")));
+        assert_eq!(range(&c.other), (date("2025-10-01"), date("2025-11-01")));
+        assert_eq!(c.title(), "Oct 2026 (to date) vs Oct 2025");
+
+        let c = parse("last-quarter", Some("writing"), today).unwrap();
+        assert_eq!(range(&c.current), (date("2026-10-01"), date("2027-01-01")));
+        assert_eq!(range(&c.other), (date("2026-07-01"), date("2026-10-01")));
+        assert_eq!(c.other.tag.as_deref(), Some("writing"));
+        assert_eq!(c.title(), "Q4 2026 (to date) vs Q3 2026 · writing");
+
+        let c = parse("last-month", None, date("2026-01-31")).unwrap();
+        assert_eq!(range(&c.other), (date("2025-12-01"), date("2026-01-01")));
+        assert_eq!(c.title(), "Jan 2026 vs Dec 2025");
+
+        let c = parse("last-year", None, today).unwrap();
+        assert_eq!(c.other.period.label, "2025");
+    }
+
+    #[test]
+    fn test_parse_explicit_periods() {
+        let today = date("2026-10-17");
+
+        let c = parse("2025-Q2", None, today).unwrap();
+        assert_eq!(range(&c.other), (date("2025-04-01"), date("2025-07-01")));
+        assert_eq!(c.current.period.start, date("2026-10-01"));
+
+        let c = parse("2024-02", None, today).unwrap();
+        assert_eq!(range(&c.other), (date("2024-02-01"), date("2024-03-01")));
+
+        let c = parse("2026-09-01..2026-09-14", None, today).unwrap();
+        assert_eq!(range(&c.other), (date("2026-09-01"), date("2026-09-15")));
+        assert_eq!(range(&c.current), (date("2026-10-04"), date("2026-10-18")));
+        assert_eq!(c.current.period.label, "Last 14 days");
+
+        for bad in ["2026-13", "2026-Q5", "soon", "26", "2026-10-05..2026-10-01"] {
+            assert!(parse(bad, None, today).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_parse_tag_vs_tag() {
+        let today = date("2026-10-17");
+        let c = parse("tag:coding", Some("writing"), today).unwrap();
+        assert_eq!(c.current.period, c.other.period);
+        assert_eq!(c.title(), "writing vs coding · Oct 2026 (to date)");
+
+        assert!(parse("tag:writing", Some("writing"), today).is_err());
+        assert!(parse("tag:", None, today).is_err());
+    }
+
+    #[test]
+    fn test_load_filters_by_tag_and_range() {
+        let db = Database::open_in_memory().unwrap();
+        let writing = db.create_tag("writing", None).unwrap();
+        let record = |tag: Option<i64>, started_at: &str| {
+            let id = db
+                .start_session_with_tag(SessionType::Work, tag, Some(1500))
+                .unwrap();
+            db.complete_session(id, 1500).unwrap();
+            db.connection()
+                .execute(
+                    "UPDATE sessions SET started_at = ?1, day = date(?1) WHERE id = ?2",
+                    rusqlite::params![started_at, id],
+                )
+                .unwrap();
+        };
+        record(Some(writing), "2026-07-10T09:00:00+00:00");
+        record(Some(writing), "2026-10-02T09:00:00+00:00");
+        record(None, "2026-10-03T09:00:00+00:00");
+
+        let c = parse("last-quarter", Some("writing"), date("2026-10-17")).unwrap();
+        assert_eq!(load(&db, &c.current).unwrap().sessions_completed, 1);
+        assert_eq!(load(&db, &c.other).unwrap().total_work_seconds, 1500);
+
+        let all = parse("last-quarter", None, date("2026-10-17")).unwrap();
+        assert_eq!(load(&db, &all.current).unwrap().sessions_completed, 2);
+
+        let missing = parse("tag:nope", None, date("2026-10-17")).unwrap();
+        assert!(load(&db, &missing.other).is_err());
+    }
+}
@@ -0,0 +1,293 @@
+//! Completion rates for `sandoro stats --completion`
+//!
+//! How many started work sessions were finished rather than skipped, reset
+//! or interrupted, by part of the day and by planned length. A low rate at
+//! some hour or length is a sign of planning more than gets done.
+
+use anyhow::Result;
+use chrono::{Local, Timelike};
+
+use crate::db::Database;
+use crate::i18n;
+use crate::insights::MIN_ATTEMPTS;
+
+/// Days of history the report covers
+pub const WINDOW_DAYS: i32 = 30;
+/// Rates below this (with enough attempts) are called out
+pub const LOW_RATE: f64 = 0.5;
+
+/// How a started work session ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Completed,
+    Skipped,
+    Reset,
+    Interrupted,
+}
+
+impl Outcome {
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "completed" => Some(Self::Completed),
+            "skipped" => Some(Self::Skipped),
+            "reset" => Some(Self::Reset),
+            "interrupted" => Some(Self::Interrupted),
+            _ => None,
+        }
+    }
+}
+
+/// Outcome counts for a group of attempts
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Tally {
+    pub completed: i32,
+    pub skipped: i32,
+    pub reset: i32,
+    pub interrupted: i32,
+}
+
+impl Tally {
+    fn add(&mut self, outcome: Outcome) {
+        match outcome {
+            Outcome::Completed => self.completed += 1,
+            Outcome::Skipped => self.skipped += 1,
+            Outcome::Reset => self.reset += 1,
+            Outcome::Interrupted => self.interrupted += 1,
+        }
+    }
+
+    pub fn attempts(&self) -> i32 {
+        self.completed + self.skipped + self.reset + self.interrupted
+    }
+
+    pub fn rate(&self) -> f64 {
+        match self.attempts() {
+            0 => 0.0,
+            n => self.completed as f64 / n as f64,
+        }
+    }
+
+    pub fn percent(&self) -> u32 {
+        (self.rate() * 100.0).round() as u32
+    }
+
+    /// Enough attempts to trust the rate, and it's low
+    pub fn is_low(&self) -> bool {
+        self.attempts() >= MIN_ATTEMPTS && self.rate() < LOW_RATE
+    }
+}
+
+/// Part of the (local) day a session started in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DayPart {
+    Morning,
+    Afternoon,
+    Evening,
+    Night,
+}
+
+impl DayPart {
+    pub const ALL: [DayPart; 4] = [
+        DayPart::Morning,
+        DayPart::Afternoon,
+        DayPart::Evening,
+        DayPart::Night,
+    ];
+
+    pub fn from_hour(hour: u32) -> Self {
+        match hour {
+            5..=11 => DayPart::Morning,
+            12..=16 => DayPart::Afternoon,
+            17..=21 => DayPart::Evening,
+            _ => DayPart::Night,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            DayPart::Morning => i18n::t("Morning   05-12"),
+            DayPart::Afternoon => i18n::t("Afternoon 12-17"),
+            DayPart::Evening => i18n::t("Evening   17-22"),
+            DayPart::Night => i18n::t("Night     22-05"),
+        }
+    }
+}
+
+/// One finished work attempt
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Attempt {
+    /// Local hour it started in
+    pub hour: u32,
+    /// Planned length; unknown for sessions recorded before it was stored
+    pub planned_minutes: Option<u32>,
+    pub outcome: Outcome,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Report {
+    pub total: Tally,
+    /// Parts of the day with attempts, in `DayPart::ALL` order
+    pub by_day_part: Vec<(DayPart, Tally)>,
+    /// Planned lengths with attempts, shortest first; unknown last
+    pub by_length: Vec<(Option<u32>, Tally)>,
+}
+
+pub fn build(attempts: &[Attempt]) -> Report {
+    let mut report = Report::default();
+    let mut parts = [Tally::default(); 4];
+    for attempt in attempts {
+        report.total.add(attempt.outcome);
+        let part = DayPart::from_hour(attempt.hour);
+        if let Some(i) = DayPart::ALL.iter().position(|p| *p == part) {
+            parts[i].add(attempt.outcome);
+        }
+        match report
+            .by_length
+            .iter_mut()
+            .find(|(minutes, _)| *minutes == attempt.planned_minutes)
+        {
+            Some((_, tally)) => tally.add(attempt.outcome),
+            None => {
+                let mut tally = Tally::default();
+                tally.add(attempt.outcome);
+                report.by_length.push((attempt.planned_minutes, tally));
+            }
+        }
+    }
+    report.by_day_part = DayPart::ALL
+        .into_iter()
+        .zip(parts)
+        .filter(|(_, tally)| tally.attempts() > 0)
+        .collect();
+    // None sorts first; unknown lengths go last
+    report
+        .by_length
+        .sort_by_key(|(minutes, _)| (minutes.is_none(), *minutes));
+    report
+}
+
+/// Completion report over the last `WINDOW_DAYS`
+pub fn load(db: &Database) -> Result<Report> {
+    let attempts: Vec<Attempt> = db
+        .get_work_attempts(WINDOW_DAYS)?
+        .into_iter()
+        .filter_map(|(started_at, planned_seconds, outcome)| {
+            Some(Attempt {
+                hour: started_at.with_timezone(&Local).hour(),
+                planned_minutes: planned_seconds.map(|s| (s.max(0) / 60) as u32),
+                outcome: Outcome::parse(&outcome)?,
+            })
+        })
+        .collect();
+    Ok(build(&attempts))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::SessionType;
+
+    fn attempt(hour: u32, minutes: u32, outcome: Outcome) -> Attempt {
+        Attempt {
+            hour,
+            planned_minutes: Some(minutes),
+            outcome,
+        }
+    }
+
+    #[test]
+    fn test_groups_by_day_part_and_length() {
+        let mut attempts = vec![
+            attempt(9, 25, Outcome::Completed),
+            attempt(10, 25, Outcome::Completed),
+            attempt(14, 50, Outcome::Skipped),
+            attempt(23, 50, Outcome::Reset),
+            attempt(2, 25, Outcome::Interrupted),
+        ];
+        attempts.push(Attempt {
+            hour: 9,
+            planned_minutes: None,
+            outcome: Outcome::Completed,
+        });
+        let report = build(&attempts);
+
+        assert_eq!(report.total.attempts(), 6);
+        assert_eq!(report.total.percent(), 50);
+        let parts: Vec<(DayPart, i32)> = report
+            .by_day_part
+            .iter()
+            .map(|(part, tally)| (*part, tally.attempts()))
+            .collect();
+        assert_eq!(
+            parts,
+            vec![
+                (DayPart::Morning, 3),
+                (DayPart::Afternoon, 1),
+                (DayPart::Night, 2)
+            ]
+        );
+        let lengths: Vec<Option<u32>> = report.by_length.iter().map(|(m, _)| *m).collect();
+        assert_eq!(lengths, vec![Some(25), Some(50), None]);
+        assert_eq!(report.by_length[1].1.completed, 0);
+    }
+
+    #[test]
+    fn test_low_rate_needs_enough_attempts() {
+        let mut tally = Tally::default();
+        for _ in 0..4 {
+            tally.add(Outcome::Skipped);
+        }
+        assert!(!tally.is_low());
+        tally.add(Outcome::Completed);
+        assert!(tally.is_low());
+    }
+
+    #[test]
+    fn test_load_classifies_outcomes() {
+        let db = Database::open_in_memory().unwrap();
+        let finished = db
+            .start_session_with_tag(SessionType::Work, None, Some(1500))
+            .unwrap();
+        db.complete_session(finished, 1500).unwrap();
+        let skipped = db
+            .start_session_with_tag(SessionType::Work, None, Some(1500))
+            .unwrap();
+        db.log_session_event(skipped, "skipped", None).unwrap();
+        let quit = db
+            .start_session_with_tag(SessionType::Work, None, Some(3000))
+            .unwrap();
+        db.interrupt_session(quit, chrono::Utc::now(), 600).unwrap();
+        // Continued on another device: it counts there
+        let moved = db
+            .start_session_with_tag(SessionType::Work, None, Some(1500))
+            .unwrap();
+        db.hand_off_session(moved, chrono::Utc::now(), 300, "laptop")
+            .unwrap();
+        // Still running: not an attempt yet
+        db.start_session_with_tag(SessionType::Work, None, Some(1500))
+            .unwrap();
+
+        let report = load(&db).unwrap();
+        assert_eq!(
+            report.total,
+            Tally {
+                completed: 1,
+                skipped: 1,
+                reset: 0,
+                interrupted: 1
+            }
+        );
+        assert_eq!(
+            report.by_length[0],
+            (
+                Some(25),
+                Tally {
+                    completed: 1,
+                    skipped: 1,
+                    reset: 0,
+                    interrupted: 0
+                }
+            )
+        );
+    }
+}
@@ -2,9 +2,13 @@
 //!
 //! Handles reading and writing config.toml
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use crate::days::DayPolicy;
+use crate::i18n;
 
 /// Focus mode type
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
@@ -13,6 +17,8 @@ pub enum FocusMode {
     #[default]
     Classic,
     Flowtime,
+    /// One named countdown or stopwatch, no breaks
+    Timer,
 }
 
 impl FocusMode {
@@ -21,6 +27,7 @@ impl FocusMode {
         match self {
             FocusMode::Classic => "classic",
             FocusMode::Flowtime => "flowtime",
+            FocusMode::Timer => "timer",
         }
     }
 
@@ -28,6 +35,7 @@ impl FocusMode {
     pub fn from_str(s: &str) -> Self {
         match s.to_lowercase().as_str() {
             "flowtime" => FocusMode::Flowtime,
+            "timer" => FocusMode::Timer,
             _ => FocusMode::Classic,
         }
     }
@@ -37,6 +45,172 @@ impl FocusMode {
         match self {
             FocusMode::Classic => "Classic Pomodoro: fixed work/break intervals",
             FocusMode::Flowtime => "Flowtime: work as long as you want, break = work time / 5",
+            FocusMode::Timer => "Timer: one named countdown or stopwatch, no breaks",
+        }
+    }
+}
+
+/// What to show in place of the icon during breaks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum BreakActivity {
+    #[default]
+    Icon,
+    /// Box-breathing guide (4-4-4-4)
+    Breathing,
+    /// Tiny snake game
+    Snake,
+}
+
+impl BreakActivity {
+    pub fn next(&self) -> Self {
+        match self {
+            BreakActivity::Icon => BreakActivity::Breathing,
+            BreakActivity::Breathing => BreakActivity::Snake,
+            BreakActivity::Snake => BreakActivity::Icon,
+        }
+    }
+
+    pub fn label_with_lang(&self, lang: &str) -> &'static str {
+        let label = match self {
+            BreakActivity::Icon => "Icon",
+            BreakActivity::Breathing => "🧘 Box breathing (4-4-4-4)",
+            BreakActivity::Snake => "🐍 Snake",
+        };
+        i18n::t_in(lang, label)
+    }
+}
+
+/// Whether quitting, skipping and resetting are held back during work
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum StrictMode {
+    #[default]
+    Off,
+    /// The key has to be pressed three times in a row
+    Confirm,
+    /// The keys do nothing until the break
+    Lock,
+}
+
+/// How fast the timer's icon animates
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum AnimationSpeed {
+    /// A still frame; the icon keeps filling with progress
+    Off,
+    Slow,
+    #[default]
+    Normal,
+    Fast,
+}
+
+impl AnimationSpeed {
+    pub fn next(&self) -> Self {
+        match self {
+            AnimationSpeed::Off => AnimationSpeed::Slow,
+            AnimationSpeed::Slow => AnimationSpeed::Normal,
+            AnimationSpeed::Normal => AnimationSpeed::Fast,
+            AnimationSpeed::Fast => AnimationSpeed::Off,
+        }
+    }
+
+    /// Ticks (100ms each) per animation frame; None when not animating
+    pub fn ticks_per_frame(&self) -> Option<u8> {
+        match self {
+            AnimationSpeed::Off => None,
+            AnimationSpeed::Slow => Some(10),
+            AnimationSpeed::Normal => Some(5),
+            AnimationSpeed::Fast => Some(2),
+        }
+    }
+
+    pub fn label_with_lang(&self, lang: &str) -> &'static str {
+        let label = match self {
+            AnimationSpeed::Off => "Off (still)",
+            AnimationSpeed::Slow => "Slow",
+            AnimationSpeed::Normal => "Normal",
+            AnimationSpeed::Fast => "Fast",
+        };
+        i18n::t_in(lang, label)
+    }
+}
+
+/// What happens to raw sessions that fall outside the retention period
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum RetentionMode {
+    /// Keep daily totals in daily_stats, drop the raw rows
+    #[default]
+    Aggregate,
+    /// Drop the raw rows entirely
+    Delete,
+}
+
+/// Which copy stays when `sandoro sync` finds a cloud session matching a
+/// local one that isn't uploaded yet
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "kebab-case")]
+pub enum ConflictPolicy {
+    /// The local session, sent over the cloud copy
+    #[default]
+    LocalWins,
+    /// Whichever copy was changed last
+    LatestWins,
+}
+
+/// Where sessions are stored
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageBackend {
+    /// ~/.sandoro/data.db
+    #[default]
+    Sqlite,
+    /// A Postgres server (builds with the `postgres` feature)
+    Postgres,
+}
+
+/// When the TUI slows down to save battery
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum LowPowerMode {
+    /// Only while running on battery
+    #[default]
+    Auto,
+    On,
+    Off,
+}
+
+/// Push service that receives break reminders for the phone
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PushProvider {
+    Ntfy,
+    Gotify,
+}
+
+impl PushProvider {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PushProvider::Ntfy => "ntfy",
+            PushProvider::Gotify => "gotify",
+        }
+    }
+}
+
+/// External time tracker that receives completed work sessions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrackerProvider {
+    Toggl,
+    Clockify,
+}
+
+impl TrackerProvider {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TrackerProvider::Toggl => "toggl",
+            TrackerProvider::Clockify => "clockify",
         }
     }
 }
@@ -44,6 +218,9 @@ impl FocusMode {
 /// Application configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
+    /// Bumped by every save (see settings.rs)
+    #[serde(default)]
+    pub revision: u64,
     #[serde(default)]
     pub timer: TimerConfig,
     #[serde(default)]
@@ -52,10 +229,44 @@ pub struct Config {
     pub notifications: NotificationsConfig,
     #[serde(default)]
     pub goals: GoalConfig,
+    /// Most minutes a day per tag, e.g. `"meetings-prep" = 60`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub budgets: BTreeMap<String, u32>,
     #[serde(default)]
     pub focus: FocusConfig,
     #[serde(default)]
     pub account: AccountConfig,
+    #[serde(default)]
+    pub retention: RetentionConfig,
+    #[serde(default)]
+    pub power: PowerConfig,
+    #[serde(default)]
+    pub accountability: AccountabilityConfig,
+    #[serde(default)]
+    pub time_tracking: TimeTrackingConfig,
+    #[serde(default)]
+    pub storage: StorageConfig,
+    #[serde(default)]
+    pub days: DaysConfig,
+    #[serde(default)]
+    pub xp: XpConfig,
+    #[serde(default)]
+    pub privacy: PrivacyConfig,
+    #[serde(default)]
+    pub escalation: EscalationConfig,
+    #[serde(default)]
+    pub tmux: TmuxConfig,
+    #[serde(default)]
+    pub ambience: AmbienceConfig,
+    #[serde(default)]
+    pub hooks: HooksConfig,
+    #[serde(default)]
+    pub dnd: DndConfig,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub workspaces: Vec<WorkspaceRule>,
+    /// Named chains of blocks, started with `sandoro routine <name>`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub routines: BTreeMap<String, Vec<RoutineStep>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -70,6 +281,12 @@ pub struct TimerConfig {
     pub sessions_until_long: u32,
     #[serde(default)]
     pub auto_start: bool,
+    /// Occasionally suggest a work length based on recent completion rates
+    #[serde(default)]
+    pub suggest_work_length: bool,
+    /// Ask for a note on each completed work session
+    #[serde(default)]
+    pub note_prompt: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -82,6 +299,66 @@ pub struct AppearanceConfig {
     pub accent: String,
     #[serde(default = "default_language")]
     pub language: String,
+    /// How fast the icon animates; `off` for a still icon
+    #[serde(default)]
+    pub animation_speed: AnimationSpeed,
+    /// Accent overrides per timer state
+    #[serde(default, skip_serializing_if = "StateAccents::is_empty")]
+    pub state_accents: StateAccents,
+    /// Show the state and the cycle's session dots in the terminal title
+    #[serde(default)]
+    pub terminal_title: bool,
+    /// Put the time left at the front of the terminal title, every second
+    #[serde(default)]
+    pub title_time: bool,
+    /// Show the phase's progress on the terminal's tab or taskbar button
+    #[serde(default)]
+    pub taskbar_progress: bool,
+    /// Colors and speed of the `rainbow` accent
+    #[serde(default)]
+    pub rainbow: RainbowConfig,
+}
+
+/// Colors and speed of the `rainbow` accent
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RainbowConfig {
+    /// A built-in palette (classic, pastel, trans, sunset, ocean), or
+    /// "custom" for `colors`
+    #[serde(default = "default_rainbow_palette")]
+    pub palette: String,
+    /// "#rrggbb" colors or accent names, used when `palette` is "custom"
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub colors: Vec<String>,
+    /// Milliseconds each color shows before the next (100-5000)
+    #[serde(default = "default_rainbow_speed_ms")]
+    pub speed_ms: u32,
+}
+
+impl Default for RainbowConfig {
+    fn default() -> Self {
+        Self {
+            palette: default_rainbow_palette(),
+            colors: Vec::new(),
+            speed_ms: default_rainbow_speed_ms(),
+        }
+    }
+}
+
+/// Accent colors used instead of `accent` while in a given timer state
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StateAccents {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub work: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub short_break: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub long_break: Option<String>,
+}
+
+impl StateAccents {
+    pub fn is_empty(&self) -> bool {
+        self.work.is_none() && self.short_break.is_none() && self.long_break.is_none()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -94,6 +371,32 @@ pub struct GoalConfig {
     pub weekly_sessions: u32,
     #[serde(default)]
     pub weekly_minutes: u32,
+    /// Day weekly goals reset on, e.g. "monday" or "sunday"
+    #[serde(default = "default_week_start")]
+    pub week_start: String,
+    /// Warn once a day when the daily goal is beyond anything done on that weekday
+    #[serde(default = "default_true")]
+    pub capacity_warnings: bool,
+    /// Notify at `reminder_time` when the daily goal isn't met yet
+    #[serde(default)]
+    pub reminder: bool,
+    /// Local time of the daily goal reminder, "HH:MM"
+    #[serde(default = "default_reminder_time")]
+    pub reminder_time: String,
+    /// Ask for today's journal note at `reminder_time`
+    #[serde(default)]
+    pub journal_prompt: bool,
+}
+
+fn default_reminder_time() -> String {
+    "20:00".to_string()
+}
+
+impl GoalConfig {
+    /// The configured week start, falling back to Monday
+    pub fn week_start_day(&self) -> chrono::Weekday {
+        self.week_start.parse().unwrap_or(chrono::Weekday::Mon)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -102,6 +405,219 @@ pub struct NotificationsConfig {
     pub sound: bool,
     #[serde(default = "default_true")]
     pub desktop: bool,
+    /// Play a short cue at these percentages of a work session (empty = off)
+    #[serde(default)]
+    pub progress_cues: Vec<u32>,
+    /// Where each kind of event goes, within the `sound` and `desktop` switches
+    #[serde(default)]
+    pub routes: NotificationRoutes,
+    /// What plays when a session ends: "beeps", "bell", "chime", "ding" or
+    /// the path of a WAV/OGG file
+    #[serde(default)]
+    pub sound_name: NotificationSound,
+}
+
+/// What plays when a session ends
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(from = "String", into = "String")]
+pub enum NotificationSound {
+    /// A few beeps, different for work and break ends
+    #[default]
+    Beeps,
+    Bell,
+    Chime,
+    Ding,
+    /// A WAV or OGG file, played by the system's audio player
+    File(PathBuf),
+}
+
+impl NotificationSound {
+    /// The next built-in sound; a file comes round to the beeps
+    pub fn next(&self) -> Self {
+        match self {
+            NotificationSound::Beeps => NotificationSound::Bell,
+            NotificationSound::Bell => NotificationSound::Chime,
+            NotificationSound::Chime => NotificationSound::Ding,
+            NotificationSound::Ding | NotificationSound::File(_) => NotificationSound::Beeps,
+        }
+    }
+
+    pub fn label_with_lang(&self, lang: &str) -> String {
+        match self {
+            NotificationSound::Beeps => i18n::t_in(lang, "Beeps").to_string(),
+            NotificationSound::Bell => i18n::t_in(lang, "Bell").to_string(),
+            NotificationSound::Chime => i18n::t_in(lang, "Chime").to_string(),
+            NotificationSound::Ding => i18n::t_in(lang, "Ding").to_string(),
+            NotificationSound::File(path) => path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| path.display().to_string()),
+        }
+    }
+}
+
+impl From<String> for NotificationSound {
+    fn from(name: String) -> Self {
+        match name.trim().to_lowercase().as_str() {
+            "" | "beeps" => NotificationSound::Beeps,
+            "bell" => NotificationSound::Bell,
+            "chime" => NotificationSound::Chime,
+            "ding" => NotificationSound::Ding,
+            _ => NotificationSound::File(PathBuf::from(name.trim())),
+        }
+    }
+}
+
+impl From<NotificationSound> for String {
+    fn from(sound: NotificationSound) -> Self {
+        match sound {
+            NotificationSound::Beeps => "beeps".to_string(),
+            NotificationSound::Bell => "bell".to_string(),
+            NotificationSound::Chime => "chime".to_string(),
+            NotificationSound::Ding => "ding".to_string(),
+            NotificationSound::File(path) => path.display().to_string(),
+        }
+    }
+}
+
+/// Where one kind of notification goes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Route {
+    #[default]
+    Both,
+    Sound,
+    Desktop,
+    /// No sound or popup, just a line in ~/.sandoro/notifications.log
+    Log,
+    Off,
+}
+
+impl Route {
+    pub fn next(&self) -> Self {
+        match self {
+            Route::Both => Route::Sound,
+            Route::Sound => Route::Desktop,
+            Route::Desktop => Route::Log,
+            Route::Log => Route::Off,
+            Route::Off => Route::Both,
+        }
+    }
+
+    pub fn sound(&self) -> bool {
+        matches!(self, Route::Both | Route::Sound)
+    }
+
+    pub fn desktop(&self) -> bool {
+        matches!(self, Route::Both | Route::Desktop)
+    }
+
+    pub fn label_with_lang(&self, lang: &str) -> &'static str {
+        let label = match self {
+            Route::Both => "Sound + desktop",
+            Route::Sound => "Sound only",
+            Route::Desktop => "Desktop only",
+            Route::Log => "Log only (silent)",
+            Route::Off => "Off",
+        };
+        i18n::t_in(lang, label)
+    }
+}
+
+/// Events that notify, in the order the settings screen lists them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NotifyEvent {
+    WorkEnd,
+    BreakEnd,
+    GoalReached,
+    GoalReminder,
+    Achievement,
+    OverBudget,
+}
+
+impl NotifyEvent {
+    pub const ALL: [NotifyEvent; 6] = [
+        NotifyEvent::WorkEnd,
+        NotifyEvent::BreakEnd,
+        NotifyEvent::GoalReached,
+        NotifyEvent::GoalReminder,
+        NotifyEvent::Achievement,
+        NotifyEvent::OverBudget,
+    ];
+
+    pub fn label_with_lang(&self, lang: &str) -> &'static str {
+        let label = match self {
+            NotifyEvent::WorkEnd => "Work ends",
+            NotifyEvent::BreakEnd => "Break ends",
+            NotifyEvent::GoalReached => "Daily goal reached",
+            NotifyEvent::GoalReminder => "Daily goal reminder",
+            NotifyEvent::Achievement => "Level up",
+            NotifyEvent::OverBudget => "Tag over budget",
+        };
+        i18n::t_in(lang, label)
+    }
+}
+
+/// `[notifications.routes]`: a route per event
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NotificationRoutes {
+    #[serde(default)]
+    pub work_end: Route,
+    #[serde(default)]
+    pub break_end: Route,
+    #[serde(default = "default_goal_route")]
+    pub goal_reached: Route,
+    #[serde(default = "default_goal_route")]
+    pub goal_reminder: Route,
+    #[serde(default = "default_achievement_route")]
+    pub achievement: Route,
+    #[serde(default)]
+    pub over_budget: Route,
+}
+
+fn default_goal_route() -> Route {
+    Route::Desktop
+}
+
+fn default_achievement_route() -> Route {
+    Route::Log
+}
+
+impl Default for NotificationRoutes {
+    fn default() -> Self {
+        Self {
+            work_end: Route::Both,
+            break_end: Route::Both,
+            goal_reached: default_goal_route(),
+            goal_reminder: default_goal_route(),
+            achievement: default_achievement_route(),
+            over_budget: Route::Both,
+        }
+    }
+}
+
+impl NotificationRoutes {
+    pub fn get(&self, event: NotifyEvent) -> Route {
+        match event {
+            NotifyEvent::WorkEnd => self.work_end,
+            NotifyEvent::BreakEnd => self.break_end,
+            NotifyEvent::GoalReached => self.goal_reached,
+            NotifyEvent::GoalReminder => self.goal_reminder,
+            NotifyEvent::Achievement => self.achievement,
+            NotifyEvent::OverBudget => self.over_budget,
+        }
+    }
+
+    pub fn get_mut(&mut self, event: NotifyEvent) -> &mut Route {
+        match event {
+            NotifyEvent::WorkEnd => &mut self.work_end,
+            NotifyEvent::BreakEnd => &mut self.break_end,
+            NotifyEvent::GoalReached => &mut self.goal_reached,
+            NotifyEvent::GoalReminder => &mut self.goal_reminder,
+            NotifyEvent::Achievement => &mut self.achievement,
+            NotifyEvent::OverBudget => &mut self.over_budget,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -110,15 +626,450 @@ pub struct FocusConfig {
     pub mode: FocusMode,
     #[serde(default)]
     pub break_snooze_enabled: bool,
+    #[serde(default)]
+    pub break_activity: BreakActivity,
+    /// Show a rotating suggestion ("drink water") during breaks
+    #[serde(default = "default_true")]
+    pub break_suggestions: bool,
+    /// Your own suggestions, shown along with the built-in ones
+    #[serde(default)]
+    pub custom_break_suggestions: Vec<String>,
+    /// Hold back `q`, `s` and `r` while a work session is under way
+    #[serde(default)]
+    pub strict: StrictMode,
+    /// Start with the tag that was selected when sandoro last ran
+    #[serde(default = "default_true")]
+    pub remember_tag: bool,
+    /// Named timers for timer focus mode
+    #[serde(default = "default_named_timers")]
+    pub timers: Vec<NamedTimer>,
+    /// Name of the timer in use (the first when empty or not found)
+    #[serde(default)]
+    pub timer: String,
+}
+
+/// A named countdown for timer focus mode, e.g. "deep work" for 90 minutes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NamedTimer {
+    pub name: String,
+    /// Length in minutes; 0 counts up like a stopwatch
+    #[serde(default)]
+    pub minutes: u32,
+}
+
+impl NamedTimer {
+    fn new(name: &str, minutes: u32) -> Self {
+        Self {
+            name: name.to_string(),
+            minutes,
+        }
+    }
+
+    /// "deep work · 90 min" / "stopwatch · counts up"
+    pub fn summary(&self, lang: &str) -> String {
+        match self.minutes {
+            0 => i18n::fill_in(lang, "{0} · counts up", &[&self.name]),
+            minutes => i18n::fill_in(lang, "{0} · {1} min", &[&self.name, &minutes]),
+        }
+    }
+}
+
+fn default_named_timers() -> Vec<NamedTimer> {
+    vec![
+        NamedTimer::new("deep work", 90),
+        NamedTimer::new("meeting", 30),
+        NamedTimer::new("stopwatch", 0),
+    ]
+}
+
+impl FocusConfig {
+    /// The named timer in use, None when none are configured
+    pub fn named_timer(&self) -> Option<&NamedTimer> {
+        self.timers
+            .iter()
+            .find(|t| t.name == self.timer)
+            .or(self.timers.first())
+    }
+
+    /// Minutes of the named timer in timer mode (0 counts up), None in the
+    /// other modes
+    pub fn timer_minutes(&self) -> Option<u32> {
+        (self.mode == FocusMode::Timer).then(|| self.named_timer().map_or(0, |t| t.minutes))
+    }
+
+    /// Move on to the next named timer
+    pub fn next_timer(&mut self) {
+        let current = self
+            .timers
+            .iter()
+            .position(|t| t.name == self.timer)
+            .unwrap_or(0);
+        if let Some(next) = self.timers.get((current + 1) % self.timers.len().max(1)) {
+            self.timer = next.name.clone();
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AccountConfig {
     #[serde(default)]
     pub license_key: String,
+    /// Publish running work sessions so another logged-in device can take
+    /// them over with `sandoro start --resume-remote`
+    #[serde(default = "default_true")]
+    pub handoff: bool,
+    /// Which copy of a session found both locally and in the cloud stays
+    #[serde(default)]
+    pub conflict_policy: ConflictPolicy,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RetentionConfig {
+    /// Days of raw session history to keep before today (0 or unset =
+    /// keep everything)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub days: Option<u32>,
+    #[serde(default)]
+    pub mode: RetentionMode,
+    /// Back up the database before pruning
+    #[serde(default = "default_true")]
+    pub backup: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PowerConfig {
+    /// Drop to one redraw per second and stop rainbow cycling
+    #[serde(default)]
+    pub low_power: LowPowerMode,
 }
 
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StorageConfig {
+    #[serde(default)]
+    pub backend: StorageBackend,
+    /// Connection string for the postgres backend, e.g.
+    /// "host=localhost user=sandoro dbname=sandoro"
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub url: String,
+}
+
+/// How sessions are bucketed into days when the UTC offset changes
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DaysConfig {
+    #[serde(default)]
+    pub policy: DayPolicy,
+}
+
+/// XP and levels for completed sessions
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct XpConfig {
+    /// Off by default; shows the level in the header when on
+    #[serde(default)]
+    pub enabled: bool,
+}
+
+/// Private mode (hides tags, notes and history until unlocked)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PrivacyConfig {
+    /// Keys to type in the locked timer to unlock it; "unlock" when empty
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub unlock_sequence: String,
+}
+
+/// Optional penalty for skipping breaks
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AccountabilityConfig {
+    /// Minutes taken off the day's quality focus per skipped break (0 = off)
+    #[serde(default)]
+    pub skip_penalty_minutes: u32,
+    /// Breaks that can be skipped each day before the penalty applies
+    #[serde(default)]
+    pub free_skips: u32,
+}
+
+impl AccountabilityConfig {
+    pub fn enabled(&self) -> bool {
+        self.skip_penalty_minutes > 0
+    }
+}
+
+/// Creates a time entry in Toggl or Clockify for each completed work session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TimeTrackingConfig {
+    /// Off when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<TrackerProvider>,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub api_token: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub workspace_id: String,
+    /// Project for sessions whose tag has no entry in `projects`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_project: Option<String>,
+    /// Tag name → project ID
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub projects: BTreeMap<String, String>,
+    #[serde(default)]
+    pub billable: bool,
+    #[serde(default = "default_entry_description")]
+    pub description: String,
+}
+
+impl TimeTrackingConfig {
+    /// The provider, if one is set up with credentials
+    pub fn enabled(&self) -> Option<TrackerProvider> {
+        self.provider
+            .filter(|_| !self.api_token.is_empty() && !self.workspace_id.is_empty())
+    }
+}
+
+/// Pushes to the phone when a finished break is left without starting work
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EscalationConfig {
+    /// Off when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<PushProvider>,
+    /// Server: your ntfy instance (https://ntfy.sh when empty) or your
+    /// Gotify instance, which has no default
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub url: String,
+    /// ntfy topic to publish to
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub topic: String,
+    /// ntfy access token (optional) or Gotify application token
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub token: String,
+    /// Minutes after the break is over to send each push, more urgent each time
+    #[serde(default = "default_escalation_ladder")]
+    pub ladder: Vec<u32>,
+    /// No pushes in this local time range, e.g. "22:00-07:00"
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub quiet_hours: String,
+}
+
+/// tmux commands run while a work session is on, when sandoro runs in tmux.
+/// `{pane}`, `{tag}` and `{minutes}` are filled in (see tmux.rs).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TmuxConfig {
+    #[serde(default)]
+    pub focus_lock: bool,
+    /// Run when a work session starts
+    #[serde(default = "default_tmux_focus")]
+    pub focus: Vec<String>,
+    /// Run on the break or when the session ends, to undo `focus`
+    #[serde(default = "default_tmux_release")]
+    pub release: Vec<String>,
+}
+
+/// A sound looped under work sessions, stopped on pauses and breaks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AmbienceConfig {
+    #[serde(default)]
+    pub sound: AmbientSound,
+    /// 0–100
+    #[serde(default = "default_ambience_volume")]
+    pub volume: u32,
+}
+
+fn default_ambience_volume() -> u32 {
+    30
+}
+
+impl Default for AmbienceConfig {
+    fn default() -> Self {
+        Self {
+            sound: AmbientSound::default(),
+            volume: default_ambience_volume(),
+        }
+    }
+}
+
+/// The system's do-not-disturb mode while a work session is on (see
+/// focus_dnd.rs)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DndConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Run instead of the built-in backend to turn it on
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub on_command: String,
+    /// Run instead of the built-in backend to turn it off
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub off_command: String,
+}
+
+/// Shell commands run on session events (see session_hooks.rs); unset
+/// runs nothing
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HooksConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_work_start: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_work_complete: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_break_start: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_break_complete: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub on_skip: Option<String>,
+    /// A command still running after this many seconds is killed
+    #[serde(default = "default_hook_timeout_secs")]
+    pub timeout_secs: u64,
+}
+
+fn default_hook_timeout_secs() -> u64 {
+    30
+}
+
+impl Default for HooksConfig {
+    fn default() -> Self {
+        Self {
+            on_work_start: None,
+            on_work_complete: None,
+            on_break_start: None,
+            on_break_complete: None,
+            on_skip: None,
+            timeout_secs: default_hook_timeout_secs(),
+        }
+    }
+}
+
+/// What plays under a work session
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum AmbientSound {
+    #[default]
+    Off,
+    /// A clock ticking once a second
+    Ticking,
+    Rain,
+    /// White noise
+    Noise,
+}
+
+impl AmbientSound {
+    pub fn next(&self) -> Self {
+        match self {
+            AmbientSound::Off => AmbientSound::Ticking,
+            AmbientSound::Ticking => AmbientSound::Rain,
+            AmbientSound::Rain => AmbientSound::Noise,
+            AmbientSound::Noise => AmbientSound::Off,
+        }
+    }
+
+    pub fn label_with_lang(&self, lang: &str) -> &'static str {
+        let label = match self {
+            AmbientSound::Off => "OFF",
+            AmbientSound::Ticking => "🕰 Ticking clock",
+            AmbientSound::Rain => "🌧 Rain",
+            AmbientSound::Noise => "📻 White noise",
+        };
+        i18n::t_in(lang, label)
+    }
+}
+
+impl Default for TmuxConfig {
+    fn default() -> Self {
+        Self {
+            focus_lock: false,
+            focus: default_tmux_focus(),
+            release: default_tmux_release(),
+        }
+    }
+}
+
+impl EscalationConfig {
+    /// The provider, if one is set up with where to push to
+    pub fn enabled(&self) -> Option<PushProvider> {
+        self.provider.filter(|&provider| {
+            self.server(provider).is_some()
+                && match provider {
+                    PushProvider::Ntfy => !self.topic.is_empty(),
+                    PushProvider::Gotify => !self.token.is_empty(),
+                }
+        })
+    }
+
+    /// Where `provider` pushes go. Gotify only goes to a `url` of its own,
+    /// so its token never reaches ntfy.sh.
+    pub fn server(&self, provider: PushProvider) -> Option<&str> {
+        let url = self.url.trim().trim_end_matches('/');
+        match provider {
+            PushProvider::Ntfy if url.is_empty() => Some(NTFY_URL),
+            PushProvider::Gotify if url.is_empty() || url == NTFY_URL => None,
+            _ => Some(url),
+        }
+    }
+
+    /// A Gotify provider without its server can't push anywhere
+    pub fn check(&self) -> Result<()> {
+        if self.provider == Some(PushProvider::Gotify)
+            && self.server(PushProvider::Gotify).is_none()
+        {
+            anyhow::bail!("`escalation.url` must be set to your Gotify server");
+        }
+        Ok(())
+    }
+}
+
+impl Default for EscalationConfig {
+    fn default() -> Self {
+        Self {
+            provider: None,
+            url: String::new(),
+            topic: String::new(),
+            token: String::new(),
+            ladder: default_escalation_ladder(),
+            quiet_hours: String::new(),
+        }
+    }
+}
+
+/// Maps a working directory or tmux session to a tag
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceRule {
+    /// Directory prefix (`~` is expanded)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    /// tmux session name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tmux: Option<String>,
+    /// Tag to select (created if it doesn't exist)
+    pub tag: String,
+}
+
+/// One entry of a routine: a block of work or a break, run `repeat` times
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RoutineStep {
+    #[serde(default)]
+    pub kind: BlockKind,
+    pub minutes: u32,
+    /// Tag the block is recorded with; the selected tag is kept without one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    #[serde(default = "default_repeat")]
+    pub repeat: u32,
+}
+
+/// What a routine block is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum BlockKind {
+    #[default]
+    Work,
+    Break,
+    LongBreak,
+}
+
+/// Progress cue thresholds used when cues are switched on from Settings
+pub const DEFAULT_PROGRESS_CUES: [u32; 2] = [50, 90];
+
 // Default value functions
+/// Public ntfy server, used when `[escalation] url` is empty
+pub const NTFY_URL: &str = "https://ntfy.sh";
+fn default_escalation_ladder() -> Vec<u32> {
+    vec![5, 15, 30]
+}
 fn default_work_duration() -> u32 {
     25
 }
@@ -131,18 +1082,58 @@ fn default_long_break() -> u32 {
 fn default_sessions_until_long() -> u32 {
     4
 }
+fn default_repeat() -> u32 {
+    1
+}
 fn default_icon() -> String {
     "hourglass".to_string()
 }
 fn default_theme() -> String {
     "default".to_string()
 }
+/// Dim the window's other panes, name it 🍅 and stop activity alerts
+fn default_tmux_focus() -> Vec<String> {
+    [
+        "setw -t {pane} window-style fg=colour244",
+        "setw -t {pane} window-active-style fg=terminal",
+        "rename-window -t {pane} 🍅",
+        "setw -t {pane} monitor-activity off",
+    ]
+    .map(String::from)
+    .to_vec()
+}
+
+fn default_tmux_release() -> Vec<String> {
+    [
+        "setw -u -t {pane} window-style",
+        "setw -u -t {pane} window-active-style",
+        "setw -t {pane} automatic-rename on",
+        "setw -u -t {pane} monitor-activity",
+    ]
+    .map(String::from)
+    .to_vec()
+}
+
+fn default_rainbow_palette() -> String {
+    "classic".to_string()
+}
+
+fn default_rainbow_speed_ms() -> u32 {
+    500
+}
+
+fn default_week_start() -> String {
+    "monday".to_string()
+}
 fn default_accent() -> String {
     "cyan".to_string()
 }
 fn default_language() -> String {
     "ja".to_string()
 }
+fn default_entry_description() -> String {
+    "Focus session".to_string()
+}
 fn default_true() -> bool {
     true
 }
@@ -154,12 +1145,29 @@ fn default_true() -> bool {
 impl Default for Config {
     fn default() -> Self {
         Self {
+            revision: 0,
             timer: TimerConfig::default(),
             appearance: AppearanceConfig::default(),
             notifications: NotificationsConfig::default(),
             goals: GoalConfig::default(),
+            budgets: BTreeMap::new(),
             focus: FocusConfig::default(),
             account: AccountConfig::default(),
+            retention: RetentionConfig::default(),
+            power: PowerConfig::default(),
+            accountability: AccountabilityConfig::default(),
+            time_tracking: TimeTrackingConfig::default(),
+            storage: StorageConfig::default(),
+            days: DaysConfig::default(),
+            xp: XpConfig::default(),
+            privacy: PrivacyConfig::default(),
+            escalation: EscalationConfig::default(),
+            tmux: TmuxConfig::default(),
+            ambience: AmbienceConfig::default(),
+            hooks: HooksConfig::default(),
+            dnd: DndConfig::default(),
+            workspaces: Vec::new(),
+            routines: BTreeMap::new(),
         }
     }
 }
@@ -172,6 +1180,8 @@ impl Default for TimerConfig {
             long_break: default_long_break(),
             sessions_until_long: default_sessions_until_long(),
             auto_start: false,
+            suggest_work_length: false,
+            note_prompt: false,
         }
     }
 }
@@ -183,6 +1193,12 @@ impl Default for AppearanceConfig {
             theme: default_theme(),
             accent: default_accent(),
             language: default_language(),
+            animation_speed: AnimationSpeed::default(),
+            state_accents: StateAccents::default(),
+            terminal_title: false,
+            title_time: false,
+            taskbar_progress: false,
+            rainbow: RainbowConfig::default(),
         }
     }
 }
@@ -195,6 +1211,11 @@ impl Default for GoalConfig {
             daily_minutes: 0,
             weekly_sessions: 0,
             weekly_minutes: 0,
+            week_start: default_week_start(),
+            capacity_warnings: true,
+            reminder: false,
+            reminder_time: default_reminder_time(),
+            journal_prompt: false,
         }
     }
 }
@@ -204,6 +1225,9 @@ impl Default for NotificationsConfig {
         Self {
             sound: default_true(),
             desktop: default_true(),
+            progress_cues: Vec::new(),
+            routes: NotificationRoutes::default(),
+            sound_name: NotificationSound::default(),
         }
     }
 }
@@ -214,6 +1238,13 @@ impl Default for FocusConfig {
         Self {
             mode: FocusMode::default(),
             break_snooze_enabled: false,
+            break_activity: BreakActivity::default(),
+            break_suggestions: true,
+            custom_break_suggestions: Vec::new(),
+            strict: StrictMode::default(),
+            remember_tag: true,
+            timers: default_named_timers(),
+            timer: String::new(),
         }
     }
 }
@@ -223,6 +1254,32 @@ impl Default for AccountConfig {
     fn default() -> Self {
         Self {
             license_key: String::new(),
+            handoff: true,
+            conflict_policy: ConflictPolicy::default(),
+        }
+    }
+}
+
+impl Default for TimeTrackingConfig {
+    fn default() -> Self {
+        Self {
+            provider: None,
+            api_token: String::new(),
+            workspace_id: String::new(),
+            default_project: None,
+            projects: BTreeMap::new(),
+            billable: false,
+            description: default_entry_description(),
+        }
+    }
+}
+
+impl Default for RetentionConfig {
+    fn default() -> Self {
+        Self {
+            days: None,
+            mode: RetentionMode::default(),
+            backup: default_true(),
         }
     }
 }
@@ -252,13 +1309,199 @@ impl Config {
         Ok(config)
     }
 
-    /// Save config to file
-    pub fn save(&self) -> Result<()> {
-        let dir = Self::config_dir()?;
-        std::fs::create_dir_all(&dir)?;
-        let path = Self::config_path()?;
-        let content = toml::to_string_pretty(self)?;
-        std::fs::write(path, content)?;
-        Ok(())
+    /// Load config.toml with a project's `.sandoro.toml` laid over it
+    pub fn load_with(project: Option<&ProjectConfig>) -> Result<Self> {
+        let config = Self::load().unwrap_or_default();
+        match project {
+            Some(project) => project.apply(&config),
+            None => Ok(config),
+        }
+    }
+
+    /// A copy safe to paste into a bug report: the license key, tokens, the
+    /// ntfy topic, the postgres connection string and the unlock sequence
+    /// are replaced when set
+    pub fn redacted(&self) -> Self {
+        const REDACTED: &str = "<redacted>";
+        let mut config = self.clone();
+        for secret in config.secrets_mut() {
+            if !secret.is_empty() {
+                *secret = REDACTED.to_string();
+            }
+        }
+        config
+    }
+
+    /// Every setting that must not be shown: anyone who has one can use
+    /// the account, post to the topic or read the data
+    fn secrets_mut(&mut self) -> [&mut String; 6] {
+        [
+            &mut self.account.license_key,
+            &mut self.time_tracking.api_token,
+            &mut self.escalation.topic,
+            &mut self.escalation.token,
+            &mut self.storage.url,
+            &mut self.privacy.unlock_sequence,
+        ]
+    }
+}
+
+/// Per-project config, looked for in the working directory and its parents
+pub const PROJECT_FILE: &str = ".sandoro.toml";
+
+/// Tables a `.sandoro.toml` may set. Anything else (hooks, tmux, storage,
+/// escalation, ...) could run commands or send data from a cloned repo, so
+/// it is left out and reported.
+pub const PROJECT_TABLES: &[&str] = &["timer", "appearance", "focus"];
+
+/// A `.sandoro.toml` found for the working directory. Its keys are laid
+/// over config.toml key by key; a table only replaces the keys it sets.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProjectConfig {
+    pub path: PathBuf,
+    /// Tag to start with, from the file's top-level `tag`
+    pub tag: Option<String>,
+    /// Top-level keys in the file that aren't in `PROJECT_TABLES`
+    pub ignored: Vec<String>,
+    table: toml::Table,
+}
+
+impl ProjectConfig {
+    /// The nearest `.sandoro.toml` in `dir` or one of its parents
+    pub fn find(dir: &Path) -> Result<Option<Self>> {
+        match dir
+            .ancestors()
+            .map(|dir| dir.join(PROJECT_FILE))
+            .find(|path| path.is_file())
+        {
+            Some(path) => Self::load(&path).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        let mut table: toml::Table =
+            toml::from_str(&content).with_context(|| format!("In {}", path.display()))?;
+        let tag = match table.remove("tag") {
+            Some(toml::Value::String(tag)) if !tag.trim().is_empty() => {
+                Some(tag.trim().to_string())
+            }
+            Some(toml::Value::String(_)) | None => None,
+            Some(_) => anyhow::bail!("In {}: tag must be a string", path.display()),
+        };
+        let ignored: Vec<String> = table
+            .keys()
+            .filter(|key| !PROJECT_TABLES.contains(&key.as_str()))
+            .cloned()
+            .collect();
+        for key in &ignored {
+            table.remove(key);
+        }
+        let project = Self {
+            path: path.to_path_buf(),
+            tag,
+            ignored,
+            table,
+        };
+        // Catch a key of the wrong type now rather than on every load
+        project.apply(&Config::default())?;
+        Ok(project)
+    }
+
+    /// `config` with this file's keys laid over it
+    pub fn apply(&self, config: &Config) -> Result<Config> {
+        let mut table = match toml::Value::try_from(config)? {
+            toml::Value::Table(table) => table,
+            _ => anyhow::bail!("config is not a table"),
+        };
+        overlay(&mut table, &self.table);
+        toml::Value::Table(table)
+            .try_into()
+            .with_context(|| format!("In {}", self.path.display()))
+    }
+}
+
+/// Set the keys of `top` in `base`, going into tables both have
+fn overlay(base: &mut toml::Table, top: &toml::Table) {
+    for (key, value) in top {
+        match (base.get_mut(key), value) {
+            (Some(toml::Value::Table(base)), toml::Value::Table(top)) => overlay(base, top),
+            _ => {
+                base.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_project_file_is_found_in_a_parent_and_laid_over() {
+        let dir = tempfile::tempdir().unwrap();
+        let nested = dir.path().join("src").join("bin");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(
+            dir.path().join(PROJECT_FILE),
+            "tag = \"work\"\n[timer]\nwork_duration = 50\n[appearance]\ntheme = \"nord\"\n",
+        )
+        .unwrap();
+
+        let project = ProjectConfig::find(&nested).unwrap().unwrap();
+        assert_eq!(project.path, dir.path().join(PROJECT_FILE));
+        assert_eq!(project.tag.as_deref(), Some("work"));
+
+        let mut global = Config::default();
+        global.timer.short_break = 7;
+        let config = project.apply(&global).unwrap();
+        assert_eq!(config.timer.work_duration, 50);
+        assert_eq!(config.appearance.theme, "nord");
+        // Keys the project doesn't set stay as in config.toml
+        assert_eq!(config.timer.short_break, 7);
+    }
+
+    #[test]
+    fn test_project_file_cannot_set_hooks() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join(PROJECT_FILE),
+            "[hooks]\non_work_start = \"touch /tmp/PWNED\"\n\
+             [tmux]\nfocus_lock = true\n[timer]\nwork_duration = 40\n",
+        )
+        .unwrap();
+
+        let project = ProjectConfig::find(dir.path()).unwrap().unwrap();
+        assert_eq!(project.ignored, vec!["hooks", "tmux"]);
+
+        let config = project.apply(&Config::default()).unwrap();
+        assert_eq!(config.timer.work_duration, 40);
+        assert_eq!(config.hooks.on_work_start, None);
+        assert!(!config.tmux.focus_lock);
+    }
+
+    #[test]
+    fn test_redacted_hides_every_secret() {
+        let mut config = Config::default();
+        for (i, secret) in config.secrets_mut().into_iter().enumerate() {
+            *secret = format!("secret-{}", i);
+        }
+        let text = toml::to_string(&config.redacted()).unwrap();
+        assert!(!text.contains("secret-"), "{}", text);
+        assert!(config
+            .redacted()
+            .secrets_mut()
+            .into_iter()
+            .all(|s| s == "<redacted>"));
+    }
+
+    #[test]
+    fn test_broken_project_file_names_itself() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(PROJECT_FILE);
+        std::fs::write(&path, "[timer]\nwork_duration = \"long\"\n").unwrap();
+        let err = ProjectConfig::find(dir.path()).unwrap_err();
+        assert!(err.to_string().contains(PROJECT_FILE));
     }
 }
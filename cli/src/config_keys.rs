@@ -0,0 +1,561 @@
+//! Settings by key, for `sandoro config`
+//!
+//! A key is a setting's section and name as config.toml has them, e.g.
+//! `timer.work_duration`, with quotes around a part that has a dot or a
+//! space in it (`budgets."deep work"`). `set` reads the value as the type
+//! the setting already has, so `50` or `on` need no TOML quoting, and only
+//! saves when the result still loads as a `Config`. A name that matches no
+//! setting is an error with the closest one suggested, where serde alone
+//! would drop it without a word.
+
+use anyhow::{anyhow, bail, Context, Result};
+use std::path::Path;
+use toml::{Table, Value};
+
+use crate::config::Config;
+use crate::icons::IconType;
+use crate::messages::Language;
+use crate::settings;
+use crate::theme::{self, Theme};
+
+/// Kept by the saves themselves (see settings.rs)
+const REVISION: &str = "revision";
+
+/// Split `timer.work_duration` or `budgets."deep work"` into its parts
+pub fn parse_key(key: &str) -> Result<Vec<String>> {
+    let mut parts = Vec::new();
+    let mut chars = key.trim().chars().peekable();
+    loop {
+        let mut part = String::new();
+        if chars.peek() == Some(&'"') {
+            chars.next();
+            loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some(c) => part.push(c),
+                    None => bail!("Unclosed quote in `{}`", key),
+                }
+            }
+        } else {
+            while let Some(&c) = chars.peek().filter(|c| is_bare(**c)) {
+                part.push(c);
+                chars.next();
+            }
+            if part.is_empty() {
+                bail!(
+                    "`{}` has an empty part; keys look like timer.work_duration",
+                    key
+                );
+            }
+        }
+        parts.push(part);
+        match chars.next() {
+            None => return Ok(parts),
+            Some('.') => {}
+            Some(c) => bail!(
+                "`{}` can't have `{}` outside quotes, e.g. budgets.\"deep work\"",
+                key,
+                c
+            ),
+        }
+    }
+}
+
+/// The key for `path`, quoting the parts `parse_key` needs quoted
+pub fn format_key(path: &[String]) -> String {
+    path.iter()
+        .map(|part| {
+            if !part.is_empty() && part.chars().all(is_bare) {
+                part.clone()
+            } else {
+                format!("\"{}\"", part)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+fn is_bare(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_' || c == '-'
+}
+
+/// The setting at `key`: strings as they are, a section as TOML
+pub fn get(config: &Config, key: &str) -> Result<String> {
+    let path = parse_key(key)?;
+    let table = settings::table(config)?;
+    match lookup(&table, &path) {
+        Some(Value::String(text)) => Ok(text.clone()),
+        Some(Value::Table(section)) => Ok(toml::to_string_pretty(section)?.trim_end().to_string()),
+        Some(value) => Ok(value.to_string()),
+        None => match suggest(&table, &path) {
+            Some(close) => bail!(
+                "No setting `{}`. Did you mean `{}`?",
+                format_key(&path),
+                close
+            ),
+            None => bail!("`{}` isn't set", format_key(&path)),
+        },
+    }
+}
+
+/// `config` with the setting at `key` changed to `value`
+pub fn set(config: &Config, key: &str, value: &str) -> Result<Config> {
+    let path = parse_key(key)?;
+    let key = format_key(&path);
+    if key == REVISION {
+        bail!("`{}` is kept by sandoro itself", REVISION);
+    }
+    let before = settings::table(config)?;
+    let value = coerce(&key, lookup(&before, &path), value)?;
+    let mut table = before.clone();
+    insert(&mut table, &path, value.clone())?;
+    let edited: Config = Value::Table(table)
+        .try_into()
+        .map_err(|err| anyhow!("Can't set `{}`: {}", key, err.message()))?;
+    if lookup(&settings::table(&edited)?, &path).is_none() && !is_empty(&value) {
+        return Err(no_setting(&before, &path));
+    }
+    check(&key, &edited)?;
+    Ok(edited)
+}
+
+/// Every setting that has a value, as `key = value` lines
+pub fn list(config: &Config) -> Result<Vec<String>> {
+    let mut table = settings::table(config)?;
+    table.remove(REVISION);
+    let mut lines = Vec::new();
+    flatten(&table, &mut Vec::new(), &mut lines);
+    Ok(lines)
+}
+
+fn flatten(table: &Table, prefix: &mut Vec<String>, lines: &mut Vec<String>) {
+    for (name, value) in table {
+        prefix.push(name.clone());
+        match value {
+            Value::Table(section) => flatten(section, prefix, lines),
+            value => lines.push(format!("{} = {}", format_key(prefix), value)),
+        }
+        prefix.pop();
+    }
+}
+
+/// Open config.toml in $VISUAL or $EDITOR (vi when neither is set) and
+/// save it once it loads, offering to go back to the editor until it does
+pub fn edit() -> Result<()> {
+    use std::io::{IsTerminal, Write};
+
+    let dir = Config::config_dir()?;
+    std::fs::create_dir_all(&dir)?;
+    let path = Config::config_path()?;
+    let original = match std::fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            toml::to_string_pretty(&Config::default())?
+        }
+        Err(err) => return Err(err.into()),
+    };
+    // Edited on the side, so nothing reads a half-fixed config.toml
+    let draft = dir.join("config.edit.toml");
+    std::fs::write(&draft, &original)?;
+
+    loop {
+        if let Err(err) = open_editor(&draft) {
+            let _ = std::fs::remove_file(&draft);
+            return Err(err);
+        }
+        let content = std::fs::read_to_string(&draft)?;
+        if content == original {
+            let _ = std::fs::remove_file(&draft);
+            println!("  No changes");
+            return Ok(());
+        }
+        let err = match check_content(&content) {
+            Ok(()) => {
+                settings::replace(&content)?;
+                let _ = std::fs::remove_file(&draft);
+                println!("  ✓ Saved {}", path.display());
+                return Ok(());
+            }
+            Err(err) => err,
+        };
+        eprintln!("  ⚠ {:#}", err);
+        if !std::io::stdin().is_terminal() {
+            break;
+        }
+        eprint!("    Edit again? [Y/n] ");
+        let _ = std::io::stderr().flush();
+        let mut answer = String::new();
+        let _ = std::io::stdin().read_line(&mut answer);
+        if !matches!(answer.trim().to_lowercase().as_str(), "" | "y" | "yes") {
+            break;
+        }
+    }
+    bail!(
+        "{} is unchanged; the edit is kept in {}",
+        path.display(),
+        draft.display()
+    )
+}
+
+fn open_editor(path: &Path) -> Result<()> {
+    let editor = ["VISUAL", "EDITOR"]
+        .iter()
+        .filter_map(|name| std::env::var(name).ok())
+        .find(|editor| !editor.trim().is_empty())
+        .unwrap_or_else(|| "vi".to_string());
+    // `code --wait` and the like
+    let mut words = editor.split_whitespace();
+    let program = words.next().unwrap_or("vi");
+    let status = std::process::Command::new(program)
+        .args(words)
+        .arg(path)
+        .status()
+        .with_context(|| format!("Couldn't start `{}`; set $EDITOR to your editor", editor))?;
+    if !status.success() {
+        bail!(
+            "`{}` exited with {}; config.toml is unchanged",
+            editor,
+            status
+        );
+    }
+    Ok(())
+}
+
+/// Why `content` can't be config.toml: it doesn't parse, a value has the
+/// wrong type, or a name matches no setting
+fn check_content(content: &str) -> Result<()> {
+    let config: Config = toml::from_str(content)?;
+    config.escalation.check()?;
+    let raw: Table = toml::from_str(content)?;
+    let loaded = settings::table(&config)?;
+    let mut unknown = Vec::new();
+    unknown_keys(&raw, &loaded, &mut Vec::new(), &mut unknown);
+    match unknown.as_slice() {
+        [] => Ok(()),
+        paths => Err(anyhow!(paths
+            .iter()
+            .map(|path| no_setting(&loaded, path).to_string())
+            .collect::<Vec<_>>()
+            .join("\n"))),
+    }
+}
+
+/// Keys in `raw` that loading it dropped
+fn unknown_keys(raw: &Table, loaded: &Table, prefix: &mut Vec<String>, out: &mut Vec<Vec<String>>) {
+    for (name, value) in raw {
+        prefix.push(name.clone());
+        match (value, loaded.get(name)) {
+            (Value::Table(raw), Some(Value::Table(loaded))) => {
+                unknown_keys(raw, loaded, prefix, out)
+            }
+            (_, Some(_)) => {}
+            // Left out again only because it's empty
+            (value, None) if is_empty(value) => {}
+            (_, None) => out.push(prefix.clone()),
+        }
+        prefix.pop();
+    }
+}
+
+/// What config.toml leaves out when saving: empty strings, lists and
+/// sections
+fn is_empty(value: &Value) -> bool {
+    match value {
+        Value::String(text) => text.is_empty(),
+        Value::Array(items) => items.is_empty(),
+        Value::Table(section) => section.values().all(is_empty),
+        _ => false,
+    }
+}
+
+fn lookup<'a>(table: &'a Table, path: &[String]) -> Option<&'a Value> {
+    let (name, sections) = path.split_last()?;
+    let mut table = table;
+    for section in sections {
+        table = table.get(section)?.as_table()?;
+    }
+    table.get(name)
+}
+
+fn insert(table: &mut Table, path: &[String], value: Value) -> Result<()> {
+    let Some((name, sections)) = path.split_last() else {
+        bail!("Empty key");
+    };
+    let mut table = table;
+    for (depth, section) in sections.iter().enumerate() {
+        table = table
+            .entry(section.clone())
+            .or_insert_with(|| Value::Table(Table::new()))
+            .as_table_mut()
+            .with_context(|| {
+                format!(
+                    "`{}` is a setting, not a section",
+                    format_key(&path[..=depth])
+                )
+            })?;
+    }
+    table.insert(name.clone(), value);
+    Ok(())
+}
+
+/// `value` as the type of the setting's `current` value; as TOML, or else
+/// a string, for a setting that has none
+fn coerce(key: &str, current: Option<&Value>, value: &str) -> Result<Value> {
+    let text = value.trim();
+    Ok(match current {
+        Some(Value::String(_)) => Value::String(value.to_string()),
+        Some(Value::Integer(_)) => Value::Integer(
+            text.parse()
+                .map_err(|_| anyhow!("`{}` takes a whole number, not `{}`", key, value))?,
+        ),
+        Some(Value::Float(_)) => Value::Float(
+            text.parse()
+                .map_err(|_| anyhow!("`{}` takes a number, not `{}`", key, value))?,
+        ),
+        Some(Value::Boolean(_)) => Value::Boolean(match text.to_lowercase().as_str() {
+            "true" | "on" | "yes" => true,
+            "false" | "off" | "no" => false,
+            _ => bail!("`{}` takes true or false, not `{}`", key, value),
+        }),
+        Some(current) => literal(text).with_context(|| {
+            format!(
+                "`{}` takes a TOML {}, not `{}`",
+                key,
+                current.type_str(),
+                value
+            )
+        })?,
+        None => literal(text).unwrap_or_else(|_| Value::String(value.to_string())),
+    })
+}
+
+/// `text` read as a TOML value, e.g. `["a", "b"]`
+fn literal(text: &str) -> Result<Value> {
+    let mut table: Table = toml::from_str(&format!("value = {}", text))?;
+    table.remove("value").context("No value")
+}
+
+/// The names a setting takes, for the ones read as a name
+fn choices(key: &str) -> Option<Vec<String>> {
+    let names = match key {
+        "appearance.theme" => Theme::free_themes(),
+        "appearance.accent" => theme::available_accent_colors(),
+        "appearance.language" => Language::ALL.iter().map(|l| l.code()).collect(),
+        "appearance.icon" => {
+            return Some(
+                IconType::free_icons()
+                    .iter()
+                    .map(|icon| icon.to_string())
+                    .collect(),
+            )
+        }
+        _ => return None,
+    };
+    Some(names.into_iter().map(str::to_string).collect())
+}
+
+/// Values that load but that the timer can't use
+fn check(key: &str, config: &Config) -> Result<()> {
+    let table = settings::table(config)?;
+    let path = parse_key(key)?;
+    let value = lookup(&table, &path);
+    if let (Some(names), Some(Value::String(name))) = (choices(key), value) {
+        if !names.contains(&name.to_lowercase()) {
+            bail!(
+                "`{}` takes one of {}, not `{}`",
+                key,
+                names.join(", "),
+                name
+            );
+        }
+    }
+    let counts = [
+        "timer.work_duration",
+        "timer.short_break",
+        "timer.long_break",
+        "timer.sessions_until_long",
+    ];
+    if counts.contains(&key) && value.and_then(Value::as_integer) == Some(0) {
+        bail!("`{}` takes at least 1", key);
+    }
+    if key.starts_with("escalation") {
+        config.escalation.check()?;
+    }
+    Ok(())
+}
+
+fn no_setting(table: &Table, path: &[String]) -> anyhow::Error {
+    let key = format_key(path);
+    match suggest(table, path) {
+        Some(close) => anyhow!("No setting `{}`. Did you mean `{}`?", key, close),
+        None => anyhow!("No setting `{}`; `sandoro config list` shows them", key),
+    }
+}
+
+/// `path` with the first part that matches nothing replaced by the closest
+/// name there, if one is close
+fn suggest(table: &Table, path: &[String]) -> Option<String> {
+    let mut table = table;
+    for (depth, part) in path.iter().enumerate() {
+        match table.get(part) {
+            Some(Value::Table(section)) => table = section,
+            Some(_) => return None,
+            None => {
+                let name = closest(part, table.keys().filter(|k| *k != REVISION))?;
+                let mut fixed = path.to_vec();
+                fixed[depth] = name.to_string();
+                return Some(format_key(&fixed));
+            }
+        }
+    }
+    None
+}
+
+fn closest<'a>(part: &str, names: impl Iterator<Item = &'a String>) -> Option<&'a str> {
+    let part = part.to_lowercase();
+    names
+        .map(|name| (distance(&part, name), name))
+        .filter(|(distance, name)| {
+            *distance <= (part.chars().count() / 3).max(2)
+                || (part.chars().count() >= 3 && name.contains(part.as_str()))
+        })
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, name)| name.as_str())
+}
+
+/// Edits to turn `a` into `b`
+fn distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = if ca == *cb {
+                diagonal
+            } else {
+                1 + diagonal.min(above).min(row[j])
+            };
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keys_split_on_dots_outside_quotes() {
+        assert_eq!(
+            parse_key("timer.work_duration").unwrap(),
+            vec!["timer", "work_duration"]
+        );
+        let path = parse_key("budgets.\"deep.work\"").unwrap();
+        assert_eq!(path, vec!["budgets", "deep.work"]);
+        assert_eq!(format_key(&path), "budgets.\"deep.work\"");
+        assert!(parse_key("timer.").is_err());
+        assert!(parse_key("budgets.\"deep").is_err());
+        assert!(parse_key("timer work").is_err());
+    }
+
+    #[test]
+    fn test_set_reads_the_value_as_the_settings_type() {
+        let config = Config::default();
+        assert_eq!(
+            set(&config, "timer.work_duration", "50")
+                .unwrap()
+                .timer
+                .work_duration,
+            50
+        );
+        assert!(
+            set(&config, "timer.auto_start", "on")
+                .unwrap()
+                .timer
+                .auto_start
+        );
+        assert_eq!(
+            set(&config, "budgets.\"deep work\"", "90").unwrap().budgets["deep work"],
+            90
+        );
+
+        let err = set(&config, "timer.work_duration", "long").unwrap_err();
+        assert!(err.to_string().contains("whole number"));
+        assert!(set(&config, "timer.work_duration", "-5").is_err());
+        assert!(set(&config, "timer.work_duration", "0").is_err());
+        assert!(set(&config, "revision", "3").is_err());
+    }
+
+    #[test]
+    fn test_names_are_checked_against_the_choices() {
+        let config = Config::default();
+        assert_eq!(
+            set(&config, "appearance.theme", "nord")
+                .unwrap()
+                .appearance
+                .theme,
+            "nord"
+        );
+        let err = set(&config, "appearance.theme", "solarised").unwrap_err();
+        assert!(err.to_string().contains("default, nord, dracula"));
+        assert!(set(&config, "appearance.language", "de").is_ok());
+        assert!(set(&config, "appearance.language", "fr").is_err());
+    }
+
+    #[test]
+    fn test_unknown_keys_suggest_the_closest() {
+        let config = Config::default();
+        let err = set(&config, "timer.work_duraton", "50").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "No setting `timer.work_duraton`. Did you mean `timer.work_duration`?"
+        );
+        let err = get(&config, "timr.work_duration").unwrap_err();
+        assert!(err.to_string().contains("`timer.work_duration`"));
+        let err = set(&config, "timer.zzz", "1").unwrap_err();
+        assert!(err.to_string().contains("sandoro config list"));
+        assert!(set(&config, "timer.work_duration.minutes", "1").is_err());
+    }
+
+    #[test]
+    fn test_get_and_list() {
+        let mut config = Config::default();
+        config.budgets.insert("deep work".to_string(), 60);
+        assert_eq!(get(&config, "appearance.theme").unwrap(), "default");
+        assert_eq!(get(&config, "timer.work_duration").unwrap(), "25");
+        assert!(get(&config, "timer")
+            .unwrap()
+            .contains("work_duration = 25"));
+
+        let lines = list(&config).unwrap();
+        assert!(lines.contains(&"timer.work_duration = 25".to_string()));
+        assert!(lines.contains(&"budgets.\"deep work\" = 60".to_string()));
+        assert!(!lines.iter().any(|line| line.starts_with("revision")));
+    }
+
+    #[test]
+    fn test_list_of_the_redacted_config_hides_secrets() {
+        let mut config = Config::default();
+        config.account.license_key = "LIC-XYZ".to_string();
+        config.escalation.topic = "secret-topic".to_string();
+        let lines = list(&config.redacted()).unwrap();
+        assert!(lines.contains(&"account.license_key = \"<redacted>\"".to_string()));
+        assert!(lines.contains(&"escalation.topic = \"<redacted>\"".to_string()));
+        assert!(!lines
+            .iter()
+            .any(|line| line.contains("LIC-XYZ") || line.contains("secret-topic")));
+    }
+
+    #[test]
+    fn test_edited_file_is_checked_before_saving() {
+        assert!(check_content("[timer]\nwork_duration = 50\n").is_ok());
+        assert!(check_content("[timer]\nwork_duration = \"long\"\n").is_err());
+        let err = check_content("[timer]\nwork_duraton = 50\n").unwrap_err();
+        assert!(err.to_string().contains("`timer.work_duration`"));
+        // Empty sections are only left out on save, not unknown
+        assert!(check_content("[appearance.state_accents]\n").is_ok());
+    }
+}
@@ -0,0 +1,270 @@
+//! Control socket for the running timer
+//!
+//! The TUI listens on `~/.sandoro/control.sock` so other commands can act
+//! on it, e.g. `sandoro snooze 5`. Notification buttons send the same
+//! commands, over a channel instead of the socket.
+//! One request per connection: a command line goes in, a reply line comes
+//! out, either `ok <message>` or `error <message>`.
+
+use anyhow::{bail, Context, Result};
+use std::path::PathBuf;
+
+use crate::config::Config;
+
+/// Longest snooze accepted, in minutes
+const MAX_SNOOZE_MINUTES: u32 = 120;
+
+/// Something to do in the running timer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    /// Extend the break by this many minutes (its full length when None),
+    /// or bring back a break that just ended
+    Snooze(Option<u32>),
+    /// Turn private mode on. Unlocking takes the sequence, so it isn't a
+    /// command: `sandoro unlock` removes the lock file and the timer sees it.
+    Lock,
+    /// Cut the work block short and start the break now
+    BreakNow,
+    /// End the break early, or start the paused work block, and work now
+    WorkNow,
+    /// Start or resume the current phase
+    Start,
+    Pause,
+    /// Move on to the next phase, as `s` does
+    Skip,
+}
+
+impl Command {
+    pub fn parse(line: &str) -> Result<Self> {
+        let mut words = line.split_whitespace();
+        match (words.next(), words.next(), words.next()) {
+            (Some("snooze"), minutes, None) => {
+                let minutes = minutes
+                    .map(|m| {
+                        m.parse::<u32>()
+                            .ok()
+                            .filter(|m| (1..=MAX_SNOOZE_MINUTES).contains(m))
+                            .with_context(|| {
+                                format!(
+                                    "Snooze takes 1 to {} minutes, got '{}'",
+                                    MAX_SNOOZE_MINUTES, m
+                                )
+                            })
+                    })
+                    .transpose()?;
+                Ok(Command::Snooze(minutes))
+            }
+            (Some("lock"), None, None) => Ok(Command::Lock),
+            (Some("break"), Some("now"), None) => Ok(Command::BreakNow),
+            (Some("work"), Some("now"), None) => Ok(Command::WorkNow),
+            (Some("start"), None, None) => Ok(Command::Start),
+            (Some("pause"), None, None) => Ok(Command::Pause),
+            (Some("skip"), None, None) => Ok(Command::Skip),
+            _ => bail!("Unknown command '{}'", line.trim()),
+        }
+    }
+
+    pub fn to_line(self) -> String {
+        match self {
+            Command::Snooze(Some(minutes)) => format!("snooze {}", minutes),
+            Command::Snooze(None) => "snooze".to_string(),
+            Command::Lock => "lock".to_string(),
+            Command::BreakNow => "break now".to_string(),
+            Command::WorkNow => "work now".to_string(),
+            Command::Start => "start".to_string(),
+            Command::Pause => "pause".to_string(),
+            Command::Skip => "skip".to_string(),
+        }
+    }
+}
+
+pub fn path() -> Result<PathBuf> {
+    Ok(Config::config_dir()?.join("control.sock"))
+}
+
+#[cfg(unix)]
+pub use unix::{send, Server};
+
+#[cfg(unix)]
+mod unix {
+    use super::*;
+    use std::io::{BufRead, BufReader, ErrorKind, Write};
+    use std::os::unix::net::{UnixListener, UnixStream};
+    use std::path::Path;
+    use std::time::Duration;
+
+    /// How long a client may take to send its command
+    const READ_TIMEOUT: Duration = Duration::from_millis(200);
+
+    /// Listening end, owned by the TUI; removes the socket when dropped
+    pub struct Server {
+        listener: UnixListener,
+        path: PathBuf,
+    }
+
+    impl Server {
+        /// Listen on the control socket. None when another running timer
+        /// already answers on it.
+        pub fn bind() -> Result<Option<Self>> {
+            Self::bind_at(path()?)
+        }
+
+        pub fn bind_at(path: PathBuf) -> Result<Option<Self>> {
+            if path.exists() {
+                if UnixStream::connect(&path).is_ok() {
+                    return Ok(None);
+                }
+                // Left behind by a timer that didn't exit cleanly
+                std::fs::remove_file(&path)?;
+            }
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir)?;
+            }
+            let listener = UnixListener::bind(&path)?;
+            listener.set_nonblocking(true)?;
+            Ok(Some(Self { listener, path }))
+        }
+
+        /// Answer every waiting request without blocking
+        pub fn poll(&self, mut handle: impl FnMut(Command) -> Result<String>) {
+            loop {
+                match self.listener.accept() {
+                    Ok((stream, _)) => {
+                        let _ = Self::answer(stream, &mut handle);
+                    }
+                    Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                    Err(_) => break,
+                }
+            }
+        }
+
+        fn answer(
+            stream: UnixStream,
+            handle: &mut impl FnMut(Command) -> Result<String>,
+        ) -> Result<()> {
+            stream.set_nonblocking(false)?;
+            stream.set_read_timeout(Some(READ_TIMEOUT))?;
+            let mut line = String::new();
+            BufReader::new(&stream).read_line(&mut line)?;
+            let reply = match Command::parse(&line).and_then(&mut *handle) {
+                Ok(message) => format!("ok {}\n", message),
+                Err(e) => format!("error {}\n", e),
+            };
+            (&stream).write_all(reply.as_bytes())?;
+            Ok(())
+        }
+    }
+
+    impl Drop for Server {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_file(&self.path);
+        }
+    }
+
+    /// Send a command to the running timer and return its reply
+    pub fn send(command: Command) -> Result<String> {
+        send_to(&path()?, command)
+    }
+
+    pub(super) fn send_to(path: &Path, command: Command) -> Result<String> {
+        let stream = match UnixStream::connect(path) {
+            Ok(stream) => stream,
+            Err(_) => bail!("No timer is running. Start one with `sandoro`."),
+        };
+        stream.set_read_timeout(Some(Duration::from_secs(2)))?;
+        (&stream).write_all(format!("{}\n", command.to_line()).as_bytes())?;
+        let mut reply = String::new();
+        BufReader::new(&stream).read_line(&mut reply)?;
+        let reply = reply.trim_end();
+        match reply.split_once(' ') {
+            Some(("ok", message)) => Ok(message.to_string()),
+            Some(("error", message)) => bail!("{}", message),
+            _ => bail!("Unexpected reply from the timer: '{}'", reply),
+        }
+    }
+}
+
+/// Without Unix sockets there's nothing to listen on
+#[cfg(not(unix))]
+pub struct Server;
+
+#[cfg(not(unix))]
+impl Server {
+    pub fn bind() -> Result<Option<Self>> {
+        Ok(None)
+    }
+
+    pub fn poll(&self, _handle: impl FnMut(Command) -> Result<String>) {}
+}
+
+#[cfg(not(unix))]
+pub fn send(_command: Command) -> Result<String> {
+    bail!("Controlling a running timer isn't supported on this platform yet.")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_commands() {
+        assert_eq!(Command::parse("snooze\n").unwrap(), Command::Snooze(None));
+        assert_eq!(
+            Command::parse("snooze 5").unwrap(),
+            Command::Snooze(Some(5))
+        );
+        assert!(Command::parse("snooze 0").is_err());
+        assert!(Command::parse("snooze five").is_err());
+        assert!(Command::parse("snooze 5 6").is_err());
+        assert!(Command::parse("quit").is_err());
+        assert!(Command::parse("lock now").is_err());
+        assert!(Command::parse("unlock").is_err());
+        assert!(Command::parse("break").is_err());
+        assert!(Command::parse("work later").is_err());
+        assert!(Command::parse("skip 2").is_err());
+        for command in [
+            Command::Snooze(None),
+            Command::Snooze(Some(10)),
+            Command::Lock,
+            Command::BreakNow,
+            Command::WorkNow,
+            Command::Start,
+            Command::Pause,
+            Command::Skip,
+        ] {
+            assert_eq!(Command::parse(&command.to_line()).unwrap(), command);
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_round_trip_over_the_socket() {
+        let path =
+            std::env::temp_dir().join(format!("sandoro-control-{}.sock", std::process::id()));
+        let server = Server::bind_at(path.clone()).unwrap().unwrap();
+        // A second timer leaves the socket to the first
+        assert!(Server::bind_at(path.clone()).unwrap().is_none());
+
+        let client = {
+            let path = path.clone();
+            std::thread::spawn(move || unix::send_to(&path, Command::Snooze(Some(5))))
+        };
+        let mut received = None;
+        while !client.is_finished() {
+            server.poll(|command| {
+                received = Some(command);
+                Ok("Break extended by 5m".to_string())
+            });
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+        assert_eq!(client.join().unwrap().unwrap(), "Break extended by 5m");
+        assert_eq!(received, Some(Command::Snooze(Some(5))));
+
+        drop(server);
+        assert!(!path.exists());
+        assert!(unix::send_to(&path, Command::Snooze(None))
+            .unwrap_err()
+            .to_string()
+            .contains("No timer is running"));
+    }
+}
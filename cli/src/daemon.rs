@@ -0,0 +1,543 @@
+//! Headless timer
+//!
+//! `sandoro daemon` runs the timer without a terminal and records sessions
+//! into the database like the TUI does. Other terminals, scripts, status
+//! bars and editors drive it through `~/.sandoro/daemon.sock`: one JSON
+//! request per connection and one JSON reply, each on a single line.
+//!
+//! ```text
+//! → {"command":"start"}
+//! ← {"ok":true,"message":"Work started, 25:00 left","status":{...}}
+//! ```
+//!
+//! Commands are `start`, `pause`, `skip`, `status` and `stop`; `status` is
+//! the same snapshot the TUI publishes to status.json. `sandoro ctl` sends
+//! them from the command line.
+//!
+//! The daemon also answers on the TUI's control socket, so the remote
+//! page's start, pause and skip reach it too. Commands it has no
+//! equivalent for (snooze, break now, work now, lock) get an error saying
+//! so rather than being ignored.
+
+use anyhow::{bail, Result};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::clock;
+use crate::config::{Config, FocusMode};
+use crate::control;
+use crate::db::{Database, SessionMode, SessionType, Tag};
+use crate::status::{self, LiveStatus};
+use crate::timer::{Timer, TimerState};
+
+/// Something for the daemon to do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "lowercase")]
+pub enum Request {
+    /// Start or resume the current phase
+    Start,
+    Pause,
+    /// Move on to the next phase; a work block skipped isn't counted
+    Skip,
+    Status,
+    /// Close the session under way and exit
+    Stop,
+}
+
+/// The daemon's answer, with its status after the request
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Reply {
+    pub ok: bool,
+    pub message: String,
+    pub status: Option<LiveStatus>,
+}
+
+pub fn path() -> Result<PathBuf> {
+    Ok(Config::config_dir()?.join("daemon.sock"))
+}
+
+/// A timer recording into `db`
+pub struct Daemon {
+    config: Config,
+    db: Database,
+    timer: Timer,
+    tag: Option<Tag>,
+    /// The session being recorded
+    session_id: Option<i64>,
+    /// Work sessions completed today, read again whenever a session starts
+    /// or ends rather than on every status
+    completed_today: u32,
+    /// Push completed work to the cloud when logged in
+    pub sync: bool,
+    pub should_stop: bool,
+}
+
+impl Daemon {
+    pub fn new(config: Config, db: Database, tag: Option<Tag>) -> Self {
+        let mut timer = Timer::with_sessions(
+            config.timer.work_duration,
+            config.timer.short_break,
+            config.timer.long_break,
+            config.timer.sessions_until_long,
+        );
+        timer.set_flowtime(config.focus.mode == FocusMode::Flowtime);
+        timer.set_named_timer(config.focus.timer_minutes());
+        let mut daemon = Self {
+            config,
+            db,
+            timer,
+            tag,
+            session_id: None,
+            completed_today: 0,
+            sync: true,
+            should_stop: false,
+        };
+        daemon.count_today();
+        daemon
+    }
+
+    fn count_today(&mut self) {
+        self.completed_today = self
+            .db
+            .get_today_stats()
+            .map_or(0, |s| s.sessions_completed) as u32;
+    }
+
+    /// Carry out a request from the control socket the TUI listens on
+    pub fn handle_control(&mut self, command: control::Command) -> Result<String> {
+        match command {
+            control::Command::Start => self.start(),
+            control::Command::Pause => self.pause(),
+            control::Command::Skip => self.skip(),
+            other => bail!(
+                "The daemon can't '{}'; it only starts, pauses and skips. Run `sandoro` for that.",
+                other.to_line()
+            ),
+        }
+    }
+
+    pub fn handle(&mut self, request: Request) -> Reply {
+        let result = match request {
+            Request::Start => self.start(),
+            Request::Pause => self.pause(),
+            Request::Skip => self.skip(),
+            Request::Status => Ok(String::new()),
+            Request::Stop => {
+                self.stop();
+                Ok("Stopped".to_string())
+            }
+        };
+        let (ok, message) = match result {
+            Ok(message) => (true, message),
+            Err(e) => (false, e.to_string()),
+        };
+        Reply {
+            ok,
+            message,
+            status: Some(self.status()),
+        }
+    }
+
+    fn start(&mut self) -> Result<String> {
+        if !self.timer.is_paused {
+            bail!("Already running.");
+        }
+        self.timer.toggle_pause();
+        match self.session_id {
+            Some(id) => {
+                let _ = self.db.unpause_session(id, clock::now_utc());
+            }
+            None => self.start_recording(),
+        }
+        let time = if self.timer.is_counting_up() {
+            "counting up".to_string()
+        } else {
+            format!("{} left", self.timer.formatted_display_time())
+        };
+        Ok(format!("{} started, {}", self.state_name(), time))
+    }
+
+    fn pause(&mut self) -> Result<String> {
+        if self.timer.is_paused {
+            bail!("Already paused.");
+        }
+        self.timer.toggle_pause();
+        if let Some(id) = self.session_id {
+            let _ = self.db.pause_session(id, clock::now_utc());
+        }
+        Ok(format!("Paused, {}", self.timer.formatted_display_time()))
+    }
+
+    fn skip(&mut self) -> Result<String> {
+        let state = self.timer.state;
+        // Flowtime work and named timers end rather than being skipped, and count
+        let counts = state == TimerState::Timer || self.timer.is_counting_up();
+        let seconds = self.timer.seconds_done();
+        self.timer.skip();
+        self.finish_recording(state, counts, seconds);
+        Ok(format!("Skipped to {}", self.state_name().to_lowercase()))
+    }
+
+    fn stop(&mut self) {
+        if let Some(id) = self.session_id.take() {
+            let _ =
+                self.db
+                    .interrupt_session(id, clock::now_utc(), self.timer.seconds_done() as i32);
+        }
+        self.should_stop = true;
+    }
+
+    /// Move the timer on, recording phases that ran out
+    pub fn tick(&mut self) {
+        let state = self.timer.state;
+        let was_running = !self.timer.is_paused;
+        let seconds = self.timer.phase_seconds();
+        self.timer.tick();
+        // A named timer starts over in the same state
+        let ended = self.timer.state != state
+            || (state == TimerState::Timer && was_running && self.timer.is_paused);
+        if ended {
+            self.finish_recording(state, true, seconds);
+            if self.config.timer.auto_start && state != TimerState::Timer {
+                self.timer.toggle_pause();
+                self.start_recording();
+            }
+        }
+    }
+
+    pub fn status(&self) -> LiveStatus {
+        let is_work = self.timer.state.is_focus();
+        LiveStatus {
+            pid: std::process::id(),
+            state: self.session_type().as_str().to_string(),
+            paused: self.timer.is_paused,
+            remaining_seconds: self.timer.remaining_seconds,
+            elapsed_seconds: self.timer.elapsed_seconds,
+            session_active: is_work && self.session_id.is_some(),
+            session_number: self.completed_today + u32::from(is_work),
+            cycle_position: self.timer.cycle_position(),
+            cycle_length: self.timer.cycle_length(),
+            tag: self.tag.as_ref().map(|t| t.name.clone()),
+            session_id: self.session_id,
+            updated_at: clock::now_utc(),
+        }
+    }
+
+    fn session_type(&self) -> SessionType {
+        match self.timer.state {
+            TimerState::Work => SessionType::Work,
+            TimerState::ShortBreak => SessionType::ShortBreak,
+            TimerState::LongBreak => SessionType::LongBreak,
+            TimerState::Timer => SessionType::Timer,
+        }
+    }
+
+    fn state_name(&self) -> &'static str {
+        match self.timer.state {
+            TimerState::Work => "Work",
+            TimerState::ShortBreak => "Short break",
+            TimerState::LongBreak => "Long break",
+            TimerState::Timer => "Timer",
+        }
+    }
+
+    fn start_recording(&mut self) {
+        // Picks up a new day too
+        self.count_today();
+        let counting_up = self.timer.is_counting_up();
+        let planned = (!counting_up).then(|| self.timer.phase_seconds() as i32);
+        let mode = if counting_up && self.timer.state == TimerState::Work {
+            SessionMode::Flowtime
+        } else {
+            SessionMode::Classic
+        };
+        self.session_id = self
+            .db
+            .start_session_in_mode(
+                self.session_type(),
+                self.tag.as_ref().map(|t| t.id),
+                planned,
+                mode,
+            )
+            .ok();
+    }
+
+    /// Close the phase's session: completed with `seconds`, or skipped
+    fn finish_recording(&mut self, state: TimerState, completed: bool, seconds: u32) {
+        let Some(id) = self.session_id.take() else {
+            return;
+        };
+        if completed {
+            let _ = self.db.complete_session(id, seconds as i32);
+            self.count_today();
+        } else {
+            let _ = self.db.log_session_event(id, "skipped", None);
+            let _ = self
+                .db
+                .interrupt_session(id, clock::now_utc(), seconds as i32);
+        }
+        if self.sync && completed && state.is_focus() {
+            let _ = crate::sync::try_sync_session(self.db.connection(), id);
+        }
+    }
+}
+
+#[cfg(unix)]
+pub use unix::{run, send};
+
+#[cfg(unix)]
+mod unix {
+    use super::*;
+    use std::io::{BufRead, BufReader, ErrorKind, Write};
+    use std::os::unix::net::{UnixListener, UnixStream};
+    use std::path::Path;
+    use std::time::{Duration, Instant};
+
+    /// How often the timer ticks and the socket is checked
+    const TICK: Duration = Duration::from_millis(100);
+    /// How long a client may take to send its request
+    const READ_TIMEOUT: Duration = Duration::from_millis(200);
+
+    /// Run until a `stop` request
+    pub fn run(mut daemon: Daemon) -> Result<()> {
+        let path = path()?;
+        let listener = bind(&path)?;
+        // A TUI started later finds it taken and leaves it to the daemon
+        let control = control::Server::bind().ok().flatten();
+        let result = serve(&mut daemon, &listener, control.as_ref());
+        let _ = std::fs::remove_file(&path);
+        let _ = status::clear();
+        result
+    }
+
+    fn bind(path: &Path) -> Result<UnixListener> {
+        if path.exists() {
+            if UnixStream::connect(path).is_ok() {
+                bail!("A daemon is already running. Stop it with `sandoro ctl stop`.");
+            }
+            // Left behind by a daemon that didn't exit cleanly
+            std::fs::remove_file(path)?;
+        }
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let listener = UnixListener::bind(path)?;
+        listener.set_nonblocking(true)?;
+        Ok(listener)
+    }
+
+    fn serve(
+        daemon: &mut Daemon,
+        listener: &UnixListener,
+        control: Option<&control::Server>,
+    ) -> Result<()> {
+        let mut last_status: Option<(LiveStatus, Instant)> = None;
+        while !daemon.should_stop {
+            loop {
+                match listener.accept() {
+                    Ok((stream, _)) => {
+                        let _ = answer(stream, daemon);
+                    }
+                    Err(e) if e.kind() == ErrorKind::WouldBlock => break,
+                    Err(_) => break,
+                }
+            }
+            if let Some(control) = control {
+                control.poll(|command| daemon.handle_control(command));
+            }
+            daemon.tick();
+
+            // Published like the TUI's, for status lines and hooks
+            let current = daemon.status();
+            let due = last_status.as_ref().is_none_or(|(last, at)| {
+                last.differs_from(&current)
+                    || at.elapsed() >= Duration::from_secs(status::REFRESH_SECS as u64)
+            });
+            if due && !daemon.should_stop && status::write(&current).is_ok() {
+                last_status = Some((current, Instant::now()));
+            }
+            std::thread::sleep(TICK);
+        }
+        Ok(())
+    }
+
+    fn answer(stream: UnixStream, daemon: &mut Daemon) -> Result<()> {
+        stream.set_nonblocking(false)?;
+        stream.set_read_timeout(Some(READ_TIMEOUT))?;
+        let mut line = String::new();
+        BufReader::new(&stream).read_line(&mut line)?;
+        let reply = match serde_json::from_str::<Request>(&line) {
+            Ok(request) => daemon.handle(request),
+            Err(e) => Reply {
+                ok: false,
+                message: format!("Bad request: {}", e),
+                status: None,
+            },
+        };
+        (&stream).write_all(format!("{}\n", serde_json::to_string(&reply)?).as_bytes())?;
+        Ok(())
+    }
+
+    /// Send a request to the running daemon and return its reply
+    pub fn send(request: Request) -> Result<Reply> {
+        let stream = match UnixStream::connect(path()?) {
+            Ok(stream) => stream,
+            Err(_) => bail!("No daemon is running. Start one with `sandoro daemon`."),
+        };
+        stream.set_read_timeout(Some(Duration::from_secs(2)))?;
+        let line = format!("{}\n", serde_json::to_string(&request)?);
+        (&stream).write_all(line.as_bytes())?;
+        let mut reply = String::new();
+        BufReader::new(&stream).read_line(&mut reply)?;
+        Ok(serde_json::from_str(&reply)?)
+    }
+}
+
+/// Without Unix sockets there's nothing to listen on
+#[cfg(not(unix))]
+pub fn run(_daemon: Daemon) -> Result<()> {
+    bail!("The daemon isn't supported on this platform yet.")
+}
+
+#[cfg(not(unix))]
+pub fn send(_request: Request) -> Result<Reply> {
+    bail!("The daemon isn't supported on this platform yet.")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::Clock;
+    use std::time::Duration;
+
+    fn daemon(config: Config) -> (Daemon, Clock) {
+        let db = Database::open_in_memory().unwrap();
+        let tag = db.create_tag("writing", None).unwrap();
+        let tag = db.get_tag(tag).unwrap();
+        let mut daemon = Daemon::new(config, db, tag);
+        daemon.sync = false;
+        let clock = Clock::frozen();
+        daemon.timer.set_clock(clock.clone());
+        (daemon, clock)
+    }
+
+    #[test]
+    fn test_requests_as_json() {
+        assert_eq!(
+            serde_json::from_str::<Request>(r#"{"command":"skip"}"#).unwrap(),
+            Request::Skip
+        );
+        assert_eq!(
+            serde_json::to_string(&Request::Status).unwrap(),
+            r#"{"command":"status"}"#
+        );
+        assert!(serde_json::from_str::<Request>(r#"{"command":"reset"}"#).is_err());
+    }
+
+    #[test]
+    fn test_records_a_work_block() {
+        let (mut d, clock) = daemon(Config::default());
+        let reply = d.handle(Request::Start);
+        assert!(reply.ok);
+        assert_eq!(reply.message, "Work started, 25:00 left");
+        let status = reply.status.unwrap();
+        assert!(status.session_active);
+        assert_eq!(status.tag.as_deref(), Some("writing"));
+        assert!(!d.handle(Request::Start).ok);
+
+        clock.advance(Duration::from_secs(25 * 60));
+        d.tick();
+        assert_eq!(d.timer.state, TimerState::ShortBreak);
+        assert!(d.timer.is_paused);
+        let today = d.db.get_today_stats().unwrap();
+        assert_eq!(today.sessions_completed, 1);
+        assert_eq!(today.total_work_seconds, 25 * 60);
+        assert_eq!(d.status().session_id, None);
+    }
+
+    #[test]
+    fn test_pauses_are_recorded_on_the_session() {
+        let (mut d, clock) = daemon(Config::default());
+        d.handle(Request::Start);
+        let id = d.session_id.unwrap();
+        clock.advance(Duration::from_secs(5 * 60));
+        d.tick();
+        assert!(d.handle(Request::Pause).ok);
+        assert!(d
+            .db
+            .get_session_detail(id)
+            .unwrap()
+            .unwrap()
+            .paused_at
+            .is_some());
+        assert!(d.handle(Request::Start).ok);
+        assert_eq!(d.session_id, Some(id));
+
+        clock.advance(Duration::from_secs(20 * 60));
+        d.tick();
+        let detail = d.db.get_session_detail(id).unwrap().unwrap();
+        assert_eq!(detail.paused_at, None);
+        assert_eq!(detail.session.duration_seconds, Some(25 * 60));
+        let events: Vec<String> =
+            d.db.get_session_events(id)
+                .unwrap()
+                .into_iter()
+                .map(|e| e.event)
+                .collect();
+        assert_eq!(events, ["started", "paused", "unpaused", "completed"]);
+    }
+
+    #[test]
+    fn test_control_commands_it_has_and_refuses_the_rest() {
+        let (mut d, clock) = daemon(Config::default());
+        assert_eq!(
+            d.handle_control(control::Command::Start).unwrap(),
+            "Work started, 25:00 left"
+        );
+        for command in [
+            control::Command::Snooze(None),
+            control::Command::BreakNow,
+            control::Command::WorkNow,
+            control::Command::Lock,
+        ] {
+            let err = d.handle_control(command).unwrap_err().to_string();
+            assert!(err.contains("only starts, pauses and skips"), "{}", err);
+        }
+        assert!(d.handle_control(control::Command::Pause).is_ok());
+        assert!(d.handle_control(control::Command::Start).is_ok());
+
+        // The count in the status follows finished sessions
+        assert_eq!(d.status().session_number, 1);
+        clock.advance(Duration::from_secs(25 * 60));
+        d.tick();
+        assert_eq!(d.status().session_number, 1);
+        d.handle(Request::Skip);
+        assert_eq!(d.status().session_number, 2);
+    }
+
+    #[test]
+    fn test_skip_and_stop_leave_nothing_open() {
+        let mut config = Config::default();
+        config.timer.auto_start = true;
+        let (mut d, clock) = daemon(config);
+        d.handle(Request::Start);
+        clock.advance(Duration::from_secs(60));
+        d.tick();
+        let reply = d.handle(Request::Skip);
+        assert_eq!(reply.message, "Skipped to short break");
+        assert_eq!(d.db.get_today_stats().unwrap().sessions_completed, 0);
+
+        // The break starts on its own with auto_start
+        assert!(d.handle(Request::Start).ok);
+        clock.advance(Duration::from_secs(5 * 60));
+        d.tick();
+        assert_eq!(d.timer.state, TimerState::Work);
+        assert!(!d.timer.is_paused);
+        assert!(d.status().session_active);
+
+        let reply = d.handle(Request::Stop);
+        assert!(reply.ok);
+        assert!(d.should_stop);
+        assert!(d.db.get_open_sessions().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,189 @@
+//! Which calendar day a session counts towards
+//!
+//! Each session stores its UTC start, the UTC offset it started at and the
+//! day it belongs to. The day is settled when the session starts, so flying
+//! somewhere or a DST change later doesn't move past work between days.
+//! `[days] policy` in config.toml picks how that day is chosen when the
+//! offset changes.
+
+use chrono::{DateTime, Duration, Local, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// How a session's day is chosen
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum DayPolicy {
+    /// The local date where the session started
+    #[default]
+    Local,
+    /// A day keeps the offset it started with until it's over there, then
+    /// the next day starts wherever you are; days never go backwards
+    Sticky,
+    /// UTC dates, as sandoro counted before offsets were recorded
+    Utc,
+}
+
+impl DayPolicy {
+    fn from_u8(n: u8) -> Self {
+        match n {
+            1 => DayPolicy::Sticky,
+            2 => DayPolicy::Utc,
+            _ => DayPolicy::Local,
+        }
+    }
+
+    fn as_u8(self) -> u8 {
+        match self {
+            DayPolicy::Local => 0,
+            DayPolicy::Sticky => 1,
+            DayPolicy::Utc => 2,
+        }
+    }
+}
+
+static POLICY: AtomicU8 = AtomicU8::new(0);
+
+/// Set the policy from config, once at startup
+pub fn init(policy: DayPolicy) {
+    POLICY.store(policy.as_u8(), Ordering::Relaxed);
+}
+
+pub fn policy() -> DayPolicy {
+    DayPolicy::from_u8(POLICY.load(Ordering::Relaxed))
+}
+
+/// The machine's current UTC offset, in minutes
+pub fn local_offset_minutes() -> i32 {
+    Local::now().offset().local_minus_utc() / 60
+}
+
+/// Date of `at` seen from a UTC offset
+pub fn date_at(at: DateTime<Utc>, offset_minutes: i32) -> NaiveDate {
+    (at + Duration::minutes(i64::from(offset_minutes))).date_naive()
+}
+
+/// Day a session starting `at` (local offset `offset_minutes`) counts
+/// towards. `last_day` is the latest day with sessions and the offset its
+/// first session started at; only `Sticky` looks at it.
+pub fn session_day(
+    policy: DayPolicy,
+    at: DateTime<Utc>,
+    offset_minutes: i32,
+    last_day: Option<(NaiveDate, i32)>,
+) -> NaiveDate {
+    match policy {
+        DayPolicy::Utc => at.date_naive(),
+        DayPolicy::Local => date_at(at, offset_minutes),
+        DayPolicy::Sticky => match last_day {
+            Some((day, anchor)) if date_at(at, anchor) == day => day,
+            Some((day, _)) => date_at(at, offset_minutes).max(day),
+            None => date_at(at, offset_minutes),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    const TOKYO: i32 = 9 * 60;
+    const SAN_FRANCISCO: i32 = -8 * 60;
+
+    fn utc(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, h, min, 0).unwrap()
+    }
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_local_uses_the_offset_of_each_session() {
+        // 23:30 in Tokyo on the 1st is still the 1st there, the 1st in UTC too
+        let late = utc(2026, 3, 1, 14, 30);
+        assert_eq!(
+            session_day(DayPolicy::Local, late, TOKYO, None),
+            date(2026, 3, 1)
+        );
+        // 08:00 in Tokyo on the 2nd is the 1st in UTC
+        let early = utc(2026, 3, 1, 23, 0);
+        assert_eq!(
+            session_day(DayPolicy::Local, early, TOKYO, None),
+            date(2026, 3, 2)
+        );
+        assert_eq!(
+            session_day(DayPolicy::Utc, early, TOKYO, None),
+            date(2026, 3, 1)
+        );
+    }
+
+    #[test]
+    fn test_dst_change_keeps_the_calendar_day() {
+        // Europe/Berlin springs forward at 01:00 UTC on 2026-03-29
+        let before = utc(2026, 3, 28, 22, 30); // 23:30 CET
+        let after = utc(2026, 3, 29, 21, 30); // 23:30 CEST
+        assert_eq!(
+            session_day(DayPolicy::Local, before, 60, None),
+            date(2026, 3, 28)
+        );
+        assert_eq!(
+            session_day(DayPolicy::Local, after, 120, None),
+            date(2026, 3, 29)
+        );
+    }
+
+    #[test]
+    fn test_sticky_finishes_the_day_where_it_started() {
+        // Work in Tokyo after midnight on the 2nd, then fly to San Francisco
+        // where it's still the afternoon of the 1st
+        let tokyo = utc(2026, 3, 1, 15, 30);
+        let day = session_day(DayPolicy::Sticky, tokyo, TOKYO, None);
+        assert_eq!(day, date(2026, 3, 2));
+
+        let landed = utc(2026, 3, 2, 0, 0); // 16:00 on the 1st in SF
+        assert_eq!(
+            session_day(DayPolicy::Local, landed, SAN_FRANCISCO, Some((day, TOKYO))),
+            date(2026, 3, 1)
+        );
+        // The 2nd carries on rather than going back to the 1st
+        assert_eq!(
+            session_day(DayPolicy::Sticky, landed, SAN_FRANCISCO, Some((day, TOKYO))),
+            date(2026, 3, 2)
+        );
+        // Once the 2nd is over in Tokyo the next day starts in SF, and it
+        // can't be earlier than the day before
+        let next = utc(2026, 3, 2, 16, 0); // 08:00 on the 2nd in SF
+        assert_eq!(
+            session_day(DayPolicy::Sticky, next, SAN_FRANCISCO, Some((day, TOKYO))),
+            date(2026, 3, 2)
+        );
+        let later = utc(2026, 3, 3, 16, 0); // 08:00 on the 3rd in SF
+        assert_eq!(
+            session_day(DayPolicy::Sticky, later, SAN_FRANCISCO, Some((day, TOKYO))),
+            date(2026, 3, 3)
+        );
+    }
+
+    #[test]
+    fn test_sticky_eastward_moves_on_when_the_old_day_ends() {
+        // Start the 1st in San Francisco, land in Tokyo on the 2nd (local)
+        let sf = utc(2026, 3, 1, 17, 0); // 09:00 on the 1st in SF
+        let day = session_day(DayPolicy::Sticky, sf, SAN_FRANCISCO, None);
+        assert_eq!(day, date(2026, 3, 1));
+
+        // 10:00 on the 2nd in Tokyo, still the evening of the 1st in SF
+        let tokyo = utc(2026, 3, 2, 1, 0);
+        assert_eq!(
+            session_day(DayPolicy::Sticky, tokyo, TOKYO, Some((day, SAN_FRANCISCO))),
+            date(2026, 3, 1)
+        );
+        // After midnight in SF the Tokyo date takes over
+        let after = utc(2026, 3, 2, 9, 0);
+        assert_eq!(
+            session_day(DayPolicy::Sticky, after, TOKYO, Some((day, SAN_FRANCISCO))),
+            date(2026, 3, 2)
+        );
+    }
+}
@@ -5,12 +5,16 @@
 // TODO: These will be used when session recording is implemented in the app
 #![allow(dead_code)]
 
-use anyhow::Result;
-use chrono::{DateTime, Utc};
-use rusqlite::{params, Connection};
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate, Utc, Weekday};
+use rusqlite::{params, Connection, ErrorCode, OpenFlags, OptionalExtension, Params};
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
+use std::time::Duration;
 
+use crate::clock;
 use crate::config::Config;
+use crate::days::{self, DayPolicy};
 
 /// Session types
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -18,6 +22,8 @@ pub enum SessionType {
     Work,
     ShortBreak,
     LongBreak,
+    /// A named timer from timer focus mode; counts as focused time
+    Timer,
 }
 
 impl SessionType {
@@ -26,6 +32,25 @@ impl SessionType {
             SessionType::Work => "work",
             SessionType::ShortBreak => "short_break",
             SessionType::LongBreak => "long_break",
+            SessionType::Timer => "timer",
+        }
+    }
+}
+
+/// How a work session was timed: counting down a set length, or counting up
+/// until it was ended (flowtime). Breaks are always classic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SessionMode {
+    #[default]
+    Classic,
+    Flowtime,
+}
+
+impl SessionMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SessionMode::Classic => "classic",
+            SessionMode::Flowtime => "flowtime",
         }
     }
 }
@@ -39,6 +64,86 @@ pub struct Session {
     pub duration_seconds: Option<i32>,
     pub session_type: String,
     pub completed: bool,
+    /// Free-text note, e.g. what got done
+    pub notes: Option<String>,
+}
+
+/// The timer as it was when quit mid-session, offered on the next launch
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SavedSession {
+    /// "work", "short_break" or "long_break"
+    pub state: String,
+    pub remaining_seconds: u32,
+    pub elapsed_seconds: u32,
+    pub session_count: u32,
+    pub tag_id: Option<i64>,
+    /// The session being recorded, closed as interrupted on quit
+    pub session_id: Option<i64>,
+    pub saved_at: DateTime<Utc>,
+}
+
+/// Columns of the CSV export, in order
+pub const CSV_COLUMNS: [&str; 10] = [
+    "id",
+    "startedAt",
+    "endedAt",
+    "durationSeconds",
+    "type",
+    "completed",
+    "tag",
+    "note",
+    "device",
+    "tagColor",
+];
+
+/// How the CSV export is written
+#[derive(Debug, Clone, Copy)]
+pub struct CsvOptions {
+    pub delimiter: u8,
+    /// Start with a UTF-8 byte order mark, so Excel reads non-ASCII text
+    pub bom: bool,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: b',',
+            bom: false,
+        }
+    }
+}
+
+/// Which sessions an export includes; all of them by default
+#[derive(Debug, Clone, Default)]
+pub struct ExportFilter {
+    /// First local day included
+    pub from: Option<NaiveDate>,
+    /// Last local day included
+    pub to: Option<NaiveDate>,
+    /// Only sessions with this tag
+    pub tag: Option<String>,
+    /// Only sessions with a note containing this (any case); empty for any
+    /// note at all
+    pub notes: Option<String>,
+}
+
+impl ExportFilter {
+    /// `WHERE` clause on `sessions s` joined to `tags t`, taking ?1 to ?4
+    const SQL: &'static str = "(?1 IS NULL OR COALESCE(s.day, date(s.started_at)) >= ?1)
+               AND (?2 IS NULL OR COALESCE(s.day, date(s.started_at)) <= ?2)
+               AND (?3 IS NULL OR t.name = ?3)
+               AND (?4 IS NULL OR (TRIM(COALESCE(s.notes, '')) != ''
+                                   AND instr(lower(s.notes), lower(?4)) > 0))";
+
+    fn params(&self) -> (Option<String>, Option<String>, Option<&str>, Option<&str>) {
+        let day = |d: &NaiveDate| d.format("%Y-%m-%d").to_string();
+        (
+            self.from.as_ref().map(day),
+            self.to.as_ref().map(day),
+            self.tag.as_deref(),
+            self.notes.as_deref(),
+        )
+    }
 }
 
 /// Daily statistics
@@ -50,6 +155,30 @@ pub struct DailyStats {
     pub longest_streak: i32,
 }
 
+/// A completed work session and the day it counts towards
+#[derive(Debug, Clone)]
+pub struct DaySession {
+    /// YYYY-MM-DD
+    pub date: String,
+    pub session: Session,
+    pub tag: Option<Tag>,
+}
+
+/// A finished work session brought in from another tracker
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedSession {
+    pub started_at: DateTime<Utc>,
+    pub ended_at: DateTime<Utc>,
+    /// UTC offset where it was recorded, which sets its day
+    pub utc_offset_minutes: i32,
+    /// Created if missing
+    pub tag: Option<String>,
+    pub notes: Option<String>,
+    /// False for a session that was stopped early, e.g. from sandoro's own
+    /// export
+    pub completed: bool,
+}
+
 /// Streak information
 #[derive(Debug, Clone)]
 pub struct StreakInfo {
@@ -58,13 +187,539 @@ pub struct StreakInfo {
 }
 
 /// A tag for categorizing sessions
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Tag {
     pub id: i64,
     pub name: String,
     pub color: Option<String>,
 }
 
+/// Settings a tag brings with it when selected; None keeps the global
+/// setting
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TagDefaults {
+    pub work_minutes: Option<u32>,
+    pub break_minutes: Option<u32>,
+    /// Icon name, as in `[appearance] icon`
+    pub icon: Option<String>,
+}
+
+/// Column of the "today" board a task sits in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskStatus {
+    Planned,
+    Doing,
+    Done,
+}
+
+impl TaskStatus {
+    pub const ALL: [TaskStatus; 3] = [TaskStatus::Planned, TaskStatus::Doing, TaskStatus::Done];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TaskStatus::Planned => "planned",
+            TaskStatus::Doing => "doing",
+            TaskStatus::Done => "done",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Self {
+        match s {
+            "doing" => TaskStatus::Doing,
+            "done" => TaskStatus::Done,
+            _ => TaskStatus::Planned,
+        }
+    }
+}
+
+/// A task on the "today" board
+#[derive(Debug, Clone, PartialEq)]
+pub struct Task {
+    pub id: i64,
+    pub title: String,
+    pub status: TaskStatus,
+    /// Work sessions the task should take, if estimated
+    pub estimate: Option<i32>,
+    /// Completed work sessions bound to the task
+    pub sessions_done: i32,
+}
+
+/// An entry in a session's event trail (started, completed, skipped, synced, ...)
+#[derive(Debug, Clone)]
+pub struct SessionEvent {
+    pub id: i64,
+    pub session_id: i64,
+    pub event: String,
+    pub detail: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Everything known about a single session row
+#[derive(Debug, Clone)]
+pub struct SessionDetail {
+    pub session: Session,
+    pub tag: Option<Tag>,
+    pub planned_seconds: Option<i32>,
+    pub cloud_id: Option<String>,
+    pub uuid: Option<String>,
+    /// Seconds spent in pauses that have ended
+    pub paused_seconds: i32,
+    /// When the pause the session is in began, while it lasts
+    pub paused_at: Option<DateTime<Utc>>,
+}
+
+impl SessionDetail {
+    /// Seconds paused up to `at`, counting a pause still going
+    pub fn paused_seconds_at(&self, at: DateTime<Utc>) -> i32 {
+        let open = self
+            .paused_at
+            .map_or(0, |since| (at - since).num_seconds().max(0) as i32);
+        self.paused_seconds + open
+    }
+}
+
+/// An interruption noted during a work session
+#[derive(Debug, Clone)]
+pub struct Interruption {
+    pub session_id: i64,
+    pub category: Option<char>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// XP awarded for a session, when the XP layer is on
+#[derive(Debug, Clone)]
+pub struct XpEvent {
+    pub session_id: Option<i64>,
+    pub reason: String,
+    pub xp: i64,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A session matched by `search_sessions`
+#[derive(Debug, Clone)]
+pub struct SearchHit {
+    pub session: Session,
+    pub tag: Option<Tag>,
+}
+
+/// Turn free text into an FTS5 query: every word must match, as a prefix.
+/// Words are quoted so FTS syntax in the input is taken literally.
+fn fts_query(input: &str) -> Option<String> {
+    let terms: Vec<String> = input
+        .split_whitespace()
+        .map(|word| word.replace('"', ""))
+        .filter(|word| !word.is_empty())
+        .map(|word| format!("\"{}\"*", word))
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" "))
+}
+
+/// A work session waiting to be sent to an external time tracker
+#[derive(Debug, Clone)]
+pub struct QueuedTimeEntry {
+    pub session_id: i64,
+    pub provider: String,
+    pub attempts: u32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_error: Option<String>,
+    /// The tracker turned it down for good; only `sandoro track flush`
+    /// tries again
+    pub dead: bool,
+}
+
+/// A change to a session waiting to go to the cloud
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncOp {
+    Insert,
+    Update,
+    Delete,
+}
+
+impl SyncOp {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SyncOp::Insert => "insert",
+            SyncOp::Update => "update",
+            SyncOp::Delete => "delete",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Self {
+        match s {
+            "update" => SyncOp::Update,
+            "delete" => SyncOp::Delete,
+            _ => SyncOp::Insert,
+        }
+    }
+}
+
+/// An entry of the sync outbox
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueuedSync {
+    pub session_id: i64,
+    pub op: SyncOp,
+    /// Row in the cloud the change goes to
+    pub cloud_id: String,
+    pub attempts: u32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_error: Option<String>,
+    /// The cloud turned it down for good; only `sandoro sync` tries again
+    pub dead: bool,
+}
+
+/// A table, view, index or trigger as stored in `sqlite_master`
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaObject {
+    /// "table", "view", "index" or "trigger"
+    pub kind: String,
+    pub name: String,
+    /// Rows in a table; None for everything else
+    pub rows: Option<i64>,
+    pub sql: String,
+}
+
+/// A column of a table or view, from `PRAGMA table_info`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnInfo {
+    pub name: String,
+    /// Declared type, empty when none (views, FTS tables)
+    pub sql_type: String,
+    pub not_null: bool,
+    pub default: Option<String>,
+    pub primary_key: bool,
+}
+
+/// Compatibility level of schema `version`; 0 before the first level
+pub fn compat_level(version: i32) -> i32 {
+    COMPAT_LEVELS
+        .iter()
+        .rev()
+        .find(|(since, _)| *since <= version)
+        .map(|(_, level)| *level)
+        .unwrap_or(0)
+}
+
+/// Record an event for a session.
+///
+/// Takes a raw connection so that the sync module can log upload attempts too.
+pub fn record_session_event(
+    conn: &Connection,
+    session_id: i64,
+    event: &str,
+    detail: Option<&str>,
+) -> Result<()> {
+    let created_at = Utc::now().to_rfc3339();
+    retry_on_busy(|| {
+        conn.execute(
+            "INSERT INTO session_events (session_id, event, detail, created_at) VALUES (?1, ?2, ?3, ?4)",
+            params![session_id, event, detail, created_at],
+        )
+    })?;
+    Ok(())
+}
+
+/// A new globally unique session id
+pub fn new_uuid() -> String {
+    uuid::Uuid::new_v4().to_string()
+}
+
+/// Queue a change to a session for the cloud. Each session has one entry:
+/// a delete replaces whatever was waiting, anything else keeps it (an
+/// update to a session not uploaded yet goes up with the insert).
+///
+/// Takes a raw connection like `record_session_event`, for the sync module.
+pub fn queue_sync(conn: &Connection, session_id: i64, op: SyncOp, cloud_id: &str) -> Result<()> {
+    retry_on_busy(|| {
+        conn.execute(
+            "INSERT INTO sync_queue (session_id, op, cloud_id, next_attempt_at)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT(session_id) DO UPDATE
+             SET op = excluded.op, attempts = 0, next_attempt_at = excluded.next_attempt_at,
+                 last_error = NULL, dead_at = NULL
+             WHERE excluded.op = 'delete'",
+            params![session_id, op.as_str(), cloud_id, Utc::now().to_rfc3339()],
+        )
+    })?;
+    Ok(())
+}
+
+/// The sync outbox, in the order changes were queued. With `due`, only
+/// entries whose next attempt is at or before that time and that the cloud
+/// hasn't turned down for good.
+pub fn get_sync_queue(conn: &Connection, due: Option<DateTime<Utc>>) -> Result<Vec<QueuedSync>> {
+    let mut stmt = conn.prepare(
+        "SELECT session_id, op, cloud_id, attempts, next_attempt_at, last_error,
+                dead_at IS NOT NULL
+         FROM sync_queue
+         WHERE ?1 IS NULL OR (next_attempt_at <= ?1 AND dead_at IS NULL)
+         ORDER BY rowid",
+    )?;
+    let entries = stmt
+        .query_map(params![due.map(|d| d.to_rfc3339())], |row| {
+            let op: String = row.get(1)?;
+            let next: String = row.get(4)?;
+            Ok(QueuedSync {
+                session_id: row.get(0)?,
+                op: SyncOp::from_str(&op),
+                cloud_id: row.get(2)?,
+                attempts: row.get(3)?,
+                next_attempt_at: parse_timestamp(&next),
+                last_error: row.get(5)?,
+                dead: row.get(6)?,
+            })
+        })?
+        .filter_map(|r| r.ok())
+        .collect();
+    Ok(entries)
+}
+
+/// Record a failed attempt at an outbox entry and when to try again
+pub fn sync_failed(
+    conn: &Connection,
+    session_id: i64,
+    error: &str,
+    next_attempt_at: DateTime<Utc>,
+) -> Result<()> {
+    retry_on_busy(|| {
+        conn.execute(
+            "UPDATE sync_queue
+             SET attempts = attempts + 1, last_error = ?2, next_attempt_at = ?3
+             WHERE session_id = ?1",
+            params![session_id, error, next_attempt_at.to_rfc3339()],
+        )
+    })?;
+    Ok(())
+}
+
+/// Set an outbox entry aside after the cloud turned it down in a way
+/// retrying won't change (a 4xx about the row itself), so it doesn't hold
+/// up the rest. It stays for `sandoro sync` to try again and report.
+pub fn sync_dead(conn: &Connection, session_id: i64, error: &str) -> Result<()> {
+    retry_on_busy(|| {
+        conn.execute(
+            "UPDATE sync_queue
+             SET attempts = attempts + 1, last_error = ?2, dead_at = ?3
+             WHERE session_id = ?1",
+            params![session_id, error, Utc::now().to_rfc3339()],
+        )
+    })?;
+    Ok(())
+}
+
+/// Take a session's entry off the outbox
+pub fn sync_done(conn: &Connection, session_id: i64) -> Result<()> {
+    retry_on_busy(|| {
+        conn.execute(
+            "DELETE FROM sync_queue WHERE session_id = ?1",
+            params![session_id],
+        )
+    })?;
+    Ok(())
+}
+
+/// Schema version stored in `PRAGMA user_version`; bump when init_schema changes
+const SCHEMA_VERSION: i32 = 21;
+/// What each schema version added, oldest first; the last entry is
+/// SCHEMA_VERSION
+pub const MIGRATIONS: [(i32, &str); 21] = [
+    (1, "Versioned schema, WAL journal"),
+    (2, "goal_weeks: weekly goals in effect each week"),
+    (
+        3,
+        "time_entry_queue: sessions waiting for Toggl or Clockify",
+    ),
+    (4, "sessions.notes and the session_search full-text index"),
+    (5, "work_days view skips clock-jump leftovers"),
+    (
+        6,
+        "sessions.utc_offset_minutes and sessions.day; work_days groups by day",
+    ),
+    (7, "xp_events: XP awards"),
+    (8, "plan_items: weekly plans per tag"),
+    (9, "sessions.mode: classic or flowtime"),
+    (10, "sessions.type takes 'timer'; work_days counts timers"),
+    (
+        11,
+        "tags.work_minutes, tags.break_minutes and tags.icon: per-tag defaults",
+    ),
+    (
+        12,
+        "interruptions: interruptions noted during work sessions",
+    ),
+    (13, "sync_queue: session changes waiting to go to the cloud"),
+    (
+        14,
+        "sessions.uuid: globally unique session ids, used in the cloud",
+    ),
+    (
+        15,
+        "sessions.paused_seconds and sessions.paused_at: time spent paused",
+    ),
+    (
+        16,
+        "session_events and sync_metadata created with the schema",
+    ),
+    (
+        17,
+        "sync_queue.dead_at: changes the cloud turned down for good",
+    ),
+    (
+        18,
+        "time_entry_queue.claimed_until and dead_at: one sender per entry, entries turned down",
+    ),
+    (
+        19,
+        "tasks and sessions.task_id: the today board and sessions bound to its tasks",
+    ),
+    (20, "journal: end-of-day notes per date"),
+    (
+        21,
+        "sessions.origin: whether a session was recorded here or pulled from the cloud",
+    ),
+];
+/// Compatibility level for tools reading the database directly, and the
+/// schema version that raised it to that level, oldest first. A new level
+/// only comes with a migration that drops or renames a table or column, or
+/// changes what stored values mean; new tables, columns, views and indexes
+/// keep the level.
+pub const COMPAT_LEVELS: [(i32, i32); 1] = [(1, 1)];
+/// First schema version with the session_search index
+const SEARCH_SCHEMA_VERSION: i32 = 4;
+/// Latest schema version that changed the work_days view (5: skip
+/// clock-jump leftovers, 6: group by each session's own day, 10: count
+/// named timers)
+const WORK_DAYS_VIEW_SCHEMA_VERSION: i32 = 10;
+/// Slack between timestamps before a session is taken for a clock jump
+pub const CLOCK_SLACK_SECONDS: i64 = 5;
+/// How long SQLite itself waits on a lock before reporting SQLITE_BUSY
+const BUSY_TIMEOUT: Duration = Duration::from_millis(1000);
+/// Extra attempts after SQLITE_BUSY, with exponential backoff
+const BUSY_RETRIES: u32 = 5;
+const BUSY_BACKOFF: Duration = Duration::from_millis(50);
+
+/// Run a database operation, retrying with backoff while the database is busy
+/// (e.g. the TUI and a `sandoro stats` process writing at the same time)
+pub fn retry_on_busy<T>(mut op: impl FnMut() -> rusqlite::Result<T>) -> rusqlite::Result<T> {
+    let mut delay = BUSY_BACKOFF;
+    let mut attempt = 0;
+    loop {
+        match op() {
+            Err(e) if is_busy(&e) && attempt < BUSY_RETRIES => {
+                std::thread::sleep(delay);
+                delay *= 2;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+fn is_busy(e: &rusqlite::Error) -> bool {
+    matches!(
+        e.sqlite_error_code(),
+        Some(ErrorCode::DatabaseBusy | ErrorCode::DatabaseLocked)
+    )
+}
+
+/// A `DaySession` from a row of day, the session's columns, the tag's
+/// columns and notes
+fn day_session(row: &rusqlite::Row) -> rusqlite::Result<DaySession> {
+    let tag = match row.get::<_, Option<i64>>(7)? {
+        Some(id) => Some(Tag {
+            id,
+            name: row.get(8)?,
+            color: row.get(9)?,
+        }),
+        None => None,
+    };
+    Ok(DaySession {
+        date: row.get(0)?,
+        session: Session {
+            id: row.get(1)?,
+            started_at: parse_timestamp(&row.get::<_, String>(2)?),
+            ended_at: row
+                .get::<_, Option<String>>(3)?
+                .map(|s| parse_timestamp(&s)),
+            duration_seconds: row.get(4)?,
+            session_type: row.get(5)?,
+            completed: row.get(6)?,
+            notes: row.get(10)?,
+        },
+        tag,
+    })
+}
+
+/// Parse an RFC 3339 timestamp stored in SQLite, falling back to now
+fn parse_timestamp(s: &str) -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339(s)
+        .unwrap_or_else(|_| Utc::now().into())
+        .with_timezone(&Utc)
+}
+
+/// Current and longest streak from the dates (YYYY-MM-DD) with completed
+/// work. A streak still counts on a day with no work yet.
+pub fn streak_from_dates(dates: &HashSet<String>, today: NaiveDate) -> StreakInfo {
+    if dates.is_empty() {
+        return StreakInfo {
+            current: 0,
+            longest: 0,
+        };
+    }
+
+    let today_str = today.format("%Y-%m-%d").to_string();
+
+    // Calculate current streak (from today backwards)
+    let mut current_streak = 0;
+    let mut check_date = today;
+
+    // Check if today has activity, if not check yesterday
+    if !dates.contains(&today_str) {
+        check_date = today - chrono::Duration::days(1);
+    }
+
+    while dates.contains(&check_date.format("%Y-%m-%d").to_string()) {
+        current_streak += 1;
+        check_date -= chrono::Duration::days(1);
+    }
+
+    // Calculate longest streak
+    let mut sorted_dates: Vec<NaiveDate> = dates
+        .iter()
+        .filter_map(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+        .collect();
+    sorted_dates.sort();
+    sorted_dates.reverse();
+
+    let mut longest_streak = 0;
+    let mut temp_streak = 1;
+
+    for i in 0..sorted_dates.len().saturating_sub(1) {
+        let diff = (sorted_dates[i] - sorted_dates[i + 1]).num_days();
+        if diff == 1 {
+            temp_streak += 1;
+        } else {
+            longest_streak = longest_streak.max(temp_streak);
+            temp_streak = 1;
+        }
+    }
+    longest_streak = longest_streak.max(temp_streak);
+
+    StreakInfo {
+        current: current_streak,
+        longest: longest_streak,
+    }
+}
+
+/// Where a database lives
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DbTarget {
+    /// data.db in the config directory
+    #[default]
+    File,
+    /// In memory only: nothing touches disk and it's gone on exit
+    Memory,
+}
+
 /// Database connection wrapper
 pub struct Database {
     conn: Connection,
@@ -78,14 +733,162 @@ impl Database {
 
     /// Open or create the database
     pub fn open() -> Result<Self> {
+        Self::open_target(DbTarget::File)
+    }
+
+    /// Open or create the database at `target`
+    pub fn open_target(target: DbTarget) -> Result<Self> {
+        if target == DbTarget::Memory {
+            return Self::open_in_memory();
+        }
         let path = Self::db_path()?;
         std::fs::create_dir_all(path.parent().unwrap())?;
         let conn = Connection::open(path)?;
+        conn.busy_timeout(BUSY_TIMEOUT)?;
+        // WAL lets readers (stats, export) run while the TUI is writing
+        retry_on_busy(|| conn.pragma_update(None, "journal_mode", "WAL"))?;
         let db = Self { conn };
         db.init_schema()?;
         Ok(db)
     }
 
+    /// Fresh in-memory database with the current schema
+    pub fn open_in_memory() -> Result<Self> {
+        let db = Self {
+            conn: Connection::open_in_memory()?,
+        };
+        db.init_schema()?;
+        Ok(db)
+    }
+
+    /// Open the database read-only, for stats and export.
+    ///
+    /// A read-only connection never takes a write lock, so it can't block
+    /// (or be blocked by) a running timer. Falls back to a writable open
+    /// first when the database is missing or its schema needs migrating.
+    pub fn open_readonly() -> Result<Self> {
+        let path = Self::db_path()?;
+        if !path.exists() {
+            drop(Self::open()?);
+        }
+
+        let connect = || -> Result<Connection> {
+            let conn = Connection::open_with_flags(
+                &path,
+                OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+            )?;
+            conn.busy_timeout(BUSY_TIMEOUT)?;
+            Ok(conn)
+        };
+
+        let mut conn = connect()?;
+        let version: i32 =
+            retry_on_busy(|| conn.query_row("PRAGMA user_version", [], |row| row.get(0)))?;
+        if version < SCHEMA_VERSION {
+            drop(conn);
+            drop(Self::open()?);
+            conn = connect()?;
+        }
+
+        Ok(Self { conn })
+    }
+
+    /// Open the database read-only exactly as it is on disk, without
+    /// migrating it, for inspecting what a user has. None when it doesn't
+    /// exist yet.
+    pub fn open_as_is() -> Result<Option<Self>> {
+        let path = Self::db_path()?;
+        if !path.exists() {
+            return Ok(None);
+        }
+        let conn = Connection::open_with_flags(
+            &path,
+            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        )?;
+        conn.busy_timeout(BUSY_TIMEOUT)?;
+        Ok(Some(Self { conn }))
+    }
+
+    /// Newest schema version this build knows
+    pub fn supported_schema_version() -> i32 {
+        SCHEMA_VERSION
+    }
+
+    /// Schema version the database is at
+    pub fn schema_version(&self) -> Result<i32> {
+        Ok(retry_on_busy(|| {
+            self.conn
+                .query_row("PRAGMA user_version", [], |row| row.get(0))
+        })?)
+    }
+
+    /// Tables, views, indexes and triggers, with row counts for tables.
+    /// Tables SQLite keeps for the full-text index are left out.
+    pub fn schema_objects(&self) -> Result<Vec<SchemaObject>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT type, name, sql FROM sqlite_master
+             WHERE sql IS NOT NULL AND name NOT LIKE 'sqlite_%'
+             ORDER BY CASE type WHEN 'table' THEN 0 WHEN 'view' THEN 1
+                                WHEN 'index' THEN 2 ELSE 3 END, name",
+        )?;
+        let objects: Vec<(String, String, String)> = retry_on_busy(|| {
+            stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))?
+                .collect()
+        })?;
+        let virtual_tables: Vec<String> = objects
+            .iter()
+            .filter(|(_, _, sql)| sql.starts_with("CREATE VIRTUAL TABLE"))
+            .map(|(_, name, _)| format!("{}_", name))
+            .collect();
+
+        let mut result = Vec::new();
+        for (kind, name, sql) in objects {
+            let shadow = kind == "table" && virtual_tables.iter().any(|v| name.starts_with(v));
+            if shadow {
+                continue;
+            }
+            let rows = if kind == "table" {
+                let count = format!("SELECT COUNT(*) FROM \"{}\"", name.replace('"', "\"\""));
+                Some(retry_on_busy(|| {
+                    self.conn.query_row(&count, [], |row| row.get(0))
+                })?)
+            } else {
+                None
+            };
+            result.push(SchemaObject {
+                kind,
+                name,
+                rows,
+                sql,
+            });
+        }
+        Ok(result)
+    }
+
+    /// Columns of the table or view `name`, in declared order
+    pub fn columns(&self, name: &str) -> Result<Vec<ColumnInfo>> {
+        let pragma = format!("PRAGMA table_info(\"{}\")", name.replace('"', "\"\""));
+        let mut stmt = self.conn.prepare(&pragma)?;
+        let columns = retry_on_busy(|| {
+            stmt.query_map([], |row| {
+                Ok(ColumnInfo {
+                    name: row.get(1)?,
+                    sql_type: row.get(2)?,
+                    not_null: row.get(3)?,
+                    default: row.get(4)?,
+                    primary_key: row.get::<_, i64>(5)? > 0,
+                })
+            })?
+            .collect()
+        })?;
+        Ok(columns)
+    }
+
+    /// Execute a statement, retrying while the database is busy
+    fn execute<P: Params + Copy>(&self, sql: &str, params: P) -> Result<usize> {
+        Ok(retry_on_busy(|| self.conn.execute(sql, params))?)
+    }
+
     /// Get a reference to the underlying connection (for sync operations)
     pub fn connection(&self) -> &Connection {
         &self.conn
@@ -93,9 +896,20 @@ impl Database {
 
     /// Initialize database schema
     fn init_schema(&self) -> Result<()> {
+        let version: i32 = retry_on_busy(|| {
+            self.conn
+                .query_row("PRAGMA user_version", [], |row| row.get(0))
+        })?;
+
+        // Views are only created if missing, so drop one that predates a change
+        if version < WORK_DAYS_VIEW_SCHEMA_VERSION {
+            self.execute("DROP VIEW IF EXISTS work_days", [])?;
+        }
+
         // Create tables first (without tag_id index since column might not exist yet)
-        self.conn.execute_batch(
-            r#"
+        retry_on_busy(|| {
+            self.conn.execute_batch(
+                r#"
             CREATE TABLE IF NOT EXISTS tags (
                 id INTEGER PRIMARY KEY AUTOINCREMENT,
                 name TEXT NOT NULL UNIQUE,
@@ -107,7 +921,7 @@ impl Database {
                 started_at DATETIME NOT NULL,
                 ended_at DATETIME,
                 duration_seconds INTEGER,
-                type TEXT NOT NULL CHECK (type IN ('work', 'short_break', 'long_break')),
+                type TEXT NOT NULL CHECK (type IN ('work', 'short_break', 'long_break', 'timer')),
                 completed BOOLEAN DEFAULT FALSE,
                 tag_id INTEGER REFERENCES tags(id)
             );
@@ -119,73 +933,554 @@ impl Database {
                 longest_streak INTEGER DEFAULT 0
             );
 
+            CREATE TABLE IF NOT EXISTS session_events (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                session_id INTEGER NOT NULL,
+                event TEXT NOT NULL,
+                detail TEXT,
+                created_at DATETIME NOT NULL
+            );
+
+            CREATE TABLE IF NOT EXISTS metadata (
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL
+            );
+
+            -- Sync bookkeeping such as the last full sync; used to be
+            -- created lazily by the sync module
+            CREATE TABLE IF NOT EXISTS sync_metadata (
+                key TEXT PRIMARY KEY,
+                value TEXT NOT NULL
+            );
+
+            -- End-of-day reflections, one per local date, kept next to
+            -- daily_stats but separate so a note never counts as a work day
+            -- (version 20)
+            CREATE TABLE IF NOT EXISTS journal (
+                date DATE PRIMARY KEY,
+                note TEXT NOT NULL,
+                updated_at DATETIME NOT NULL
+            );
+
+            -- Weekly goals in effect for each week, kept for goal history
+            CREATE TABLE IF NOT EXISTS goal_weeks (
+                week_start DATE PRIMARY KEY,
+                minutes_goal INTEGER NOT NULL,
+                sessions_goal INTEGER NOT NULL
+            );
+
+            -- Tasks on the "today" board (version 19); done_at is set when a
+            -- task reaches Done
+            CREATE TABLE IF NOT EXISTS tasks (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                title TEXT NOT NULL,
+                status TEXT NOT NULL DEFAULT 'planned'
+                    CHECK (status IN ('planned', 'doing', 'done')),
+                estimate INTEGER,
+                sessions_done INTEGER NOT NULL DEFAULT 0,
+                created_at DATETIME NOT NULL,
+                done_at DATETIME
+            );
+
+            -- Work sessions planned per tag for each week
+            CREATE TABLE IF NOT EXISTS plan_items (
+                week_start DATE NOT NULL,
+                tag_id INTEGER NOT NULL REFERENCES tags(id),
+                sessions INTEGER NOT NULL,
+                PRIMARY KEY (week_start, tag_id)
+            );
+
+            -- XP awarded by the optional gamification layer, one row per award
+            CREATE TABLE IF NOT EXISTS xp_events (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                session_id INTEGER,
+                reason TEXT NOT NULL,
+                xp INTEGER NOT NULL,
+                created_at DATETIME NOT NULL
+            );
+
+            -- Interruptions noted during work sessions; category is a single
+            -- character picked by the user, e.g. ' internal or - external
+            CREATE TABLE IF NOT EXISTS interruptions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                session_id INTEGER NOT NULL REFERENCES sessions(id),
+                category TEXT,
+                created_at DATETIME NOT NULL
+            );
+
+            -- Work sessions waiting to be sent to an external time tracker
+            CREATE TABLE IF NOT EXISTS time_entry_queue (
+                session_id INTEGER PRIMARY KEY,
+                provider TEXT NOT NULL,
+                attempts INTEGER NOT NULL DEFAULT 0,
+                next_attempt_at DATETIME NOT NULL,
+                last_error TEXT
+            );
+
+            -- Session changes waiting to go to the cloud, one per session;
+            -- cloud_id is the cloud row the change goes to
+            CREATE TABLE IF NOT EXISTS sync_queue (
+                session_id INTEGER PRIMARY KEY,
+                op TEXT NOT NULL CHECK (op IN ('insert', 'update', 'delete')),
+                cloud_id TEXT NOT NULL,
+                attempts INTEGER NOT NULL DEFAULT 0,
+                next_attempt_at DATETIME NOT NULL,
+                last_error TEXT
+            );
+
             CREATE INDEX IF NOT EXISTS idx_sessions_started ON sessions(started_at);
             CREATE INDEX IF NOT EXISTS idx_sessions_type ON sessions(type);
+            CREATE INDEX IF NOT EXISTS idx_session_events_session ON session_events(session_id);
+            CREATE INDEX IF NOT EXISTS idx_interruptions_session ON interruptions(session_id);
             "#,
-        )?;
+            )
+        })?;
 
         // Migration: add tag_id column if it doesn't exist (for existing DBs)
         self.migrate_add_tag_id()?;
+        self.add_column_if_missing("sessions", "planned_seconds", "INTEGER")?;
+        // cloud_id used to be added lazily by the sync module
+        self.add_column_if_missing("sessions", "cloud_id", "TEXT")?;
+        self.add_column_if_missing("sessions", "notes", "TEXT")?;
+        // The task a work session was bound to on the board (version 19)
+        self.add_column_if_missing("sessions", "task_id", "INTEGER REFERENCES tasks(id)")?;
+        // Where and which day each session started; NULL before version 6,
+        // when days were UTC dates
+        self.add_column_if_missing("sessions", "utc_offset_minutes", "INTEGER")?;
+        self.add_column_if_missing("sessions", "day", "DATE")?;
+        // Sessions from before version 9 are all taken as classic
+        self.add_column_if_missing("sessions", "mode", "TEXT NOT NULL DEFAULT 'classic'")?;
+        self.migrate_timer_session_type()?;
+        // Per-tag defaults; NULL keeps the settings
+        self.add_column_if_missing("tags", "work_minutes", "INTEGER")?;
+        self.add_column_if_missing("tags", "break_minutes", "INTEGER")?;
+        self.add_column_if_missing("tags", "icon", "TEXT")?;
+        // Globally unique id, the session's id in the cloud (version 14)
+        self.add_column_if_missing("sessions", "uuid", "TEXT")?;
+        self.backfill_session_uuids()?;
+        // Time spent paused, and when a pause still going began (version 15)
+        self.add_column_if_missing("sessions", "paused_seconds", "INTEGER NOT NULL DEFAULT 0")?;
+        self.add_column_if_missing("sessions", "paused_at", "TEXT")?;
+        // Outbox entries set aside after a permanent failure (version 17)
+        self.add_column_if_missing("sync_queue", "dead_at", "DATETIME")?;
+        // Time entries held by a flush, and ones set aside (version 18)
+        self.add_column_if_missing("time_entry_queue", "claimed_until", "DATETIME")?;
+        self.add_column_if_missing("time_entry_queue", "dead_at", "DATETIME")?;
+        // 'local' or 'cloud'; NULL for sessions from before version 21, and
+        // imported ones, whose device isn't known
+        self.add_column_if_missing("sessions", "origin", "TEXT")?;
+
+        // The view reads the day column, so it comes after the migrations
+        retry_on_busy(|| {
+            self.conn.execute_batch(
+                r#"
+            -- Completed work per day: raw sessions plus totals kept by retention.
+            -- Negative durations and sessions dated more than a day ahead are
+            -- left behind by clock jumps and don't count.
+            CREATE VIEW IF NOT EXISTS work_days AS
+                SELECT COALESCE(day, date(started_at)) AS date,
+                       COALESCE(SUM(MAX(duration_seconds, 0)), 0) AS total_seconds,
+                       COUNT(*) AS sessions
+                FROM sessions
+                WHERE type IN ('work', 'timer') AND completed = TRUE
+                  AND datetime(started_at) <= datetime('now', '+1 day')
+                GROUP BY COALESCE(day, date(started_at))
+                UNION ALL
+                SELECT date, total_work_seconds, sessions_completed
+                FROM daily_stats;
+            "#,
+            )
+        })?;
 
         // Create tag index after migration ensures column exists
-        self.conn.execute(
+        self.execute(
             "CREATE INDEX IF NOT EXISTS idx_sessions_tag ON sessions(tag_id)",
             [],
         )?;
+        self.execute(
+            "CREATE INDEX IF NOT EXISTS idx_sessions_day ON sessions(day)",
+            [],
+        )?;
+        self.execute(
+            "CREATE UNIQUE INDEX IF NOT EXISTS idx_sessions_uuid ON sessions(uuid)",
+            [],
+        )?;
+
+        self.init_search()?;
+        if version < SEARCH_SCHEMA_VERSION {
+            self.rebuild_search()?;
+        }
+
+        self.conn
+            .pragma_update(None, "user_version", SCHEMA_VERSION)?;
 
         Ok(())
     }
 
-    /// Migration: Add tag_id column to sessions table if it doesn't exist
-    fn migrate_add_tag_id(&self) -> Result<()> {
-        // Check if tag_id column exists
-        let mut stmt = self.conn.prepare("PRAGMA table_info(sessions)")?;
-        let columns: Vec<String> = stmt
-            .query_map([], |row| row.get::<_, String>(1))?
-            .filter_map(|r| r.ok())
-            .collect();
+    /// Full-text index over each session's tag name and notes, kept up to date
+    /// by triggers. Rows are keyed by session id.
+    fn init_search(&self) -> Result<()> {
+        retry_on_busy(|| {
+            self.conn.execute_batch(
+                r#"
+            CREATE VIRTUAL TABLE IF NOT EXISTS session_search USING fts5(tag, notes);
 
-        if !columns.contains(&"tag_id".to_string()) {
-            self.conn.execute(
-                "ALTER TABLE sessions ADD COLUMN tag_id INTEGER REFERENCES tags(id)",
-                [],
-            )?;
-        }
+            CREATE TRIGGER IF NOT EXISTS session_search_insert AFTER INSERT ON sessions BEGIN
+                INSERT INTO session_search (rowid, tag, notes)
+                VALUES (new.id, (SELECT name FROM tags WHERE id = new.tag_id), new.notes);
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS session_search_update
+            AFTER UPDATE OF tag_id, notes ON sessions BEGIN
+                DELETE FROM session_search WHERE rowid = old.id;
+                INSERT INTO session_search (rowid, tag, notes)
+                VALUES (new.id, (SELECT name FROM tags WHERE id = new.tag_id), new.notes);
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS session_search_delete AFTER DELETE ON sessions BEGIN
+                DELETE FROM session_search WHERE rowid = old.id;
+            END;
+
+            CREATE TRIGGER IF NOT EXISTS session_search_tag_rename
+            AFTER UPDATE OF name ON tags BEGIN
+                UPDATE session_search SET tag = new.name
+                WHERE rowid IN (SELECT id FROM sessions WHERE tag_id = new.id);
+            END;
+            "#,
+            )
+        })?;
         Ok(())
     }
 
-    /// Start a new session
-    pub fn start_session(&self, session_type: SessionType) -> Result<i64> {
-        let now = Utc::now();
-        self.conn.execute(
-            "INSERT INTO sessions (started_at, type, completed) VALUES (?1, ?2, FALSE)",
-            params![now.to_rfc3339(), session_type.as_str()],
-        )?;
-        Ok(self.conn.last_insert_rowid())
+    /// Re-index every session (for databases created before the index)
+    fn rebuild_search(&self) -> Result<()> {
+        retry_on_busy(|| {
+            self.conn.execute_batch(
+                "DELETE FROM session_search;
+                 INSERT INTO session_search (rowid, tag, notes)
+                 SELECT s.id, t.name, s.notes
+                 FROM sessions s LEFT JOIN tags t ON s.tag_id = t.id;",
+            )
+        })?;
+        Ok(())
     }
 
-    /// Complete a session
-    pub fn complete_session(&self, session_id: i64, duration_seconds: i32) -> Result<()> {
-        let now = Utc::now();
-        self.conn.execute(
-            "UPDATE sessions SET ended_at = ?1, duration_seconds = ?2, completed = TRUE WHERE id = ?3",
-            params![now.to_rfc3339(), duration_seconds, session_id],
+    /// Migration: let sessions.type take 'timer' (version 10). SQLite can't
+    /// change a CHECK constraint, so the table is copied into one declared
+    /// with the wider CHECK. Runs while the work_days view is dropped; the
+    /// search triggers are recreated by init_search.
+    fn migrate_timer_session_type(&self) -> Result<()> {
+        let sql: String = self.conn.query_row(
+            "SELECT sql FROM sqlite_master WHERE type = 'table' AND name = 'sessions'",
+            [],
+            |row| row.get(0),
         )?;
+        if sql.contains("'timer'") {
+            return Ok(());
+        }
+        let widened = sql.replacen("sessions", "sessions_widened", 1).replacen(
+            "'long_break')",
+            "'long_break', 'timer')",
+            1,
+        );
+        // Keep references to sessions in other tables as they are
+        self.conn.pragma_update(None, "legacy_alter_table", true)?;
+        let result = retry_on_busy(|| {
+            let tx = self.conn.unchecked_transaction()?;
+            tx.execute_batch(&format!(
+                "DROP TRIGGER IF EXISTS session_search_tag_rename;
+                 {};
+                 INSERT INTO sessions_widened SELECT * FROM sessions;
+                 DROP TABLE sessions;
+                 ALTER TABLE sessions_widened RENAME TO sessions;
+                 CREATE INDEX IF NOT EXISTS idx_sessions_started ON sessions(started_at);
+                 CREATE INDEX IF NOT EXISTS idx_sessions_type ON sessions(type);",
+                widened
+            ))?;
+            tx.commit()
+        });
+        self.conn.pragma_update(None, "legacy_alter_table", false)?;
+        result?;
         Ok(())
     }
 
-    /// Get today's statistics
-    pub fn get_today_stats(&self) -> Result<DailyStats> {
-        let today = Utc::now().format("%Y-%m-%d").to_string();
-        self.get_date_stats(&today)
+    /// Migration: give every session a uuid (version 14). One already in the
+    /// cloud keeps its cloud id, so both sides keep calling it the same.
+    fn backfill_session_uuids(&self) -> Result<()> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT id, cloud_id FROM sessions WHERE uuid IS NULL")?;
+        let missing: Vec<(i64, Option<String>)> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .filter_map(|r| r.ok())
+            .collect();
+        if missing.is_empty() {
+            return Ok(());
+        }
+        retry_on_busy(|| {
+            let tx = self.conn.unchecked_transaction()?;
+            for (id, cloud_id) in &missing {
+                let uuid = cloud_id.clone().unwrap_or_else(new_uuid);
+                tx.execute(
+                    "UPDATE sessions SET uuid = ?1 WHERE id = ?2",
+                    params![uuid, id],
+                )?;
+            }
+            tx.commit()
+        })?;
+        Ok(())
+    }
+
+    /// Migration: Add tag_id column to sessions table if it doesn't exist
+    fn migrate_add_tag_id(&self) -> Result<()> {
+        self.add_column_if_missing("sessions", "tag_id", "INTEGER REFERENCES tags(id)")
+    }
+
+    /// Add a column to a table if it doesn't exist yet (for existing DBs)
+    fn add_column_if_missing(&self, table: &str, column: &str, definition: &str) -> Result<()> {
+        let mut stmt = self
+            .conn
+            .prepare(&format!("PRAGMA table_info({})", table))?;
+        let columns: Vec<String> = stmt
+            .query_map([], |row| row.get::<_, String>(1))?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        if !columns.iter().any(|c| c == column) {
+            self.execute(
+                &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition),
+                [],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Start a new session
+    pub fn start_session(&self, session_type: SessionType) -> Result<i64> {
+        self.start_session_with_tag(session_type, None, None)
+    }
+
+    /// Complete a session
+    pub fn complete_session(&self, session_id: i64, duration_seconds: i32) -> Result<()> {
+        self.end_session(session_id, clock::now_utc(), duration_seconds, true)?;
+        let detail = format!("duration {}s", duration_seconds);
+        self.log_session_event(session_id, "completed", Some(&detail))?;
+        Ok(())
+    }
+
+    /// Close a session that stopped before its end. It doesn't count toward stats.
+    pub fn interrupt_session(
+        &self,
+        session_id: i64,
+        ended_at: DateTime<Utc>,
+        duration_seconds: i32,
+    ) -> Result<()> {
+        self.end_session(session_id, ended_at, duration_seconds, false)?;
+        let detail = format!("duration {}s", duration_seconds);
+        self.log_session_event(session_id, "interrupted", Some(&detail))?;
+        Ok(())
+    }
+
+    /// Close a session another device took over. It counts on that device,
+    /// so it's neither completed nor interrupted here.
+    pub fn hand_off_session(
+        &self,
+        session_id: i64,
+        ended_at: DateTime<Utc>,
+        duration_seconds: i32,
+        to: &str,
+    ) -> Result<()> {
+        self.end_session(session_id, ended_at, duration_seconds, false)?;
+        let detail = format!("to {}", to);
+        self.log_session_event(session_id, "handed_off", Some(&detail))?;
+        Ok(())
+    }
+
+    /// Open a session closed as interrupted again, to carry on recording it.
+    /// It's paused from when it was closed. False when it has since been
+    /// completed or deleted.
+    pub fn reopen_session(&self, session_id: i64) -> Result<bool> {
+        let changed = self.execute(
+            "UPDATE sessions SET ended_at = NULL, duration_seconds = NULL, paused_at = ended_at
+             WHERE id = ?1 AND completed = FALSE AND ended_at IS NOT NULL",
+            params![session_id],
+        )?;
+        if changed > 0 {
+            self.log_session_event(session_id, "resumed", None)?;
+        }
+        Ok(changed > 0)
+    }
+
+    /// Note that a session was paused at `at`. False when it's already
+    /// paused or has ended.
+    pub fn pause_session(&self, session_id: i64, at: DateTime<Utc>) -> Result<bool> {
+        let changed = self.execute(
+            "UPDATE sessions SET paused_at = ?1
+             WHERE id = ?2 AND paused_at IS NULL AND ended_at IS NULL",
+            params![at.to_rfc3339(), session_id],
+        )?;
+        if changed > 0 {
+            self.log_session_event(session_id, "paused", None)?;
+        }
+        Ok(changed > 0)
+    }
+
+    /// End a session's pause at `at`, adding it to the time paused. False
+    /// when it isn't paused.
+    pub fn unpause_session(&self, session_id: i64, at: DateTime<Utc>) -> Result<bool> {
+        let seconds = self.close_pause(session_id, at)?;
+        if let Some(seconds) = seconds {
+            let detail = format!("paused {}s", seconds);
+            self.log_session_event(session_id, "unpaused", Some(&detail))?;
+        }
+        Ok(seconds.is_some())
+    }
+
+    /// Fold an open pause into paused_seconds, returning its length
+    fn close_pause(&self, session_id: i64, at: DateTime<Utc>) -> Result<Option<i32>> {
+        let paused_at: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT paused_at FROM sessions WHERE id = ?1",
+                params![session_id],
+                |row| row.get(0),
+            )
+            .optional()?
+            .flatten();
+        let Some(paused_at) = paused_at else {
+            return Ok(None);
+        };
+        // A clock set back mid-pause makes it no pause at all
+        let seconds = (at - parse_timestamp(&paused_at)).num_seconds().max(0) as i32;
+        self.execute(
+            "UPDATE sessions SET paused_seconds = paused_seconds + ?1, paused_at = NULL
+             WHERE id = ?2",
+            params![seconds, session_id],
+        )?;
+        Ok(Some(seconds))
+    }
+
+    /// Seconds paused during completed work on days in `[start, end]`
+    /// (YYYY-MM-DD); the recorded durations already leave them out
+    pub fn get_paused_seconds(&self, start: &str, end: &str) -> Result<i32> {
+        Ok(retry_on_busy(|| {
+            self.conn.query_row(
+                "SELECT COALESCE(SUM(paused_seconds), 0) FROM sessions
+                 WHERE type IN ('work', 'timer') AND completed = TRUE
+                   AND COALESCE(day, date(started_at)) BETWEEN ?1 AND ?2",
+                params![start, end],
+                |row| row.get(0),
+            )
+        })?)
+    }
+
+    /// Complete a session left open by a crash, with an estimated duration
+    pub fn recover_session(
+        &self,
+        session_id: i64,
+        ended_at: DateTime<Utc>,
+        duration_seconds: i32,
+    ) -> Result<()> {
+        self.end_session(session_id, ended_at, duration_seconds, true)?;
+        let detail = format!("estimated duration {}s", duration_seconds);
+        self.log_session_event(session_id, "recovered", Some(&detail))?;
+        Ok(())
+    }
+
+    fn end_session(
+        &self,
+        session_id: i64,
+        ended_at: DateTime<Utc>,
+        duration_seconds: i32,
+        completed: bool,
+    ) -> Result<()> {
+        // A session that ends while paused was paused until then
+        self.close_pause(session_id, ended_at)?;
+        self.execute(
+            "UPDATE sessions SET ended_at = ?1, duration_seconds = ?2, completed = ?3 WHERE id = ?4",
+            params![
+                ended_at.to_rfc3339(),
+                duration_seconds.max(0),
+                completed,
+                session_id
+            ],
+        )?;
+
+        // A clock set back mid-session ends it before it started
+        let started_at: Option<String> = self
+            .conn
+            .query_row(
+                "SELECT started_at FROM sessions WHERE id = ?1",
+                params![session_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        let ends_early = started_at
+            .is_some_and(|s| (parse_timestamp(&s) - ended_at).num_seconds() > CLOCK_SLACK_SECONDS);
+        if duration_seconds < 0 || ends_early {
+            let detail = if duration_seconds < 0 {
+                format!("negative duration {}s recorded as 0s", duration_seconds)
+            } else {
+                "ended before it started".to_string()
+            };
+            self.log_session_event(session_id, "clock_jump", Some(&detail))?;
+        }
+        Ok(())
+    }
+
+    /// Sessions that were never ended, skipped or reset, i.e. left behind by
+    /// a run that crashed or was killed. Oldest first. Only sessions with a
+    /// "started" event count: older versions left skipped and reset sessions
+    /// open without an event trail, and those aren't crashes.
+    pub fn get_open_sessions(&self) -> Result<Vec<SessionDetail>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id FROM sessions s
+             WHERE ended_at IS NULL AND completed = FALSE
+               AND EXISTS (
+                   SELECT 1 FROM session_events e
+                   WHERE e.session_id = s.id AND e.event = 'started'
+               )
+               AND NOT EXISTS (
+                   SELECT 1 FROM session_events e
+                   WHERE e.session_id = s.id AND e.event IN ('skipped', 'reset')
+               )
+             ORDER BY started_at ASC",
+        )?;
+        let ids: Vec<i64> = stmt
+            .query_map([], |row| row.get(0))?
+            .filter_map(|r| r.ok())
+            .collect();
+        let mut sessions = Vec::new();
+        for id in ids {
+            if let Some(detail) = self.get_session_detail(id)? {
+                sessions.push(detail);
+            }
+        }
+        Ok(sessions)
+    }
+
+    /// Append an event to a session's event trail
+    pub fn log_session_event(
+        &self,
+        session_id: i64,
+        event: &str,
+        detail: Option<&str>,
+    ) -> Result<()> {
+        record_session_event(&self.conn, session_id, event, detail)
+    }
+
+    /// Get today's statistics
+    pub fn get_today_stats(&self) -> Result<DailyStats> {
+        let today = self.today_str()?;
+        self.get_date_stats(&today)
     }
 
     /// Get statistics for a specific date (YYYY-MM-DD format)
     pub fn get_date_stats(&self, date: &str) -> Result<DailyStats> {
         let mut stmt = self.conn.prepare(
-            "SELECT COALESCE(SUM(duration_seconds), 0), COUNT(*)
-             FROM sessions
-             WHERE date(started_at) = ?1 AND type = 'work' AND completed = TRUE",
+            "SELECT COALESCE(SUM(total_seconds), 0), COALESCE(SUM(sessions), 0)
+             FROM work_days
+             WHERE date = ?1",
         )?;
 
         let (total_seconds, count): (i32, i32) =
@@ -199,23 +1494,117 @@ impl Database {
         })
     }
 
+    /// Work sessions with a planned length over the last N days, grouped by
+    /// planned minutes: (minutes, attempts, completed). An attempt is a
+    /// session that was finished, skipped, reset or interrupted; sessions
+    /// still running or handed off to another device don't count.
+    pub fn get_work_length_attempts(&self, days: i32) -> Result<Vec<(u32, i32, i32)>> {
+        let offset = format!("-{} days", days);
+        let today = self.today_str()?;
+        let mut stmt = self.conn.prepare(
+            "SELECT s.planned_seconds / 60, COUNT(*), SUM(s.completed = TRUE)
+             FROM sessions s
+             WHERE s.type IN ('work', 'timer')
+               AND s.planned_seconds IS NOT NULL
+               AND COALESCE(s.day, date(s.started_at)) >= date(?2, ?1)
+               AND (s.completed = TRUE OR s.ended_at IS NOT NULL OR EXISTS (
+                   SELECT 1 FROM session_events e
+                   WHERE e.session_id = s.id AND e.event IN ('skipped', 'reset')
+               ))
+               AND NOT EXISTS (
+                   SELECT 1 FROM session_events e
+                   WHERE e.session_id = s.id AND e.event = 'handed_off'
+               )
+             GROUP BY s.planned_seconds / 60
+             ORDER BY s.planned_seconds / 60",
+        )?;
+        let rows = stmt
+            .query_map(params![offset, today], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(rows)
+    }
+
+    /// Every finished work attempt over the last N days, oldest first:
+    /// (started_at, planned_seconds, outcome). The outcome is "completed",
+    /// "skipped", "reset" or "interrupted"; sessions still running or handed
+    /// off to another device are left out, as in `get_work_length_attempts`.
+    #[allow(clippy::type_complexity)]
+    pub fn get_work_attempts(
+        &self,
+        days: i32,
+    ) -> Result<Vec<(DateTime<Utc>, Option<i32>, String)>> {
+        let offset = format!("-{} days", days);
+        let today = self.today_str()?;
+        let mut stmt = self.conn.prepare(
+            "SELECT s.started_at, s.planned_seconds,
+                    CASE
+                        WHEN s.completed = TRUE THEN 'completed'
+                        WHEN EXISTS (SELECT 1 FROM session_events e
+                                     WHERE e.session_id = s.id AND e.event = 'handed_off')
+                            THEN NULL
+                        WHEN EXISTS (SELECT 1 FROM session_events e
+                                     WHERE e.session_id = s.id AND e.event = 'skipped')
+                            THEN 'skipped'
+                        WHEN EXISTS (SELECT 1 FROM session_events e
+                                     WHERE e.session_id = s.id AND e.event = 'reset')
+                            THEN 'reset'
+                        WHEN s.ended_at IS NOT NULL THEN 'interrupted'
+                    END AS outcome
+             FROM sessions s
+             WHERE s.type IN ('work', 'timer')
+               AND COALESCE(s.day, date(s.started_at)) >= date(?2, ?1)
+               AND outcome IS NOT NULL
+             ORDER BY s.started_at ASC",
+        )?;
+        let rows = stmt
+            .query_map(params![offset, today], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, Option<i32>>(1)?,
+                    row.get::<_, String>(2)?,
+                ))
+            })?
+            .filter_map(|r| r.ok())
+            .map(|(started_at, planned, outcome)| (parse_timestamp(&started_at), planned, outcome))
+            .collect();
+        Ok(rows)
+    }
+
+    /// Count breaks skipped on a date (YYYY-MM-DD)
+    pub fn count_skipped_breaks(&self, date: &str) -> Result<i32> {
+        let count = self.conn.query_row(
+            "SELECT COUNT(*) FROM sessions s
+             WHERE s.type IN ('short_break', 'long_break')
+               AND COALESCE(s.day, date(s.started_at)) = ?1
+               AND EXISTS (
+                   SELECT 1 FROM session_events e
+                   WHERE e.session_id = s.id AND e.event = 'skipped'
+               )",
+            params![date],
+            |row| row.get(0),
+        )?;
+        Ok(count)
+    }
+
     /// Get stats for the last N days
     pub fn get_daily_stats(&self, days: i32) -> Result<Vec<DailyStats>> {
         let mut stmt = self.conn.prepare(
-            "SELECT date(started_at) as date,
-                    COALESCE(SUM(duration_seconds), 0) as total_seconds,
-                    COUNT(*) as sessions
-             FROM sessions
-             WHERE date(started_at) >= date('now', ?1)
-               AND type = 'work'
-               AND completed = TRUE
-             GROUP BY date(started_at)
-             ORDER BY date(started_at) DESC",
+            "SELECT date,
+                    SUM(total_seconds) as total_seconds,
+                    SUM(sessions) as sessions
+             FROM work_days
+             WHERE date >= date(?2, ?1)
+             GROUP BY date
+             ORDER BY date DESC",
         )?;
 
         let offset = format!("-{} days", days);
+        let today = self.today_str()?;
         let stats = stmt
-            .query_map(params![offset], |row| {
+            .query_map(params![offset, today], |row| {
                 Ok(DailyStats {
                     date: row.get(0)?,
                     total_work_seconds: row.get(1)?,
@@ -232,15 +1621,15 @@ impl Database {
     /// Get weekly total (last 7 days)
     pub fn get_week_stats(&self) -> Result<DailyStats> {
         let mut stmt = self.conn.prepare(
-            "SELECT COALESCE(SUM(duration_seconds), 0), COUNT(*)
-             FROM sessions
-             WHERE date(started_at) >= date('now', '-7 days')
-               AND type = 'work'
-               AND completed = TRUE",
+            "SELECT COALESCE(SUM(total_seconds), 0), COALESCE(SUM(sessions), 0)
+             FROM work_days
+             WHERE date >= date(?1, '-7 days')",
         )?;
 
-        let (total_seconds, count): (i32, i32) =
-            stmt.query_row([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        let (total_seconds, count): (i32, i32) = stmt
+            .query_row(params![self.today_str()?], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })?;
 
         Ok(DailyStats {
             date: "Last 7 days".to_string(),
@@ -250,18 +1639,303 @@ impl Database {
         })
     }
 
+    /// Totals for dates in `[start, end)` (YYYY-MM-DD)
+    pub fn get_range_stats(&self, start: &str, end: &str) -> Result<DailyStats> {
+        let mut stmt = self.conn.prepare(
+            "SELECT COALESCE(SUM(total_seconds), 0), COALESCE(SUM(sessions), 0)
+             FROM work_days
+             WHERE date >= ?1 AND date < ?2",
+        )?;
+
+        let (total_seconds, count): (i32, i32) =
+            stmt.query_row(params![start, end], |row| Ok((row.get(0)?, row.get(1)?)))?;
+
+        Ok(DailyStats {
+            date: start.to_string(),
+            total_work_seconds: total_seconds,
+            sessions_completed: count,
+            longest_streak: 0,
+        })
+    }
+
+    /// Totals of each day in `[start, end)` (YYYY-MM-DD) with completed
+    /// work, oldest first. Pruned days count too.
+    pub fn get_range_days(&self, start: &str, end: &str) -> Result<Vec<DailyStats>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT date, SUM(total_seconds), SUM(sessions)
+             FROM work_days
+             WHERE date >= ?1 AND date < ?2
+             GROUP BY date
+             ORDER BY date",
+        )?;
+        let days = stmt
+            .query_map(params![start, end], |row| {
+                Ok(DailyStats {
+                    date: row.get(0)?,
+                    total_work_seconds: row.get(1)?,
+                    sessions_completed: row.get(2)?,
+                    longest_streak: 0,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(days)
+    }
+
+    /// Every completed work and timer session counting towards a day in
+    /// `[start, end)` (YYYY-MM-DD), with its tag, oldest first. Pruned days
+    /// have no sessions left.
+    pub fn get_sessions_in_range(&self, start: &str, end: &str) -> Result<Vec<DaySession>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT COALESCE(s.day, date(s.started_at)) AS date,
+                   s.id, s.started_at, s.ended_at, s.duration_seconds, s.type, s.completed,
+                   t.id, t.name, t.color, s.notes
+            FROM sessions s
+            LEFT JOIN tags t ON s.tag_id = t.id
+            WHERE s.type IN ('work', 'timer') AND s.completed = TRUE
+              AND COALESCE(s.day, date(s.started_at)) >= ?1
+              AND COALESCE(s.day, date(s.started_at)) < ?2
+            ORDER BY s.started_at, s.id
+            "#,
+        )?;
+        let sessions = stmt
+            .query_map(params![start, end], day_session)?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(sessions)
+    }
+
+    /// Get completed work with one tag between two dates (end exclusive).
+    /// Pruned days keep no tags, so only raw sessions count.
+    pub fn get_range_stats_for_tag(
+        &self,
+        start: &str,
+        end: &str,
+        tag_id: i64,
+    ) -> Result<DailyStats> {
+        let mut stmt = self.conn.prepare(
+            "SELECT COALESCE(SUM(MAX(duration_seconds, 0)), 0), COUNT(*)
+             FROM sessions
+             WHERE type IN ('work', 'timer') AND completed = TRUE AND tag_id = ?3
+               AND COALESCE(day, date(started_at)) >= ?1
+               AND COALESCE(day, date(started_at)) < ?2",
+        )?;
+
+        let (total_seconds, count): (i32, i32) = stmt
+            .query_row(params![start, end, tag_id], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })?;
+
+        Ok(DailyStats {
+            date: start.to_string(),
+            total_work_seconds: total_seconds,
+            sessions_completed: count,
+            longest_streak: 0,
+        })
+    }
+
+    /// Queue a session for the external time tracker (no-op if already queued)
+    pub fn queue_time_entry(&self, session_id: i64, provider: &str) -> Result<()> {
+        self.execute(
+            "INSERT OR IGNORE INTO time_entry_queue (session_id, provider, next_attempt_at)
+             VALUES (?1, ?2, ?3)",
+            params![session_id, provider, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// Queued time entries, oldest session first. With `due`, only the ones
+    /// whose next attempt is at or before that time and that the tracker
+    /// hasn't turned down for good.
+    pub fn get_time_entry_queue(&self, due: Option<DateTime<Utc>>) -> Result<Vec<QueuedTimeEntry>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT session_id, provider, attempts, next_attempt_at, last_error,
+                    dead_at IS NOT NULL
+             FROM time_entry_queue
+             WHERE ?1 IS NULL OR (next_attempt_at <= ?1 AND dead_at IS NULL)
+             ORDER BY session_id",
+        )?;
+        let entries = stmt
+            .query_map(params![due.map(|d| d.to_rfc3339())], |row| {
+                let next: String = row.get(3)?;
+                Ok(QueuedTimeEntry {
+                    session_id: row.get(0)?,
+                    provider: row.get(1)?,
+                    attempts: row.get(2)?,
+                    next_attempt_at: parse_timestamp(&next),
+                    last_error: row.get(4)?,
+                    dead: row.get(5)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(entries)
+    }
+
+    /// Take a queued entry to send it, until `until`. False when another
+    /// flush (in this process or another) holds it, so it isn't sent twice.
+    pub fn claim_time_entry(
+        &self,
+        session_id: i64,
+        now: DateTime<Utc>,
+        until: DateTime<Utc>,
+    ) -> Result<bool> {
+        let claimed = self.execute(
+            "UPDATE time_entry_queue SET claimed_until = ?3
+             WHERE session_id = ?1 AND (claimed_until IS NULL OR claimed_until <= ?2)",
+            params![session_id, now.to_rfc3339(), until.to_rfc3339()],
+        )?;
+        Ok(claimed == 1)
+    }
+
+    /// Record a failed upload and when to try again
+    pub fn time_entry_failed(
+        &self,
+        session_id: i64,
+        error: &str,
+        next_attempt_at: DateTime<Utc>,
+    ) -> Result<()> {
+        self.execute(
+            "UPDATE time_entry_queue
+             SET attempts = attempts + 1, last_error = ?2, next_attempt_at = ?3,
+                 claimed_until = NULL
+             WHERE session_id = ?1",
+            params![session_id, error, next_attempt_at.to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// Set an entry aside after the tracker turned it down in a way retrying
+    /// won't change, so it doesn't hold up the rest
+    pub fn time_entry_dead(&self, session_id: i64, error: &str) -> Result<()> {
+        self.execute(
+            "UPDATE time_entry_queue
+             SET attempts = attempts + 1, last_error = ?2, dead_at = ?3,
+                 claimed_until = NULL
+             WHERE session_id = ?1",
+            params![session_id, error, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// Remove a session from the queue, logging the tracker's entry ID if it
+    /// was sent
+    pub fn time_entry_done(&self, session_id: i64, entry: Option<&str>) -> Result<()> {
+        self.execute(
+            "DELETE FROM time_entry_queue WHERE session_id = ?1",
+            params![session_id],
+        )?;
+        if let Some(entry) = entry {
+            self.log_session_event(session_id, "time_entry", Some(entry))?;
+        }
+        Ok(())
+    }
+
+    /// Remember the weekly goals in effect for the week starting on `week_start`
+    pub fn set_goal_week(
+        &self,
+        week_start: &str,
+        minutes_goal: u32,
+        sessions_goal: u32,
+    ) -> Result<()> {
+        self.execute(
+            "INSERT OR REPLACE INTO goal_weeks (week_start, minutes_goal, sessions_goal)
+             VALUES (?1, ?2, ?3)",
+            params![week_start, minutes_goal, sessions_goal],
+        )?;
+        Ok(())
+    }
+
+    /// Plan `sessions` for a tag in the week starting on `week_start`; zero
+    /// removes the tag from that week's plan
+    pub fn set_plan_item(&self, week_start: &str, tag_id: i64, sessions: u32) -> Result<()> {
+        if sessions == 0 {
+            self.execute(
+                "DELETE FROM plan_items WHERE week_start = ?1 AND tag_id = ?2",
+                params![week_start, tag_id],
+            )?;
+        } else {
+            self.execute(
+                "INSERT OR REPLACE INTO plan_items (week_start, tag_id, sessions)
+                 VALUES (?1, ?2, ?3)",
+                params![week_start, tag_id, sessions],
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Remove the whole plan for a week; returns the number of tags it had
+    pub fn clear_plan(&self, week_start: &str) -> Result<usize> {
+        self.execute(
+            "DELETE FROM plan_items WHERE week_start = ?1",
+            params![week_start],
+        )
+    }
+
+    /// The plan for the week `[week_start, week_end)` as (tag, planned,
+    /// completed work sessions with the tag), largest plan first
+    pub fn get_plan(&self, week_start: &str, week_end: &str) -> Result<Vec<(Tag, u32, i32)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT t.id, t.name, t.color, p.sessions, COUNT(s.id)
+             FROM plan_items p
+             JOIN tags t ON t.id = p.tag_id
+             LEFT JOIN sessions s
+                 ON s.tag_id = p.tag_id
+                AND s.type IN ('work', 'timer') AND s.completed = TRUE
+                AND COALESCE(s.day, date(s.started_at)) >= ?1
+                AND COALESCE(s.day, date(s.started_at)) < ?2
+             WHERE p.week_start = ?1
+             GROUP BY p.tag_id
+             ORDER BY p.sessions DESC, t.name ASC",
+        )?;
+        let items = stmt
+            .query_map(params![week_start, week_end], |row| {
+                Ok((
+                    Tag {
+                        id: row.get(0)?,
+                        name: row.get(1)?,
+                        color: row.get(2)?,
+                    },
+                    row.get(3)?,
+                    row.get(4)?,
+                ))
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(items)
+    }
+
+    /// Recorded weekly goals as (week_start, minutes_goal, sessions_goal),
+    /// most recent first
+    pub fn get_goal_weeks(&self, limit: i32) -> Result<Vec<(String, u32, u32)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT week_start, minutes_goal, sessions_goal
+             FROM goal_weeks
+             ORDER BY week_start DESC
+             LIMIT ?1",
+        )?;
+        let weeks = stmt
+            .query_map(params![limit], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(weeks)
+    }
+
     /// Get monthly total (last 30 days)
     pub fn get_month_stats(&self) -> Result<DailyStats> {
         let mut stmt = self.conn.prepare(
-            "SELECT COALESCE(SUM(duration_seconds), 0), COUNT(*)
-             FROM sessions
-             WHERE date(started_at) >= date('now', '-30 days')
-               AND type = 'work'
-               AND completed = TRUE",
+            "SELECT COALESCE(SUM(total_seconds), 0), COALESCE(SUM(sessions), 0)
+             FROM work_days
+             WHERE date >= date(?1, '-30 days')",
         )?;
 
-        let (total_seconds, count): (i32, i32) =
-            stmt.query_row([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        let (total_seconds, count): (i32, i32) = stmt
+            .query_row(params![self.today_str()?], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })?;
 
         Ok(DailyStats {
             date: "Last 30 days".to_string(),
@@ -271,19 +1945,42 @@ impl Database {
         })
     }
 
+    /// Completed work timed in `mode` over the last `days` days, today
+    /// included; pruned days keep no mode, so only raw sessions count
+    pub fn get_mode_stats(&self, mode: SessionMode, days: i32) -> Result<DailyStats> {
+        let mut stmt = self.conn.prepare(
+            "SELECT COALESCE(SUM(MAX(duration_seconds, 0)), 0), COUNT(*)
+             FROM sessions
+             WHERE type = 'work' AND completed = TRUE AND mode = ?1
+               AND COALESCE(day, date(started_at)) >= date(?2, ?3)",
+        )?;
+
+        let (total_seconds, count): (i32, i32) = stmt.query_row(
+            params![mode.as_str(), self.today_str()?, format!("-{} days", days)],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+
+        Ok(DailyStats {
+            date: format!("Last {} days", days),
+            total_work_seconds: total_seconds,
+            sessions_completed: count,
+            longest_streak: 0,
+        })
+    }
+
     /// Get previous week's total (8-14 days ago)
     pub fn get_previous_week_stats(&self) -> Result<DailyStats> {
         let mut stmt = self.conn.prepare(
-            "SELECT COALESCE(SUM(duration_seconds), 0), COUNT(*)
-             FROM sessions
-             WHERE date(started_at) >= date('now', '-14 days')
-               AND date(started_at) < date('now', '-7 days')
-               AND type = 'work'
-               AND completed = TRUE",
+            "SELECT COALESCE(SUM(total_seconds), 0), COALESCE(SUM(sessions), 0)
+             FROM work_days
+             WHERE date >= date(?1, '-14 days')
+               AND date < date(?1, '-7 days')",
         )?;
 
-        let (total_seconds, count): (i32, i32) =
-            stmt.query_row([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        let (total_seconds, count): (i32, i32) = stmt
+            .query_row(params![self.today_str()?], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })?;
 
         Ok(DailyStats {
             date: "Previous 7 days".to_string(),
@@ -296,16 +1993,16 @@ impl Database {
     /// Get previous month's total (31-60 days ago)
     pub fn get_previous_month_stats(&self) -> Result<DailyStats> {
         let mut stmt = self.conn.prepare(
-            "SELECT COALESCE(SUM(duration_seconds), 0), COUNT(*)
-             FROM sessions
-             WHERE date(started_at) >= date('now', '-60 days')
-               AND date(started_at) < date('now', '-30 days')
-               AND type = 'work'
-               AND completed = TRUE",
+            "SELECT COALESCE(SUM(total_seconds), 0), COALESCE(SUM(sessions), 0)
+             FROM work_days
+             WHERE date >= date(?1, '-60 days')
+               AND date < date(?1, '-30 days')",
         )?;
 
-        let (total_seconds, count): (i32, i32) =
-            stmt.query_row([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        let (total_seconds, count): (i32, i32) = stmt
+            .query_row(params![self.today_str()?], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })?;
 
         Ok(DailyStats {
             date: "Previous 30 days".to_string(),
@@ -317,26 +2014,25 @@ impl Database {
 
     /// Get heatmap data for the last N weeks (returns all days including zeros)
     pub fn get_heatmap_data(&self, weeks: i32) -> Result<Vec<DailyStats>> {
-        use chrono::{Duration, Local};
+        use chrono::Duration;
 
         let days = weeks * 7;
-        let today = Local::now().date_naive();
+        let today = self.today()?;
 
         // Get actual data from DB
         let mut stmt = self.conn.prepare(
-            "SELECT date(started_at) as date,
-                    COALESCE(SUM(duration_seconds), 0) as total_seconds,
-                    COUNT(*) as sessions
-             FROM sessions
-             WHERE date(started_at) >= date('now', ?1)
-               AND type = 'work'
-               AND completed = TRUE
-             GROUP BY date(started_at)",
+            "SELECT date,
+                    SUM(total_seconds) as total_seconds,
+                    SUM(sessions) as sessions
+             FROM work_days
+             WHERE date >= date(?2, ?1)
+             GROUP BY date",
         )?;
 
         let offset = format!("-{} days", days);
+        let today_str = today.format("%Y-%m-%d").to_string();
         let db_stats: std::collections::HashMap<String, (i32, i32)> = stmt
-            .query_map(params![offset], |row| {
+            .query_map(params![offset, today_str], |row| {
                 let date: String = row.get(0)?;
                 let total: i32 = row.get(1)?;
                 let count: i32 = row.get(2)?;
@@ -366,14 +2062,10 @@ impl Database {
 
     /// Get streak information
     pub fn get_streak(&self) -> Result<StreakInfo> {
-        use chrono::{Duration, Local, NaiveDate};
-        use std::collections::HashSet;
-
         // Get all unique dates with completed work sessions
         let mut stmt = self.conn.prepare(
-            "SELECT DISTINCT date(started_at) as date
-             FROM sessions
-             WHERE type = 'work' AND completed = TRUE
+            "SELECT DISTINCT date
+             FROM work_days
              ORDER BY date DESC",
         )?;
 
@@ -382,75 +2074,33 @@ impl Database {
             .filter_map(|r| r.ok())
             .collect();
 
-        if dates.is_empty() {
-            return Ok(StreakInfo {
-                current: 0,
-                longest: 0,
-            });
-        }
-
-        let today = Local::now().date_naive();
-        let today_str = today.format("%Y-%m-%d").to_string();
+        Ok(streak_from_dates(&dates, self.today()?))
+    }
 
-        // Calculate current streak (from today backwards)
-        let mut current_streak = 0;
-        let mut check_date = today;
-
-        // Check if today has activity, if not check yesterday
-        if !dates.contains(&today_str) {
-            check_date = today - Duration::days(1);
-        }
-
-        while dates.contains(&check_date.format("%Y-%m-%d").to_string()) {
-            current_streak += 1;
-            check_date -= Duration::days(1);
-        }
-
-        // Calculate longest streak
-        let mut sorted_dates: Vec<NaiveDate> = dates
-            .iter()
-            .filter_map(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
-            .collect();
-        sorted_dates.sort();
-        sorted_dates.reverse();
-
-        let mut longest_streak = 0;
-        let mut temp_streak = 1;
-
-        for i in 0..sorted_dates.len().saturating_sub(1) {
-            let diff = (sorted_dates[i] - sorted_dates[i + 1]).num_days();
-            if diff == 1 {
-                temp_streak += 1;
-            } else {
-                longest_streak = longest_streak.max(temp_streak);
-                temp_streak = 1;
-            }
-        }
-        longest_streak = longest_streak.max(temp_streak);
-
-        Ok(StreakInfo {
-            current: current_streak,
-            longest: longest_streak,
-        })
-    }
-
-    /// Export all sessions to JSON format
-    pub fn export_to_json(&self) -> Result<String> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, started_at, ended_at, duration_seconds, type, completed
-             FROM sessions
-             ORDER BY started_at DESC",
-        )?;
+    /// Export the sessions `filter` picks to JSON format
+    pub fn export_to_json(&self, filter: &ExportFilter) -> Result<String> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT s.id, s.started_at, s.ended_at, s.duration_seconds, s.type, s.completed,
+                    s.notes, t.name, t.color
+             FROM sessions s
+             LEFT JOIN tags t ON s.tag_id = t.id
+             WHERE {}
+             ORDER BY s.started_at DESC",
+            ExportFilter::SQL
+        ))?;
 
         let sessions: Vec<serde_json::Value> = stmt
-            .query_map([], |row| {
+            .query_map(filter.params(), |row| {
                 Ok(serde_json::json!({
                     "id": row.get::<_, i64>(0)?,
                     "startedAt": row.get::<_, String>(1)?,
                     "endedAt": row.get::<_, Option<String>>(2)?,
                     "durationSeconds": row.get::<_, Option<i32>>(3)?,
                     "type": row.get::<_, String>(4)?,
-                    "completed": row.get::<_, bool>(5)?
+                    "completed": row.get::<_, bool>(5)?,
+                    "notes": row.get::<_, Option<String>>(6)?,
+                    "tag": row.get::<_, Option<String>>(7)?,
+                    "tagColor": row.get::<_, Option<String>>(8)?
                 }))
             })?
             .filter_map(|r| r.ok())
@@ -459,47 +2109,64 @@ impl Database {
         Ok(serde_json::to_string_pretty(&sessions)?)
     }
 
-    /// Export all sessions to CSV format
-    pub fn export_to_csv(&self) -> Result<String> {
-        let mut stmt = self.conn.prepare(
-            "SELECT id, started_at, ended_at, duration_seconds, type, completed
-             FROM sessions
-             ORDER BY started_at DESC",
-        )?;
-
-        let mut csv = String::from("id,startedAt,endedAt,durationSeconds,type,completed\n");
-
-        stmt.query_map([], |row| {
-            let id: i64 = row.get(0)?;
-            let started_at: String = row.get(1)?;
-            let ended_at: Option<String> = row.get(2)?;
-            let duration_seconds: Option<i32> = row.get(3)?;
-            let session_type: String = row.get(4)?;
-            let completed: bool = row.get(5)?;
-            Ok((
-                id,
-                started_at,
-                ended_at,
-                duration_seconds,
-                session_type,
-                completed,
-            ))
-        })?
-        .filter_map(|r| r.ok())
-        .for_each(
-            |(id, started_at, ended_at, duration_seconds, session_type, completed)| {
-                csv.push_str(&format!(
-                    "{},{},{},{},{},{}\n",
-                    id,
-                    started_at,
-                    ended_at.unwrap_or_default(),
-                    duration_seconds.map(|d| d.to_string()).unwrap_or_default(),
-                    session_type,
-                    completed
-                ));
-            },
-        );
+    /// Export the sessions `filter` picks to CSV (RFC 4180: fields with the
+    /// delimiter, quotes or line breaks are quoted, quotes doubled). `device`
+    /// is the machine the session was recorded on: `this_device` for sessions
+    /// recorded here, `cloud` for ones pulled in by sync, and empty when that
+    /// isn't known (sessions from before version 21, imported ones).
+    pub fn export_to_csv(
+        &self,
+        filter: &ExportFilter,
+        options: &CsvOptions,
+        this_device: &str,
+    ) -> Result<String> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT s.id, s.started_at, s.ended_at, s.duration_seconds, s.type, s.completed,
+                    t.name, s.notes,
+                    s.origin, t.color
+             FROM sessions s
+             LEFT JOIN tags t ON s.tag_id = t.id
+             WHERE {}
+             ORDER BY s.started_at DESC",
+            ExportFilter::SQL
+        ))?;
 
+        let mut writer = csv::WriterBuilder::new()
+            .delimiter(options.delimiter)
+            .terminator(csv::Terminator::CRLF)
+            .from_writer(Vec::new());
+        writer.write_record(CSV_COLUMNS)?;
+
+        let rows = stmt.query_map(filter.params(), |row| {
+            Ok([
+                row.get::<_, i64>(0)?.to_string(),
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<String>>(2)?.unwrap_or_default(),
+                row.get::<_, Option<i32>>(3)?
+                    .map(|d| d.to_string())
+                    .unwrap_or_default(),
+                row.get::<_, String>(4)?,
+                row.get::<_, bool>(5)?.to_string(),
+                row.get::<_, Option<String>>(6)?.unwrap_or_default(),
+                row.get::<_, Option<String>>(7)?.unwrap_or_default(),
+                match row.get::<_, Option<String>>(8)?.as_deref() {
+                    Some("local") => this_device.to_string(),
+                    Some("cloud") => "cloud".to_string(),
+                    _ => String::new(),
+                },
+                row.get::<_, Option<String>>(9)?.unwrap_or_default(),
+            ])
+        })?;
+        for row in rows {
+            writer.write_record(&row?)?;
+        }
+
+        let mut csv = if options.bom {
+            "\u{feff}".to_string()
+        } else {
+            String::new()
+        };
+        csv.push_str(&String::from_utf8(writer.into_inner()?)?);
         Ok(csv)
     }
 
@@ -507,7 +2174,7 @@ impl Database {
 
     /// Create a new tag
     pub fn create_tag(&self, name: &str, color: Option<&str>) -> Result<i64> {
-        self.conn.execute(
+        self.execute(
             "INSERT INTO tags (name, color) VALUES (?1, ?2)",
             params![name, color],
         )?;
@@ -566,67 +2233,666 @@ impl Database {
         Ok(tag)
     }
 
+    /// Durations and icon stored with a tag
+    pub fn get_tag_defaults(&self, tag_id: i64) -> Result<TagDefaults> {
+        let defaults = self
+            .conn
+            .query_row(
+                "SELECT work_minutes, break_minutes, icon FROM tags WHERE id = ?1",
+                params![tag_id],
+                |row| {
+                    Ok(TagDefaults {
+                        work_minutes: row.get(0)?,
+                        break_minutes: row.get(1)?,
+                        icon: row.get(2)?,
+                    })
+                },
+            )
+            .optional()?;
+        Ok(defaults.unwrap_or_default())
+    }
+
+    /// Store the durations and icon a tag brings with it
+    pub fn set_tag_defaults(&self, tag_id: i64, defaults: &TagDefaults) -> Result<()> {
+        self.execute(
+            "UPDATE tags SET work_minutes = ?1, break_minutes = ?2, icon = ?3 WHERE id = ?4",
+            params![
+                defaults.work_minutes,
+                defaults.break_minutes,
+                defaults.icon,
+                tag_id
+            ],
+        )?;
+        Ok(())
+    }
+
     /// Delete a tag (sets sessions with this tag to NULL)
     pub fn delete_tag(&self, tag_id: i64) -> Result<()> {
         // First, remove tag from sessions
-        self.conn.execute(
+        self.execute(
             "UPDATE sessions SET tag_id = NULL WHERE tag_id = ?1",
             params![tag_id],
         )?;
+        self.execute("DELETE FROM plan_items WHERE tag_id = ?1", params![tag_id])?;
         // Then delete the tag
-        self.conn
-            .execute("DELETE FROM tags WHERE id = ?1", params![tag_id])?;
+        self.execute("DELETE FROM tags WHERE id = ?1", params![tag_id])?;
         Ok(())
     }
 
     /// Update a tag
     pub fn update_tag(&self, tag_id: i64, name: &str, color: Option<&str>) -> Result<()> {
-        self.conn.execute(
+        self.execute(
             "UPDATE tags SET name = ?1, color = ?2 WHERE id = ?3",
             params![name, color, tag_id],
         )?;
         Ok(())
     }
 
-    /// Start a new session with optional tag
+    /// Add a task to the Planned column
+    pub fn create_task(&self, title: &str) -> Result<i64> {
+        self.execute(
+            "INSERT INTO tasks (title, status, created_at) VALUES (?1, 'planned', ?2)",
+            params![title, Utc::now().to_rfc3339()],
+        )?;
+        Ok(self.conn.last_insert_rowid())
+    }
+
+    /// Tasks for the "today" board: everything not done, plus tasks done today
+    pub fn get_board_tasks(&self) -> Result<Vec<Task>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, title, status, estimate, sessions_done FROM tasks
+             WHERE status != 'done' OR date(done_at) = date('now')
+             ORDER BY id",
+        )?;
+        let tasks = stmt
+            .query_map([], |row| {
+                Ok(Task {
+                    id: row.get(0)?,
+                    title: row.get(1)?,
+                    status: TaskStatus::from_str(&row.get::<_, String>(2)?),
+                    estimate: row.get(3)?,
+                    sessions_done: row.get(4)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(tasks)
+    }
+
+    /// Move a task to another column
+    pub fn set_task_status(&self, task_id: i64, status: TaskStatus) -> Result<()> {
+        let done_at = (status == TaskStatus::Done).then(|| Utc::now().to_rfc3339());
+        self.execute(
+            "UPDATE tasks SET status = ?1, done_at = ?2 WHERE id = ?3",
+            params![status.as_str(), done_at, task_id],
+        )?;
+        Ok(())
+    }
+
+    /// Set how many work sessions a task should take
+    pub fn set_task_estimate(&self, task_id: i64, estimate: Option<i32>) -> Result<()> {
+        self.execute(
+            "UPDATE tasks SET estimate = ?1 WHERE id = ?2",
+            params![estimate, task_id],
+        )?;
+        Ok(())
+    }
+
+    /// Delete a task (sessions bound to it keep their time, unbound)
+    pub fn delete_task(&self, task_id: i64) -> Result<()> {
+        self.execute(
+            "UPDATE sessions SET task_id = NULL WHERE task_id = ?1",
+            params![task_id],
+        )?;
+        self.execute("DELETE FROM tasks WHERE id = ?1", params![task_id])?;
+        Ok(())
+    }
+
+    /// Bind a session to a task; a planned task moves to Doing
+    pub fn bind_session_task(&self, session_id: i64, task_id: i64) -> Result<()> {
+        self.execute(
+            "UPDATE sessions SET task_id = ?1 WHERE id = ?2",
+            params![task_id, session_id],
+        )?;
+        self.execute(
+            "UPDATE tasks SET status = 'doing' WHERE id = ?1 AND status = 'planned'",
+            params![task_id],
+        )?;
+        Ok(())
+    }
+
+    /// Count a completed work session towards a task. The task moves to Done
+    /// once its estimate is reached. Returns the new status.
+    pub fn record_task_session(&self, task_id: i64) -> Result<TaskStatus> {
+        self.execute(
+            "UPDATE tasks SET sessions_done = sessions_done + 1 WHERE id = ?1",
+            params![task_id],
+        )?;
+        self.execute(
+            "UPDATE tasks SET status = 'done', done_at = ?1
+             WHERE id = ?2 AND status != 'done'
+               AND estimate IS NOT NULL AND sessions_done >= estimate",
+            params![Utc::now().to_rfc3339(), task_id],
+        )?;
+        let status: String = self.conn.query_row(
+            "SELECT status FROM tasks WHERE id = ?1",
+            params![task_id],
+            |row| row.get(0),
+        )?;
+        Ok(TaskStatus::from_str(&status))
+    }
+
+    /// Latest day with sessions and the offset its first session started
+    /// at, for days that outlast a change of offset
+    fn last_day(&self) -> Result<Option<(NaiveDate, i32)>> {
+        let row: Option<(String, i32)> = self
+            .conn
+            .query_row(
+                "SELECT day, utc_offset_minutes FROM sessions
+                 WHERE day = (SELECT MAX(day) FROM sessions)
+                   AND utc_offset_minutes IS NOT NULL
+                 ORDER BY started_at ASC LIMIT 1",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+        Ok(row.and_then(|(day, offset)| {
+            NaiveDate::parse_from_str(&day, "%Y-%m-%d")
+                .ok()
+                .map(|day| (day, offset))
+        }))
+    }
+
+    /// Day a session starting `at` with this offset counts towards
+    fn day_for(&self, at: DateTime<Utc>, offset_minutes: i32) -> Result<NaiveDate> {
+        let policy = days::policy();
+        let last_day = match policy {
+            DayPolicy::Sticky => self.last_day()?,
+            DayPolicy::Local | DayPolicy::Utc => None,
+        };
+        Ok(days::session_day(policy, at, offset_minutes, last_day))
+    }
+
+    /// The day a session started now would count towards
+    pub fn today(&self) -> Result<NaiveDate> {
+        self.day_for(clock::now_utc(), days::local_offset_minutes())
+    }
+
+    /// `today()` as YYYY-MM-DD, for date windows in queries
+    pub fn today_str(&self) -> Result<String> {
+        Ok(self.today()?.format("%Y-%m-%d").to_string())
+    }
+
+    /// Start a new classic session with optional tag and planned duration
     pub fn start_session_with_tag(
         &self,
         session_type: SessionType,
         tag_id: Option<i64>,
+        planned_seconds: Option<i32>,
+    ) -> Result<i64> {
+        self.start_session_in_mode(session_type, tag_id, planned_seconds, SessionMode::Classic)
+    }
+
+    /// Start a new session timed in `mode`
+    pub fn start_session_in_mode(
+        &self,
+        session_type: SessionType,
+        tag_id: Option<i64>,
+        planned_seconds: Option<i32>,
+        mode: SessionMode,
     ) -> Result<i64> {
-        let now = Utc::now();
-        self.conn.execute(
-            "INSERT INTO sessions (started_at, type, completed, tag_id) VALUES (?1, ?2, FALSE, ?3)",
-            params![now.to_rfc3339(), session_type.as_str(), tag_id],
+        let now = clock::now_utc();
+        let offset = days::local_offset_minutes();
+        let day = self.day_for(now, offset)?;
+        self.execute(
+            "INSERT INTO sessions
+                 (started_at, type, completed, tag_id, planned_seconds, utc_offset_minutes, day,
+                  mode, uuid, origin)
+             VALUES (?1, ?2, FALSE, ?3, ?4, ?5, ?6, ?7, ?8, 'local')",
+            params![
+                now.to_rfc3339(),
+                session_type.as_str(),
+                tag_id,
+                planned_seconds,
+                offset,
+                day.format("%Y-%m-%d").to_string(),
+                mode.as_str(),
+                new_uuid()
+            ],
         )?;
-        Ok(self.conn.last_insert_rowid())
+        let id = self.conn.last_insert_rowid();
+        let detail = planned_seconds.map(|p| format!("planned {}s", p));
+        self.log_session_event(id, "started", detail.as_deref())?;
+
+        // A clock set back between sessions starts this one before the last ended
+        let previous: Option<(i64, String)> = self
+            .conn
+            .query_row(
+                "SELECT id, COALESCE(ended_at, started_at) FROM sessions
+                 WHERE id < ?1 ORDER BY id DESC LIMIT 1",
+                params![id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+        if let Some((previous, ended_at)) = previous {
+            if (parse_timestamp(&ended_at) - now).num_seconds() > CLOCK_SLACK_SECONDS {
+                let detail = format!("started before session #{} ended", previous);
+                self.log_session_event(id, "clock_jump", Some(&detail))?;
+            }
+        }
+        Ok(id)
     }
 
-    /// Delete a session by ID
+    /// Delete a session by ID, with its interruptions
+    ///
+    /// The event trail is kept so that deleted sessions can still be inspected.
     pub fn delete_session(&self, session_id: i64) -> Result<()> {
-        self.conn
-            .execute("DELETE FROM sessions WHERE id = ?1", params![session_id])?;
+        let cloud_id = self.cloud_id_for(session_id)?;
+        self.execute(
+            "DELETE FROM interruptions WHERE session_id = ?1",
+            params![session_id],
+        )?;
+        self.execute("DELETE FROM sessions WHERE id = ?1", params![session_id])?;
+        self.log_session_event(session_id, "deleted", None)?;
+        if let Some(cloud_id) = cloud_id {
+            queue_sync(&self.conn, session_id, SyncOp::Delete, &cloud_id)?;
+        }
+        Ok(())
+    }
+
+    /// Combine two sessions that are really one block split by a crash or a
+    /// double start. The one that started first is kept, with the earliest
+    /// start, the latest end and the two durations added up; the other row
+    /// is removed. Both trails record the merge. Returns the kept id.
+    pub fn merge_sessions(&self, first: i64, second: i64) -> Result<i64> {
+        if first == second {
+            anyhow::bail!("Can't merge session #{} with itself", first);
+        }
+        let load = |id: i64| -> Result<(Session, Option<i64>, Option<String>)> {
+            self.conn
+                .query_row(
+                    "SELECT id, started_at, ended_at, duration_seconds, type, completed,
+                            tag_id, notes
+                     FROM sessions WHERE id = ?1",
+                    params![id],
+                    |row| {
+                        let session = Session {
+                            id: row.get(0)?,
+                            started_at: parse_timestamp(&row.get::<_, String>(1)?),
+                            ended_at: row
+                                .get::<_, Option<String>>(2)?
+                                .map(|s| parse_timestamp(&s)),
+                            duration_seconds: row.get(3)?,
+                            session_type: row.get(4)?,
+                            completed: row.get(5)?,
+                            notes: row.get(7)?,
+                        };
+                        Ok((session, row.get(6)?, row.get(7)?))
+                    },
+                )
+                .optional()?
+                .with_context(|| format!("Session #{} not found", id))
+        };
+        let (a, b) = (load(first)?, load(second)?);
+        // An open row may be the timer running now; removing it or giving
+        // it an end would break that session
+        if let Some(open) = [&a.0, &b.0].into_iter().find(|s| s.ended_at.is_none()) {
+            anyhow::bail!("Can't merge session #{}: it hasn't ended yet", open.id);
+        }
+        if a.0.session_type != b.0.session_type {
+            anyhow::bail!(
+                "Can't merge a {} session with a {} session",
+                a.0.session_type,
+                b.0.session_type
+            );
+        }
+        let (keep, gone) = if b.0.started_at < a.0.started_at {
+            (b, a)
+        } else {
+            (a, b)
+        };
+        let (kept, removed) = (&keep.0, &gone.0);
+        let removed_cloud_id = self.cloud_id_for(removed.id)?;
+
+        let ended_at = kept.ended_at.max(removed.ended_at);
+        let duration = match (kept.duration_seconds, removed.duration_seconds) {
+            (None, None) => None,
+            (x, y) => Some(x.unwrap_or(0).max(0) + y.unwrap_or(0).max(0)),
+        };
+        let notes = match (keep.2.as_deref(), gone.2.as_deref()) {
+            (Some(x), Some(y)) if !x.is_empty() && !y.is_empty() => Some(format!("{}\n{}", x, y)),
+            (Some(x), _) if !x.is_empty() => Some(x.to_string()),
+            (_, y) => y.map(str::to_string),
+        };
+
+        retry_on_busy(|| {
+            let tx = self.conn.unchecked_transaction()?;
+            tx.execute(
+                "UPDATE sessions
+                 SET ended_at = ?1, duration_seconds = ?2, completed = ?3,
+                     tag_id = COALESCE(tag_id, ?4), notes = ?5,
+                     paused_seconds = paused_seconds
+                         + (SELECT paused_seconds FROM sessions WHERE id = ?7)
+                 WHERE id = ?6",
+                params![
+                    ended_at.map(|t| t.to_rfc3339()),
+                    duration,
+                    kept.completed || removed.completed,
+                    gone.1,
+                    notes,
+                    kept.id,
+                    removed.id
+                ],
+            )?;
+            // XP and interruptions stay with the block; a queued time entry
+            // goes with the row
+            tx.execute(
+                "UPDATE xp_events SET session_id = ?1 WHERE session_id = ?2",
+                params![kept.id, removed.id],
+            )?;
+            tx.execute(
+                "UPDATE interruptions SET session_id = ?1 WHERE session_id = ?2",
+                params![kept.id, removed.id],
+            )?;
+            tx.execute(
+                "DELETE FROM time_entry_queue WHERE session_id = ?1",
+                params![removed.id],
+            )?;
+            tx.execute("DELETE FROM sessions WHERE id = ?1", params![removed.id])?;
+            tx.commit()
+        })?;
+
+        let detail = match duration {
+            Some(d) => format!("from #{}, duration {}s", removed.id, d),
+            None => format!("from #{}", removed.id),
+        };
+        self.log_session_event(kept.id, "merged", Some(&detail))?;
+        self.log_session_event(removed.id, "merged_into", Some(&format!("#{}", kept.id)))?;
+        self.queue_cloud_change(kept.id, SyncOp::Update)?;
+        if let Some(cloud_id) = removed_cloud_id {
+            queue_sync(&self.conn, removed.id, SyncOp::Delete, &cloud_id)?;
+        }
+        Ok(kept.id)
+    }
+
+    /// Add finished work sessions from another tracker in one transaction,
+    /// creating their tags as needed. A session starting at the same moment
+    /// as a work session already recorded (or earlier in `sessions`) is
+    /// skipped. With `dry_run` the transaction is rolled back. Returns how
+    /// many were added.
+    pub fn import_sessions(
+        &self,
+        sessions: &[ImportedSession],
+        source: &str,
+        dry_run: bool,
+    ) -> Result<usize> {
+        let policy = days::policy();
+        let created_at = Utc::now().to_rfc3339();
+        let detail = format!("from {}", source);
+        let added = retry_on_busy(|| {
+            let tx = self.conn.unchecked_transaction()?;
+            let mut added = 0;
+            for session in sessions {
+                let started_at = session.started_at.to_rfc3339();
+                let exists: bool = tx.query_row(
+                    "SELECT COUNT(*) > 0 FROM sessions
+                     WHERE type = 'work' AND datetime(started_at) = datetime(?1)",
+                    params![started_at],
+                    |row| row.get(0),
+                )?;
+                if exists {
+                    continue;
+                }
+                let tag_id = match &session.tag {
+                    Some(name) => {
+                        let id: Option<i64> = tx
+                            .query_row(
+                                "SELECT id FROM tags WHERE name = ?1",
+                                params![name],
+                                |row| row.get(0),
+                            )
+                            .optional()?;
+                        match id {
+                            Some(id) => Some(id),
+                            None => {
+                                tx.execute("INSERT INTO tags (name) VALUES (?1)", params![name])?;
+                                Some(tx.last_insert_rowid())
+                            }
+                        }
+                    }
+                    None => None,
+                };
+                let seconds = (session.ended_at - session.started_at).num_seconds() as i32;
+                let day =
+                    days::session_day(policy, session.started_at, session.utc_offset_minutes, None);
+                tx.execute(
+                    "INSERT INTO sessions
+                         (started_at, ended_at, duration_seconds, type, completed, tag_id,
+                          notes, utc_offset_minutes, day, uuid)
+                     VALUES (?1, ?2, ?3, 'work', ?4, ?5, ?6, ?7, ?8, ?9)",
+                    params![
+                        started_at,
+                        session.ended_at.to_rfc3339(),
+                        seconds,
+                        session.completed,
+                        tag_id,
+                        session.notes,
+                        session.utc_offset_minutes,
+                        day.format("%Y-%m-%d").to_string(),
+                        new_uuid()
+                    ],
+                )?;
+                tx.execute(
+                    "INSERT INTO session_events (session_id, event, detail, created_at)
+                     VALUES (?1, 'imported', ?2, ?3)",
+                    params![tx.last_insert_rowid(), detail, created_at],
+                )?;
+                added += 1;
+            }
+            if !dry_run {
+                tx.commit()?;
+            }
+            Ok(added)
+        })?;
+        Ok(added)
+    }
+
+    /// The cloud row a session is, or is about to be, uploaded as
+    fn cloud_id_for(&self, session_id: i64) -> Result<Option<String>> {
+        let cloud_id = self
+            .conn
+            .query_row(
+                "SELECT COALESCE(
+                     (SELECT cloud_id FROM sessions WHERE id = ?1),
+                     (SELECT cloud_id FROM sync_queue WHERE session_id = ?1))",
+                params![session_id],
+                |row| row.get::<_, Option<String>>(0),
+            )
+            .optional()?
+            .flatten();
+        Ok(cloud_id)
+    }
+
+    /// Queue a change to a session that is (or is going to be) in the cloud
+    fn queue_cloud_change(&self, session_id: i64, op: SyncOp) -> Result<()> {
+        if let Some(cloud_id) = self.cloud_id_for(session_id)? {
+            queue_sync(&self.conn, session_id, op, &cloud_id)?;
+        }
         Ok(())
     }
 
+    /// Get full details of a single session (any type, completed or not)
+    pub fn get_session_detail(&self, session_id: i64) -> Result<Option<SessionDetail>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT s.id, s.started_at, s.ended_at, s.duration_seconds, s.type, s.completed,
+                   s.planned_seconds, s.cloud_id, t.id, t.name, t.color, s.notes, s.uuid,
+                   s.paused_seconds, s.paused_at
+            FROM sessions s
+            LEFT JOIN tags t ON s.tag_id = t.id
+            WHERE s.id = ?1
+            "#,
+        )?;
+
+        let detail = stmt
+            .query_row(params![session_id], |row| {
+                let session = Session {
+                    id: row.get(0)?,
+                    started_at: parse_timestamp(&row.get::<_, String>(1)?),
+                    ended_at: row
+                        .get::<_, Option<String>>(2)?
+                        .map(|s| parse_timestamp(&s)),
+                    duration_seconds: row.get(3)?,
+                    session_type: row.get(4)?,
+                    completed: row.get(5)?,
+                    notes: row.get(11)?,
+                };
+                let tag_id: Option<i64> = row.get(8)?;
+                let tag = if let Some(id) = tag_id {
+                    Some(Tag {
+                        id,
+                        name: row.get(9)?,
+                        color: row.get(10)?,
+                    })
+                } else {
+                    None
+                };
+                Ok(SessionDetail {
+                    session,
+                    tag,
+                    planned_seconds: row.get(6)?,
+                    cloud_id: row.get(7)?,
+                    uuid: row.get(12)?,
+                    paused_seconds: row.get(13)?,
+                    paused_at: row
+                        .get::<_, Option<String>>(14)?
+                        .map(|s| parse_timestamp(&s)),
+                })
+            })
+            .optional()?;
+        Ok(detail)
+    }
+
+    /// Get the event trail of a session, oldest first
+    pub fn get_session_events(&self, session_id: i64) -> Result<Vec<SessionEvent>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, session_id, event, detail, created_at
+             FROM session_events
+             WHERE session_id = ?1
+             ORDER BY created_at ASC, id ASC",
+        )?;
+        let events = stmt
+            .query_map(params![session_id], |row| {
+                Ok(SessionEvent {
+                    id: row.get(0)?,
+                    session_id: row.get(1)?,
+                    event: row.get(2)?,
+                    detail: row.get(3)?,
+                    created_at: parse_timestamp(&row.get::<_, String>(4)?),
+                })
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(events)
+    }
+
     /// Update a session's tag
     pub fn update_session_tag(&self, session_id: i64, tag_id: Option<i64>) -> Result<()> {
-        self.conn.execute(
+        self.execute(
             "UPDATE sessions SET tag_id = ?1 WHERE id = ?2",
             params![tag_id, session_id],
         )?;
+        self.log_session_event(session_id, "retagged", None)?;
+        self.queue_cloud_change(session_id, SyncOp::Update)
+    }
+
+    /// Set a session's note; blank clears it
+    pub fn set_session_notes(&self, session_id: i64, notes: &str) -> Result<()> {
+        let notes = Some(notes.trim()).filter(|n| !n.is_empty());
+        self.execute(
+            "UPDATE sessions SET notes = ?1 WHERE id = ?2",
+            params![notes, session_id],
+        )?;
         Ok(())
     }
 
+    /// Completed work and timer sessions `filter` picks, with their day and
+    /// tag, newest first
+    pub fn get_filtered_sessions(&self, filter: &ExportFilter) -> Result<Vec<DaySession>> {
+        let mut stmt = self.conn.prepare(&format!(
+            "SELECT COALESCE(s.day, date(s.started_at)),
+                    s.id, s.started_at, s.ended_at, s.duration_seconds, s.type, s.completed,
+                    t.id, t.name, t.color, s.notes
+             FROM sessions s
+             LEFT JOIN tags t ON s.tag_id = t.id
+             WHERE s.type IN ('work', 'timer') AND s.completed = TRUE AND {}
+             ORDER BY s.started_at DESC",
+            ExportFilter::SQL
+        ))?;
+        let sessions = stmt
+            .query_map(filter.params(), day_session)?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(sessions)
+    }
+
+    /// Completed work sessions whose tag or notes match every word of `query`
+    /// (words match as prefixes), most recent first
+    pub fn search_sessions(&self, query: &str, limit: i32) -> Result<Vec<SearchHit>> {
+        let Some(fts_query) = fts_query(query) else {
+            return Ok(Vec::new());
+        };
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT s.id, s.started_at, s.ended_at, s.duration_seconds, s.type, s.completed,
+                   t.id, t.name, t.color, s.notes
+            FROM session_search
+            JOIN sessions s ON s.id = session_search.rowid
+            LEFT JOIN tags t ON s.tag_id = t.id
+            WHERE session_search MATCH ?1
+              AND s.type IN ('work', 'timer') AND s.completed = TRUE
+            ORDER BY s.started_at DESC
+            LIMIT ?2
+            "#,
+        )?;
+
+        let hits = stmt
+            .query_map(params![fts_query, limit], |row| {
+                let session = Session {
+                    id: row.get(0)?,
+                    started_at: parse_timestamp(&row.get::<_, String>(1)?),
+                    ended_at: row
+                        .get::<_, Option<String>>(2)?
+                        .map(|s| parse_timestamp(&s)),
+                    duration_seconds: row.get(3)?,
+                    session_type: row.get(4)?,
+                    completed: row.get(5)?,
+                    notes: row.get(9)?,
+                };
+                let tag = match row.get::<_, Option<i64>>(6)? {
+                    Some(id) => Some(Tag {
+                        id,
+                        name: row.get(7)?,
+                        color: row.get(8)?,
+                    }),
+                    None => None,
+                };
+                Ok(SearchHit { session, tag })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(hits)
+    }
+
     /// Get recent completed work sessions
     pub fn get_recent_sessions(&self, limit: i32) -> Result<Vec<(Session, Option<Tag>)>> {
         let mut stmt = self.conn.prepare(
             r#"
             SELECT s.id, s.started_at, s.ended_at, s.duration_seconds, s.type, s.completed,
-                   t.id, t.name, t.color
+                   t.id, t.name, t.color, s.notes
             FROM sessions s
             LEFT JOIN tags t ON s.tag_id = t.id
-            WHERE s.type = 'work' AND s.completed = TRUE
+            WHERE s.type IN ('work', 'timer') AND s.completed = TRUE
             ORDER BY s.started_at DESC
             LIMIT ?1
             "#,
@@ -647,6 +2913,7 @@ impl Database {
                     duration_seconds: row.get(3)?,
                     session_type: row.get(4)?,
                     completed: row.get(5)?,
+                    notes: row.get(9)?,
                 };
                 let tag_id: Option<i64> = row.get(6)?;
                 let tag = if let Some(id) = tag_id {
@@ -666,18 +2933,60 @@ impl Database {
         Ok(sessions)
     }
 
+    /// Every session row (any type, ended or not), oldest first
+    pub fn get_all_sessions(&self) -> Result<Vec<Session>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT id, started_at, ended_at, duration_seconds, type, completed, notes
+             FROM sessions
+             ORDER BY started_at ASC, id ASC",
+        )?;
+        let sessions = stmt
+            .query_map([], |row| {
+                Ok(Session {
+                    id: row.get(0)?,
+                    started_at: parse_timestamp(&row.get::<_, String>(1)?),
+                    ended_at: row
+                        .get::<_, Option<String>>(2)?
+                        .map(|s| parse_timestamp(&s)),
+                    duration_seconds: row.get(3)?,
+                    session_type: row.get(4)?,
+                    completed: row.get(5)?,
+                    notes: row.get(6)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(sessions)
+    }
+
+    /// Sessions flagged as clock jumps when they were recorded: (id, detail)
+    pub fn get_clock_jump_flags(&self) -> Result<Vec<(i64, String)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT e.session_id, COALESCE(e.detail, '')
+             FROM session_events e JOIN sessions s ON s.id = e.session_id
+             WHERE e.event = 'clock_jump'
+             ORDER BY e.id",
+        )?;
+        let flags = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(flags)
+    }
+
     /// Get statistics grouped by tag
     pub fn get_stats_by_tag(&self, days: i32) -> Result<Vec<(Option<Tag>, i32, i32)>> {
         let offset = format!("-{} days", days);
+        let today = self.today_str()?;
         let mut stmt = self.conn.prepare(
             r#"
             SELECT t.id, t.name, t.color,
-                   COALESCE(SUM(s.duration_seconds), 0) as total_seconds,
+                   COALESCE(SUM(MAX(s.duration_seconds, 0)), 0) as total_seconds,
                    COUNT(s.id) as sessions
             FROM sessions s
             LEFT JOIN tags t ON s.tag_id = t.id
-            WHERE date(s.started_at) >= date('now', ?1)
-              AND s.type = 'work'
+            WHERE COALESCE(s.day, date(s.started_at)) >= date(?2, ?1)
+              AND s.type IN ('work', 'timer')
               AND s.completed = TRUE
             GROUP BY s.tag_id
             ORDER BY total_seconds DESC
@@ -685,7 +2994,7 @@ impl Database {
         )?;
 
         let stats = stmt
-            .query_map(params![offset], |row| {
+            .query_map(params![offset, today], |row| {
                 let tag_id: Option<i64> = row.get(0)?;
                 let tag = if let Some(id) = tag_id {
                     Some(Tag {
@@ -705,4 +3014,1454 @@ impl Database {
 
         Ok(stats)
     }
+
+    /// Completed work by tag for dates in `[start, end)`, largest first:
+    /// (tag, seconds, sessions)
+    pub fn get_range_stats_by_tag(
+        &self,
+        start: &str,
+        end: &str,
+    ) -> Result<Vec<(Option<Tag>, i32, i32)>> {
+        let mut stmt = self.conn.prepare(
+            r#"
+            SELECT t.id, t.name, t.color,
+                   COALESCE(SUM(MAX(s.duration_seconds, 0)), 0) as total_seconds,
+                   COUNT(s.id) as sessions
+            FROM sessions s
+            LEFT JOIN tags t ON s.tag_id = t.id
+            WHERE COALESCE(s.day, date(s.started_at)) >= ?1
+              AND COALESCE(s.day, date(s.started_at)) < ?2
+              AND s.type IN ('work', 'timer')
+              AND s.completed = TRUE
+            GROUP BY s.tag_id
+            ORDER BY total_seconds DESC
+            "#,
+        )?;
+
+        let stats = stmt
+            .query_map(params![start, end], |row| {
+                let tag = match row.get::<_, Option<i64>>(0)? {
+                    Some(id) => Some(Tag {
+                        id,
+                        name: row.get(1)?,
+                        color: row.get(2)?,
+                    }),
+                    None => None,
+                };
+                Ok((tag, row.get(3)?, row.get(4)?))
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(stats)
+    }
+
+    /// Work sessions completed and skipped for dates in `[start, end)`.
+    /// Sessions from before the event trail have no 'skipped' event, so an
+    /// ended, uncompleted one of those counts as skipped.
+    pub fn get_range_completion(&self, start: &str, end: &str) -> Result<(i32, i32)> {
+        let counts = self.conn.query_row(
+            "SELECT COALESCE(SUM(s.completed = TRUE), 0),
+                    COALESCE(SUM(s.completed = FALSE AND (
+                        EXISTS (
+                            SELECT 1 FROM session_events e
+                            WHERE e.session_id = s.id AND e.event = 'skipped'
+                        )
+                        OR (s.ended_at IS NOT NULL AND NOT EXISTS (
+                            SELECT 1 FROM session_events e WHERE e.session_id = s.id
+                        ))
+                    )), 0)
+             FROM sessions s
+             WHERE s.type IN ('work', 'timer')
+               AND COALESCE(s.day, date(s.started_at)) >= ?1
+               AND COALESCE(s.day, date(s.started_at)) < ?2",
+            params![start, end],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        Ok(counts)
+    }
+
+    /// Average length in seconds of work sessions completed for dates in
+    /// `[start, end)`; None without any
+    pub fn get_range_average_session(&self, start: &str, end: &str) -> Result<Option<i32>> {
+        let average: Option<f64> = self.conn.query_row(
+            "SELECT AVG(MAX(duration_seconds, 0))
+             FROM sessions
+             WHERE type IN ('work', 'timer') AND completed = TRUE
+               AND COALESCE(day, date(started_at)) >= ?1
+               AND COALESCE(day, date(started_at)) < ?2",
+            params![start, end],
+            |row| row.get(0),
+        )?;
+        Ok(average.map(|seconds| seconds.round() as i32))
+    }
+
+    /// Average work per day of the week for dates in `[start, end)`, over
+    /// the days with any focus: (weekday, average seconds, days), Monday
+    /// first and weekdays without focus left out
+    pub fn get_range_weekday_averages(
+        &self,
+        start: &str,
+        end: &str,
+    ) -> Result<Vec<(Weekday, i32, i32)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT (CAST(strftime('%w', date) AS INTEGER) + 6) % 7 AS weekday,
+                    SUM(total_seconds) / COUNT(DISTINCT date),
+                    COUNT(DISTINCT date)
+             FROM work_days
+             WHERE date >= ?1 AND date < ?2 AND sessions > 0
+             GROUP BY weekday
+             ORDER BY weekday",
+        )?;
+        let rows = stmt
+            .query_map(params![start, end], |row| {
+                Ok((row.get::<_, u8>(0)?, row.get(1)?, row.get(2)?))
+            })?
+            .filter_map(|r| r.ok())
+            .filter_map(|(weekday, seconds, days)| {
+                Weekday::try_from(weekday).ok().map(|d| (d, seconds, days))
+            })
+            .collect();
+        Ok(rows)
+    }
+
+    /// Today's completed work by tag, largest first: (tag, seconds)
+    pub fn get_today_stats_by_tag(&self) -> Result<Vec<(Option<Tag>, i32)>> {
+        Ok(self
+            .get_stats_by_tag(0)?
+            .into_iter()
+            .map(|(tag, seconds, _)| (tag, seconds))
+            .collect())
+    }
+
+    /// Get completed work per local hour of day over the last N days (all 24 hours)
+    ///
+    /// Hours are local to where each session started; sessions from before
+    /// offsets were recorded use this machine's time zone. Only raw
+    /// sessions carry a start time, so days folded into daily totals by the
+    /// retention policy are not included.
+    pub fn get_stats_by_hour(&self, days: i32) -> Result<Vec<(u32, i32, i32)>> {
+        let offset = format!("-{} days", days);
+        let today = self.today_str()?;
+        let mut stmt = self.conn.prepare(
+            "SELECT CAST(strftime('%H', CASE
+                        WHEN utc_offset_minutes IS NULL THEN datetime(started_at, 'localtime')
+                        ELSE datetime(started_at, printf('%+d minutes', utc_offset_minutes))
+                    END) AS INTEGER) as hour,
+                    COALESCE(SUM(MAX(duration_seconds, 0)), 0) as total_seconds,
+                    COUNT(*) as sessions
+             FROM sessions
+             WHERE COALESCE(day, date(started_at)) >= date(?2, ?1)
+               AND type IN ('work', 'timer')
+               AND completed = TRUE
+             GROUP BY hour",
+        )?;
+
+        let mut hours: Vec<(u32, i32, i32)> = (0..24).map(|h| (h, 0, 0)).collect();
+        let rows = stmt
+            .query_map(params![offset, today], |row| {
+                Ok((
+                    row.get::<_, u32>(0)?,
+                    row.get::<_, i32>(1)?,
+                    row.get::<_, i32>(2)?,
+                ))
+            })?
+            .filter_map(|r| r.ok());
+        for (hour, seconds, sessions) in rows {
+            if let Some(bucket) = hours.get_mut(hour as usize) {
+                *bucket = (hour, seconds, sessions);
+            }
+        }
+
+        Ok(hours)
+    }
+
+    /// Save the journal note for a date (YYYY-MM-DD), replacing any earlier one
+    pub fn set_journal(&self, date: &str, note: &str) -> Result<()> {
+        self.execute(
+            "INSERT OR REPLACE INTO journal (date, note, updated_at) VALUES (?1, ?2, ?3)",
+            params![date, note, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// Remove the journal note for a date; returns whether there was one
+    pub fn delete_journal(&self, date: &str) -> Result<bool> {
+        Ok(self.execute("DELETE FROM journal WHERE date = ?1", params![date])? > 0)
+    }
+
+    /// Journal note for a date (YYYY-MM-DD)
+    pub fn get_journal(&self, date: &str) -> Result<Option<String>> {
+        let note = self
+            .conn
+            .query_row(
+                "SELECT note FROM journal WHERE date = ?1",
+                params![date],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(note)
+    }
+
+    /// Journal notes from `since` (YYYY-MM-DD) on as (date, note), newest first
+    pub fn get_journal_since(&self, since: &str) -> Result<Vec<(String, String)>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT date, note FROM journal WHERE date >= ?1 ORDER BY date DESC")?;
+        let notes = stmt
+            .query_map(params![since], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(notes)
+    }
+
+    /// Note an interruption of a work session at `at`, with an optional
+    /// one-character category
+    pub fn add_interruption(
+        &self,
+        session_id: i64,
+        category: Option<char>,
+        at: DateTime<Utc>,
+    ) -> Result<()> {
+        self.execute(
+            "INSERT INTO interruptions (session_id, category, created_at) VALUES (?1, ?2, ?3)",
+            params![session_id, category.map(String::from), at.to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// A session's interruptions, oldest first
+    pub fn get_session_interruptions(&self, session_id: i64) -> Result<Vec<Interruption>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT session_id, category, created_at FROM interruptions
+             WHERE session_id = ?1 ORDER BY created_at, id",
+        )?;
+        let interruptions = stmt
+            .query_map(params![session_id], |row| {
+                Ok(Interruption {
+                    session_id: row.get(0)?,
+                    category: row
+                        .get::<_, Option<String>>(1)?
+                        .and_then(|c| c.chars().next()),
+                    created_at: parse_timestamp(&row.get::<_, String>(2)?),
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(interruptions)
+    }
+
+    /// How many interruptions each of `session_ids` has, leaving out the
+    /// ones with none
+    pub fn count_interruptions(&self, session_ids: &[i64]) -> Result<HashMap<i64, u32>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT COUNT(*) FROM interruptions WHERE session_id = ?1")?;
+        let mut counts = HashMap::new();
+        for &id in session_ids {
+            let count: u32 = stmt.query_row(params![id], |row| row.get(0))?;
+            if count > 0 {
+                counts.insert(id, count);
+            }
+        }
+        Ok(counts)
+    }
+
+    /// Interruptions of sessions on days `start..=end` (YYYY-MM-DD) per
+    /// category as (category, count), most frequent first
+    pub fn get_interruptions_by_category(
+        &self,
+        start: &str,
+        end: &str,
+    ) -> Result<Vec<(Option<char>, i32)>> {
+        let mut stmt = self.conn.prepare(
+            "SELECT i.category, COUNT(*) FROM interruptions i
+             JOIN sessions s ON s.id = i.session_id
+             WHERE COALESCE(s.day, date(s.started_at)) BETWEEN ?1 AND ?2
+             GROUP BY i.category
+             ORDER BY COUNT(*) DESC, i.category",
+        )?;
+        let rows = stmt
+            .query_map(params![start, end], |row| {
+                Ok((
+                    row.get::<_, Option<String>>(0)?
+                        .and_then(|c| c.chars().next()),
+                    row.get(1)?,
+                ))
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(rows)
+    }
+
+    /// Award XP, optionally for a session
+    pub fn add_xp(&self, session_id: Option<i64>, reason: &str, xp: i64) -> Result<()> {
+        self.execute(
+            "INSERT INTO xp_events (session_id, reason, xp, created_at) VALUES (?1, ?2, ?3, ?4)",
+            params![session_id, reason, xp, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// All XP awarded so far
+    pub fn total_xp(&self) -> Result<i64> {
+        let total =
+            self.conn
+                .query_row("SELECT COALESCE(SUM(xp), 0) FROM xp_events", [], |row| {
+                    row.get(0)
+                })?;
+        Ok(total)
+    }
+
+    /// XP per reason as (reason, xp)
+    pub fn xp_by_reason(&self) -> Result<Vec<(String, i64)>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT reason, SUM(xp) FROM xp_events GROUP BY reason")?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(rows)
+    }
+
+    /// Every XP award, oldest first
+    pub fn xp_log(&self) -> Result<Vec<XpEvent>> {
+        let mut stmt = self
+            .conn
+            .prepare("SELECT session_id, reason, xp, created_at FROM xp_events ORDER BY id ASC")?;
+        let events = stmt
+            .query_map([], |row| {
+                Ok(XpEvent {
+                    session_id: row.get(0)?,
+                    reason: row.get(1)?,
+                    xp: row.get(2)?,
+                    created_at: parse_timestamp(&row.get::<_, String>(3)?),
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(events)
+    }
+
+    /// Read a value from the metadata table
+    pub fn get_metadata(&self, key: &str) -> Result<Option<String>> {
+        let value = self
+            .conn
+            .query_row(
+                "SELECT value FROM metadata WHERE key = ?1",
+                params![key],
+                |row| row.get(0),
+            )
+            .ok();
+        Ok(value)
+    }
+
+    /// Write a value to the metadata table
+    pub fn set_metadata(&self, key: &str, value: &str) -> Result<()> {
+        self.execute(
+            "INSERT OR REPLACE INTO metadata (key, value) VALUES (?1, ?2)",
+            params![key, value],
+        )?;
+        Ok(())
+    }
+
+    /// Write a consistent copy of the database to `path`
+    pub fn backup_to(&self, path: &std::path::Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        self.execute("VACUUM INTO ?1", params![path.to_string_lossy()])?;
+        Ok(())
+    }
+
+    /// Count raw sessions and distinct work days that started before `cutoff` (YYYY-MM-DD)
+    pub fn count_sessions_before(&self, cutoff: &str) -> Result<(i64, i64)> {
+        let counts = self.conn.query_row(
+            "SELECT COUNT(*),
+                    COUNT(DISTINCT CASE WHEN type IN ('work', 'timer') AND completed = TRUE
+                                        THEN COALESCE(day, date(started_at)) END)
+             FROM sessions
+             WHERE COALESCE(day, date(started_at)) < ?1",
+            params![cutoff],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+        Ok(counts)
+    }
+
+    /// Remove raw sessions (and their event trail) that started before `cutoff`.
+    ///
+    /// With `keep_totals`, completed work is first folded into daily_stats so
+    /// stats and streaks still see those days. Returns the number of sessions removed.
+    pub fn prune_sessions_before(&self, cutoff: &str, keep_totals: bool) -> Result<usize> {
+        let removed = retry_on_busy(|| {
+            let tx = self.conn.unchecked_transaction()?;
+
+            if keep_totals {
+                tx.execute(
+                    "INSERT INTO daily_stats (date, total_work_seconds, sessions_completed)
+                     SELECT COALESCE(day, date(started_at)),
+                            COALESCE(SUM(MAX(duration_seconds, 0)), 0), COUNT(*)
+                     FROM sessions
+                     WHERE COALESCE(day, date(started_at)) < ?1 AND type IN ('work', 'timer') AND completed = TRUE
+                     GROUP BY COALESCE(day, date(started_at))
+                     ON CONFLICT(date) DO UPDATE SET
+                         total_work_seconds = total_work_seconds + excluded.total_work_seconds,
+                         sessions_completed = sessions_completed + excluded.sessions_completed",
+                    params![cutoff],
+                )?;
+            }
+
+            tx.execute(
+                "DELETE FROM session_events WHERE session_id IN
+                     (SELECT id FROM sessions WHERE COALESCE(day, date(started_at)) < ?1)",
+                params![cutoff],
+            )?;
+            tx.execute(
+                "DELETE FROM interruptions WHERE session_id IN
+                     (SELECT id FROM sessions WHERE COALESCE(day, date(started_at)) < ?1)",
+                params![cutoff],
+            )?;
+            let removed = tx.execute(
+                "DELETE FROM sessions WHERE COALESCE(day, date(started_at)) < ?1",
+                params![cutoff],
+            )?;
+
+            tx.commit()?;
+            Ok(removed)
+        })?;
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn busy() -> rusqlite::Error {
+        rusqlite::Error::SqliteFailure(rusqlite::ffi::Error::new(rusqlite::ffi::SQLITE_BUSY), None)
+    }
+
+    #[test]
+    fn test_schema_objects_and_version() {
+        assert_eq!(MIGRATIONS.last().unwrap().0, SCHEMA_VERSION);
+        let db = Database::open_in_memory().unwrap();
+        db.create_tag("writing", None).unwrap();
+        assert_eq!(db.schema_version().unwrap(), SCHEMA_VERSION);
+
+        let objects = db.schema_objects().unwrap();
+        let find = |name: &str| objects.iter().find(|o| o.name == name);
+        assert_eq!(find("tags").unwrap().rows, Some(1));
+        assert_eq!(find("sessions").unwrap().rows, Some(0));
+        assert_eq!(find("work_days").unwrap().kind, "view");
+        assert_eq!(find("work_days").unwrap().rows, None);
+        assert!(find("session_search").is_some());
+        assert!(find("session_search_data").is_none());
+        assert!(find("idx_sessions_day").is_some());
+    }
+
+    #[test]
+    fn test_retry_on_busy_recovers() {
+        let mut calls = 0;
+        let result = retry_on_busy(|| {
+            calls += 1;
+            if calls < 3 {
+                Err(busy())
+            } else {
+                Ok(calls)
+            }
+        });
+        assert_eq!(result.unwrap(), 3);
+    }
+
+    #[test]
+    fn test_retry_on_busy_gives_up() {
+        let mut calls = 0;
+        let result: rusqlite::Result<()> = retry_on_busy(|| {
+            calls += 1;
+            Err(busy())
+        });
+        assert!(result.is_err());
+        assert_eq!(calls, BUSY_RETRIES + 1);
+    }
+
+    #[test]
+    fn test_retry_on_busy_ignores_other_errors() {
+        let mut calls = 0;
+        let result: rusqlite::Result<()> = retry_on_busy(|| {
+            calls += 1;
+            Err(rusqlite::Error::QueryReturnedNoRows)
+        });
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn test_csv_export_round_trips() {
+        let db = Database::open_in_memory().unwrap();
+        let tag = db.create_tag("client \"A\", ops", Some("#ff8800")).unwrap();
+        let note = "line one\nline two; \"quoted\"";
+        let local = completed_work(&db, Some(tag), Some(note));
+        // Pulled in by sync from another device
+        let pulled = completed_work(&db, None, None);
+        db.execute(
+            "UPDATE sessions SET cloud_id = 'c1', origin = 'cloud' WHERE id = ?1",
+            params![pulled],
+        )
+        .unwrap();
+        // Uploaded before version 21: where it was recorded isn't known
+        let legacy = completed_work(&db, None, None);
+        db.execute(
+            "UPDATE sessions SET cloud_id = 'c2', origin = NULL WHERE id = ?1",
+            params![legacy],
+        )
+        .unwrap();
+
+        let options = CsvOptions {
+            delimiter: b';',
+            bom: true,
+        };
+        let csv = db
+            .export_to_csv(&ExportFilter::default(), &options, "laptop")
+            .unwrap();
+        let body = csv.strip_prefix('\u{feff}').unwrap();
+        let mut reader = csv::ReaderBuilder::new()
+            .delimiter(b';')
+            .from_reader(body.as_bytes());
+        assert_eq!(
+            reader.headers().unwrap(),
+            &csv::StringRecord::from(CSV_COLUMNS.to_vec())
+        );
+        let rows: Vec<csv::StringRecord> = reader.records().map(|r| r.unwrap()).collect();
+        assert_eq!(rows.len(), 3);
+
+        let row = |id: i64| rows.iter().find(|r| r[0] == id.to_string()).unwrap();
+        let local = row(local);
+        assert_eq!(&local[3], "1500");
+        assert_eq!(&local[4], "work");
+        assert_eq!(&local[5], "true");
+        assert_eq!(&local[6], "client \"A\", ops");
+        assert_eq!(&local[7], note);
+        assert_eq!(&local[8], "laptop");
+        assert_eq!(&local[9], "#ff8800");
+        let pulled = row(pulled);
+        assert_eq!(
+            (&pulled[6], &pulled[7], &pulled[8], &pulled[9]),
+            ("", "", "cloud", "")
+        );
+        assert_eq!(&row(legacy)[8], "");
+
+        // Defaults: commas, no BOM
+        let csv = db
+            .export_to_csv(&ExportFilter::default(), &CsvOptions::default(), "laptop")
+            .unwrap();
+        assert!(csv.starts_with("id,startedAt,endedAt,"));
+        assert!(csv.contains("\"client \"\"A\"\", ops\""));
+
+        let json: Vec<serde_json::Value> =
+            serde_json::from_str(&db.export_to_json(&ExportFilter::default()).unwrap()).unwrap();
+        let tagged = json.iter().find(|s| !s["tag"].is_null()).unwrap();
+        assert_eq!(tagged["tag"], "client \"A\", ops");
+        assert_eq!(tagged["tagColor"], "#ff8800");
+    }
+
+    #[test]
+    fn test_sessions_and_days_in_range() {
+        let db = Database::open_in_memory().unwrap();
+        let writing = db.create_tag("writing", None).unwrap();
+        for (day, time, tag) in [
+            ("2026-09-30", "09:00", None),
+            ("2026-10-02", "14:00", Some(writing)),
+            ("2026-10-02", "09:00", None),
+            ("2026-11-01", "09:00", None),
+        ] {
+            let id = completed_work(&db, tag, None);
+            db.execute(
+                "UPDATE sessions SET started_at = ?1, day = ?2 WHERE id = ?3",
+                params![format!("{}T{}:00+00:00", day, time), day, id],
+            )
+            .unwrap();
+        }
+        // An interrupted session doesn't count
+        let interrupted = db.start_session(SessionType::Work).unwrap();
+        db.interrupt_session(interrupted, Utc::now(), 300).unwrap();
+        db.execute(
+            "UPDATE sessions SET day = '2026-10-02' WHERE id = ?1",
+            params![interrupted],
+        )
+        .unwrap();
+
+        let october = db
+            .get_sessions_in_range("2026-10-01", "2026-11-01")
+            .unwrap();
+        assert_eq!(october.len(), 2);
+        assert!(october.iter().all(|s| s.date == "2026-10-02"));
+        // Oldest first, with the tag
+        assert!(october[0].tag.is_none());
+        assert_eq!(october[1].tag.as_ref().unwrap().name, "writing");
+        assert_eq!(october[1].session.duration_seconds, Some(1500));
+
+        let days = db.get_range_days("2026-10-01", "2026-11-01").unwrap();
+        assert_eq!(days.len(), 1);
+        assert_eq!(days[0].date, "2026-10-02");
+        assert_eq!(days[0].sessions_completed, 2);
+        assert_eq!(days[0].total_work_seconds, 3000);
+    }
+
+    #[test]
+    fn test_range_completion_counts_legacy_skips() {
+        let db = Database::open_in_memory().unwrap();
+        completed_work(&db, None, None);
+        // Skipped with an event trail
+        let skipped = db.start_session(SessionType::Work).unwrap();
+        db.log_session_event(skipped, "skipped", None).unwrap();
+        // Interrupted: ended but not skipped
+        let interrupted = db.start_session(SessionType::Work).unwrap();
+        db.interrupt_session(interrupted, Utc::now(), 300).unwrap();
+        // From before the event trail: ended, not completed, no events
+        let legacy = db.start_session(SessionType::Work).unwrap();
+        db.execute(
+            "UPDATE sessions SET ended_at = started_at WHERE id = ?1",
+            params![legacy],
+        )
+        .unwrap();
+        db.execute(
+            "DELETE FROM session_events WHERE session_id = ?1",
+            params![legacy],
+        )
+        .unwrap();
+        // Still running, without events either
+        let open = db.start_session(SessionType::Work).unwrap();
+        db.execute(
+            "DELETE FROM session_events WHERE session_id = ?1",
+            params![open],
+        )
+        .unwrap();
+
+        let today = db.today().unwrap();
+        let day = |date: NaiveDate| date.format("%Y-%m-%d").to_string();
+        let (start, end) = (day(today), day(today + chrono::Duration::days(1)));
+        assert_eq!(db.get_range_completion(&start, &end).unwrap(), (1, 2));
+    }
+
+    #[test]
+    fn test_session_notes_and_the_notes_filter() {
+        let db = Database::open_in_memory().unwrap();
+        let auth = completed_work(&db, None, None);
+        let other = completed_work(&db, None, Some("wrote docs"));
+        completed_work(&db, None, None);
+
+        db.set_session_notes(auth, "  Fixed the AUTH bug ").unwrap();
+        let recent = db.get_recent_sessions(10).unwrap();
+        let noted = recent.iter().find(|(s, _)| s.id == auth).unwrap();
+        assert_eq!(noted.0.notes.as_deref(), Some("Fixed the AUTH bug"));
+
+        let with = |text: &str| -> Vec<i64> {
+            let filter = ExportFilter {
+                notes: Some(text.to_string()),
+                ..ExportFilter::default()
+            };
+            let mut ids: Vec<i64> = db
+                .get_filtered_sessions(&filter)
+                .unwrap()
+                .iter()
+                .map(|s| s.session.id)
+                .collect();
+            ids.sort();
+            ids
+        };
+        assert_eq!(with("auth"), vec![auth]);
+        // Empty matches any note
+        assert_eq!(with(""), vec![auth, other]);
+
+        let filter = ExportFilter {
+            notes: Some("docs".to_string()),
+            ..ExportFilter::default()
+        };
+        let json: Vec<serde_json::Value> =
+            serde_json::from_str(&db.export_to_json(&filter).unwrap()).unwrap();
+        assert_eq!(json.len(), 1);
+        assert_eq!(json[0]["notes"], "wrote docs");
+
+        // A blank note clears it
+        db.set_session_notes(auth, "  ").unwrap();
+        assert_eq!(with("auth"), Vec::<i64>::new());
+    }
+
+    #[test]
+    fn test_interruptions_follow_their_session() {
+        let db = Database::open_in_memory().unwrap();
+        let first = completed_work(&db, None, None);
+        let second = completed_work(&db, None, None);
+        let quiet = completed_work(&db, None, None);
+        db.add_interruption(first, Some('\''), Utc::now()).unwrap();
+        db.add_interruption(first, Some('-'), Utc::now()).unwrap();
+        db.add_interruption(second, Some('-'), Utc::now()).unwrap();
+        db.add_interruption(second, None, Utc::now()).unwrap();
+
+        let list = db.get_session_interruptions(first).unwrap();
+        assert_eq!(
+            list.iter().map(|i| i.category).collect::<Vec<_>>(),
+            vec![Some('\''), Some('-')]
+        );
+        let counts = db.count_interruptions(&[first, second, quiet]).unwrap();
+        assert_eq!(counts.get(&first), Some(&2));
+        assert_eq!(counts.get(&quiet), None);
+
+        let today = db.today_str().unwrap();
+        assert_eq!(
+            db.get_interruptions_by_category(&today, &today).unwrap(),
+            vec![(Some('-'), 2), (None, 1), (Some('\''), 1)]
+        );
+
+        db.merge_sessions(first, second).unwrap();
+        assert_eq!(db.get_session_interruptions(first).unwrap().len(), 4);
+        db.delete_session(first).unwrap();
+        assert!(db
+            .get_interruptions_by_category(&today, &today)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_pauses_add_up_apart_from_the_duration() {
+        let db = Database::open_in_memory().unwrap();
+        let id = db
+            .start_session_with_tag(SessionType::Work, None, Some(1500))
+            .unwrap();
+        let started = db
+            .get_session_detail(id)
+            .unwrap()
+            .unwrap()
+            .session
+            .started_at;
+        let at = |minutes| started + chrono::Duration::minutes(minutes);
+
+        assert!(!db.unpause_session(id, at(1)).unwrap());
+        assert!(db.pause_session(id, at(5)).unwrap());
+        assert!(!db.pause_session(id, at(6)).unwrap());
+        assert!(db.unpause_session(id, at(8)).unwrap());
+        // Paused when it ends: the pause lasts until then
+        db.pause_session(id, at(20)).unwrap();
+        let detail = db.get_session_detail(id).unwrap().unwrap();
+        assert_eq!(detail.paused_seconds_at(at(25)), 3 * 60 + 5 * 60);
+        db.recover_session(id, at(25), 1500).unwrap();
+
+        let detail = db.get_session_detail(id).unwrap().unwrap();
+        assert_eq!(detail.paused_at, None);
+        assert_eq!(detail.paused_seconds, 3 * 60 + 5 * 60);
+        assert_eq!(detail.session.duration_seconds, Some(1500));
+        let events: Vec<String> = db
+            .get_session_events(id)
+            .unwrap()
+            .into_iter()
+            .map(|e| e.event)
+            .collect();
+        assert_eq!(&events[1..3], ["paused", "unpaused"]);
+
+        let today = db.today_str().unwrap();
+        assert_eq!(
+            db.get_paused_seconds(&today, &today).unwrap(),
+            detail.paused_seconds
+        );
+        // Totals still count the time run, not the time paused
+        assert_eq!(db.get_today_stats().unwrap().total_work_seconds, 1500);
+    }
+
+    #[test]
+    fn test_export_filters() {
+        let db = Database::open_in_memory().unwrap();
+        let work = db.create_tag("work", None).unwrap();
+        let ids: Vec<i64> = [
+            ("2026-09-30", Some(work)),
+            ("2026-10-01", Some(work)),
+            ("2026-10-15", None),
+            ("2026-10-31", Some(work)),
+            ("2026-11-01", Some(work)),
+        ]
+        .into_iter()
+        .map(|(day, tag)| {
+            let id = completed_work(&db, tag, None);
+            db.execute(
+                "UPDATE sessions SET started_at = ?1, day = ?2 WHERE id = ?3",
+                params![format!("{}T09:00:00+00:00", day), day, id],
+            )
+            .unwrap();
+            id
+        })
+        .collect();
+
+        let date = |s: &str| NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap();
+        let october = ExportFilter {
+            from: Some(date("2026-10-01")),
+            to: Some(date("2026-10-31")),
+            tag: Some("work".to_string()),
+            notes: None,
+        };
+        let json: Vec<serde_json::Value> =
+            serde_json::from_str(&db.export_to_json(&october).unwrap()).unwrap();
+        let exported: Vec<i64> = json.iter().map(|s| s["id"].as_i64().unwrap()).collect();
+        assert_eq!(exported, vec![ids[3], ids[1]]);
+
+        let csv = db
+            .export_to_csv(&october, &CsvOptions::default(), "laptop")
+            .unwrap();
+        assert_eq!(csv.lines().count(), 3);
+
+        // Open-ended ranges, and no tag filter
+        let since = ExportFilter {
+            from: Some(date("2026-10-15")),
+            ..ExportFilter::default()
+        };
+        let json: Vec<serde_json::Value> =
+            serde_json::from_str(&db.export_to_json(&since).unwrap()).unwrap();
+        assert_eq!(json.len(), 3);
+        let all = db.export_to_json(&ExportFilter::default()).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Vec<serde_json::Value>>(&all)
+                .unwrap()
+                .len(),
+            5
+        );
+    }
+
+    fn completed_work(db: &Database, tag_id: Option<i64>, notes: Option<&str>) -> i64 {
+        let id = db
+            .start_session_with_tag(SessionType::Work, tag_id, Some(1500))
+            .unwrap();
+        db.complete_session(id, 1500).unwrap();
+        db.execute(
+            "UPDATE sessions SET notes = ?1 WHERE id = ?2",
+            params![notes, id],
+        )
+        .unwrap();
+        id
+    }
+
+    fn search_ids(db: &Database, query: &str) -> Vec<i64> {
+        let mut ids: Vec<i64> = db
+            .search_sessions(query, 50)
+            .unwrap()
+            .iter()
+            .map(|hit| hit.session.id)
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn test_import_skips_sessions_already_recorded() {
+        let db = Database::open_in_memory().unwrap();
+        db.create_tag("writing", None).unwrap();
+        let start = Utc::now() - chrono::Duration::days(3);
+        let imported = |minutes: i64, tag: Option<&str>| ImportedSession {
+            started_at: start + chrono::Duration::minutes(minutes),
+            ended_at: start + chrono::Duration::minutes(minutes + 25),
+            utc_offset_minutes: 0,
+            tag: tag.map(str::to_string),
+            notes: Some("from Toggl".to_string()),
+            completed: true,
+        };
+        let batch = vec![
+            imported(0, Some("writing")),
+            imported(30, Some("ops")),
+            imported(30, Some("ops")),
+        ];
+
+        assert_eq!(db.import_sessions(&batch, "Toggl", true).unwrap(), 2);
+        assert!(db.get_all_sessions().unwrap().is_empty());
+        assert!(db.get_tag_by_name("ops").unwrap().is_none());
+
+        assert_eq!(db.import_sessions(&batch, "Toggl", false).unwrap(), 2);
+        assert_eq!(db.import_sessions(&batch, "Toggl", false).unwrap(), 0);
+        let sessions = db.get_recent_sessions(10).unwrap();
+        assert_eq!(sessions.len(), 2);
+        assert!(sessions.iter().all(|(s, _)| s.completed
+            && s.duration_seconds == Some(1500)
+            && s.notes.as_deref() == Some("from Toggl")));
+        let tags: Vec<_> = sessions
+            .iter()
+            .map(|(_, t)| t.clone().unwrap().name)
+            .collect();
+        assert_eq!(tags, ["ops", "writing"]);
+        let events = db.get_session_events(sessions[0].0.id).unwrap();
+        assert_eq!(events[0].event, "imported");
+        assert_eq!(db.get_all_tags().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_sync_queue_keeps_one_change_per_session() {
+        let db = Database::open_in_memory().unwrap();
+        let finished = || {
+            let id = db.start_session(SessionType::Work).unwrap();
+            db.complete_session(id, 1500).unwrap();
+            id
+        };
+        let queue = || get_sync_queue(db.connection(), None).unwrap();
+
+        // Never uploaded nor queued: nothing to tell the cloud
+        let local = finished();
+        db.update_session_tag(local, None).unwrap();
+        db.delete_session(local).unwrap();
+        assert!(queue().is_empty());
+
+        // Waiting to go up: a retag rides along with the insert
+        let pending = finished();
+        queue_sync(db.connection(), pending, SyncOp::Insert, "cloud-a").unwrap();
+        db.update_session_tag(pending, None).unwrap();
+        assert_eq!(queue()[0].op, SyncOp::Insert);
+
+        // Uploaded: a retag is an update, and deleting it replaces that
+        let uploaded = finished();
+        db.execute(
+            "UPDATE sessions SET cloud_id = 'cloud-b' WHERE id = ?1",
+            params![uploaded],
+        )
+        .unwrap();
+        db.update_session_tag(uploaded, None).unwrap();
+        sync_failed(
+            db.connection(),
+            uploaded,
+            "offline",
+            Utc::now() + chrono::Duration::hours(1),
+        )
+        .unwrap();
+        let entry = queue().pop().unwrap();
+        assert_eq!((entry.op, entry.attempts), (SyncOp::Update, 1));
+        assert!(get_sync_queue(db.connection(), Some(Utc::now()))
+            .unwrap()
+            .iter()
+            .all(|q| q.session_id != uploaded));
+        db.delete_session(uploaded).unwrap();
+        let entry = queue().pop().unwrap();
+        assert_eq!(
+            (entry.op, entry.cloud_id.as_str(), entry.attempts),
+            (SyncOp::Delete, "cloud-b", 0)
+        );
+
+        // Deleting one still waiting to go up removes it from the cloud too,
+        // in case an earlier attempt got through
+        db.delete_session(pending).unwrap();
+        assert_eq!(queue()[0].op, SyncOp::Delete);
+        sync_done(db.connection(), pending).unwrap();
+        assert_eq!(queue().len(), 1);
+    }
+
+    #[test]
+    fn test_dead_outbox_entries_wait_for_a_manual_sync() {
+        let db = Database::open_in_memory().unwrap();
+        let id = completed_work(&db, None, None);
+        queue_sync(db.connection(), id, SyncOp::Insert, "cloud-a").unwrap();
+        sync_dead(db.connection(), id, "400 Bad Request").unwrap();
+
+        assert!(get_sync_queue(db.connection(), Some(Utc::now()))
+            .unwrap()
+            .is_empty());
+        let entry = get_sync_queue(db.connection(), None)
+            .unwrap()
+            .pop()
+            .unwrap();
+        assert!(entry.dead);
+        assert_eq!(entry.attempts, 1);
+        assert_eq!(entry.last_error.as_deref(), Some("400 Bad Request"));
+
+        // A delete replaces it and is sent as usual
+        queue_sync(db.connection(), id, SyncOp::Delete, "cloud-a").unwrap();
+        let due = get_sync_queue(db.connection(), Some(Utc::now())).unwrap();
+        assert_eq!(due.len(), 1);
+        assert!(!due[0].dead);
+    }
+
+    #[test]
+    fn test_merge_split_sessions() {
+        let db = Database::open_in_memory().unwrap();
+        let writing = db.create_tag("writing", None).unwrap();
+        let first = completed_work(&db, None, Some("outline"));
+        let second = completed_work(&db, Some(writing), Some("draft"));
+        db.execute(
+            "UPDATE sessions SET started_at = '2026-03-02T09:00:00+00:00',
+                                 ended_at = '2026-03-02T09:10:00+00:00',
+                                 duration_seconds = 600, day = '2026-03-02'
+             WHERE id = ?1",
+            params![first],
+        )
+        .unwrap();
+        db.execute(
+            "UPDATE sessions SET started_at = '2026-03-02T09:11:00+00:00',
+                                 ended_at = '2026-03-02T09:26:00+00:00',
+                                 duration_seconds = 900, day = '2026-03-02',
+                                 paused_seconds = 60
+             WHERE id = ?1",
+            params![second],
+        )
+        .unwrap();
+        let other_day = db.get_date_stats("2026-03-02").unwrap();
+        assert_eq!(other_day.sessions_completed, 2);
+
+        // Either order keeps the one that started first
+        assert_eq!(db.merge_sessions(second, first).unwrap(), first);
+        let detail = db.get_session_detail(first).unwrap().unwrap();
+        assert_eq!(detail.session.duration_seconds, Some(1500));
+        assert_eq!(
+            detail.session.ended_at.unwrap().to_rfc3339(),
+            "2026-03-02T09:26:00+00:00"
+        );
+        assert_eq!(detail.paused_seconds, 60);
+        assert_eq!(detail.tag.unwrap().id, writing);
+        assert!(db.get_session_detail(second).unwrap().is_none());
+        assert_eq!(search_ids(&db, "draft"), vec![first]);
+
+        let stats = db.get_date_stats("2026-03-02").unwrap();
+        assert_eq!(stats.sessions_completed, 1);
+        assert_eq!(stats.total_work_seconds, 1500);
+
+        let events = |id| {
+            db.get_session_events(id)
+                .unwrap()
+                .into_iter()
+                .map(|e| e.event)
+                .collect::<Vec<_>>()
+        };
+        assert!(events(first).contains(&"merged".to_string()));
+        assert!(events(second).contains(&"merged_into".to_string()));
+
+        assert!(db.merge_sessions(first, first).is_err());
+        assert!(db.merge_sessions(first, second).is_err());
+        let brk = db.start_session(SessionType::ShortBreak).unwrap();
+        assert!(db.merge_sessions(first, brk).is_err());
+    }
+
+    #[test]
+    fn test_merge_refuses_an_open_session() {
+        let db = Database::open_in_memory().unwrap();
+        let closed = completed_work(&db, None, None);
+        let open = db.start_session(SessionType::Work).unwrap();
+
+        for (x, y) in [(closed, open), (open, closed)] {
+            let err = db.merge_sessions(x, y).unwrap_err();
+            assert!(err.to_string().contains("hasn't ended"));
+        }
+        let running = db.get_session_detail(open).unwrap().unwrap();
+        assert_eq!(running.session.ended_at, None);
+        assert!(db.get_session_detail(closed).unwrap().is_some());
+    }
+
+    #[test]
+    fn test_task_moves_through_board() {
+        let db = Database::open_in_memory().unwrap();
+        let task = db.create_task("Write report").unwrap();
+        let other = db.create_task("Inbox zero").unwrap();
+        db.set_task_estimate(task, Some(2)).unwrap();
+
+        let session = db.start_session(SessionType::Work).unwrap();
+        db.bind_session_task(session, task).unwrap();
+        assert_eq!(db.record_task_session(task).unwrap(), TaskStatus::Doing);
+        assert_eq!(db.record_task_session(task).unwrap(), TaskStatus::Done);
+
+        // Done today stays on the board; done earlier drops off
+        db.set_task_status(other, TaskStatus::Done).unwrap();
+        db.execute(
+            "UPDATE tasks SET done_at = '2020-01-01T00:00:00+00:00' WHERE id = ?1",
+            params![other],
+        )
+        .unwrap();
+        let board = db.get_board_tasks().unwrap();
+        assert_eq!(board.len(), 1);
+        assert_eq!(board[0].status, TaskStatus::Done);
+        assert_eq!(board[0].sessions_done, 2);
+
+        db.delete_task(task).unwrap();
+        let bound: Option<i64> = db
+            .conn
+            .query_row(
+                "SELECT task_id FROM sessions WHERE id = ?1",
+                params![session],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(bound, None);
+    }
+
+    #[test]
+    fn test_journal_notes() {
+        let db = Database::open_in_memory().unwrap();
+        db.set_journal("2026-03-01", "first draft").unwrap();
+        db.set_journal("2026-03-01", "shipped the parser").unwrap();
+        db.set_journal("2026-03-03", "reviews").unwrap();
+
+        assert_eq!(
+            db.get_journal("2026-03-01").unwrap().as_deref(),
+            Some("shipped the parser")
+        );
+        assert_eq!(
+            db.get_journal_since("2026-03-02").unwrap(),
+            vec![("2026-03-03".to_string(), "reviews".to_string())]
+        );
+        assert!(db.delete_journal("2026-03-03").unwrap());
+        assert!(!db.delete_journal("2026-03-03").unwrap());
+
+        // A note alone doesn't make a work day
+        assert_eq!(db.get_streak().unwrap().longest, 0);
+    }
+
+    #[test]
+    fn test_clock_jumps_are_flagged_and_skipped() {
+        let db = Database::open_in_memory().unwrap();
+        let id = completed_work(&db, None, None);
+        let negative = db.start_session(SessionType::Work).unwrap();
+        db.complete_session(negative, -90).unwrap();
+        assert_eq!(
+            db.get_clock_jump_flags().unwrap(),
+            vec![(
+                negative,
+                "negative duration -90s recorded as 0s".to_string()
+            )]
+        );
+
+        // The clock went back an hour: the next session starts before the last ended
+        let later = (Utc::now() + chrono::Duration::hours(1)).to_rfc3339();
+        db.execute(
+            "UPDATE sessions SET ended_at = ?1 WHERE id = ?2",
+            params![later, negative],
+        )
+        .unwrap();
+        let overlapping = db.start_session(SessionType::Work).unwrap();
+        let flags = db.get_clock_jump_flags().unwrap();
+        assert_eq!(
+            flags.last(),
+            Some(&(
+                overlapping,
+                format!("started before session #{} ended", negative)
+            ))
+        );
+
+        // Rows written before the guard: a negative total and a future day
+        db.execute(
+            "UPDATE sessions SET duration_seconds = -600 WHERE id = ?1",
+            params![negative],
+        )
+        .unwrap();
+        let future = completed_work(&db, None, None);
+        db.execute(
+            "UPDATE sessions SET started_at = '2999-01-01T09:00:00+00:00', day = NULL
+             WHERE id = ?1",
+            params![future],
+        )
+        .unwrap();
+        let today = db.get_today_stats().unwrap();
+        assert_eq!(today.total_work_seconds, 1500);
+        assert_eq!(today.sessions_completed, 2);
+        assert_eq!(
+            db.get_date_stats("2999-01-01").unwrap().sessions_completed,
+            0
+        );
+        assert!(db.get_all_sessions().unwrap().iter().any(|s| s.id == id));
+    }
+
+    #[test]
+    fn test_fts_query() {
+        assert_eq!(
+            fts_query("billing refac").as_deref(),
+            Some("\"billing\"* \"refac\"*")
+        );
+        assert_eq!(
+            fts_query("a\"OR\" NEAR(").as_deref(),
+            Some("\"aOR\"* \"NEAR(\"*")
+        );
+        assert_eq!(fts_query("  "), None);
+    }
+
+    #[test]
+    fn test_search_sessions_by_notes_and_tag() {
+        let db = Database::open_in_memory().unwrap();
+        let work = db.create_tag("work", None).unwrap();
+        let billing = completed_work(&db, Some(work), Some("Billing refactor: split invoices"));
+        let parser = completed_work(&db, None, Some("parser tests"));
+        let tagged = completed_work(&db, Some(work), None);
+
+        assert_eq!(search_ids(&db, "billing"), vec![billing]);
+        assert_eq!(search_ids(&db, "REFAC"), vec![billing]);
+        assert_eq!(search_ids(&db, "billing parser"), Vec::<i64>::new());
+        assert_eq!(search_ids(&db, "work"), vec![billing, tagged]);
+        assert_eq!(search_ids(&db, ""), Vec::<i64>::new());
+
+        // The index follows tag renames, retagging and deletes
+        db.update_tag(work, "client-a", None).unwrap();
+        assert_eq!(search_ids(&db, "client"), vec![billing, tagged]);
+        db.update_session_tag(parser, Some(work)).unwrap();
+        assert_eq!(search_ids(&db, "client"), vec![billing, parser, tagged]);
+        db.delete_session(billing).unwrap();
+        assert_eq!(search_ids(&db, "billing"), Vec::<i64>::new());
+
+        // Unfinished sessions aren't results
+        db.start_session_with_tag(SessionType::Work, Some(work), None)
+            .unwrap();
+        assert_eq!(search_ids(&db, "client").len(), 2);
+    }
+
+    #[test]
+    fn test_rebuild_search_indexes_existing_sessions() {
+        let db = Database::open_in_memory().unwrap();
+        let id = completed_work(&db, None, Some("billing"));
+        db.execute("DELETE FROM session_search", []).unwrap();
+        assert!(search_ids(&db, "billing").is_empty());
+
+        db.rebuild_search().unwrap();
+        assert_eq!(search_ids(&db, "billing"), vec![id]);
+    }
+
+    #[test]
+    fn test_sessions_count_towards_the_day_they_started() {
+        let db = Database::open_in_memory().unwrap();
+        let id = completed_work(&db, None, None);
+        let today = db.today().unwrap().format("%Y-%m-%d").to_string();
+        let (offset, day): (i32, String) = db
+            .conn
+            .query_row(
+                "SELECT utc_offset_minutes, day FROM sessions WHERE id = ?1",
+                params![id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(offset, days::local_offset_minutes());
+        assert_eq!(day, today);
+
+        // 23:30 UTC on the 1st was 08:30 on the 2nd in Tokyo
+        let tokyo = completed_work(&db, None, None);
+        db.execute(
+            "UPDATE sessions SET started_at = '2026-03-01T23:30:00+00:00',
+                                 utc_offset_minutes = 540, day = '2026-03-02'
+             WHERE id = ?1",
+            params![tokyo],
+        )
+        .unwrap();
+        // A session from before offsets were recorded keeps its UTC date
+        let legacy = completed_work(&db, None, None);
+        db.execute(
+            "UPDATE sessions SET started_at = '2026-03-01T23:45:00+00:00',
+                                 utc_offset_minutes = NULL, day = NULL
+             WHERE id = ?1",
+            params![legacy],
+        )
+        .unwrap();
+
+        assert_eq!(
+            db.get_date_stats("2026-03-02").unwrap().sessions_completed,
+            1
+        );
+        assert_eq!(
+            db.get_date_stats("2026-03-01").unwrap().sessions_completed,
+            1
+        );
+        db.execute("DELETE FROM sessions WHERE id = ?1", params![id])
+            .unwrap();
+        let hours = db.get_stats_by_hour(100_000).unwrap();
+        // The legacy one lands on this machine's local hour, wherever that is
+        assert!(hours[8].2 >= 1);
+        assert_eq!(hours.iter().map(|h| h.2).sum::<i32>(), 2);
+    }
+
+    #[test]
+    fn test_stats_by_hour_buckets_across_local_hour_boundaries() {
+        let db = Database::open_in_memory().unwrap();
+        for (started_at, offset) in [
+            // 08:59 and 09:00 in Tokyo, a minute apart
+            ("2026-03-01T23:59:00+00:00", 540),
+            ("2026-03-02T00:00:00+00:00", 540),
+            // 23:30 the day before in the Azores
+            ("2026-03-02T00:30:00+00:00", -60),
+            // 05:59 and 06:30 in India, half an hour off UTC
+            ("2026-03-02T00:29:00+00:00", 330),
+            ("2026-03-02T01:00:00+00:00", 330),
+        ] {
+            let id = completed_work(&db, None, None);
+            db.execute(
+                "UPDATE sessions SET started_at = ?1, utc_offset_minutes = ?2 WHERE id = ?3",
+                params![started_at, offset, id],
+            )
+            .unwrap();
+        }
+
+        let hours = db.get_stats_by_hour(100_000).unwrap();
+        let busy: Vec<(u32, i32, i32)> = hours.into_iter().filter(|h| h.2 > 0).collect();
+        assert_eq!(
+            busy,
+            vec![
+                (5, 1500, 1),
+                (6, 1500, 1),
+                (8, 1500, 1),
+                (9, 1500, 1),
+                (23, 1500, 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_work_days_view_is_rebuilt_for_session_days() {
+        let db = Database::open_in_memory().unwrap();
+        // A version 5 database: no day columns and the old view
+        db.conn
+            .execute_batch(
+                "DROP VIEW work_days;
+             DROP INDEX idx_sessions_day;
+             ALTER TABLE sessions DROP COLUMN day;
+             ALTER TABLE sessions DROP COLUMN utc_offset_minutes;
+             CREATE VIEW work_days AS
+                 SELECT date(started_at) AS date, 0 AS total_seconds, 0 AS sessions
+                 FROM sessions GROUP BY date(started_at);
+             PRAGMA user_version = 5;",
+            )
+            .unwrap();
+        db.init_schema().unwrap();
+
+        completed_work(&db, None, None);
+        assert_eq!(db.get_today_stats().unwrap().total_work_seconds, 1500);
+    }
+
+    #[test]
+    fn test_sessions_get_uuids_and_old_ones_are_backfilled() {
+        let db = Database::open_in_memory().unwrap();
+        let old = completed_work(&db, None, None);
+        let uploaded = completed_work(&db, None, None);
+        // A version 13 database: no uuids yet, one session in the cloud
+        db.conn
+            .execute_batch(&format!(
+                "DROP INDEX idx_sessions_uuid;
+                 UPDATE sessions SET uuid = NULL;
+                 UPDATE sessions SET cloud_id = 'cloud-1' WHERE id = {};
+                 PRAGMA user_version = 13;",
+                uploaded
+            ))
+            .unwrap();
+        db.init_schema().unwrap();
+
+        let uuid = |id| db.get_session_detail(id).unwrap().unwrap().uuid.unwrap();
+        assert_eq!(uuid(uploaded), "cloud-1");
+        assert_eq!(uuid(old).len(), 36);
+        let new = completed_work(&db, None, None);
+        assert_ne!(uuid(new), uuid(old));
+        // Running the migration again keeps them
+        let before = uuid(old);
+        db.init_schema().unwrap();
+        assert_eq!(uuid(old), before);
+    }
+
+    #[test]
+    fn test_tasks_come_with_version_19() {
+        let db = Database::open_in_memory().unwrap();
+        let session = completed_work(&db, None, None);
+        // A version 18 database, from before the today board
+        db.conn
+            .execute_batch("DROP TABLE tasks; PRAGMA user_version = 18;")
+            .unwrap();
+        db.init_schema().unwrap();
+        assert_eq!(db.schema_version().unwrap(), SCHEMA_VERSION);
+
+        let task = db.create_task("Draft the intro").unwrap();
+        db.bind_session_task(session, task).unwrap();
+        let board = db.get_board_tasks().unwrap();
+        assert_eq!(board.len(), 1);
+        assert_eq!(board[0].status, TaskStatus::Doing);
+    }
+
+    #[test]
+    fn test_journal_comes_with_version_20() {
+        let db = Database::open_in_memory().unwrap();
+        // A version 19 database, from before the journal
+        db.conn
+            .execute_batch("DROP TABLE journal; PRAGMA user_version = 19;")
+            .unwrap();
+        db.init_schema().unwrap();
+        assert_eq!(db.schema_version().unwrap(), SCHEMA_VERSION);
+
+        db.set_journal("2026-10-16", "shipped the parser").unwrap();
+        assert_eq!(
+            db.get_journal("2026-10-16").unwrap().as_deref(),
+            Some("shipped the parser")
+        );
+        assert_eq!(db.get_journal("2026-10-17").unwrap(), None);
+    }
+
+    #[test]
+    fn test_sync_metadata_is_created_with_the_schema() {
+        let db = Database::open_in_memory().unwrap();
+        // A version 15 database, from before sync_metadata was in the schema
+        db.conn
+            .execute_batch("DROP TABLE sync_metadata; PRAGMA user_version = 15;")
+            .unwrap();
+        db.init_schema().unwrap();
+        assert_eq!(crate::sync::get_last_sync(&db.conn).unwrap(), None);
+        assert!(db
+            .schema_objects()
+            .unwrap()
+            .iter()
+            .any(|o| o.name == "sync_metadata"));
+    }
+
+    #[test]
+    fn test_timer_sessions_after_widening_the_type_check() {
+        let db = Database::open_in_memory().unwrap();
+        // A version 9 database, whose sessions.type doesn't take 'timer'
+        db.conn
+            .execute_batch(
+                "DROP VIEW work_days;
+             DROP TABLE sessions;
+             CREATE TABLE sessions (
+                 id INTEGER PRIMARY KEY AUTOINCREMENT,
+                 started_at DATETIME NOT NULL,
+                 ended_at DATETIME,
+                 duration_seconds INTEGER,
+                 type TEXT NOT NULL CHECK (type IN ('work', 'short_break', 'long_break')),
+                 completed BOOLEAN DEFAULT FALSE,
+                 tag_id INTEGER REFERENCES tags(id)
+             );
+             PRAGMA user_version = 9;",
+            )
+            .unwrap();
+        db.init_schema().unwrap();
+        let tag = db.create_tag("writing", None).unwrap();
+        let work = completed_work(&db, Some(tag), Some("chapter two"));
+        // Running the migration again leaves the table alone
+        db.init_schema().unwrap();
+
+        let timer = db
+            .start_session_in_mode(
+                SessionType::Timer,
+                Some(tag),
+                Some(5400),
+                SessionMode::Classic,
+            )
+            .unwrap();
+        db.complete_session(timer, 5400).unwrap();
+
+        // Timers count as focused time, but not toward classic work
+        assert_eq!(db.get_today_stats().unwrap().total_work_seconds, 6900);
+        assert_eq!(
+            db.get_mode_stats(SessionMode::Classic, 1)
+                .unwrap()
+                .total_work_seconds,
+            1500
+        );
+        // The search index and its triggers survive the copy
+        assert_eq!(search_ids(&db, "chapter"), vec![work]);
+        db.update_tag(tag, "drafting", None).unwrap();
+        assert_eq!(search_ids(&db, "drafting"), vec![work, timer]);
+    }
+
+    #[test]
+    fn test_tag_defaults_round_trip() {
+        let db = Database::open_in_memory().unwrap();
+        let tag = db.create_tag("writing", None).unwrap();
+        assert_eq!(db.get_tag_defaults(tag).unwrap(), TagDefaults::default());
+
+        let defaults = TagDefaults {
+            work_minutes: Some(50),
+            break_minutes: Some(10),
+            icon: Some("coffee".to_string()),
+        };
+        db.set_tag_defaults(tag, &defaults).unwrap();
+        assert_eq!(db.get_tag_defaults(tag).unwrap(), defaults);
+        // Renaming keeps them
+        db.update_tag(tag, "drafting", None).unwrap();
+        assert_eq!(db.get_tag_defaults(tag).unwrap(), defaults);
+    }
 }
@@ -0,0 +1,197 @@
+//! sandoro:// URL handling
+//!
+//! Lets OS-level automations (Shortcuts, AutoHotkey, KDE shortcuts) start a
+//! session with parameters, e.g. `sandoro://start?tag=writing&work=50`.
+//! See docs/URL_SCHEME.md.
+
+use anyhow::{bail, Context, Result};
+use url::Url;
+
+use crate::app::SessionOverrides;
+
+pub const SCHEME: &str = "sandoro";
+
+/// Longest duration accepted from a URL, in minutes
+const MAX_MINUTES: u32 = 240;
+
+fn parse_minutes(key: &str, value: &str) -> Result<u32> {
+    let minutes: u32 = value
+        .parse()
+        .with_context(|| format!("'{}' must be a number of minutes, got '{}'", key, value))?;
+    if minutes == 0 || minutes > MAX_MINUTES {
+        bail!("'{}' must be between 1 and {} minutes", key, MAX_MINUTES);
+    }
+    Ok(minutes)
+}
+
+/// Parse a sandoro:// URL into session overrides.
+///
+/// `start` begins the session immediately; `open` only prepares it.
+pub fn parse(input: &str) -> Result<SessionOverrides> {
+    let url = Url::parse(input).with_context(|| format!("Invalid URL: {}", input))?;
+    if url.scheme() != SCHEME {
+        bail!("Expected a {}:// URL, got {}://", SCHEME, url.scheme());
+    }
+
+    // sandoro://start parses "start" as the host; sandoro:start as the path
+    let action = url
+        .host_str()
+        .filter(|h| !h.is_empty())
+        .unwrap_or_else(|| url.path())
+        .trim_matches('/');
+
+    let mut overrides = SessionOverrides {
+        autostart: match action {
+            "start" => true,
+            "open" => false,
+            other => bail!("Unknown action '{}'. Use start or open.", other),
+        },
+        ..Default::default()
+    };
+
+    for (key, value) in url.query_pairs() {
+        match key.as_ref() {
+            "tag" if !value.trim().is_empty() => overrides.tag = Some(value.trim().to_string()),
+            "tag" => {}
+            "work" => overrides.work = Some(parse_minutes(&key, &value)?),
+            "short" | "short_break" => overrides.short_break = Some(parse_minutes(&key, &value)?),
+            "long" | "long_break" => overrides.long_break = Some(parse_minutes(&key, &value)?),
+            other => bail!(
+                "Unknown parameter '{}'. Use tag, work, short or long.",
+                other
+            ),
+        }
+    }
+
+    Ok(overrides)
+}
+
+/// Register sandoro as the handler for sandoro:// URLs. Returns a description
+/// of what was registered.
+pub fn register() -> Result<String> {
+    let exe = std::env::current_exe().context("Failed to locate the sandoro executable")?;
+    register_for(&exe)
+}
+
+#[cfg(target_os = "linux")]
+fn register_for(exe: &std::path::Path) -> Result<String> {
+    use std::process::Command;
+
+    let dir = dirs::data_dir()
+        .context("Failed to locate the data directory")?
+        .join("applications");
+    std::fs::create_dir_all(&dir)?;
+    let desktop = dir.join("sandoro-url.desktop");
+    std::fs::write(
+        &desktop,
+        format!(
+            "[Desktop Entry]\n\
+             Type=Application\n\
+             Name=sandoro\n\
+             Exec=\"{}\" url %u\n\
+             Terminal=true\n\
+             NoDisplay=true\n\
+             MimeType=x-scheme-handler/{};\n",
+            exe.display(),
+            SCHEME
+        ),
+    )?;
+
+    let status = Command::new("xdg-mime")
+        .args([
+            "default",
+            "sandoro-url.desktop",
+            &format!("x-scheme-handler/{}", SCHEME),
+        ])
+        .status()
+        .context("Failed to run xdg-mime (is xdg-utils installed?)")?;
+    if !status.success() {
+        bail!("xdg-mime failed to register the handler");
+    }
+    // Refresh the cache where available; xdg-mime alone is enough elsewhere
+    let _ = Command::new("update-desktop-database").arg(&dir).status();
+
+    Ok(format!("Registered {}", desktop.display()))
+}
+
+#[cfg(windows)]
+fn register_for(exe: &std::path::Path) -> Result<String> {
+    use std::process::Command;
+
+    let key = format!(r"HKCU\Software\Classes\{}", SCHEME);
+    let command = format!("\"{}\" url \"%1\"", exe.display());
+    let entries: [(String, &[&str]); 3] = [
+        (key.clone(), &["/ve", "/d", "URL:sandoro"]),
+        (key.clone(), &["/v", "URL Protocol", "/d", ""]),
+        (
+            format!(r"{}\shell\open\command", key),
+            &["/ve", "/d", &command],
+        ),
+    ];
+    for (path, args) in entries.iter() {
+        let status = Command::new("reg")
+            .arg("add")
+            .arg(path)
+            .args(*args)
+            .arg("/f")
+            .status()
+            .context("Failed to run reg.exe")?;
+        if !status.success() {
+            bail!("Failed to write registry key {}", path);
+        }
+    }
+    Ok(format!("Registered {}", key))
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+fn register_for(_exe: &std::path::Path) -> Result<String> {
+    bail!(
+        "macOS only routes URL schemes to app bundles. \
+         Use a Shortcuts \"Run Shell Script\" action with `sandoro url <URL>` instead."
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_start_with_params() {
+        let overrides =
+            parse("sandoro://start?tag=writing&work=50&short=10&long_break=20").unwrap();
+        assert_eq!(
+            overrides,
+            SessionOverrides {
+                tag: Some("writing".to_string()),
+                work: Some(50),
+                short_break: Some(10),
+                long_break: Some(20),
+                autostart: true,
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_open_does_not_autostart() {
+        let overrides = parse("sandoro://open").unwrap();
+        assert!(!overrides.autostart);
+        assert_eq!(overrides.work, None);
+        assert!(parse("sandoro:open?work=25").is_ok());
+    }
+
+    #[test]
+    fn test_parse_decodes_tag() {
+        let overrides = parse("sandoro://start?tag=deep%20work").unwrap();
+        assert_eq!(overrides.tag.as_deref(), Some("deep work"));
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_input() {
+        assert!(parse("https://start?work=5").is_err());
+        assert!(parse("sandoro://stop").is_err());
+        assert!(parse("sandoro://start?work=0").is_err());
+        assert!(parse("sandoro://start?work=999").is_err());
+        assert!(parse("sandoro://start?work=abc").is_err());
+        assert!(parse("sandoro://start?wrok=25").is_err());
+    }
+}
@@ -0,0 +1,186 @@
+//! `sandoro demo`: a self-contained showcase for screenshots and GIFs
+//!
+//! Runs the TUI on an in-memory database filled with twelve weeks of
+//! made-up history, with the timer sped up and the theme and icon changing
+//! every few seconds. The history comes from a fixed seed, so every run
+//! (relative to today) looks the same and recordings are reproducible.
+
+use anyhow::Result;
+use chrono::{Datelike, Duration, Local, NaiveDate, TimeZone, Utc, Weekday};
+use rusqlite::params;
+use std::time::Instant;
+
+use crate::days;
+use crate::db::Database;
+
+/// Timer seconds per real second: a 25 minute session takes 25 seconds
+pub const DEFAULT_SPEED: u32 = 60;
+
+/// Weeks of history, matching the stats heatmap
+const HISTORY_WEEKS: i64 = 12;
+
+/// Real time between theme and icon changes
+const CYCLE_EVERY: std::time::Duration = std::time::Duration::from_secs(8);
+
+/// Tags and colors of the made-up history
+const TAGS: [(&str, &str); 4] = [
+    ("writing", "#e07a5f"),
+    ("code", "#3d85c6"),
+    ("reading", "#81b29a"),
+    ("design", "#f2cc8f"),
+];
+
+/// Day notes, by days ago
+const JOURNAL: [(i64, &str); 3] = [
+    (1, "Finished the chapter draft, long focused morning"),
+    (6, "Slow start, better after lunch"),
+    (20, "Shipped the release"),
+];
+
+/// Demo settings from the command line
+#[derive(Debug, Clone)]
+pub struct Demo {
+    pub speed: u32,
+    /// Change theme and icon every few seconds
+    pub cycle: bool,
+    last_cycle: Instant,
+}
+
+impl Demo {
+    pub fn new(speed: u32, cycle: bool) -> Self {
+        Self {
+            speed: speed.max(1),
+            cycle,
+            last_cycle: Instant::now(),
+        }
+    }
+
+    /// Whether it's time to show the next theme and icon
+    pub fn cycle_due(&mut self) -> bool {
+        if !self.cycle || self.last_cycle.elapsed() < CYCLE_EVERY {
+            return false;
+        }
+        self.last_cycle = Instant::now();
+        true
+    }
+}
+
+/// Small xorshift generator so the history doesn't depend on a crate
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// Uniform in `0..n`
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+}
+
+/// Fill an empty database with tags, sessions and notes for the weeks up
+/// to `today`. The last nine days all have work, so there's a streak.
+pub fn seed(db: &Database, today: NaiveDate) -> Result<()> {
+    let tag_ids = TAGS
+        .iter()
+        .map(|(name, color)| db.create_tag(name, Some(color)))
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut rng = Rng(0x5a4d_0d0e);
+    let conn = db.connection();
+    let now = Utc::now();
+    for days_ago in (0..HISTORY_WEEKS * 7).rev() {
+        let date = today - Duration::days(days_ago);
+        let weekend = matches!(date.weekday(), Weekday::Sat | Weekday::Sun);
+        let sessions = match days_ago {
+            // Today is under way
+            0 => 3,
+            1..=8 => 2 + rng.below(6),
+            _ if weekend => rng.below(3),
+            _ if rng.below(6) == 0 => 0,
+            _ => 1 + rng.below(8),
+        };
+
+        let at = |hour| {
+            Local
+                .from_local_datetime(&date.and_hms_opt(hour, 0, 0).unwrap())
+                .earliest()
+                .map(|t| t.with_timezone(&Utc))
+        };
+        // Days start at nine; today's sessions fit in the last few hours
+        let mut start = if days_ago == 0 {
+            at(0).map(|midnight| midnight.max(now - Duration::hours(5)))
+        } else {
+            at(9)
+        };
+        for _ in 0..sessions {
+            let Some(started_at) = start else { break };
+            let minutes = [25, 25, 25, 30, 45, 50][rng.below(6) as usize];
+            let ended_at = started_at + Duration::minutes(minutes);
+            if ended_at > now {
+                break;
+            }
+            // Mostly one tag a day, as real days tend to go
+            let tag = tag_ids[((days_ago as u64 + rng.below(2)) % tag_ids.len() as u64) as usize];
+            conn.execute(
+                "INSERT INTO sessions
+                     (started_at, ended_at, duration_seconds, type, completed, tag_id,
+                      planned_seconds, utc_offset_minutes, day, uuid, origin)
+                 VALUES (?1, ?2, ?3, 'work', TRUE, ?4, ?3, ?5, ?6, ?7, 'local')",
+                params![
+                    started_at.to_rfc3339(),
+                    ended_at.to_rfc3339(),
+                    minutes * 60,
+                    tag,
+                    days::local_offset_minutes(),
+                    date.format("%Y-%m-%d").to_string(),
+                    crate::db::new_uuid()
+                ],
+            )?;
+            start = Some(ended_at + Duration::minutes(5 + rng.below(40) as i64));
+        }
+    }
+
+    for (days_ago, note) in JOURNAL {
+        let date = today - Duration::days(days_ago);
+        db.set_journal(&date.format("%Y-%m-%d").to_string(), note)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seed_has_a_streak_and_history() {
+        let db = Database::open_in_memory().unwrap();
+        seed(&db, Local::now().date_naive()).unwrap();
+
+        assert!(db.get_streak().unwrap().current >= 8);
+        assert_eq!(db.get_all_tags().unwrap().len(), TAGS.len());
+        let active_days = db
+            .get_heatmap_data(HISTORY_WEEKS as i32)
+            .unwrap()
+            .iter()
+            .filter(|d| d.total_work_seconds > 0)
+            .count();
+        assert!(active_days > 40, "only {} active days", active_days);
+    }
+
+    #[test]
+    fn test_seed_is_reproducible() {
+        let totals = || {
+            let db = Database::open_in_memory().unwrap();
+            seed(&db, NaiveDate::from_ymd_opt(2026, 3, 2).unwrap()).unwrap();
+            db.get_range_stats("2000-01-01", "9999-12-31")
+                .unwrap()
+                .total_work_seconds
+        };
+        assert_eq!(totals(), totals());
+    }
+}
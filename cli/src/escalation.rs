@@ -0,0 +1,273 @@
+//! Break-over reminders pushed to the phone
+//!
+//! When a break runs out and work isn't started, the desktop notification
+//! is easy to miss. With `[escalation]` set up, sandoro follows up with a
+//! push to an ntfy topic or a Gotify server at each step of the ladder
+//! (minutes after the break ended), each one more urgent than the last,
+//! and stops as soon as work starts. Steps that fall in quiet hours are
+//! skipped rather than sent late.
+
+use anyhow::{bail, Result};
+use chrono::{DateTime, Duration, Local, NaiveTime};
+use serde_json::json;
+
+use crate::config::{EscalationConfig, PushProvider};
+
+const REQUEST_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Local time range without pushes; may run past midnight
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QuietHours {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl QuietHours {
+    /// "22:00-07:00"
+    pub fn parse(text: &str) -> Result<Self> {
+        let parse = |t: &str| NaiveTime::parse_from_str(t.trim(), "%H:%M");
+        match text.split_once('-').map(|(a, b)| (parse(a), parse(b))) {
+            Some((Ok(start), Ok(end))) if start != end => Ok(Self { start, end }),
+            _ => bail!("Quiet hours look like \"22:00-07:00\", got '{}'", text),
+        }
+    }
+
+    pub fn contains(&self, time: NaiveTime) -> bool {
+        if self.start < self.end {
+            self.start <= time && time < self.end
+        } else {
+            time >= self.start || time < self.end
+        }
+    }
+}
+
+/// Follow-ups for one ignored break
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Escalation {
+    pub break_ended_at: DateTime<Local>,
+    /// Ladder steps already handled (sent or skipped)
+    pub steps_done: usize,
+}
+
+/// A push to send now
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Push {
+    /// Step of the ladder, from 0; later steps are more urgent
+    pub step: usize,
+    pub minutes: u32,
+}
+
+impl Escalation {
+    pub fn new(break_ended_at: DateTime<Local>) -> Self {
+        Self {
+            break_ended_at,
+            steps_done: 0,
+        }
+    }
+
+    /// The push due at `now`, if any. Steps that came due during quiet
+    /// hours are passed over; when several are due at once only the last
+    /// is sent.
+    pub fn due(
+        &mut self,
+        ladder: &[u32],
+        quiet: Option<QuietHours>,
+        now: DateTime<Local>,
+    ) -> Option<Push> {
+        let waited = now - self.break_ended_at;
+        let mut push = None;
+        while let Some(&minutes) = ladder.get(self.steps_done) {
+            if waited < Duration::minutes(i64::from(minutes)) {
+                break;
+            }
+            push = Some(Push {
+                step: self.steps_done,
+                minutes,
+            });
+            self.steps_done += 1;
+        }
+        push.filter(|_| !quiet.is_some_and(|q| q.contains(now.time())))
+    }
+}
+
+impl Push {
+    pub fn title(&self) -> &'static str {
+        "Break's over"
+    }
+
+    pub fn message(&self) -> String {
+        format!(
+            "Your break ended {} minutes ago. Start the next session when you're ready.",
+            self.minutes
+        )
+    }
+
+    /// ntfy priority: default, high, then urgent
+    fn ntfy_priority(&self) -> u8 {
+        (3 + self.step as u8).min(5)
+    }
+
+    /// Gotify priority: 5 and up makes a sound on Android, 8 and up pops up
+    fn gotify_priority(&self) -> u8 {
+        [5, 8, 10][self.step.min(2)]
+    }
+}
+
+/// URL, headers and body of a push
+type PushRequest = (String, Vec<(&'static str, String)>, String);
+
+/// URL, headers and body of the push request
+pub fn request(
+    config: &EscalationConfig,
+    provider: PushProvider,
+    push: &Push,
+) -> Result<PushRequest> {
+    config.check()?;
+    let Some(base) = config.server(provider) else {
+        bail!("No server set for {}", provider.as_str());
+    };
+    Ok(match provider {
+        PushProvider::Ntfy => {
+            let mut headers = vec![
+                ("Title", push.title().to_string()),
+                ("Priority", push.ntfy_priority().to_string()),
+                ("Tags", "tomato".to_string()),
+            ];
+            if !config.token.is_empty() {
+                headers.push(("Authorization", format!("Bearer {}", config.token)));
+            }
+            (
+                format!("{}/{}", base, urlencoding::encode(&config.topic)),
+                headers,
+                push.message(),
+            )
+        }
+        PushProvider::Gotify => (
+            format!("{}/message", base),
+            vec![
+                ("X-Gotify-Key", config.token.clone()),
+                ("Content-Type", "application/json".to_string()),
+            ],
+            json!({
+                "title": push.title(),
+                "message": push.message(),
+                "priority": push.gotify_priority(),
+            })
+            .to_string(),
+        ),
+    })
+}
+
+pub fn send(config: &EscalationConfig, provider: PushProvider, push: &Push) -> Result<()> {
+    let (url, headers, body) = request(config, provider, push)?;
+    let client = reqwest::blocking::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()?;
+    let mut request = client.post(&url).body(body);
+    for (key, value) in headers {
+        request = request.header(key, value);
+    }
+    let response = request.send()?;
+    let status = response.status();
+    if !status.is_success() {
+        let text = response.text().unwrap_or_default();
+        bail!("{} returned {}: {}", provider.as_str(), status, text.trim());
+    }
+    Ok(())
+}
+
+/// Send without holding up the timer; a missed push isn't retried
+pub fn send_in_background(config: &EscalationConfig, provider: PushProvider, push: Push) {
+    let config = config.clone();
+    std::thread::spawn(move || {
+        let _ = send(&config, provider, &push);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(h: u32, m: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(2026, 10, 17, h, m, 0).unwrap()
+    }
+
+    #[test]
+    fn test_ladder_steps_come_due_in_turn() {
+        let ladder = [5, 15, 30];
+        let mut escalation = Escalation::new(at(14, 0));
+        assert_eq!(escalation.due(&ladder, None, at(14, 4)), None);
+        assert_eq!(
+            escalation.due(&ladder, None, at(14, 5)),
+            Some(Push {
+                step: 0,
+                minutes: 5
+            })
+        );
+        assert_eq!(escalation.due(&ladder, None, at(14, 6)), None);
+        // Asleep for a while: only the latest step goes out
+        let push = escalation.due(&ladder, None, at(14, 40)).unwrap();
+        assert_eq!((push.step, push.minutes), (2, 30));
+        assert_eq!(escalation.due(&ladder, None, at(15, 0)), None);
+    }
+
+    #[test]
+    fn test_quiet_hours_skip_steps() {
+        let quiet = QuietHours::parse("22:00-07:00").unwrap();
+        assert!(quiet.contains(NaiveTime::from_hms_opt(23, 30, 0).unwrap()));
+        assert!(quiet.contains(NaiveTime::from_hms_opt(6, 59, 0).unwrap()));
+        assert!(!quiet.contains(NaiveTime::from_hms_opt(7, 0, 0).unwrap()));
+        assert!(QuietHours::parse("12:00-13:00")
+            .unwrap()
+            .contains(NaiveTime::from_hms_opt(12, 30, 0).unwrap()));
+        assert!(QuietHours::parse("late").is_err());
+        assert!(QuietHours::parse("22:00-22:00").is_err());
+
+        let mut escalation = Escalation::new(at(21, 50));
+        assert_eq!(
+            escalation
+                .due(&[5, 15], Some(quiet), at(21, 55))
+                .unwrap()
+                .step,
+            0
+        );
+        // The 22:05 step is skipped, not sent in the morning
+        assert_eq!(escalation.due(&[5, 15], Some(quiet), at(22, 5)), None);
+        assert_eq!(escalation.steps_done, 2);
+    }
+
+    #[test]
+    fn test_requests_for_each_provider() {
+        let push = Push {
+            step: 1,
+            minutes: 15,
+        };
+        let mut config = EscalationConfig {
+            topic: "my topic".to_string(),
+            ..Default::default()
+        };
+        let (url, headers, body) = request(&config, PushProvider::Ntfy, &push).unwrap();
+        assert_eq!(url, "https://ntfy.sh/my%20topic");
+        assert!(headers.contains(&("Priority", "4".to_string())));
+        assert!(!headers.iter().any(|(k, _)| *k == "Authorization"));
+        assert!(body.contains("15 minutes ago"));
+
+        config.provider = Some(PushProvider::Gotify);
+        config.token = "app-token".to_string();
+        // A Gotify token never goes to the ntfy default
+        assert!(request(&config, PushProvider::Gotify, &push).is_err());
+        assert_eq!(config.enabled(), None);
+        assert!(config.check().is_err());
+        config.url = "https://ntfy.sh".to_string();
+        assert!(request(&config, PushProvider::Gotify, &push).is_err());
+
+        config.url = "https://push.example.com/".to_string();
+        assert_eq!(config.enabled(), Some(PushProvider::Gotify));
+        let (url, headers, body) = request(&config, PushProvider::Gotify, &push).unwrap();
+        assert_eq!(url, "https://push.example.com/message");
+        assert!(headers.contains(&("X-Gotify-Key", "app-token".to_string())));
+        let body: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(body["priority"], 8);
+    }
+}
@@ -0,0 +1,327 @@
+//! The system's do-not-disturb mode during work sessions
+//!
+//! With `[dnd] enabled = true`, do not disturb is turned on when a work
+//! session starts and off again on the break, when the session ends or when
+//! sandoro quits. If it was already on, it's left alone both ways.
+//!
+//! - GNOME: notification banners off through `gsettings`.
+//! - KDE Plasma: the "Do not disturb until" of plasmanotifyrc, written with
+//!   `kwriteconfig6` (or 5) so Plasma notices. It's set 12 hours ahead, so it
+//!   lapses by itself if sandoro is killed.
+//! - Other Linux desktops: dunst's `dunstctl set-paused`, when dunst runs.
+//! - macOS: Focus has no API, so the Shortcuts "Sandoro DND On" and "Sandoro
+//!   DND Off" are run; each needs a "Set Focus" action.
+//! - Windows: Focus Assist has no public API either, so there's no built-in
+//!   backend.
+//!
+//! `on_command` and `off_command` replace the built-in backend on any system,
+//! run through the shell.
+
+use std::process::Command;
+
+use chrono::{DateTime, Datelike, Duration, Local, Timelike};
+
+use crate::clock;
+use crate::config::DndConfig;
+use crate::session_hooks;
+
+/// Names of the macOS Shortcuts that switch a Focus on and off
+pub const SHORTCUT_ON: &str = "Sandoro DND On";
+pub const SHORTCUT_OFF: &str = "Sandoro DND Off";
+
+/// How long the KDE setting lasts if it's never turned back off
+const KDE_LAPSE_HOURS: i64 = 12;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Backend {
+    Commands {
+        on: String,
+        off: String,
+    },
+    Gnome,
+    Kde,
+    Dunst,
+    Shortcuts,
+    /// Why there's nothing to switch
+    Unsupported(String),
+}
+
+/// Turns do not disturb on and off as work sessions start and stop
+#[derive(Debug)]
+pub struct FocusDnd {
+    config: DndConfig,
+    /// Picked the first time it's turned on
+    backend: Option<Backend>,
+    /// Work is on, so do not disturb should be
+    focused: bool,
+    /// It was already on when work started, so it isn't touched
+    left_alone: bool,
+}
+
+impl FocusDnd {
+    pub fn new(config: &DndConfig) -> Self {
+        Self {
+            config: config.clone(),
+            backend: None,
+            focused: false,
+            left_alone: false,
+        }
+    }
+
+    /// Call on every loop with whether a work session is on. Switches do
+    /// not disturb when that changes; Err says why it couldn't.
+    pub fn update(&mut self, working: bool) -> Result<(), String> {
+        if working == self.focused {
+            return Ok(());
+        }
+        self.focused = working;
+        let config = &self.config;
+        let backend = self.backend.get_or_insert_with(|| detect(config));
+        if working {
+            self.left_alone = backend.is_on();
+            if self.left_alone {
+                return Ok(());
+            }
+        } else if self.left_alone {
+            return Ok(());
+        }
+        backend.switch(working, clock::now())
+    }
+
+    /// Turn do not disturb back off if it's on for a work session, e.g.
+    /// when quitting
+    pub fn release(&mut self) {
+        let _ = self.update(false);
+    }
+}
+
+/// The backend for this system and config
+fn detect(config: &DndConfig) -> Backend {
+    if !config.on_command.trim().is_empty() || !config.off_command.trim().is_empty() {
+        return Backend::Commands {
+            on: config.on_command.clone(),
+            off: config.off_command.clone(),
+        };
+    }
+    if cfg!(target_os = "macos") {
+        Backend::Shortcuts
+    } else if cfg!(windows) {
+        Backend::Unsupported(
+            "Focus Assist can't be switched by other apps; set [dnd] on_command and off_command"
+                .to_string(),
+        )
+    } else {
+        let desktop = std::env::var("XDG_CURRENT_DESKTOP").unwrap_or_default();
+        desktop_backend(&desktop).unwrap_or_else(|| {
+            if output("dunstctl", &["is-paused"]).is_ok() {
+                Backend::Dunst
+            } else {
+                Backend::Unsupported(
+                    "no do-not-disturb for this desktop; set [dnd] on_command and off_command"
+                        .to_string(),
+                )
+            }
+        })
+    }
+}
+
+/// Backend for $XDG_CURRENT_DESKTOP, e.g. "ubuntu:GNOME" or "KDE"
+fn desktop_backend(desktop: &str) -> Option<Backend> {
+    let names: Vec<&str> = desktop.split(':').collect();
+    if names.iter().any(|d| d.eq_ignore_ascii_case("KDE")) {
+        Some(Backend::Kde)
+    } else if names.iter().any(|d| d.eq_ignore_ascii_case("GNOME")) {
+        Some(Backend::Gnome)
+    } else {
+        None
+    }
+}
+
+impl Backend {
+    /// Whether do not disturb is already on, where that can be told
+    fn is_on(&self) -> bool {
+        match self {
+            Backend::Gnome => output(
+                "gsettings",
+                &["get", "org.gnome.desktop.notifications", "show-banners"],
+            )
+            .is_ok_and(|out| out.trim() == "false"),
+            Backend::Dunst => {
+                output("dunstctl", &["is-paused"]).is_ok_and(|out| out.trim() == "true")
+            }
+            _ => false,
+        }
+    }
+
+    fn switch(&self, on: bool, now: DateTime<Local>) -> Result<(), String> {
+        match self {
+            Backend::Commands { on: on_cmd, off } => {
+                let command = if on { on_cmd } else { off };
+                if command.trim().is_empty() {
+                    return Ok(());
+                }
+                let out = session_hooks::shell(command)
+                    .output()
+                    .map_err(|err| format!("`{}`: {}", command, err))?;
+                if out.status.success() {
+                    Ok(())
+                } else {
+                    let stderr = String::from_utf8_lossy(&out.stderr);
+                    Err(format!("`{}`: {}", command, stderr.trim()))
+                }
+            }
+            Backend::Kde => {
+                let args = kde_args(on, now);
+                let args: Vec<&str> = args.iter().map(String::as_str).collect();
+                // Plasma 6 ships kwriteconfig6, Plasma 5 kwriteconfig5
+                output("kwriteconfig6", &args)
+                    .or_else(|_| output("kwriteconfig5", &args))
+                    .map(|_| ())
+            }
+            Backend::Unsupported(why) => Err(why.clone()),
+            _ => {
+                let (program, args) = self.command(on);
+                output(program, &args).map(|_| ())
+            }
+        }
+    }
+
+    /// The program and arguments that switch it, for the backends that are
+    /// a single fixed command
+    fn command(&self, on: bool) -> (&'static str, Vec<&'static str>) {
+        match self {
+            Backend::Gnome => (
+                "gsettings",
+                vec![
+                    "set",
+                    "org.gnome.desktop.notifications",
+                    "show-banners",
+                    if on { "false" } else { "true" },
+                ],
+            ),
+            Backend::Dunst => (
+                "dunstctl",
+                vec!["set-paused", if on { "true" } else { "false" }],
+            ),
+            _ => (
+                "shortcuts",
+                vec!["run", if on { SHORTCUT_ON } else { SHORTCUT_OFF }],
+            ),
+        }
+    }
+}
+
+/// kwriteconfig arguments: "Do not disturb until" 12 hours from `now`, or
+/// cleared
+fn kde_args(on: bool, now: DateTime<Local>) -> Vec<String> {
+    let mut args: Vec<String> = [
+        "--file",
+        "plasmanotifyrc",
+        "--group",
+        "DoNotDisturb",
+        "--key",
+        "Until",
+        "--notify",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect();
+    if on {
+        // KConfig's date-time format
+        let until = now + Duration::hours(KDE_LAPSE_HOURS);
+        args.push(format!(
+            "{},{},{},{},{},{}",
+            until.year(),
+            until.month(),
+            until.day(),
+            until.hour(),
+            until.minute(),
+            until.second()
+        ));
+    } else {
+        args.push("--delete".to_string());
+    }
+    args
+}
+
+/// Run `program` and return its stdout, or why it failed
+fn output(program: &str, args: &[&str]) -> Result<String, String> {
+    let out = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|err| format!("can't run {}: {}", program, err))?;
+    if out.status.success() {
+        Ok(String::from_utf8_lossy(&out.stdout).into_owned())
+    } else {
+        let stderr = String::from_utf8_lossy(&out.stderr);
+        Err(format!("{}: {}", program, stderr.trim()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_desktop_backend() {
+        assert_eq!(desktop_backend("ubuntu:GNOME"), Some(Backend::Gnome));
+        assert_eq!(desktop_backend("KDE"), Some(Backend::Kde));
+        assert_eq!(desktop_backend("sway"), None);
+        assert_eq!(desktop_backend(""), None);
+    }
+
+    #[test]
+    fn test_commands_replace_the_backend() {
+        let config = DndConfig {
+            enabled: true,
+            on_command: "focus-on".to_string(),
+            off_command: String::new(),
+        };
+        assert_eq!(
+            detect(&config),
+            Backend::Commands {
+                on: "focus-on".to_string(),
+                off: String::new(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_kde_until_lapses() {
+        let now = Local.with_ymd_and_hms(2026, 10, 17, 15, 30, 5).unwrap();
+        let on = kde_args(true, now);
+        assert_eq!(on.last().unwrap(), "2026,10,18,3,30,5");
+        let off = kde_args(false, now);
+        assert_eq!(off.last().unwrap(), "--delete");
+    }
+
+    #[test]
+    fn test_only_switches_on_a_change() {
+        let mut dnd = FocusDnd {
+            config: DndConfig::default(),
+            backend: Some(Backend::Unsupported("nope".to_string())),
+            focused: false,
+            left_alone: false,
+        };
+        assert!(dnd.update(false).is_ok());
+        assert_eq!(dnd.update(true).unwrap_err(), "nope");
+        // Still working: nothing runs again until the session stops
+        assert!(dnd.update(true).is_ok());
+        assert!(dnd.update(false).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_commands_run_through_the_shell() {
+        let backend = Backend::Commands {
+            on: "true".to_string(),
+            off: "echo broken >&2; exit 1".to_string(),
+        };
+        assert!(backend.switch(true, clock::now()).is_ok());
+        assert_eq!(
+            backend.switch(false, clock::now()).unwrap_err(),
+            "`echo broken >&2; exit 1`: broken"
+        );
+    }
+}
@@ -0,0 +1,139 @@
+//! End-of-day reminder for the daily goal
+//!
+//! With `[goals] reminder = true`, the timer looks at today's progress once a
+//! day at `reminder_time` and sends a notification when the daily sessions
+//! or minutes goal isn't met yet, saying what's left. A timer started after
+//! that time checks straight away. With `journal_prompt = true`, the same
+//! time opens a prompt for today's journal note.
+
+use anyhow::{bail, Result};
+use chrono::{DateTime, Local, NaiveDate, NaiveTime};
+
+use crate::config::GoalConfig;
+
+/// "20:00"
+pub fn parse_time(text: &str) -> Result<NaiveTime> {
+    match NaiveTime::parse_from_str(text.trim(), "%H:%M") {
+        Ok(time) => Ok(time),
+        Err(_) => bail!("The reminder time looks like \"20:00\", got '{}'", text),
+    }
+}
+
+/// Tracks the day already checked, so the reminder goes out once a day
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Reminder {
+    checked_on: Option<NaiveDate>,
+}
+
+impl Reminder {
+    /// Whether today's check is due at `now`. Each day is checked once, at
+    /// or after the reminder time, whatever the outcome.
+    pub fn due(&mut self, goals: &GoalConfig, now: DateTime<Local>) -> bool {
+        let any_goal = goals.daily_sessions > 0 || goals.daily_minutes > 0;
+        if !goals.reminder || !any_goal {
+            return false;
+        }
+        self.due_at(&goals.reminder_time, now)
+    }
+
+    /// Whether today's check at `time` ("HH:MM") is due at `now`; false for
+    /// a time that doesn't parse
+    pub fn due_at(&mut self, time: &str, now: DateTime<Local>) -> bool {
+        let Ok(time) = parse_time(time) else {
+            return false;
+        };
+        let today = now.date_naive();
+        if now.time() < time || self.checked_on == Some(today) {
+            return false;
+        }
+        self.checked_on = Some(today);
+        true
+    }
+}
+
+/// What's left of the daily goal after `sessions` sessions and `seconds` of
+/// work, e.g. "2 sessions and 40 min", or None when it's met
+pub fn shortfall(goals: &GoalConfig, sessions: i32, seconds: i32) -> Option<String> {
+    let mut left = Vec::new();
+    let sessions_left = goals.daily_sessions as i32 - sessions;
+    if goals.daily_sessions > 0 && sessions_left > 0 {
+        left.push(if sessions_left == 1 {
+            "1 session".to_string()
+        } else {
+            format!("{} sessions", sessions_left)
+        });
+    }
+    let minutes_left = goals.daily_minutes as i32 - seconds / 60;
+    if goals.daily_minutes > 0 && minutes_left > 0 {
+        left.push(format!("{} min", minutes_left));
+    }
+    if left.is_empty() {
+        None
+    } else {
+        Some(left.join(" and "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(day: u32, hour: u32, minute: u32) -> DateTime<Local> {
+        Local
+            .with_ymd_and_hms(2026, 10, day, hour, minute, 0)
+            .unwrap()
+    }
+
+    fn goals() -> GoalConfig {
+        GoalConfig {
+            daily_sessions: 4,
+            daily_minutes: 120,
+            reminder: true,
+            ..GoalConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_reminder_is_due_once_a_day_after_the_time() {
+        let goals = goals();
+        let mut reminder = Reminder::default();
+        assert!(!reminder.due(&goals, at(17, 19, 59)));
+        assert!(reminder.due(&goals, at(17, 20, 0)));
+        assert!(!reminder.due(&goals, at(17, 22, 30)));
+        // Started late the next day: due straight away
+        assert!(reminder.due(&goals, at(18, 23, 10)));
+    }
+
+    #[test]
+    fn test_reminder_needs_switching_on_and_a_daily_goal() {
+        let mut reminder = Reminder::default();
+        let off = GoalConfig {
+            reminder: false,
+            ..goals()
+        };
+        assert!(!reminder.due(&off, at(17, 21, 0)));
+        let no_goal = GoalConfig {
+            reminder: true,
+            ..GoalConfig::default()
+        };
+        assert!(!reminder.due(&no_goal, at(17, 21, 0)));
+        let bad_time = GoalConfig {
+            reminder_time: "8pm".to_string(),
+            ..goals()
+        };
+        assert!(!reminder.due(&bad_time, at(17, 21, 0)));
+        assert!(parse_time("8pm").is_err());
+    }
+
+    #[test]
+    fn test_shortfall_says_whats_left() {
+        let goals = goals();
+        assert_eq!(
+            shortfall(&goals, 2, 80 * 60).as_deref(),
+            Some("2 sessions and 40 min")
+        );
+        assert_eq!(shortfall(&goals, 3, 120 * 60).as_deref(), Some("1 session"));
+        assert_eq!(shortfall(&goals, 5, 150 * 60), None);
+    }
+}
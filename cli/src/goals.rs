@@ -0,0 +1,175 @@
+//! Weekly goal weeks
+//!
+//! Weekly goals follow calendar weeks starting on `goals.week_start`, so
+//! progress resets at the week boundary. The goals in effect each week are
+//! recorded so past weeks can be shown as met or missed.
+
+use anyhow::Result;
+use chrono::{Datelike, Duration, NaiveDate, Weekday};
+
+use crate::config::Config;
+use crate::db::Database;
+
+/// Number of past weeks shown in goal history
+pub const HISTORY_WEEKS: i32 = 6;
+
+/// A calendar week and the focus recorded in it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Week {
+    /// First day of the week
+    pub start: NaiveDate,
+    pub minutes: i32,
+    pub sessions: i32,
+}
+
+impl Week {
+    /// Last day of the week
+    pub fn end(&self) -> NaiveDate {
+        self.start + Duration::days(6)
+    }
+}
+
+/// A past or current week with the goals that applied to it
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeekOutcome {
+    pub week: Week,
+    /// 0 when the goal was not set
+    pub minutes_goal: u32,
+    pub sessions_goal: u32,
+}
+
+impl WeekOutcome {
+    /// Every goal that was set was reached
+    pub fn met(&self) -> bool {
+        (self.minutes_goal == 0 || self.week.minutes >= self.minutes_goal as i32)
+            && (self.sessions_goal == 0 || self.week.sessions >= self.sessions_goal as i32)
+    }
+}
+
+/// First day of the week containing `date`
+pub fn week_start(date: NaiveDate, start: Weekday) -> NaiveDate {
+    let offset = (7 + date.weekday().num_days_from_monday() - start.num_days_from_monday()) % 7;
+    date - Duration::days(offset as i64)
+}
+
+/// Days since the start of the week, 0-6
+pub fn day_of_week(date: NaiveDate, start: Weekday) -> usize {
+    (date - week_start(date, start)).num_days() as usize
+}
+
+/// Focus recorded in the week starting on `start`
+pub fn load_week(db: &Database, start: NaiveDate) -> Result<Week> {
+    let end = start + Duration::days(7);
+    let stats = db.get_range_stats(
+        &start.format("%Y-%m-%d").to_string(),
+        &end.format("%Y-%m-%d").to_string(),
+    )?;
+    Ok(Week {
+        start,
+        minutes: stats.total_work_seconds / 60,
+        sessions: stats.sessions_completed,
+    })
+}
+
+/// Focus recorded so far in the week containing `today`
+pub fn current_week(db: &Database, config: &Config, today: NaiveDate) -> Result<Week> {
+    load_week(db, week_start(today, config.goals.week_start_day()))
+}
+
+/// Record the current weekly goals for this week. Called by the timer, so the
+/// goals a week ends with are the ones it's judged by.
+pub fn record_week(db: &Database, config: &Config, today: NaiveDate) -> Result<()> {
+    let goals = &config.goals;
+    if goals.weekly_minutes == 0 && goals.weekly_sessions == 0 {
+        return Ok(());
+    }
+    let start = week_start(today, goals.week_start_day());
+    db.set_goal_week(
+        &start.format("%Y-%m-%d").to_string(),
+        goals.weekly_minutes,
+        goals.weekly_sessions,
+    )
+}
+
+/// Recorded weeks before the current one, most recent first
+pub fn history(db: &Database, config: &Config, today: NaiveDate) -> Result<Vec<WeekOutcome>> {
+    let current = week_start(today, config.goals.week_start_day());
+    let mut outcomes = Vec::new();
+    for (start, minutes_goal, sessions_goal) in db.get_goal_weeks(HISTORY_WEEKS + 1)? {
+        let Ok(start) = NaiveDate::parse_from_str(&start, "%Y-%m-%d") else {
+            continue;
+        };
+        if start >= current {
+            continue;
+        }
+        outcomes.push(WeekOutcome {
+            week: load_week(db, start)?,
+            minutes_goal,
+            sessions_goal,
+        });
+    }
+    outcomes.truncate(HISTORY_WEEKS as usize);
+    Ok(outcomes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_week_start() {
+        // 2026-10-15 is a Thursday
+        let thursday = date("2026-10-15");
+        assert_eq!(week_start(thursday, Weekday::Mon), date("2026-10-12"));
+        assert_eq!(week_start(thursday, Weekday::Sun), date("2026-10-11"));
+        assert_eq!(week_start(thursday, Weekday::Thu), thursday);
+        assert_eq!(week_start(thursday, Weekday::Fri), date("2026-10-09"));
+        assert_eq!(day_of_week(thursday, Weekday::Mon), 3);
+        assert_eq!(day_of_week(thursday, Weekday::Sun), 4);
+    }
+
+    #[test]
+    fn test_week_outcome_met() {
+        let outcome = |minutes, sessions, minutes_goal, sessions_goal| WeekOutcome {
+            week: Week {
+                start: date("2026-10-12"),
+                minutes,
+                sessions,
+            },
+            minutes_goal,
+            sessions_goal,
+        };
+        assert!(outcome(600, 3, 600, 0).met());
+        assert!(!outcome(599, 30, 600, 0).met());
+        assert!(!outcome(600, 3, 600, 4).met());
+        assert!(outcome(0, 0, 0, 0).met());
+    }
+
+    #[test]
+    fn test_history_skips_current_week() {
+        let db = Database::open_in_memory().unwrap();
+        let mut config = Config::default();
+        config.goals.weekly_minutes = 300;
+
+        let today = date("2026-10-15");
+        record_week(&db, &config, today - Duration::days(14)).unwrap();
+        config.goals.weekly_minutes = 600;
+        record_week(&db, &config, today - Duration::days(7)).unwrap();
+        record_week(&db, &config, today).unwrap();
+
+        let weeks = history(&db, &config, today).unwrap();
+        let summary: Vec<(NaiveDate, u32)> = weeks
+            .iter()
+            .map(|w| (w.week.start, w.minutes_goal))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![(date("2026-10-05"), 600), (date("2026-09-28"), 300)]
+        );
+        assert!(!weeks[0].met());
+    }
+}
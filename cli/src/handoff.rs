@@ -0,0 +1,485 @@
+//! Handing a work session over between devices
+//!
+//! While a work session runs, a logged-in timer publishes it to the cloud
+//! (`active_sessions`) every `HEARTBEAT_SECS`. `sandoro start --resume-remote`
+//! on another device picks it up: it continues with the remaining time and
+//! tag, and marks the original as handed off. The original timer sees that
+//! on its next heartbeat and drops its session unrecorded, so the session
+//! only counts once, on the device that finishes it.
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Utc};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use crate::auth;
+use crate::config::Config;
+use crate::supabase::{ActiveSession, SupabaseClient};
+
+/// How often a running session is re-published
+pub const HEARTBEAT_SECS: u64 = 30;
+/// A session that hasn't been published for this long is left alone
+pub const STALE_AFTER_SECS: i64 = 90;
+/// How long quitting waits for the published session to be cleared
+const FINISH_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// This machine, as other devices see it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Device {
+    pub id: String,
+    pub name: String,
+}
+
+impl Device {
+    /// This machine's id (made up once and kept in `~/.sandoro/device_id`)
+    /// and host name
+    pub fn this() -> Result<Self> {
+        let path = Config::config_dir()?.join("device_id");
+        let id = match std::fs::read_to_string(&path) {
+            Ok(id) if !id.trim().is_empty() => id.trim().to_string(),
+            _ => {
+                let id = uuid::Uuid::new_v4().to_string();
+                if let Some(dir) = path.parent() {
+                    std::fs::create_dir_all(dir)?;
+                }
+                std::fs::write(&path, &id)
+                    .with_context(|| format!("Could not write {}", path.display()))?;
+                id
+            }
+        };
+        Ok(Self {
+            id,
+            name: host_name(),
+        })
+    }
+}
+
+/// This machine's host name
+pub fn host_name() -> String {
+    std::fs::read_to_string("/etc/hostname")
+        .ok()
+        .or_else(|| std::env::var("HOSTNAME").ok())
+        .or_else(|| std::env::var("COMPUTERNAME").ok())
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "another device".to_string())
+}
+
+/// What the timer takes over from another device
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Adoption {
+    pub remaining_seconds: u32,
+    pub work_minutes: u32,
+    pub tag: Option<String>,
+    pub from_device: Device,
+}
+
+/// Whether the remote session can be taken over by `me` at `now`, and with
+/// how much time left
+pub fn adoptable(
+    remote: Option<&ActiveSession>,
+    me: &Device,
+    now: DateTime<Utc>,
+) -> Result<Adoption> {
+    let Some(remote) = remote else {
+        bail!("No session is running on your other devices.");
+    };
+    if remote.device_id == me.id {
+        bail!("The running session is already on this device.");
+    }
+    if let Some(to) = &remote.handed_off_to {
+        bail!(
+            "The session on {} was already taken over by {}.",
+            remote.device_name,
+            to
+        );
+    }
+    let silent = (now - remote.heartbeat_at).num_seconds().max(0);
+    if silent > STALE_AFTER_SECS {
+        bail!(
+            "The session on {} stopped sending updates {} minutes ago.",
+            remote.device_name,
+            silent / 60
+        );
+    }
+    if remote.session_type != "work" {
+        bail!("{} is on a break, not a work session.", remote.device_name);
+    }
+    // A running timer kept counting down since its last heartbeat
+    let elapsed = if remote.paused { 0 } else { silent };
+    let remaining = i64::from(remote.remaining_seconds) - elapsed;
+    if remaining <= 0 {
+        bail!("The session on {} has just finished.", remote.device_name);
+    }
+    Ok(Adoption {
+        remaining_seconds: remaining as u32,
+        work_minutes: remote.work_minutes.max(1) as u32,
+        tag: remote.tag.clone(),
+        from_device: Device {
+            id: remote.device_id.clone(),
+            name: remote.device_name.clone(),
+        },
+    })
+}
+
+/// Name of the device that took over the session `me` published, if one did
+pub fn taken_over_by(remote: &ActiveSession, me: &Device) -> Option<String> {
+    if remote.device_id == me.id {
+        remote.handed_off_to.clone()
+    } else if remote.resumed_from.as_deref() == Some(me.id.as_str()) {
+        Some(remote.device_name.clone())
+    } else {
+        None
+    }
+}
+
+/// Take over the session running on another device, for
+/// `sandoro start --resume-remote`
+pub fn adopt() -> Result<Adoption> {
+    let Some(client) = SupabaseClient::new()? else {
+        bail!("Log in with `sandoro login` on both devices to hand sessions over.");
+    };
+    let me = Device::this()?;
+    let remote = client.get_active_session()?;
+    let adoption = adoptable(remote.as_ref(), &me, Utc::now())?;
+    client.hand_off_active_session(&adoption.from_device.id, &me.name)?;
+    Ok(adoption)
+}
+
+/// A running work session, as the timer reports it for the heartbeat
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Beat {
+    pub paused: bool,
+    pub remaining_seconds: u32,
+    pub work_minutes: u32,
+    pub tag: Option<String>,
+}
+
+impl Beat {
+    /// Same session in the same state, apart from the countdown
+    fn same_as(&self, other: &Beat) -> bool {
+        self.paused == other.paused
+            && self.work_minutes == other.work_minutes
+            && self.tag == other.tag
+    }
+}
+
+enum Message {
+    /// A session to publish, with the generation of the local session
+    Publish(u64, ActiveSession),
+    Clear(String),
+}
+
+/// Publishes the running session from a background thread, so the timer
+/// never waits on the network, and reports when another device takes it
+pub struct Heartbeat {
+    user_id: String,
+    device: Device,
+    /// Device the current session was taken over from
+    resumed_from: Option<String>,
+    tx: Sender<Message>,
+    taken_over: Receiver<(u64, String)>,
+    last: Option<(Beat, Instant)>,
+    /// Counts local sessions, so a takeover only ends the one it was for
+    generation: u64,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl Heartbeat {
+    /// Start publishing; None when not logged in
+    pub fn start(resumed_from: Option<String>) -> Result<Option<Self>> {
+        let Some(creds) = auth::load_credentials()? else {
+            return Ok(None);
+        };
+        let Some(client) = SupabaseClient::new()? else {
+            return Ok(None);
+        };
+        let device = Device::this()?;
+        let (tx, rx) = mpsc::channel();
+        let (taken_tx, taken_over) = mpsc::channel();
+        let me = device.clone();
+        let worker = std::thread::spawn(move || {
+            // Session this device has on the server, and one taken from it
+            let mut published = None;
+            let mut taken = None;
+            for message in rx {
+                // Offline is fine: the next heartbeat tries again
+                let _ =
+                    match message {
+                        Message::Publish(generation, _) if taken == Some(generation) => Ok(()),
+                        Message::Publish(generation, session) => client
+                            .get_active_session()
+                            .and_then(|remote| match remote.and_then(|r| taken_over_by(&r, &me)) {
+                                // Only a session that was published here can be
+                                // taken; a new one replaces what's left over
+                                Some(by) if published == Some(generation) => {
+                                    taken = Some(generation);
+                                    Ok(taken_tx.send((generation, by))?)
+                                }
+                                _ => {
+                                    client.publish_active_session(&session)?;
+                                    published = Some(generation);
+                                    Ok(())
+                                }
+                            }),
+                        Message::Clear(device_id) => client.clear_active_session(&device_id),
+                    };
+            }
+        });
+        let mut heartbeat = Self::new(creds.user_id, device, resumed_from, tx, taken_over);
+        heartbeat.worker = Some(worker);
+        Ok(Some(heartbeat))
+    }
+
+    fn new(
+        user_id: String,
+        device: Device,
+        resumed_from: Option<String>,
+        tx: Sender<Message>,
+        taken_over: Receiver<(u64, String)>,
+    ) -> Self {
+        Self {
+            user_id,
+            device,
+            resumed_from,
+            tx,
+            taken_over,
+            last: None,
+            generation: 0,
+            worker: None,
+        }
+    }
+
+    /// Clear the published session on quit, waiting at most
+    /// `FINISH_TIMEOUT` for the last request. When the network is slower
+    /// than that, the session goes stale on the server instead.
+    pub fn finish(self) {
+        self.finish_within(FINISH_TIMEOUT);
+    }
+
+    fn finish_within(mut self, timeout: Duration) {
+        self.tick(None);
+        let Self { tx, worker, .. } = self;
+        drop(tx);
+        if let Some(worker) = worker {
+            let (done_tx, done) = mpsc::channel();
+            std::thread::spawn(move || {
+                let _ = worker.join();
+                let _ = done_tx.send(());
+            });
+            let _ = done.recv_timeout(timeout);
+        }
+    }
+
+    /// Publish `beat` when it changed or a heartbeat is due, or clear the
+    /// published session once it's over. Returns the name of the device
+    /// that took the session over, if one did.
+    pub fn tick(&mut self, beat: Option<Beat>) -> Option<String> {
+        match beat {
+            Some(beat) => {
+                let due = match &self.last {
+                    None => {
+                        self.generation += 1;
+                        true
+                    }
+                    Some((last, at)) => {
+                        !last.same_as(&beat) || at.elapsed() >= Duration::from_secs(HEARTBEAT_SECS)
+                    }
+                };
+                if due {
+                    let session = self.session(&beat);
+                    let _ = self.tx.send(Message::Publish(self.generation, session));
+                    self.last = Some((beat, Instant::now()));
+                }
+            }
+            None => {
+                if self.last.take().is_some() {
+                    let _ = self.tx.send(Message::Clear(self.device.id.clone()));
+                }
+                self.resumed_from = None;
+            }
+        }
+        let current = self.last.is_some().then_some(self.generation);
+        self.taken_over
+            .try_iter()
+            .find(|(generation, _)| Some(*generation) == current)
+            .map(|(_, by)| by)
+    }
+
+    fn session(&self, beat: &Beat) -> ActiveSession {
+        ActiveSession {
+            user_id: self.user_id.clone(),
+            device_id: self.device.id.clone(),
+            device_name: self.device.name.clone(),
+            session_type: "work".to_string(),
+            paused: beat.paused,
+            remaining_seconds: beat.remaining_seconds as i32,
+            work_minutes: beat.work_minutes as i32,
+            tag: beat.tag.clone(),
+            heartbeat_at: Utc::now(),
+            handed_off_to: None,
+            resumed_from: self.resumed_from.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn device(id: &str) -> Device {
+        Device {
+            id: id.to_string(),
+            name: format!("{}-host", id),
+        }
+    }
+
+    fn remote(now: DateTime<Utc>, seconds_ago: i64, paused: bool) -> ActiveSession {
+        ActiveSession {
+            user_id: "user".to_string(),
+            device_id: "desktop".to_string(),
+            device_name: "desktop-host".to_string(),
+            session_type: "work".to_string(),
+            paused,
+            remaining_seconds: 600,
+            work_minutes: 25,
+            tag: Some("writing".to_string()),
+            heartbeat_at: now - chrono::Duration::seconds(seconds_ago),
+            handed_off_to: None,
+            resumed_from: None,
+        }
+    }
+
+    #[test]
+    fn test_adoption_counts_time_since_the_heartbeat() {
+        let now = Utc::now();
+        let laptop = device("laptop");
+        let running = adoptable(Some(&remote(now, 20, false)), &laptop, now).unwrap();
+        assert_eq!(running.remaining_seconds, 580);
+        assert_eq!(running.work_minutes, 25);
+        assert_eq!(running.tag.as_deref(), Some("writing"));
+        assert_eq!(running.from_device, device("desktop"));
+        // A paused timer hasn't moved
+        let paused = adoptable(Some(&remote(now, 20, true)), &laptop, now).unwrap();
+        assert_eq!(paused.remaining_seconds, 600);
+    }
+
+    #[test]
+    fn test_adoption_refuses_what_it_cannot_take() {
+        let now = Utc::now();
+        let laptop = device("laptop");
+        let reason = |session: Option<&ActiveSession>, me: &Device| {
+            adoptable(session, me, now).unwrap_err().to_string()
+        };
+        assert!(reason(None, &laptop).contains("No session"));
+        assert!(reason(Some(&remote(now, 0, false)), &device("desktop")).contains("already on"));
+        let mut taken = remote(now, 0, false);
+        taken.handed_off_to = Some("tablet".to_string());
+        assert!(reason(Some(&taken), &laptop).contains("taken over by tablet"));
+        assert!(reason(Some(&remote(now, 600, true)), &laptop).contains("10 minutes ago"));
+        let mut on_break = remote(now, 0, false);
+        on_break.session_type = "short_break".to_string();
+        assert!(reason(Some(&on_break), &laptop).contains("on a break"));
+        let mut finishing = remote(now, 30, false);
+        finishing.remaining_seconds = 20;
+        assert!(reason(Some(&finishing), &laptop).contains("just finished"));
+    }
+
+    #[test]
+    fn test_original_device_learns_it_was_taken_over() {
+        let now = Utc::now();
+        let desktop = device("desktop");
+        let mut session = remote(now, 0, false);
+        assert_eq!(taken_over_by(&session, &desktop), None);
+        session.handed_off_to = Some("laptop-host".to_string());
+        assert_eq!(
+            taken_over_by(&session, &desktop).as_deref(),
+            Some("laptop-host")
+        );
+        // The laptop has since replaced the row with its own
+        let mut resumed = remote(now, 0, false);
+        resumed.device_id = "laptop".to_string();
+        resumed.device_name = "laptop-host".to_string();
+        resumed.resumed_from = Some("desktop".to_string());
+        assert_eq!(
+            taken_over_by(&resumed, &desktop).as_deref(),
+            Some("laptop-host")
+        );
+        assert_eq!(taken_over_by(&resumed, &device("tablet")), None);
+    }
+
+    #[test]
+    fn test_heartbeat_publishes_changes_and_clears_once() {
+        let (tx, rx) = mpsc::channel();
+        let (taken_tx, taken_rx) = mpsc::channel();
+        let mut heartbeat = Heartbeat::new(
+            "user".to_string(),
+            device("laptop"),
+            Some("desktop".to_string()),
+            tx,
+            taken_rx,
+        );
+        let beat = |paused, remaining_seconds| Beat {
+            paused,
+            remaining_seconds,
+            work_minutes: 25,
+            tag: None,
+        };
+
+        assert_eq!(heartbeat.tick(Some(beat(false, 600))), None);
+        match rx.try_recv().unwrap() {
+            Message::Publish(generation, session) => {
+                assert_eq!(generation, 1);
+                assert_eq!(session.remaining_seconds, 600);
+                assert_eq!(session.resumed_from.as_deref(), Some("desktop"));
+            }
+            Message::Clear(_) => panic!("expected a publish"),
+        }
+        // The countdown alone waits for the next heartbeat
+        heartbeat.tick(Some(beat(false, 599)));
+        assert!(rx.try_recv().is_err());
+        heartbeat.tick(Some(beat(true, 599)));
+        assert!(matches!(rx.try_recv().unwrap(), Message::Publish(1, _)));
+
+        heartbeat.tick(None);
+        assert!(matches!(rx.try_recv().unwrap(), Message::Clear(id) if id == "laptop"));
+        heartbeat.tick(None);
+        assert!(rx.try_recv().is_err());
+
+        // The next session is this device's own
+        heartbeat.tick(Some(beat(false, 1500)));
+        match rx.try_recv().unwrap() {
+            Message::Publish(generation, session) => {
+                assert_eq!(generation, 2);
+                assert_eq!(session.resumed_from, None);
+            }
+            Message::Clear(_) => panic!("expected a publish"),
+        }
+        // Only a takeover of the session running now ends it
+        taken_tx.send((1, "tablet-host".to_string())).unwrap();
+        assert_eq!(heartbeat.tick(Some(beat(false, 1499))), None);
+        taken_tx.send((2, "tablet-host".to_string())).unwrap();
+        assert_eq!(
+            heartbeat.tick(Some(beat(false, 1498))).as_deref(),
+            Some("tablet-host")
+        );
+    }
+
+    #[test]
+    fn test_finish_does_not_wait_on_a_stuck_request() {
+        let (tx, rx) = mpsc::channel::<Message>();
+        let (_taken_tx, taken_rx) = mpsc::channel();
+        let mut heartbeat =
+            Heartbeat::new("user".to_string(), device("laptop"), None, tx, taken_rx);
+        // A worker whose request never comes back
+        let (_hold, stuck) = mpsc::channel::<()>();
+        heartbeat.worker = Some(std::thread::spawn(move || {
+            let _ = stuck.recv();
+            drop(rx);
+        }));
+        let started = Instant::now();
+        heartbeat.finish_within(Duration::from_millis(50));
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+}
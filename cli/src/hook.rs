@@ -0,0 +1,227 @@
+//! Git hook integration
+//!
+//! `sandoro hook install` adds a prepare-commit-msg hook that appends the
+//! current focus session (e.g. `[🍅 #3 writing]`) to commit messages made
+//! while a work session is active. The session is read from the live status
+//! file published by the running timer.
+
+use anyhow::{bail, Context, Result};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::status::{self, LiveStatus};
+
+const HOOK_NAME: &str = "prepare-commit-msg";
+/// Identifies hooks written by sandoro so we never touch anyone else's
+const HOOK_MARKER: &str = "# Installed by `sandoro hook install`";
+const BACKUP_SUFFIX: &str = "sandoro-backup";
+
+/// Hooks directory of the current repository, honoring core.hooksPath
+fn hooks_dir() -> Result<PathBuf> {
+    let output = Command::new("git")
+        .args(["rev-parse", "--git-path", "hooks"])
+        .output()
+        .context("Failed to run git")?;
+    if !output.status.success() {
+        bail!("Not inside a git repository");
+    }
+    let dir = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+fn hook_script(exe: &Path) -> String {
+    format!(
+        r#"#!/bin/sh
+{marker}
+# Appends the current focus session to commit messages. Remove with `sandoro hook remove`.
+case "$2" in merge|squash|commit) exit 0 ;; esac
+SANDORO="{exe}"
+[ -x "$SANDORO" ] || SANDORO=sandoro
+command -v "$SANDORO" >/dev/null 2>&1 || exit 0
+"$SANDORO" hook run "$1" >/dev/null 2>&1 || true
+"#,
+        marker = HOOK_MARKER,
+        exe = exe.display()
+    )
+}
+
+fn is_ours(path: &Path) -> bool {
+    std::fs::read_to_string(path).is_ok_and(|s| s.contains(HOOK_MARKER))
+}
+
+/// Install the hook in the current repository. An existing hook that
+/// sandoro didn't write is only replaced with `force`, and is kept as a backup.
+pub fn install(force: bool) -> Result<PathBuf> {
+    let path = hooks_dir()?.join(HOOK_NAME);
+    if path.exists() && !is_ours(&path) {
+        if !force {
+            bail!(
+                "{} already exists. Use --force to replace it (it will be kept as {}.{}).",
+                path.display(),
+                HOOK_NAME,
+                BACKUP_SUFFIX
+            );
+        }
+        std::fs::rename(&path, path.with_extension(BACKUP_SUFFIX))?;
+    }
+
+    let exe = std::env::current_exe().unwrap_or_else(|_| PathBuf::from("sandoro"));
+    std::fs::write(&path, hook_script(&exe))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))?;
+    }
+    Ok(path)
+}
+
+/// Remove the hook, restoring a hook it replaced
+pub fn remove() -> Result<PathBuf> {
+    let path = hooks_dir()?.join(HOOK_NAME);
+    if !path.exists() {
+        bail!("No {} hook installed", HOOK_NAME);
+    }
+    if !is_ours(&path) {
+        bail!("{} was not installed by sandoro", path.display());
+    }
+    std::fs::remove_file(&path)?;
+    let backup = path.with_extension(BACKUP_SUFFIX);
+    if backup.exists() {
+        std::fs::rename(backup, &path)?;
+    }
+    Ok(path)
+}
+
+/// Commit annotation for a running work session, e.g. `[🍅 #3 writing]`
+pub fn session_label(status: &LiveStatus) -> Option<String> {
+    if !matches!(status.state.as_str(), "work" | "timer") || !status.session_active {
+        return None;
+    }
+    Some(match &status.tag {
+        Some(tag) => format!("[🍅 #{} {}]", status.session_number, tag),
+        None => format!("[🍅 #{}]", status.session_number),
+    })
+}
+
+/// Append the label as the last paragraph of a commit message, before git's
+/// comment lines. Returns None if the message is already annotated, or has
+/// nothing but blank and comment lines, so git can still abort an empty
+/// commit.
+pub fn annotate(message: &str, label: &str) -> Option<String> {
+    if message.contains("[🍅 ") {
+        return None;
+    }
+    let lines: Vec<&str> = message.lines().collect();
+    let comments_at = lines
+        .iter()
+        .position(|l| l.starts_with('#'))
+        .unwrap_or(lines.len());
+    let mut body: Vec<&str> = lines[..comments_at].to_vec();
+    while body.last().is_some_and(|l| l.trim().is_empty()) {
+        body.pop();
+    }
+    if body.is_empty() {
+        return None;
+    }
+
+    let mut result = body.join("\n");
+    result.push_str("\n\n");
+    result.push_str(label);
+    result.push('\n');
+    if comments_at < lines.len() {
+        result.push('\n');
+        result.push_str(&lines[comments_at..].join("\n"));
+        result.push('\n');
+    }
+    Some(result)
+}
+
+/// Called by the hook with the commit message file
+pub fn run(message_file: &Path) -> Result<()> {
+    let Some(label) = status::read()?.as_ref().and_then(session_label) else {
+        return Ok(());
+    };
+    let message = std::fs::read_to_string(message_file)?;
+    if let Some(annotated) = annotate(&message, &label) {
+        std::fs::write(message_file, annotated)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn status(state: &str, active: bool, tag: Option<&str>) -> LiveStatus {
+        LiveStatus {
+            pid: 1,
+            state: state.to_string(),
+            paused: false,
+            remaining_seconds: 0,
+            elapsed_seconds: 0,
+            session_active: active,
+            session_number: 3,
+            cycle_position: 1,
+            cycle_length: 4,
+            tag: tag.map(str::to_string),
+            session_id: None,
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_session_label() {
+        assert_eq!(
+            session_label(&status("work", true, Some("writing"))).as_deref(),
+            Some("[🍅 #3 writing]")
+        );
+        assert_eq!(
+            session_label(&status("work", true, None)).as_deref(),
+            Some("[🍅 #3]")
+        );
+        assert_eq!(session_label(&status("work", false, None)), None);
+        assert_eq!(session_label(&status("short_break", true, None)), None);
+    }
+
+    #[test]
+    fn test_annotate_message_from_command_line() {
+        assert_eq!(
+            annotate("Fix parser\n", "[🍅 #3]").unwrap(),
+            "Fix parser\n\n[🍅 #3]\n"
+        );
+    }
+
+    #[test]
+    fn test_annotate_keeps_comments_last() {
+        let message = "Fix docs\n\n# Please enter the commit message\n# On branch main\n";
+        assert_eq!(
+            annotate(message, "[🍅 #1 docs]").unwrap(),
+            "Fix docs\n\n[🍅 #1 docs]\n\n# Please enter the commit message\n# On branch main\n"
+        );
+    }
+
+    #[test]
+    fn test_annotate_leaves_empty_messages_to_abort() {
+        assert_eq!(annotate("", "[🍅 #1]"), None);
+        assert_eq!(annotate("\n  \n", "[🍅 #1]"), None);
+        let commented = "\n# Please enter the commit message\n# On branch main\n";
+        assert_eq!(annotate(commented, "[🍅 #1]"), None);
+    }
+
+    #[test]
+    fn test_annotate_is_idempotent() {
+        let once = annotate("Fix\n", "[🍅 #3]").unwrap();
+        assert_eq!(annotate(&once, "[🍅 #4]"), None);
+    }
+
+    #[test]
+    fn test_hook_script_skips_amend_and_merge() {
+        let script = hook_script(Path::new("/usr/bin/sandoro"));
+        assert!(script.starts_with("#!/bin/sh\n"));
+        assert!(script.contains(HOOK_MARKER));
+        assert!(script.contains("merge|squash|commit) exit 0"));
+        assert!(script.contains("SANDORO=\"/usr/bin/sandoro\""));
+    }
+}
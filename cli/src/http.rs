@@ -0,0 +1,73 @@
+//! Telling failed requests worth retrying from ones that never will go
+//! through
+//!
+//! Outboxes (cloud sync, time trackers) retry with backoff when offline,
+//! but a request the server turns down for what it says, like a row it
+//! won't take, fails the same way every time. Those are set aside so they
+//! don't hold up the rest of the queue.
+
+use reqwest::StatusCode;
+
+/// The server answered with an error status
+#[derive(Debug)]
+pub struct StatusError {
+    pub status: StatusCode,
+    pub message: String,
+}
+
+impl StatusError {
+    pub fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+
+    /// A 4xx about the request itself. Authentication (401, 403), timeouts
+    /// (408) and rate limits (429) can clear up on their own, and so can 5xx.
+    pub fn is_permanent(&self) -> bool {
+        self.status.is_client_error()
+            && !matches!(
+                self.status,
+                StatusCode::UNAUTHORIZED
+                    | StatusCode::FORBIDDEN
+                    | StatusCode::REQUEST_TIMEOUT
+                    | StatusCode::TOO_MANY_REQUESTS
+            )
+    }
+}
+
+impl std::fmt::Display for StatusError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for StatusError {}
+
+/// Whether sending the same request again can't help. Network errors and
+/// anything but a `StatusError` are taken as passing.
+pub fn is_permanent(err: &anyhow::Error) -> bool {
+    err.downcast_ref::<StatusError>()
+        .is_some_and(StatusError::is_permanent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_request_errors_are_permanent() {
+        let err = |code: u16| -> anyhow::Error {
+            StatusError::new(StatusCode::from_u16(code).unwrap(), "failed").into()
+        };
+        assert!(is_permanent(&err(400)));
+        assert!(is_permanent(&err(422)));
+        for code in [401, 403, 408, 429, 500, 503] {
+            assert!(!is_permanent(&err(code)), "{}", code);
+        }
+        assert!(!is_permanent(&anyhow::anyhow!("connection refused")));
+        // Context added on the way up keeps it recognizable
+        assert!(is_permanent(&err(409).context("Failed to sync session 3")));
+    }
+}
@@ -0,0 +1,1090 @@
+//! Translations of what the user reads: the timer screen, settings, stats
+//! and the rest of the CLI output, and `--help`
+//!
+//! Follows `appearance.language`: Japanese, English, Spanish, German or
+//! Chinese. Every string is looked up in one table, [`STRINGS`], keyed by
+//! its message id. The id is the English text itself, so English needs no
+//! row and anything missing from the table falls back to English. Where one
+//! English word needs two translations, the id starts with a context and
+//! `\u{4}`, as in gettext ("goals\u{4}Daily"). Sentences with values in them
+//! are templates with `{0}`, `{1}`… so each language can put the values
+//! where its grammar wants them; see [`fill`].
+//!
+//! Units and dates have helpers of their own: Japanese and Chinese use
+//! their own units ("1時間25分", "10月17日"), while numbers, percentages and
+//! ISO dates stay as they are so columns line up. Tag names, notes and
+//! anything meant for scripts (`--json`, exports) are never translated.
+
+use chrono::{Datelike, NaiveDate, Weekday};
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::OnceLock;
+
+use crate::messages::Language;
+
+/// Index into `Language::ALL` of the output language
+static LANGUAGE: AtomicU8 = AtomicU8::new(1);
+
+/// Pick the output language once, at startup
+pub fn init(language: &str) {
+    let lang = Language::from_str(language);
+    let index = Language::ALL.iter().position(|l| *l == lang).unwrap_or(1);
+    LANGUAGE.store(index as u8, Ordering::Relaxed);
+}
+
+/// The output language picked by [`init`]; English until then
+pub fn language() -> Language {
+    Language::ALL[LANGUAGE.load(Ordering::Relaxed) as usize]
+}
+
+/// `id` in the output language
+pub fn t(id: &'static str) -> &'static str {
+    translate(language(), id)
+}
+
+/// `id` in `lang`, an `appearance.language` value; for the timer, which
+/// reads the language from its config
+pub fn t_in(lang: &str, id: &'static str) -> &'static str {
+    translate(Language::from_str(lang), id)
+}
+
+/// The template `id` in the output language with `{0}`, `{1}`… replaced by
+/// `args`
+pub fn fill(id: &'static str, args: &[&dyn Display]) -> String {
+    fill_template(t(id), args)
+}
+
+/// [`fill`] in `lang`, an `appearance.language` value
+pub fn fill_in(lang: &str, id: &'static str, args: &[&dyn Display]) -> String {
+    fill_template(t_in(lang, id), args)
+}
+
+fn translate(lang: Language, id: &'static str) -> &'static str {
+    let translated = column(lang).and_then(|column| table().get(id).map(|row| row[column]));
+    translated.unwrap_or_else(|| english(id))
+}
+
+/// Column of `lang` in [`STRINGS`]; None for English, which is the id
+fn column(lang: Language) -> Option<usize> {
+    match lang {
+        Language::English => None,
+        Language::Japanese => Some(0),
+        Language::Spanish => Some(1),
+        Language::German => Some(2),
+        Language::Chinese => Some(3),
+    }
+}
+
+/// The id without its context
+fn english(id: &'static str) -> &'static str {
+    id.split_once('\u{4}').map_or(id, |(_, text)| text)
+}
+
+fn table() -> &'static HashMap<&'static str, [&'static str; 4]> {
+    static TABLE: OnceLock<HashMap<&'static str, [&'static str; 4]>> = OnceLock::new();
+    TABLE.get_or_init(|| STRINGS.iter().copied().collect())
+}
+
+fn fill_template(template: &str, args: &[&dyn Display]) -> String {
+    args.iter()
+        .enumerate()
+        .fold(template.to_string(), |text, (i, arg)| {
+            text.replace(&format!("{{{}}}", i), &arg.to_string())
+        })
+}
+
+/// "1h 25m" / "1時間25分" / "1小时25分钟"; under an hour, just the minutes
+pub fn duration(seconds: i32) -> String {
+    duration_in(seconds, language())
+}
+
+fn duration_in(seconds: i32, lang: Language) -> String {
+    let hours = seconds / 3600;
+    let minutes = (seconds % 3600) / 60;
+    match (hours > 0, lang) {
+        (true, Language::Japanese) => format!("{}時間{}分", hours, minutes),
+        (false, Language::Japanese) => format!("{}分", minutes),
+        (true, Language::Chinese) => format!("{}小时{}分钟", hours, minutes),
+        (false, Language::Chinese) => format!("{}分钟", minutes),
+        (true, _) => format!("{}h {}m", hours, minutes),
+        (false, _) => format!("{}m", minutes),
+    }
+}
+
+/// "90m" / "90分", for goals set in minutes
+pub fn minutes(count: impl Display) -> String {
+    match language() {
+        Language::Japanese => format!("{}分", count),
+        Language::Chinese => format!("{}分钟", count),
+        _ => format!("{}m", count),
+    }
+}
+
+/// "4 sessions" / "4セッション"
+pub fn sessions(count: impl Display) -> String {
+    match language() {
+        Language::Japanese => format!("{}セッション", count),
+        Language::English => format!("{} sessions", count),
+        Language::Spanish => format!("{} sesiones", count),
+        Language::German => format!("{} Sitzungen", count),
+        Language::Chinese => format!("{}次专注", count),
+    }
+}
+
+/// "12 days" / "12日"
+pub fn days(count: impl Display) -> String {
+    match language() {
+        Language::Japanese => format!("{}日", count),
+        Language::English => format!("{} days", count),
+        Language::Spanish => format!("{} días", count),
+        Language::German => format!("{} Tage", count),
+        Language::Chinese => format!("{}天", count),
+    }
+}
+
+/// "Oct 17" / "10月17日" / "17 oct" / "17. Okt."
+pub fn month_day(date: NaiveDate) -> String {
+    month_day_in(date, language())
+}
+
+fn month_day_in(date: NaiveDate, lang: Language) -> String {
+    let month = date.month0() as usize;
+    match lang {
+        Language::Japanese | Language::Chinese => {
+            format!("{}月{}日", date.month(), date.day())
+        }
+        Language::English => date.format("%b %d").to_string(),
+        Language::Spanish => format!("{} {}", date.day(), MONTHS_ES[month]),
+        Language::German => format!("{}. {}", date.day(), MONTHS_DE[month]),
+    }
+}
+
+const MONTHS_ES: [&str; 12] = [
+    "ene", "feb", "mar", "abr", "may", "jun", "jul", "ago", "sept", "oct", "nov", "dic",
+];
+const MONTHS_DE: [&str; 12] = [
+    "Jan.", "Feb.", "März", "Apr.", "Mai", "Juni", "Juli", "Aug.", "Sept.", "Okt.", "Nov.", "Dez.",
+];
+
+/// "Fri Oct 17" / "10月17日(金)"
+pub fn weekday_month_day(date: NaiveDate) -> String {
+    let lang = language();
+    let day = weekday_short_in(date.weekday(), lang);
+    match lang {
+        Language::Japanese => format!("{}({})", month_day_in(date, lang), day),
+        Language::English => date.format("%a %b %d").to_string(),
+        _ => format!("{} {}", day, month_day_in(date, lang)),
+    }
+}
+
+/// "Monday" / "月曜日"
+pub fn weekday(day: Weekday) -> String {
+    weekday_in(day, language())
+}
+
+pub fn weekday_in(day: Weekday, lang: Language) -> String {
+    let names = match lang {
+        Language::Japanese => {
+            return format!("{}曜日", weekday_short_in(day, lang));
+        }
+        Language::English => [
+            "Monday",
+            "Tuesday",
+            "Wednesday",
+            "Thursday",
+            "Friday",
+            "Saturday",
+            "Sunday",
+        ],
+        Language::Spanish => [
+            "lunes",
+            "martes",
+            "miércoles",
+            "jueves",
+            "viernes",
+            "sábado",
+            "domingo",
+        ],
+        Language::German => [
+            "Montag",
+            "Dienstag",
+            "Mittwoch",
+            "Donnerstag",
+            "Freitag",
+            "Samstag",
+            "Sonntag",
+        ],
+        Language::Chinese => [
+            "星期一",
+            "星期二",
+            "星期三",
+            "星期四",
+            "星期五",
+            "星期六",
+            "星期日",
+        ],
+    };
+    names[day.num_days_from_monday() as usize].to_string()
+}
+
+/// "Mon" / "月"
+pub fn weekday_short(day: Weekday) -> &'static str {
+    weekday_short_in(day, language())
+}
+
+fn weekday_short_in(day: Weekday, lang: Language) -> &'static str {
+    let names = match lang {
+        Language::Japanese => ["月", "火", "水", "木", "金", "土", "日"],
+        Language::English => ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"],
+        Language::Spanish => ["lun", "mar", "mié", "jue", "vie", "sáb", "dom"],
+        Language::German => ["Mo", "Di", "Mi", "Do", "Fr", "Sa", "So"],
+        Language::Chinese => ["周一", "周二", "周三", "周四", "周五", "周六", "周日"],
+    };
+    names[day.num_days_from_monday() as usize]
+}
+
+/// One-letter (or one-character) weekday labels from Sunday, for the rows
+/// of the heatmap
+pub fn weekday_initials() -> [&'static str; 7] {
+    match language() {
+        Language::Japanese => ["日", "月", "火", "水", "木", "金", "土"],
+        Language::English => ["S", "M", "T", "W", "T", "F", "S"],
+        Language::Spanish => ["D", "L", "M", "X", "J", "V", "S"],
+        Language::German => ["S", "M", "D", "M", "D", "F", "S"],
+        Language::Chinese => ["日", "一", "二", "三", "四", "五", "六"],
+    }
+}
+
+/// `text` padded with spaces to `width` terminal columns; `{:<width}`
+/// counts characters, which puts Japanese labels out of line
+pub fn pad(text: &str, width: usize) -> String {
+    format!(
+        "{}{}",
+        text,
+        " ".repeat(width.saturating_sub(self::width(text)))
+    )
+}
+
+/// Terminal columns `text` takes
+pub fn width(text: &str) -> usize {
+    text.chars().map(columns).sum()
+}
+
+/// Columns a character takes in the terminal: two for CJK and full-width
+fn columns(c: char) -> usize {
+    match c as u32 {
+        0x1100..=0x115F
+        | 0x2E80..=0xA4CF
+        | 0xAC00..=0xD7A3
+        | 0xF900..=0xFAFF
+        | 0xFF00..=0xFF60
+        | 0xFFE0..=0xFFE6 => 2,
+        _ => 1,
+    }
+}
+
+/// One-line help for the top-level command ("") and its subcommands, in
+/// Japanese, Spanish, German and Chinese
+const HELP: [(&str, [&str; 4]); 34] = [
+    (
+        "",
+        [
+            "ASCIIアートで動く、ターミナル向けポモドーロタイマー",
+            "Temporizador Pomodoro para la terminal con animaciones en ASCII",
+            "Pomodoro-Timer für das Terminal mit ASCII-Animationen",
+            "终端里的番茄钟，带 ASCII 动画",
+        ],
+    ),
+    (
+        "start",
+        [
+            "ポモドーロを始める",
+            "Empezar un pomodoro",
+            "Einen Pomodoro starten",
+            "开始一个番茄钟",
+        ],
+    ),
+    (
+        "stats",
+        [
+            "統計を表示する",
+            "Mostrar estadísticas",
+            "Statistiken anzeigen",
+            "显示统计",
+        ],
+    ),
+    (
+        "login",
+        [
+            "ログインしてクラウドと同期する",
+            "Iniciar sesión para sincronizar con la nube",
+            "Anmelden, um mit der Cloud zu synchronisieren",
+            "登录并与云端同步",
+        ],
+    ),
+    (
+        "logout",
+        [
+            "ログアウトして保存した認証情報を削除する",
+            "Cerrar sesión y borrar las credenciales guardadas",
+            "Abmelden und gespeicherte Zugangsdaten löschen",
+            "退出登录并删除保存的凭据",
+        ],
+    ),
+    (
+        "whoami",
+        [
+            "同期先のアカウントを表示する",
+            "Mostrar con qué cuenta se sincroniza",
+            "Anzeigen, mit welchem Konto synchronisiert wird",
+            "显示同步到哪个账号",
+        ],
+    ),
+    (
+        "sync",
+        [
+            "ローカルのデータをクラウドと同期する (docs/SYNC.md を参照)",
+            "Sincronizar los datos locales con la nube (ver docs/SYNC.md)",
+            "Lokale Daten mit der Cloud synchronisieren (siehe docs/SYNC.md)",
+            "将本地数据与云端同步（见 docs/SYNC.md）",
+        ],
+    ),
+    (
+        "search",
+        [
+            "タグやメモから作業セッションを探す",
+            "Buscar sesiones de trabajo por etiqueta o notas",
+            "Arbeitssitzungen nach Tag oder Notizen suchen",
+            "按标签或备注查找专注记录",
+        ],
+    ),
+    (
+        "insights",
+        [
+            "直近2週間のセッションから作業時間を提案する",
+            "Sugerir una duración de trabajo a partir de las dos últimas semanas",
+            "Eine Arbeitsdauer aus den letzten zwei Wochen vorschlagen",
+            "根据最近两周的记录建议专注时长",
+        ],
+    ),
+    (
+        "journal",
+        [
+            "一日の終わりにひとことメモを書く、または最近のメモを一覧する",
+            "Escribir una nota al final del día o listar las recientes",
+            "Eine kurze Notiz zum Tagesende schreiben oder die letzten auflisten",
+            "写一句今日小结，或列出最近的小结",
+        ],
+    ),
+    (
+        "sessions",
+        [
+            "記録したセッションを確認する",
+            "Revisar las sesiones registradas",
+            "Aufgezeichnete Sitzungen ansehen",
+            "查看记录的专注",
+        ],
+    ),
+    (
+        "db",
+        [
+            "データベースのメンテナンス",
+            "Mantenimiento de la base de datos",
+            "Datenbankwartung",
+            "数据库维护",
+        ],
+    ),
+    (
+        "hook",
+        [
+            "コミットに現在のセッションを記録するgitフックを管理する",
+            "Gestionar el hook de git que etiqueta los commits con la sesión actual",
+            "Den Git-Hook verwalten, der Commits mit der aktuellen Sitzung markiert",
+            "管理在提交中记录当前专注的 git 钩子",
+        ],
+    ),
+    (
+        "track",
+        [
+            "完了した作業セッションをTogglやClockifyに送る",
+            "Enviar las sesiones de trabajo completadas a Toggl o Clockify",
+            "Abgeschlossene Arbeitssitzungen an Toggl oder Clockify senden",
+            "将完成的专注发送到 Toggl 或 Clockify",
+        ],
+    ),
+    (
+        "import",
+        [
+            "TogglやClockifyなどのCSVからセッションを取り込む",
+            "Añadir sesiones desde un CSV de Toggl, Clockify u otro",
+            "Sitzungen aus einem CSV-Export von Toggl, Clockify u. a. übernehmen",
+            "从 Toggl、Clockify 等导出的 CSV 导入记录",
+        ],
+    ),
+    (
+        "url",
+        [
+            "sandoro:// のURLを処理する (docs/URL_SCHEME.md を参照)",
+            "Abrir una URL sandoro:// (ver docs/URL_SCHEME.md)",
+            "Eine sandoro://-URL verarbeiten (siehe docs/URL_SCHEME.md)",
+            "处理 sandoro:// 链接（见 docs/URL_SCHEME.md）",
+        ],
+    ),
+    (
+        "serve",
+        [
+            "埋め込み用のSVG画像やスマホ用のリモコンをHTTPで配信する (docs/SERVE.md を参照)",
+            "Servir por HTTP imágenes SVG para incrustar y un mando para el móvil (ver docs/SERVE.md)",
+            "SVG-Bilder zum Einbetten und eine Handy-Fernbedienung per HTTP bereitstellen (siehe docs/SERVE.md)",
+            "通过 HTTP 提供可嵌入的 SVG 图片和手机遥控页（见 docs/SERVE.md）",
+        ],
+    ),
+    (
+        "snooze",
+        [
+            "実行中の休憩を延長する、または終わったばかりの休憩を戻す",
+            "Alargar el descanso en curso o recuperar el que acaba de terminar",
+            "Die laufende Pause verlängern oder die gerade beendete zurückholen",
+            "延长当前休息，或恢复刚结束的休息",
+        ],
+    ),
+    (
+        "break",
+        [
+            "実行中のタイマーを休憩に切り替える",
+            "Pasar el temporizador en marcha a un descanso",
+            "Den laufenden Timer auf Pause umstellen",
+            "将正在运行的计时切换为休息",
+        ],
+    ),
+    (
+        "work",
+        [
+            "実行中のタイマーを作業に戻す",
+            "Volver a poner el temporizador en marcha en trabajo",
+            "Den laufenden Timer wieder auf Arbeit stellen",
+            "将正在运行的计时切换回专注",
+        ],
+    ),
+    (
+        "daemon",
+        [
+            "タイマーをバックグラウンドで動かす (`sandoro ctl` で操作)",
+            "Ejecutar el temporizador en segundo plano (se controla con `sandoro ctl`)",
+            "Den Timer im Hintergrund laufen lassen (Steuerung mit `sandoro ctl`)",
+            "在后台运行计时（用 `sandoro ctl` 控制）",
+        ],
+    ),
+    (
+        "status",
+        [
+            "実行中のタイマーをステータスバー向けに1行で表示する (waybar・polybar・tmux)",
+            "Mostrar el temporizador en marcha en una línea para barras de estado (waybar, polybar, tmux)",
+            "Den laufenden Timer als eine Zeile für Statusleisten zeigen (waybar, polybar, tmux)",
+            "为状态栏单行显示正在运行的计时（waybar、polybar、tmux）",
+        ],
+    ),
+    (
+        "ctl",
+        [
+            "`sandoro daemon` で動かしたタイマーを操作する",
+            "Controlar el temporizador iniciado con `sandoro daemon`",
+            "Den mit `sandoro daemon` gestarteten Timer steuern",
+            "控制由 `sandoro daemon` 运行的计时",
+        ],
+    ),
+    (
+        "lock",
+        [
+            "タグ・メモ・履歴を隠す (プライベートモード)",
+            "Ocultar etiquetas, notas e historial (modo privado)",
+            "Tags, Notizen und Verlauf verbergen (privater Modus)",
+            "隐藏标签、备注和历史（隐私模式）",
+        ],
+    ),
+    (
+        "unlock",
+        [
+            "プライベートモードを解除する (解除の文字列を入力)",
+            "Salir del modo privado (escribiendo la secuencia de desbloqueo)",
+            "Den privaten Modus beenden (Entsperrfolge eingeben)",
+            "退出隐私模式（输入解锁序列）",
+        ],
+    ),
+    (
+        "demo",
+        [
+            "架空の履歴でタイマーを見せる (スクリーンショットやGIF用)",
+            "Mostrar el temporizador con un historial inventado (capturas y GIF)",
+            "Den Timer mit erfundenem Verlauf zeigen (Screenshots und GIFs)",
+            "用虚构的历史展示计时（用于截图和 GIF）",
+        ],
+    ),
+    (
+        "plan",
+        [
+            "今週のタグごとのセッション数を計画する、または進み具合を表示する",
+            "Planificar las sesiones de la semana por etiqueta o ver cómo va",
+            "Die Sitzungen der Woche pro Tag planen oder den Stand zeigen",
+            "按标签规划本周的专注次数，或查看进度",
+        ],
+    ),
+    (
+        "review",
+        [
+            "先週を振り返り、ひとこと書いて今週の目標を決める",
+            "Repasar la semana pasada, escribir una nota y fijar los objetivos de esta",
+            "Auf die letzte Woche zurückblicken, eine Notiz schreiben und Ziele setzen",
+            "回顾上周，写一句话并设定本周目标",
+        ],
+    ),
+    (
+        "level",
+        [
+            "XPのレベルと次のレベルまでの進み具合を表示する",
+            "Mostrar el nivel de XP y lo que falta para el siguiente",
+            "XP-Stufe und den Fortschritt zur nächsten zeigen",
+            "显示 XP 等级和升级进度",
+        ],
+    ),
+    (
+        "report",
+        [
+            "週間・月間のまとめを表示するか、過去30日間を単体HTMLページに書き出す",
+            "Mostrar un resumen semanal o mensual, o guardar los últimos 30 días como una página HTML independiente",
+            "Eine Wochen- oder Monatsübersicht ausgeben oder die letzten 30 Tage als eigenständige HTML-Seite speichern",
+            "显示每周或每月总结，或将最近30天导出为单个 HTML 页面",
+        ],
+    ),
+    (
+        "share",
+        [
+            "集中時間・連続日数・最高の日とヒートマップをまとめたカードを書き出す",
+            "Guardar una tarjeta con el tiempo de enfoque, la racha, el mejor día y el mapa de calor",
+            "Eine Karte mit Fokuszeit, Serie, bestem Tag und Heatmap speichern",
+            "导出一张包含专注时长、连续天数、最佳一天和热力图的卡片",
+        ],
+    ),
+    (
+        "schema",
+        [
+            "データベースのスキーマ・マイグレーション・行数と有効な設定を表示する",
+            "Mostrar el esquema, las migraciones y las filas de la base de datos y la configuración en uso",
+            "Schema, Migrationen und Zeilenzahlen der Datenbank sowie die wirksame Konfiguration zeigen",
+            "显示数据库结构、迁移、行数和生效的配置",
+        ],
+    ),
+    (
+        "routine",
+        [
+            "設定したルーティンを順に実行する、またはルーティンを一覧する",
+            "Recorrer una rutina configurada o listar las rutinas",
+            "Eine eingerichtete Routine durchlaufen oder Routinen auflisten",
+            "依次运行设定的例程，或列出例程",
+        ],
+    ),
+    (
+        "config",
+        [
+            "タイマーを開かずに設定を表示・変更する",
+            "Ver o cambiar la configuración sin abrir el temporizador",
+            "Einstellungen ansehen oder ändern, ohne den Timer zu öffnen",
+            "不打开计时器查看或修改设置",
+        ],
+    ),
+];
+
+/// Help for the subcommand `name` ("" for sandoro itself) in the output
+/// language; None in English, which is the help clap already has
+pub fn help(name: &str) -> Option<&'static str> {
+    help_in(name, language())
+}
+
+fn help_in(name: &str, lang: Language) -> Option<&'static str> {
+    let column = column(lang)?;
+    HELP.iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, help)| help[column])
+}
+
+/// Translate the one-line help of `command` and its subcommands; flags
+/// keep their English help
+pub fn localize_help(mut command: clap::Command) -> clap::Command {
+    if let Some(about) = help("") {
+        command = command.about(about);
+    }
+    let names: Vec<String> = command
+        .get_subcommands()
+        .map(|c| c.get_name().to_string())
+        .collect();
+    for name in names {
+        if let Some(about) = help(&name) {
+            command = command.mut_subcommand(&name, |c| c.about(about));
+        }
+    }
+    command
+}
+
+/// Translations by message id, in Japanese, Spanish, German and Chinese
+const STRINGS: &[(&str, [&str; 4])] = &[
+    // Timer screen and its prompts
+    ("WORKING", ["作業中", "TRABAJANDO", "ARBEIT", "专注中"]),
+    ("SHORT BREAK", ["短い休憩", "DESCANSO CORTO", "KURZE PAUSE", "短休息"]),
+    ("LONG BREAK", ["長い休憩", "DESCANSO LARGO", "LANGE PAUSE", "长休息"]),
+    ("TIMER", ["タイマー", "TEMPORIZADOR", "TIMER", "计时"]),
+    ("PAUSED", ["一時停止", "EN PAUSA", "PAUSIERT", "已暂停"]),
+    ("Today: {0}  ({1} sessions)", ["今日: {0}  ({1}セッション)", "Hoy: {0}  ({1} sesiones)", "Heute: {0}  ({1} Sitzungen)", "今天: {0}  ({1}次专注)"]),
+    ("Today: …  (loading)", ["今日: …  (読み込み中)", "Hoy: …  (cargando)", "Heute: …  (lädt)", "今天: …  (加载中)"]),
+    ("Round: {0}/{1} {2}", ["ラウンド: {0}/{1} {2}", "Ronda: {0}/{1} {2}", "Runde: {0}/{1} {2}", "轮次: {0}/{1} {2}"]),
+    ("Routine: {0}", ["ルーティン: {0}", "Rutina: {0}", "Routine: {0}", "例程: {0}"]),
+    ("Tag:", ["タグ:", "Etiqueta:", "Tag:", "标签:"]),
+    ("Goal", ["目標", "Objetivo", "Ziel", "目标"]),
+    ("Plan", ["計画", "Plan", "Plan", "计划"]),
+    ("ephemeral", ["保存なし", "efímero", "flüchtig", "不保存"]),
+    ("strict", ["厳格", "estricto", "strikt", "严格"]),
+    ("(overridden)", ["(手動で変更)", "(cambiada)", "(überschrieben)", "(已手动更改)"]),
+    ("quality focus {0}", ["質の高い集中 {0}", "enfoque de calidad {0}", "Qualitätsfokus {0}", "高质量专注 {0}"]),
+    ("⏳ {0} over budget: {1} of {2} today", ["⏳ {0} が上限超過: 今日 {1} / {2}", "⏳ {0} supera su límite: {1} de {2} hoy", "⏳ {0} über dem Limit: heute {1} von {2}", "⏳ {0} 超出上限：今天 {1} / {2}"]),
+    ("⚠ do not disturb: {0}", ["⚠ おやすみモード: {0}", "⚠ no molestar: {0}", "⚠ Nicht stören: {0}", "⚠ 勿扰模式：{0}"]),
+    ("Today {0} · {1} sessions", ["今日 {0} · {1}セッション", "Hoy {0} · {1} sesiones", "Heute {0} · {1} Sitzungen", "今天 {0} · {1}次专注"]),
+    ("Today … · loading", ["今日 … · 読み込み中", "Hoy … · cargando", "Heute … · lädt", "今天 … · 加载中"]),
+    ("Breathe in", ["吸って", "Inspira", "Einatmen", "吸气"]),
+    ("Breathe out", ["吐いて", "Espira", "Ausatmen", "呼气"]),
+    ("Hold", ["止めて", "Mantén", "Halten", "屏息"]),
+    ("Score: {0}  [←↑↓→/hjkl]", ["スコア: {0}  [←↑↓→/hjkl]", "Puntos: {0}  [←↑↓→/hjkl]", "Punkte: {0}  [←↑↓→/hjkl]", "得分: {0}  [←↑↓→/hjkl]"]),
+    ("Game over ({0})  Arrow key to restart", ["ゲームオーバー ({0})  方向キーでリスタート", "Fin de la partida ({0})  Flecha para reiniciar", "Spiel vorbei ({0})  Pfeiltaste für Neustart", "游戏结束 ({0})  按方向键重新开始"]),
+    ("Session note", ["セッションのメモ", "Nota de la sesión", "Sitzungsnotiz", "专注备注"]),
+    ("[Enter] Save  [Esc] Skip", ["[Enter] 保存  [Esc] スキップ", "[Enter] Guardar  [Esc] Omitir", "[Enter] Speichern  [Esc] Überspringen", "[Enter] 保存  [Esc] 跳过"]),
+    ("Interruption", ["中断", "Interrupción", "Unterbrechung", "打断"]),
+    ("Interrupted at {0} (#{1} this session)", ["{0} に中断 (このセッションで{1}回目)", "Interrupción a las {0} (n.º {1} en esta sesión)", "Unterbrochen um {0} (Nr. {1} in dieser Sitzung)", "{0} 被打断（本次第 {1} 次）"]),
+    ("[']  internal   [-]  external", ["[']  内的   [-]  外的", "[']  interna   [-]  externa", "[']  intern   [-]  extern", "[']  内部   [-]  外部"]),
+    ("or any other key as the category", ["ほかのキーはその文字を分類にする", "o cualquier otra tecla como categoría", "oder eine andere Taste als Kategorie", "或按其他键作为分类"]),
+    ("[Enter] No category  [Esc] Cancel", ["[Enter] 分類なし  [Esc] キャンセル", "[Enter] Sin categoría  [Esc] Cancelar", "[Enter] Ohne Kategorie  [Esc] Abbrechen", "[Enter] 不分类  [Esc] 取消"]),
+    ("Routines", ["ルーティン", "Rutinas", "Routinen", "例程"]),
+    ("[↑↓/jk] Choose  [Enter] Start  [Esc] Close", ["[↑↓/jk] 選択  [Enter] 開始  [Esc] 閉じる", "[↑↓/jk] Elegir  [Enter] Empezar  [Esc] Cerrar", "[↑↓/jk] Wählen  [Enter] Starten  [Esc] Schließen", "[↑↓/jk] 选择  [Enter] 开始  [Esc] 关闭"]),
+    ("Notification Routing", ["通知の振り分け", "Destino de los avisos", "Benachrichtigungswege", "通知去向"]),
+    ("Sound or desktop is OFF in Settings for every event", ["設定で音かデスクトップ通知がすべてのイベントでOFFです", "El sonido o el escritorio está desactivado en Ajustes para todos los eventos", "Ton oder Desktop ist in den Einstellungen für alle Ereignisse AUS", "设置中的声音或桌面通知对所有事件都已关闭"]),
+    ("[↑↓/jk] Choose  [Enter/→] Change  [Esc] Close", ["[↑↓/jk] 選択  [Enter/→] 変更  [Esc] 閉じる", "[↑↓/jk] Elegir  [Enter/→] Cambiar  [Esc] Cerrar", "[↑↓/jk] Wählen  [Enter/→] Ändern  [Esc] Schließen", "[↑↓/jk] 选择  [Enter/→] 更改  [Esc] 关闭"]),
+    ("Resume previous session?", ["前回のセッションを再開しますか？", "¿Reanudar la sesión anterior?", "Vorherige Sitzung fortsetzen?", "继续上次的专注？"]),
+    ("Work", ["作業", "Trabajo", "Arbeit", "专注"]),
+    ("Short break", ["短い休憩", "Descanso corto", "Kurze Pause", "短休息"]),
+    ("Long break", ["長い休憩", "Descanso largo", "Lange Pause", "长休息"]),
+    ("{0} in", ["{0} 経過", "{0} transcurridos", "{0} vergangen", "已进行 {0}"]),
+    ("{0} left", ["残り {0}", "quedan {0}", "noch {0}", "剩余 {0}"]),
+    ("{0}, quit {1}", ["{0}、{1} に終了", "{0}, cerrado el {1}", "{0}, beendet {1}", "{0}，{1} 退出"]),
+    ("[r] Resume  [n] Start fresh", ["[r] 再開  [n] 新しく始める", "[r] Reanudar  [n] Empezar de nuevo", "[r] Fortsetzen  [n] Neu beginnen", "[r] 继续  [n] 重新开始"]),
+    ("Unfinished session", ["終わっていないセッション", "Sesión sin terminar", "Unbeendete Sitzung", "未完成的专注"]),
+    ("Unfinished session (1 of {0})", ["終わっていないセッション (1 / {0})", "Sesión sin terminar (1 de {0})", "Unbeendete Sitzung (1 von {0})", "未完成的专注（1 / {0}）"]),
+    ("Started {0}, planned {1}", ["{0} に開始、予定 {1}", "Empezó el {0}, previsto {1}", "Begonnen {0}, geplant {1}", "{0} 开始，计划 {1}"]),
+    ("Started {0}, open-ended", ["{0} に開始、時間の予定なし", "Empezó el {0}, sin duración fija", "Begonnen {0}, ohne feste Dauer", "{0} 开始，不限时长"]),
+    ("About {0} done when the timer stopped", ["タイマーが止まった時点で約 {0}", "Unos {0} hechos cuando se detuvo el temporizador", "Etwa {0} geschafft, als der Timer stoppte", "计时停止时约完成 {0}"]),
+    ("Up to {0} (no record of when it stopped)", ["最大 {0} (止まった時刻の記録なし)", "Hasta {0} (no consta cuándo se detuvo)", "Bis zu {0} (kein Eintrag, wann er stoppte)", "最多 {0}（没有停止时间的记录）"]),
+    ("No record of how long it ran", ["動いていた時間の記録はありません", "No consta cuánto duró", "Kein Eintrag, wie lange er lief", "没有运行时长的记录"]),
+    ("[c] Complete ({0})  ", ["[c] 完了 ({0})  ", "[c] Completar ({0})  ", "[c] Abschließen ({0})  ", "[c] 完成 ({0})  "]),
+    ("[C/I/D] All {0}  ", ["[C/I/D] {0}件すべて  ", "[C/I/D] Las {0}  ", "[C/I/D] Alle {0}  ", "[C/I/D] 全部 {0} 条  "]),
+    ("[i] Interrupted  [d] Discard", ["[i] 中断  [d] 破棄", "[i] Interrumpida  [d] Descartar", "[i] Unterbrochen  [d] Verwerfen", "[i] 已中断  [d] 丢弃"]),
+    ("[Esc] Later", ["[Esc] あとで", "[Esc] Más tarde", "[Esc] Später", "[Esc] 稍后"]),
+    ("Today", ["今日", "Hoy", "Heute", "今天"]),
+    ("Planned", ["予定", "Previstas", "Geplant", "计划"]),
+    ("Doing", ["作業中", "En curso", "In Arbeit", "进行中"]),
+    ("Done", ["完了", "Hechas", "Erledigt", "已完成"]),
+    ("[a] Add a task", ["[a] タスクを追加", "[a] Añadir una tarea", "[a] Aufgabe hinzufügen", "[a] 添加任务"]),
+    ("[Enter] Bind a task to the timer", ["[Enter] タスクをタイマーに結びつける", "[Enter] Asociar una tarea al temporizador", "[Enter] Aufgabe an den Timer binden", "[Enter] 将任务绑定到计时"]),
+    // Footers
+    ("  🔒 Private · type the unlock sequence  [Space] Pause  [Ctrl+C] Quit", ["  🔒 プライベート · 解除の文字列を入力  [Space] 一時停止  [Ctrl+C] 終了", "  🔒 Privado · escribe la secuencia de desbloqueo  [Space] Pausa  [Ctrl+C] Salir", "  🔒 Privat · Entsperrfolge eingeben  [Space] Pause  [Ctrl+C] Beenden", "  🔒 隐私模式 · 输入解锁序列  [Space] 暂停  [Ctrl+C] 退出"]),
+    ("  [Space] Pause  [rrr] Reset  [sss] Skip  [Tab] Settings  [qqq] Quit", ["  [Space] 一時停止  [rrr] リセット  [sss] スキップ  [Tab] 設定  [qqq] 終了", "  [Space] Pausa  [rrr] Reiniciar  [sss] Saltar  [Tab] Ajustes  [qqq] Salir", "  [Space] Pause  [rrr] Zurücksetzen  [sss] Überspringen  [Tab] Einstellungen  [qqq] Beenden", "  [Space] 暂停  [rrr] 重置  [sss] 跳过  [Tab] 设置  [qqq] 退出"]),
+    ("  [Space] Pause  [Tab] Settings · quit, skip and reset wait for the break", ["  [Space] 一時停止  [Tab] 設定 · 終了・スキップ・リセットは休憩まで待機", "  [Space] Pausa  [Tab] Ajustes · salir, saltar y reiniciar esperan al descanso", "  [Space] Pause  [Tab] Einstellungen · Beenden, Überspringen und Zurücksetzen warten auf die Pause", "  [Space] 暂停  [Tab] 设置 · 退出、跳过和重置要等到休息"]),
+    (" [Space] Pause  [r] Reset  [s] Skip  [Tab] Settings  [q] Quit", [" [Space] 一時停止  [r] リセット  [s] スキップ  [Tab] 設定  [q] 終了", " [Space] Pausa  [r] Reiniciar  [s] Saltar  [Tab] Ajustes  [q] Salir", " [Space] Pause  [r] Zurücksetzen  [s] Überspringen  [Tab] Einstellungen  [q] Beenden", " [Space] 暂停  [r] 重置  [s] 跳过  [Tab] 设置  [q] 退出"]),
+    (" [Space] Pause  [r] Reset  [s] Skip  [t] Tag  [Tab] Settings  [q] Quit", [" [Space] 一時停止  [r] リセット  [s] スキップ  [t] タグ  [Tab] 設定  [q] 終了", " [Space] Pausa  [r] Reiniciar  [s] Saltar  [t] Etiqueta  [Tab] Ajustes  [q] Salir", " [Space] Pause  [r] Zurücksetzen  [s] Überspringen  [t] Tag  [Tab] Einstellungen  [q] Beenden", " [Space] 暂停  [r] 重置  [s] 跳过  [t] 标签  [Tab] 设置  [q] 退出"]),
+    ("  Search tags and notes  [Enter] Keep filter  [Esc] Clear", ["  タグとメモを検索  [Enter] 絞り込みを保持  [Esc] クリア", "  Buscar en etiquetas y notas  [Enter] Mantener filtro  [Esc] Borrar", "  Tags und Notizen durchsuchen  [Enter] Filter behalten  [Esc] Löschen", "  搜索标签和备注  [Enter] 保留筛选  [Esc] 清除"]),
+    ("  Type tag name  [Enter] Add  [Esc] Cancel", ["  タグ名を入力  [Enter] 追加  [Esc] キャンセル", "  Escribe el nombre de la etiqueta  [Enter] Añadir  [Esc] Cancelar", "  Tagnamen eingeben  [Enter] Hinzufügen  [Esc] Abbrechen", "  输入标签名  [Enter] 添加  [Esc] 取消"]),
+    ("  [↑↓/jk] Navigate  [Enter] Select  [/] Search  [Tab] Back  [q] Quit", ["  [↑↓/jk] 移動  [Enter] 選択  [/] 検索  [Tab] 戻る  [q] 終了", "  [↑↓/jk] Moverse  [Enter] Elegir  [/] Buscar  [Tab] Volver  [q] Salir", "  [↑↓/jk] Navigieren  [Enter] Auswählen  [/] Suchen  [Tab] Zurück  [q] Beenden", "  [↑↓/jk] 移动  [Enter] 选择  [/] 搜索  [Tab] 返回  [q] 退出"]),
+    ("  [↑↓] Change  [Enter] Confirm  [Esc] Cancel", ["  [↑↓] 変更  [Enter] 決定  [Esc] キャンセル", "  [↑↓] Cambiar  [Enter] Confirmar  [Esc] Cancelar", "  [↑↓] Ändern  [Enter] Bestätigen  [Esc] Abbrechen", "  [↑↓] 更改  [Enter] 确认  [Esc] 取消"]),
+    ("  [↑↓/jk] Navigate  [Enter] Select  [Tab] Back  [q] Quit", ["  [↑↓/jk] 移動  [Enter] 選択  [Tab] 戻る  [q] 終了", "  [↑↓/jk] Moverse  [Enter] Elegir  [Tab] Volver  [q] Salir", "  [↑↓/jk] Navigieren  [Enter] Auswählen  [Tab] Zurück  [q] Beenden", "  [↑↓/jk] 移动  [Enter] 选择  [Tab] 返回  [q] 退出"]),
+    ("  Type task title  [Enter] Add  [Esc] Cancel", ["  タスク名を入力  [Enter] 追加  [Esc] キャンセル", "  Escribe el título de la tarea  [Enter] Añadir  [Esc] Cancelar", "  Aufgabentitel eingeben  [Enter] Hinzufügen  [Esc] Abbrechen", "  输入任务标题  [Enter] 添加  [Esc] 取消"]),
+    ("  [←→↑↓] Move  [a] Add  [Enter] Bind  [<>] Column  [+-] Est.  [x] Del  [b] Close", ["  [←→↑↓] 移動  [a] 追加  [Enter] 結びつけ  [<>] 列  [+-] 見積  [x] 削除  [b] 閉じる", "  [←→↑↓] Mover  [a] Añadir  [Enter] Asociar  [<>] Columna  [+-] Est.  [x] Borrar  [b] Cerrar", "  [←→↑↓] Bewegen  [a] Neu  [Enter] Binden  [<>] Spalte  [+-] Schätz.  [x] Lösch.  [b] Schließen", "  [←→↑↓] 移动  [a] 添加  [Enter] 绑定  [<>] 列  [+-] 估计  [x] 删除  [b] 关闭"]),
+    ("  [Space] Pause  [r] Reset  [s] Skip  [m] Mode  [z] Snooze  [Tab] Settings  [q] Quit", ["  [Space] 一時停止  [r] リセット  [s] スキップ  [m] モード  [z] 延長  [Tab] 設定  [q] 終了", "  [Space] Pausa  [r] Reiniciar  [s] Saltar  [m] Modo  [z] Alargar  [Tab] Ajustes  [q] Salir", "  [Space] Pause  [r] Zurücksetzen  [s] Überspringen  [m] Modus  [z] Verlängern  [Tab] Einstellungen  [q] Beenden", "  [Space] 暂停  [r] 重置  [s] 跳过  [m] 模式  [z] 延长  [Tab] 设置  [q] 退出"]),
+    ("  [Space] Pause  [r] Reset  [s] Skip  [t] Tag  [m] Mode  [Tab] Settings  [q] Quit", ["  [Space] 一時停止  [r] リセット  [s] スキップ  [t] タグ  [m] モード  [Tab] 設定  [q] 終了", "  [Space] Pausa  [r] Reiniciar  [s] Saltar  [t] Etiqueta  [m] Modo  [Tab] Ajustes  [q] Salir", "  [Space] Pause  [r] Zurücksetzen  [s] Überspringen  [t] Tag  [m] Modus  [Tab] Einstellungen  [q] Beenden", "  [Space] 暂停  [r] 重置  [s] 跳过  [t] 标签  [m] 模式  [Tab] 设置  [q] 退出"]),
+    ("  [Space] Pause  [r] Reset  [s] Skip  [m] Mode  [Tab] Settings  [q] Quit", ["  [Space] 一時停止  [r] リセット  [s] スキップ  [m] モード  [Tab] 設定  [q] 終了", "  [Space] Pausa  [r] Reiniciar  [s] Saltar  [m] Modo  [Tab] Ajustes  [q] Salir", "  [Space] Pause  [r] Zurücksetzen  [s] Überspringen  [m] Modus  [Tab] Einstellungen  [q] Beenden", "  [Space] 暂停  [r] 重置  [s] 跳过  [m] 模式  [Tab] 设置  [q] 退出"]),
+    // Settings
+    ("Settings", ["設定", "Ajustes", "Einstellungen", "设置"]),
+    ("Theme", ["テーマ", "Tema", "Design", "主题"]),
+    ("Accent Color", ["アクセントカラー", "Color de acento", "Akzentfarbe", "强调色"]),
+    ("Icon", ["アイコン", "Icono", "Symbol", "图标"]),
+    ("Animation Speed", ["アニメーション速度", "Velocidad de animación", "Animationstempo", "动画速度"]),
+    ("Rainbow Palette", ["虹のパレット", "Paleta arcoíris", "Regenbogenpalette", "彩虹调色板"]),
+    ("Rainbow Speed", ["虹の速さ", "Velocidad del arcoíris", "Regenbogentempo", "彩虹速度"]),
+    ("Work Duration", ["作業時間", "Duración del trabajo", "Arbeitsdauer", "专注时长"]),
+    ("Short Break", ["短い休憩", "Descanso corto", "Kurze Pause", "短休息"]),
+    ("Long Break", ["長い休憩", "Descanso largo", "Lange Pause", "长休息"]),
+    ("Auto Start", ["自動開始", "Inicio automático", "Autostart", "自动开始"]),
+    ("Session Notes", ["完了時にメモ", "Notas de sesión", "Sitzungsnotizen", "专注备注"]),
+    ("Focus Mode", ["フォーカスモード", "Modo de enfoque", "Fokusmodus", "专注模式"]),
+    ("Timer", ["タイマー", "Temporizador", "Timer", "计时"]),
+    ("Break Snooze", ["休憩延長", "Alargar descanso", "Pause verlängern", "延长休息"]),
+    ("Break Activity", ["休憩中の表示", "Actividad de descanso", "Pausenaktivität", "休息活动"]),
+    ("Sound", ["サウンド", "Sonido", "Ton", "声音"]),
+    ("Notification Sound", ["終了の音", "Sonido de aviso", "Benachrichtigungston", "提示音"]),
+    ("Ambience", ["作業中の環境音", "Sonido ambiente", "Hintergrundklang", "环境音"]),
+    ("Ambience Volume", ["環境音の音量", "Volumen del ambiente", "Lautstärke Hintergrund", "环境音音量"]),
+    ("Progress Cues", ["進捗サウンド", "Avisos de progreso", "Fortschrittstöne", "进度提示音"]),
+    ("Desktop Notification", ["デスクトップ通知", "Aviso de escritorio", "Desktop-Benachrichtigung", "桌面通知"]),
+    ("Do Not Disturb", ["作業中はおやすみモード", "No molestar", "Nicht stören", "勿扰模式"]),
+    ("Daily Sessions Goal", ["1日のセッション目標", "Objetivo diario de sesiones", "Tagesziel Sitzungen", "每日专注次数目标"]),
+    ("Daily Minutes Goal", ["1日の作業時間目標", "Objetivo diario de minutos", "Tagesziel Minuten", "每日专注分钟目标"]),
+    ("Weekly Sessions Goal", ["週間セッション目標", "Objetivo semanal de sesiones", "Wochenziel Sitzungen", "每周专注次数目标"]),
+    ("Weekly Minutes Goal", ["週間作業時間目標", "Objetivo semanal de minutos", "Wochenziel Minuten", "每周专注分钟目标"]),
+    ("── Tags ──", ["── タグ ──", "── Etiquetas ──", "── Tags ──", "── 标签 ──"]),
+    ("Add New Tag", ["新しいタグを追加", "Añadir etiqueta", "Neuen Tag hinzufügen", "添加新标签"]),
+    ("Delete Tag", ["タグを削除", "Borrar etiqueta", "Tag löschen", "删除标签"]),
+    ("Tag Defaults", ["タグの既定値", "Valores por etiqueta", "Tag-Vorgaben", "标签默认值"]),
+    ("Tag Work", ["タグの作業時間", "Trabajo de la etiqueta", "Tag-Arbeitszeit", "标签专注时长"]),
+    ("Tag Break", ["タグの休憩時間", "Descanso de la etiqueta", "Tag-Pause", "标签休息时长"]),
+    ("Tag Icon", ["タグのアイコン", "Icono de la etiqueta", "Tag-Symbol", "标签图标"]),
+    ("Tag Color", ["タグの色", "Color de la etiqueta", "Tag-Farbe", "标签颜色"]),
+    ("── Session History ──", ["── セッション履歴 ──", "── Historial de sesiones ──", "── Sitzungsverlauf ──", "── 专注历史 ──"]),
+    ("Change Session Tag", ["セッションのタグを変更", "Cambiar etiqueta de sesión", "Sitzungs-Tag ändern", "更改专注标签"]),
+    ("Edit Session Note", ["セッションのメモを編集", "Editar nota de sesión", "Sitzungsnotiz bearbeiten", "编辑专注备注"]),
+    ("Merge With Previous", ["前のセッションと結合", "Unir con la anterior", "Mit vorheriger zusammenführen", "与上一条合并"]),
+    ("Delete Session", ["セッションを削除", "Borrar sesión", "Sitzung löschen", "删除专注"]),
+    ("← Back to Timer", ["← タイマーに戻る", "← Volver al temporizador", "← Zurück zum Timer", "← 返回计时"]),
+    ("ON", ["ON", "SÍ", "AN", "开"]),
+    ("OFF", ["OFF", "NO", "AUS", "关"]),
+    ("Not set", ["未設定", "Sin definir", "Nicht gesetzt", "未设置"]),
+    ("{0} min", ["{0}分", "{0} min", "{0} Min.", "{0}分钟"]),
+    ("{0} sessions", ["{0}セッション", "{0} sesiones", "{0} Sitzungen", "{0}次专注"]),
+    ("(no tags)", ["(タグなし)", "(sin etiquetas)", "(keine Tags)", "(没有标签)"]),
+    ("(no sessions)", ["(セッションなし)", "(sin sesiones)", "(keine Sitzungen)", "(没有记录)"]),
+    ("(no matches)", ["(該当なし)", "(sin resultados)", "(keine Treffer)", "(没有匹配)"]),
+    ("(no note)", ["(メモなし)", "(sin nota)", "(keine Notiz)", "(没有备注)"]),
+    ("({0} matching \"{1}\")", ["(\"{1}\" に一致: {0}件)", "({0} con \"{1}\")", "({0} mit \"{1}\")", "(匹配 \"{1}\"：{0} 条)"]),
+    ("({0} recent)", ["(最近の{0}件)", "({0} recientes)", "({0} zuletzt)", "(最近 {0} 条)"]),
+    ("[↑↓ select]", ["[↑↓ 選択]", "[↑↓ elegir]", "[↑↓ wählen]", "[↑↓ 选择]"]),
+    ("[↑↓ select, Enter delete]", ["[↑↓ 選択、Enter 削除]", "[↑↓ elegir, Enter borrar]", "[↑↓ wählen, Enter löschen]", "[↑↓ 选择，Enter 删除]"]),
+    ("[↑↓ select, Enter confirm]", ["[↑↓ 選択、Enter 決定]", "[↑↓ elegir, Enter confirmar]", "[↑↓ wählen, Enter bestätigen]", "[↑↓ 选择，Enter 确认]"]),
+    ("[↑↓ select, Enter edit]", ["[↑↓ 選択、Enter 編集]", "[↑↓ elegir, Enter editar]", "[↑↓ wählen, Enter bearbeiten]", "[↑↓ 选择，Enter 编辑]"]),
+    ("[↑↓ select, Enter merge]", ["[↑↓ 選択、Enter 結合]", "[↑↓ elegir, Enter unir]", "[↑↓ wählen, Enter zusammenführen]", "[↑↓ 选择，Enter 合并]"]),
+    (" [editing ↑↓]", [" [編集中 ↑↓]", " [editando ↑↓]", " [Bearbeiten ↑↓]", " [编辑中 ↑↓]"]),
+    ("🍅 Classic (fixed intervals)", ["🍅 クラシック (決まった間隔)", "🍅 Clásico (intervalos fijos)", "🍅 Klassisch (feste Intervalle)", "🍅 经典（固定间隔）"]),
+    ("🌊 Flowtime (work as long as you want)", ["🌊 フロータイム (好きなだけ作業)", "🌊 Flowtime (trabaja lo que quieras)", "🌊 Flowtime (so lange du willst)", "🌊 心流（想做多久就做多久）"]),
+    ("⏱ Timer (one countdown or stopwatch)", ["⏱ タイマー (1回のカウントダウンかストップウォッチ)", "⏱ Temporizador (una cuenta atrás o cronómetro)", "⏱ Timer (ein Countdown oder Stoppuhr)", "⏱ 计时（一次倒计时或秒表）"]),
+    ("🧘 Box breathing (4-4-4-4)", ["🧘 呼吸ガイド (4-4-4-4)", "🧘 Respiración cuadrada (4-4-4-4)", "🧘 Box-Atmung (4-4-4-4)", "🧘 箱式呼吸 (4-4-4-4)"]),
+    ("🐍 Snake", ["🐍 スネーク", "🐍 Serpiente", "🐍 Snake", "🐍 贪吃蛇"]),
+    ("Off (still)", ["オフ (静止)", "Apagada (fija)", "Aus (still)", "关闭（静止）"]),
+    ("Slow", ["ゆっくり", "Lenta", "Langsam", "慢"]),
+    ("Normal", ["ふつう", "Normal", "Normal", "正常"]),
+    ("Fast", ["速い", "Rápida", "Schnell", "快"]),
+    ("Beeps", ["ビープ", "Pitidos", "Pieptöne", "哔声"]),
+    ("Bell", ["ベル", "Campana", "Glocke", "铃声"]),
+    ("Chime", ["チャイム", "Carillón", "Gong", "钟声"]),
+    ("Ding", ["ディン", "Ding", "Ding", "叮"]),
+    ("🕰 Ticking clock", ["🕰 時計の音", "🕰 Tictac de reloj", "🕰 Tickende Uhr", "🕰 时钟滴答"]),
+    ("🌧 Rain", ["🌧 雨音", "🌧 Lluvia", "🌧 Regen", "🌧 雨声"]),
+    ("📻 White noise", ["📻 ホワイトノイズ", "📻 Ruido blanco", "📻 Weißes Rauschen", "📻 白噪音"]),
+    ("Sound + desktop", ["音 + デスクトップ", "Sonido + escritorio", "Ton + Desktop", "声音 + 桌面"]),
+    ("Sound only", ["音のみ", "Solo sonido", "Nur Ton", "仅声音"]),
+    ("Desktop only", ["デスクトップのみ", "Solo escritorio", "Nur Desktop", "仅桌面"]),
+    ("Log only (silent)", ["ログのみ (無音)", "Solo registro (silencio)", "Nur Protokoll (still)", "仅日志（静音）"]),
+    ("Off", ["オフ", "Desactivado", "Aus", "关闭"]),
+    ("Work ends", ["作業の終了", "Fin del trabajo", "Arbeit endet", "专注结束"]),
+    ("Break ends", ["休憩の終了", "Fin del descanso", "Pause endet", "休息结束"]),
+    ("Daily goal reached", ["日次目標の達成", "Objetivo diario cumplido", "Tagesziel erreicht", "达成每日目标"]),
+    ("Daily goal reminder", ["日次目標のリマインダー", "Recordatorio del objetivo diario", "Erinnerung ans Tagesziel", "每日目标提醒"]),
+    ("Level up", ["レベルアップ", "Subida de nivel", "Stufenaufstieg", "升级"]),
+    ("Tag over budget", ["タグの上限超過", "Etiqueta por encima del límite", "Tag über dem Limit", "标签超出上限"]),
+    ("{0} · counts up", ["{0} · カウントアップ", "{0} · cuenta hacia arriba", "{0} · zählt hoch", "{0} · 正计时"]),
+    ("{0} · {1} min", ["{0} · {1}分", "{0} · {1} min", "{0} · {1} Min.", "{0} · {1}分钟"]),
+    // Suggestions in the timer
+    ("You've never done {0} sessions on a {1} — aim for {2}?", ["{1}に{0}セッションできた日はまだありません — {2}セッションを目指しては？", "Nunca has hecho {0} sesiones un {1}: ¿qué tal {2}?", "Du hast an einem {1} noch nie {0} Sitzungen geschafft – wie wäre es mit {2}?", "你还从未在{1}完成过 {0} 次专注，目标定为 {2} 次如何？"]),
+    ("You've never focused {0}m on a {1} — aim for {2}m?", ["{1}に{0}分集中できた日はまだありません — {2}分を目指しては？", "Nunca te has concentrado {0}m un {1}: ¿qué tal {2}m?", "Du warst an einem {1} noch nie {0}m fokussiert – wie wäre es mit {2}m?", "你还从未在{1}专注过 {0} 分钟，目标定为 {2} 分钟如何？"]),
+    ("You finish {0}% of {1}m sessions but only {2}% of {3}m — try {4}m", ["{1}分のセッションは{0}%完了、{3}分は{2}%だけ — {4}分を試してみては？", "Terminas el {0}% de las sesiones de {1}m pero solo el {2}% de las de {3}m: prueba {4}m", "Du schaffst {0}% der {1}m-Sitzungen, aber nur {2}% der {3}m-Sitzungen – versuch {4}m", "{1} 分钟的专注完成了 {0}%，{3} 分钟的只有 {2}%，试试 {4} 分钟"]),
+    ("You finish {0}% of {1}m sessions — try {2}m", ["{1}分のセッションは{0}%完了 — {2}分に延ばしてみては？", "Terminas el {0}% de las sesiones de {1}m: prueba {2}m", "Du schaffst {0}% der {1}m-Sitzungen – versuch {2}m", "{1} 分钟的专注完成了 {0}%，试试 {2} 分钟"]),
+    ("You finish only {0}% of {1}m sessions — try {2}m", ["{1}分のセッションは{0}%しか完了していません — {2}分を試してみては？", "Solo terminas el {0}% de las sesiones de {1}m: prueba {2}m", "Du schaffst nur {0}% der {1}m-Sitzungen – versuch {2}m", "{1} 分钟的专注只完成了 {0}%，试试 {2} 分钟"]),
+    ("Try {0}m sessions", ["{0}分のセッションを試してみては？", "Prueba sesiones de {0}m", "Versuch {0}m-Sitzungen", "试试 {0} 分钟的专注"]),
+    // Stats and the rest of the CLI
+    ("sandoro Statistics", ["sandoro 統計", "Estadísticas de sandoro", "sandoro-Statistik", "sandoro 统计"]),
+    ("Streak:", ["連続:", "Racha:", "Serie:", "连续:"]),
+    ("longest:", ["最長", "récord:", "längste:", "最长"]),
+    ("Last 7 Days", ["過去7日間", "Últimos 7 días", "Letzte 7 Tage", "最近7天"]),
+    ("Last 30 Days", ["過去30日間", "Últimos 30 días", "Letzte 30 Tage", "最近30天"]),
+    ("Detailed stats need the SQLite backend ([storage] backend = \"sqlite\").", ["詳しい統計は SQLite バックエンドで表示できます ([storage] backend = \"sqlite\")。", "Las estadísticas detalladas necesitan el backend SQLite ([storage] backend = \"sqlite\").", "Ausführliche Statistiken brauchen das SQLite-Backend ([storage] backend = \"sqlite\").", "详细统计需要 SQLite 后端（[storage] backend = \"sqlite\"）。"]),
+    ("and {0} more days", ["ほか{0}日", "y {0} días más", "und {0} weitere Tage", "另外 {0} 天"]),
+    ("Flowtime:", ["フロータイム:", "Flowtime:", "Flowtime:", "心流:"]),
+    ("Paused:", ["一時停止:", "En pausa:", "Pausiert:", "暂停:"]),
+    ("effective focus", ["実質の集中", "enfoque real", "effektiver Fokus", "实际专注"]),
+    ("on the clock", ["経過", "en el reloj", "auf der Uhr", "计时总长"]),
+    ("internal", ["内的", "interna", "intern", "内部"]),
+    ("external", ["外的", "externa", "extern", "外部"]),
+    ("no category", ["分類なし", "sin categoría", "ohne Kategorie", "未分类"]),
+    ("Interruptions:", ["中断:", "Interrupciones:", "Unterbrechungen:", "打断:"]),
+    ("Daily breakdown:", ["日ごとの内訳:", "Desglose diario:", "Tagesübersicht:", "每日明细:"]),
+    ("quality focus", ["質の高い集中", "enfoque de calidad", "Qualitätsfokus", "高质量专注"]),
+    ("Stats by Tag (Last 30 days)", ["タグ別の統計 (過去30日間)", "Estadísticas por etiqueta (últimos 30 días)", "Statistik nach Tag (letzte 30 Tage)", "按标签统计（最近30天）"]),
+    ("No data found for the last 30 days.", ["過去30日間のデータはありません。", "No hay datos de los últimos 30 días.", "Keine Daten für die letzten 30 Tage.", "最近30天没有数据。"]),
+    ("No tag", ["タグなし", "Sin etiqueta", "Ohne Tag", "无标签"]),
+    ("today {0} of {1} budget", ["今日 {0} / 上限 {1}", "hoy {0} de un límite de {1}", "heute {0} von {1} Limit", "今天 {0} / 上限 {1}"]),
+    ("Stats by Hour (Last 30 days)", ["時間帯別の統計 (過去30日間)", "Estadísticas por hora (últimos 30 días)", "Statistik nach Stunde (letzte 30 Tage)", "按小时统计（最近30天）"]),
+    ("Sessions by Hour (last {0} weeks)", ["時間帯別のセッション (過去{0}週間)", "Sesiones por hora (últimas {0} semanas)", "Sitzungen nach Stunde (letzte {0} Wochen)", "按小时的专注（最近{0}周）"]),
+    ("No sessions in this period.", ["この期間のセッションはありません。", "No hay sesiones en este periodo.", "Keine Sitzungen in diesem Zeitraum.", "这段时间没有专注记录。"]),
+    ("Peak:", ["ピーク:", "Pico:", "Spitze:", "高峰:"]),
+    ("Activity (last {0} weeks)", ["アクティビティ (過去{0}週間)", "Actividad (últimas {0} semanas)", "Aktivität (letzte {0} Wochen)", "活动（最近{0}周）"]),
+    ("Less", ["少", "Menos", "Weniger", "少"]),
+    ("More", ["多", "Más", "Mehr", "多"]),
+    ("Plan (week of {0})", ["計画 ({0}からの週)", "Plan (semana del {0})", "Plan (Woche ab {0})", "计划（{0}起的一周）"]),
+    ("No plan yet. Try `sandoro plan set <tag> <sessions>`.", ["まだ計画がありません。`sandoro plan set <tag> <sessions>` で立てられます。", "Aún no hay plan. Prueba `sandoro plan set <tag> <sessions>`.", "Noch kein Plan. Versuch `sandoro plan set <tag> <sessions>`.", "还没有计划。试试 `sandoro plan set <tag> <sessions>`。"]),
+    ("{0} of {1} planned sessions done ({2}%)", ["予定{1}セッションのうち{0}完了 ({2}%)", "{0} de {1} sesiones previstas hechas ({2}%)", "{0} von {1} geplanten Sitzungen erledigt ({2}%)", "计划的 {1} 次专注已完成 {0} 次（{2}%）"]),
+    ("Last week: {0} ({1}% of {2} planned sessions)", ["先週: {0} (予定{2}セッションの{1}%)", "Semana pasada: {0} ({1}% de {2} sesiones previstas)", "Letzte Woche: {0} ({1}% von {2} geplanten Sitzungen)", "上周：{0}（计划 {2} 次专注的 {1}%）"]),
+    ("Heatmap written to", ["ヒートマップを書き出しました:", "Mapa de calor guardado en", "Heatmap gespeichert unter", "热力图已写入"]),
+    ("Card written to", ["カードを書き出しました:", "Tarjeta guardada en", "Karte gespeichert unter", "卡片已写入"]),
+    ("Report written to", ["レポートを書き出しました:", "Informe guardado en", "Bericht gespeichert unter", "报告已写入"]),
+    ("No routines yet. Add one under [routines] in ~/.sandoro/config.toml.", ["ルーティンはまだありません。~/.sandoro/config.toml の [routines] に追加してください。", "Aún no hay rutinas. Añade una en [routines] de ~/.sandoro/config.toml.", "Noch keine Routinen. Lege eine unter [routines] in ~/.sandoro/config.toml an.", "还没有例程。请在 ~/.sandoro/config.toml 的 [routines] 下添加。"]),
+    ("Start one with `sandoro routine <name>`, or press `o` in the timer.", ["`sandoro routine <名前>` またはタイマーで `o` を押して始めます。", "Empieza una con `sandoro routine <name>` o pulsa `o` en el temporizador.", "Starte eine mit `sandoro routine <name>` oder drück `o` im Timer.", "用 `sandoro routine <name>` 开始，或在计时界面按 `o`。"]),
+    ("Completion (Last {0} days)", ["完了率 (過去{0}日間)", "Finalización (últimos {0} días)", "Abschlussquote (letzte {0} Tage)", "完成率（最近{0}天）"]),
+    ("No finished work sessions in the last {0} days.", ["過去{0}日間に終えた作業セッションはありません。", "No hay sesiones de trabajo terminadas en los últimos {0} días.", "Keine beendeten Arbeitssitzungen in den letzten {0} Tagen.", "最近{0}天没有结束的专注。"]),
+    ("{0} started │ {1} completed ({2}%) │ {3} skipped │ {4} reset │ {5} interrupted", ["開始 {0} │ 完了 {1} ({2}%) │ スキップ {3} │ リセット {4} │ 中断 {5}", "{0} empezadas │ {1} completadas ({2}%) │ {3} saltadas │ {4} reiniciadas │ {5} interrumpidas", "{0} begonnen │ {1} abgeschlossen ({2}%) │ {3} übersprungen │ {4} zurückgesetzt │ {5} unterbrochen", "开始 {0} │ 完成 {1}（{2}%）│ 跳过 {3} │ 重置 {4} │ 中断 {5}"]),
+    ("{0} of {1}", ["{0}/{1}", "{0} de {1}", "{0} von {1}", "{0}/{1}"]),
+    ("  ⚠ often abandoned", ["  ⚠ 途中でやめがち", "  ⚠ se abandona a menudo", "  ⚠ oft abgebrochen", "  ⚠ 常常中途放弃"]),
+    ("By time of day", ["時間帯別", "Por momento del día", "Nach Tageszeit", "按时段"]),
+    ("By planned length", ["予定の長さ別", "Por duración prevista", "Nach geplanter Länge", "按计划时长"]),
+    ("unknown", ["不明", "desconocida", "unbekannt", "未知"]),
+    ("Morning   05-12", ["午前  05-12", "Mañana    05-12", "Morgen     05-12", "上午  05-12"]),
+    ("Afternoon 12-17", ["午後  12-17", "Tarde     12-17", "Nachmittag 12-17", "下午  12-17"]),
+    ("Evening   17-22", ["夕方  17-22", "Noche     17-22", "Abend      17-22", "傍晚  17-22"]),
+    ("Night     22-05", ["夜間  22-05", "Madrugada 22-05", "Nacht      22-05", "夜间  22-05"]),
+    ("Trend", ["傾向", "Tendencia", "Trend", "趋势"]),
+    ("7-day avg", ["7日平均", "Media 7 días", "7-Tage-Schnitt", "7天平均"]),
+    ("28-day avg", ["28日平均", "Media 28 días", "28-Tage-Schnitt", "28天平均"]),
+    ("day", ["日", "día", "Tag", "天"]),
+    ("Last 28 days", ["過去28日間", "Últimos 28 días", "Letzte 28 Tage", "最近28天"]),
+    ("improving", ["上向き", "mejorando", "steigend", "上升"]),
+    ("flat", ["横ばい", "estable", "gleichbleibend", "持平"]),
+    ("declining", ["下向き", "bajando", "fallend", "下降"]),
+    ("Goals", ["目標", "Objetivos", "Ziele", "目标"]),
+    ("No goals set. Configure goals in settings.", ["目標が設定されていません。設定画面で目標を決めてください。", "No hay objetivos. Configúralos en los ajustes.", "Keine Ziele gesetzt. Lege sie in den Einstellungen fest.", "还没有设定目标。请在设置中设定。"]),
+    ("goals\u{4}Daily", ["今日", "Hoy", "Heute", "今天"]),
+    ("Daily", ["日ごと", "Por día", "Täglich", "每日"]),
+    ("This week", ["今週", "Esta semana", "Diese Woche", "本周"]),
+    ("Past weeks", ["過去の週", "Semanas anteriores", "Frühere Wochen", "之前几周"]),
+    ("Forecast (this week, {0}–{1})", ["見通し (今週、{0}曜日〜{1}曜日)", "Previsión (esta semana, {0}–{1})", "Prognose (diese Woche, {0}–{1})", "预测（本周，{0}至{1}）"]),
+    ("goal reached ✓", ["目標達成 ✓", "objetivo cumplido ✓", "Ziel erreicht ✓", "已达成 ✓"]),
+    ("at this pace you'll hit it today ({0} projected)", ["このペースなら今日達成 (見込み {0})", "a este ritmo lo cumples hoy ({0} previstos)", "bei diesem Tempo schaffst du es heute ({0} erwartet)", "按这个节奏今天就能达成（预计 {0}）"]),
+    ("at this pace you'll hit it by {0} ({1} projected)", ["このペースなら{0}までに達成 (見込み {1})", "a este ritmo lo cumples el {0} ({1} previstos)", "bei diesem Tempo schaffst du es bis {0} ({1} erwartet)", "按这个节奏{0}前能达成（预计 {1}）"]),
+    ("behind, {0} projected; need {1}/day for the rest of the week", ["遅れ気味、見込み {0}。残りの日は1日{1}が必要", "vas retrasado, {0} previstos; hacen falta {1}/día el resto de la semana", "im Rückstand, {0} erwartet; nötig sind {1}/Tag für den Rest der Woche", "进度落后，预计 {0}；本周剩下每天需要 {1}"]),
+    ("Time:    ", ["時間:       ", "Tiempo:  ", "Zeit:     ", "时长:    "]),
+    ("Sessions:", ["セッション: ", "Sesiones:", "Sitzungen:", "专注次数:"]),
+    ("m", ["分", "m", "m", "分钟"]),
+    (" sessions", ["セッション", " sesiones", " Sitzungen", "次"]),
+    ("Comparison", ["比較", "Comparación", "Vergleich", "对比"]),
+    ("This Week vs Last Week", ["今週 vs 先週", "Esta semana vs la pasada", "Diese Woche vs letzte Woche", "本周 vs 上周"]),
+    ("This Month vs Last Month", ["今月 vs 先月", "Este mes vs el pasado", "Dieser Monat vs letzter Monat", "本月 vs 上月"]),
+    ("All", ["すべて", "Todo", "Alle", "全部"]),
+    ("Session notes", ["セッションのメモ", "Notas de sesión", "Sitzungsnotizen", "专注备注"]),
+    ("Session notes with", ["メモの検索:", "Notas de sesión con", "Sitzungsnotizen mit", "备注包含"]),
+    ("No sessions found.", ["セッションが見つかりません。", "No se encontraron sesiones.", "Keine Sitzungen gefunden.", "没有找到专注记录。"]),
+    ("Only the day's totals are kept; its sessions were pruned.", ["この日は合計のみ保存されています (セッションは整理済み)。", "Solo se guardan los totales del día; sus sesiones se depuraron.", "Nur die Tagessummen sind erhalten; die Sitzungen wurden bereinigt.", "只保留了当天的合计；专注记录已清理。"]),
+    ("No sessions.", ["セッションはありません。", "No hay sesiones.", "Keine Sitzungen.", "没有专注记录。"]),
+    // Export, plans and XP
+    ("Error: Unknown export format '{0}'. Use 'json' or 'csv'.", ["エラー: 不明なエクスポート形式 '{0}' です。'json' か 'csv' を指定してください。", "Error: formato de exportación desconocido '{0}'. Usa 'json' o 'csv'.", "Fehler: Unbekanntes Exportformat '{0}'. Verwende 'json' oder 'csv'.", "错误：未知的导出格式 '{0}'。请使用 'json' 或 'csv'。"]),
+    ("Exported sessions to: {0}", ["セッションを書き出しました: {0}", "Sesiones exportadas a: {0}", "Sitzungen exportiert nach: {0}", "专注记录已导出到：{0}"]),
+    ("✓ {0} taken out of the plan", ["✓ {0} を計画から外しました", "✓ {0} se quitó del plan", "✓ {0} aus dem Plan genommen", "✓ 已将 {0} 移出计划"]),
+    ("✓ {0} sessions of {1} planned for the week of {2}", ["✓ {2}からの週に {1} を{0}セッション計画しました", "✓ {0} sesiones de {1} previstas para la semana del {2}", "✓ {0} Sitzungen {1} für die Woche ab {2} geplant", "✓ 已为{2}起的一周计划 {1} {0} 次"]),
+    ("No plan for the week of {0}.", ["{0}からの週の計画はありません。", "No hay plan para la semana del {0}.", "Kein Plan für die Woche ab {0}.", "{0}起的一周没有计划。"]),
+    ("✓ Plan for the week of {0} cleared", ["✓ {0}からの週の計画を消しました", "✓ Plan de la semana del {0} borrado", "✓ Plan für die Woche ab {0} gelöscht", "✓ 已清除{0}起的一周的计划"]),
+    ("XP is off. Turn it on in ~/.sandoro/config.toml:", ["XP はオフです。~/.sandoro/config.toml でオンにできます:", "La XP está desactivada. Actívala en ~/.sandoro/config.toml:", "XP ist aus. Schalte es in ~/.sandoro/config.toml ein:", "XP 已关闭。请在 ~/.sandoro/config.toml 中开启："]),
+    ("★ Level {0} · {1}", ["★ レベル {0} · {1}", "★ Nivel {0} · {1}", "★ Stufe {0} · {1}", "★ 等级 {0} · {1}"]),
+    ("{0} XP, {1} to level {2}", ["{0} XP、レベル{2}まであと {1}", "{0} XP, {1} para el nivel {2}", "{0} XP, {1} bis Stufe {2}", "{0} XP，距等级 {2} 还差 {1}"]),
+    ("Seedling", ["芽生え", "Semilla", "Keimling", "幼苗"]),
+    ("Sprout", ["若葉", "Brote", "Spross", "新芽"]),
+    ("Apprentice", ["見習い", "Aprendiz", "Lehrling", "学徒"]),
+    ("Focused", ["集中の人", "Concentrado", "Fokussiert", "专注者"]),
+    ("Steady Hand", ["確かな手", "Mano firme", "Ruhige Hand", "稳健之手"]),
+    ("Deep Worker", ["深い仕事人", "Trabajo profundo", "Tiefenarbeiter", "深度工作者"]),
+    ("Flow Adept", ["フローの達人", "Experto en flow", "Flow-Kenner", "心流行家"]),
+    ("Time Sculptor", ["時の彫刻家", "Escultor del tiempo", "Zeitbildhauer", "时间雕刻师"]),
+    ("Zen Master", ["禅の師", "Maestro zen", "Zen-Meister", "禅师"]),
+    ("Sandoro Sage", ["砂時計の賢者", "Sabio de sandoro", "Sandoro-Weiser", "sandoro 贤者"]),
+    ("Streak bonus", ["連続ボーナス", "Bonus de racha", "Serienbonus", "连续奖励"]),
+    ("Daily goals", ["毎日の目標", "Objetivos diarios", "Tagesziele", "每日目标"]),
+    ("Breaks taken", ["取った休憩", "Descansos tomados", "Genommene Pausen", "休息次数"]),
+    // Settings that can't be read
+    ("⚠ {0} can't be read: {1}", ["⚠ {0} を読み込めません: {1}", "⚠ No se puede leer {0}: {1}", "⚠ {0} kann nicht gelesen werden: {1}", "⚠ 无法读取 {0}：{1}"]),
+    ("No backup to restore; using the default settings.", ["復元できるバックアップがありません。既定の設定を使います。", "No hay copia de seguridad que restaurar; se usan los ajustes predeterminados.", "Keine Sicherung zum Wiederherstellen; die Standardeinstellungen werden verwendet.", "没有可恢复的备份；使用默认设置。"]),
+    ("Using the default settings. The backup from {0} is at {1}", ["既定の設定を使います。{0} のバックアップは {1} にあります", "Se usan los ajustes predeterminados. La copia de {0} está en {1}", "Die Standardeinstellungen werden verwendet. Die Sicherung vom {0} liegt unter {1}", "使用默认设置。{0} 的备份位于 {1}"]),
+    ("Restore the backup from {0}? [Y/n] ", ["{0} のバックアップを復元しますか? [Y/n] ", "¿Restaurar la copia de {0}? [Y/n] ", "Sicherung vom {0} wiederherstellen? [Y/n] ", "恢复 {0} 的备份吗？[Y/n] "]),
+    ("✓ Restored. The unreadable file is kept as config.toml.broken", ["✓ 復元しました。読み込めなかったファイルは config.toml.broken として残してあります", "✓ Restaurado. El archivo ilegible se guarda como config.toml.broken", "✓ Wiederhergestellt. Die unlesbare Datei bleibt als config.toml.broken erhalten", "✓ 已恢复。无法读取的文件保留为 config.toml.broken"]),
+    ("Couldn't restore the backup: {0}", ["バックアップを復元できませんでした: {0}", "No se pudo restaurar la copia: {0}", "Die Sicherung konnte nicht wiederhergestellt werden: {0}", "无法恢复备份：{0}"]),
+    ("Using the default settings.", ["既定の設定を使います。", "Se usan los ajustes predeterminados.", "Die Standardeinstellungen werden verwendet.", "使用默认设置。"]),
+    // Account and sync
+    ("Already logged in as: {0}", ["ログイン済みです: {0}", "Ya has iniciado sesión como: {0}", "Bereits angemeldet als: {0}", "已登录：{0}"]),
+    ("Use 'sandoro logout' to log out first.", ["先に 'sandoro logout' でログアウトしてください。", "Cierra sesión primero con 'sandoro logout'.", "Melde dich zuerst mit 'sandoro logout' ab.", "请先用 'sandoro logout' 退出登录。"]),
+    ("Logging in with {0}...", ["{0} でログインしています...", "Iniciando sesión con {0}...", "Anmeldung mit {0}...", "正在用 {0} 登录..."]),
+    ("✓ Successfully logged in!", ["✓ ログインしました!", "✓ ¡Sesión iniciada!", "✓ Erfolgreich angemeldet!", "✓ 登录成功！"]),
+    ("User: {0}", ["ユーザー: {0}", "Usuario: {0}", "Benutzer: {0}", "用户：{0}"]),
+    ("Your sessions will now sync with the cloud.", ["これからセッションがクラウドと同期されます。", "Tus sesiones se sincronizarán ahora con la nube.", "Deine Sitzungen werden jetzt mit der Cloud synchronisiert.", "你的专注记录将与云端同步。"]),
+    ("Run 'sandoro sync' to sync existing sessions.", ["既存のセッションは 'sandoro sync' で同期できます。", "Ejecuta 'sandoro sync' para sincronizar las sesiones existentes.", "Führe 'sandoro sync' aus, um vorhandene Sitzungen zu synchronisieren.", "运行 'sandoro sync' 同步已有的专注记录。"]),
+    ("Not logged in.", ["ログインしていません。", "No has iniciado sesión.", "Nicht angemeldet.", "未登录。"]),
+    ("✓ Logged out from: {0}", ["✓ ログアウトしました: {0}", "✓ Sesión cerrada de: {0}", "✓ Abgemeldet von: {0}", "✓ 已退出：{0}"]),
+    ("✓ Logged out.", ["✓ ログアウトしました。", "✓ Sesión cerrada.", "✓ Abgemeldet.", "✓ 已退出登录。"]),
+    ("Your local data is preserved.", ["ローカルのデータはそのまま残ります。", "Tus datos locales se conservan.", "Deine lokalen Daten bleiben erhalten.", "本地数据会保留。"]),
+    ("User ID: {0}", ["ユーザー ID: {0}", "ID de usuario: {0}", "Benutzer-ID: {0}", "用户 ID：{0}"]),
+    ("Sync Status", ["同期の状態", "Estado de sincronización", "Sync-Status", "同步状态"]),
+    ("Checking what a sync would change...", ["同期で何が変わるか確認しています...", "Comprobando qué cambiaría una sincronización...", "Prüfe, was eine Synchronisierung ändern würde...", "正在检查同步会改变什么..."]),
+    ("Syncing with cloud...", ["クラウドと同期しています...", "Sincronizando con la nube...", "Synchronisiere mit der Cloud...", "正在与云端同步..."]),
+    ("Would upload:", ["アップロード予定:", "Se subirían:", "Würde hochladen:", "将上传："]),
+    ("Would download:", ["ダウンロード予定:", "Se bajarían:", "Würde herunterladen:", "将下载："]),
+    ("Uploaded:", ["アップロード:", "Subidas:", "Hochgeladen:", "已上传："]),
+    ("Downloaded:", ["ダウンロード:", "Bajadas:", "Heruntergeladen:", "已下载："]),
+    ("Dry run complete", ["確認が終わりました", "Simulación completada", "Probelauf abgeschlossen", "预演完成"]),
+    ("Sync complete!", ["同期が終わりました!", "¡Sincronización completada!", "Synchronisierung abgeschlossen!", "同步完成！"]),
+    ("Conflicts ({0} cloud sessions match local ones and weren't downloaded):", ["競合 (クラウドの{0}セッションがローカルと一致したためダウンロードしませんでした):", "Conflictos ({0} sesiones de la nube coinciden con locales y no se bajaron):", "Konflikte ({0} Cloud-Sitzungen passen zu lokalen und wurden nicht heruntergeladen):", "冲突（{0} 条云端记录与本地记录重合，未下载）："]),
+    ("; the local one goes up over it", ["。ローカルの方で上書きします", "; la local la sustituye en la nube", "; die lokale ersetzt sie in der Cloud", "；用本地记录覆盖云端"]),
+    ("; the local one takes its values", ["。ローカルの方をクラウドの値にします", "; la local toma sus valores", "; die lokale übernimmt ihre Werte", "；本地记录采用云端的值"]),
+    ("- {0} finished {1} matches local session #{2}{3}", ["- {1} に終わった {0} がローカルのセッション #{2} と一致{3}", "- {0} terminada el {1} coincide con la sesión local #{2}{3}", "- {0}, beendet {1}, passt zu lokaler Sitzung #{2}{3}", "- {1} 结束的 {0} 与本地记录 #{2} 重合{3}"]),
+    ("Errors:", ["エラー:", "Errores:", "Fehler:", "错误："]),
+    // Search, insights and the journal
+    ("Sessions matching \"{0}\"", ["\"{0}\" に一致するセッション", "Sesiones que coinciden con \"{0}\"", "Sitzungen zu \"{0}\"", "匹配 \"{0}\" 的专注记录"]),
+    ("Showing the {0} most recent; use --limit for more.", ["新しい順に{0}件を表示しています。もっと見るには --limit を使います。", "Se muestran las {0} más recientes; usa --limit para ver más.", "Die {0} neuesten werden gezeigt; mehr mit --limit.", "显示最近的 {0} 条；用 --limit 查看更多。"]),
+    ("Insights (last {0} days)", ["インサイト (過去{0}日間)", "Ideas (últimos {0} días)", "Erkenntnisse (letzte {0} Tage)", "洞察（最近{0}天）"]),
+    ("No finished or abandoned work sessions yet.", ["終えた作業セッションもやめた作業セッションもまだありません。", "Aún no hay sesiones de trabajo terminadas ni abandonadas.", "Noch keine beendeten oder abgebrochenen Arbeitssitzungen.", "还没有完成或放弃的专注。"]),
+    ("{0}m │ {1} sessions │ {2}% finished │ {3}", ["{0}分 │ {1}セッション │ 完了 {2}% │ {3}", "{0}m │ {1} sesiones │ {2}% terminadas │ {3}", "{0}m │ {1} Sitzungen │ {2}% beendet │ {3}", "{0}分钟 │ {1}次 │ 完成 {2}% │ {3}"]),
+    ("Not enough data yet: {0} sessions of a length are needed.", ["まだデータが足りません: 同じ長さのセッションが{0}回必要です。", "Aún no hay datos suficientes: hacen falta {0} sesiones de una misma duración.", "Noch zu wenige Daten: nötig sind {0} Sitzungen einer Länge.", "数据还不够：同一时长需要 {0} 次专注。"]),
+    ("Your {0}m work length suits you well.", ["今の{0}分の作業時間が合っています。", "Tu duración de trabajo de {0}m te va bien.", "Deine Arbeitslänge von {0}m passt gut zu dir.", "{0}分钟的专注时长很适合你。"]),
+    ("✓ Removed the journal note for {0}", ["✓ {0} の日誌を削除しました", "✓ Nota del diario del {0} eliminada", "✓ Journalnotiz für {0} entfernt", "✓ 已删除 {0} 的日志"]),
+    ("No journal note for {0}", ["{0} の日誌はありません", "No hay nota del diario para el {0}", "Keine Journalnotiz für {0}", "{0} 没有日志"]),
+    ("✓ Journal saved for {0}", ["✓ {0} の日誌を保存しました", "✓ Diario guardado para el {0}", "✓ Journal für {0} gespeichert", "✓ 已保存 {0} 的日志"]),
+    ("Journal", ["日誌", "Diario", "Journal", "日志"]),
+    ("No notes yet. Add one with: sandoro journal \"...\"", ["まだメモがありません。追加するには: sandoro journal \"...\"", "Aún no hay notas. Añade una con: sandoro journal \"...\"", "Noch keine Notizen. Füge eine hinzu mit: sandoro journal \"...\"", "还没有记录。这样添加：sandoro journal \"...\""]),
+    ("✓ Goals saved", ["✓ 目標を保存しました", "✓ Objetivos guardados", "✓ Ziele gespeichert", "✓ 目标已保存"]),
+    // Reports and cards
+    ("Weekly report", ["週間レポート", "Informe semanal", "Wochenbericht", "周报"]),
+    ("Monthly report", ["月間レポート", "Informe mensual", "Monatsbericht", "月报"]),
+    ("new", ["新規", "nuevo", "neu", "新"]),
+    ("vs previous 7 days", ["前の7日間比", "frente a los 7 días anteriores", "ggü. den 7 Tagen davor", "对比前7天"]),
+    ("vs previous 30 days", ["前の30日間比", "frente a los 30 días anteriores", "ggü. den 30 Tagen davor", "对比前30天"]),
+    ("No work sessions in this period.", ["この期間の作業セッションはありません。", "No hay sesiones de trabajo en este periodo.", "Keine Arbeitssitzungen in diesem Zeitraum.", "这段时间没有专注。"]),
+    ("Avg session", ["平均の長さ", "Sesión media", "Ø Sitzung", "平均时长"]),
+    ("Completion", ["完了率", "Finalización", "Abschlussquote", "完成率"]),
+    ("{0}% ({1} completed, {2} skipped)", ["{0}% (完了 {1} / スキップ {2})", "{0}% ({1} completadas, {2} saltadas)", "{0}% ({1} abgeschlossen, {2} übersprungen)", "{0}%（完成 {1} / 跳过 {2}）"]),
+    ("By tag", ["タグ別", "Por etiqueta", "Nach Tag", "按标签"]),
+    ("(untagged)", ["(タグなし)", "(sin etiqueta)", "(ohne Tag)", "(无标签)"]),
+    ("By weekday (average on days with focus)", ["曜日別 (集中した日の平均)", "Por día de la semana (media de los días con enfoque)", "Nach Wochentag (Schnitt der Tage mit Fokus)", "按星期（有专注的日子的平均）"]),
+    ("Focus", ["集中時間", "Enfoque", "Fokus", "专注时长"]),
+    ("Sessions", ["セッション", "Sesiones", "Sitzungen", "专注次数"]),
+    ("Per day", ["1日平均", "Por día", "Pro Tag", "每天平均"]),
+    ("Streak", ["連続", "Racha", "Serie", "连续"]),
+    ("Best day", ["最高の日", "Mejor día", "Bester Tag", "最佳一天"]),
+    ("Activity", ["アクティビティ", "Actividad", "Aktivität", "活动"]),
+    ("Daily focus", ["日ごとの集中時間", "Enfoque diario", "Fokus pro Tag", "每日专注"]),
+    ("Time of day", ["時間帯", "Momento del día", "Tageszeit", "时段"]),
+    ("Tags", ["タグ", "Etiquetas", "Tags", "标签"]),
+    ("report", ["レポート", "informe", "Bericht", "报告"]),
+    ("Today · time", ["今日 · 時間", "Hoy · tiempo", "Heute · Zeit", "今天 · 时长"]),
+    ("Today · sessions", ["今日 · セッション", "Hoy · sesiones", "Heute · Sitzungen", "今天 · 次数"]),
+    ("Week · time", ["今週 · 時間", "Semana · tiempo", "Woche · Zeit", "本周 · 时长"]),
+    ("Week · sessions", ["今週 · セッション", "Semana · sesiones", "Woche · Sitzungen", "本周 · 次数"]),
+    ("focus graph", ["集中グラフ", "gráfico de enfoque", "Fokusgrafik", "专注图"]),
+    ("Focus graph", ["集中グラフ", "Gráfico de enfoque", "Fokusgrafik", "专注图"]),
+    ("best", ["最長", "récord", "beste", "最长"]),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_durations_and_dates_in_each_language() {
+        assert_eq!(duration_in(5100, Language::English), "1h 25m");
+        assert_eq!(duration_in(1500, Language::English), "25m");
+        assert_eq!(duration_in(5100, Language::Japanese), "1時間25分");
+        assert_eq!(duration_in(1500, Language::Japanese), "25分");
+        assert_eq!(duration_in(5100, Language::Chinese), "1小时25分钟");
+        assert_eq!(duration_in(5100, Language::German), "1h 25m");
+        let date = NaiveDate::from_ymd_opt(2026, 10, 7).unwrap();
+        assert_eq!(month_day_in(date, Language::English), "Oct 07");
+        assert_eq!(month_day_in(date, Language::Japanese), "10月7日");
+        assert_eq!(month_day_in(date, Language::Spanish), "7 oct");
+        assert_eq!(month_day_in(date, Language::German), "7. Okt.");
+        assert_eq!(weekday_short_in(Weekday::Sun, Language::Japanese), "日");
+        assert_eq!(weekday_in(Weekday::Sun, Language::Japanese), "日曜日");
+        assert_eq!(weekday_in(Weekday::Wed, Language::Spanish), "miércoles");
+        assert_eq!(pad("7-day avg", 12), "7-day avg   ");
+        assert_eq!(pad("7日平均", 12), "7日平均     ");
+        assert_eq!(pad("too long to fit", 4), "too long to fit");
+    }
+
+    #[test]
+    fn test_lookup_falls_back_to_english() {
+        assert_eq!(t_in("ja", "Settings"), "設定");
+        assert_eq!(t_in("de", "Settings"), "Einstellungen");
+        assert_eq!(t_in("en", "Settings"), "Settings");
+        assert_eq!(t_in("es", "Not in the table"), "Not in the table");
+        // The context is only for telling ids apart
+        assert_eq!(t_in("en", "goals\u{4}Daily"), "Daily");
+        assert_eq!(t_in("ja", "goals\u{4}Daily"), "今日");
+        assert_eq!(t_in("ja", "Daily"), "日ごと");
+        // Each language puts the values where it needs them
+        let args: [&dyn Display; 3] = [&"A", &4, &12];
+        assert_eq!(
+            fill_in("en", "Last week: {0} ({1}% of {2} planned sessions)", &args),
+            "Last week: A (4% of 12 planned sessions)"
+        );
+        assert_eq!(
+            fill_in("ja", "Last week: {0} ({1}% of {2} planned sessions)", &args),
+            "先週: A (予定12セッションの4%)"
+        );
+    }
+
+    /// `{0}`, `{1}`… in `text`, sorted
+    fn placeholders(text: &str) -> Vec<&str> {
+        let mut found: Vec<&str> = text
+            .match_indices('{')
+            .filter_map(|(at, _)| {
+                let end = at + text[at..].find('}')?;
+                let inner = &text[at + 1..end];
+                (!inner.is_empty() && inner.chars().all(|c| c.is_ascii_digit()))
+                    .then(|| &text[at..=end])
+            })
+            .collect();
+        found.sort_unstable();
+        found
+    }
+
+    #[test]
+    fn test_every_translation_is_complete() {
+        let mut ids = std::collections::HashSet::new();
+        for (id, row) in STRINGS {
+            assert!(ids.insert(*id), "`{}` is in the table twice", id);
+            for text in row {
+                assert!(!text.is_empty(), "`{}` has an empty translation", id);
+                assert_eq!(
+                    placeholders(text),
+                    placeholders(id),
+                    "`{}` has other placeholders in \"{}\"",
+                    id,
+                    text
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_every_top_level_command_has_help_in_every_language() {
+        use clap::CommandFactory;
+        let cli = crate::Cli::command();
+        for lang in Language::ALL {
+            if lang == Language::English {
+                continue;
+            }
+            assert!(help_in("", lang).is_some());
+            for sub in cli.get_subcommands() {
+                assert!(
+                    help_in(sub.get_name(), lang).is_some(),
+                    "no {:?} help for `{}`",
+                    lang,
+                    sub.get_name()
+                );
+            }
+        }
+    }
+}
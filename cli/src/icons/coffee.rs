@@ -79,38 +79,7 @@ pub fn render_coffee_with_options(
     let is_working = !is_break && percent > 0.0 && percent < 100.0;
     let work_frame = animation_frame % 4;
 
-    // Generate coffee fill with gradient (wider cup, original height)
-    let generate_fill = |level: usize| -> Vec<String> {
-        let units_per_row = 3;
-        let mut rows = Vec::new();
-
-        for row in 0..ROWS {
-            let row_from_bottom = ROWS - 1 - row;
-            let row_start_unit = row_from_bottom * units_per_row;
-            let fill_amount = level.saturating_sub(row_start_unit).min(units_per_row);
-
-            let ch = if fill_amount == 0 {
-                ' '
-            } else if fill_amount >= units_per_row {
-                // Gradient: denser at bottom
-                if row_from_bottom <= 1 {
-                    '▓'
-                } else if row_from_bottom <= 3 {
-                    '▒'
-                } else {
-                    '░'
-                }
-            } else {
-                // Boundary row (surface) = wave
-                '~'
-            };
-
-            rows.push(ch.to_string().repeat(W));
-        }
-        rows
-    };
-
-    let fill = generate_fill(remaining_level);
+    let fill = fill_rows(remaining_level, ROWS, W);
 
     // Steam pattern (work mode: 4-phase rising animation, wider)
     let (steam0, steam1, steam2) = if show_steam && is_working {
@@ -175,6 +144,137 @@ pub fn render_coffee_with_options(
     }
 }
 
+/// Medium coffee cup: three rows of coffee under one line of steam
+pub fn render_coffee_medium(
+    percent: f32,
+    animation_frame: u8,
+    is_break: bool,
+    is_flowtime_work: bool,
+) -> Vec<String> {
+    const ROWS: usize = 3;
+    const W: usize = 8;
+    let (top, fill) = if is_flowtime_work {
+        let top = match animation_frame % 4 {
+            0 => " ∞  ~   ",
+            1 => "  ~   ∞ ",
+            2 => " ∞   ~  ",
+            _ => "   ~  ∞ ",
+        };
+        (top, vec!["▓".repeat(W); ROWS])
+    } else {
+        let (remaining_level, show_steam, is_pouring, is_working) =
+            cup_state(percent, is_break, ROWS * 3);
+        let top = if is_pouring {
+            if animation_frame.is_multiple_of(2) {
+                "  ╲│╱   "
+            } else {
+                "  ╲ ╱   "
+            }
+        } else if show_steam && is_working {
+            match animation_frame % 4 {
+                0 => " ~   ~  ",
+                1 => "  ~   ~ ",
+                2 => "   ~   ~",
+                _ => "        ",
+            }
+        } else if show_steam {
+            " ~   ~  "
+        } else {
+            "        "
+        };
+        (top, fill_rows(remaining_level, ROWS, W))
+    };
+    vec![
+        format!("  {}   ", top),
+        " ╭────────╮  ".to_string(),
+        format!(" │{}├╮ ", fill[0]),
+        format!(" │{}││ ", fill[1]),
+        format!(" │{}├╯ ", fill[2]),
+        " ╰────────╯  ".to_string(),
+    ]
+}
+
+/// Small coffee cup: a single row of coffee
+pub fn render_coffee_small(
+    percent: f32,
+    animation_frame: u8,
+    is_break: bool,
+    is_flowtime_work: bool,
+) -> Vec<String> {
+    const SHADES: [char; 4] = [' ', '░', '▒', '▓'];
+    let (top, coffee) = if is_flowtime_work {
+        (" ∞ ", '▓')
+    } else {
+        let (remaining_level, show_steam, is_pouring, is_working) = cup_state(percent, is_break, 3);
+        let top = if is_pouring {
+            if animation_frame.is_multiple_of(2) {
+                " ▼ "
+            } else {
+                " ▽ "
+            }
+        } else if show_steam && (!is_working || animation_frame.is_multiple_of(2)) {
+            "~ ~"
+        } else if show_steam {
+            " ~ "
+        } else {
+            "   "
+        };
+        (top, SHADES[remaining_level])
+    };
+    vec![
+        format!(" {}  ", top),
+        format!("│{}├╮", coffee.to_string().repeat(3)),
+        "╰───╯ ".to_string(),
+    ]
+}
+
+/// Coffee left (0..=`max_level`), whether it still steams, whether a break is
+/// pouring a refill and whether work is under way
+fn cup_state(percent: f32, is_break: bool, max_level: usize) -> (usize, bool, bool, bool) {
+    // During break, progress shows how much has been refilled (0→100)
+    let effective_progress = if is_break { 100.0 - percent } else { percent };
+    let remaining_level =
+        ((100.0 - effective_progress) / 100.0 * max_level as f32).round() as usize;
+    let running = percent > 0.0 && percent < 100.0;
+    (
+        remaining_level.min(max_level),
+        effective_progress < 60.0,
+        is_break && running,
+        !is_break && running,
+    )
+}
+
+/// Coffee rows, top row first, with gradient and a wavy surface
+fn fill_rows(level: usize, rows_count: usize, width: usize) -> Vec<String> {
+    let units_per_row = 3;
+    let mut rows = Vec::new();
+
+    for row in 0..rows_count {
+        let row_from_bottom = rows_count - 1 - row;
+        let row_start_unit = row_from_bottom * units_per_row;
+        let fill_amount = level.saturating_sub(row_start_unit).min(units_per_row);
+
+        let ch = if fill_amount == 0 {
+            ' '
+        } else if fill_amount >= units_per_row {
+            // Gradient: denser at bottom
+            if row_from_bottom <= 1 {
+                '▓'
+            } else if row_from_bottom <= 3 {
+                '▒'
+            } else {
+                '░'
+            }
+        } else {
+            // Boundary row (surface) = wave
+            '~'
+        };
+
+        rows.push(ch.to_string().repeat(width));
+    }
+    rows
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -262,4 +362,19 @@ mod tests {
         // Pouring animation should alternate
         assert_ne!(frame0[0], frame1[0]);
     }
+
+    #[test]
+    fn test_coffee_smaller_sizes() {
+        let medium = render_coffee_medium(0.0, 0, false, false);
+        assert_eq!(medium.len(), 6);
+        assert_eq!(medium[2], " │▒▒▒▒▒▒▒▒├╮ ");
+        assert_eq!(medium[4], " │▓▓▓▓▓▓▓▓├╯ ");
+        // Drained cup, pouring a refill on a break
+        let medium = render_coffee_medium(0.0, 0, true, false);
+        assert_eq!(medium[4], " │        ├╯ ");
+
+        let small = render_coffee_small(100.0, 0, false, false);
+        assert_eq!(small[1], "│   ├╮");
+        assert!(render_coffee_small(50.0, 0, true, false)[0].contains('▼'));
+    }
 }
@@ -0,0 +1,232 @@
+//! Campfire ASCII art animation
+//!
+//! Work mode: the flames burn down as the session goes on
+//! Break mode: the fire is rekindled, with 3-phase flicker throughout
+
+const FLAMES: [&str; 6] = [
+    "        )        ",
+    "       ) \\       ",
+    "      / ) (      ",
+    "     ( (   )     ",
+    "    (  )  ( )    ",
+    "     \\(__)_/     ",
+];
+const EMBERS: &str = "     . ' . '     ";
+const LOGS: [&str; 2] = ["   ===========   ", "  /__/_____\\__\\  "];
+
+/// Render fire at given percentage (0 = full flames, 100 = embers)
+pub fn render_fire(percent: f32, animation_frame: u8) -> Vec<String> {
+    render_fire_with_direction(percent, animation_frame, false)
+}
+
+/// Render fire with flow direction control
+pub fn render_fire_with_direction(
+    percent: f32,
+    animation_frame: u8,
+    is_break: bool,
+) -> Vec<String> {
+    render_fire_with_options(percent, animation_frame, is_break, false)
+}
+
+/// Render fire with all options including flowtime support
+pub fn render_fire_with_options(
+    percent: f32,
+    animation_frame: u8,
+    is_break: bool,
+    is_flowtime_work: bool,
+) -> Vec<String> {
+    const SPARKS: [&str; 3] = [
+        "     '     .     ",
+        "   .     '       ",
+        "       .     '   ",
+    ];
+    let campfire = Campfire {
+        flames: &FLAMES,
+        embers: EMBERS,
+        logs: &LOGS,
+        sparks: SPARKS,
+    };
+    campfire.render(percent, animation_frame, is_break, is_flowtime_work)
+}
+
+/// Medium fire: three rows of flame on a single log
+pub fn render_fire_medium(
+    percent: f32,
+    animation_frame: u8,
+    is_break: bool,
+    is_flowtime_work: bool,
+) -> Vec<String> {
+    let campfire = Campfire {
+        flames: &["     )     ", "    ( )    ", "   (_)_)   "],
+        embers: "   . ' .   ",
+        logs: &["  =======  "],
+        sparks: ["   '   .   ", " .    '    ", "     .   ' "],
+    };
+    campfire.render(percent, animation_frame, is_break, is_flowtime_work)
+}
+
+/// Small fire: two rows of flame
+pub fn render_fire_small(
+    percent: f32,
+    animation_frame: u8,
+    is_break: bool,
+    is_flowtime_work: bool,
+) -> Vec<String> {
+    let campfire = Campfire {
+        flames: &["  )  ", " (_) "],
+        embers: " .'. ",
+        logs: &["====="],
+        sparks: [" '   ", "   . ", "  '  "],
+    };
+    campfire.render(percent, animation_frame, is_break, is_flowtime_work)
+}
+
+/// One size of campfire; every row is the same width
+struct Campfire<'a> {
+    /// Flame rows, top first
+    flames: &'a [&'a str],
+    /// Shown in place of the bottom flame row once the fire is out
+    embers: &'a str,
+    logs: &'a [&'a str],
+    /// Sparks over a full fire, one row per flicker frame
+    sparks: [&'a str; 3],
+}
+
+impl Campfire<'_> {
+    fn render(
+        &self,
+        percent: f32,
+        animation_frame: u8,
+        is_break: bool,
+        is_flowtime_work: bool,
+    ) -> Vec<String> {
+        let rows = self.flames.len();
+        let frame = animation_frame % 3;
+        let width = self.logs[0].chars().count();
+
+        // Flowtime work mode keeps the fire at full height
+        let height = if is_flowtime_work {
+            rows
+        } else {
+            // During break, progress shows the fire being rekindled (0→100)
+            let effective_progress = if is_break { 100.0 - percent } else { percent };
+            ((100.0 - effective_progress) / 100.0 * rows as f32).ceil() as usize
+        };
+
+        let sparks = if is_flowtime_work {
+            format!(
+                "{}∞{}",
+                " ".repeat(width / 2),
+                " ".repeat(width - width / 2 - 1)
+            )
+        } else if height == rows {
+            self.sparks[frame as usize].to_string()
+        } else {
+            " ".repeat(width)
+        };
+
+        let mut lines = vec![sparks];
+        for (row, flame) in self.flames.iter().enumerate() {
+            if row < rows - height {
+                let blank = if row == rows - 1 {
+                    self.embers.to_string()
+                } else {
+                    " ".repeat(width)
+                };
+                lines.push(blank);
+            } else {
+                lines.push(flicker(flame, frame, row < rows / 2));
+            }
+        }
+        lines.extend(self.logs.iter().map(|s| s.to_string()));
+        lines
+    }
+}
+
+/// Frame 0 is the flame as drawn, frame 1 leans the other way and frame 2
+/// sways the tips (the upper half of the rows) to the right
+fn flicker(row: &str, frame: u8, is_tip: bool) -> String {
+    match frame {
+        0 => row.to_string(),
+        1 => row
+            .chars()
+            .rev()
+            .map(|c| match c {
+                '(' => ')',
+                ')' => '(',
+                '/' => '\\',
+                '\\' => '/',
+                c => c,
+            })
+            .collect(),
+        _ if is_tip => {
+            let mut chars: Vec<char> = row.chars().collect();
+            chars.rotate_right(1);
+            chars.into_iter().collect()
+        }
+        _ => row.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fire_line_count() {
+        for percent in [0.0, 50.0, 100.0] {
+            let lines = render_fire(percent, 0);
+            assert_eq!(lines.len(), 9);
+            let width = lines[0].chars().count();
+            assert!(lines.iter().all(|l| l.chars().count() == width));
+        }
+    }
+
+    #[test]
+    fn test_fire_burns_down() {
+        let full = render_fire(0.0, 0);
+        assert_eq!(full[1], FLAMES[0]);
+        let low = render_fire(80.0, 0);
+        assert!(low[1].trim().is_empty());
+        let out = render_fire(100.0, 0);
+        assert_eq!(out[6], EMBERS);
+    }
+
+    #[test]
+    fn test_fire_rekindles_during_break() {
+        let start = render_fire_with_direction(0.0, 0, true);
+        assert_eq!(start[6], EMBERS);
+        let end = render_fire_with_direction(100.0, 0, true);
+        assert_eq!(end[1], FLAMES[0]);
+    }
+
+    #[test]
+    fn test_fire_flicker_frames() {
+        let frames: Vec<_> = (0..3).map(|f| render_fire(0.0, f)).collect();
+        assert_ne!(frames[0], frames[1]);
+        assert_ne!(frames[1], frames[2]);
+        assert_ne!(frames[0], frames[2]);
+    }
+
+    #[test]
+    fn test_fire_flowtime() {
+        let lines = render_fire_with_options(90.0, 0, false, true);
+        assert!(lines[0].contains('∞'));
+        assert_eq!(lines[1], FLAMES[0]);
+    }
+
+    #[test]
+    fn test_fire_smaller_sizes() {
+        for render in [render_fire_medium, render_fire_small] {
+            let full = render(0.0, 0, false, false);
+            let width = full[0].chars().count();
+            assert!(full.iter().all(|l| l.chars().count() == width));
+            assert!(!full[1].trim().is_empty());
+            // Burnt out: only embers over the logs
+            let out = render(100.0, 0, false, false);
+            assert!(out[1..out.len() - 1].iter().all(|l| !l.contains(')')));
+        }
+        assert_eq!(render_fire_medium(0.0, 0, false, false).len(), 5);
+        assert_eq!(render_fire_small(0.0, 0, false, false).len(), 4);
+    }
+}
@@ -0,0 +1,234 @@
+//! Handheld game console ASCII art animation
+//!
+//! Work mode: the player runs and jumps through the level towards the flag
+//! Break mode: the game is paused and the player's HP bar refills
+
+/// Width of the console screen
+const SCREEN: usize = 16;
+/// Rightmost column the player reaches on the full screen
+const GOAL_COL: usize = SCREEN - 3;
+const COIN_COLS: [usize; 4] = [3, 6, 9, 12];
+const GROUND: &str = "▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔";
+const FLOWTIME_GROUND: &str = "▔▔▔ ▔▔▔▔▔ ▔▔▔▔ ▔";
+
+/// Render game at given percentage (0 = level start, 100 = at the flag)
+pub fn render_game(percent: f32, animation_frame: u8) -> Vec<String> {
+    render_game_with_direction(percent, animation_frame, false)
+}
+
+/// Render game with flow direction control
+pub fn render_game_with_direction(
+    percent: f32,
+    animation_frame: u8,
+    is_break: bool,
+) -> Vec<String> {
+    render_game_with_options(percent, animation_frame, is_break, false)
+}
+
+/// Render game with all options including flowtime support
+pub fn render_game_with_options(
+    percent: f32,
+    animation_frame: u8,
+    is_break: bool,
+    is_flowtime_work: bool,
+) -> Vec<String> {
+    let screen = screen(percent, animation_frame, is_break, is_flowtime_work, SCREEN);
+
+    vec![
+        "╭────────────────────╮".to_string(),
+        "│ ╭────────────────╮ │".to_string(),
+        format!("│ │{}│ │", screen[0]),
+        format!("│ │{}│ │", screen[1]),
+        format!("│ │{}│ │", screen[2]),
+        "│ ╰────────────────╯ │".to_string(),
+        "│  -+-        (B)(A) │".to_string(),
+        "│      [=] [=]       │".to_string(),
+        "╰────────────────────╯".to_string(),
+    ]
+}
+
+/// Medium console: just the screen in its bezel
+pub fn render_game_medium(
+    percent: f32,
+    animation_frame: u8,
+    is_break: bool,
+    is_flowtime_work: bool,
+) -> Vec<String> {
+    let screen = screen(percent, animation_frame, is_break, is_flowtime_work, 12);
+    vec![
+        "╭────────────╮".to_string(),
+        format!("│{}│", screen[0]),
+        format!("│{}│", screen[1]),
+        format!("│{}│", screen[2]),
+        "╰────────────╯".to_string(),
+    ]
+}
+
+/// Small console: a narrower screen without a bezel
+pub fn render_game_small(
+    percent: f32,
+    animation_frame: u8,
+    is_break: bool,
+    is_flowtime_work: bool,
+) -> Vec<String> {
+    screen(percent, animation_frame, is_break, is_flowtime_work, 10)
+        .into_iter()
+        .map(|row| format!("│{}│", row))
+        .collect()
+}
+
+/// The three screen rows, `width` (at least 10) columns wide: sky, the lane
+/// the player runs in and the ground
+fn screen(
+    percent: f32,
+    animation_frame: u8,
+    is_break: bool,
+    is_flowtime_work: bool,
+    width: usize,
+) -> [String; 3] {
+    // Rightmost column the player reaches, next to the flag
+    let goal_col = width - 3;
+    let frame = animation_frame % 4;
+    let jumping = !frame.is_multiple_of(2);
+
+    if is_flowtime_work {
+        // Flowtime work mode: an endless runner with the ground scrolling by
+        let mut air = vec![' '; width];
+        let mut lane = vec![' '; width];
+        if jumping {
+            air[4] = '@';
+        } else {
+            lane[4] = '@';
+        }
+        air[goal_col] = '∞';
+        let ground: String = FLOWTIME_GROUND
+            .chars()
+            .cycle()
+            .skip(frame as usize)
+            .take(width)
+            .collect();
+        [
+            air.into_iter().collect(),
+            lane.into_iter().collect(),
+            ground,
+        ]
+    } else if is_break {
+        // HP refills over the break (0→100)
+        let bar = width - 6;
+        let filled = ((percent / 100.0) * bar as f32).round() as usize;
+        let filled = filled.min(bar);
+        let z = if frame.is_multiple_of(2) { "z " } else { " Z" };
+        [
+            format!("  PAUSE{}{} ", " ".repeat(width - 10), z),
+            format!(" HP[{}{}] ", "▓".repeat(filled), "░".repeat(bar - filled)),
+            GROUND.chars().take(width).collect(),
+        ]
+    } else {
+        let x = ((percent / 100.0) * goal_col as f32).round() as usize;
+        let x = x.min(goal_col);
+        let mut air = vec![' '; width];
+        let mut lane = vec![' '; width];
+        // Coins ahead of the player are still to be collected
+        for &col in COIN_COLS.iter().filter(|&&col| col > x && col < goal_col) {
+            air[col] = 'o';
+        }
+        air[width - 2] = '|';
+        air[width - 1] = '>';
+        lane[width - 2] = '|';
+        // Only jump while the level is being played
+        if jumping && percent > 0.0 && percent < 100.0 {
+            air[x] = '@';
+        } else {
+            lane[x] = '@';
+        }
+        [
+            air.into_iter().collect(),
+            lane.into_iter().collect(),
+            GROUND.chars().take(width).collect(),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn player_col(line: &str) -> Option<usize> {
+        line.chars().position(|c| c == '@')
+    }
+
+    #[test]
+    fn test_game_line_count() {
+        for (percent, is_break) in [(0.0, false), (50.0, false), (100.0, false), (50.0, true)] {
+            for frame in 0..4 {
+                let lines = render_game_with_direction(percent, frame, is_break);
+                assert_eq!(lines.len(), 9);
+                assert!(lines.iter().all(|l| l.chars().count() == 22));
+            }
+        }
+    }
+
+    #[test]
+    fn test_game_player_runs_to_flag() {
+        let start = render_game(0.0, 0);
+        let middle = render_game(50.0, 0);
+        let end = render_game(100.0, 0);
+        assert!(player_col(&middle[3]) > player_col(&start[3]));
+        assert_eq!(player_col(&end[3]), Some(3 + GOAL_COL));
+    }
+
+    #[test]
+    fn test_game_coins_collected() {
+        let start = render_game(0.0, 0);
+        let end = render_game(100.0, 0);
+        assert_eq!(start[2].matches('o').count(), COIN_COLS.len());
+        assert_eq!(end[2].matches('o').count(), 0);
+    }
+
+    #[test]
+    fn test_game_jump_animation() {
+        let frame0 = render_game(50.0, 0);
+        let frame1 = render_game(50.0, 1);
+        assert!(frame0[3].contains('@'));
+        assert!(frame1[2].contains('@'));
+    }
+
+    #[test]
+    fn test_game_break_refills_hp() {
+        let start = render_game_with_direction(0.0, 0, true);
+        let end = render_game_with_direction(100.0, 0, true);
+        assert!(start[2].contains("PAUSE"));
+        assert!(!start[3].contains('▓'));
+        assert!(!end[3].contains('░'));
+    }
+
+    #[test]
+    fn test_game_flowtime_scrolls() {
+        let frame0 = render_game_with_options(0.0, 0, false, true);
+        let frame2 = render_game_with_options(0.0, 2, false, true);
+        assert!(frame0[2].contains('∞'));
+        assert_ne!(frame0[4], frame2[4]);
+    }
+
+    #[test]
+    fn test_game_smaller_sizes() {
+        for (render, len, width) in [
+            (
+                render_game_medium as fn(f32, u8, bool, bool) -> Vec<String>,
+                5,
+                14,
+            ),
+            (render_game_small, 3, 12),
+        ] {
+            for (percent, is_break) in [(0.0, false), (100.0, false), (50.0, true)] {
+                let lines = render(percent, 1, is_break, false);
+                assert_eq!(lines.len(), len);
+                assert!(lines.iter().all(|l| l.chars().count() == width));
+            }
+            // The player still ends up next to the flag
+            let end = render(100.0, 0, false, false);
+            let lane = &end[len / 2];
+            assert_eq!(player_col(lane), Some(width - 4));
+        }
+    }
+}
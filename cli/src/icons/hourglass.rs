@@ -72,68 +72,165 @@ pub fn render_hourglass_with_options(
     const MAX_LEVEL: usize = 18; // 6 rows × 3 gradient steps
     const W: usize = 10;
 
-    // Calculate sand levels based on direction
-    let (top_level, bottom_level, is_upward): (usize, usize, bool) = if is_break {
-        let top = ((percent as f64 / 100.0) * MAX_LEVEL as f64).round() as usize;
-        let bot = (((100.0 - percent) as f64 / 100.0) * MAX_LEVEL as f64).round() as usize;
-        (top.min(MAX_LEVEL), bot.min(MAX_LEVEL), true)
+    let (top_level, bottom_level) = sand_levels(percent, is_break, MAX_LEVEL);
+    let top_sand = sand_rows(top_level, ROWS, W);
+    let bot_sand = sand_rows(bottom_level, ROWS, W);
+
+    // Helper to slice unicode string by char indices
+    fn slice_chars(s: &str, start: usize, end: usize) -> String {
+        s.chars().skip(start).take(end - start).collect()
+    }
+
+    let ((flow1, flow2), (neck1, neck2)) = flow_marks(percent, animation_frame, is_break);
+
+    // Frame design matching web version
+    vec![
+        " ╔══════════╗ ".to_string(),
+        " ║▄▄▄▄▄▄▄▄▄▄║ ".to_string(),
+        format!(" ║{}║ ", top_sand[0]),
+        format!(" ║{}║ ", top_sand[1]),
+        format!(" ║{}║ ", top_sand[2]),
+        format!(" ║{}║ ", top_sand[3]),
+        format!("  ╲{}╱  ", slice_chars(&top_sand[4], 1, W - 1)),
+        format!("   ╲{}╱   ", slice_chars(&top_sand[5], 2, W - 2)),
+        format!("    ╲ {}{} ╱    ", flow1, flow2),
+        format!("     ╲{}{}╱     ", neck1, neck2),
+        format!("     ╱{}{}╲     ", neck2, neck1),
+        format!("    ╱ {}{} ╲    ", flow2, flow1),
+        format!("   ╱{}╲   ", slice_chars(&bot_sand[0], 2, W - 2)),
+        format!("  ╱{}╲  ", slice_chars(&bot_sand[1], 1, W - 1)),
+        format!(" ║{}║ ", bot_sand[2]),
+        format!(" ║{}║ ", bot_sand[3]),
+        format!(" ║{}║ ", bot_sand[4]),
+        format!(" ║{}║ ", bot_sand[5]),
+        " ║▀▀▀▀▀▀▀▀▀▀║ ".to_string(),
+        " ╚══════════╝ ".to_string(),
+    ]
+}
+
+/// Medium hourglass: two rows of sand per bulb
+pub fn render_hourglass_medium(
+    percent: f32,
+    animation_frame: u8,
+    is_break: bool,
+    is_flowtime_work: bool,
+) -> Vec<String> {
+    const ROWS: usize = 2;
+    const W: usize = 6;
+    let (top_sand, bot_sand, flow, neck) = if is_flowtime_work {
+        let frame = animation_frame % 2;
+        let flow = if frame == 0 { "∞·" } else { "·∞" };
+        let neck = if frame == 0 { "▓░" } else { "░▓" };
+        let sand = vec!["░".repeat(W); ROWS];
+        (sand.clone(), sand, flow.to_string(), neck.to_string())
     } else {
-        let top = (((100.0 - percent) as f64 / 100.0) * MAX_LEVEL as f64).round() as usize;
-        let bot = ((percent as f64 / 100.0) * MAX_LEVEL as f64).round() as usize;
-        (top.min(MAX_LEVEL), bot.min(MAX_LEVEL), false)
+        let (top_level, bottom_level) = sand_levels(percent, is_break, ROWS * 3);
+        let ((flow1, flow2), (neck1, neck2)) = flow_marks(percent, animation_frame, is_break);
+        (
+            sand_rows(top_level, ROWS, W),
+            sand_rows(bottom_level, ROWS, W),
+            format!("{}{}", flow1, flow2),
+            format!("{}{}", neck2, neck1),
+        )
     };
+    let inner = |row: &str| -> String { row.chars().skip(1).take(W - 2).collect() };
+    vec![
+        " ╔══════╗ ".to_string(),
+        format!(" ║{}║ ", top_sand[0]),
+        format!("  ╲{}╱  ", inner(&top_sand[1])),
+        format!("   ╲{}╱   ", flow),
+        format!("   ╱{}╲   ", neck),
+        format!("  ╱{}╲  ", inner(&bot_sand[0])),
+        format!(" ║{}║ ", bot_sand[1]),
+        " ╚══════╝ ".to_string(),
+    ]
+}
 
-    // Generate sand rows with gradient
-    let generate_sand = |level: usize| -> Vec<String> {
-        let units_per_row = 3;
-        let mut rows = Vec::new();
-
-        for row in 0..ROWS {
-            let row_from_bottom = ROWS - 1 - row;
-            let row_start_unit = row_from_bottom * units_per_row;
-            let fill_amount = level.saturating_sub(row_start_unit).min(units_per_row);
-
-            let ch = if fill_amount == 0 {
-                ' '
-            } else if fill_amount >= units_per_row {
-                // Gradient: denser at bottom
-                if row_from_bottom <= 1 {
-                    '▓'
-                } else if row_from_bottom <= 3 {
-                    '▒'
-                } else {
-                    '░'
-                }
-            } else {
-                // Partial fill (boundary row)
-                if fill_amount == 1 {
-                    '░'
-                } else if fill_amount == 2 {
-                    '▒'
-                } else {
-                    '▓'
-                }
-            };
-
-            rows.push(ch.to_string().repeat(W));
-        }
-        rows
+/// Small hourglass: one row of sand per bulb around the neck
+pub fn render_hourglass_small(
+    percent: f32,
+    animation_frame: u8,
+    is_break: bool,
+    is_flowtime_work: bool,
+) -> Vec<String> {
+    const SHADES: [char; 4] = [' ', '░', '▒', '▓'];
+    let (top, bottom, neck) = if is_flowtime_work {
+        ('░', '░', "∞")
+    } else {
+        let (top_level, bottom_level) = sand_levels(percent, is_break, 3);
+        let ((flow, _), _) = flow_marks(percent, animation_frame, is_break);
+        (SHADES[top_level], SHADES[bottom_level], flow)
     };
+    vec![
+        format!("╲{}╱", top.to_string().repeat(3)),
+        format!(" ╲{}╱ ", neck),
+        format!("╱{}╲", bottom.to_string().repeat(3)),
+    ]
+}
+
+/// Sand in the top and bottom bulbs, each 0..=`max_level`
+///
+/// Work drains the top into the bottom; a break runs the other way.
+fn sand_levels(percent: f32, is_break: bool, max_level: usize) -> (usize, usize) {
+    let level = |p: f32| -> usize {
+        (((p as f64 / 100.0) * max_level as f64).round() as usize).min(max_level)
+    };
+    if is_break {
+        (level(percent), level(100.0 - percent))
+    } else {
+        (level(100.0 - percent), level(percent))
+    }
+}
 
-    let top_sand = generate_sand(top_level);
-    let bot_sand = generate_sand(bottom_level);
+/// Sand rows for one bulb, top row first, with gradient
+fn sand_rows(level: usize, rows_count: usize, width: usize) -> Vec<String> {
+    let units_per_row = 3;
+    let mut rows = Vec::new();
 
-    // Helper to slice unicode string by char indices
-    fn slice_chars(s: &str, start: usize, end: usize) -> String {
-        s.chars().skip(start).take(end - start).collect()
+    for row in 0..rows_count {
+        let row_from_bottom = rows_count - 1 - row;
+        let row_start_unit = row_from_bottom * units_per_row;
+        let fill_amount = level.saturating_sub(row_start_unit).min(units_per_row);
+
+        let ch = if fill_amount == 0 {
+            ' '
+        } else if fill_amount >= units_per_row {
+            // Gradient: denser at bottom
+            if row_from_bottom <= 1 {
+                '▓'
+            } else if row_from_bottom <= 3 {
+                '▒'
+            } else {
+                '░'
+            }
+        } else {
+            // Partial fill (boundary row)
+            if fill_amount == 1 {
+                '░'
+            } else if fill_amount == 2 {
+                '▒'
+            } else {
+                '▓'
+            }
+        };
+
+        rows.push(ch.to_string().repeat(width));
     }
+    rows
+}
+
+/// A pair of one-cell marks
+type Marks = (&'static str, &'static str);
 
+/// Sand falling through the neck and the sand held in it; blank unless the
+/// timer is running
+fn flow_marks(percent: f32, animation_frame: u8, is_break: bool) -> (Marks, Marks) {
     let flowing = percent > 0.0 && percent < 100.0;
     let frame = animation_frame % 4;
 
     // Flowing sand animation (4-phase for finer detail)
-    let (flow1, flow2) = if flowing {
-        if is_upward {
+    let flow = if flowing {
+        if is_break {
             // Break mode: blinking upward
             if frame.is_multiple_of(2) {
                 ("↑", "°")
@@ -154,8 +251,8 @@ pub fn render_hourglass_with_options(
     };
 
     // Bottleneck sand expression (only during flow)
-    let (neck1, neck2) = if flowing {
-        if is_upward {
+    let neck = if flowing {
+        if is_break {
             // Break mode
             if frame.is_multiple_of(2) {
                 ("·", "°")
@@ -175,29 +272,7 @@ pub fn render_hourglass_with_options(
         (" ", " ")
     };
 
-    // Frame design matching web version
-    vec![
-        " ╔══════════╗ ".to_string(),
-        " ║▄▄▄▄▄▄▄▄▄▄║ ".to_string(),
-        format!(" ║{}║ ", top_sand[0]),
-        format!(" ║{}║ ", top_sand[1]),
-        format!(" ║{}║ ", top_sand[2]),
-        format!(" ║{}║ ", top_sand[3]),
-        format!("  ╲{}╱  ", slice_chars(&top_sand[4], 1, W - 1)),
-        format!("   ╲{}╱   ", slice_chars(&top_sand[5], 2, W - 2)),
-        format!("    ╲ {}{} ╱    ", flow1, flow2),
-        format!("     ╲{}{}╱     ", neck1, neck2),
-        format!("     ╱{}{}╲     ", neck2, neck1),
-        format!("    ╱ {}{} ╲    ", flow2, flow1),
-        format!("   ╱{}╲   ", slice_chars(&bot_sand[0], 2, W - 2)),
-        format!("  ╱{}╲  ", slice_chars(&bot_sand[1], 1, W - 1)),
-        format!(" ║{}║ ", bot_sand[2]),
-        format!(" ║{}║ ", bot_sand[3]),
-        format!(" ║{}║ ", bot_sand[4]),
-        format!(" ║{}║ ", bot_sand[5]),
-        " ║▀▀▀▀▀▀▀▀▀▀║ ".to_string(),
-        " ╚══════════╝ ".to_string(),
-    ]
+    (flow, neck)
 }
 
 #[cfg(test)]
@@ -292,4 +367,20 @@ mod tests {
         assert!(lines[19].contains("╚"));
         assert!(lines[19].contains("╝"));
     }
+
+    #[test]
+    fn test_hourglass_smaller_sizes() {
+        let medium = render_hourglass_medium(0.0, 0, false, false);
+        assert_eq!(medium.len(), 8);
+        // Full top bulb, empty bottom bulb, nothing flowing yet
+        assert_eq!(medium[1], " ║▓▓▓▓▓▓║ ");
+        assert_eq!(medium[6], " ║      ║ ");
+        assert_eq!(medium[3], "   ╲  ╱   ");
+
+        let small = render_hourglass_small(100.0, 0, false, false);
+        assert_eq!(small, vec!["╲   ╱", " ╲ ╱ ", "╱▓▓▓╲"]);
+        // A break pours back into the top
+        assert_eq!(render_hourglass_small(100.0, 0, true, false)[0], "╲▓▓▓╱");
+        assert!(render_hourglass_small(50.0, 0, false, true)[1].contains('∞'));
+    }
 }
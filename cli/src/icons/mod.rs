@@ -4,9 +4,16 @@
 #![allow(dead_code)]
 
 pub mod coffee;
+pub mod fire;
+pub mod game;
 pub mod hourglass;
+pub mod music;
 pub mod progress;
+pub mod rocket;
+pub mod star;
+pub mod target;
 pub mod tomato;
+pub mod wave;
 
 /// Icon types available in the app
 #[derive(Clone, Copy, PartialEq, Eq, Debug)]
@@ -58,6 +65,17 @@ impl IconType {
         true
     }
 
+    /// Number of animation frames the icon cycles through
+    pub fn frame_count(&self) -> u8 {
+        match self {
+            IconType::Hourglass | IconType::Coffee => 4,
+            IconType::Rocket | IconType::Wave | IconType::Game | IconType::Music => 4,
+            IconType::Fire => 3,
+            IconType::Tomato | IconType::Progress | IconType::Target | IconType::Star => 2,
+            IconType::None => 1,
+        }
+    }
+
     /// Get the emoji representation
     pub fn emoji(&self) -> &'static str {
         match self {
@@ -133,6 +151,27 @@ impl std::fmt::Display for IconType {
     }
 }
 
+/// How big an icon is drawn. The timer uses the largest that fits the
+/// terminal and falls back to the progress bar when none does.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum IconSize {
+    /// Three or four lines, about ten columns
+    Small,
+    /// Up to eight lines, about fifteen columns
+    Medium,
+    /// The full drawing
+    Large,
+}
+
+impl IconSize {
+    /// Largest first
+    pub const ALL: [IconSize; 3] = [IconSize::Large, IconSize::Medium, IconSize::Small];
+}
+
+/// Signature shared by every rendering: percent, animation frame, break,
+/// flowtime work
+type Render = fn(f32, u8, bool, bool) -> Vec<String>;
+
 /// Icon rendering state
 pub struct IconState {
     pub icon_type: IconType,
@@ -140,6 +179,7 @@ pub struct IconState {
     pub animation_frame: u8,
     pub is_animating: bool,
     pub is_flowtime_work: bool,
+    pub size: IconSize,
 }
 
 impl IconState {
@@ -150,6 +190,7 @@ impl IconState {
             animation_frame: 0,
             is_animating: false,
             is_flowtime_work: false,
+            size: IconSize::Large,
         }
     }
 
@@ -160,49 +201,63 @@ impl IconState {
 
     /// Render the icon with break direction support and flowtime work mode
     pub fn render_with_direction(&self, is_break: bool) -> Vec<String> {
-        match self.icon_type {
-            IconType::None => vec![],
-            IconType::Progress => progress::render_progress_with_options(
-                self.percent,
-                self.animation_frame,
-                is_break,
-                self.is_flowtime_work,
-            ),
-            IconType::Hourglass => hourglass::render_hourglass_with_options(
-                self.percent,
-                self.animation_frame,
-                is_break,
-                self.is_flowtime_work,
-            ),
-            IconType::Tomato => tomato::render_tomato_with_options(
-                self.percent,
-                self.animation_frame,
-                is_break,
-                self.is_flowtime_work,
-            ),
-            IconType::Coffee => coffee::render_coffee_with_options(
-                self.percent,
-                self.animation_frame,
-                is_break,
-                self.is_flowtime_work,
-            ),
-            _ => vec!["[Icon not implemented]".to_string()],
-        }
+        let Some(render) = self.renderer() else {
+            return vec![];
+        };
+        render(
+            self.percent,
+            self.animation_frame,
+            is_break,
+            self.is_flowtime_work,
+        )
+    }
+
+    /// The rendering for this icon at this size
+    fn renderer(&self) -> Option<Render> {
+        use IconSize::{Large, Medium, Small};
+        let render: Render = match (self.icon_type, self.size) {
+            (IconType::None, _) => return None,
+            (IconType::Progress, Large) => progress::render_progress_with_options,
+            (IconType::Progress, Medium) => progress::render_progress_medium,
+            (IconType::Progress, Small) => progress::render_progress_small,
+            (IconType::Hourglass, Large) => hourglass::render_hourglass_with_options,
+            (IconType::Hourglass, Medium) => hourglass::render_hourglass_medium,
+            (IconType::Hourglass, Small) => hourglass::render_hourglass_small,
+            (IconType::Tomato, Large) => tomato::render_tomato_with_options,
+            (IconType::Tomato, Medium) => tomato::render_tomato_medium,
+            (IconType::Tomato, Small) => tomato::render_tomato_small,
+            (IconType::Coffee, Large) => coffee::render_coffee_with_options,
+            (IconType::Coffee, Medium) => coffee::render_coffee_medium,
+            (IconType::Coffee, Small) => coffee::render_coffee_small,
+            (IconType::Target, Large) => target::render_target_with_options,
+            (IconType::Target, Medium) => target::render_target_medium,
+            (IconType::Target, Small) => target::render_target_small,
+            (IconType::Fire, Large) => fire::render_fire_with_options,
+            (IconType::Fire, Medium) => fire::render_fire_medium,
+            (IconType::Fire, Small) => fire::render_fire_small,
+            (IconType::Star, Large) => star::render_star_with_options,
+            (IconType::Star, Medium) => star::render_star_medium,
+            (IconType::Star, Small) => star::render_star_small,
+            (IconType::Rocket, Large) => rocket::render_rocket_with_options,
+            (IconType::Rocket, Medium) => rocket::render_rocket_medium,
+            (IconType::Rocket, Small) => rocket::render_rocket_small,
+            (IconType::Wave, Large) => wave::render_wave_with_options,
+            (IconType::Wave, Medium) => wave::render_wave_medium,
+            (IconType::Wave, Small) => wave::render_wave_small,
+            (IconType::Game, Large) => game::render_game_with_options,
+            (IconType::Game, Medium) => game::render_game_medium,
+            (IconType::Game, Small) => game::render_game_small,
+            (IconType::Music, Large) => music::render_music_with_options,
+            (IconType::Music, Medium) => music::render_music_medium,
+            (IconType::Music, Small) => music::render_music_small,
+        };
+        Some(render)
     }
 
     /// Advance animation to next frame
     pub fn advance_animation(&mut self) {
         if self.is_animating {
-            let max_frames = match self.icon_type {
-                IconType::Hourglass => 4,
-                IconType::Coffee => 4,
-                IconType::Tomato => 2,
-                IconType::Progress => 2,
-                IconType::Fire => 3,
-                IconType::Star => 2,
-                _ => 1,
-            };
-            self.animation_frame = (self.animation_frame + 1) % max_frames;
+            self.animation_frame = (self.animation_frame + 1) % self.icon_type.frame_count();
         }
     }
 }
@@ -0,0 +1,214 @@
+//! Music staff ASCII art animation
+//!
+//! Work mode: notes are written onto the staff as the session goes on
+//! Break mode: the finished tune plays back under a moving playhead
+
+const WIDTH: usize = 24;
+/// First row of the five staff lines
+const STAFF_TOP: usize = 3;
+const NOTE_COLS: [usize; 8] = [3, 5, 8, 10, 13, 15, 18, 20];
+/// Staff line each note sits on, 0 being the top line
+const PITCHES: [usize; 8] = [2, 0, 1, 3, 4, 2, 1, 0];
+
+/// Render music at given percentage (0 = empty staff, 100 = tune complete)
+pub fn render_music(percent: f32, animation_frame: u8) -> Vec<String> {
+    render_music_with_direction(percent, animation_frame, false)
+}
+
+/// Render music with flow direction control
+pub fn render_music_with_direction(
+    percent: f32,
+    animation_frame: u8,
+    is_break: bool,
+) -> Vec<String> {
+    render_music_with_options(percent, animation_frame, is_break, false)
+}
+
+/// Render music with all options including flowtime support
+pub fn render_music_with_options(
+    percent: f32,
+    animation_frame: u8,
+    is_break: bool,
+    is_flowtime_work: bool,
+) -> Vec<String> {
+    let staff = Staff {
+        width: WIDTH,
+        lines: 5,
+        note_cols: &NOTE_COLS,
+        pitches: &PITCHES,
+        stem: STAFF_TOP - 1,
+    };
+    staff.render(percent, animation_frame, is_break, is_flowtime_work)
+}
+
+/// Medium staff: three lines and four notes
+pub fn render_music_medium(
+    percent: f32,
+    animation_frame: u8,
+    is_break: bool,
+    is_flowtime_work: bool,
+) -> Vec<String> {
+    let staff = Staff {
+        width: 14,
+        lines: 3,
+        note_cols: &[3, 5, 8, 10],
+        pitches: &[1, 0, 2, 1],
+        stem: 2,
+    };
+    staff.render(percent, animation_frame, is_break, is_flowtime_work)
+}
+
+/// Small staff: two lines and four notes without stems
+pub fn render_music_small(
+    percent: f32,
+    animation_frame: u8,
+    is_break: bool,
+    is_flowtime_work: bool,
+) -> Vec<String> {
+    let staff = Staff {
+        width: 10,
+        lines: 2,
+        note_cols: &[2, 4, 6, 8],
+        pitches: &[1, 0, 1, 0],
+        stem: 0,
+    };
+    staff.render(percent, animation_frame, is_break, is_flowtime_work)
+}
+
+/// One size of staff, drawn under a row for the playhead
+struct Staff<'a> {
+    width: usize,
+    /// Number of staff lines
+    lines: usize,
+    note_cols: &'a [usize],
+    /// Staff line each note sits on, 0 being the top line
+    pitches: &'a [usize],
+    /// Rows of stem above each note head
+    stem: usize,
+}
+
+impl Staff<'_> {
+    fn render(
+        &self,
+        percent: f32,
+        animation_frame: u8,
+        is_break: bool,
+        is_flowtime_work: bool,
+    ) -> Vec<String> {
+        let width = self.width;
+        let staff_top = 1 + self.stem;
+        let frame = (animation_frame % 4) as usize;
+        let blink = !frame.is_multiple_of(2);
+
+        let mut grid = vec![vec![' '; width]; staff_top + self.lines];
+        for row in grid.iter_mut().skip(staff_top) {
+            row.fill('─');
+            row[0] = '│';
+            row[width - 1] = '│';
+        }
+
+        let mut draw_note = |slot: usize, pitch: usize, head: char| {
+            let col = self.note_cols[slot];
+            let row = staff_top + pitch;
+            grid[row][col] = head;
+            for stem in 1..=self.stem {
+                grid[row - stem][col] = '│';
+            }
+        };
+
+        if is_flowtime_work {
+            // Flowtime work mode: an endless tune scrolling along the staff
+            for slot in 0..self.note_cols.len() {
+                draw_note(slot, self.pitches[(slot + frame) % self.pitches.len()], 'o');
+            }
+            grid[0][1] = '∞';
+        } else if is_break {
+            // The playhead moves across the staff over the break (0→100)
+            let playhead = 1 + ((percent / 100.0) * (width - 3) as f32).round() as usize;
+            let playhead = playhead.min(width - 2);
+            for (slot, &pitch) in self.pitches.iter().enumerate() {
+                let playing = self.note_cols[slot].abs_diff(playhead) <= 1;
+                draw_note(slot, pitch, if playing { '@' } else { 'o' });
+            }
+            grid[0][playhead] = if blink { 'V' } else { 'v' };
+        } else {
+            let written = ((percent / 100.0) * self.note_cols.len() as f32).round() as usize;
+            let written = written.min(self.note_cols.len());
+            for (slot, &pitch) in self.pitches.iter().enumerate().take(written) {
+                // The newest note blinks while it's being written
+                let newest = slot + 1 == written && percent < 100.0;
+                draw_note(slot, pitch, if newest && blink { '@' } else { 'o' });
+            }
+        }
+
+        grid.into_iter()
+            .map(|row| row.into_iter().collect())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn note_count(lines: &[String]) -> usize {
+        lines.iter().map(|l| l.matches(['o', '@']).count()).sum()
+    }
+
+    #[test]
+    fn test_music_line_count() {
+        for percent in [0.0, 50.0, 100.0] {
+            let lines = render_music(percent, 0);
+            assert_eq!(lines.len(), STAFF_TOP + 5);
+            assert!(lines.iter().all(|l| l.chars().count() == WIDTH));
+        }
+    }
+
+    #[test]
+    fn test_music_notes_written() {
+        assert_eq!(note_count(&render_music(0.0, 0)), 0);
+        assert_eq!(note_count(&render_music(50.0, 0)), 4);
+        assert_eq!(note_count(&render_music(100.0, 0)), 8);
+    }
+
+    #[test]
+    fn test_music_newest_note_blinks() {
+        let frame0 = render_music(50.0, 0);
+        let frame1 = render_music(50.0, 1);
+        assert!(!frame0.join("").contains('@'));
+        assert!(frame1.join("").contains('@'));
+    }
+
+    #[test]
+    fn test_music_playhead_during_break() {
+        let start = render_music_with_direction(0.0, 0, true);
+        let end = render_music_with_direction(100.0, 0, true);
+        assert_eq!(note_count(&start), 8);
+        assert!(start[0].find('v') < end[0].find('v'));
+    }
+
+    #[test]
+    fn test_music_flowtime_scrolls() {
+        let frame0 = render_music_with_options(0.0, 0, false, true);
+        let frame1 = render_music_with_options(0.0, 1, false, true);
+        assert!(frame0[0].contains('∞'));
+        assert_ne!(frame0, frame1);
+    }
+
+    #[test]
+    fn test_music_smaller_sizes() {
+        let medium = render_music_medium(100.0, 0, false, false);
+        assert_eq!(medium.len(), 6);
+        assert_eq!(
+            medium.iter().map(|l| l.matches('o').count()).sum::<usize>(),
+            4
+        );
+        let small = render_music_small(50.0, 0, false, false);
+        assert_eq!(small.len(), 3);
+        assert_eq!(
+            small.iter().map(|l| l.matches('o').count()).sum::<usize>(),
+            2
+        );
+        assert!(render_music_small(50.0, 0, true, false)[0].contains('v'));
+    }
+}
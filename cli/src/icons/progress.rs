@@ -69,6 +69,72 @@ pub fn render_progress_with_options(
     }
 }
 
+/// Medium progress bar: a ten-block bar over the percentage
+pub fn render_progress_medium(
+    percent: f32,
+    animation_frame: u8,
+    is_break: bool,
+    is_flowtime_work: bool,
+) -> Vec<String> {
+    const WIDTH: usize = 10;
+    if is_flowtime_work {
+        return vec![
+            format!("[{}]", flow_wave(animation_frame, WIDTH)),
+            "∞ FLOW".to_string(),
+        ];
+    }
+    let effective_progress = if is_break { 100.0 - percent } else { percent };
+    let is_refilling = is_break && percent > 0.0 && percent < 100.0;
+    let arrow = match (is_refilling, animation_frame % 2) {
+        (false, _) => "",
+        (true, 0) => " ◀",
+        (true, _) => " ◄",
+    };
+    vec![
+        format!("[{}]", bar(effective_progress, WIDTH)),
+        format!("{:>3}%{}", effective_progress.round() as i32, arrow),
+    ]
+}
+
+/// Small progress bar: five blocks and the percentage on one line
+pub fn render_progress_small(
+    percent: f32,
+    animation_frame: u8,
+    is_break: bool,
+    is_flowtime_work: bool,
+) -> Vec<String> {
+    const WIDTH: usize = 5;
+    if is_flowtime_work {
+        return vec![format!("{} ∞", flow_wave(animation_frame, WIDTH))];
+    }
+    let effective_progress = if is_break { 100.0 - percent } else { percent };
+    vec![format!(
+        "{} {:>3}%",
+        bar(effective_progress, WIDTH),
+        effective_progress.round() as i32
+    )]
+}
+
+/// `width` blocks filled in proportion to `progress` (0.0-100.0)
+fn bar(progress: f32, width: usize) -> String {
+    let filled = ((progress / 100.0) * width as f32).round() as usize;
+    let filled = filled.min(width);
+    format!("{}{}", "█".repeat(filled), "░".repeat(width - filled))
+}
+
+/// A bright band sweeping across `width` cells for flowtime work
+fn flow_wave(animation_frame: u8, width: usize) -> String {
+    let head = (animation_frame as usize) % width;
+    (0..width)
+        .map(|i| match (i + width - head) % width {
+            0 => '▓',
+            1 => '█',
+            2 => '▓',
+            _ => '░',
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -160,4 +226,20 @@ mod tests {
         // At 100%, no refilling
         assert!(!lines[0].contains("REFILL"));
     }
+
+    #[test]
+    fn test_progress_smaller_sizes() {
+        let medium = render_progress_medium(50.0, 0, false, false);
+        assert_eq!(medium, vec!["[█████░░░░░]", " 50%"]);
+        assert_eq!(
+            render_progress_small(50.0, 0, false, false),
+            vec!["███░░  50%"]
+        );
+        // Break refills from the other end
+        assert_eq!(
+            render_progress_small(80.0, 0, true, false),
+            vec!["█░░░░  20%"]
+        );
+        assert!(render_progress_small(0.0, 3, false, true)[0].ends_with('∞'));
+    }
 }
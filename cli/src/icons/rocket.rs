@@ -0,0 +1,252 @@
+//! Rocket ASCII art animation
+//!
+//! Work mode: the rocket lifts off the pad and climbs as the session goes on
+//! Break mode: the engine is off and the rocket drifts back down to the pad
+
+const ROCKET: [&str; 5] = ["   ^   ", "  / \\  ", "  |o|  ", "  | |  ", " /|_|\\ "];
+const EXHAUST: [[&str; 2]; 2] = [["  )|(  ", "  ' '  "], ["  (|)  ", "   '   "]];
+const SKY: [&str; 12] = [
+    " .           * ",
+    "            .  ",
+    "   *           ",
+    "             . ",
+    " .             ",
+    "           *   ",
+    "  .            ",
+    "             . ",
+    "   *           ",
+    "           .   ",
+    " .             ",
+    "            *  ",
+];
+const GROUND: &str = "_____[___]_____";
+/// Column the rocket is drawn from
+const LEFT: usize = 4;
+/// Highest row the top of the rocket sits on the pad
+const PAD_TOP: usize = 5;
+/// Row the rocket hovers at in flowtime work mode
+const CRUISE_TOP: usize = 3;
+
+/// Render rocket at given percentage (0 = on the pad, 100 = top of the sky)
+pub fn render_rocket(percent: f32, animation_frame: u8) -> Vec<String> {
+    render_rocket_with_direction(percent, animation_frame, false)
+}
+
+/// Render rocket with flow direction control
+pub fn render_rocket_with_direction(
+    percent: f32,
+    animation_frame: u8,
+    is_break: bool,
+) -> Vec<String> {
+    render_rocket_with_options(percent, animation_frame, is_break, false)
+}
+
+/// Render rocket with all options including flowtime support
+pub fn render_rocket_with_options(
+    percent: f32,
+    animation_frame: u8,
+    is_break: bool,
+    is_flowtime_work: bool,
+) -> Vec<String> {
+    let launch = Launch {
+        rocket: &ROCKET,
+        exhaust: [&EXHAUST[0], &EXHAUST[1]],
+        sky: &SKY,
+        ground: GROUND,
+        flowtime_ground: "_____[_∞_]_____",
+        left: LEFT,
+        pad_top: PAD_TOP,
+        cruise_top: CRUISE_TOP,
+    };
+    launch.render(percent, animation_frame, is_break, is_flowtime_work)
+}
+
+/// Medium rocket: a three-row rocket in a shorter sky
+pub fn render_rocket_medium(
+    percent: f32,
+    animation_frame: u8,
+    is_break: bool,
+    is_flowtime_work: bool,
+) -> Vec<String> {
+    let launch = Launch {
+        rocket: &[" ^ ", "|o|", "/_\\"],
+        exhaust: [&[" ' "], &[" : "]],
+        sky: &[
+            " .      * ",
+            "        . ",
+            "  *       ",
+            "         .",
+            " .        ",
+            "       *  ",
+        ],
+        ground: "___[___]__",
+        flowtime_ground: "___[_∞_]__",
+        left: 3,
+        pad_top: 2,
+        cruise_top: 1,
+    };
+    launch.render(percent, animation_frame, is_break, is_flowtime_work)
+}
+
+/// Small rocket: a single cell climbing three rows
+pub fn render_rocket_small(
+    percent: f32,
+    animation_frame: u8,
+    is_break: bool,
+    is_flowtime_work: bool,
+) -> Vec<String> {
+    let launch = Launch {
+        rocket: &["^"],
+        exhaust: [&["'"], &[":"]],
+        sky: &[" .  *", "    .", "*    "],
+        ground: "_[_]_",
+        flowtime_ground: "_[∞]_",
+        left: 2,
+        pad_top: 1,
+        cruise_top: 0,
+    };
+    launch.render(percent, animation_frame, is_break, is_flowtime_work)
+}
+
+/// One size of launch: the sky fits the rocket and its exhaust with
+/// `pad_top` rows of climb above them
+struct Launch<'a> {
+    rocket: &'a [&'a str],
+    /// Exhaust under the rocket, one per animation frame
+    exhaust: [&'a [&'a str]; 2],
+    sky: &'a [&'a str],
+    ground: &'a str,
+    flowtime_ground: &'a str,
+    /// Column the rocket is drawn from
+    left: usize,
+    /// Highest row the top of the rocket sits on the pad
+    pad_top: usize,
+    /// Row the rocket hovers at in flowtime work mode
+    cruise_top: usize,
+}
+
+impl Launch<'_> {
+    fn render(
+        &self,
+        percent: f32,
+        animation_frame: u8,
+        is_break: bool,
+        is_flowtime_work: bool,
+    ) -> Vec<String> {
+        let frame = (animation_frame % 4) as usize;
+        let sky = self.sky;
+
+        let (top, scroll) = if is_flowtime_work {
+            // Flowtime work mode: cruising with the stars streaming past
+            (self.cruise_top, frame)
+        } else {
+            // During break, progress shows the descent (100→0)
+            let effective_progress = if is_break { 100.0 - percent } else { percent };
+            let climb = ((effective_progress / 100.0) * self.pad_top as f32).round() as usize;
+            (self.pad_top - climb.min(self.pad_top), 0)
+        };
+
+        let mut grid: Vec<Vec<char>> = (0..sky.len())
+            .map(|row| {
+                sky[(row + sky.len() - scroll % sky.len()) % sky.len()]
+                    .chars()
+                    .collect()
+            })
+            .collect();
+
+        let mut sprite: Vec<&str> = self.rocket.to_vec();
+        let engine_on = !is_break && (is_flowtime_work || percent < 100.0);
+        if engine_on {
+            sprite.extend(self.exhaust[frame % 2]);
+        }
+        for (offset, row) in sprite.iter().enumerate() {
+            let Some(cells) = grid.get_mut(top + offset) else {
+                break;
+            };
+            for (col, ch) in row.chars().enumerate() {
+                cells[self.left + col] = ch;
+            }
+        }
+
+        let mut lines: Vec<String> = grid
+            .into_iter()
+            .map(|row| row.into_iter().collect())
+            .collect();
+        lines.push(if is_flowtime_work {
+            self.flowtime_ground.to_string()
+        } else {
+            self.ground.to_string()
+        });
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rocket_top(lines: &[String]) -> usize {
+        lines.iter().position(|l| l.contains('^')).unwrap()
+    }
+
+    #[test]
+    fn test_rocket_line_count() {
+        for percent in [0.0, 50.0, 100.0] {
+            let lines = render_rocket(percent, 0);
+            assert_eq!(lines.len(), 13);
+            let width = lines[0].chars().count();
+            assert!(lines.iter().all(|l| l.chars().count() == width));
+        }
+    }
+
+    #[test]
+    fn test_rocket_climbs() {
+        assert_eq!(rocket_top(&render_rocket(0.0, 0)), PAD_TOP);
+        assert!(rocket_top(&render_rocket(50.0, 0)) < PAD_TOP);
+        assert_eq!(rocket_top(&render_rocket(100.0, 0)), 0);
+    }
+
+    #[test]
+    fn test_rocket_exhaust_animation() {
+        let frame0 = render_rocket(30.0, 0);
+        let frame1 = render_rocket(30.0, 1);
+        assert_ne!(frame0, frame1);
+    }
+
+    #[test]
+    fn test_rocket_descends_without_engine_during_break() {
+        let start = render_rocket_with_direction(0.0, 0, true);
+        assert_eq!(rocket_top(&start), 0);
+        let end = render_rocket_with_direction(100.0, 0, true);
+        assert_eq!(rocket_top(&end), PAD_TOP);
+        let art = end.join("");
+        assert!(!art.contains(")|(") && !art.contains("(|)"));
+    }
+
+    #[test]
+    fn test_rocket_flowtime_scrolls_stars() {
+        let frame0 = render_rocket_with_options(0.0, 0, false, true);
+        let frame2 = render_rocket_with_options(0.0, 2, false, true);
+        assert_eq!(rocket_top(&frame0), CRUISE_TOP);
+        assert_ne!(frame0[0], frame2[0]);
+        assert!(frame0[12].contains('∞'));
+    }
+
+    #[test]
+    fn test_rocket_smaller_sizes() {
+        let rocket_row = |lines: &[String]| lines.iter().position(|l| l.contains('^'));
+        for (render, len) in [
+            (
+                render_rocket_medium as fn(f32, u8, bool, bool) -> Vec<String>,
+                7,
+            ),
+            (render_rocket_small, 4),
+        ] {
+            let pad = render(0.0, 0, false, false);
+            let sky = render(100.0, 0, false, false);
+            assert_eq!(pad.len(), len);
+            assert_eq!(rocket_row(&sky), Some(0));
+            assert!(rocket_row(&pad) > rocket_row(&sky));
+        }
+    }
+}
@@ -0,0 +1,232 @@
+//! Star ASCII art animation
+//!
+//! Work mode: the star fills with light from the bottom up
+//! Break mode: the light fades back out, with twinkling sparkles around it
+
+/// `#` marks the inside of the star
+const STAR: [&str; 9] = [
+    "         /\\         ",
+    "        /##\\        ",
+    "  _____/####\\_____  ",
+    "  \\##############/  ",
+    "   '.##########.'   ",
+    "     )########(     ",
+    "    /###.''.###\\    ",
+    "   /##.'    '.##\\   ",
+    "  /.'          '.\\  ",
+];
+/// Rows holding the inside of the star
+const FIRST_ROW: usize = 1;
+const LAST_ROW: usize = 7;
+/// Sparkle positions, alternating between the two pairs
+const SPARKLES: [[(usize, usize); 2]; 2] = [[(0, 3), (5, 18)], [(0, 16), (5, 1)]];
+
+/// Render star at given percentage (0 = dark, 100 = fully lit)
+pub fn render_star(percent: f32, animation_frame: u8) -> Vec<String> {
+    render_star_with_direction(percent, animation_frame, false)
+}
+
+/// Render star with flow direction control
+pub fn render_star_with_direction(
+    percent: f32,
+    animation_frame: u8,
+    is_break: bool,
+) -> Vec<String> {
+    render_star_with_options(percent, animation_frame, is_break, false)
+}
+
+/// Render star with all options including flowtime support
+pub fn render_star_with_options(
+    percent: f32,
+    animation_frame: u8,
+    is_break: bool,
+    is_flowtime_work: bool,
+) -> Vec<String> {
+    let star = Star {
+        art: &STAR,
+        first_row: FIRST_ROW,
+        last_row: LAST_ROW,
+        sparkles: SPARKLES,
+    };
+    star.render(percent, animation_frame, is_break, is_flowtime_work)
+}
+
+/// Medium star: four rows of light
+pub fn render_star_medium(
+    percent: f32,
+    animation_frame: u8,
+    is_break: bool,
+    is_flowtime_work: bool,
+) -> Vec<String> {
+    let star = Star {
+        art: &[
+            "     /\\     ",
+            "  __/##\\__  ",
+            "  \\######/  ",
+            "   )####(   ",
+            "  /##..##\\  ",
+            " /.'    '.\\ ",
+        ],
+        first_row: 1,
+        last_row: 4,
+        sparkles: [[(0, 2), (3, 10)], [(0, 9), (3, 1)]],
+    };
+    star.render(percent, animation_frame, is_break, is_flowtime_work)
+}
+
+/// Small star: a single row of light
+pub fn render_star_small(
+    percent: f32,
+    animation_frame: u8,
+    is_break: bool,
+    is_flowtime_work: bool,
+) -> Vec<String> {
+    let star = Star {
+        art: &["  /\\  ", "<####>", " /''\\ "],
+        first_row: 1,
+        last_row: 1,
+        sparkles: [[(0, 0), (2, 5)], [(0, 5), (2, 0)]],
+    };
+    star.render(percent, animation_frame, is_break, is_flowtime_work)
+}
+
+/// One size of star; `#` marks its inside and the last row is replaced by
+/// the flowtime marker
+struct Star<'a> {
+    art: &'a [&'a str],
+    /// Rows holding the inside of the star
+    first_row: usize,
+    last_row: usize,
+    /// Sparkle positions, alternating between the two pairs
+    sparkles: [[(usize, usize); 2]; 2],
+}
+
+impl Star<'_> {
+    fn render(
+        &self,
+        percent: f32,
+        animation_frame: u8,
+        is_break: bool,
+        is_flowtime_work: bool,
+    ) -> Vec<String> {
+        const UNITS_PER_ROW: usize = 3;
+        let rows = self.last_row - self.first_row + 1;
+        let max_level = rows * UNITS_PER_ROW;
+        let frame = (animation_frame % 2) as usize;
+
+        // During break, progress shows the light fading (100→0)
+        let effective_progress = if is_break { 100.0 - percent } else { percent };
+        let level = ((effective_progress / 100.0) * max_level as f32).round() as usize;
+
+        let mut grid: Vec<Vec<char>> = self.art.iter().map(|row| row.chars().collect()).collect();
+        for (row, cells) in grid.iter_mut().enumerate() {
+            let row_from_bottom = self.last_row.saturating_sub(row);
+            let ch = if is_flowtime_work {
+                // Flowtime work mode: fully lit, pulsing between shades
+                if (row + frame).is_multiple_of(2) {
+                    '▓'
+                } else {
+                    '▒'
+                }
+            } else {
+                let fill = level
+                    .saturating_sub(row_from_bottom * UNITS_PER_ROW)
+                    .min(UNITS_PER_ROW);
+                match fill {
+                    0 => ' ',
+                    1 => '░',
+                    2 => '▒',
+                    _ => '▓',
+                }
+            };
+            for cell in cells.iter_mut().filter(|c| **c == '#') {
+                *cell = ch;
+            }
+        }
+
+        for (index, sparkles) in self.sparkles.iter().enumerate() {
+            let ch = if index == frame { '*' } else { '.' };
+            for &(row, col) in sparkles {
+                grid[row][col] = ch;
+            }
+        }
+
+        let mut lines: Vec<String> = grid
+            .into_iter()
+            .map(|row| row.into_iter().collect())
+            .collect();
+        if is_flowtime_work {
+            let width = lines[0].chars().count();
+            if let Some(last) = lines.last_mut() {
+                *last = format!(
+                    "{}∞{}",
+                    " ".repeat(width / 2),
+                    " ".repeat(width - width / 2 - 1)
+                );
+            }
+        }
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_star_line_count() {
+        for percent in [0.0, 50.0, 100.0] {
+            let lines = render_star(percent, 0);
+            assert_eq!(lines.len(), 9);
+            let width = lines[0].chars().count();
+            assert!(lines.iter().all(|l| l.chars().count() == width));
+        }
+    }
+
+    #[test]
+    fn test_star_fills_from_bottom() {
+        let empty = render_star(0.0, 0).join("");
+        assert!(!empty.contains('▓'));
+        let half = render_star(50.0, 0);
+        assert!(half[LAST_ROW].contains('▓'));
+        assert!(!half[FIRST_ROW].contains('▓'));
+        let full = render_star(100.0, 0);
+        assert!(full[FIRST_ROW].contains('▓'));
+    }
+
+    #[test]
+    fn test_star_fades_during_break() {
+        let start = render_star_with_direction(0.0, 0, true);
+        assert!(start[FIRST_ROW].contains('▓'));
+        let end = render_star_with_direction(100.0, 0, true).join("");
+        assert!(!end.contains('▓'));
+    }
+
+    #[test]
+    fn test_star_sparkles_twinkle() {
+        let frame0 = render_star(50.0, 0);
+        let frame1 = render_star(50.0, 1);
+        assert_ne!(frame0[0], frame1[0]);
+        assert_ne!(frame0[5], frame1[5]);
+    }
+
+    #[test]
+    fn test_star_flowtime() {
+        let lines = render_star_with_options(0.0, 0, false, true);
+        assert!(lines[FIRST_ROW].contains('▓') || lines[FIRST_ROW].contains('▒'));
+        assert!(lines[8].contains('∞'));
+    }
+
+    #[test]
+    fn test_star_smaller_sizes() {
+        let dark = render_star_medium(0.0, 0, false, false);
+        assert_eq!(dark.len(), 6);
+        assert!(!dark.join("").contains('▓'));
+        let lit = render_star_medium(100.0, 0, false, false);
+        assert!(lit[3].contains(")▓▓▓▓("));
+
+        assert_eq!(render_star_small(100.0, 0, false, false)[1], "<▓▓▓▓>");
+        // Fading on a break
+        assert_eq!(render_star_small(100.0, 0, true, false)[1], "<    >");
+    }
+}
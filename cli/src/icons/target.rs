@@ -0,0 +1,213 @@
+//! Target ASCII art animation
+//!
+//! Work mode: an arrow flies in from the left and hits the bullseye at 100%
+//! Break mode: the arrow is pulled back out while the bullseye blinks
+
+const TARGET: [&str; 7] = [
+    "      .-'''''''-.      ",
+    "    .'  .-----.  '.    ",
+    "   /  .'  .-.  '.  \\   ",
+    "  |  |   ( o )   |  |  ",
+    "   \\  '.  '-'  .'  /   ",
+    "    '.  '-----'  .'    ",
+    "      '-._____.-'      ",
+];
+/// Row and column of the bullseye
+const CENTER_ROW: usize = 3;
+const CENTER_COL: usize = 11;
+/// Arrow length including fletching and tip
+const ARROW_LEN: usize = 6;
+
+/// Render target at given percentage (0 = arrow drawn, 100 = bullseye hit)
+pub fn render_target(percent: f32, animation_frame: u8) -> Vec<String> {
+    render_target_with_direction(percent, animation_frame, false)
+}
+
+/// Render target with flow direction control
+pub fn render_target_with_direction(
+    percent: f32,
+    animation_frame: u8,
+    is_break: bool,
+) -> Vec<String> {
+    render_target_with_options(percent, animation_frame, is_break, false)
+}
+
+/// Render target with all options including flowtime support
+pub fn render_target_with_options(
+    percent: f32,
+    animation_frame: u8,
+    is_break: bool,
+    is_flowtime_work: bool,
+) -> Vec<String> {
+    let target = Target {
+        art: &TARGET,
+        center_row: CENTER_ROW,
+        center_col: CENTER_COL,
+        arrow_len: ARROW_LEN,
+    };
+    target.render(percent, animation_frame, is_break, is_flowtime_work)
+}
+
+/// Medium target: two rings and a shorter arrow
+pub fn render_target_medium(
+    percent: f32,
+    animation_frame: u8,
+    is_break: bool,
+    is_flowtime_work: bool,
+) -> Vec<String> {
+    let target = Target {
+        art: &[
+            "   .-'''-.   ",
+            "  / .---. \\  ",
+            " | ( (o) ) | ",
+            "  \\ '---' /  ",
+            "   '-...-'   ",
+        ],
+        center_row: 2,
+        center_col: 6,
+        arrow_len: 4,
+    };
+    target.render(percent, animation_frame, is_break, is_flowtime_work)
+}
+
+/// Small target: just the bullseye
+pub fn render_target_small(
+    percent: f32,
+    animation_frame: u8,
+    is_break: bool,
+    is_flowtime_work: bool,
+) -> Vec<String> {
+    let target = Target {
+        art: &["    .-.  ", "   ( o ) ", "    '-'  "],
+        center_row: 1,
+        center_col: 5,
+        arrow_len: 3,
+    };
+    target.render(percent, animation_frame, is_break, is_flowtime_work)
+}
+
+/// One size of target, drawn above a caption row
+struct Target<'a> {
+    art: &'a [&'a str],
+    /// Row and column of the bullseye
+    center_row: usize,
+    center_col: usize,
+    /// Arrow length including fletching and tip
+    arrow_len: usize,
+}
+
+impl Target<'_> {
+    fn render(
+        &self,
+        percent: f32,
+        animation_frame: u8,
+        is_break: bool,
+        is_flowtime_work: bool,
+    ) -> Vec<String> {
+        let (center_row, center_col) = (self.center_row, self.center_col);
+        let mut grid: Vec<Vec<char>> = self.art.iter().map(|row| row.chars().collect()).collect();
+        let width = grid[0].len();
+        let frame = animation_frame % 2;
+        let mut caption = " ".repeat(width);
+
+        if is_flowtime_work {
+            // Flowtime work mode: no arrow, the bullseye pulses
+            grid[center_row][center_col] = if frame == 0 { 'o' } else { '@' };
+            caption = format!(
+                "{}∞{}",
+                " ".repeat(center_col),
+                " ".repeat(width - center_col - 1)
+            );
+        } else {
+            // During break, the arrow is pulled back out (100→0)
+            let effective_progress = if is_break { 100.0 - percent } else { percent };
+            let tip = ((effective_progress / 100.0) * center_col as f32).round() as usize;
+            let hit = tip >= center_col;
+            let tip = tip.min(center_col - 1);
+
+            // Fletching flutters while the arrow is in flight
+            let fletching = if is_break || frame == 0 { '>' } else { '}' };
+            for offset in 0..self.arrow_len {
+                let Some(col) = tip.checked_sub(offset) else {
+                    break;
+                };
+                grid[center_row][col] = match offset {
+                    0 => '>',
+                    _ if offset >= self.arrow_len - 2 => fletching,
+                    _ => '-',
+                };
+            }
+
+            if hit {
+                grid[center_row][center_col] = '*';
+            } else if is_break && percent > 0.0 && percent < 100.0 {
+                grid[center_row][center_col] = if frame == 0 { 'o' } else { 'O' };
+            }
+        }
+
+        let mut lines: Vec<String> = grid
+            .into_iter()
+            .map(|row| row.into_iter().collect())
+            .collect();
+        lines.push(caption);
+        lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_target_line_count() {
+        for percent in [0.0, 50.0, 100.0] {
+            let lines = render_target(percent, 0);
+            assert_eq!(lines.len(), 8);
+            let width = lines[0].chars().count();
+            assert!(lines.iter().all(|l| l.chars().count() == width));
+        }
+    }
+
+    #[test]
+    fn test_target_arrow_moves_in() {
+        let start = render_target(10.0, 0);
+        let middle = render_target(60.0, 0);
+        let tip = |lines: &[String]| lines[CENTER_ROW].chars().position(|c| c == '>');
+        assert!(tip(&middle) > tip(&start));
+    }
+
+    #[test]
+    fn test_target_bullseye_hit() {
+        let lines = render_target(100.0, 0);
+        assert_eq!(lines[CENTER_ROW].chars().nth(CENTER_COL), Some('*'));
+    }
+
+    #[test]
+    fn test_target_break_pulls_arrow_out() {
+        let start = render_target_with_direction(0.0, 0, true);
+        assert_eq!(start[CENTER_ROW].chars().nth(CENTER_COL), Some('*'));
+        let middle = render_target_with_direction(50.0, 0, true);
+        let frame1 = render_target_with_direction(50.0, 1, true);
+        assert_ne!(middle[CENTER_ROW], frame1[CENTER_ROW]);
+    }
+
+    #[test]
+    fn test_target_flowtime() {
+        let lines = render_target_with_options(0.0, 0, false, true);
+        assert!(lines[7].contains('∞'));
+        assert!(!lines[CENTER_ROW].contains('>'));
+    }
+
+    #[test]
+    fn test_target_smaller_sizes() {
+        let drawn = render_target_medium(0.0, 0, false, false);
+        assert_eq!(drawn.len(), 6);
+        assert!(drawn[2].starts_with('>'));
+        let hit = render_target_medium(100.0, 0, false, false);
+        assert_eq!(hit[2].chars().nth(6), Some('*'));
+
+        let small = render_target_small(100.0, 0, false, false);
+        assert_eq!(small[1], "  >>>* ) ");
+        assert!(render_target_small(0.0, 0, false, true)[3].contains('∞'));
+    }
+}
@@ -161,32 +161,13 @@ pub fn render_tomato_with_options(
     ];
 
     // Stem + tomatoes (work mode: slide left/right together)
-    let stem_and_tomatoes: Vec<String> = if is_working {
-        let mut result = vec!["~~~~═════════════════════~~~~".to_string()];
-        if work_frame == 0 {
-            // Slide left (shift 1 char left)
-            for line in &base_tomatoes {
-                let chars: Vec<char> = line.chars().collect();
-                let shifted: String = chars.iter().skip(1).collect::<String>() + " ";
-                result.push(shifted);
-            }
-        } else {
-            // Slide right (shift 1 char right)
-            for line in &base_tomatoes {
-                let chars: Vec<char> = line.chars().collect();
-                let len = chars.len();
-                let shifted: String =
-                    " ".to_string() + &chars[..len.saturating_sub(1)].iter().collect::<String>();
-                result.push(shifted);
-            }
-        }
-        result
+    let mut stem_and_tomatoes = vec!["~~~~═════════════════════~~~~".to_string()];
+    if is_working {
+        stem_and_tomatoes.extend(sway(&base_tomatoes, work_frame));
     } else {
         // Static position
-        let mut result = vec!["~~~~═════════════════════~~~~".to_string()];
-        result.extend(base_tomatoes);
-        result
-    };
+        stem_and_tomatoes.extend(base_tomatoes);
+    }
 
     // Sun animation during break regeneration
     if is_growing {
@@ -219,6 +200,131 @@ pub fn render_tomato_with_options(
     }
 }
 
+/// Medium tomato: a single tomato under the vine
+pub fn render_tomato_medium(
+    percent: f32,
+    animation_frame: u8,
+    is_break: bool,
+    is_flowtime_work: bool,
+) -> Vec<String> {
+    const ROWS: usize = 4;
+    let frame = animation_frame % 2;
+    let (level, is_growing, is_working) = vine_state(percent, is_break, ROWS);
+    let fill = |row: usize, width: usize| -> String {
+        let ch = if is_flowtime_work {
+            if frame == 0 {
+                "▓"
+            } else {
+                "░"
+            }
+        } else if level > ROWS - 1 - row {
+            "▓"
+        } else {
+            " "
+        };
+        ch.repeat(width)
+    };
+    let tomato = vec![
+        "    \\|/     ".to_string(),
+        format!("  /:{}:\\  ", fill(0, 4)),
+        format!(" |:{}:| ", fill(1, 6)),
+        format!(" |:{}:| ", fill(2, 6)),
+        format!("  \\:{}:/  ", fill(3, 4)),
+    ];
+
+    let mut lines = Vec::new();
+    if is_growing {
+        let sun = if frame == 0 {
+            "  -( )-  "
+        } else {
+            "  \\( )/  "
+        };
+        lines.push(format!("{:<12}", sun));
+    }
+    if is_flowtime_work {
+        let vine = if frame == 0 {
+            "~~∞ FLOW ∞~~"
+        } else {
+            "~~═ FLOW ═~~"
+        };
+        lines.push(vine.to_string());
+    } else {
+        lines.push("~~════════~~".to_string());
+    }
+    if is_working {
+        lines.extend(sway(&tomato, frame));
+    } else {
+        lines.extend(tomato);
+    }
+    lines
+}
+
+/// Small tomato: one row of fill between the calyx and the base
+pub fn render_tomato_small(
+    percent: f32,
+    animation_frame: u8,
+    is_break: bool,
+    is_flowtime_work: bool,
+) -> Vec<String> {
+    const WIDTH: usize = 4;
+    let frame = animation_frame % 2;
+    let (level, is_growing, is_working) = vine_state(percent, is_break, WIDTH);
+    let fill = if is_flowtime_work {
+        (if frame == 0 { "▓" } else { "░" }).repeat(WIDTH)
+    } else {
+        format!("{}{}", "▓".repeat(level), " ".repeat(WIDTH - level))
+    };
+    let top = if is_flowtime_work {
+        "  ∞   "
+    } else if is_growing && frame == 1 {
+        "  *   "
+    } else {
+        " \\|/  "
+    };
+    let tomato = vec![
+        format!(" {} ", top),
+        format!(" ({}) ", fill),
+        "  \\__/  ".to_string(),
+    ];
+    if is_working {
+        sway(&tomato, frame)
+    } else {
+        tomato
+    }
+}
+
+/// Fill level (0..=`max_level`), whether a break is regrowing the tomato and
+/// whether work is under way
+fn vine_state(percent: f32, is_break: bool, max_level: usize) -> (usize, bool, bool) {
+    // During break, progress shows recovery (0→100 means empty→full)
+    let effective_progress = if is_break { 100.0 - percent } else { percent };
+    let level = ((100.0 - effective_progress) / 100.0 * max_level as f32).round() as usize;
+    let running = percent > 0.0 && percent < 100.0;
+    (
+        level.min(max_level),
+        is_break && running,
+        !is_break && running,
+    )
+}
+
+/// Shift every line one column left on frame 0 and right on frame 1
+fn sway(lines: &[String], frame: u8) -> Vec<String> {
+    lines
+        .iter()
+        .map(|line| {
+            let chars: Vec<char> = line.chars().collect();
+            if frame == 0 {
+                // Slide left (shift 1 char left)
+                chars.iter().skip(1).collect::<String>() + " "
+            } else {
+                // Slide right (shift 1 char right)
+                let len = chars.len();
+                " ".to_string() + &chars[..len.saturating_sub(1)].iter().collect::<String>()
+            }
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -311,4 +417,21 @@ mod tests {
         // Should have fill gradient characters
         assert!(all_lines.contains("▓"));
     }
+
+    #[test]
+    fn test_tomato_smaller_sizes() {
+        let full = render_tomato_medium(100.0, 0, false, false);
+        assert_eq!(full.len(), 6);
+        let empty = render_tomato_medium(0.0, 0, false, false);
+        assert!(empty[3].contains('▓'));
+        assert!(!full[3].contains('▓'));
+        // The sun comes out while a break regrows the tomato
+        assert_eq!(render_tomato_medium(50.0, 0, true, false).len(), 7);
+
+        assert_eq!(render_tomato_small(0.0, 0, false, false)[1], " (▓▓▓▓) ");
+        assert_eq!(
+            render_tomato_small(50.0, 0, true, false)[1].trim(),
+            "(▓▓  )"
+        );
+    }
 }
@@ -0,0 +1,188 @@
+//! Ocean wave ASCII art animation
+//!
+//! Work mode: the tide goes out as the session goes on
+//! Break mode: the tide comes back in, with rolling 4-phase waves on top
+
+const WIDTH: usize = 20;
+const SURFACE: &str = "^~~~";
+const SEABED: &str = "▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔▔";
+const SHORE: &str = "   .    o     .  ,  ";
+
+/// Render wave at given percentage (0 = high tide, 100 = low tide)
+pub fn render_wave(percent: f32, animation_frame: u8) -> Vec<String> {
+    render_wave_with_direction(percent, animation_frame, false)
+}
+
+/// Render wave with flow direction control
+pub fn render_wave_with_direction(
+    percent: f32,
+    animation_frame: u8,
+    is_break: bool,
+) -> Vec<String> {
+    render_wave_with_options(percent, animation_frame, is_break, false)
+}
+
+/// Render wave with all options including flowtime support
+pub fn render_wave_with_options(
+    percent: f32,
+    animation_frame: u8,
+    is_break: bool,
+    is_flowtime_work: bool,
+) -> Vec<String> {
+    tide(
+        percent,
+        animation_frame,
+        is_break,
+        is_flowtime_work,
+        WIDTH,
+        8,
+    )
+}
+
+/// Medium wave: four rows of water
+pub fn render_wave_medium(
+    percent: f32,
+    animation_frame: u8,
+    is_break: bool,
+    is_flowtime_work: bool,
+) -> Vec<String> {
+    tide(percent, animation_frame, is_break, is_flowtime_work, 12, 4)
+}
+
+/// Small wave: two rows of water
+pub fn render_wave_small(
+    percent: f32,
+    animation_frame: u8,
+    is_break: bool,
+    is_flowtime_work: bool,
+) -> Vec<String> {
+    tide(percent, animation_frame, is_break, is_flowtime_work, 8, 2)
+}
+
+/// `rows` rows of water, `width` (at most [`WIDTH`]) columns wide, between
+/// the sky and the seabed
+fn tide(
+    percent: f32,
+    animation_frame: u8,
+    is_break: bool,
+    is_flowtime_work: bool,
+    width: usize,
+    rows: usize,
+) -> Vec<String> {
+    let frame = (animation_frame % 4) as usize;
+
+    // Flowtime work mode keeps the tide in
+    let level = if is_flowtime_work {
+        rows
+    } else {
+        // During break, progress shows the tide coming in (0→100)
+        let effective_progress = if is_break { 100.0 - percent } else { percent };
+        ((100.0 - effective_progress) / 100.0 * rows as f32).round() as usize
+    };
+
+    // Waves roll to the right while the tide goes out and to the left
+    // while it comes in
+    let shift = if is_break { 4 - frame } else { frame };
+    let surface: String = SURFACE
+        .chars()
+        .cycle()
+        .skip(shift % 4)
+        .take(width)
+        .collect();
+
+    let sky = if is_flowtime_work {
+        format!(
+            "{}∞{}",
+            " ".repeat(width / 2),
+            " ".repeat(width - width / 2 - 1)
+        )
+    } else {
+        " ".repeat(width)
+    };
+    let mut lines = vec![sky];
+    for row in 0..rows {
+        let depth = (row + level).checked_sub(rows);
+        let line = match depth {
+            None if row == rows - 1 => SHORE.chars().take(width).collect(),
+            None => " ".repeat(width),
+            Some(0) => surface.clone(),
+            Some(1) => "░".repeat(width),
+            Some(2) => "▒".repeat(width),
+            Some(_) => "▓".repeat(width),
+        };
+        lines.push(line);
+    }
+    lines.push(SEABED.chars().take(width).collect());
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn surface_row(lines: &[String]) -> Option<usize> {
+        lines.iter().position(|l| l.contains('^'))
+    }
+
+    #[test]
+    fn test_wave_line_count() {
+        for percent in [0.0, 50.0, 100.0] {
+            let lines = render_wave(percent, 0);
+            assert_eq!(lines.len(), 10);
+            assert!(lines.iter().all(|l| l.chars().count() == WIDTH));
+        }
+    }
+
+    #[test]
+    fn test_wave_tide_goes_out() {
+        assert_eq!(surface_row(&render_wave(0.0, 0)), Some(1));
+        assert_eq!(surface_row(&render_wave(50.0, 0)), Some(5));
+        let low = render_wave(100.0, 0);
+        assert_eq!(surface_row(&low), None);
+        assert_eq!(low[8], SHORE);
+    }
+
+    #[test]
+    fn test_wave_tide_comes_in_during_break() {
+        assert_eq!(surface_row(&render_wave_with_direction(0.0, 0, true)), None);
+        assert_eq!(
+            surface_row(&render_wave_with_direction(100.0, 0, true)),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn test_wave_rolls() {
+        let frames: Vec<_> = (0..4).map(|f| render_wave(20.0, f)).collect();
+        for pair in frames.windows(2) {
+            assert_ne!(pair[0], pair[1]);
+        }
+    }
+
+    #[test]
+    fn test_wave_flowtime() {
+        let lines = render_wave_with_options(90.0, 0, false, true);
+        assert!(lines[0].contains('∞'));
+        assert_eq!(surface_row(&lines), Some(1));
+    }
+
+    #[test]
+    fn test_wave_smaller_sizes() {
+        let high = render_wave_medium(0.0, 0, false, false);
+        assert_eq!(high.len(), 6);
+        assert!(high.iter().all(|l| l.chars().count() == 12));
+        assert_eq!(surface_row(&high), Some(1));
+        assert_eq!(
+            surface_row(&render_wave_medium(50.0, 0, false, false)),
+            Some(3)
+        );
+
+        let small = render_wave_small(100.0, 0, false, false);
+        assert_eq!(small.len(), 4);
+        assert_eq!(surface_row(&small), None);
+        assert_eq!(
+            surface_row(&render_wave_small(100.0, 0, true, false)),
+            Some(1)
+        );
+    }
+}
@@ -0,0 +1,386 @@
+//! Importing history from other trackers
+//!
+//! `sandoro import` reads a CSV export from Toggl, Clockify or any tool that
+//! can write start and end times (ActivityWatch, spreadsheets, sandoro's own
+//! `stats --export csv`) and adds each row as a finished work session. The
+//! project, or the tag column, becomes the session's tag after `--map`
+//! rules; the description becomes its note.
+
+use anyhow::{bail, Context, Result};
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+
+use crate::db::ImportedSession;
+
+/// `--format` of `sandoro import`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+    /// Toggl Track detailed report (Start date, Start time, End date, ...)
+    Toggl,
+    /// Clockify detailed report (Start Date, Start Time, End Date, ...)
+    Clockify,
+    /// Columns start and end (or duration in seconds), optional tag and notes
+    GenericCsv,
+}
+
+impl Format {
+    pub fn name(self) -> &'static str {
+        match self {
+            Format::Toggl => "Toggl",
+            Format::Clockify => "Clockify",
+            Format::GenericCsv => "CSV",
+        }
+    }
+}
+
+/// Dates in tracker exports: ISO first, then Clockify's US default
+const DATE_FORMATS: [&str; 4] = ["%Y-%m-%d", "%m/%d/%Y", "%d.%m.%Y", "%Y/%m/%d"];
+const TIME_FORMATS: [&str; 4] = ["%H:%M:%S", "%H:%M", "%I:%M:%S %p", "%I:%M %p"];
+
+/// `--map PROJECT=TAG`: entries from PROJECT get TAG (no tag when empty).
+/// PROJECT `*` catches every project without a rule of its own.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagRule {
+    pub project: String,
+    pub tag: String,
+}
+
+pub fn parse_rule(value: &str) -> Result<TagRule, String> {
+    match value.split_once('=') {
+        Some((project, tag)) if !project.trim().is_empty() => Ok(TagRule {
+            project: project.trim().to_string(),
+            tag: tag.trim().to_string(),
+        }),
+        _ => Err("use PROJECT=TAG, e.g. \"Client A=client-a\"".to_string()),
+    }
+}
+
+/// The tag for an entry's project: its rule, else the `*` rule, else the
+/// project name itself. Projects match case-insensitively.
+pub fn map_tag(rules: &[TagRule], project: &str) -> Option<String> {
+    let project = project.trim();
+    let rule = rules
+        .iter()
+        .find(|r| r.project.eq_ignore_ascii_case(project))
+        .or_else(|| rules.iter().find(|r| r.project == "*"));
+    let tag = match rule {
+        Some(rule) => rule.tag.as_str(),
+        None => project,
+    };
+    (!tag.is_empty()).then(|| tag.to_string())
+}
+
+/// What came out of a file: the sessions to add and the rows left out
+#[derive(Debug, Clone, Default)]
+pub struct Parsed {
+    pub sessions: Vec<ImportedSession>,
+    /// (line, reason)
+    pub rejected: Vec<(u64, String)>,
+}
+
+pub fn parse(text: &str, format: Format, rules: &[TagRule]) -> Result<Parsed> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .from_reader(text.as_bytes());
+    let headers: Vec<String> = reader
+        .headers()
+        .context("Couldn't read the CSV header")?
+        .iter()
+        .map(|h| h.trim().to_lowercase())
+        .collect();
+    let column = |names: &[&str]| {
+        names
+            .iter()
+            .find_map(|name| headers.iter().position(|h| h == name))
+    };
+    let columns = match format {
+        Format::Toggl | Format::Clockify => Columns {
+            start: Start::Split(
+                column(&["start date"]).context("No \"Start date\" column")?,
+                column(&["start time"]).context("No \"Start time\" column")?,
+            ),
+            end: End::Split(
+                column(&["end date"]).context("No \"End date\" column")?,
+                column(&["end time"]).context("No \"End time\" column")?,
+            ),
+            tag: column(&["project"]),
+            notes: column(&["description"]),
+            kind: None,
+            completed: None,
+        },
+        Format::GenericCsv => Columns {
+            start: Start::Timestamp(
+                column(&["start", "started_at", "startedat", "timestamp"])
+                    .context("No \"start\" column")?,
+            ),
+            end: match column(&["end", "ended_at", "endedat"]) {
+                Some(end) => End::Timestamp(end),
+                None => End::Seconds(
+                    column(&["duration", "duration_seconds", "durationseconds"])
+                        .context("No \"end\" or \"duration\" column")?,
+                ),
+            },
+            tag: column(&["tag", "project"]),
+            notes: column(&["notes", "note", "description", "title"]),
+            kind: column(&["type"]),
+            completed: column(&["completed"]),
+        },
+    };
+
+    let mut parsed = Parsed::default();
+    for record in reader.records() {
+        let record = record?;
+        let line = record.position().map(|p| p.line()).unwrap_or(0);
+        let field = |i: Option<usize>| {
+            i.and_then(|i| record.get(i))
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+        };
+        if record.iter().all(|f| f.trim().is_empty()) {
+            continue;
+        }
+        // sandoro's own export has breaks too
+        if let Some(kind) = field(columns.kind) {
+            if kind != "work" {
+                continue;
+            }
+        }
+        let completed = match field(columns.completed).map(str::to_lowercase).as_deref() {
+            None | Some("true" | "1" | "yes") => Ok(true),
+            Some("false" | "0" | "no") => Ok(false),
+            Some(other) => Err(anyhow::anyhow!("completed '{}' isn't true or false", other)),
+        };
+        match completed.and_then(|completed| Ok((columns.read(&field)?, completed))) {
+            Ok(((started_at, ended_at), completed)) => parsed.sessions.push(ImportedSession {
+                started_at,
+                ended_at,
+                utc_offset_minutes: Local
+                    .from_utc_datetime(&started_at.naive_utc())
+                    .offset()
+                    .local_minus_utc()
+                    / 60,
+                tag: field(columns.tag).and_then(|project| map_tag(rules, project)),
+                notes: field(columns.notes).map(str::to_string),
+                completed,
+            }),
+            Err(e) => parsed.rejected.push((line, e.to_string())),
+        }
+    }
+    Ok(parsed)
+}
+
+enum Start {
+    Split(usize, usize),
+    Timestamp(usize),
+}
+
+enum End {
+    Split(usize, usize),
+    Timestamp(usize),
+    Seconds(usize),
+}
+
+struct Columns {
+    start: Start,
+    end: End,
+    tag: Option<usize>,
+    notes: Option<usize>,
+    kind: Option<usize>,
+    completed: Option<usize>,
+}
+
+impl Columns {
+    fn read<'a>(
+        &self,
+        field: &impl Fn(Option<usize>) -> Option<&'a str>,
+    ) -> Result<(DateTime<Utc>, DateTime<Utc>)> {
+        let get = |i: usize, what: &str| field(Some(i)).with_context(|| format!("no {}", what));
+        let started_at = match self.start {
+            Start::Split(date, time) => {
+                local_split(get(date, "start date")?, get(time, "start time")?)?
+            }
+            Start::Timestamp(i) => timestamp(get(i, "start")?)?,
+        };
+        let ended_at = match self.end {
+            End::Split(date, time) => local_split(get(date, "end date")?, get(time, "end time")?)?,
+            End::Timestamp(i) => timestamp(get(i, "end")?)?,
+            End::Seconds(i) => {
+                let text = get(i, "duration")?;
+                let seconds: f64 = text
+                    .parse()
+                    .with_context(|| format!("duration '{}' isn't seconds", text))?;
+                started_at + chrono::Duration::milliseconds((seconds * 1000.0) as i64)
+            }
+        };
+        if ended_at <= started_at {
+            bail!("ends before it starts");
+        }
+        Ok((started_at, ended_at))
+    }
+}
+
+fn local(naive: NaiveDateTime) -> Result<DateTime<Utc>> {
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .map(|t| t.with_timezone(&Utc))
+        .with_context(|| format!("{} doesn't exist in local time", naive))
+}
+
+fn local_split(date: &str, time: &str) -> Result<DateTime<Utc>> {
+    let date = DATE_FORMATS
+        .iter()
+        .find_map(|f| NaiveDate::parse_from_str(date, f).ok())
+        .with_context(|| format!("date '{}' not understood", date))?;
+    let time = TIME_FORMATS
+        .iter()
+        .find_map(|f| NaiveTime::parse_from_str(time, f).ok())
+        .with_context(|| format!("time '{}' not understood", time))?;
+    local(date.and_time(time))
+}
+
+/// RFC 3339, or a local "YYYY-MM-DD HH:MM[:SS]"
+fn timestamp(text: &str) -> Result<DateTime<Utc>> {
+    if let Ok(t) = DateTime::parse_from_rfc3339(text) {
+        return Ok(t.with_timezone(&Utc));
+    }
+    ["%Y-%m-%d %H:%M:%S", "%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M:%S"]
+        .iter()
+        .find_map(|f| NaiveDateTime::parse_from_str(text, f).ok())
+        .with_context(|| format!("time '{}' not understood", text))
+        .and_then(local)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(text: &str) -> DateTime<Utc> {
+        local(NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M").unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_toggl_rows_become_sessions() {
+        let csv = "\u{feff}User,Email,Client,Project,Task,Description,Billable,Start date,Start time,End date,End time,Duration,Tags\n\
+                   Kei,k@example.com,,Writing,,Chapter 3,No,2024-03-04,09:00:00,2024-03-04,09:25:00,00:25:00,\n\
+                   Kei,k@example.com,,,,,No,2024-03-04,23:50:00,2024-03-05,00:20:00,00:30:00,\n";
+        let parsed = parse(csv, Format::Toggl, &[]).unwrap();
+        assert!(parsed.rejected.is_empty());
+        let first = &parsed.sessions[0];
+        assert_eq!(first.started_at, at("2024-03-04 09:00"));
+        assert_eq!(first.ended_at, at("2024-03-04 09:25"));
+        assert_eq!(first.tag.as_deref(), Some("Writing"));
+        assert_eq!(first.notes.as_deref(), Some("Chapter 3"));
+        // Past midnight, without a project
+        let second = &parsed.sessions[1];
+        assert_eq!(
+            second.ended_at - second.started_at,
+            chrono::Duration::minutes(30)
+        );
+        assert_eq!(second.tag, None);
+    }
+
+    #[test]
+    fn test_clockify_dates_and_twelve_hour_times() {
+        let csv = "Project,Client,Description,Task,User,Start Date,Start Time,End Date,End Time\n\
+                   Ops,,On call,,Kei,03/04/2024,01:30:00 PM,03/04/2024,02:15:00 PM\n\
+                   Ops,,Broken,,Kei,03/04/2024,02:15:00 PM,03/04/2024,01:00:00 PM\n";
+        let parsed = parse(csv, Format::Clockify, &[]).unwrap();
+        assert_eq!(parsed.sessions.len(), 1);
+        assert_eq!(parsed.sessions[0].started_at, at("2024-03-04 13:30"));
+        assert_eq!(
+            parsed.rejected,
+            vec![(3, "ends before it starts".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_generic_csv_takes_durations_and_skips_breaks() {
+        let csv = "timestamp,duration,title\n\
+                   2024-03-04T09:00:00Z,1500.5,Editor\n";
+        let parsed = parse(csv, Format::GenericCsv, &[]).unwrap();
+        let s = &parsed.sessions[0];
+        assert_eq!(
+            s.ended_at - s.started_at,
+            chrono::Duration::milliseconds(1_500_500)
+        );
+        assert_eq!(s.notes.as_deref(), Some("Editor"));
+
+        let csv = "id,startedAt,endedAt,durationSeconds,type,completed,tag,note\n\
+                   1,2024-03-04T09:00:00+00:00,2024-03-04T09:25:00+00:00,1500,work,true,code,\n\
+                   2,2024-03-04T09:25:00+00:00,2024-03-04T09:30:00+00:00,300,short_break,true,,\n";
+        let parsed = parse(csv, Format::GenericCsv, &[]).unwrap();
+        assert_eq!(parsed.sessions.len(), 1);
+        assert_eq!(parsed.sessions[0].tag.as_deref(), Some("code"));
+
+        assert!(parse("when,what\n", Format::GenericCsv, &[]).is_err());
+    }
+
+    #[test]
+    fn test_own_export_round_trips() {
+        use crate::db::{CsvOptions, Database, ExportFilter, SessionType};
+
+        let db = Database::open_in_memory().unwrap();
+        let done = db.start_session(SessionType::Work).unwrap();
+        db.complete_session(done, 1500).unwrap();
+        let stopped = db.start_session(SessionType::Work).unwrap();
+        db.interrupt_session(stopped, Utc::now(), 600).unwrap();
+        // Apart, so they don't start at the same moment
+        db.connection()
+            .execute(
+                "UPDATE sessions SET started_at = '2024-03-04T09:00:00+00:00',
+                                 ended_at = '2024-03-04T09:25:00+00:00'
+             WHERE id = ?1",
+                [done],
+            )
+            .unwrap();
+        db.connection()
+            .execute(
+                "UPDATE sessions SET started_at = '2024-03-04T10:00:00+00:00',
+                                 ended_at = '2024-03-04T10:10:00+00:00'
+             WHERE id = ?1",
+                [stopped],
+            )
+            .unwrap();
+        let csv = db
+            .export_to_csv(&ExportFilter::default(), &CsvOptions::default(), "here")
+            .unwrap();
+
+        let parsed = parse(&csv, Format::GenericCsv, &[]).unwrap();
+        assert!(parsed.rejected.is_empty());
+        let other = Database::open_in_memory().unwrap();
+        assert_eq!(
+            other
+                .import_sessions(&parsed.sessions, "CSV", false)
+                .unwrap(),
+            2
+        );
+        let mut completed: Vec<(String, bool)> = other
+            .get_all_sessions()
+            .unwrap()
+            .into_iter()
+            .map(|s| (s.started_at.to_rfc3339(), s.completed))
+            .collect();
+        completed.sort();
+        assert_eq!(
+            completed,
+            [
+                ("2024-03-04T09:00:00+00:00".to_string(), true),
+                ("2024-03-04T10:00:00+00:00".to_string(), false)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rules_map_projects_to_tags() {
+        let rules: Vec<TagRule> = ["client a=client-a", "Admin=", "*=other"]
+            .iter()
+            .map(|r| parse_rule(r).unwrap())
+            .collect();
+        assert_eq!(map_tag(&rules, "Client A").as_deref(), Some("client-a"));
+        assert_eq!(map_tag(&rules, "Admin"), None);
+        assert_eq!(map_tag(&rules, "Misc").as_deref(), Some("other"));
+        assert_eq!(map_tag(&[], "Misc").as_deref(), Some("Misc"));
+        assert!(parse_rule("no-equals").is_err());
+    }
+}
@@ -0,0 +1,270 @@
+//! Work-length suggestions from recent completion rates
+//!
+//! Looks at how often work sessions of each planned length were finished
+//! rather than skipped, reset or interrupted, and suggests a length that is
+//! likely to be finished.
+
+use anyhow::Result;
+
+use crate::config::Config;
+use crate::db::Database;
+use crate::i18n;
+use crate::messages::Language;
+
+/// Days of history the suggestion is based on
+pub const WINDOW_DAYS: i32 = 14;
+/// Attempts needed before a length's completion rate is trusted
+pub const MIN_ATTEMPTS: i32 = 5;
+/// Completion rate a suggested length should reach
+const TARGET_RATE: f64 = 0.75;
+/// A length finished this reliably can grow
+const EASY_RATE: f64 = 0.9;
+const STEP_MINUTES: u32 = 5;
+const MIN_MINUTES: u32 = 10;
+const MAX_MINUTES: u32 = 90;
+/// Metadata key holding the local date the last tip was shown
+const TIP_SHOWN_KEY: &str = "work_length_tip_on";
+
+/// Attempts at one planned work length
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LengthStats {
+    pub minutes: u32,
+    pub attempts: i32,
+    pub completed: i32,
+}
+
+impl LengthStats {
+    pub fn rate(&self) -> f64 {
+        if self.attempts == 0 {
+            0.0
+        } else {
+            self.completed as f64 / self.attempts as f64
+        }
+    }
+
+    fn percent(&self) -> u32 {
+        (self.rate() * 100.0).round() as u32
+    }
+
+    fn trusted(&self) -> bool {
+        self.attempts >= MIN_ATTEMPTS
+    }
+}
+
+/// A suggested work length and the evidence for it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Suggestion {
+    pub minutes: u32,
+    /// Length that is usually finished
+    pub easy: Option<LengthStats>,
+    /// Length that is often abandoned
+    pub hard: Option<LengthStats>,
+}
+
+impl Suggestion {
+    pub fn message(&self, lang: Language) -> String {
+        let lang = lang.code();
+        match (self.easy, self.hard) {
+            (Some(easy), Some(hard)) => i18n::fill_in(
+                lang,
+                "You finish {0}% of {1}m sessions but only {2}% of {3}m — try {4}m",
+                &[
+                    &easy.percent(),
+                    &easy.minutes,
+                    &hard.percent(),
+                    &hard.minutes,
+                    &self.minutes,
+                ],
+            ),
+            (Some(easy), None) => i18n::fill_in(
+                lang,
+                "You finish {0}% of {1}m sessions — try {2}m",
+                &[&easy.percent(), &easy.minutes, &self.minutes],
+            ),
+            (None, Some(hard)) => i18n::fill_in(
+                lang,
+                "You finish only {0}% of {1}m sessions — try {2}m",
+                &[&hard.percent(), &hard.minutes, &self.minutes],
+            ),
+            (None, None) => i18n::fill_in(lang, "Try {0}m sessions", &[&self.minutes]),
+        }
+    }
+}
+
+fn round_to_step(minutes: f64) -> u32 {
+    let steps = (minutes / STEP_MINUTES as f64).round() as u32;
+    (steps * STEP_MINUTES).clamp(MIN_MINUTES, MAX_MINUTES)
+}
+
+/// Suggest a work length, or None when the data doesn't point away from
+/// `current`
+pub fn suggest(lengths: &[LengthStats], current: u32) -> Option<Suggestion> {
+    let mut trusted: Vec<LengthStats> = lengths.iter().copied().filter(|l| l.trusted()).collect();
+    trusted.sort_by_key(|l| l.minutes);
+
+    let easy = trusted
+        .iter()
+        .rev()
+        .find(|l| l.rate() >= TARGET_RATE)
+        .copied();
+    let hard = trusted
+        .iter()
+        .find(|l| l.rate() < TARGET_RATE && easy.is_none_or(|e| l.minutes > e.minutes))
+        .copied();
+
+    let minutes = match (easy, hard) {
+        // Estimate where the completion rate drops below the target
+        (Some(easy), Some(hard)) => {
+            let span = (hard.minutes - easy.minutes) as f64;
+            let drop = (easy.rate() - TARGET_RATE) / (easy.rate() - hard.rate());
+            round_to_step(easy.minutes as f64 + span * drop).clamp(
+                easy.minutes,
+                hard.minutes.saturating_sub(STEP_MINUTES).max(easy.minutes),
+            )
+        }
+        (Some(easy), None) if easy.rate() >= EASY_RATE && easy.minutes >= current => {
+            round_to_step((easy.minutes + STEP_MINUTES) as f64)
+        }
+        (None, Some(hard)) if hard.minutes <= current => {
+            let scaled = hard.minutes as f64 * hard.rate() / TARGET_RATE;
+            round_to_step(scaled).min(hard.minutes.saturating_sub(STEP_MINUTES).max(MIN_MINUTES))
+        }
+        _ => return None,
+    };
+
+    (minutes != current).then_some(Suggestion {
+        minutes,
+        easy,
+        hard,
+    })
+}
+
+/// Completion stats per work length over the last `WINDOW_DAYS`
+pub fn load(db: &Database) -> Result<Vec<LengthStats>> {
+    Ok(db
+        .get_work_length_attempts(WINDOW_DAYS)?
+        .into_iter()
+        .map(|(minutes, attempts, completed)| LengthStats {
+            minutes,
+            attempts,
+            completed,
+        })
+        .collect())
+}
+
+/// The work-length tip for the timer screen, at most once a day and only
+/// with `[timer] suggest_work_length` on
+pub fn daily_tip(db: &Database, config: &Config) -> Result<Option<String>> {
+    if !config.timer.suggest_work_length {
+        return Ok(None);
+    }
+    let today = db.today_str()?;
+    if db.get_metadata(TIP_SHOWN_KEY)?.as_deref() == Some(today.as_str()) {
+        return Ok(None);
+    }
+    let Some(suggestion) = suggest(&load(db)?, config.timer.work_duration) else {
+        return Ok(None);
+    };
+    db.set_metadata(TIP_SHOWN_KEY, &today)?;
+    let lang = Language::from_str(&config.appearance.language);
+    Ok(Some(suggestion.message(lang)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::SessionType;
+
+    fn length(minutes: u32, attempts: i32, completed: i32) -> LengthStats {
+        LengthStats {
+            minutes,
+            attempts,
+            completed,
+        }
+    }
+
+    #[test]
+    fn test_suggest_between_easy_and_hard() {
+        let lengths = [length(25, 20, 19), length(50, 5, 2)];
+        let suggestion = suggest(&lengths, 50).unwrap();
+        assert_eq!(suggestion.minutes, 35);
+        assert_eq!(
+            suggestion.message(Language::English),
+            "You finish 95% of 25m sessions but only 40% of 50m — try 35m"
+        );
+    }
+
+    #[test]
+    fn test_suggest_longer_when_easy() {
+        let suggestion = suggest(&[length(25, 10, 10)], 25).unwrap();
+        assert_eq!(suggestion.minutes, 30);
+        assert_eq!(
+            suggestion.message(Language::English),
+            "You finish 100% of 25m sessions — try 30m"
+        );
+        // Already working longer than the easy length
+        assert_eq!(suggest(&[length(25, 10, 10)], 40), None);
+    }
+
+    #[test]
+    fn test_suggest_shorter_when_hard() {
+        let suggestion = suggest(&[length(50, 10, 4)], 50).unwrap();
+        assert_eq!(suggestion.minutes, 25);
+        assert_eq!(
+            suggestion.message(Language::English),
+            "You finish only 40% of 50m sessions — try 25m"
+        );
+        assert_eq!(suggest(&[length(15, 10, 1)], 15).unwrap().minutes, 10);
+    }
+
+    #[test]
+    fn test_no_suggestion_without_enough_data() {
+        assert_eq!(suggest(&[length(25, 4, 0)], 25), None);
+        assert_eq!(suggest(&[], 25), None);
+        // Finished often enough, but not easily
+        assert_eq!(suggest(&[length(25, 10, 8)], 25), None);
+    }
+
+    #[test]
+    fn test_load_counts_finished_and_abandoned() {
+        let db = Database::open_in_memory().unwrap();
+        let session = |planned: Option<i32>| {
+            db.start_session_with_tag(SessionType::Work, None, planned)
+                .unwrap()
+        };
+        let done = session(Some(1500));
+        db.complete_session(done, 1500).unwrap();
+        let skipped = session(Some(1500));
+        db.log_session_event(skipped, "skipped", None).unwrap();
+        let interrupted = session(Some(3000));
+        db.interrupt_session(interrupted, chrono::Utc::now(), 600)
+            .unwrap();
+        // Still running, and flowtime without a plan: not counted
+        session(Some(1500));
+        let flow = session(None);
+        db.complete_session(flow, 2400).unwrap();
+
+        assert_eq!(load(&db).unwrap(), vec![length(25, 2, 1), length(50, 1, 0)]);
+    }
+
+    #[test]
+    fn test_daily_tip_once_a_day() {
+        let db = Database::open_in_memory().unwrap();
+        for _ in 0..5 {
+            let id = db
+                .start_session_with_tag(SessionType::Work, None, Some(1500))
+                .unwrap();
+            db.complete_session(id, 1500).unwrap();
+        }
+        let mut config = Config::default();
+        config.appearance.language = "en".to_string();
+        assert_eq!(daily_tip(&db, &config).unwrap(), None);
+
+        config.timer.suggest_work_length = true;
+        assert_eq!(
+            daily_tip(&db, &config).unwrap().as_deref(),
+            Some("You finish 100% of 25m sessions — try 30m")
+        );
+        assert_eq!(daily_tip(&db, &config).unwrap(), None);
+    }
+}
@@ -2,24 +2,84 @@
 //!
 //! A pomodoro timer featuring hourglass animations and customizable themes.
 
-use anyhow::Result;
-use clap::{Parser, Subcommand};
+use anyhow::{Context, Result};
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
 
+mod accountability;
+mod ambience;
+mod analytics;
 mod app;
+mod audio;
+mod audit;
 mod auth;
+mod board;
+mod break_activity;
+mod budget;
+mod calendar;
+mod capacity;
+mod clock;
+mod color;
+mod compare;
+mod completion;
 mod config;
+mod config_keys;
+mod control;
+mod daemon;
+mod days;
 mod db;
+mod deeplink;
+mod demo;
+mod escalation;
+mod focus_dnd;
+mod goal_reminder;
+mod goals;
+mod handoff;
+mod hook;
+mod http;
+mod i18n;
 mod icons;
+mod import;
+mod insights;
 mod messages;
 mod notification;
+#[cfg(feature = "postgres")]
+mod pg_storage;
+mod plan;
+mod power;
+mod privacy;
+mod recovery;
+mod report;
+mod retention;
+mod review;
+mod routine;
+mod schema;
+mod serve;
+mod session_hooks;
+mod settings;
+mod share;
+mod stats_json;
+mod status;
+mod status_line;
+mod storage;
+mod summary;
 mod supabase;
+mod svg;
 mod sync;
 
 use config::Config;
 use db::DailyStats;
+use storage::Storage;
+mod tag_bar;
+mod terminal;
 mod theme;
+mod time_tracking;
 mod timer;
+mod tmux;
+#[cfg(test)]
+mod tui_test;
 mod ui;
+mod workspace;
+mod xp;
 
 #[derive(Parser)]
 #[command(name = "sandoro")]
@@ -33,6 +93,34 @@ LICENSE: MIT - (c) 2025 K. Masuda")]
 struct Cli {
     #[command(subcommand)]
     command: Option<Commands>,
+
+    /// Don't pick a tag from the current directory or tmux session, or use
+    /// a project's .sandoro.toml
+    #[arg(long, global = true)]
+    no_workspace: bool,
+
+    /// Tick once per second and skip animations to save battery
+    #[arg(long, global = true)]
+    low_power: bool,
+
+    /// Use an in-memory database and save nothing (demos, screenshots)
+    #[arg(long, global = true)]
+    ephemeral: bool,
+
+    /// Run the clock N times faster, for trying transitions and the day
+    /// rollover (development; implies --ephemeral)
+    #[arg(long, global = true, hide = true, value_name = "N",
+          value_parser = clap::value_parser!(u32).range(1..=3600))]
+    time_scale: Option<u32>,
+
+    /// Show the timer on three lines without borders, e.g. in a small tmux pane
+    #[arg(long, global = true)]
+    compact: bool,
+
+    /// Color stats, heatmaps and errors: auto (terminal only, honoring
+    /// NO_COLOR), always or never
+    #[arg(long, global = true, value_enum, default_value_t = color::ColorChoice::Auto)]
+    color: color::ColorChoice,
 }
 
 #[derive(Subcommand)]
@@ -50,97 +138,510 @@ enum Commands {
         /// Long break duration in minutes (default: 15)
         #[arg(short, long, default_value = "15")]
         long_break: u32,
+
+        /// Take over the work session running on another logged-in device
+        #[arg(long)]
+        resume_remote: bool,
     },
     /// Show statistics
-    Stats {
-        /// Show daily stats
-        #[arg(short, long)]
-        day: bool,
+    Stats(StatsArgs),
+    /// Login to sync data with cloud
+    Login {
+        /// OAuth provider to use
+        #[arg(short, long, default_value = "google")]
+        provider: String,
+    },
+    /// Logout and remove stored credentials
+    Logout,
+    /// Show which account sessions sync to
+    Whoami,
+    /// Sync local data with cloud (see docs/SYNC.md)
+    Sync {
+        /// Show sync status only
+        #[arg(short, long, conflicts_with_all = ["push", "pull", "dry_run"])]
+        status: bool,
 
-        /// Show weekly stats
-        #[arg(short, long)]
-        week: bool,
+        /// Only upload local sessions
+        #[arg(long, conflicts_with = "pull")]
+        push: bool,
 
-        /// Show monthly stats
-        #[arg(short, long)]
-        month: bool,
+        /// Only download cloud sessions
+        #[arg(long)]
+        pull: bool,
 
-        /// Show stats for a specific date (YYYY-MM-DD)
+        /// Show what would be uploaded and downloaded without changing anything
+        #[arg(short = 'n', long)]
+        dry_run: bool,
+    },
+    /// Find work sessions by tag or notes
+    Search {
+        /// Words to look for; all must match, as word prefixes
+        query: String,
+
+        /// Maximum number of sessions to list
+        #[arg(short = 'n', long, default_value = "20")]
+        limit: i32,
+    },
+    /// Suggest a work length from the last two weeks of sessions
+    Insights,
+    /// Write a short end-of-day note, or list recent ones
+    Journal {
+        /// Note to save; without one, recent notes are listed
+        note: Option<String>,
+
+        /// Date the note is for (YYYY-MM-DD, default today)
         #[arg(long)]
         date: Option<String>,
 
-        /// Number of weeks to show in heatmap (default: 12)
-        #[arg(long, default_value = "12")]
+        /// Remove the note for the date
+        #[arg(long, conflicts_with = "note")]
+        delete: bool,
+    },
+    /// Inspect recorded sessions
+    Sessions {
+        #[command(subcommand)]
+        command: SessionsCommand,
+    },
+    /// Database maintenance
+    Db {
+        #[command(subcommand)]
+        command: DbCommand,
+    },
+    /// Manage the git hook that tags commits with the current session
+    Hook {
+        #[command(subcommand)]
+        command: HookCommand,
+    },
+    /// Send completed work sessions to Toggl or Clockify
+    Track {
+        #[command(subcommand)]
+        command: TrackCommand,
+    },
+    /// Add sessions from a Toggl, Clockify or other CSV export
+    Import {
+        /// CSV file to read
+        file: std::path::PathBuf,
+
+        /// Where the file comes from
+        #[arg(long, value_enum)]
+        format: import::Format,
+
+        /// Tag sessions from PROJECT with TAG (none when empty); repeat for
+        /// more projects, `*` for every other one
+        #[arg(long = "map", value_name = "PROJECT=TAG", value_parser = import::parse_rule)]
+        rules: Vec<import::TagRule>,
+
+        /// Show what would be imported without changing anything
+        #[arg(short = 'n', long)]
+        dry_run: bool,
+    },
+    /// Handle a sandoro:// URL, e.g. sandoro://start?tag=writing&work=50 (see docs/URL_SCHEME.md)
+    Url {
+        /// URL to handle
+        #[arg(required_unless_present = "register")]
+        url: Option<String>,
+
+        /// Register sandoro as the system handler for sandoro:// URLs
+        #[arg(long, conflicts_with = "url")]
+        register: bool,
+    },
+    /// Serve embeddable SVG images, or a remote control for your phone, over HTTP (see docs/SERVE.md)
+    Serve {
+        /// Serve /heatmap.svg and /streak-badge.svg
+        #[arg(long)]
+        badge: bool,
+
+        /// Serve a page at /remote for starting, pausing and skipping the running timer
+        #[arg(long)]
+        remote: bool,
+
+        /// Address to listen on (default: 127.0.0.1, or 0.0.0.0 with --remote)
+        #[arg(long)]
+        bind: Option<String>,
+
+        /// Port to listen on
+        #[arg(short, long, default_value = "7317")]
+        port: u16,
+
+        /// Seconds clients and proxies may cache images
+        #[arg(long, default_value = "300")]
+        max_age: u32,
+    },
+    /// Extend the running timer's break, or bring back one that just ended
+    Snooze {
+        /// Minutes to add (default: the break's full length)
+        minutes: Option<u32>,
+    },
+    /// Switch the running timer to its break
+    Break {
+        #[command(subcommand)]
+        command: NowCommand,
+    },
+    /// Switch the running timer back to work
+    Work {
+        #[command(subcommand)]
+        command: NowCommand,
+    },
+    /// Run the timer in the background, controlled with `sandoro ctl`
+    Daemon {
+        /// Tag the daemon's sessions with this tag, creating it if needed
+        #[arg(long, value_name = "NAME")]
+        tag: Option<String>,
+    },
+    /// Print the running timer as one line for status bars (waybar,
+    /// polybar, tmux)
+    Status {
+        /// plain, waybar, tmux or json
+        #[arg(long, value_enum, default_value_t = status_line::Format::Plain)]
+        format: status_line::Format,
+    },
+    /// Control the background timer started with `sandoro daemon`
+    Ctl {
+        #[command(subcommand)]
+        command: CtlCommand,
+    },
+    /// Hide tags, notes and history (private mode)
+    Lock,
+    /// Leave private mode; asks for the unlock sequence
+    Unlock,
+    /// Showcase the timer on made-up history, for screenshots and GIFs
+    Demo {
+        /// Timer seconds per real second
+        #[arg(long, default_value_t = demo::DEFAULT_SPEED)]
+        speed: u32,
+
+        /// Keep the configured theme and icon instead of cycling through them
+        #[arg(long)]
+        no_cycle: bool,
+    },
+    /// Plan this week's sessions per tag, or show how the plan is going
+    Plan {
+        #[command(subcommand)]
+        command: Option<PlanCommand>,
+    },
+    /// Look back on last week, write one line about it and set this week's goals
+    Review,
+    /// Show your XP level and progress to the next one
+    Level {
+        /// Print the level and every XP award as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Print a weekly or monthly summary, or write the last 30 days as a standalone HTML page
+    #[command(group(
+        clap::ArgGroup::new("report_kind")
+            .args(["html", "week", "month"])
+            .required(true)
+    ))]
+    Report {
+        /// File to write, e.g. report.html
+        #[arg(long, value_name = "FILE")]
+        html: Option<std::path::PathBuf>,
+
+        /// Summarize the last 7 days: time per tag, best day, completion rate and trends
+        #[arg(long)]
+        week: bool,
+
+        /// Summarize the last 30 days, with averages by weekday
+        #[arg(long)]
+        month: bool,
+    },
+    /// Write a stats card to share: focus time, sessions, streak, best day and the heatmap
+    Share {
+        /// File to write: .svg, or .png with the png feature
+        #[arg(short, long, value_name = "FILE", default_value = "sandoro-card.svg")]
+        output: std::path::PathBuf,
+
+        /// Weeks the card covers, 1-53
+        #[arg(long, default_value_t = share::DEFAULT_WEEKS)]
         weeks: i32,
+    },
+    /// Print the database schema, migrations, row counts and effective config
+    Schema {
+        /// Print JSON instead of TOML
+        #[arg(long)]
+        json: bool,
+    },
+    /// Run a routine from config block by block, or list the routines
+    Routine {
+        /// Routine to start; lists them when left out
+        name: Option<String>,
+    },
+    /// Read or change settings without opening the timer
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+}
+
+#[derive(clap::Args)]
+#[command(group(
+    clap::ArgGroup::new("tag_scope")
+        .args(["compare_with", "export"])
+        .multiple(true)
+))]
+struct StatsArgs {
+    /// Show daily stats
+    #[arg(short, long)]
+    day: bool,
+
+    /// Show weekly stats
+    #[arg(short, long)]
+    week: bool,
+
+    /// Show monthly stats
+    #[arg(short, long)]
+    month: bool,
+
+    /// Show stats for a specific date (YYYY-MM-DD)
+    #[arg(long)]
+    date: Option<String>,
+
+    /// Number of weeks to show in heatmap (default: 12, or 53 like GitHub's
+    /// graph for --svg and --png)
+    #[arg(long)]
+    weeks: Option<i32>,
 
-        /// Interactive heatmap navigation mode
-        #[arg(short, long)]
-        interactive: bool,
+    /// Interactive heatmap navigation mode
+    #[arg(short, long)]
+    interactive: bool,
+
+    /// Browse a month calendar of sessions; Enter lists a day's sessions
+    #[arg(long)]
+    calendar: bool,
+
+    /// Export sessions to file (json or csv)
+    #[arg(long, value_name = "FORMAT")]
+    export: Option<String>,
+
+    /// Field separator for --export csv, e.g. ';' or 'tab'
+    #[arg(long, value_name = "CHAR", default_value = ",", value_parser = parse_delimiter)]
+    delimiter: u8,
+
+    /// Start the CSV with a byte order mark, so Excel reads non-ASCII text
+    #[arg(long)]
+    bom: bool,
+
+    /// Only export sessions from this day on (YYYY-MM-DD)
+    #[arg(long, value_name = "DATE", requires = "export", value_parser = parse_day)]
+    from: Option<chrono::NaiveDate>,
+
+    /// Only export sessions up to and including this day (YYYY-MM-DD)
+    #[arg(long, value_name = "DATE", requires = "export", value_parser = parse_day)]
+    to: Option<chrono::NaiveDate>,
+
+    /// Show comparison with previous period
+    #[arg(short = 'c', long)]
+    compare: bool,
+
+    /// Compare the current period with another period or tag: last-month,
+    /// last-quarter, last-year, same-month-last-year, YYYY, YYYY-MM, YYYY-Qn,
+    /// START..END or tag:NAME
+    #[arg(long, value_name = "PERIOD")]
+    compare_with: Option<String>,
+
+    /// Only count sessions with this tag in --compare-with, or export them
+    /// with --export
+    #[arg(long, value_name = "NAME", requires = "tag_scope")]
+    tag: Option<String>,
+
+    /// List sessions whose note contains TEXT (any note when left out), or
+    /// only export those with --export
+    #[arg(long, value_name = "TEXT", num_args = 0..=1, default_missing_value = "")]
+    notes: Option<String>,
+
+    /// Show goal progress
+    #[arg(short = 'g', long)]
+    goals: bool,
+
+    /// Show stats grouped by tag
+    #[arg(short = 't', long)]
+    by_tag: bool,
+
+    /// Show rolling averages and trend chart
+    #[arg(long)]
+    trend: bool,
+
+    /// Show stats grouped by hour of day
+    #[arg(long)]
+    by_hour: bool,
+
+    /// Show a histogram of the hours work sessions are done in, over the
+    /// heatmap's weeks
+    #[arg(long)]
+    hours: bool,
 
-        /// Export sessions to file (json or csv)
-        #[arg(long, value_name = "FORMAT")]
-        export: Option<String>,
+    /// Show how many started work sessions were finished, by time of day
+    /// and planned length
+    #[arg(long)]
+    completion: bool,
 
-        /// Show comparison with previous period
-        #[arg(short = 'c', long)]
-        compare: bool,
+    /// Print every aggregate as JSON (see docs/STATS_JSON.md)
+    #[arg(long)]
+    json: bool,
 
-        /// Show goal progress
-        #[arg(short = 'g', long)]
-        goals: bool,
+    /// Print a shields.io-style SVG badge: streak or today
+    #[arg(long, value_name = "KIND", visible_alias = "svg-badge")]
+    badge: Option<String>,
 
-        /// Show stats grouped by tag
-        #[arg(short = 't', long)]
-        by_tag: bool,
+    /// Write the heatmap to an SVG file, in the accent color
+    #[arg(long, value_name = "FILE")]
+    svg: Option<std::path::PathBuf>,
+
+    /// Write the heatmap to a PNG file, at twice the SVG's size (needs the
+    /// png feature)
+    #[arg(long, value_name = "FILE")]
+    png: Option<std::path::PathBuf>,
+}
+
+#[derive(Subcommand)]
+enum PlanCommand {
+    /// Plan a number of sessions for a tag (0 takes it out of the plan)
+    Set {
+        /// Tag name
+        tag: String,
+        /// Work sessions for the week
+        sessions: u32,
+
+        /// Plan next week instead of this one
+        #[arg(long)]
+        next: bool,
     },
-    /// Login to sync data with cloud
-    Login {
-        /// OAuth provider to use
-        #[arg(short, long, default_value = "google")]
-        provider: String,
+    /// Remove a week's plan
+    Clear {
+        /// Clear next week's plan instead of this one
+        #[arg(long)]
+        next: bool,
     },
-    /// Logout and remove stored credentials
-    Logout,
-    /// Sync local data with cloud
-    Sync {
-        /// Show sync status only
-        #[arg(short, long)]
-        status: bool,
+}
+
+#[derive(Subcommand)]
+enum DbCommand {
+    /// Apply the retention policy now
+    Prune {
+        /// Show what would be removed without changing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// List sessions whose timestamps suggest the system clock jumped
+    Audit,
+    /// Print the schema version, compatibility level and columns as JSON
+    Compat,
+}
+
+#[derive(Subcommand)]
+enum ConfigCommand {
+    /// Print a setting, e.g. appearance.theme
+    Get {
+        /// Section and name, e.g. timer.work_duration
+        key: String,
+    },
+    /// Change a setting, e.g. timer.work_duration 50
+    Set {
+        /// Section and name, e.g. timer.work_duration
+        key: String,
+        /// New value, read as the setting's type
+        #[arg(allow_hyphen_values = true)]
+        value: String,
+    },
+    /// Print every setting that has a value, with secrets hidden
+    List,
+    /// Open config.toml in $VISUAL or $EDITOR and check it before saving
+    Edit,
+}
+
+#[derive(Subcommand)]
+enum TrackCommand {
+    /// Show the time tracker setup and queued entries
+    Status,
+    /// Send queued entries now, ignoring retry delays
+    Flush,
+}
+
+#[derive(Subcommand)]
+enum CtlCommand {
+    /// Start or resume the current phase
+    Start,
+    /// Pause the current phase
+    Pause,
+    /// Move on to the next phase
+    Skip,
+    /// Show the phase and time left
+    Status {
+        /// Print the daemon's status as JSON
+        #[arg(long)]
+        json: bool,
+    },
+    /// Stop the daemon, closing the session under way
+    Stop,
+}
+
+#[derive(Subcommand)]
+enum NowCommand {
+    /// Right away, e.g. when a meeting starts or ends; a work block cut
+    /// short is kept as interrupted
+    Now,
+}
+
+#[derive(Subcommand)]
+enum HookCommand {
+    /// Install a prepare-commit-msg hook in the current repository
+    Install {
+        /// Replace an existing hook (kept as a backup)
+        #[arg(long)]
+        force: bool,
+    },
+    /// Remove the hook and restore any hook it replaced
+    Remove,
+    /// Annotate a commit message file (called by the hook)
+    #[command(hide = true)]
+    Run {
+        /// Commit message file
+        file: std::path::PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+enum SessionsCommand {
+    /// Show everything recorded about a session
+    Show {
+        /// Session ID
+        id: i64,
+    },
+    /// Combine two sessions split by a crash or a double start into one
+    Merge {
+        /// Session ID
+        first: i64,
+        /// The other session's ID
+        second: i64,
     },
 }
 
 fn format_duration(seconds: i32) -> String {
-    let hours = seconds / 3600;
-    let minutes = (seconds % 3600) / 60;
-    if hours > 0 {
-        format!("{}h {}m", hours, minutes)
-    } else {
-        format!("{}m", minutes)
-    }
+    i18n::duration(seconds)
 }
 
-/// Get activity level for heatmap display (0-4)
-fn get_activity_level(total_seconds: i32) -> usize {
-    if total_seconds == 0 {
-        0
-    } else if total_seconds < 30 * 60 {
-        1 // < 30min
-    } else if total_seconds < 60 * 60 {
-        2 // < 1h
-    } else if total_seconds < 120 * 60 {
-        3 // < 2h
-    } else {
-        4 // 2h+
+/// A YYYY-MM-DD date given on the command line
+fn parse_day(value: &str) -> Result<chrono::NaiveDate, String> {
+    chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| "use YYYY-MM-DD".to_string())
+}
+
+/// A single-byte CSV delimiter; `tab` for a tab
+fn parse_delimiter(value: &str) -> Result<u8, String> {
+    match value {
+        "tab" | "\\t" => Ok(b'\t'),
+        _ => match value.as_bytes() {
+            [byte] if *byte != b'"' && *byte != b'\n' && *byte != b'\r' => Ok(*byte),
+            _ => Err("use one ASCII character other than a quote, or 'tab'".to_string()),
+        },
     }
 }
 
 /// Get ANSI color code for accent color at specified opacity level
 fn get_accent_ansi(accent: &str, level: usize) -> String {
-    use crate::theme::ThemeColor;
-
     if level == 0 {
         // Gray for no activity
-        return "\x1b[38;2;100;100;100m".to_string();
+        return color::fg((100, 100, 100));
     }
 
     // For rainbow mode, use different rainbow colors based on level
@@ -152,48 +653,102 @@ fn get_accent_ansi(accent: &str, level: usize) -> String {
             3 => (200, 200, 0), // Yellow (high activity)
             _ => (255, 0, 128), // Pink/Magenta (very high activity)
         };
-        return format!("\x1b[38;2;{};{};{}m", r, g, b);
+        return color::fg((r, g, b));
     }
 
-    let color = ThemeColor::from_accent_name(accent);
-    let (r, g, b) = match color {
-        ThemeColor::Rgb { r, g, b } => (r, g, b),
-        ThemeColor::Named(_) => (34, 211, 238), // Default cyan
-    };
-
-    // Scale opacity based on level (1-4)
-    let opacity = match level {
-        1 => 0.4,
-        2 => 0.6,
-        3 => 0.8,
-        _ => 1.0,
-    };
-
-    let r = (r as f32 * opacity) as u8;
-    let g = (g as f32 * opacity) as u8;
-    let b = (b as f32 * opacity) as u8;
-
-    format!("\x1b[38;2;{};{};{}m", r, g, b)
+    color::fg(theme::heatmap_level_rgb(accent, level))
 }
 
 /// Get ANSI color code for rainbow heatmap based on activity level
 /// Each level gets a distinct, vibrant rainbow color (like the web version)
-fn get_rainbow_heatmap_ansi(level: usize) -> String {
+fn get_rainbow_heatmap_ansi(rainbow: &theme::Rainbow, level: usize) -> String {
     if level == 0 {
         // Dim gray for no activity
-        return "\x1b[38;2;60;60;60m".to_string();
+        return color::fg((60, 60, 60));
     }
 
     // Assign distinct, vibrant rainbow colors to each activity level
     // Web version uses animated gradient per cell; we use distinct colors per level
-    let (r, g, b) = match level {
-        1 => (80, 200, 220), // Cyan (low activity)
-        2 => (80, 220, 120), // Green (medium)
-        3 => (255, 200, 60), // Yellow-orange (high)
-        _ => (255, 80, 180), // Magenta-pink (very high)
+    let (r, g, b) = rainbow.heatmap(level);
+
+    color::fg((r, g, b))
+}
+
+/// Rows of bars in the `stats --hours` histogram
+const HISTOGRAM_HEIGHT: usize = 6;
+
+/// A histogram of completed work sessions by hour of day over the last
+/// `weeks` weeks, one two-column bar per hour in the heatmap's colors
+fn hour_histogram(db: &db::Database, config: &Config, weeks: i32) -> Result<Vec<String>> {
+    let hours = db.get_stats_by_hour(weeks * 7)?;
+    let mut lines = vec![
+        format!(
+            "  🕐 {}",
+            color::heading(&i18n::fill("Sessions by Hour (last {0} weeks)", &[&weeks]))
+        ),
+        "  ──────────────────────────────".to_string(),
+    ];
+    let Some(&(peak_hour, _, peak)) = hours
+        .iter()
+        .max_by_key(|(hour, _, sessions)| (*sessions, -(*hour as i64)))
+    else {
+        return Ok(lines);
+    };
+    if peak == 0 {
+        lines.push(format!("     {}", i18n::t("No sessions in this period.")));
+        return Ok(lines);
+    }
+
+    let accent = &config.appearance.accent;
+    let rainbow = theme::Rainbow::from_config(&config.appearance.rainbow);
+    let paint = |level: usize| {
+        if accent == "rainbow" {
+            get_rainbow_heatmap_ansi(&rainbow, level)
+        } else {
+            get_accent_ansi(accent, level)
+        }
     };
+    let reset = color::reset();
+    const EIGHTHS: [char; 9] = [' ', '▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
 
-    format!("\x1b[38;2;{};{};{}m", r, g, b)
+    // Bar heights in eighths of a row; any session shows at least a sliver
+    let bars: Vec<(usize, usize)> = hours
+        .iter()
+        .map(|&(_, _, sessions)| {
+            let sessions = sessions.max(0) as usize;
+            let peak = peak as usize;
+            let height = (sessions * HISTOGRAM_HEIGHT * 8).div_ceil(peak);
+            let level = (sessions * 4).div_ceil(peak);
+            (height, level)
+        })
+        .collect();
+    for row in 0..HISTOGRAM_HEIGHT {
+        let floor = (HISTOGRAM_HEIGHT - 1 - row) * 8;
+        let label = if row == 0 {
+            format!("{:>4}", peak)
+        } else {
+            " ".repeat(4)
+        };
+        let mut line = format!("  {} │", label);
+        for &(height, level) in &bars {
+            let cell = EIGHTHS[height.saturating_sub(floor).min(8)];
+            line.push_str(&format!("{}{}{}{} ", paint(level), cell, cell, reset));
+        }
+        lines.push(line.trim_end().to_string());
+    }
+    lines.push(format!("       └{}", "─".repeat(24 * 3)));
+    let labels: String = (0..24)
+        .step_by(3)
+        .map(|hour| format!("{:<9}", hour))
+        .collect();
+    lines.push(format!("        {}", labels.trim_end()));
+    lines.push(format!(
+        "     {} {:02}:00 ({})",
+        i18n::t("Peak:"),
+        peak_hour,
+        i18n::sessions(peak)
+    ));
+    Ok(lines)
 }
 
 /// Display heatmap using Unicode block characters with accent color
@@ -208,10 +763,10 @@ fn show_heatmap(db: &db::Database, weeks: i32) -> Result<()> {
         return Ok(());
     }
 
-    // Unicode block character
-    let block = '█';
-
-    println!("  Activity (last {} weeks)", weeks);
+    println!(
+        "  {}",
+        color::heading(&i18n::fill("Activity (last {0} weeks)", &[&weeks]))
+    );
     println!();
 
     // Parse dates and organize into weeks
@@ -229,7 +784,7 @@ fn show_heatmap(db: &db::Database, weeks: i32) -> Result<()> {
     }
 
     // Day labels (show Mon, Wed, Fri)
-    let day_labels = ["S", "M", "T", "W", "T", "F", "S"];
+    let day_labels = i18n::weekday_initials();
 
     // Group by weeks (columns)
     let mut week_columns: Vec<Vec<(u32, i32)>> = Vec::new(); // (day_of_week, seconds)
@@ -252,6 +807,7 @@ fn show_heatmap(db: &db::Database, weeks: i32) -> Result<()> {
     }
 
     let is_rainbow = accent == "rainbow";
+    let rainbow = theme::Rainbow::from_config(&config.appearance.rainbow);
 
     // Print heatmap rows (one per day of week)
     for day in 0..7 {
@@ -269,13 +825,13 @@ fn show_heatmap(db: &db::Database, weeks: i32) -> Result<()> {
             if seconds < 0 {
                 print!(" "); // No data (future or before start)
             } else {
-                let level = get_activity_level(seconds);
+                let level = theme::activity_level(seconds);
                 let color = if is_rainbow {
-                    get_rainbow_heatmap_ansi(level)
+                    get_rainbow_heatmap_ansi(&rainbow, level)
                 } else {
                     get_accent_ansi(accent, level)
                 };
-                print!("{}{}\x1b[0m", color, block);
+                print!("{}{}{}", color, color::heatmap_glyph(level), color::reset());
             }
         }
         println!();
@@ -284,181 +840,924 @@ fn show_heatmap(db: &db::Database, weeks: i32) -> Result<()> {
     println!();
 
     // Legend with colors
-    print!("     Less ");
+    print!("     {} ", i18n::t("Less"));
     for level in 0..=4 {
         let color = if is_rainbow {
-            get_rainbow_heatmap_ansi(level)
+            get_rainbow_heatmap_ansi(&rainbow, level)
         } else {
             get_accent_ansi(accent, level)
         };
-        print!("{}{}\x1b[0m ", color, block);
+        print!(
+            "{}{}{} ",
+            color,
+            color::heatmap_glyph(level),
+            color::reset()
+        );
     }
-    println!("More");
+    println!("{}", i18n::t("More"));
     println!();
 
     Ok(())
 }
 
-#[allow(clippy::too_many_arguments)]
-fn show_stats(
-    _day: bool,
-    week: bool,
-    month: bool,
-    date: Option<String>,
-    weeks: i32,
-    interactive: bool,
-    export: Option<String>,
-    compare: bool,
-    goals: bool,
-    by_tag: bool,
+/// Streak and totals, for storage backends other than SQLite
+fn show_storage_summary(storage: &dyn Storage) -> Result<()> {
+    println!();
+    println!("  📊 {}", color::heading(i18n::t("sandoro Statistics")));
+    println!("  ─────────────────────");
+    println!();
+
+    let streak = storage.get_streak()?;
+    println!(
+        "  🔥 {}  {} ({} {})",
+        i18n::t("Streak:"),
+        streak_days(streak.current),
+        i18n::t("longest:"),
+        i18n::days(streak.longest)
+    );
+    println!();
+
+    let today = storage.get_today_stats()?;
+    for (label, stats) in [
+        (format!("{} ({})", i18n::t("Today"), today.date), today),
+        (
+            i18n::t("Last 7 Days").to_string(),
+            storage.get_week_stats()?,
+        ),
+        (
+            i18n::t("Last 30 Days").to_string(),
+            storage.get_month_stats()?,
+        ),
+    ] {
+        println!(
+            "  📅 {} │ {:>8} │ {}",
+            i18n::pad(&label, 18),
+            format_duration(stats.total_work_seconds),
+            i18n::sessions(stats.sessions_completed)
+        );
+    }
+    println!();
+    println!(
+        "     {}",
+        i18n::t("Detailed stats need the SQLite backend ([storage] backend = \"sqlite\").")
+    );
+    println!();
+    Ok(())
+}
+
+/// How much of the last `days` days' work was flowtime, when any was
+fn print_flowtime(db: &db::Database, days: i32) -> Result<()> {
+    let flow = db.get_mode_stats(db::SessionMode::Flowtime, days)?;
+    if flow.sessions_completed > 0 {
+        println!(
+            "     🌊 {} {} ({})",
+            i18n::t("Flowtime:"),
+            format_duration(flow.total_work_seconds),
+            i18n::sessions(flow.sessions_completed)
+        );
+    }
+    Ok(())
+}
+
+/// First and last day (YYYY-MM-DD) of `date`, or of the last `days` days
+/// up to today
+fn stats_days(db: &db::Database, date: Option<&str>, days: i64) -> Result<(String, String)> {
+    Ok(match date {
+        Some(date) => (date.to_string(), date.to_string()),
+        None => {
+            let today = db.today_str()?;
+            let start = chrono::NaiveDate::parse_from_str(&today, "%Y-%m-%d")?
+                - chrono::Duration::days(days);
+            (start.format("%Y-%m-%d").to_string(), today)
+        }
+    })
+}
+
+/// Time paused during the completed work, beside the focus time it left,
+/// when there was any
+fn print_pauses(
+    db: &db::Database,
+    focus_seconds: i32,
+    date: Option<&str>,
+    days: i64,
 ) -> Result<()> {
-    let db = db::Database::open()?;
+    let (start, end) = stats_days(db, date, days)?;
+    let paused = db.get_paused_seconds(&start, &end)?;
+    if paused == 0 {
+        return Ok(());
+    }
+    println!(
+        "     ⏸  {} {} · {} {} / {} {}",
+        i18n::t("Paused:"),
+        format_duration(paused),
+        i18n::t("effective focus"),
+        format_duration(focus_seconds),
+        format_duration(focus_seconds + paused),
+        i18n::t("on the clock")
+    );
+    Ok(())
+}
+
+/// Interruptions of work sessions by category, on `date` or else from `days`
+/// days ago through today
+fn print_interruptions(db: &db::Database, date: Option<&str>, days: i64) -> Result<()> {
+    let (start, end) = stats_days(db, date, days)?;
+    let counts = db.get_interruptions_by_category(&start, &end)?;
+    let total: i32 = counts.iter().map(|(_, n)| n).sum();
+    if total == 0 {
+        return Ok(());
+    }
+    let categories: Vec<String> = counts
+        .iter()
+        .map(|(category, n)| {
+            let name = match category {
+                Some('\'') => i18n::t("internal").to_string(),
+                Some('-') => i18n::t("external").to_string(),
+                Some(c) => c.to_string(),
+                None => i18n::t("no category").to_string(),
+            };
+            format!("{} {}", name, n)
+        })
+        .collect();
+    println!(
+        "     ⚡ {} {} ({})",
+        i18n::t("Interruptions:"),
+        total,
+        categories.join(", ")
+    );
+    Ok(())
+}
+
+fn show_stats(args: StatsArgs) -> Result<()> {
     let config = Config::load().unwrap_or_default();
+    let storage = storage::open_readonly(&config)?;
+    // The week's plan goes with today's and this week's stats
+    let show_plan = !args.month && args.date.is_none();
+
+    if let Some(kind) = &args.badge {
+        let accent = &config.appearance.accent;
+        let svg = match kind.to_lowercase().as_str() {
+            "streak" => svg::streak_badge(storage.get_streak()?.current, accent),
+            "today" => svg::focus_badge(storage.get_today_stats()?.total_work_seconds, accent),
+            _ => anyhow::bail!("Unknown badge '{}'. Use 'streak' or 'today'.", kind),
+        };
+        println!("{}", svg);
+        return Ok(());
+    }
+    if args.svg.is_some() || args.png.is_some() {
+        return export_heatmap_image(
+            storage.as_ref(),
+            &config,
+            args.weeks.unwrap_or(53),
+            args.svg.as_deref(),
+            args.png.as_deref(),
+        );
+    }
+    let weeks = args.weeks.unwrap_or(12);
+
+    // Everything past the summary needs the SQLite backend
+    let Some(db) = storage.sqlite() else {
+        if args.json {
+            anyhow::bail!("stats --json needs the SQLite backend for now.");
+        }
+        return show_storage_summary(storage.as_ref());
+    };
+
+    if args.json || args.export.is_some() || args.notes.is_some() {
+        privacy::ensure_unlocked()?;
+    }
+    if args.calendar {
+        return run_calendar(db, &config);
+    }
+    if args.json {
+        let doc = stats_json::build(db, &config, args.date.as_deref(), weeks)?;
+        println!("{}", stats_json::render(&doc)?);
+        return Ok(());
+    }
 
     // Handle export
-    if let Some(format) = export {
+    if let Some(format) = args.export {
+        if let (Some(from), Some(to)) = (args.from, args.to) {
+            if from > to {
+                anyhow::bail!("--from {} is after --to {}.", from, to);
+            }
+        }
+        if let Some(tag) = &args.tag {
+            if !db.get_all_tags()?.iter().any(|t| &t.name == tag) {
+                anyhow::bail!("No tag named '{}'.", tag);
+            }
+        }
+        let filter = db::ExportFilter {
+            from: args.from,
+            to: args.to,
+            tag: args.tag.clone(),
+            notes: args.notes.clone(),
+        };
         let content = match format.to_lowercase().as_str() {
-            "json" => db.export_to_json()?,
-            "csv" => db.export_to_csv()?,
+            "json" => db.export_to_json(&filter)?,
+            "csv" => {
+                let options = db::CsvOptions {
+                    delimiter: args.delimiter,
+                    bom: args.bom,
+                };
+                db.export_to_csv(&filter, &options, &handoff::host_name())?
+            }
             _ => {
                 println!(
-                    "Error: Unknown export format '{}'. Use 'json' or 'csv'.",
-                    format
+                    "{}",
+                    i18n::fill(
+                        "Error: Unknown export format '{0}'. Use 'json' or 'csv'.",
+                        &[&format]
+                    )
                 );
                 return Ok(());
             }
         };
         let filename = format!("sandoro-sessions.{}", format.to_lowercase());
         std::fs::write(&filename, content)?;
-        println!("Exported sessions to: {}", filename);
+        println!("{}", i18n::fill("Exported sessions to: {0}", &[&filename]));
         return Ok(());
     }
 
+    if let Some(notes) = &args.notes {
+        return show_noted_sessions(db, notes);
+    }
+
     println!();
-    println!("  📊 sandoro Statistics");
+    println!("  📊 {}", color::heading(i18n::t("sandoro Statistics")));
     println!("  ─────────────────────");
     println!();
 
     // Show streak info
     let streak = db.get_streak()?;
     println!(
-        "  🔥 Streak:  {} days (longest: {} days)",
-        streak.current, streak.longest
+        "  🔥 {}  {} ({} {})",
+        i18n::t("Streak:"),
+        streak_days(streak.current),
+        i18n::t("longest:"),
+        i18n::days(streak.longest)
     );
     println!();
 
-    if let Some(date_str) = date {
+    if let Some(date_str) = args.date {
         // Specific date stats
         let stats = db.get_date_stats(&date_str)?;
-        println!("  📅 {}", stats.date);
+        println!("  📅 {}", color::heading(&stats.date));
         println!();
         println!("     ⏱  {}", format_duration(stats.total_work_seconds));
-        println!("     📊 {} sessions", stats.sessions_completed);
-    } else if month {
+        println!("     📊 {}", i18n::sessions(stats.sessions_completed));
+        print_pauses(db, stats.total_work_seconds, Some(&stats.date), 0)?;
+        print_interruptions(db, Some(&stats.date), 0)?;
+        if let Some(note) = db.get_journal(&stats.date)? {
+            println!("     📝 {}", privacy::hide(&note));
+        }
+    } else if args.month {
         // Monthly stats (last 30 days)
         let stats = db.get_month_stats()?;
-        println!("  📅 Last 30 Days");
+        println!("  📅 {}", color::heading(i18n::t("Last 30 Days")));
         println!();
         println!("     ⏱  {}", format_duration(stats.total_work_seconds));
-        println!("     📊 {} sessions", stats.sessions_completed);
+        println!("     📊 {}", i18n::sessions(stats.sessions_completed));
+        print_flowtime(db, 30)?;
+        print_pauses(db, stats.total_work_seconds, None, 30)?;
+        print_interruptions(db, None, 30)?;
         println!();
 
         // Daily breakdown (time-focused)
         let daily = db.get_daily_stats(30)?;
+        let journal = recent_journal(db, 30)?;
         if !daily.is_empty() {
-            println!("  Daily breakdown:");
+            println!("  {}", i18n::t("Daily breakdown:"));
             for s in daily.iter().take(10) {
                 println!(
-                    "     {} │ {:>8} │ {} sessions",
+                    "     {} │ {:>8} │ {}",
                     s.date,
                     format_duration(s.total_work_seconds),
-                    s.sessions_completed
+                    i18n::sessions(s.sessions_completed)
                 );
+                print_journal_note(&journal, &s.date);
             }
             if daily.len() > 10 {
-                println!("     ... and {} more days", daily.len() - 10);
+                println!(
+                    "     ... {}",
+                    i18n::fill("and {0} more days", &[&(daily.len() - 10)])
+                );
             }
         }
-    } else if week {
+    } else if args.week {
         // Weekly stats (last 7 days)
         let stats = db.get_week_stats()?;
-        println!("  📅 Last 7 Days");
+        println!("  📅 {}", color::heading(i18n::t("Last 7 Days")));
         println!();
         println!("     ⏱  {}", format_duration(stats.total_work_seconds));
-        println!("     📊 {} sessions", stats.sessions_completed);
+        println!("     📊 {}", i18n::sessions(stats.sessions_completed));
+        print_flowtime(db, 7)?;
+        print_pauses(db, stats.total_work_seconds, None, 7)?;
+        print_interruptions(db, None, 7)?;
         println!();
 
         // Daily breakdown (time-focused)
         let daily = db.get_daily_stats(7)?;
+        let journal = recent_journal(db, 7)?;
         if !daily.is_empty() {
-            println!("  Daily breakdown:");
+            println!("  {}", i18n::t("Daily breakdown:"));
             for s in &daily {
                 println!(
-                    "     {} │ {:>8} │ {} sessions",
+                    "     {} │ {:>8} │ {}",
                     s.date,
                     format_duration(s.total_work_seconds),
-                    s.sessions_completed
+                    i18n::sessions(s.sessions_completed)
                 );
+                print_journal_note(&journal, &s.date);
             }
         }
     } else {
         // Default: Today's stats (day flag or no flag) - time prominently displayed
         let stats = db.get_today_stats()?;
-        println!("  📅 Today ({})", stats.date);
+        println!("  📅 {} ({})", color::heading(i18n::t("Today")), stats.date);
         println!();
         println!("     ⏱  {}", format_duration(stats.total_work_seconds));
-        println!("     📊 {} sessions", stats.sessions_completed);
+        println!("     📊 {}", i18n::sessions(stats.sessions_completed));
+        print_pauses(db, stats.total_work_seconds, None, 0)?;
+        print_interruptions(db, None, 0)?;
+        if let Some(note) = db.get_journal(&stats.date)? {
+            println!("     📝 {}", privacy::hide(&note));
+        }
+        if let Some(penalty) = accountability::today(db, &config.accountability)? {
+            if let Some(warning) = penalty.warning() {
+                println!(
+                    "     🧘 {} · {} {} (−{})",
+                    warning,
+                    i18n::t("quality focus"),
+                    format_duration(penalty.quality_focus(stats.total_work_seconds)),
+                    format_duration(penalty.penalty_seconds)
+                );
+            }
+        }
     }
 
     // Show goal progress if requested or if goals are set
-    if goals || has_goals_enabled(&config) {
+    if args.goals || has_goals_enabled(&config) {
         println!();
-        show_goal_progress(&db, &config)?;
+        show_goal_progress(db, &config)?;
+    }
+
+    if show_plan {
+        show_week_plan(db, &config, false)?;
     }
 
     // Show comparison if requested
-    if compare {
+    if args.compare {
         println!();
-        show_comparison(&db)?;
+        show_comparison(db)?;
     }
 
-    println!();
-
+    if let Some(spec) = &args.compare_with {
+        println!();
+        show_comparison_with(db, spec, args.tag.as_deref())?;
+    }
+
+    // Show rolling averages and trend if requested
+    if args.trend {
+        println!();
+        show_trend(db)?;
+    }
+
+    println!();
+
     // Show heatmap
-    if interactive {
-        run_interactive_heatmap(&db, weeks)?;
+    if args.interactive {
+        run_interactive_heatmap(db, weeks)?;
     } else {
-        show_heatmap(&db, weeks)?;
+        show_heatmap(db, weeks)?;
     }
 
     // Show tag-based statistics
-    if by_tag {
+    if args.by_tag {
         println!();
-        println!("  🏷️  Stats by Tag (Last 30 days)");
+        println!(
+            "  🏷️  {}",
+            color::heading(i18n::t("Stats by Tag (Last 30 days)"))
+        );
         println!("  ─────────────────────────────");
 
         let tag_stats = db.get_stats_by_tag(30)?;
         if tag_stats.is_empty() {
-            println!("     No data found for the last 30 days.");
+            println!("     {}", i18n::t("No data found for the last 30 days."));
         } else {
+            // Budgeted tags also show today against the budget
+            let budgets = budget::usage(&config.budgets, &db.get_today_stats_by_tag()?);
             for (tag, total_seconds, sessions) in tag_stats {
                 let tag_name = match &tag {
-                    Some(t) => &t.name,
-                    None => "No tag",
+                    Some(t) => color::paint(tag_bar::tag_rgb(t), &privacy::hide(&t.name)),
+                    None => i18n::t("No tag").to_string(),
+                };
+                let usage = tag
+                    .as_ref()
+                    .and_then(|t| budgets.iter().find(|u| u.tag == t.name));
+                let budget = match usage {
+                    Some(usage) => {
+                        let text = format!(
+                            " │ {}",
+                            i18n::fill(
+                                "today {0} of {1} budget",
+                                &[
+                                    &format_duration(usage.seconds),
+                                    &format_duration(usage.budget_minutes as i32 * 60)
+                                ]
+                            )
+                        );
+                        if usage.is_over() {
+                            color::down(&format!("{} ⚠", text))
+                        } else {
+                            text
+                        }
+                    }
+                    None => String::new(),
                 };
                 println!(
-                    "     {} │ {} │ {} sessions",
+                    "     {} │ {} │ {}{}",
                     tag_name,
                     format_duration(total_seconds),
-                    sessions
+                    i18n::sessions(sessions),
+                    budget
+                );
+            }
+        }
+    }
+
+    // Show hour-of-day statistics
+    if args.by_hour {
+        println!();
+        println!(
+            "  🕐 {}",
+            color::heading(i18n::t("Stats by Hour (Last 30 days)"))
+        );
+        println!("  ──────────────────────────────");
+
+        let hours = db.get_stats_by_hour(30)?;
+        if hours.iter().all(|(_, seconds, _)| *seconds == 0) {
+            println!("     {}", i18n::t("No data found for the last 30 days."));
+        } else {
+            for (hour, total_seconds, sessions) in hours.into_iter().filter(|h| h.2 > 0) {
+                println!(
+                    "     {:02}:00 │ {:>8} │ {}",
+                    hour,
+                    format_duration(total_seconds),
+                    i18n::sessions(sessions)
+                );
+            }
+        }
+    }
+
+    if args.hours {
+        println!();
+        for line in hour_histogram(db, &config, weeks)? {
+            println!("{}", line);
+        }
+    }
+
+    if args.completion {
+        println!();
+        show_completion(db)?;
+    }
+
+    Ok(())
+}
+
+fn handle_plan(command: Option<PlanCommand>) -> Result<()> {
+    let config = Config::load().unwrap_or_default();
+    let weeks_ago = |next: bool| if next { -1 } else { 0 };
+    match command {
+        Some(PlanCommand::Set {
+            tag,
+            sessions,
+            next,
+        }) => {
+            let db = db::Database::open()?;
+            let start = plan::week_of(&config, db.today()?, weeks_ago(next));
+            plan::set(&db, start, &tag, sessions)?;
+            if sessions == 0 {
+                println!("  {}", i18n::fill("✓ {0} taken out of the plan", &[&tag]));
+            } else {
+                println!(
+                    "  {}",
+                    i18n::fill(
+                        "✓ {0} sessions of {1} planned for the week of {2}",
+                        &[&sessions, &tag, &i18n::month_day(start)]
+                    )
                 );
             }
         }
+        Some(PlanCommand::Clear { next }) => {
+            let db = db::Database::open()?;
+            let start = plan::week_of(&config, db.today()?, weeks_ago(next));
+            match plan::clear(&db, start)? {
+                0 => println!(
+                    "  {}",
+                    i18n::fill("No plan for the week of {0}.", &[&i18n::month_day(start)])
+                ),
+                _ => println!(
+                    "  {}",
+                    i18n::fill(
+                        "✓ Plan for the week of {0} cleared",
+                        &[&i18n::month_day(start)]
+                    )
+                ),
+            }
+        }
+        None => {
+            let db = db::Database::open_readonly()?;
+            show_week_plan(&db, &config, true)?;
+            println!();
+        }
+    }
+    Ok(())
+}
+
+/// This week's plan against what's done, and last week's grade. With
+/// `always`, says so when there's no plan instead of printing nothing.
+fn show_week_plan(db: &db::Database, config: &Config, always: bool) -> Result<()> {
+    let today = db.today()?;
+    let current = plan::load(db, plan::week_of(config, today, 0))?;
+    let last = plan::load(db, plan::week_of(config, today, 1))?;
+    if current.is_empty() && last.is_empty() && !always {
+        return Ok(());
+    }
+
+    println!();
+    println!(
+        "  🗓  {}",
+        color::heading(&i18n::fill(
+            "Plan (week of {0})",
+            &[&i18n::month_day(current.start)]
+        ))
+    );
+    println!("  ─────────────────────────────");
+    if current.is_empty() {
+        println!(
+            "     {}",
+            i18n::t("No plan yet. Try `sandoro plan set <tag> <sessions>`.")
+        );
+    }
+    let names: Vec<String> = current
+        .items
+        .iter()
+        .map(|i| privacy::hide(&i.tag.name))
+        .collect();
+    let width = names.iter().map(|n| n.chars().count()).max().unwrap_or(0);
+    for (item, name) in current.items.iter().zip(&names) {
+        let percent = (item.done.max(0) as u32 * 100 / item.planned).min(100);
+        println!(
+            "     {:<width$} │ {} {:>2}/{}{}",
+            name,
+            create_progress_bar(percent, 10),
+            item.done,
+            item.planned,
+            if item.done >= item.planned as i32 {
+                "  ✓"
+            } else {
+                ""
+            },
+            width = width
+        );
+    }
+    if !current.is_empty() {
+        println!(
+            "     {}",
+            i18n::fill(
+                "{0} of {1} planned sessions done ({2}%)",
+                &[&current.done(), &current.planned(), &current.percent()]
+            )
+        );
+    }
+    if !last.is_empty() {
+        println!(
+            "     {}",
+            i18n::fill(
+                "Last week: {0} ({1}% of {2} planned sessions)",
+                &[&last.grade().letter(), &last.percent(), &last.planned()]
+            )
+        );
+    }
+    Ok(())
+}
+
+/// Write the heatmap as an SVG and/or PNG image for a blog or README
+fn export_heatmap_image(
+    storage: &dyn storage::Storage,
+    config: &Config,
+    weeks: i32,
+    svg_path: Option<&std::path::Path>,
+    png_path: Option<&std::path::Path>,
+) -> Result<()> {
+    // Daily totals are history
+    privacy::ensure_unlocked()?;
+    if !(1..=53).contains(&weeks) {
+        anyhow::bail!("--weeks must be between 1 and 53, got {}.", weeks);
+    }
+    let image = svg::heatmap(&storage.get_heatmap_data(weeks)?, &config.appearance.accent);
+    // The PNG first, so a build without it stops before writing anything
+    if let Some(path) = png_path {
+        write_png(path, &image)?;
+    }
+    if let Some(path) = svg_path {
+        std::fs::write(path, &image)
+            .with_context(|| format!("Could not write {}", path.display()))?;
+    }
+    for path in png_path.into_iter().chain(svg_path) {
+        println!("  ✓ {} {}", i18n::t("Heatmap written to"), path.display());
+    }
+    Ok(())
+}
+
+#[cfg(feature = "png")]
+fn write_png(path: &std::path::Path, image: &str) -> Result<()> {
+    std::fs::write(path, svg::to_png(image, 2.0)?)
+        .with_context(|| format!("Could not write {}", path.display()))
+}
+
+#[cfg(not(feature = "png"))]
+fn write_png(_path: &std::path::Path, _image: &str) -> Result<()> {
+    anyhow::bail!(
+        "This build can't write PNG. Use --svg, or build with `cargo install sandoro --features png`."
+    )
+}
+
+fn write_share_card(path: &std::path::Path, weeks: i32) -> Result<()> {
+    // Daily totals are history
+    privacy::ensure_unlocked()?;
+    if !(1..=53).contains(&weeks) {
+        anyhow::bail!("--weeks must be between 1 and 53, got {}.", weeks);
+    }
+    let config = Config::load().unwrap_or_default();
+    let storage = storage::open_readonly(&config)?;
+    let card = share::build(storage.as_ref(), &config, weeks)?;
+    let is_png = path
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("png"));
+    if is_png {
+        write_png(path, &card)?;
+    } else {
+        std::fs::write(path, card)
+            .with_context(|| format!("Could not write {}", path.display()))?;
+    }
+    println!("  ✓ {} {}", i18n::t("Card written to"), path.display());
+    Ok(())
+}
+
+fn write_report(path: &std::path::Path) -> Result<()> {
+    // Tag names and daily totals are history too
+    privacy::ensure_unlocked()?;
+    let config = Config::load().unwrap_or_default();
+    let db = db::Database::open_readonly()?;
+    std::fs::write(path, report::build(&db, &config)?)
+        .with_context(|| format!("Could not write {}", path.display()))?;
+    println!("  ✓ {} {}", i18n::t("Report written to"), path.display());
+    Ok(())
+}
+
+fn print_summary(period: summary::Period) -> Result<()> {
+    privacy::ensure_unlocked()?;
+    let config = Config::load().unwrap_or_default();
+    let db = db::Database::open_readonly()?;
+    let today = db.today()?;
+    print!(
+        "{}",
+        summary::render(&summary::build(&db, &config, period, today)?)
+    );
+    Ok(())
+}
+
+/// Print each routine in config with its blocks
+fn list_routines() -> Result<()> {
+    let config = Config::load()?;
+    let names = routine::names(&config);
+    if names.is_empty() {
+        println!(
+            "{}",
+            i18n::t("No routines yet. Add one under [routines] in ~/.sandoro/config.toml.")
+        );
+        return Ok(());
+    }
+    let width = names.iter().map(|n| n.chars().count()).max().unwrap_or(0);
+    for name in &names {
+        println!(
+            "  {:<width$}  {}",
+            name,
+            privacy::hide(&routine::describe(&config, name)),
+            width = width
+        );
+    }
+    println!();
+    println!(
+        "  {}",
+        i18n::t("Start one with `sandoro routine <name>`, or press `o` in the timer.")
+    );
+    Ok(())
+}
+
+fn show_level(json: bool) -> Result<()> {
+    let config = Config::load().unwrap_or_default();
+    let db = db::Database::open_readonly()?;
+    let level = xp::level_for(db.total_xp()?);
+
+    if json {
+        let log: Vec<serde_json::Value> = db
+            .xp_log()?
+            .iter()
+            .map(|event| {
+                serde_json::json!({
+                    "sessionId": event.session_id,
+                    "reason": event.reason,
+                    "xp": event.xp,
+                    "createdAt": event.created_at.to_rfc3339(),
+                })
+            })
+            .collect();
+        let out = serde_json::json!({
+            "enabled": config.xp.enabled,
+            "level": level.level,
+            "title": level.title(),
+            "xp": level.xp,
+            "toNext": level.remaining(),
+            "events": log,
+        });
+        println!("{}", serde_json::to_string_pretty(&out)?);
+        return Ok(());
+    }
+
+    if !config.xp.enabled {
+        println!(
+            "  {}",
+            i18n::t("XP is off. Turn it on in ~/.sandoro/config.toml:")
+        );
+        println!();
+        println!("     [xp]");
+        println!("     enabled = true");
+        return Ok(());
+    }
+
+    println!(
+        "  {}",
+        i18n::fill(
+            "★ Level {0} · {1}",
+            &[&level.level, &i18n::t(level.title())]
+        )
+    );
+    println!("  ─────────────────────────────");
+    println!(
+        "     {} {:>3}% │ {}",
+        create_progress_bar(level.percent(), 20),
+        level.percent(),
+        i18n::fill(
+            "{0} XP, {1} to level {2}",
+            &[&level.xp, &level.remaining(), &(level.level + 1)]
+        )
+    );
+    println!();
+    for (reason, earned) in xp::breakdown(&db)? {
+        println!(
+            "     {} │ {:>6} XP",
+            i18n::pad(i18n::t(reason.label()), 13),
+            earned
+        );
+    }
+    Ok(())
+}
+
+/// Completion rates of started work sessions (Last 30 days)
+fn show_completion(db: &db::Database) -> Result<()> {
+    println!(
+        "  ✅ {}",
+        color::heading(&i18n::fill(
+            "Completion (Last {0} days)",
+            &[&completion::WINDOW_DAYS]
+        ))
+    );
+    println!("  ─────────────────────────────");
+
+    let report = completion::load(db)?;
+    let total = report.total;
+    if total.attempts() == 0 {
+        println!(
+            "     {}",
+            i18n::fill(
+                "No finished work sessions in the last {0} days.",
+                &[&completion::WINDOW_DAYS]
+            )
+        );
+        return Ok(());
+    }
+    println!(
+        "     {}",
+        i18n::fill(
+            "{0} started │ {1} completed ({2}%) │ {3} skipped │ {4} reset │ {5} interrupted",
+            &[
+                &total.attempts(),
+                &total.completed,
+                &total.percent(),
+                &total.skipped,
+                &total.reset,
+                &total.interrupted
+            ]
+        )
+    );
+
+    let row = |label: &str, tally: &completion::Tally| {
+        let count = i18n::fill("{0} of {1}", &[&tally.completed, &tally.attempts()]);
+        println!(
+            "     {} │ {} {:>3}% │ {}{}",
+            i18n::pad(label, 15),
+            create_progress_bar(tally.percent(), 10),
+            tally.percent(),
+            count,
+            if tally.is_low() {
+                i18n::t("  ⚠ often abandoned")
+            } else {
+                ""
+            }
+        );
+    };
+
+    println!();
+    println!("     {}", i18n::t("By time of day"));
+    for (part, tally) in &report.by_day_part {
+        row(part.label(), tally);
+    }
+
+    println!();
+    println!("     {}", i18n::t("By planned length"));
+    for (minutes, tally) in &report.by_length {
+        let label = match minutes {
+            Some(minutes) => i18n::fill("{0} min", &[&minutes]),
+            None => i18n::t("unknown").to_string(),
+        };
+        row(&label, tally);
+    }
+
+    Ok(())
+}
+
+/// Show 7/28-day rolling averages with trend arrows and a sparkline chart
+fn show_trend(db: &db::Database) -> Result<()> {
+    // 8 weeks of history so the 28-day rolling average is fully populated
+    let data = db.get_heatmap_data(8)?;
+    let minutes: Vec<f64> = data
+        .iter()
+        .map(|s| f64::from(s.total_work_seconds) / 60.0)
+        .collect();
+
+    let last_28_start = minutes.len().saturating_sub(28);
+    let last_28 = &minutes[last_28_start..];
+    let rolling_7 = analytics::rolling_average(&minutes, 7);
+
+    println!("  📈 {}", color::heading(i18n::t("Trend")));
+    println!("  ────────");
+
+    let summary = analytics::summarize_trend(&minutes);
+    for (label, avg, trend) in [
+        (i18n::t("7-day avg"), summary.avg_7d, summary.trend_7d),
+        (i18n::t("28-day avg"), summary.avg_28d, summary.trend_28d),
+    ] {
+        println!(
+            "     {} │ {:>8}/{} │ {} {}",
+            i18n::pad(label, 12),
+            format_duration((avg * 60.0).round() as i32),
+            i18n::t("day"),
+            match trend {
+                analytics::Trend::Improving => color::up(trend.arrow()),
+                analytics::Trend::Declining => color::down(trend.arrow()),
+                analytics::Trend::Flat => trend.arrow().to_string(),
+            },
+            trend.label()
+        );
     }
 
+    println!();
+    println!(
+        "     {} │{}│",
+        i18n::pad(i18n::t("Last 28 days"), 12),
+        analytics::sparkline(last_28)
+    );
+    println!(
+        "     {} │{}│",
+        i18n::pad(i18n::t("7-day avg"), 12),
+        analytics::sparkline(&rolling_7[last_28_start..])
+    );
+
     Ok(())
 }
 
+/// A running streak stands out; a broken one doesn't
+fn streak_days(days: i32) -> String {
+    if days > 0 {
+        color::highlight(&i18n::days(days))
+    } else {
+        i18n::days(days)
+    }
+}
+
 /// Check if any goals are enabled
 fn has_goals_enabled(config: &Config) -> bool {
     config.goals.daily_sessions > 0
@@ -467,20 +1766,20 @@ fn has_goals_enabled(config: &Config) -> bool {
         || config.goals.weekly_minutes > 0
 }
 
-/// Calculate percentage change
+/// Calculate percentage change, green when up and red when down
 fn calculate_change(current: i32, previous: i32) -> String {
     if previous == 0 {
         if current > 0 {
-            "↑ +100%".to_string()
+            color::up("↑ +100%")
         } else {
             "→ 0%".to_string()
         }
     } else {
         let change = ((current - previous) as f64 / previous as f64 * 100.0).round() as i32;
         if change > 0 {
-            format!("↑ +{}%", change)
+            color::up(&format!("↑ +{}%", change))
         } else if change < 0 {
-            format!("↓ {}%", change)
+            color::down(&format!("↓ {}%", change))
         } else {
             "→ 0%".to_string()
         }
@@ -490,39 +1789,70 @@ fn calculate_change(current: i32, previous: i32) -> String {
 /// Show goal progress (time-focused: minutes goals shown first)
 fn show_goal_progress(db: &db::Database, config: &Config) -> Result<()> {
     let today_stats = db.get_today_stats()?;
-    let week_stats = db.get_week_stats()?;
+    let week = goals::current_week(db, config, db.today()?)?;
     let is_rainbow = config.appearance.accent == "rainbow";
+    let rainbow = theme::Rainbow::from_config(&config.appearance.rainbow);
 
-    println!("  🎯 Goals");
+    println!("  🎯 {}", color::heading(i18n::t("Goals")));
     println!("  ────────");
 
     let has_daily_goals = config.goals.daily_sessions > 0 || config.goals.daily_minutes > 0;
     let has_weekly_goals = config.goals.weekly_sessions > 0 || config.goals.weekly_minutes > 0;
 
     if !has_daily_goals && !has_weekly_goals {
-        println!("     No goals set. Configure goals in settings.");
+        println!(
+            "     {}",
+            i18n::t("No goals set. Configure goals in settings.")
+        );
         return Ok(());
     }
 
     if has_daily_goals {
         println!();
-        println!("  📅 Daily");
+        println!("  📅 {}", color::heading(i18n::t("goals\u{4}Daily")));
 
         // Time goal first (primary metric)
         if config.goals.daily_minutes > 0 {
             let today_minutes = today_stats.total_work_seconds / 60;
             let progress = (today_minutes as f64 / config.goals.daily_minutes as f64 * 100.0)
                 .min(100.0) as u32;
-            let bar = if is_rainbow && progress < 100 {
-                create_rainbow_progress_bar(progress, 20)
+            // Split by tag once anything today is tagged
+            let segments = tag_bar::segments(&db.get_today_stats_by_tag()?);
+            let bar = if let Some(segments) = &segments {
+                create_segmented_progress_bar(segments, config.goals.daily_minutes as i32 * 60, 20)
+            } else if is_rainbow && progress < 100 {
+                create_rainbow_progress_bar(&rainbow, progress, 20)
             } else {
                 create_progress_bar(progress, 20)
             };
-            let check = if progress >= 100 { "✓" } else { " " };
+            let check = if progress >= 100 {
+                color::up("✓")
+            } else {
+                " ".to_string()
+            };
             println!(
-                "     ⏱  Time:     {} {}m/{}m [{}] {}%",
-                check, today_minutes, config.goals.daily_minutes, bar, progress
+                "     ⏱  {} {} {}/{} [{}] {}%",
+                i18n::t("Time:    "),
+                check,
+                i18n::minutes(today_minutes),
+                i18n::minutes(config.goals.daily_minutes),
+                bar,
+                progress
             );
+            if let Some(segments) = &segments {
+                let legend: Vec<String> = segments
+                    .iter()
+                    .map(|s| {
+                        format!(
+                            "{} {} {}",
+                            color::paint(s.rgb, "■"),
+                            privacy::hide(&s.label),
+                            format_duration(s.seconds)
+                        )
+                    })
+                    .collect();
+                println!("                   {}", legend.join("  "));
+            }
         }
 
         // Sessions goal second (secondary metric)
@@ -532,56 +1862,256 @@ fn show_goal_progress(db: &db::Database, config: &Config) -> Result<()> {
                 * 100.0)
                 .min(100.0) as u32;
             let bar = if is_rainbow && progress < 100 {
-                create_rainbow_progress_bar(progress, 20)
+                create_rainbow_progress_bar(&rainbow, progress, 20)
             } else {
                 create_progress_bar(progress, 20)
             };
-            let check = if progress >= 100 { "✓" } else { " " };
+            let check = if progress >= 100 {
+                color::up("✓")
+            } else {
+                " ".to_string()
+            };
             println!(
-                "     📊 Sessions: {} {}/{} [{}] {}%",
-                check, today_stats.sessions_completed, config.goals.daily_sessions, bar, progress
+                "     📊 {} {} {}/{} [{}] {}%",
+                i18n::t("Sessions:"),
+                check,
+                today_stats.sessions_completed,
+                config.goals.daily_sessions,
+                bar,
+                progress
             );
         }
     }
 
     if has_weekly_goals {
         println!();
-        println!("  📅 Weekly");
+        println!(
+            "  📅 {} ({} – {})",
+            color::heading(i18n::t("This week")),
+            i18n::weekday_month_day(week.start),
+            i18n::weekday_month_day(week.end())
+        );
 
         // Time goal first (primary metric)
         if config.goals.weekly_minutes > 0 {
-            let week_minutes = week_stats.total_work_seconds / 60;
+            let week_minutes = week.minutes;
             let progress = (week_minutes as f64 / config.goals.weekly_minutes as f64 * 100.0)
                 .min(100.0) as u32;
             let bar = if is_rainbow && progress < 100 {
-                create_rainbow_progress_bar(progress, 20)
+                create_rainbow_progress_bar(&rainbow, progress, 20)
             } else {
                 create_progress_bar(progress, 20)
             };
-            let check = if progress >= 100 { "✓" } else { " " };
+            let check = if progress >= 100 {
+                color::up("✓")
+            } else {
+                " ".to_string()
+            };
             println!(
-                "     ⏱  Time:     {} {}m/{}m [{}] {}%",
-                check, week_minutes, config.goals.weekly_minutes, bar, progress
+                "     ⏱  {} {} {}/{} [{}] {}%",
+                i18n::t("Time:    "),
+                check,
+                i18n::minutes(week_minutes),
+                i18n::minutes(config.goals.weekly_minutes),
+                bar,
+                progress
             );
         }
 
         // Sessions goal second (secondary metric)
         if config.goals.weekly_sessions > 0 {
-            let progress = (week_stats.sessions_completed as f64
-                / config.goals.weekly_sessions as f64
-                * 100.0)
+            let progress = (week.sessions as f64 / config.goals.weekly_sessions as f64 * 100.0)
                 .min(100.0) as u32;
             let bar = if is_rainbow && progress < 100 {
-                create_rainbow_progress_bar(progress, 20)
+                create_rainbow_progress_bar(&rainbow, progress, 20)
             } else {
                 create_progress_bar(progress, 20)
             };
-            let check = if progress >= 100 { "✓" } else { " " };
+            let check = if progress >= 100 {
+                color::up("✓")
+            } else {
+                " ".to_string()
+            };
             println!(
-                "     📊 Sessions: {} {}/{} [{}] {}%",
-                check, week_stats.sessions_completed, config.goals.weekly_sessions, bar, progress
+                "     📊 {} {} {}/{} [{}] {}%",
+                i18n::t("Sessions:"),
+                check,
+                week.sessions,
+                config.goals.weekly_sessions,
+                bar,
+                progress
             );
         }
+
+        println!();
+        show_goal_forecast(db, config)?;
+        show_goal_history(db, config)?;
+    }
+
+    Ok(())
+}
+
+/// Met/missed weekly goals for recent weeks
+fn show_goal_history(db: &db::Database, config: &Config) -> Result<()> {
+    let weeks = goals::history(db, config, db.today()?)?;
+    if weeks.is_empty() {
+        return Ok(());
+    }
+
+    println!();
+    println!("  📜 {}", color::heading(i18n::t("Past weeks")));
+    for outcome in weeks {
+        let mut parts = Vec::new();
+        if outcome.minutes_goal > 0 {
+            parts.push(format!(
+                "⏱  {}/{}",
+                i18n::minutes(outcome.week.minutes),
+                i18n::minutes(outcome.minutes_goal)
+            ));
+        }
+        if outcome.sessions_goal > 0 {
+            parts.push(format!(
+                "📊 {}/{}",
+                outcome.week.sessions,
+                i18n::sessions(outcome.sessions_goal)
+            ));
+        }
+        println!(
+            "     {} {} – {}  {}",
+            if outcome.met() {
+                color::up("✓")
+            } else {
+                color::down("✗")
+            },
+            i18n::month_day(outcome.week.start),
+            i18n::month_day(outcome.week.end()),
+            parts.join("  ")
+        );
+    }
+    Ok(())
+}
+
+/// Project this week's goal attainment from weekday habits
+fn show_goal_forecast(db: &db::Database, config: &Config) -> Result<()> {
+    use chrono::{Duration, NaiveDate};
+
+    // Number of full weeks before this one used for weekday averages
+    const HISTORY_WEEKS: i64 = 8;
+
+    let first_day = config.goals.week_start_day();
+    let today = db.today()?;
+    let today_index = goals::day_of_week(today, first_day);
+    let week_start = today - Duration::days(today_index as i64);
+    let history_start = week_start - Duration::weeks(HISTORY_WEEKS);
+
+    let mut week_minutes = 0.0;
+    let mut week_sessions = 0.0;
+    let mut today_minutes = 0.0;
+    let mut today_sessions = 0.0;
+    let mut avg_minutes = [0.0; 7];
+    let mut avg_sessions = [0.0; 7];
+
+    for s in db.get_heatmap_data(HISTORY_WEEKS as i32 + 1)? {
+        let Ok(date) = NaiveDate::parse_from_str(&s.date, "%Y-%m-%d") else {
+            continue;
+        };
+        let minutes = f64::from(s.total_work_seconds) / 60.0;
+        let sessions = f64::from(s.sessions_completed);
+        if date >= week_start {
+            week_minutes += minutes;
+            week_sessions += sessions;
+            if date == today {
+                today_minutes = minutes;
+                today_sessions = sessions;
+            }
+        } else if date >= history_start {
+            let weekday = goals::day_of_week(date, first_day);
+            avg_minutes[weekday] += minutes / HISTORY_WEEKS as f64;
+            avg_sessions[weekday] += sessions / HISTORY_WEEKS as f64;
+        }
+    }
+
+    println!(
+        "  🔮 {}",
+        color::heading(&i18n::fill(
+            "Forecast (this week, {0}–{1})",
+            &[
+                &i18n::weekday_short(first_day),
+                &i18n::weekday_short(first_day.pred())
+            ]
+        ))
+    );
+    // Names of the week's days, counted from its first day
+    let day_name = |day: usize| {
+        let mut weekday = first_day;
+        for _ in 0..day {
+            weekday = weekday.succ();
+        }
+        i18n::weekday(weekday)
+    };
+
+    let describe = |forecast: &analytics::GoalForecast, progress: f64, goal: u32, unit: &str| {
+        if progress >= f64::from(goal) {
+            return i18n::t("goal reached ✓").to_string();
+        }
+        let projected = format!("{:.0}{}", forecast.projected, unit);
+        match forecast.hit_day {
+            Some(day) if day == today_index => i18n::fill(
+                "at this pace you'll hit it today ({0} projected)",
+                &[&projected],
+            ),
+            Some(day) => i18n::fill(
+                "at this pace you'll hit it by {0} ({1} projected)",
+                &[&day_name(day), &projected],
+            ),
+            None => i18n::fill(
+                "behind, {0} projected; need {1}/day for the rest of the week",
+                &[
+                    &projected,
+                    &format!("{:.1}{}", forecast.required_daily, unit),
+                ],
+            ),
+        }
+    };
+
+    if config.goals.weekly_minutes > 0 {
+        let forecast = analytics::forecast_weekly_goal(
+            f64::from(config.goals.weekly_minutes),
+            week_minutes,
+            today_index,
+            today_minutes,
+            &avg_minutes,
+        );
+        println!(
+            "     ⏱  {} {}",
+            i18n::t("Time:    "),
+            describe(
+                &forecast,
+                week_minutes,
+                config.goals.weekly_minutes,
+                i18n::t("m")
+            )
+        );
+    }
+
+    if config.goals.weekly_sessions > 0 {
+        let forecast = analytics::forecast_weekly_goal(
+            f64::from(config.goals.weekly_sessions),
+            week_sessions,
+            today_index,
+            today_sessions,
+            &avg_sessions,
+        );
+        println!(
+            "     📊 {} {}",
+            i18n::t("Sessions:"),
+            describe(
+                &forecast,
+                week_sessions,
+                config.goals.weekly_sessions,
+                i18n::t(" sessions")
+            )
+        );
     }
 
     Ok(())
@@ -591,86 +2121,121 @@ fn show_goal_progress(db: &db::Database, config: &Config) -> Result<()> {
 fn create_progress_bar(percent: u32, width: usize) -> String {
     let filled = (percent as usize * width / 100).min(width);
     let empty = width - filled;
-    format!("{}{}", "█".repeat(filled), "░".repeat(empty))
+    format!(
+        "{}{}",
+        color::accent(&"█".repeat(filled)),
+        "░".repeat(empty)
+    )
+}
+
+/// Create a progress bar with one colored stretch per tag segment
+fn create_segmented_progress_bar(
+    segments: &[tag_bar::Segment],
+    goal_seconds: i32,
+    width: usize,
+) -> String {
+    let cells = tag_bar::cells(segments, goal_seconds, width);
+    let mut result = String::new();
+    for (segment, count) in segments.iter().zip(&cells) {
+        result.push_str(&color::paint(segment.rgb, &"█".repeat(*count)));
+    }
+    let filled: usize = cells.iter().sum();
+    result.push_str(&"░".repeat(width - filled));
+    result
 }
 
-/// Create an ASCII progress bar with rainbow colors
-fn create_rainbow_progress_bar(percent: u32, width: usize) -> String {
+/// Create an ASCII progress bar in the rainbow palette's colors
+fn create_rainbow_progress_bar(rainbow: &theme::Rainbow, percent: u32, width: usize) -> String {
     let filled = (percent as usize * width / 100).min(width);
     let empty = width - filled;
 
-    // Rainbow colors for the filled portion
-    let rainbow_colors = [
-        (255, 0, 0),   // Red
-        (255, 127, 0), // Orange
-        (255, 255, 0), // Yellow
-        (0, 255, 0),   // Green
-        (0, 255, 255), // Cyan
-        (0, 0, 255),   // Blue
-        (128, 0, 255), // Purple
-    ];
-
     let mut result = String::new();
     for i in 0..filled {
-        let color_idx = (i * rainbow_colors.len() / width.max(1)) % rainbow_colors.len();
-        let (r, g, b) = rainbow_colors[color_idx];
-        result.push_str(&format!("\x1b[38;2;{};{};{}m█\x1b[0m", r, g, b));
+        result.push_str(&color::paint(rainbow.bar(i, width), "█"));
     }
     result.push_str(&"░".repeat(empty));
     result
 }
 
-/// Show comparison with previous period (time-focused display)
-fn show_comparison(db: &db::Database) -> Result<()> {
-    println!("  📈 Comparison");
-    println!("  ─────────────");
-    println!();
-
-    // This week vs last week (time is primary metric)
-    let this_week = db.get_week_stats()?;
-    let last_week = db.get_previous_week_stats()?;
-
-    println!("  📅 This Week vs Last Week");
+/// Print one comparison block (time is the primary metric)
+fn print_comparison(title: &str, current: &DailyStats, previous: &DailyStats) {
+    println!("  📅 {}", color::heading(title));
     println!(
         "     ⏱  {} vs {} ({})",
-        format_duration(this_week.total_work_seconds),
-        format_duration(last_week.total_work_seconds),
-        calculate_change(this_week.total_work_seconds, last_week.total_work_seconds)
+        format_duration(current.total_work_seconds),
+        format_duration(previous.total_work_seconds),
+        calculate_change(current.total_work_seconds, previous.total_work_seconds)
     );
     println!(
-        "     📊 {} vs {} sessions ({})",
-        this_week.sessions_completed,
-        last_week.sessions_completed,
-        calculate_change(this_week.sessions_completed, last_week.sessions_completed)
+        "     📊 {} vs {} ({})",
+        current.sessions_completed,
+        i18n::sessions(previous.sessions_completed),
+        calculate_change(current.sessions_completed, previous.sessions_completed)
     );
+}
 
-    println!();
-
-    // This month vs last month (time is primary metric)
-    let this_month = db.get_month_stats()?;
-    let last_month = db.get_previous_month_stats()?;
+/// Show comparison with previous period (time-focused display)
+fn show_comparison(db: &db::Database) -> Result<()> {
+    println!("  📈 {}", color::heading(i18n::t("Comparison")));
+    println!("  ─────────────");
+    println!();
 
-    println!("  📅 This Month vs Last Month");
-    println!(
-        "     ⏱  {} vs {} ({})",
-        format_duration(this_month.total_work_seconds),
-        format_duration(last_month.total_work_seconds),
-        calculate_change(this_month.total_work_seconds, last_month.total_work_seconds)
+    print_comparison(
+        i18n::t("This Week vs Last Week"),
+        &db.get_week_stats()?,
+        &db.get_previous_week_stats()?,
     );
-    println!(
-        "     📊 {} vs {} sessions ({})",
-        this_month.sessions_completed,
-        last_month.sessions_completed,
-        calculate_change(this_month.sessions_completed, last_month.sessions_completed)
+    println!();
+    print_comparison(
+        i18n::t("This Month vs Last Month"),
+        &db.get_month_stats()?,
+        &db.get_previous_month_stats()?,
     );
 
     Ok(())
 }
 
+/// Show comparison with a period or tag given on the command line
+fn show_comparison_with(db: &db::Database, spec: &str, tag: Option<&str>) -> Result<()> {
+    let comparison = compare::parse(spec, tag, db.today()?)?;
+    let current = compare::load(db, &comparison.current)?;
+    let other = compare::load(db, &comparison.other)?;
+
+    println!("  📈 {}", color::heading(i18n::t("Comparison")));
+    println!("  ─────────────");
+    println!();
+    print_comparison(&comparison.title(), &current, &other);
+
+    Ok(())
+}
+
+/// `stats --calendar`, in the heatmap's colors
+fn run_calendar(db: &db::Database, config: &Config) -> Result<()> {
+    let accent = &config.appearance.accent;
+    let rainbow = theme::Rainbow::from_config(&config.appearance.rainbow);
+    let levels = std::array::from_fn(|level| {
+        if accent == "rainbow" {
+            get_rainbow_heatmap_ansi(&rainbow, level)
+        } else {
+            get_accent_ansi(accent, level)
+        }
+    });
+    let style = calendar::Style {
+        levels,
+        selected: format!(
+            "{}{}",
+            color::bg(theme::ThemeColor::from_accent_name(accent).to_rgb()),
+            color::fg((0, 0, 0))
+        ),
+        accent: get_accent_ansi(accent, 4),
+    };
+    calendar::run(db, db.today()?, &style)
+}
+
 /// Interactive heatmap navigation using arrow keys
 #[allow(clippy::type_complexity)]
 fn run_interactive_heatmap(db: &db::Database, initial_weeks: i32) -> Result<()> {
-    use chrono::{Datelike, Duration, Local, NaiveDate};
+    use chrono::{Datelike, Duration, NaiveDate};
     use crossterm::{
         cursor,
         event::{self, Event, KeyCode, KeyEvent},
@@ -699,23 +2264,24 @@ fn run_interactive_heatmap(db: &db::Database, initial_weeks: i32) -> Result<()>
     // Current week range (can be changed with +/-)
     let mut weeks = initial_weeks;
 
-    // Unicode block character (we'll use color instead of different characters)
-    let block = '█';
     let day_labels = ["Sun", "Mon", "Tue", "Wed", "Thu", "Fri", "Sat"];
 
     // ANSI colors - use accent color
     let accent_color = get_accent_ansi(&accent, 4); // Full accent color
-    let dim = "\x1b[2m";
-    let reset = "\x1b[0m";
-    let bold = "\x1b[1m";
+    let dim = color::dim();
+    let reset = color::reset();
+    let bold = color::bold();
     // Selection background using accent color
     let bg_accent = format!(
-        "\x1b[48;2;{};{};{}m\x1b[30m",
-        theme::ThemeColor::from_accent_name(&accent).to_rgb().0,
-        theme::ThemeColor::from_accent_name(&accent).to_rgb().1,
-        theme::ThemeColor::from_accent_name(&accent).to_rgb().2,
+        "{}{}",
+        color::bg(theme::ThemeColor::from_accent_name(&accent).to_rgb()),
+        color::fg((0, 0, 0))
     );
 
+    // Journal notes shown in the selected date's info box (up to the
+    // 104-week maximum)
+    let journal = recent_journal(db, 104 * 7)?;
+
     // Build the grid properly aligned to weekdays (like Web version)
     let build_grid =
         |weeks: i32, db: &db::Database| -> Result<(Vec<Vec<Option<DailyStats>>>, usize, usize)> {
@@ -725,7 +2291,7 @@ fn run_interactive_heatmap(db: &db::Database, initial_weeks: i32) -> Result<()>
             let stats_map: HashMap<String, &DailyStats> =
                 data.iter().map(|s| (s.date.clone(), s)).collect();
 
-            let today = Local::now().date_naive();
+            let today = db.today()?;
             let today_str = today.format("%Y-%m-%d").to_string();
             let current_day_of_week = today.weekday().num_days_from_sunday() as usize;
 
@@ -850,6 +2416,7 @@ fn run_interactive_heatmap(db: &db::Database, initial_weeks: i32) -> Result<()>
         write!(stdout, "\r\n")?;
 
         let is_rainbow = accent == "rainbow";
+        let rainbow = theme::Rainbow::from_config(&config.appearance.rainbow);
 
         // Draw heatmap with grid (only visible portion)
         for (day, day_label) in day_labels.iter().enumerate() {
@@ -862,13 +2429,15 @@ fn run_interactive_heatmap(db: &db::Database, initial_weeks: i32) -> Result<()>
 
             for week in visible_start..visible_end {
                 if let Some(Some(day_data)) = grid.get(week).and_then(|w| w.get(day)) {
-                    let level = get_activity_level(day_data.total_work_seconds);
+                    let level = theme::activity_level(day_data.total_work_seconds);
                     let color = if is_rainbow {
-                        get_rainbow_heatmap_ansi(level)
+                        get_rainbow_heatmap_ansi(&rainbow, level)
                     } else {
                         get_accent_ansi(&accent, level)
                     };
 
+                    let block = color::heatmap_glyph(level);
+
                     if week == selected_week && day == selected_day {
                         // Selected: accent background
                         write!(stdout, "{}[{}]{}", bg_accent, block, reset)?;
@@ -900,11 +2469,11 @@ fn run_interactive_heatmap(db: &db::Database, initial_weeks: i32) -> Result<()>
         write!(stdout, "\r\n       Less ")?;
         for level in 0..=4 {
             let color = if is_rainbow {
-                get_rainbow_heatmap_ansi(level)
+                get_rainbow_heatmap_ansi(&rainbow, level)
             } else {
                 get_accent_ansi(&accent, level)
             };
-            write!(stdout, "{}{}{} ", color, block, reset)?;
+            write!(stdout, "{}{}{} ", color, color::heatmap_glyph(level), reset)?;
         }
         write!(stdout, "More\r\n\r\n")?;
 
@@ -926,14 +2495,18 @@ fn run_interactive_heatmap(db: &db::Database, initial_weeks: i32) -> Result<()>
                     day_data.date.clone()
                 };
 
+            let note = journal
+                .get(&day_data.date)
+                .map(|note| ui::truncate(note, 23));
+
             // Use rainbow colors for info box border if in rainbow mode
             if is_rainbow {
                 // Use vibrant colors for the info box (same as level 1-4 + magenta for top)
-                let c1 = "\x1b[38;2;255;80;180m"; // Magenta-pink (top border)
-                let c2 = "\x1b[38;2;80;200;220m"; // Cyan
-                let c3 = "\x1b[38;2;80;220;120m"; // Green
-                let c4 = "\x1b[38;2;255;200;60m"; // Yellow-orange
-                let c5 = "\x1b[38;2;255;80;180m"; // Magenta-pink (bottom border)
+                let c1 = color::fg((255, 80, 180)); // Magenta-pink (top border)
+                let c2 = color::fg((80, 200, 220)); // Cyan
+                let c3 = color::fg((80, 220, 120)); // Green
+                let c4 = color::fg((255, 200, 60)); // Yellow-orange
+                let c5 = color::fg((255, 80, 180)); // Magenta-pink (bottom border)
                 write!(
                     stdout,
                     "  {}┌─────────────────────────────┐{}\r\n",
@@ -954,6 +2527,13 @@ fn run_interactive_heatmap(db: &db::Database, initial_weeks: i32) -> Result<()>
                     "  {}│{} 📊 {:<23}{}│{}\r\n",
                     c4, reset, sessions_str, c4, reset
                 )?;
+                if let Some(note) = &note {
+                    write!(
+                        stdout,
+                        "  {}│{} 📝 {:<23}{}│{}\r\n",
+                        c4, reset, note, c4, reset
+                    )?;
+                }
                 write!(
                     stdout,
                     "  {}└─────────────────────────────┘{}\r\n",
@@ -980,6 +2560,13 @@ fn run_interactive_heatmap(db: &db::Database, initial_weeks: i32) -> Result<()>
                     "  {}│{} 📊 {:<23}{}│{}\r\n",
                     accent_color, reset, sessions_str, accent_color, reset
                 )?;
+                if let Some(note) = &note {
+                    write!(
+                        stdout,
+                        "  {}│{} 📝 {:<23}{}│{}\r\n",
+                        accent_color, reset, note, accent_color, reset
+                    )?;
+                }
                 write!(
                     stdout,
                     "  {}└─────────────────────────────┘{}\r\n",
@@ -988,6 +2575,12 @@ fn run_interactive_heatmap(db: &db::Database, initial_weeks: i32) -> Result<()>
             }
         }
 
+        // When in the day the sessions in range were done
+        write!(stdout, "\r\n")?;
+        for line in hour_histogram(db, &config, weeks)? {
+            write!(stdout, "{}\r\n", line)?;
+        }
+
         stdout.flush()?;
 
         // Handle key input
@@ -997,56 +2590,48 @@ fn run_interactive_heatmap(db: &db::Database, initial_weeks: i32) -> Result<()>
                 KeyCode::Left | KeyCode::Char('h') => {
                     selected_week = selected_week.saturating_sub(1);
                 }
-                KeyCode::Right | KeyCode::Char('l') => {
-                    if selected_week < num_weeks - 1 {
+                KeyCode::Right | KeyCode::Char('l')
+                    if selected_week < num_weeks - 1
                         // Check if the cell has data (not future)
-                        if grid
+                        && grid
                             .get(selected_week + 1)
                             .and_then(|w| w.get(selected_day))
                             .map(|d| d.is_some())
-                            .unwrap_or(false)
-                        {
-                            selected_week += 1;
-                        }
-                    }
+                            .unwrap_or(false) =>
+                {
+                    selected_week += 1;
                 }
                 KeyCode::Up | KeyCode::Char('k') => {
                     selected_day = selected_day.saturating_sub(1);
                 }
-                KeyCode::Down | KeyCode::Char('j') => {
-                    if selected_day < 6 {
-                        selected_day += 1;
-                    }
+                KeyCode::Down | KeyCode::Char('j') if selected_day < 6 => {
+                    selected_day += 1;
                 }
-                KeyCode::Char('+') | KeyCode::Char('=') => {
+                KeyCode::Char('+') | KeyCode::Char('=') if weeks < 104 => {
                     // Increase weeks (max 104 = 2 years)
-                    if weeks < 104 {
-                        weeks += 4;
-                        if weeks > 104 {
-                            weeks = 104;
-                        }
-                        let (new_grid, _, _) = build_grid(weeks, db)?;
-                        grid = new_grid;
-                        num_weeks = grid.len();
-                        month_labels = build_month_labels(&grid);
-                        // Keep selection at the end
-                        selected_week = num_weeks.saturating_sub(1);
+                    weeks += 4;
+                    if weeks > 104 {
+                        weeks = 104;
                     }
+                    let (new_grid, _, _) = build_grid(weeks, db)?;
+                    grid = new_grid;
+                    num_weeks = grid.len();
+                    month_labels = build_month_labels(&grid);
+                    // Keep selection at the end
+                    selected_week = num_weeks.saturating_sub(1);
                 }
-                KeyCode::Char('-') | KeyCode::Char('_') => {
+                KeyCode::Char('-') | KeyCode::Char('_') if weeks > 4 => {
                     // Decrease weeks (min 4)
-                    if weeks > 4 {
-                        weeks -= 4;
-                        if weeks < 4 {
-                            weeks = 4;
-                        }
-                        let (new_grid, _, _) = build_grid(weeks, db)?;
-                        grid = new_grid;
-                        num_weeks = grid.len();
-                        month_labels = build_month_labels(&grid);
-                        // Keep selection at the end
-                        selected_week = num_weeks.saturating_sub(1);
+                    weeks -= 4;
+                    if weeks < 4 {
+                        weeks = 4;
                     }
+                    let (new_grid, _, _) = build_grid(weeks, db)?;
+                    grid = new_grid;
+                    num_weeks = grid.len();
+                    month_labels = build_month_labels(&grid);
+                    // Keep selection at the end
+                    selected_week = num_weeks.saturating_sub(1);
                 }
                 _ => {}
             }
@@ -1060,42 +2645,115 @@ fn run_interactive_heatmap(db: &db::Database, initial_weeks: i32) -> Result<()>
     Ok(())
 }
 
-fn main() -> Result<()> {
-    let cli = Cli::parse();
+/// Load config.toml. When it doesn't parse, say so and offer the backup from
+/// the last save instead of quietly running on defaults.
+fn load_config() -> Config {
+    use std::io::{IsTerminal, Write};
+
+    let err = match Config::load() {
+        Ok(config) => return config,
+        Err(err) => err,
+    };
+    let path = Config::config_path()
+        .map(|p| p.display().to_string())
+        .unwrap_or_else(|_| "config.toml".to_string());
+    // Speak the language of the settings that take over
+    i18n::init(&Config::default().appearance.language);
+    eprintln!(
+        "  {}",
+        i18n::fill("⚠ {0} can't be read: {1}", &[&path, &err])
+    );
+
+    let Some((backup, saved)) = settings::backup() else {
+        eprintln!(
+            "    {}",
+            i18n::t("No backup to restore; using the default settings.")
+        );
+        return Config::default();
+    };
+    let saved = chrono::DateTime::<chrono::Local>::from(saved).format("%Y-%m-%d %H:%M");
+    if !(std::io::stdin().is_terminal() && std::io::stderr().is_terminal()) {
+        eprintln!(
+            "    {}",
+            i18n::fill(
+                "Using the default settings. The backup from {0} is at {1}",
+                &[&saved, &backup.display()]
+            )
+        );
+        return Config::default();
+    }
+
+    eprint!(
+        "    {}",
+        i18n::fill("Restore the backup from {0}? [Y/n] ", &[&saved])
+    );
+    let _ = std::io::stderr().flush();
+    let mut answer = String::new();
+    let _ = std::io::stdin().read_line(&mut answer);
+    if matches!(answer.trim().to_lowercase().as_str(), "" | "y" | "yes") {
+        match settings::restore_backup() {
+            Ok(config) => {
+                eprintln!(
+                    "  {}",
+                    i18n::t("✓ Restored. The unreadable file is kept as config.toml.broken")
+                );
+                return config;
+            }
+            Err(err) => eprintln!(
+                "    {}",
+                i18n::fill("Couldn't restore the backup: {0}", &[&err])
+            ),
+        }
+    }
+    eprintln!("    {}", i18n::t("Using the default settings."));
+    Config::default()
+}
+
+fn main() {
+    let config = load_config();
+    // The language is needed before parsing, for --help
+    i18n::init(&config.appearance.language);
+    let matches = i18n::localize_help(Cli::command()).get_matches();
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|err| err.exit());
+    color::init(cli.color, &config.appearance);
+    days::init(config.days.policy);
+    privacy::init();
+    if let Err(err) = run(cli) {
+        // Same layout as returning the error from main, with a colored label
+        eprintln!("{} {:?}", color::error_label(), err);
+        std::process::exit(1);
+    }
+}
+
+fn run(cli: Cli) -> Result<()> {
+    // A sped-up clock would fill the real history with sessions from the
+    // future, so it only runs on a throwaway database
+    if let Some(scale) = cli.time_scale {
+        clock::init(scale);
+    }
+    let run_options = app::RunOptions {
+        no_workspace: cli.no_workspace,
+        low_power: cli.low_power,
+        ephemeral: cli.ephemeral || clock::is_scaled(),
+        compact: cli.compact,
+        ..Default::default()
+    };
 
     match cli.command {
         Some(Commands::Start {
             work: _,
             short_break: _,
             long_break: _,
+            resume_remote,
         }) => {
             // Config is loaded from file; CLI args are deprecated
-            app::run()?;
+            app::run(app::RunOptions {
+                resume_remote,
+                ..run_options
+            })?;
         }
-        Some(Commands::Stats {
-            day,
-            week,
-            month,
-            date,
-            weeks,
-            interactive,
-            export,
-            compare,
-            goals,
-            by_tag,
-        }) => {
-            show_stats(
-                day,
-                week,
-                month,
-                date,
-                weeks,
-                interactive,
-                export,
-                compare,
-                goals,
-                by_tag,
-            )?;
+        Some(Commands::Stats(args)) => {
+            show_stats(args)?;
         }
         Some(Commands::Login { provider }) => {
             handle_login(&provider)?;
@@ -1103,12 +2761,197 @@ fn main() -> Result<()> {
         Some(Commands::Logout) => {
             handle_logout()?;
         }
-        Some(Commands::Sync { status }) => {
-            handle_sync(status)?;
+        Some(Commands::Whoami) => {
+            handle_whoami()?;
+        }
+        Some(Commands::Sync {
+            status,
+            push,
+            pull,
+            dry_run,
+        }) => {
+            if status {
+                handle_sync_status()?;
+            } else {
+                let direction = match (push, pull) {
+                    (true, _) => sync::SyncDirection::Push,
+                    (_, true) => sync::SyncDirection::Pull,
+                    _ => sync::SyncDirection::Both,
+                };
+                let conflicts = Config::load().unwrap_or_default().account.conflict_policy;
+                handle_sync(&sync::SyncOptions {
+                    direction,
+                    dry_run,
+                    conflicts,
+                })?;
+            }
+        }
+        Some(Commands::Search { query, limit }) => {
+            handle_search(&query, limit)?;
+        }
+        Some(Commands::Insights) => {
+            handle_insights()?;
+        }
+        Some(Commands::Journal { note, date, delete }) => {
+            handle_journal(note.as_deref(), date.as_deref(), delete)?;
+        }
+        Some(Commands::Sessions { command }) => match command {
+            SessionsCommand::Show { id } => handle_sessions_show(id)?,
+            SessionsCommand::Merge { first, second } => handle_sessions_merge(first, second)?,
+        },
+        Some(Commands::Db { command }) => match command {
+            DbCommand::Prune { dry_run } => handle_db_prune(dry_run)?,
+            DbCommand::Audit => handle_db_audit()?,
+            DbCommand::Compat => {
+                let db = db::Database::open_as_is()?;
+                let report = schema::build_compat(db.as_ref())?;
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            }
+        },
+        Some(Commands::Hook { command }) => match command {
+            HookCommand::Install { force } => {
+                let path = hook::install(force)?;
+                println!("  ✓ Installed {}", path.display());
+            }
+            HookCommand::Remove => {
+                let path = hook::remove()?;
+                println!("  ✓ Removed {}", path.display());
+            }
+            HookCommand::Run { file } => hook::run(&file)?,
+        },
+        Some(Commands::Track { command }) => match command {
+            TrackCommand::Status => handle_track_status()?,
+            TrackCommand::Flush => handle_track_flush()?,
+        },
+        Some(Commands::Import {
+            file,
+            format,
+            rules,
+            dry_run,
+        }) => {
+            handle_import(&file, format, &rules, dry_run)?;
+        }
+        Some(Commands::Url { url, register }) => {
+            if register {
+                println!("  ✓ {}", deeplink::register()?);
+            } else if let Some(url) = url {
+                app::run(app::RunOptions {
+                    overrides: deeplink::parse(&url)?,
+                    ..run_options
+                })?;
+            }
+        }
+        Some(Commands::Serve {
+            badge,
+            remote,
+            bind,
+            port,
+            max_age,
+        }) => {
+            // The remote is for a phone on the same network
+            let bind =
+                bind.unwrap_or_else(|| if remote { "0.0.0.0" } else { "127.0.0.1" }.to_string());
+            serve::run(serve::ServeOptions {
+                bind,
+                port,
+                badge,
+                remote_token: remote.then(serve::new_token),
+                max_age,
+            })?;
+        }
+        Some(Commands::Snooze { minutes }) => {
+            let reply = control::send(control::Command::Snooze(minutes))?;
+            println!("  ✓ {}", reply);
+        }
+        Some(Commands::Break {
+            command: NowCommand::Now,
+        }) => {
+            let reply = control::send(control::Command::BreakNow)?;
+            println!("  ✓ {}", reply);
+        }
+        Some(Commands::Work {
+            command: NowCommand::Now,
+        }) => {
+            let reply = control::send(control::Command::WorkNow)?;
+            println!("  ✓ {}", reply);
+        }
+        Some(Commands::Daemon { tag }) => {
+            handle_daemon(tag, run_options.ephemeral)?;
+        }
+        Some(Commands::Status { format }) => {
+            handle_status(format)?;
+        }
+        Some(Commands::Ctl { command }) => {
+            handle_ctl(command)?;
+        }
+        Some(Commands::Lock) => {
+            handle_lock()?;
+        }
+        Some(Commands::Unlock) => {
+            handle_unlock()?;
         }
+        Some(Commands::Demo { speed, no_cycle }) => {
+            app::run(app::RunOptions {
+                demo: Some(demo::Demo::new(speed, !no_cycle)),
+                // The made-up tags shouldn't depend on where it's run
+                no_workspace: true,
+                ..run_options
+            })?;
+        }
+        Some(Commands::Plan { command }) => {
+            handle_plan(command)?;
+        }
+        Some(Commands::Review) => {
+            handle_review()?;
+        }
+        Some(Commands::Level { json }) => {
+            show_level(json)?;
+        }
+        Some(Commands::Report { html, month, .. }) => match html {
+            Some(path) => write_report(&path)?,
+            None => {
+                let period = if month {
+                    summary::Period::Month
+                } else {
+                    summary::Period::Week
+                };
+                print_summary(period)?;
+            }
+        },
+        Some(Commands::Share { output, weeks }) => write_share_card(&output, weeks)?,
+        Some(Commands::Schema { json }) => {
+            let config = Config::load()?;
+            let db = db::Database::open_as_is()?;
+            let report = schema::build(db.as_ref(), &config)?;
+            println!("{}", schema::render(&report, json)?);
+        }
+        Some(Commands::Routine { name: Some(name) }) => {
+            app::run(app::RunOptions {
+                routine: Some(name),
+                ..run_options
+            })?;
+        }
+        Some(Commands::Routine { name: None }) => {
+            list_routines()?;
+        }
+        Some(Commands::Config { command }) => match command {
+            ConfigCommand::Get { key } => println!("{}", config_keys::get(&Config::load()?, &key)?),
+            ConfigCommand::Set { key, value } => {
+                let base = Config::load()?;
+                let edited = config_keys::set(&base, &key, &value)?;
+                let saved = settings::save(&base, &edited)?;
+                println!("  ✓ {} = {}", key, config_keys::get(&saved, &key)?);
+            }
+            ConfigCommand::List => {
+                for line in config_keys::list(&Config::load()?.redacted())? {
+                    println!("{}", line);
+                }
+            }
+            ConfigCommand::Edit => config_keys::edit()?,
+        },
         None => {
             // Default: start timer with settings from config file
-            app::run()?;
+            app::run(run_options)?;
         }
     }
 
@@ -1119,51 +2962,50 @@ fn handle_login(provider: &str) -> Result<()> {
     // Validate provider
     let provider = provider.to_lowercase();
     if provider != "google" && provider != "github" {
-        println!(
-            "Error: Invalid provider '{}'. Use 'google' or 'github'.",
-            provider
-        );
-        return Ok(());
+        anyhow::bail!("Invalid provider '{}'. Use 'google' or 'github'.", provider);
     }
 
     // Check if already logged in
     if auth::is_logged_in() {
         if let Ok(Some((user_id, email))) = auth::get_current_user() {
             println!(
-                "Already logged in as: {}",
-                email.as_deref().unwrap_or(&user_id)
+                "{}",
+                i18n::fill(
+                    "Already logged in as: {0}",
+                    &[&email.as_deref().unwrap_or(&user_id)]
+                )
             );
-            println!("Use 'sandoro logout' to log out first.");
+            println!("{}", i18n::t("Use 'sandoro logout' to log out first."));
             return Ok(());
         }
     }
 
-    println!("Logging in with {}...", provider);
+    println!("{}", i18n::fill("Logging in with {0}...", &[&provider]));
     println!();
 
-    match auth::login(&provider) {
-        Ok(creds) => {
-            println!();
-            println!("✓ Successfully logged in!");
-            println!(
-                "  User: {}",
-                creds.email.as_deref().unwrap_or(&creds.user_id)
-            );
-            println!();
-            println!("Your sessions will now sync with the cloud.");
-            println!("Run 'sandoro sync' to sync existing sessions.");
-        }
-        Err(e) => {
-            println!("Error: Failed to login: {}", e);
-        }
-    }
+    let creds = auth::login(&provider).context("Login failed")?;
+    println!();
+    println!("{}", i18n::t("✓ Successfully logged in!"));
+    println!(
+        "  {}",
+        i18n::fill(
+            "User: {0}",
+            &[&creds.email.as_deref().unwrap_or(&creds.user_id)]
+        )
+    );
+    println!();
+    println!("{}", i18n::t("Your sessions will now sync with the cloud."));
+    println!(
+        "{}",
+        i18n::t("Run 'sandoro sync' to sync existing sessions.")
+    );
 
     Ok(())
 }
 
 fn handle_logout() -> Result<()> {
     if !auth::is_logged_in() {
-        println!("Not logged in.");
+        println!("{}", i18n::t("Not logged in."));
         return Ok(());
     }
 
@@ -1173,55 +3015,958 @@ fn handle_logout() -> Result<()> {
     auth::delete_credentials()?;
 
     if let Some(user) = user_info {
-        println!("✓ Logged out from: {}", user);
+        println!("{}", i18n::fill("✓ Logged out from: {0}", &[&user]));
     } else {
-        println!("✓ Logged out.");
+        println!("{}", i18n::t("✓ Logged out."));
     }
-    println!("Your local data is preserved.");
+    println!("{}", i18n::t("Your local data is preserved."));
 
     Ok(())
 }
 
-fn handle_sync(status_only: bool) -> Result<()> {
+/// Exits non-zero when logged out, so scripts can check before syncing
+fn handle_whoami() -> Result<()> {
+    match auth::get_current_user()? {
+        Some((user_id, Some(email))) => {
+            println!("{}", email);
+            println!("  {}", i18n::fill("User ID: {0}", &[&user_id]));
+        }
+        Some((user_id, None)) => println!("{}", user_id),
+        None => anyhow::bail!("Not logged in. Run 'sandoro login' first."),
+    }
+    Ok(())
+}
+
+fn handle_sync_status() -> Result<()> {
     let db = db::Database::open()?;
+    let status = sync::get_sync_status(db.connection())?;
+    println!();
+    println!("  ☁️  {}", i18n::t("Sync Status"));
+    println!("  ─────────────");
+    for line in status.lines() {
+        println!("  {}", line);
+    }
+    Ok(())
+}
+
+/// Exits non-zero when anything failed, so it can run from cron
+fn handle_sync(options: &sync::SyncOptions) -> Result<()> {
+    if !auth::is_logged_in() {
+        anyhow::bail!("Not logged in. Run 'sandoro login' first.");
+    }
+    let db = db::Database::open()?;
+
+    if options.dry_run {
+        println!("{}", i18n::t("Checking what a sync would change..."));
+    } else {
+        println!("{}", i18n::t("Syncing with cloud..."));
+    }
+    println!();
+
+    let result = sync::sync(db.connection(), options).context("Sync failed")?;
+    let (uploaded, downloaded) = if options.dry_run {
+        (i18n::t("Would upload:"), i18n::t("Would download:"))
+    } else {
+        (i18n::t("Uploaded:"), i18n::t("Downloaded:"))
+    };
+    let width = i18n::width(uploaded).max(i18n::width(downloaded));
+    if result.errors.is_empty() {
+        println!(
+            "✓ {}",
+            if options.dry_run {
+                i18n::t("Dry run complete")
+            } else {
+                i18n::t("Sync complete!")
+            }
+        );
+    }
+    if options.direction != sync::SyncDirection::Pull {
+        println!(
+            "  {} {}",
+            i18n::pad(uploaded, width),
+            i18n::sessions(result.uploaded)
+        );
+    }
+    if options.direction != sync::SyncDirection::Push {
+        println!(
+            "  {} {}",
+            i18n::pad(downloaded, width),
+            i18n::sessions(result.downloaded)
+        );
+    }
 
-    if status_only {
-        let status = sync::get_sync_status(db.connection())?;
+    if !result.conflicts.is_empty() {
         println!();
-        println!("  ☁️  Sync Status");
-        println!("  ─────────────");
-        for line in status.lines() {
-            println!("  {}", line);
+        println!(
+            "  {}",
+            i18n::fill(
+                "Conflicts ({0} cloud sessions match local ones and weren't downloaded):",
+                &[&result.conflicts.len()]
+            )
+        );
+        for conflict in &result.conflicts {
+            let outcome = match conflict.resolution {
+                Some(sync::Resolution::KeptLocal) => i18n::t("; the local one goes up over it"),
+                Some(sync::Resolution::TookCloud) => i18n::t("; the local one takes its values"),
+                None => "",
+            };
+            println!(
+                "    {}",
+                i18n::fill(
+                    "- {0} finished {1} matches local session #{2}{3}",
+                    &[
+                        &conflict.session_type,
+                        &conflict.completed_at,
+                        &conflict.local_id,
+                        &outcome
+                    ]
+                )
+            );
         }
+    }
+
+    if !result.errors.is_empty() {
+        println!();
+        println!("  {}", i18n::t("Errors:"));
+        for error in &result.errors {
+            println!("    - {}", error);
+        }
+        anyhow::bail!("Sync finished with {} error(s)", result.errors.len());
+    }
+
+    Ok(())
+}
+
+/// Format a UTC timestamp in local time for display
+fn format_local_time(dt: &chrono::DateTime<chrono::Utc>) -> String {
+    dt.with_timezone(&chrono::Local)
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string()
+}
+
+fn handle_search(query: &str, limit: i32) -> Result<()> {
+    privacy::ensure_unlocked()?;
+    let db = db::Database::open_readonly()?;
+    let hits = db.search_sessions(query, limit)?;
+
+    println!();
+    println!(
+        "  🔍 {}",
+        i18n::fill("Sessions matching \"{0}\"", &[&query.trim()])
+    );
+    println!("  ─────────────");
+
+    if hits.is_empty() {
+        println!("     {}", i18n::t("No sessions found."));
+        println!();
         return Ok(());
     }
 
-    if !auth::is_logged_in() {
-        println!("Not logged in. Run 'sandoro login' first.");
+    for hit in &hits {
+        let started = hit.session.started_at.with_timezone(&chrono::Local);
+        let tag = hit
+            .tag
+            .as_ref()
+            .map(|t| t.name.as_str())
+            .unwrap_or(i18n::t("No tag"));
+        println!(
+            "     #{:<5} │ {} │ {:>7} │ {}",
+            hit.session.id,
+            started.format("%Y-%m-%d %H:%M"),
+            format_duration(hit.session.duration_seconds.unwrap_or(0)),
+            tag
+        );
+        if let Some(notes) = hit
+            .session
+            .notes
+            .as_deref()
+            .filter(|n| !n.trim().is_empty())
+        {
+            println!("            {}", notes.trim());
+        }
+    }
+
+    let total: i32 = hits
+        .iter()
+        .filter_map(|hit| hit.session.duration_seconds)
+        .sum();
+    println!();
+    println!(
+        "     {} │ {}",
+        i18n::sessions(hits.len() as i32),
+        format_duration(total)
+    );
+    if hits.len() as i32 == limit {
+        println!(
+            "     {}",
+            i18n::fill(
+                "Showing the {0} most recent; use --limit for more.",
+                &[&limit]
+            )
+        );
+    }
+    println!();
+    Ok(())
+}
+
+/// `stats --notes`: completed sessions with a note containing `text`
+fn show_noted_sessions(db: &db::Database, text: &str) -> Result<()> {
+    let sessions = db.get_filtered_sessions(&db::ExportFilter {
+        notes: Some(text.trim().to_string()),
+        ..db::ExportFilter::default()
+    })?;
+
+    println!();
+    if text.trim().is_empty() {
+        println!("  📝 {}", color::heading(i18n::t("Session notes")));
+    } else {
+        println!(
+            "  📝 {} \"{}\"",
+            color::heading(i18n::t("Session notes with")),
+            text.trim()
+        );
+    }
+    println!("  ─────────────");
+
+    if sessions.is_empty() {
+        println!("     {}", i18n::t("No sessions found."));
+        println!();
         return Ok(());
     }
 
-    println!("Syncing with cloud...");
+    for entry in &sessions {
+        let session = &entry.session;
+        let started = session.started_at.with_timezone(&chrono::Local);
+        let tag = entry
+            .tag
+            .as_ref()
+            .map(|t| t.name.as_str())
+            .unwrap_or("No tag");
+        println!(
+            "     #{:<5} │ {} │ {:>7} │ {}",
+            session.id,
+            started.format("%Y-%m-%d %H:%M"),
+            format_duration(session.duration_seconds.unwrap_or(0)),
+            tag
+        );
+        if let Some(notes) = session.notes.as_deref() {
+            println!("            {}", notes.trim());
+        }
+    }
+
+    let total: i32 = sessions
+        .iter()
+        .filter_map(|entry| entry.session.duration_seconds)
+        .sum();
     println!();
+    println!(
+        "     {} │ {}",
+        i18n::sessions(sessions.len() as i32),
+        format_duration(total)
+    );
+    println!();
+    Ok(())
+}
+
+fn handle_insights() -> Result<()> {
+    let config = Config::load().unwrap_or_default();
+    let db = db::Database::open_readonly()?;
+    let lengths = insights::load(&db)?;
+
+    println!();
+    println!(
+        "  💡 {}",
+        i18n::fill("Insights (last {0} days)", &[&insights::WINDOW_DAYS])
+    );
+    println!("  ─────────────");
+
+    if lengths.is_empty() {
+        println!(
+            "     {}",
+            i18n::t("No finished or abandoned work sessions yet.")
+        );
+        println!();
+        return Ok(());
+    }
+
+    for length in &lengths {
+        let percent = (length.rate() * 100.0).round() as u32;
+        println!(
+            "     {}",
+            i18n::fill(
+                "{0}m │ {1} sessions │ {2}% finished │ {3}",
+                &[
+                    &format!("{:>3}", length.minutes),
+                    &format!("{:>3}", length.attempts),
+                    &format!("{:>3}", percent),
+                    &create_progress_bar(percent, 10)
+                ]
+            )
+        );
+    }
 
-    match sync::sync(db.connection()) {
-        Ok(result) => {
-            println!("✓ Sync complete!");
-            println!("  Uploaded:   {} sessions", result.uploaded);
-            println!("  Downloaded: {} sessions", result.downloaded);
-
-            if !result.errors.is_empty() {
-                println!();
-                println!("  Warnings:");
-                for error in &result.errors {
-                    println!("    - {}", error);
+    println!();
+    let current = config.timer.work_duration;
+    match insights::suggest(&lengths, current) {
+        Some(suggestion) => {
+            let lang = messages::Language::from_str(&config.appearance.language);
+            println!("     💡 {}", suggestion.message(lang));
+        }
+        None if lengths.iter().all(|l| l.attempts < insights::MIN_ATTEMPTS) => println!(
+            "     {}",
+            i18n::fill(
+                "Not enough data yet: {0} sessions of a length are needed.",
+                &[&insights::MIN_ATTEMPTS]
+            )
+        ),
+        None => println!(
+            "     {}",
+            i18n::fill("Your {0}m work length suits you well.", &[&current])
+        ),
+    }
+    println!();
+    Ok(())
+}
+
+/// Days of notes `sandoro journal` lists
+const JOURNAL_LIST_DAYS: i64 = 30;
+
+fn handle_journal(note: Option<&str>, date: Option<&str>, delete: bool) -> Result<()> {
+    let date = date
+        .map(|d| {
+            chrono::NaiveDate::parse_from_str(d, "%Y-%m-%d")
+                .map_err(|_| anyhow::anyhow!("Invalid date '{}'. Use YYYY-MM-DD.", d))
+        })
+        .transpose()?;
+    let day_of = |db: &db::Database| -> Result<String> {
+        Ok(match date {
+            Some(d) => d.format("%Y-%m-%d").to_string(),
+            None => db.today_str()?,
+        })
+    };
+
+    if delete {
+        let db = db::Database::open()?;
+        let day = day_of(&db)?;
+        if db.delete_journal(&day)? {
+            println!(
+                "  {}",
+                i18n::fill("✓ Removed the journal note for {0}", &[&day])
+            );
+        } else {
+            println!("  {}", i18n::fill("No journal note for {0}", &[&day]));
+        }
+        return Ok(());
+    }
+
+    if let Some(note) = note.map(str::trim).filter(|n| !n.is_empty()) {
+        let db = db::Database::open()?;
+        let day = day_of(&db)?;
+        db.set_journal(&day, note)?;
+        println!("  {}", i18n::fill("✓ Journal saved for {0}", &[&day]));
+        return Ok(());
+    }
+
+    privacy::ensure_unlocked()?;
+    let db = db::Database::open_readonly()?;
+    let day = day_of(&db)?;
+    let notes = if date.is_some() {
+        db.get_journal(&day)?
+            .map(|n| vec![(day.clone(), n)])
+            .unwrap_or_default()
+    } else {
+        let since = db.today()? - chrono::Duration::days(JOURNAL_LIST_DAYS - 1);
+        db.get_journal_since(&since.format("%Y-%m-%d").to_string())?
+    };
+
+    println!();
+    println!("  📝 {}", i18n::t("Journal"));
+    println!("  ─────────────");
+    if notes.is_empty() {
+        println!(
+            "     {}",
+            i18n::t("No notes yet. Add one with: sandoro journal \"...\"")
+        );
+    }
+    for (date, note) in &notes {
+        let stats = db.get_date_stats(date)?;
+        println!(
+            "     {} │ {:>8} │ {}",
+            date,
+            format_duration(stats.total_work_seconds),
+            note
+        );
+    }
+    println!();
+    Ok(())
+}
+
+fn handle_review() -> Result<()> {
+    privacy::ensure_unlocked()?;
+    let config = Config::load()?;
+    let db = db::Database::open()?;
+    let today = db.today()?;
+    let review = review::WeekReview::build(&db, &config, today)?;
+
+    let stdin = std::io::stdin();
+    let edited = review::run(
+        &db,
+        &config,
+        &review,
+        today,
+        &mut stdin.lock(),
+        &mut std::io::stdout(),
+    )?;
+    let (before, after) = (&config.goals, &edited.goals);
+    if (before.weekly_sessions, before.weekly_minutes)
+        != (after.weekly_sessions, after.weekly_minutes)
+    {
+        let saved = settings::save(&config, &edited)?;
+        goals::record_week(&db, &saved, today)?;
+        println!("  {}", i18n::t("✓ Goals saved"));
+    }
+    println!();
+    Ok(())
+}
+
+/// Journal notes from the last `days` days, by date
+fn recent_journal(
+    db: &db::Database,
+    days: i64,
+) -> Result<std::collections::HashMap<String, String>> {
+    let since = db.today()? - chrono::Duration::days(days);
+    Ok(db
+        .get_journal_since(&since.format("%Y-%m-%d").to_string())?
+        .into_iter()
+        .collect())
+}
+
+/// Print a day's journal note under a breakdown row
+fn print_journal_note(journal: &std::collections::HashMap<String, String>, date: &str) {
+    if let Some(note) = journal.get(date) {
+        println!("                  📝 {}", privacy::hide(note));
+    }
+}
+
+fn handle_daemon(tag: Option<String>, ephemeral: bool) -> Result<()> {
+    if let Some(running) = status::read()? {
+        anyhow::bail!(
+            "A timer is already running (pid {}). Quit it before starting the daemon.",
+            running.pid
+        );
+    }
+    let config = Config::load()?;
+    let db = if ephemeral {
+        db::Database::open_target(db::DbTarget::Memory)?
+    } else {
+        db::Database::open()?
+    };
+    let tag = match tag {
+        Some(name) => {
+            let existing = db.get_all_tags()?.into_iter().find(|t| t.name == name);
+            match existing {
+                Some(tag) => Some(tag),
+                None => db.get_tag(db.create_tag(&name, None)?)?,
+            }
+        }
+        None => None,
+    };
+    println!(
+        "  ⏱  sandoro daemon running (pid {}). Control it with `sandoro ctl start|pause|skip|status|stop`.",
+        std::process::id()
+    );
+    let mut daemon = daemon::Daemon::new(config, db, tag);
+    // Nothing from a throwaway database goes to the cloud
+    daemon.sync = !ephemeral;
+    daemon::run(daemon)
+}
+
+fn handle_status(format: status_line::Format) -> Result<()> {
+    let running = status::read()?;
+    let is_running = running.is_some();
+    let status = match running {
+        Some(mut status) => {
+            status_line::catch_up(&mut status, chrono::Utc::now());
+            Some(status)
+        }
+        None => status::read_last()?,
+    };
+    // The daemon doesn't mask its tag, so hide it here in private mode
+    let locked = privacy::is_locked();
+    let status = status.map(|mut status| {
+        status.tag = status.tag.map(|t| privacy::mask(&t, locked));
+        status
+    });
+    // Status bars run this every second or so; only JSON opens the database
+    let today = if format == status_line::Format::Json {
+        let config = Config::load().unwrap_or_default();
+        storage::open_readonly(&config)
+            .and_then(|s| s.get_today_stats())
+            .ok()
+    } else {
+        None
+    };
+    let snapshot = status_line::Snapshot {
+        status,
+        running: is_running,
+        today,
+    };
+    println!("{}", status_line::render(&snapshot, format)?);
+    Ok(())
+}
+
+fn handle_ctl(command: CtlCommand) -> Result<()> {
+    let (request, json) = match command {
+        CtlCommand::Start => (daemon::Request::Start, false),
+        CtlCommand::Pause => (daemon::Request::Pause, false),
+        CtlCommand::Skip => (daemon::Request::Skip, false),
+        CtlCommand::Status { json } => (daemon::Request::Status, json),
+        CtlCommand::Stop => (daemon::Request::Stop, false),
+    };
+    let reply = daemon::send(request)?;
+    if json {
+        println!("{}", serde_json::to_string_pretty(&reply.status)?);
+        return Ok(());
+    }
+    if !reply.ok {
+        anyhow::bail!("{}", reply.message);
+    }
+    match (request, reply.status) {
+        (daemon::Request::Status, Some(status)) => {
+            let state = match status.state.as_str() {
+                "short_break" => "Short break",
+                "long_break" => "Long break",
+                "timer" => "Timer",
+                _ => "Work",
+            };
+            let clock = |s: u32| format!("{:02}:{:02}", s / 60, s % 60);
+            let time = if status.remaining_seconds == 0 && status.elapsed_seconds > 0 {
+                format!("{} in", clock(status.elapsed_seconds))
+            } else {
+                format!("{} left", clock(status.remaining_seconds))
+            };
+            let paused = if status.paused { " (paused)" } else { "" };
+            let tag = status.tag.map(|t| format!(" · {}", t)).unwrap_or_default();
+            println!("  ⏱  {}{}, {}{}", state, tag, time, paused);
+        }
+        _ => println!("  ✓ {}", reply.message),
+    }
+    Ok(())
+}
+
+fn handle_lock() -> Result<()> {
+    privacy::set_locked(true)?;
+    // A running timer hides everything right away
+    let _ = control::send(control::Command::Lock);
+    println!("  🔒 Private mode on. Unlock with `sandoro unlock`.");
+    Ok(())
+}
+
+fn handle_unlock() -> Result<()> {
+    if !privacy::is_locked() {
+        println!("  Private mode is already off.");
+        return Ok(());
+    }
+    let config = Config::load().unwrap_or_default();
+    let typed = read_hidden("  Unlock sequence: ")?;
+    if typed != privacy::unlock_sequence(&config) {
+        anyhow::bail!("That isn't the unlock sequence.");
+    }
+    // A running timer notices the lock file is gone
+    privacy::set_locked(false)?;
+    println!("  🔓 Private mode off.");
+    Ok(())
+}
+
+/// Read a line without echoing it when stdin is a terminal
+fn read_hidden(prompt: &str) -> Result<String> {
+    use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+    use crossterm::terminal::{disable_raw_mode, enable_raw_mode};
+    use std::io::{IsTerminal, Write};
+
+    print!("{}", prompt);
+    std::io::stdout().flush()?;
+    if !std::io::stdin().is_terminal() {
+        let mut line = String::new();
+        std::io::stdin().read_line(&mut line)?;
+        println!();
+        return Ok(line.trim().to_string());
+    }
+
+    enable_raw_mode()?;
+    let mut typed = String::new();
+    let result = loop {
+        match event::read() {
+            Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => match key.code {
+                KeyCode::Enter => break Ok(typed),
+                KeyCode::Esc => break Err(anyhow::anyhow!("Cancelled.")),
+                KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    break Err(anyhow::anyhow!("Cancelled."))
+                }
+                KeyCode::Backspace => {
+                    typed.pop();
+                }
+                KeyCode::Char(c) => typed.push(c),
+                _ => {}
+            },
+            Ok(_) => {}
+            Err(e) => break Err(e.into()),
+        }
+    };
+    disable_raw_mode()?;
+    println!();
+    result
+}
+
+fn handle_sessions_merge(first: i64, second: i64) -> Result<()> {
+    let db = db::Database::open()?;
+    let kept = db.merge_sessions(first, second)?;
+    let removed = if kept == first { second } else { first };
+    let session = db
+        .get_session_detail(kept)?
+        .context("Merged session not found")?
+        .session;
+    println!(
+        "  ✓ Merged #{} into #{}: started {}, {}",
+        removed,
+        kept,
+        format_local_time(&session.started_at),
+        format_duration(session.duration_seconds.unwrap_or(0))
+    );
+    Ok(())
+}
+
+fn handle_import(
+    file: &std::path::Path,
+    format: import::Format,
+    rules: &[import::TagRule],
+    dry_run: bool,
+) -> Result<()> {
+    let text = std::fs::read_to_string(file)
+        .with_context(|| format!("Couldn't read {}", file.display()))?;
+    let parsed = import::parse(&text, format, rules)?;
+    let db = db::Database::open()?;
+    let added = db.import_sessions(&parsed.sessions, format.name(), dry_run)?;
+    let duplicates = parsed.sessions.len() - added;
+
+    println!();
+    println!("  📥 Import from {}", format.name());
+    println!("  ─────────────");
+    let verb = if dry_run { "Would import" } else { "Imported" };
+    println!("     {} {} sessions", verb, added);
+    if duplicates > 0 {
+        println!("     Skipped {} already recorded", duplicates);
+    }
+    if !parsed.rejected.is_empty() {
+        println!(
+            "     Skipped {} rows not understood:",
+            parsed.rejected.len()
+        );
+        for (line, reason) in parsed.rejected.iter().take(5) {
+            println!("       line {}: {}", line, reason);
+        }
+        if parsed.rejected.len() > 5 {
+            println!("       …");
+        }
+    }
+    println!();
+    Ok(())
+}
+
+fn handle_sessions_show(id: i64) -> Result<()> {
+    privacy::ensure_unlocked()?;
+    let db = db::Database::open_readonly()?;
+    let detail = db.get_session_detail(id)?;
+    let events = db.get_session_events(id)?;
+
+    println!();
+    println!("  🔎 Session #{}", id);
+    println!("  ─────────────");
+
+    match &detail {
+        Some(d) => {
+            let s = &d.session;
+            let status = if s.completed {
+                "completed"
+            } else if s.ended_at.is_some() {
+                "ended (not completed)"
+            } else if events.iter().any(|e| e.event == "skipped") {
+                "skipped"
+            } else if events.iter().any(|e| e.event == "reset") {
+                "reset"
+            } else {
+                "incomplete"
+            };
+            println!("     Type:      {}", s.session_type);
+            println!("     Status:    {}", status);
+            println!("     Started:   {}", format_local_time(&s.started_at));
+            match &s.ended_at {
+                Some(ended) => {
+                    let wall = (*ended - s.started_at).num_seconds();
+                    println!("     Ended:     {}", format_local_time(ended));
+                    println!("     Wall time: {}s", wall);
                 }
+                None => println!("     Ended:     -"),
+            }
+            match d.planned_seconds {
+                Some(p) => println!("     Planned:   {} ({}s)", format_duration(p), p),
+                None => println!("     Planned:   - (open-ended or recorded before tracking)"),
+            }
+            match s.duration_seconds {
+                Some(a) => println!("     Actual:    {} ({}s)", format_duration(a), a),
+                None => println!("     Actual:    -"),
+            }
+            let paused = d.paused_seconds_at(chrono::Utc::now());
+            if paused > 0 || d.paused_at.is_some() {
+                let still = if d.paused_at.is_some() {
+                    ", paused now"
+                } else {
+                    ""
+                };
+                println!(
+                    "     Paused:    {} ({}s{})",
+                    format_duration(paused),
+                    paused,
+                    still
+                );
+            }
+            println!(
+                "     Tag:       {}",
+                d.tag.as_ref().map(|t| t.name.as_str()).unwrap_or("No tag")
+            );
+            let interruptions = db.get_session_interruptions(id)?;
+            if !interruptions.is_empty() {
+                let times: Vec<String> = interruptions
+                    .iter()
+                    .map(|i| {
+                        let time = i.created_at.with_timezone(&chrono::Local).format("%H:%M");
+                        match i.category {
+                            Some(c) => format!("{} {}", time, c),
+                            None => time.to_string(),
+                        }
+                    })
+                    .collect();
+                println!(
+                    "     Interrupted: {}x ({})",
+                    interruptions.len(),
+                    times.join(", ")
+                );
+            }
+        }
+        None => {
+            if events.is_empty() {
+                println!("     Session not found.");
+                return Ok(());
+            }
+            println!("     Session row no longer exists (see events below).");
+        }
+    }
+
+    // Sync status
+    println!();
+    println!("  ☁️  Sync");
+    if let Some(uuid) = detail.as_ref().and_then(|d| d.uuid.as_deref()) {
+        println!("     UUID:      {}", uuid);
+    }
+    let cloud_id = detail.as_ref().and_then(|d| d.cloud_id.clone());
+    println!(
+        "     Cloud ID:  {}",
+        cloud_id.as_deref().unwrap_or("(not synced)")
+    );
+    if let Some(attempt) = events
+        .iter()
+        .rev()
+        .find(|e| e.event == "synced" || e.event == "sync_failed")
+    {
+        println!(
+            "     Last attempt: {} at {}",
+            attempt.event,
+            format_local_time(&attempt.created_at)
+        );
+        if attempt.event == "sync_failed" {
+            if let Some(error) = &attempt.detail {
+                println!("     Last error:   {}", error);
             }
         }
-        Err(e) => {
-            println!("Error: Sync failed: {}", e);
+    }
+    match sync::get_last_sync(db.connection())? {
+        Some(t) => println!("     Last full sync: {}", format_local_time(&t)),
+        None => println!("     Last full sync: never"),
+    }
+
+    // Event trail
+    println!();
+    println!("  📜 Events");
+    if events.is_empty() {
+        println!("     No events recorded.");
+    } else {
+        for e in &events {
+            println!(
+                "     {} │ {:<12} │ {}",
+                format_local_time(&e.created_at),
+                e.event,
+                e.detail.as_deref().unwrap_or("")
+            );
         }
     }
 
     Ok(())
 }
+
+fn handle_track_status() -> Result<()> {
+    let config = Config::load().unwrap_or_default();
+    let db = db::Database::open()?;
+    let tracking = &config.time_tracking;
+
+    println!();
+    println!("  ⏲  Time Tracking");
+    println!("  ────────────────");
+    match tracking.enabled() {
+        Some(provider) => {
+            println!("     Provider:  {}", provider.as_str());
+            println!("     Workspace: {}", tracking.workspace_id);
+            if let Some(project) = &tracking.default_project {
+                println!("     Project:   {} (default)", project);
+            }
+            for (tag, project) in &tracking.projects {
+                println!("     Project:   {} ← {}", project, tag);
+            }
+        }
+        None => {
+            println!("     Not set up.");
+            println!("     Set [time_tracking] in ~/.sandoro/config.toml to enable it.");
+        }
+    }
+
+    let queue = db.get_time_entry_queue(None)?;
+    println!();
+    if queue.is_empty() {
+        println!("     Nothing queued.");
+    } else {
+        println!("     Queued: {} sessions", queue.len());
+        for entry in &queue {
+            let retry = if entry.dead {
+                format!(
+                    "turned down after {} attempts; `sandoro track flush` retries",
+                    entry.attempts
+                )
+            } else if entry.attempts == 0 {
+                "pending".to_string()
+            } else {
+                format!(
+                    "{} attempts, next {}",
+                    entry.attempts,
+                    format_local_time(&entry.next_attempt_at)
+                )
+            };
+            println!(
+                "     #{:<5} │ {:<8} │ {}",
+                entry.session_id, entry.provider, retry
+            );
+            if let Some(error) = &entry.last_error {
+                println!("            {}", error);
+            }
+        }
+    }
+    println!();
+    Ok(())
+}
+
+fn handle_track_flush() -> Result<()> {
+    let config = Config::load().unwrap_or_default();
+    if config.time_tracking.enabled().is_none() {
+        println!("Time tracking is not set up. Set [time_tracking] in ~/.sandoro/config.toml.");
+        return Ok(());
+    }
+    let db = db::Database::open()?;
+    let result = time_tracking::flush(&db, &config, true)?;
+    let left = db.get_time_entry_queue(None)?.len();
+    println!("  ✓ Sent {} entries", result.sent);
+    if left > 0 {
+        println!("     {} still queued (see `sandoro track status`)", left);
+    }
+    Ok(())
+}
+
+fn handle_db_prune(dry_run: bool) -> Result<()> {
+    let config = Config::load().unwrap_or_default();
+    let db = db::Database::open()?;
+
+    println!();
+    println!("  🧹 Retention");
+    println!("  ────────────");
+
+    let Some(report) = retention::prune(&db, &config, dry_run)? else {
+        println!("     Retention is disabled; all history is kept.");
+        println!("     Set [retention] days in ~/.sandoro/config.toml to enable it.");
+        println!();
+        return Ok(());
+    };
+
+    let mode = match config.retention.mode {
+        config::RetentionMode::Aggregate => "keep daily totals",
+        config::RetentionMode::Delete => "delete",
+    };
+    println!(
+        "     Keeping:  {} days ({})",
+        retention::effective_days(&config),
+        mode
+    );
+    println!("     Cutoff:   before {}", report.cutoff.format("%Y-%m-%d"));
+
+    if report.sessions_removed == 0 {
+        println!("     Nothing to prune.");
+    } else if report.dry_run {
+        println!("     Would remove {} sessions", report.sessions_removed);
+        if report.days_aggregated > 0 {
+            println!("     Would keep totals for {} days", report.days_aggregated);
+        }
+    } else {
+        println!("     Removed {} sessions", report.sessions_removed);
+        if report.days_aggregated > 0 {
+            println!("     Kept totals for {} days", report.days_aggregated);
+        }
+        if let Some(backup) = &report.backup {
+            println!("     Backup:   {}", backup.display());
+        }
+    }
+    println!();
+
+    Ok(())
+}
+
+fn handle_db_audit() -> Result<()> {
+    let db = db::Database::open_readonly()?;
+    let anomalies = audit::audit(&db)?;
+
+    println!();
+    println!("  🩺 Session audit");
+    println!("  ────────────────");
+
+    if anomalies.is_empty() {
+        println!("     No anomalies found.");
+        println!();
+        return Ok(());
+    }
+
+    for anomaly in &anomalies {
+        let s = &anomaly.session;
+        println!(
+            "     #{:<5} {}  {:<11} {}",
+            s.id,
+            format_local_time(&s.started_at),
+            s.session_type,
+            anomaly.problem.describe()
+        );
+    }
+    let sessions: std::collections::HashSet<i64> = anomalies.iter().map(|a| a.session.id).collect();
+    println!();
+    println!(
+        "     {} sessions look wrong. Stats ignore negative durations and",
+        sessions.len()
+    );
+    println!("     sessions in the future; see one with `sandoro sessions show <id>`.");
+    println!();
+
+    Ok(())
+}
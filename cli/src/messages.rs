@@ -1,12 +1,15 @@
 //! Context-aware greeting messages
 //!
 //! Provides friendly messages based on time of day and timer state
-//! Supports multiple languages (ja, en)
+//! Supports multiple languages (ja, en); Spanish, German and Chinese get
+//! their own break suggestions and the English messages otherwise
 //! Messages rotate every 10 seconds for variety
 //! Includes stats-based encouragement and achievement messages
+//! Breaks can show a rotating activity suggestion instead
 
-use chrono::{Local, Timelike};
+use chrono::Timelike;
 
+use crate::clock;
 use crate::timer::TimerState;
 
 /// Language for context messages
@@ -14,16 +17,41 @@ use crate::timer::TimerState;
 pub enum Language {
     Japanese,
     English,
+    Spanish,
+    German,
+    Chinese,
 }
 
 impl Language {
+    pub const ALL: [Language; 5] = [
+        Language::Japanese,
+        Language::English,
+        Language::Spanish,
+        Language::German,
+        Language::Chinese,
+    ];
+
     pub fn from_str(s: &str) -> Self {
         match s.to_lowercase().as_str() {
             "ja" | "japanese" | "日本語" => Language::Japanese,
             "en" | "english" => Language::English,
+            "es" | "spanish" | "español" => Language::Spanish,
+            "de" | "german" | "deutsch" => Language::German,
+            "zh" | "chinese" | "中文" => Language::Chinese,
             _ => Language::Japanese, // Default to Japanese
         }
     }
+
+    /// The `appearance.language` value for this language
+    pub fn code(&self) -> &'static str {
+        match self {
+            Language::Japanese => "ja",
+            Language::English => "en",
+            Language::Spanish => "es",
+            Language::German => "de",
+            Language::Chinese => "zh",
+        }
+    }
 }
 
 /// User statistics for context-aware messages
@@ -40,7 +68,7 @@ pub struct UserStats {
 
 /// Get rotation index based on current time (changes every 10 seconds)
 fn get_rotation_index(max: usize) -> usize {
-    let now = Local::now();
+    let now = clock::now();
     // Combine seconds and minutes for more variety
     let seed = (now.second() / 10) as usize + (now.minute() as usize * 6);
     seed % max
@@ -53,7 +81,7 @@ pub fn get_context_message(
     lang: Language,
     stats: Option<&UserStats>,
 ) -> String {
-    let hour = Local::now().hour();
+    let hour = clock::now().hour();
 
     // State-specific messages take priority
     match state {
@@ -63,7 +91,7 @@ pub fn get_context_message(
         TimerState::LongBreak => {
             return get_long_break_message(lang, stats).to_string();
         }
-        TimerState::Work => {
+        TimerState::Work | TimerState::Timer => {
             if !is_running {
                 // Check for achievement messages first when paused
                 if let Some(s) = stats {
@@ -141,7 +169,7 @@ fn get_achievement_message(stats: &UserStats, lang: Language) -> Option<String>
                 return Some(format!("🏅 最長記録更新！{}日連続！", stats.current_streak));
             }
         }
-        Language::English => {
+        _ => {
             // Session milestones
             if stats.total_sessions == 100 {
                 return Some("🎉 100 sessions! Amazing dedication!".to_string());
@@ -236,7 +264,7 @@ fn get_encouragement_message(stats: &UserStats, lang: Language) -> Option<String
                 return Some(msgs[idx % msgs.len()].clone());
             }
         }
-        Language::English => {
+        _ => {
             // Beating yesterday
             if stats.today_work_seconds > stats.yesterday_seconds && stats.yesterday_seconds > 0 {
                 let diff_min = (stats.today_work_seconds - stats.yesterday_seconds) / 60;
@@ -339,7 +367,7 @@ fn get_time_based_message(hour: u32, lang: Language) -> &'static str {
             }
             _ => "集中していきましょう！",
         },
-        Language::English => match hour {
+        _ => match hour {
             6..=10 => {
                 const MSGS: &[&str] = &[
                     "Good morning! Let's start the day strong.",
@@ -416,7 +444,7 @@ fn get_paused_message(hour: u32, lang: Language, stats: Option<&UserStats>) -> &
                     ];
                     return MSGS[idx % MSGS.len()];
                 }
-                Language::English => {
+                _ => {
                     const MSGS: &[&str] = &[
                         "Keep your streak going! Ready?",
                         "Streak in progress! Start now?",
@@ -478,7 +506,7 @@ fn get_paused_message(hour: u32, lang: Language, stats: Option<&UserStats>) -> &
             }
             _ => "Spaceキーで開始できます",
         },
-        Language::English => match hour {
+        _ => match hour {
             6..=10 => {
                 const MSGS: &[&str] = &[
                     "Ready to start your morning session?",
@@ -544,7 +572,7 @@ fn get_short_break_message(lang: Language, stats: Option<&UserStats>) -> &'stati
                     ];
                     return MSGS[idx % MSGS.len()];
                 }
-                Language::English => {
+                _ => {
                     const MSGS: &[&str] = &[
                         "4+ sessions today! Great job!",
                         "You're on fire! Rest well.",
@@ -572,7 +600,7 @@ fn get_short_break_message(lang: Language, stats: Option<&UserStats>) -> &'stati
             ];
             MSGS[idx % MSGS.len()]
         }
-        Language::English => {
+        _ => {
             const MSGS: &[&str] = &[
                 "Take a breather! Stretch those muscles.",
                 "Quick break! Rest your eyes.",
@@ -606,7 +634,7 @@ fn get_long_break_message(lang: Language, stats: Option<&UserStats>) -> &'static
                     ];
                     return MSGS[idx % MSGS.len()];
                 }
-                Language::English => {
+                _ => {
                     const MSGS: &[&str] = &[
                         "2+ hours done! Enjoy your break!",
                         "Great progress! Rest well.",
@@ -632,7 +660,7 @@ fn get_long_break_message(lang: Language, stats: Option<&UserStats>) -> &'static
             ];
             MSGS[idx % MSGS.len()]
         }
-        Language::English => {
+        _ => {
             const MSGS: &[&str] = &[
                 "Great cycle! Take a well-deserved break.",
                 "Excellent work! Relax and recharge.",
@@ -647,3 +675,101 @@ fn get_long_break_message(lang: Language, stats: Option<&UserStats>) -> &'static
         }
     }
 }
+
+/// A rotating break suggestion ("drink water"), drawn from the built-in
+/// list for `lang` followed by the user's own entries
+pub fn get_break_activity(lang: Language, custom: &[String]) -> String {
+    let builtin = break_suggestions(lang);
+    let custom: Vec<&str> = custom
+        .iter()
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .collect();
+    let idx = get_rotation_index(builtin.len() + custom.len());
+    pick_suggestion(builtin, &custom, idx).to_string()
+}
+
+fn pick_suggestion<'a>(builtin: &[&'a str], custom: &[&'a str], idx: usize) -> &'a str {
+    if idx < builtin.len() {
+        builtin[idx]
+    } else {
+        custom[(idx - builtin.len()) % custom.len()]
+    }
+}
+
+fn break_suggestions(lang: Language) -> &'static [&'static str] {
+    match lang {
+        Language::Japanese => &[
+            "20-20-20：20秒間、6m先を眺めよう",
+            "首をゆっくり回してほぐそう",
+            "水を一杯飲みましょう",
+            "立ち上がって背伸びしよう",
+            "肩を上げて、ストンと下ろそう",
+            "手首と指をストレッチ",
+            "窓を開けて空気を入れ替えよう",
+            "目を閉じて、ゆっくり5回深呼吸",
+        ],
+        Language::English => &[
+            "20-20-20: look 20 ft away for 20 seconds",
+            "Stretch your neck, slowly side to side",
+            "Drink a glass of water",
+            "Stand up and reach for the ceiling",
+            "Shrug your shoulders up, then drop them",
+            "Stretch your wrists and fingers",
+            "Open a window, get some fresh air",
+            "Close your eyes, take 5 slow breaths",
+        ],
+        Language::Spanish => &[
+            "20-20-20: mira a 6 m durante 20 segundos",
+            "Estira el cuello, despacio de lado a lado",
+            "Bebe un vaso de agua",
+            "Ponte de pie y estírate hacia el techo",
+            "Sube los hombros y déjalos caer",
+            "Estira las muñecas y los dedos",
+            "Abre una ventana y toma aire fresco",
+            "Cierra los ojos y respira hondo 5 veces",
+        ],
+        Language::German => &[
+            "20-20-20: 20 Sekunden lang 6 m weit schauen",
+            "Dehne den Nacken, langsam von Seite zu Seite",
+            "Trink ein Glas Wasser",
+            "Steh auf und streck dich zur Decke",
+            "Schultern hochziehen und fallen lassen",
+            "Dehne Handgelenke und Finger",
+            "Öffne ein Fenster, hol frische Luft",
+            "Augen zu, 5 langsame Atemzüge",
+        ],
+        Language::Chinese => &[
+            "20-20-20：看向6米外20秒",
+            "慢慢左右转动脖子",
+            "喝一杯水",
+            "站起来，向上伸展",
+            "耸起肩膀，再放下",
+            "活动一下手腕和手指",
+            "开窗透透气",
+            "闭上眼睛，慢慢深呼吸5次",
+        ],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_break_suggestions_include_custom_entries() {
+        let builtin = break_suggestions(Language::English);
+        let custom = ["Water the plants"];
+        assert_eq!(pick_suggestion(builtin, &custom, 0), builtin[0]);
+        assert_eq!(
+            pick_suggestion(builtin, &custom, builtin.len()),
+            "Water the plants"
+        );
+        for lang in Language::ALL {
+            assert_eq!(break_suggestions(lang).len(), builtin.len());
+        }
+        // Blank entries are left out of the rotation
+        let blank = vec!["  ".to_string()];
+        assert!(builtin.contains(&get_break_activity(Language::English, &blank).as_str()));
+    }
+}
@@ -1,85 +1,486 @@
 //! Notification and sound handling
 //!
 //! Provides desktop notifications and terminal bell for session completion
+//! and the other events in `[notifications.routes]`. Each event is routed on
+//! its own (sound, desktop, both, a silent log line, or nothing), within the
+//! global `sound` and `desktop` switches.
+//!
+//! Session-complete notifications carry buttons (Start break, Skip break,
+//! +5 min) where the platform shows them. A click comes back to the running
+//! timer over a channel as the control command the button stands for.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Sender;
 
+use crate::audio::{self, Tone};
+use crate::config::{Config, NotificationSound, NotificationsConfig, NotifyEvent, Route};
+use crate::control::Command;
 use crate::timer::TimerState;
 
-/// Send a desktop notification
-#[cfg(feature = "notifications")]
-pub fn send_notification(state: TimerState) {
-    use notify_rust::Notification;
+/// Minutes the +5 min button adds to a break
+pub const ACTION_SNOOZE_MINUTES: u32 = 5;
+
+/// A button clicked on a notification: what it asks for, and the phase the
+/// timer was in when the notification went out, so a late click on an old
+/// notification can be told apart
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Clicked {
+    pub command: Command,
+    pub phase: TimerState,
+}
+
+/// Buttons for a notification and where clicks on them go
+#[derive(Debug, Clone)]
+#[cfg_attr(not(feature = "notifications"), allow(dead_code))]
+pub struct Actions {
+    /// The control command each button stands for, and its label
+    pub buttons: Vec<(Command, &'static str)>,
+    pub phase: TimerState,
+    pub sender: Sender<Clicked>,
+}
+
+/// What an event does once its route and the global switches are applied
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Delivery {
+    pub sound: bool,
+    pub desktop: bool,
+    pub log: bool,
+}
+
+/// How `event` is delivered under `config`
+pub fn delivery(config: &NotificationsConfig, event: NotifyEvent) -> Delivery {
+    let route = config.routes.get(event);
+    Delivery {
+        sound: config.sound && route.sound(),
+        desktop: config.desktop && route.desktop(),
+        log: route == Route::Log,
+    }
+}
+
+fn deliver(
+    delivery: Delivery,
+    sound: impl FnOnce(),
+    summary: &str,
+    body: &str,
+    actions: Option<Actions>,
+) {
+    if delivery.sound {
+        sound();
+    }
+    if delivery.desktop {
+        show(summary, body, actions);
+    }
+    if delivery.log {
+        log(summary, body);
+    }
+}
+
+/// Append the event to ~/.sandoro/notifications.log
+fn log(summary: &str, body: &str) {
+    let Ok(dir) = Config::config_dir() else {
+        return;
+    };
+    let _ = std::fs::create_dir_all(&dir);
+    if let Ok(mut file) = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(dir.join("notifications.log"))
+    {
+        let _ = writeln!(
+            file,
+            "{}  {}  {}",
+            chrono::Local::now().format("%Y-%m-%d %H:%M:%S"),
+            summary,
+            body
+        );
+    }
+}
 
-    let (summary, body) = match state {
+/// Title and text of the notification for the end of `state`
+fn session_text(state: TimerState, private: bool) -> (&'static str, &'static str) {
+    match state {
+        // Private mode: nothing about what was going on
+        _ if private => ("sandoro", "Timer finished."),
         TimerState::Work => ("Work Session Complete!", "Time for a break."),
         TimerState::ShortBreak => ("Break Over!", "Ready to get back to work?"),
         TimerState::LongBreak => (
             "Long Break Over!",
             "Feeling refreshed? Time to start a new cycle!",
         ),
+        TimerState::Timer => ("Timer Done!", "Time's up."),
+    }
+}
+
+/// Buttons for the end of `state`. `continues` means the next phase starts
+/// running by itself (auto-start or a routine), so there's nothing to start.
+/// A break can be made longer or skipped; a finished break can be brought
+/// back while work waits to start.
+pub fn session_actions(state: TimerState, continues: bool) -> Vec<(Command, &'static str)> {
+    let snooze = (Command::Snooze(Some(ACTION_SNOOZE_MINUTES)), "+5 min");
+    match state {
+        TimerState::Work if continues => vec![(Command::Skip, "Skip break"), snooze],
+        TimerState::Work => vec![
+            (Command::Start, "Start break"),
+            (Command::Skip, "Skip break"),
+            snooze,
+        ],
+        TimerState::ShortBreak | TimerState::LongBreak if !continues => {
+            vec![(Command::Start, "Start work"), snooze]
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Show the notification. With actions it goes out from a thread of its
+/// own, which waits for a click and sends it back.
+#[cfg(feature = "notifications")]
+fn show(summary: &str, body: &str, actions: Option<Actions>) {
+    let mut notification = notify_rust::Notification::new();
+    notification.summary(summary).body(body).timeout(5000);
+    platform(&mut notification);
+    let Some(actions) = actions.filter(|a| !a.buttons.is_empty()) else {
+        if let Err(e) = notification.show() {
+            eprintln!("Failed to send notification: {}", e);
+        }
+        return;
     };
+    for (command, label) in &actions.buttons {
+        notification.action(&command.to_line(), label);
+    }
+    // Long enough to reach for a button
+    notification.timeout(ACTIONS_TIMEOUT_MS);
+    std::thread::spawn(move || match notification.show() {
+        Ok(handle) => handle.wait_for_action(|action| {
+            // "default" (the body) and "__closed" aren't commands
+            if let Ok(command) = Command::parse(action) {
+                let _ = actions.sender.send(Clicked {
+                    command,
+                    phase: actions.phase,
+                });
+            }
+        }),
+        Err(e) => eprintln!("Failed to send notification: {}", e),
+    });
+}
 
-    if let Err(e) = Notification::new()
-        .summary(summary)
-        .body(body)
-        .appname("sandoro")
-        .timeout(5000)
-        .show()
-    {
-        eprintln!("Failed to send notification: {}", e);
+/// How long a notification with buttons stays up, where that can be set
+#[cfg(feature = "notifications")]
+const ACTIONS_TIMEOUT_MS: i32 = 20_000;
+
+#[cfg(all(feature = "notifications", not(windows)))]
+fn platform(notification: &mut notify_rust::Notification) {
+    notification.appname("sandoro");
+}
+
+/// Windows: a WinRT toast under sandoro's own AppUserModelID, so it's
+/// titled "sandoro" in the Action Center instead of "Windows PowerShell"
+#[cfg(all(feature = "notifications", windows))]
+fn platform(notification: &mut notify_rust::Notification) {
+    if windows::register_app_id() {
+        notification.app_id(windows::APP_ID);
+    }
+}
+
+#[cfg(all(feature = "notifications", windows))]
+mod windows {
+    use std::process::{Command, Stdio};
+    use std::sync::OnceLock;
+
+    /// AppUserModelID the toasts are sent under
+    pub const APP_ID: &str = "Sandoro.Timer";
+
+    /// Register the app id for toasts under HKCU (no installer or Start menu
+    /// shortcut needed). Once per run; false if `reg` failed, in which case
+    /// toasts go out under PowerShell's id.
+    pub fn register_app_id() -> bool {
+        static REGISTERED: OnceLock<bool> = OnceLock::new();
+        *REGISTERED.get_or_init(|| {
+            let key = format!(r"HKCU\Software\Classes\AppUserModelId\{}", APP_ID);
+            Command::new("reg")
+                .args(["add", &key, "/v", "DisplayName", "/t", "REG_SZ"])
+                .args(["/d", "sandoro", "/f"])
+                .stdin(Stdio::null())
+                .stdout(Stdio::null())
+                .stderr(Stdio::null())
+                .status()
+                .map(|s| s.success())
+                .unwrap_or(false)
+        })
     }
 }
 
 /// Fallback when notifications feature is disabled
 #[cfg(not(feature = "notifications"))]
-pub fn send_notification(_state: TimerState) {
+fn show(_summary: &str, _body: &str, _actions: Option<Actions>) {
     // No-op when notifications are disabled
 }
 
-/// Play a terminal bell sound
-#[allow(dead_code)]
+/// Play a terminal bell sound. Windows terminals often drop or mute the
+/// bell, so there it's a short tone through the sound engine instead.
 pub fn play_bell() {
-    // Print ASCII bell character
-    print!("\x07");
+    if cfg!(windows) {
+        audio::play_tones("bell", &[Tone::new(880.0, 150, 0)], 0.4);
+    } else {
+        // Print ASCII bell character
+        print!("\x07");
+    }
 }
 
-/// Play notification sound based on state
-/// Uses terminal bell since Web Audio equivalent isn't available in terminal
-pub fn play_sound(state: TimerState, _volume: f32) {
-    // Different patterns for different states (simulated with multiple bells)
+/// Tones for the end of `state`: three beeps for work, two for a short
+/// break, four (triumphant) for a long break
+fn session_tones(state: TimerState) -> Vec<Tone> {
     match state {
-        TimerState::Work => {
-            // Three beeps for work completion
-            print!("\x07");
-            std::thread::sleep(std::time::Duration::from_millis(200));
-            print!("\x07");
-            std::thread::sleep(std::time::Duration::from_millis(200));
-            print!("\x07");
-        }
-        TimerState::ShortBreak => {
-            // Two beeps for short break
-            print!("\x07");
-            std::thread::sleep(std::time::Duration::from_millis(150));
-            print!("\x07");
-        }
-        TimerState::LongBreak => {
-            // Four beeps for long break (triumphant)
-            print!("\x07");
-            std::thread::sleep(std::time::Duration::from_millis(300));
-            print!("\x07");
-            std::thread::sleep(std::time::Duration::from_millis(150));
-            print!("\x07");
-            std::thread::sleep(std::time::Duration::from_millis(300));
-            print!("\x07");
+        TimerState::Work | TimerState::Timer => vec![Tone::new(660.0, 150, 200); 3],
+        TimerState::ShortBreak => vec![Tone::new(880.0, 150, 150); 2],
+        TimerState::LongBreak => vec![
+            Tone::new(523.25, 150, 300),
+            Tone::new(659.25, 150, 150),
+            Tone::new(783.99, 150, 300),
+            Tone::new(1046.5, 300, 0),
+        ],
+    }
+}
+
+/// Tones of the built-in `sound` for the end of `state`; a file falls back
+/// to the beeps
+fn sound_tones(sound: &NotificationSound, state: TimerState) -> Vec<Tone> {
+    match sound {
+        NotificationSound::Beeps | NotificationSound::File(_) => session_tones(state),
+        // Two long strikes that ring out
+        NotificationSound::Bell => vec![Tone::new(880.0, 900, 150), Tone::new(880.0, 1200, 0)],
+        // Westminster-style descending thirds
+        NotificationSound::Chime => vec![
+            Tone::new(1318.5, 350, 50),
+            Tone::new(1046.5, 350, 50),
+            Tone::new(783.99, 700, 0),
+        ],
+        NotificationSound::Ding => vec![Tone::new(1568.0, 400, 0)],
+    }
+}
+
+/// `~/` at the start of a configured path means the home directory
+fn expand_home(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), dirs::home_dir()) {
+        (Ok(rest), Some(home)) => home.join(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// Play the notification `sound` for the end of `state` through the sound
+/// engine (the terminal bell when no audio player is available). A sound
+/// file that's missing or can't be played falls back to the beeps.
+pub fn play_sound(state: TimerState, sound: &NotificationSound, volume: f32) {
+    if let NotificationSound::File(path) = sound {
+        let path = expand_home(path);
+        if path.is_file() && audio::play_file(&path) {
+            return;
         }
     }
+    let name = match state {
+        TimerState::Work => "work-end",
+        TimerState::ShortBreak => "short-break-end",
+        TimerState::LongBreak => "long-break-end",
+        TimerState::Timer => "timer-end",
+    };
+    audio::play_tones(name, &sound_tones(sound, state), volume);
 }
 
-/// Notify session completion with both sound and desktop notification
-pub fn notify_session_complete(state: TimerState, sound_enabled: bool, desktop_enabled: bool) {
-    if sound_enabled {
-        play_sound(state, 0.5);
+/// Play the cue for the `step`-th progress threshold of a work session.
+///
+/// Each later threshold plays one more note, climbing a pentatonic scale,
+/// so the cues form a growing sequence.
+pub fn play_progress_cue(step: usize) {
+    const NOTES: [f32; 5] = [523.25, 587.33, 659.25, 783.99, 880.0];
+    let tones: Vec<Tone> = NOTES
+        .iter()
+        .take(step + 1)
+        .map(|&f| Tone::new(f, 120, 60))
+        .collect();
+    audio::play_tones(&format!("cue-{}", step), &tones, 0.3);
+}
+
+/// Notify session completion with `sound`, routed as work or break end.
+/// `private` keeps the notification's text generic. `actions` puts buttons
+/// on the desktop notification.
+pub fn notify_session_complete(
+    state: TimerState,
+    sound: &NotificationSound,
+    delivery: Delivery,
+    private: bool,
+    actions: Option<Actions>,
+) {
+    let (summary, body) = session_text(state, private);
+    deliver(
+        delivery,
+        || play_sound(state, sound, 0.5),
+        summary,
+        body,
+        actions,
+    );
+}
+
+/// Warn that the running session took `tag` past its daily budget.
+/// `private` leaves the tag out.
+pub fn notify_over_budget(tag: &str, budget: &str, delivery: Delivery, private: bool) {
+    if private {
+        deliver(
+            delivery,
+            play_bell,
+            "sandoro",
+            "A tag is over its daily budget.",
+            None,
+        );
+    } else {
+        deliver(
+            delivery,
+            play_bell,
+            "Over budget",
+            &format!("{} is past its {} for today.", tag, budget),
+            None,
+        );
     }
-    if desktop_enabled {
-        send_notification(state);
+}
+
+/// The session just recorded met today's goal
+pub fn notify_goal_reached(delivery: Delivery) {
+    deliver(
+        delivery,
+        || play_progress_cue(4),
+        "Daily goal reached!",
+        "That's today's goal done.",
+        None,
+    );
+}
+
+/// The daily goal isn't met by the reminder time; `left` is what's still to
+/// do, e.g. "2 sessions and 40 min"
+pub fn notify_goal_reminder(left: &str, delivery: Delivery) {
+    deliver(
+        delivery,
+        || play_progress_cue(1),
+        "Daily goal not met yet",
+        &format!("{} to go for today's goal.", left),
+        None,
+    );
+}
+
+/// XP took the total to a new level, e.g. "Lv 3 Apprentice"
+pub fn notify_level_up(badge: &str, delivery: Delivery) {
+    deliver(
+        delivery,
+        || play_progress_cue(2),
+        "Level up!",
+        &format!("You reached {}.", badge),
+        None,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_tones_keep_beep_counts() {
+        assert_eq!(session_tones(TimerState::Work).len(), 3);
+        assert_eq!(session_tones(TimerState::ShortBreak).len(), 2);
+        assert_eq!(session_tones(TimerState::LongBreak).len(), 4);
+    }
+
+    #[test]
+    fn test_builtin_sounds_differ_from_beeps() {
+        let beeps = session_tones(TimerState::Work);
+        for sound in [
+            NotificationSound::Bell,
+            NotificationSound::Chime,
+            NotificationSound::Ding,
+        ] {
+            assert_ne!(sound_tones(&sound, TimerState::Work), beeps);
+            // The same sound ends work and breaks
+            assert_eq!(
+                sound_tones(&sound, TimerState::Work),
+                sound_tones(&sound, TimerState::LongBreak)
+            );
+        }
+        let file = NotificationSound::File(PathBuf::from("/missing.wav"));
+        assert_eq!(sound_tones(&file, TimerState::Work), beeps);
+    }
+
+    #[test]
+    fn test_sound_names_and_paths_from_config() {
+        let config: NotificationsConfig = toml::from_str("sound_name = \"Chime\"").unwrap();
+        assert_eq!(config.sound_name, NotificationSound::Chime);
+        let config: NotificationsConfig =
+            toml::from_str("sound_name = \"~/sounds/gong.ogg\"").unwrap();
+        assert_eq!(
+            config.sound_name,
+            NotificationSound::File(PathBuf::from("~/sounds/gong.ogg"))
+        );
+        assert_eq!(config.sound_name.label_with_lang("en"), "gong.ogg");
+        assert!(toml::to_string(&config)
+            .unwrap()
+            .contains("sound_name = \"~/sounds/gong.ogg\""));
+        assert_eq!(NotificationSound::default().next(), NotificationSound::Bell);
+    }
+
+    #[test]
+    fn test_session_actions_fit_what_comes_next() {
+        let commands = |state, continues| -> Vec<Command> {
+            session_actions(state, continues)
+                .into_iter()
+                .map(|(command, _)| command)
+                .collect()
+        };
+        let snooze = Command::Snooze(Some(ACTION_SNOOZE_MINUTES));
+        assert_eq!(
+            commands(TimerState::Work, false),
+            vec![Command::Start, Command::Skip, snooze]
+        );
+        // The break is already running
+        assert_eq!(
+            commands(TimerState::Work, true),
+            vec![Command::Skip, snooze]
+        );
+        assert_eq!(
+            commands(TimerState::LongBreak, false),
+            vec![Command::Start, snooze]
+        );
+        assert!(commands(TimerState::ShortBreak, true).is_empty());
+        assert!(commands(TimerState::Timer, false).is_empty());
+        // Button ids are control lines, so a click parses back
+        for (command, _) in session_actions(TimerState::Work, false) {
+            assert_eq!(Command::parse(&command.to_line()).unwrap(), command);
+        }
+    }
+
+    #[test]
+    fn test_routes_apply_within_global_switches() {
+        let mut config = NotificationsConfig::default();
+        config.routes.break_end = Route::Desktop;
+        config.routes.goal_reached = Route::Sound;
+
+        let work = delivery(&config, NotifyEvent::WorkEnd);
+        assert!(work.sound && work.desktop && !work.log);
+        let brk = delivery(&config, NotifyEvent::BreakEnd);
+        assert!(!brk.sound && brk.desktop);
+        let level = delivery(&config, NotifyEvent::Achievement);
+        assert_eq!(
+            level,
+            Delivery {
+                log: true,
+                ..Default::default()
+            }
+        );
+
+        config.sound = false;
+        assert_eq!(
+            delivery(&config, NotifyEvent::GoalReached),
+            Delivery::default()
+        );
+        config.routes.over_budget = Route::Off;
+        assert_eq!(
+            delivery(&config, NotifyEvent::OverBudget),
+            Delivery::default()
+        );
     }
 }
@@ -0,0 +1,476 @@
+//! Postgres storage backend (`postgres` feature)
+//!
+//! Keeps sessions, tags, their event trail and metadata in a self-hosted
+//! Postgres database instead of ~/.sandoro/data.db. The tables mirror the
+//! SQLite ones; they're created on first connect and brought up to date
+//! once per `SCHEMA_VERSION`, which is kept in the metadata table. Dates are
+//! grouped in UTC; `[days] policy` only applies to SQLite.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use postgres::{Client, NoTls, Row};
+use std::cell::RefCell;
+use std::collections::HashSet;
+
+use crate::db::{
+    streak_from_dates, DailyStats, Session, SessionMode, SessionType, StreakInfo, Tag,
+};
+use crate::storage::Storage;
+
+/// Version of `SCHEMA`; bump when it changes so existing databases get it
+const SCHEMA_VERSION: i32 = 2;
+/// Metadata key holding the schema version a database is at
+const SCHEMA_VERSION_KEY: &str = "pg_schema_version";
+
+/// The whole schema; safe to run again on a database that has any earlier
+/// version of it
+const SCHEMA: &str = r#"
+CREATE TABLE IF NOT EXISTS tags (
+    id BIGSERIAL PRIMARY KEY,
+    name TEXT NOT NULL UNIQUE,
+    color TEXT
+);
+
+CREATE TABLE IF NOT EXISTS sessions (
+    id BIGSERIAL PRIMARY KEY,
+    started_at TIMESTAMPTZ NOT NULL,
+    ended_at TIMESTAMPTZ,
+    duration_seconds INTEGER,
+    type TEXT NOT NULL CHECK (type IN ('work', 'short_break', 'long_break', 'timer')),
+    completed BOOLEAN NOT NULL DEFAULT FALSE,
+    tag_id BIGINT REFERENCES tags(id) ON DELETE SET NULL,
+    planned_seconds INTEGER
+);
+
+ALTER TABLE sessions ADD COLUMN IF NOT EXISTS mode TEXT NOT NULL DEFAULT 'classic';
+ALTER TABLE sessions DROP CONSTRAINT IF EXISTS sessions_type_check;
+ALTER TABLE sessions ADD CONSTRAINT sessions_type_check
+    CHECK (type IN ('work', 'short_break', 'long_break', 'timer'));
+
+CREATE TABLE IF NOT EXISTS session_events (
+    id BIGSERIAL PRIMARY KEY,
+    session_id BIGINT NOT NULL,
+    event TEXT NOT NULL,
+    detail TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE TABLE IF NOT EXISTS metadata (
+    key TEXT PRIMARY KEY,
+    value TEXT NOT NULL
+);
+
+-- Completed work with its UTC date, skipping clock-jump leftovers
+-- (negative durations, sessions dated more than a day ahead)
+CREATE OR REPLACE VIEW work_sessions AS
+    SELECT id, tag_id,
+           (started_at AT TIME ZONE 'UTC')::date AS date,
+           GREATEST(COALESCE(duration_seconds, 0), 0) AS seconds
+    FROM sessions
+    WHERE type IN ('work', 'timer') AND completed AND started_at <= now() + interval '1 day';
+
+CREATE INDEX IF NOT EXISTS idx_sessions_started ON sessions(started_at);
+CREATE INDEX IF NOT EXISTS idx_session_events_session ON session_events(session_id);
+"#;
+
+/// Today's UTC date, the day sessions are grouped under
+const TODAY: &str = "(now() AT TIME ZONE 'UTC')::date";
+
+pub struct PgStorage {
+    client: RefCell<Client>,
+}
+
+impl PgStorage {
+    /// Connect and create or update the tables if needed
+    pub fn connect(url: &str) -> Result<Self> {
+        if url.is_empty() {
+            anyhow::bail!("Set [storage] url to the Postgres connection string.");
+        }
+        let mut client = Client::connect(url, NoTls).context("Could not connect to Postgres")?;
+        migrate(&mut client)?;
+        Ok(Self {
+            client: RefCell::new(client),
+        })
+    }
+
+    /// Schema version the database is at; 0 before the first connect
+    fn schema_version(client: &mut impl postgres::GenericClient) -> Result<i32> {
+        let row = client.query_opt(
+            "SELECT value FROM metadata WHERE key = $1",
+            &[&SCHEMA_VERSION_KEY],
+        )?;
+        Ok(row
+            .and_then(|row| row.get::<_, String>(0).parse().ok())
+            .unwrap_or(0))
+    }
+
+    fn totals(
+        &self,
+        filter: &str,
+        params: &[&(dyn postgres::types::ToSql + Sync)],
+    ) -> Result<(i32, i32)> {
+        let sql = format!(
+            "SELECT COALESCE(SUM(seconds), 0)::int, COUNT(*)::int FROM work_sessions WHERE {}",
+            filter
+        );
+        let row = self.client.borrow_mut().query_one(sql.as_str(), params)?;
+        Ok((row.get(0), row.get(1)))
+    }
+}
+
+/// Run `SCHEMA` unless the database is already at `SCHEMA_VERSION`. Other
+/// clients connecting at the same time wait for it on an advisory lock.
+fn migrate(client: &mut Client) -> Result<()> {
+    client.batch_execute(
+        "CREATE TABLE IF NOT EXISTS metadata (
+             key TEXT PRIMARY KEY,
+             value TEXT NOT NULL
+         )",
+    )?;
+    if PgStorage::schema_version(client)? >= SCHEMA_VERSION {
+        return Ok(());
+    }
+
+    let mut tx = client.transaction()?;
+    tx.execute(
+        "SELECT pg_advisory_xact_lock(hashtext('sandoro schema'))",
+        &[],
+    )?;
+    if PgStorage::schema_version(&mut tx)? < SCHEMA_VERSION {
+        tx.batch_execute(SCHEMA)?;
+        tx.execute(
+            "INSERT INTO metadata (key, value) VALUES ($1, $2)
+             ON CONFLICT (key) DO UPDATE SET value = excluded.value",
+            &[&SCHEMA_VERSION_KEY, &SCHEMA_VERSION.to_string()],
+        )?;
+    }
+    tx.commit()?;
+    Ok(())
+}
+
+fn tag_from(row: &Row, first: usize) -> Option<Tag> {
+    let id: Option<i64> = row.get(first);
+    id.map(|id| Tag {
+        id,
+        name: row.get(first + 1),
+        color: row.get(first + 2),
+    })
+}
+
+fn stats(date: String, (total_work_seconds, sessions_completed): (i32, i32)) -> DailyStats {
+    DailyStats {
+        date,
+        total_work_seconds,
+        sessions_completed,
+        longest_streak: 0,
+    }
+}
+
+impl Storage for PgStorage {
+    fn start_session_in_mode(
+        &self,
+        session_type: SessionType,
+        tag_id: Option<i64>,
+        planned_seconds: Option<i32>,
+        mode: SessionMode,
+    ) -> Result<i64> {
+        let row = self.client.borrow_mut().query_one(
+            "INSERT INTO sessions (started_at, type, completed, tag_id, planned_seconds, mode)
+             VALUES (now(), $1, FALSE, $2, $3, $4) RETURNING id",
+            &[
+                &session_type.as_str(),
+                &tag_id,
+                &planned_seconds,
+                &mode.as_str(),
+            ],
+        )?;
+        let id: i64 = row.get(0);
+        let detail = planned_seconds.map(|p| format!("planned {}s", p));
+        self.log_session_event(id, "started", detail.as_deref())?;
+        Ok(id)
+    }
+
+    fn complete_session(&self, session_id: i64, duration_seconds: i32) -> Result<()> {
+        self.client.borrow_mut().execute(
+            "UPDATE sessions SET ended_at = now(), duration_seconds = $1, completed = TRUE
+             WHERE id = $2",
+            &[&duration_seconds.max(0), &session_id],
+        )?;
+        let detail = format!("duration {}s", duration_seconds);
+        self.log_session_event(session_id, "completed", Some(&detail))
+    }
+
+    fn interrupt_session(
+        &self,
+        session_id: i64,
+        ended_at: DateTime<Utc>,
+        duration_seconds: i32,
+    ) -> Result<()> {
+        self.client.borrow_mut().execute(
+            "UPDATE sessions SET ended_at = $1, duration_seconds = $2, completed = FALSE
+             WHERE id = $3",
+            &[&ended_at, &duration_seconds.max(0), &session_id],
+        )?;
+        let detail = format!("duration {}s", duration_seconds);
+        self.log_session_event(session_id, "interrupted", Some(&detail))
+    }
+
+    fn hand_off_session(
+        &self,
+        session_id: i64,
+        ended_at: DateTime<Utc>,
+        duration_seconds: i32,
+        to: &str,
+    ) -> Result<()> {
+        self.client.borrow_mut().execute(
+            "UPDATE sessions SET ended_at = $1, duration_seconds = $2, completed = FALSE
+             WHERE id = $3",
+            &[&ended_at, &duration_seconds.max(0), &session_id],
+        )?;
+        let detail = format!("to {}", to);
+        self.log_session_event(session_id, "handed_off", Some(&detail))
+    }
+
+    fn log_session_event(&self, session_id: i64, event: &str, detail: Option<&str>) -> Result<()> {
+        self.client.borrow_mut().execute(
+            "INSERT INTO session_events (session_id, event, detail) VALUES ($1, $2, $3)",
+            &[&session_id, &event, &detail],
+        )?;
+        Ok(())
+    }
+
+    fn delete_session(&self, session_id: i64) -> Result<()> {
+        self.client
+            .borrow_mut()
+            .execute("DELETE FROM sessions WHERE id = $1", &[&session_id])?;
+        self.log_session_event(session_id, "deleted", None)
+    }
+
+    fn update_session_tag(&self, session_id: i64, tag_id: Option<i64>) -> Result<()> {
+        self.client.borrow_mut().execute(
+            "UPDATE sessions SET tag_id = $1 WHERE id = $2",
+            &[&tag_id, &session_id],
+        )?;
+        Ok(())
+    }
+
+    fn get_recent_sessions(&self, limit: i32) -> Result<Vec<(Session, Option<Tag>)>> {
+        let rows = self.client.borrow_mut().query(
+            "SELECT s.id, s.started_at, s.ended_at, s.duration_seconds, s.type, s.completed,
+                    t.id, t.name, t.color
+             FROM sessions s LEFT JOIN tags t ON s.tag_id = t.id
+             WHERE s.type IN ('work', 'timer') AND s.completed
+             ORDER BY s.started_at DESC
+             LIMIT $1",
+            &[&i64::from(limit)],
+        )?;
+        Ok(rows
+            .iter()
+            .map(|row| {
+                let session = Session {
+                    id: row.get(0),
+                    started_at: row.get(1),
+                    ended_at: row.get(2),
+                    duration_seconds: row.get(3),
+                    session_type: row.get(4),
+                    completed: row.get(5),
+                    notes: None,
+                };
+                (session, tag_from(row, 6))
+            })
+            .collect())
+    }
+
+    fn get_all_tags(&self) -> Result<Vec<Tag>> {
+        let rows = self
+            .client
+            .borrow_mut()
+            .query("SELECT id, name, color FROM tags ORDER BY name", &[])?;
+        Ok(rows.iter().filter_map(|row| tag_from(row, 0)).collect())
+    }
+
+    fn create_tag(&self, name: &str, color: Option<&str>) -> Result<i64> {
+        let row = self.client.borrow_mut().query_one(
+            "INSERT INTO tags (name, color) VALUES ($1, $2) RETURNING id",
+            &[&name, &color],
+        )?;
+        Ok(row.get(0))
+    }
+
+    fn delete_tag(&self, tag_id: i64) -> Result<()> {
+        // Sessions keep running untagged (ON DELETE SET NULL)
+        self.client
+            .borrow_mut()
+            .execute("DELETE FROM tags WHERE id = $1", &[&tag_id])?;
+        Ok(())
+    }
+
+    fn get_date_stats(&self, date: &str) -> Result<DailyStats> {
+        let totals = self.totals("date = $1::text::date", &[&date])?;
+        Ok(stats(date.to_string(), totals))
+    }
+
+    fn get_range_stats(&self, start: &str, end: &str) -> Result<DailyStats> {
+        let totals = self.totals(
+            "date >= $1::text::date AND date < $2::text::date",
+            &[&start, &end],
+        )?;
+        Ok(stats(start.to_string(), totals))
+    }
+
+    fn get_daily_stats(&self, days: i32) -> Result<Vec<DailyStats>> {
+        let sql = format!(
+            "SELECT date::text, SUM(seconds)::int, COUNT(*)::int
+             FROM work_sessions
+             WHERE date >= ({} - $1::int)
+             GROUP BY date
+             ORDER BY date DESC",
+            TODAY
+        );
+        let rows = self.client.borrow_mut().query(sql.as_str(), &[&days])?;
+        Ok(rows
+            .iter()
+            .map(|row| stats(row.get(0), (row.get(1), row.get(2))))
+            .collect())
+    }
+
+    fn get_today_stats_by_tag(&self) -> Result<Vec<(Option<Tag>, i32)>> {
+        let sql = format!(
+            "SELECT SUM(w.seconds)::int AS total, t.id, t.name, t.color
+             FROM work_sessions w LEFT JOIN tags t ON w.tag_id = t.id
+             WHERE w.date = {}
+             GROUP BY t.id, t.name, t.color
+             ORDER BY total DESC",
+            TODAY
+        );
+        let rows = self.client.borrow_mut().query(sql.as_str(), &[])?;
+        Ok(rows
+            .iter()
+            .map(|row| (tag_from(row, 1), row.get(0)))
+            .collect())
+    }
+
+    fn get_streak(&self) -> Result<StreakInfo> {
+        let rows = self
+            .client
+            .borrow_mut()
+            .query("SELECT DISTINCT date::text FROM work_sessions", &[])?;
+        let dates: HashSet<String> = rows.iter().map(|row| row.get(0)).collect();
+        Ok(streak_from_dates(&dates, self.today()?))
+    }
+
+    fn get_metadata(&self, key: &str) -> Result<Option<String>> {
+        let row = self
+            .client
+            .borrow_mut()
+            .query_opt("SELECT value FROM metadata WHERE key = $1", &[&key])?;
+        Ok(row.map(|row| row.get(0)))
+    }
+
+    fn set_metadata(&self, key: &str, value: &str) -> Result<()> {
+        self.client.borrow_mut().execute(
+            "INSERT INTO metadata (key, value) VALUES ($1, $2)
+             ON CONFLICT (key) DO UPDATE SET value = excluded.value",
+            &[&key, &value],
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Runs against a scratch database named by SANDORO_TEST_POSTGRES_URL;
+    /// skipped when it isn't set
+    #[test]
+    fn test_records_and_reports() {
+        let Ok(url) = std::env::var("SANDORO_TEST_POSTGRES_URL") else {
+            return;
+        };
+        let pg = PgStorage::connect(&url).unwrap();
+        pg.client
+            .borrow_mut()
+            .batch_execute("TRUNCATE sessions, tags, session_events, metadata RESTART IDENTITY")
+            .unwrap();
+
+        let tag = pg.create_tag("writing", Some("blue")).unwrap();
+        let id = pg
+            .start_session_in_mode(
+                SessionType::Work,
+                Some(tag),
+                Some(1500),
+                SessionMode::Classic,
+            )
+            .unwrap();
+        pg.complete_session(id, 1500).unwrap();
+        let skipped = pg
+            .start_session_in_mode(SessionType::Work, None, Some(1500), SessionMode::Flowtime)
+            .unwrap();
+        pg.interrupt_session(skipped, Utc::now(), 300).unwrap();
+
+        let today = pg.get_today_stats().unwrap();
+        assert_eq!(
+            (today.total_work_seconds, today.sessions_completed),
+            (1500, 1)
+        );
+        assert_eq!(pg.get_week_stats().unwrap().sessions_completed, 1);
+        assert_eq!(pg.get_streak().unwrap().current, 1);
+        assert_eq!(pg.get_heatmap_data(1).unwrap().len(), 7);
+        let by_tag = pg.get_today_stats_by_tag().unwrap();
+        assert_eq!(by_tag[0].0.as_ref().map(|t| t.id), Some(tag));
+
+        pg.delete_tag(tag).unwrap();
+        let recent = pg.get_recent_sessions(5).unwrap();
+        assert_eq!(recent.len(), 1);
+        assert!(recent[0].1.is_none());
+
+        pg.set_metadata("last_tag_id", "1").unwrap();
+        pg.set_metadata("last_tag_id", "2").unwrap();
+        assert_eq!(
+            pg.get_metadata("last_tag_id").unwrap().as_deref(),
+            Some("2")
+        );
+        assert_eq!(pg.get_metadata("missing").unwrap(), None);
+    }
+
+    #[test]
+    fn test_schema_runs_once_per_version() {
+        let Ok(url) = std::env::var("SANDORO_TEST_POSTGRES_URL") else {
+            return;
+        };
+        let pg = PgStorage::connect(&url).unwrap();
+        let mut client = pg.client.borrow_mut();
+        assert_eq!(
+            PgStorage::schema_version(&mut *client).unwrap(),
+            SCHEMA_VERSION
+        );
+        // Connecting again leaves the constraint alone
+        let constraint = |client: &mut Client| -> u32 {
+            client
+                .query_one(
+                    "SELECT oid FROM pg_constraint WHERE conname = 'sessions_type_check'",
+                    &[],
+                )
+                .unwrap()
+                .get(0)
+        };
+        let before = constraint(&mut client);
+        PgStorage::connect(&url).unwrap();
+        assert_eq!(constraint(&mut client), before);
+
+        // An older database is brought up to date
+        client
+            .execute(
+                "UPDATE metadata SET value = '1' WHERE key = $1",
+                &[&SCHEMA_VERSION_KEY],
+            )
+            .unwrap();
+        PgStorage::connect(&url).unwrap();
+        assert_eq!(
+            PgStorage::schema_version(&mut *client).unwrap(),
+            SCHEMA_VERSION
+        );
+        assert_ne!(constraint(&mut client), before);
+    }
+}
@@ -0,0 +1,208 @@
+//! Weekly plans: a budget of work sessions spread across tags
+//!
+//! `sandoro plan set writing 8` plans eight writing sessions for the week
+//! (weeks start on `goals.week_start`). The timer shows planned against
+//! done for each tag, and once a week is over it gets a grade for how much
+//! of the plan was carried out.
+
+use anyhow::{bail, Result};
+use chrono::{Duration, NaiveDate};
+
+use crate::config::Config;
+use crate::db::{Database, Tag};
+use crate::goals;
+
+/// Most sessions that can be planned for one tag in a week
+pub const MAX_SESSIONS: u32 = 100;
+
+/// One tag's share of the plan
+#[derive(Debug, Clone, PartialEq)]
+pub struct PlanItem {
+    pub tag: Tag,
+    pub planned: u32,
+    /// Completed work sessions with the tag this week
+    pub done: i32,
+}
+
+impl PlanItem {
+    /// Done sessions that count towards the plan (going over doesn't)
+    fn counted(&self) -> u32 {
+        (self.done.max(0) as u32).min(self.planned)
+    }
+}
+
+/// Plan for one week and how it's going
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeekPlan {
+    pub start: NaiveDate,
+    /// Largest plan first
+    pub items: Vec<PlanItem>,
+}
+
+impl WeekPlan {
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn planned(&self) -> u32 {
+        self.items.iter().map(|i| i.planned).sum()
+    }
+
+    pub fn done(&self) -> i32 {
+        self.items.iter().map(|i| i.done).sum()
+    }
+
+    /// Share of the plan carried out, 0-100; sessions over a tag's plan
+    /// don't make up for another tag
+    pub fn percent(&self) -> u32 {
+        match self.planned() {
+            0 => 0,
+            planned => self.items.iter().map(|i| i.counted()).sum::<u32>() * 100 / planned,
+        }
+    }
+
+    pub fn grade(&self) -> Grade {
+        Grade::from_percent(self.percent())
+    }
+
+    /// "writing 3/8 · code 2/4", for the timer
+    pub fn summary(&self) -> String {
+        self.items
+            .iter()
+            .map(|i| format!("{} {}/{}", i.tag.name, i.done, i.planned))
+            .collect::<Vec<_>>()
+            .join(" · ")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Grade {
+    A,
+    B,
+    C,
+    D,
+    F,
+}
+
+impl Grade {
+    pub fn from_percent(percent: u32) -> Self {
+        match percent {
+            90.. => Grade::A,
+            75..=89 => Grade::B,
+            50..=74 => Grade::C,
+            25..=49 => Grade::D,
+            _ => Grade::F,
+        }
+    }
+
+    pub fn letter(&self) -> char {
+        match self {
+            Grade::A => 'A',
+            Grade::B => 'B',
+            Grade::C => 'C',
+            Grade::D => 'D',
+            Grade::F => 'F',
+        }
+    }
+}
+
+fn day(date: NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+/// First day of the week `weeks_ago` weeks before the one containing `today`
+pub fn week_of(config: &Config, today: NaiveDate, weeks_ago: i64) -> NaiveDate {
+    goals::week_start(today, config.goals.week_start_day()) - Duration::weeks(weeks_ago)
+}
+
+pub fn load(db: &Database, start: NaiveDate) -> Result<WeekPlan> {
+    let end = start + Duration::days(7);
+    let items = db
+        .get_plan(&day(start), &day(end))?
+        .into_iter()
+        .map(|(tag, planned, done)| PlanItem { tag, planned, done })
+        .collect();
+    Ok(WeekPlan { start, items })
+}
+
+/// Plan `sessions` for the tag named `tag` in the week starting `start`;
+/// zero takes the tag out of the plan
+pub fn set(db: &Database, start: NaiveDate, tag: &str, sessions: u32) -> Result<()> {
+    if sessions > MAX_SESSIONS {
+        bail!("Plan at most {} sessions per tag", MAX_SESSIONS);
+    }
+    let Some(tag) = db.get_all_tags()?.into_iter().find(|t| t.name == tag) else {
+        bail!(
+            "No tag named '{}'. Add it in the timer's settings first.",
+            tag
+        );
+    };
+    db.set_plan_item(&day(start), tag.id, sessions)
+}
+
+pub fn clear(db: &Database, start: NaiveDate) -> Result<usize> {
+    db.clear_plan(&day(start))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::SessionType;
+
+    fn item(name: &str, planned: u32, done: i32) -> PlanItem {
+        PlanItem {
+            tag: Tag {
+                id: 0,
+                name: name.to_string(),
+                color: None,
+            },
+            planned,
+            done,
+        }
+    }
+
+    #[test]
+    fn test_grade_counts_each_tag_up_to_its_plan() {
+        let plan = WeekPlan {
+            start: NaiveDate::from_ymd_opt(2026, 10, 12).unwrap(),
+            items: vec![item("writing", 8, 12), item("code", 4, 0)],
+        };
+        assert_eq!(plan.planned(), 12);
+        assert_eq!(plan.done(), 12);
+        // Extra writing doesn't cover the code that wasn't done
+        assert_eq!(plan.percent(), 66);
+        assert_eq!(plan.grade(), Grade::C);
+        assert_eq!(plan.summary(), "writing 12/8 · code 0/4");
+        assert_eq!(Grade::from_percent(100), Grade::A);
+        assert_eq!(Grade::from_percent(10), Grade::F);
+    }
+
+    #[test]
+    fn test_plan_is_stored_per_week_and_counts_tagged_work() {
+        let db = Database::open_in_memory().unwrap();
+        let writing = db.create_tag("writing", None).unwrap();
+        db.create_tag("code", None).unwrap();
+        let config = Config::default();
+        let today = db.today().unwrap();
+        let this_week = week_of(&config, today, 0);
+
+        set(&db, this_week, "writing", 5).unwrap();
+        set(&db, this_week, "code", 2).unwrap();
+        set(&db, week_of(&config, today, 1), "code", 9).unwrap();
+        assert!(set(&db, this_week, "nope", 1).is_err());
+
+        let id = db
+            .start_session_with_tag(SessionType::Work, Some(writing), Some(1500))
+            .unwrap();
+        db.complete_session(id, 1500).unwrap();
+
+        let plan = load(&db, this_week).unwrap();
+        assert_eq!(plan.summary(), "writing 1/5 · code 0/2");
+
+        set(&db, this_week, "code", 0).unwrap();
+        assert_eq!(load(&db, this_week).unwrap().items.len(), 1);
+        assert_eq!(clear(&db, this_week).unwrap(), 1);
+        assert!(load(&db, this_week).unwrap().is_empty());
+        assert_eq!(load(&db, week_of(&config, today, 1)).unwrap().planned(), 9);
+    }
+}
@@ -0,0 +1,78 @@
+//! Power source detection
+//!
+//! Used by low-power mode to slow the TUI down while a laptop runs on battery.
+
+/// Whether the machine is running on battery.
+///
+/// Returns None when the power source can't be determined (desktops, VMs,
+/// unsupported platforms), which callers treat as mains power.
+pub fn on_battery() -> Option<bool> {
+    detect()
+}
+
+#[cfg(target_os = "linux")]
+fn detect() -> Option<bool> {
+    let entries = std::fs::read_dir("/sys/class/power_supply").ok()?;
+    let mut battery = None;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let read = |name: &str| {
+            std::fs::read_to_string(path.join(name))
+                .map(|s| s.trim().to_string())
+                .unwrap_or_default()
+        };
+        match read("type").as_str() {
+            // Any adapter plugged in means we're not on battery
+            "Mains" if read("online") == "1" => return Some(false),
+            // Skip peripheral batteries such as wireless mice
+            "Battery" if read("scope") != "Device" => {
+                let discharging = read("status") == "Discharging";
+                battery = Some(battery.unwrap_or(false) || discharging);
+            }
+            _ => {}
+        }
+    }
+    battery
+}
+
+#[cfg(target_os = "macos")]
+fn detect() -> Option<bool> {
+    let output = std::process::Command::new("pmset")
+        .args(["-g", "batt"])
+        .output()
+        .ok()?;
+    parse_pmset(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Parse `pmset -g batt`, whose first line is e.g. "Now drawing from 'Battery Power'"
+#[cfg(any(target_os = "macos", test))]
+fn parse_pmset(output: &str) -> Option<bool> {
+    let first = output.lines().next()?;
+    if first.contains("'Battery Power'") {
+        Some(true)
+    } else if first.contains("'AC Power'") {
+        Some(false)
+    } else {
+        None
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+fn detect() -> Option<bool> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pmset() {
+        assert_eq!(
+            parse_pmset("Now drawing from 'Battery Power'\n -InternalBattery-0 85%; discharging"),
+            Some(true)
+        );
+        assert_eq!(parse_pmset("Now drawing from 'AC Power'\n"), Some(false));
+        assert_eq!(parse_pmset(""), None);
+    }
+}
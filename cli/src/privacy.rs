@@ -0,0 +1,141 @@
+//! Private mode: a quick lock for screen-sharing and pairing
+//!
+//! `p` in the timer (or `sandoro lock`) hides tag names, notes and session
+//! history: the TUI and `sandoro stats` show asterisks instead, commands
+//! that list history refuse to run, and desktop notifications say nothing
+//! about the session. Typing `[privacy] unlock_sequence` in the timer (or
+//! answering `sandoro unlock`) brings everything back. The lock is a marker
+//! file, so it holds across restarts and for commands run in other panes.
+
+use anyhow::{bail, Result};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::config::Config;
+
+/// What hidden text is shown as; the same width whatever it hides
+pub const MASK: &str = "*****";
+
+/// Sequence used when `[privacy] unlock_sequence` is empty or has spaces
+pub const DEFAULT_UNLOCK_SEQUENCE: &str = "unlock";
+
+static LOCKED: AtomicBool = AtomicBool::new(false);
+
+fn path() -> Result<PathBuf> {
+    Ok(Config::config_dir()?.join("private.lock"))
+}
+
+/// Whether the lock is on, read from disk
+pub fn is_locked() -> bool {
+    path().is_ok_and(|p| p.exists())
+}
+
+/// Turn the lock on or off for every sandoro process
+pub fn set_locked(locked: bool) -> Result<()> {
+    let path = path()?;
+    if locked {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(&path, "")?;
+    } else if path.exists() {
+        std::fs::remove_file(&path)?;
+    }
+    LOCKED.store(locked, Ordering::Relaxed);
+    Ok(())
+}
+
+/// Read the lock once, at startup, for command output
+pub fn init() {
+    LOCKED.store(is_locked(), Ordering::Relaxed);
+}
+
+/// `text`, or the mask when `locked`
+pub fn mask(text: &str, locked: bool) -> String {
+    if locked {
+        MASK.to_string()
+    } else {
+        text.to_string()
+    }
+}
+
+/// `text` as command output shows it: masked while the lock is on
+pub fn hide(text: &str) -> String {
+    mask(text, LOCKED.load(Ordering::Relaxed))
+}
+
+/// Stop commands that would print history while the lock is on
+pub fn ensure_unlocked() -> Result<()> {
+    if LOCKED.load(Ordering::Relaxed) {
+        bail!("History is hidden while private mode is on. Unlock it with `sandoro unlock`.");
+    }
+    Ok(())
+}
+
+/// The configured unlock sequence, or the default when it can't be typed
+pub fn unlock_sequence(config: &Config) -> &str {
+    let sequence = config.privacy.unlock_sequence.as_str();
+    if sequence.is_empty() || sequence.chars().any(char::is_whitespace) {
+        DEFAULT_UNLOCK_SEQUENCE
+    } else {
+        sequence
+    }
+}
+
+/// Keys typed while locked, kept just long enough to spot the sequence
+#[derive(Debug, Default)]
+pub struct Unlocker {
+    typed: String,
+}
+
+impl Unlocker {
+    /// Add a typed character; true once the last ones spell `sequence`
+    pub fn push(&mut self, c: char, sequence: &str) -> bool {
+        self.typed.push(c);
+        let keep = sequence.chars().count();
+        let extra = self.typed.chars().count().saturating_sub(keep);
+        self.typed = self.typed.chars().skip(extra).collect();
+        if self.typed == sequence {
+            self.typed.clear();
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.typed.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unlock_sequence_is_found_among_other_keys() {
+        let mut unlocker = Unlocker::default();
+        let typed: Vec<bool> = "xxunlounlock"
+            .chars()
+            .map(|c| unlocker.push(c, "unlock"))
+            .collect();
+        assert_eq!(typed.iter().filter(|&&done| done).count(), 1);
+        assert!(typed[typed.len() - 1]);
+
+        unlocker.push('u', "unlock");
+        unlocker.clear();
+        assert!(!"nlock".chars().any(|c| unlocker.push(c, "unlock")));
+    }
+
+    #[test]
+    fn test_sequence_falls_back_when_it_cannot_be_typed() {
+        let mut config = Config::default();
+        assert_eq!(unlock_sequence(&config), DEFAULT_UNLOCK_SEQUENCE);
+        config.privacy.unlock_sequence = "open sesame".to_string();
+        assert_eq!(unlock_sequence(&config), DEFAULT_UNLOCK_SEQUENCE);
+        config.privacy.unlock_sequence = "42x".to_string();
+        assert_eq!(unlock_sequence(&config), "42x");
+        assert_eq!(mask("writing", true), MASK);
+        assert_eq!(mask("writing", false), "writing");
+    }
+}
@@ -0,0 +1,242 @@
+//! Recovery of sessions left open by a crash
+//!
+//! A session row stays open (no end time, not completed) when the timer dies
+//! mid-session. Rows from versions without an event trail are left alone. On the next start the TUI lists those rows and asks what to
+//! do with each: complete it with an estimated duration, keep it as
+//! interrupted, or discard it.
+
+use anyhow::{bail, Result};
+use chrono::{DateTime, Duration, Utc};
+
+use crate::db::{Database, SessionDetail};
+use crate::status::LiveStatus;
+
+/// How long a session probably ran
+#[derive(Debug, Clone, PartialEq)]
+pub struct Estimate {
+    pub seconds: i32,
+    pub ended_at: DateTime<Utc>,
+    /// Taken from the dead timer's last status rather than the plan
+    pub from_status: bool,
+}
+
+/// A session a previous run left open
+#[derive(Debug, Clone)]
+pub struct OpenSession {
+    pub detail: SessionDetail,
+    /// None for open-ended (flowtime) sessions with no status to go on
+    pub estimate: Option<Estimate>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecoveryAction {
+    /// Count it, with the estimated duration
+    Complete,
+    /// Keep it as interrupted; it doesn't count toward stats
+    Interrupt,
+    Discard,
+}
+
+/// Estimate a session's duration from the last status its timer published,
+/// or from the plan when the timer never got to publish one
+pub fn estimate(
+    detail: &SessionDetail,
+    last_status: Option<&LiveStatus>,
+    now: DateTime<Utc>,
+) -> Option<Estimate> {
+    let started_at = detail.session.started_at;
+
+    if let Some(status) = last_status.filter(|s| s.session_id == Some(detail.session.id)) {
+        let seconds = match detail.planned_seconds {
+            Some(planned) => (planned - status.remaining_seconds as i32).clamp(0, planned),
+            None => status.elapsed_seconds as i32,
+        };
+        return Some(Estimate {
+            seconds,
+            ended_at: status.updated_at,
+            from_status: true,
+        });
+    }
+
+    // Assume the session ran to the end if there was time for it, leaving
+    // out the time it was paused
+    let planned = detail.planned_seconds?;
+    let paused = i64::from(detail.paused_seconds_at(now));
+    let wall = ((now - started_at).num_seconds() - paused).clamp(0, planned as i64) as i32;
+    Some(Estimate {
+        seconds: wall,
+        ended_at: started_at + Duration::seconds(wall as i64 + paused),
+        from_status: false,
+    })
+}
+
+/// Open sessions left behind by previous runs
+pub fn find(db: &Database, last_status: Option<&LiveStatus>) -> Result<Vec<OpenSession>> {
+    let now = Utc::now();
+    Ok(db
+        .get_open_sessions()?
+        .into_iter()
+        .map(|detail| OpenSession {
+            estimate: estimate(&detail, last_status, now),
+            detail,
+        })
+        .collect())
+}
+
+/// Apply the chosen action to an open session
+pub fn resolve(db: &Database, open: &OpenSession, action: RecoveryAction) -> Result<()> {
+    let id = open.detail.session.id;
+    match (action, &open.estimate) {
+        (RecoveryAction::Complete, Some(estimate)) => {
+            db.recover_session(id, estimate.ended_at, estimate.seconds)
+        }
+        (RecoveryAction::Complete, None) => {
+            bail!("Session #{} has no duration to complete it with", id)
+        }
+        (RecoveryAction::Interrupt, Some(estimate)) => {
+            db.interrupt_session(id, estimate.ended_at, estimate.seconds)
+        }
+        (RecoveryAction::Interrupt, None) => {
+            db.interrupt_session(id, open.detail.session.started_at, 0)
+        }
+        (RecoveryAction::Discard, _) => db.delete_session(id),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::SessionType;
+
+    fn status(session_id: i64, remaining: u32, elapsed: u32) -> LiveStatus {
+        LiveStatus {
+            pid: 1,
+            state: "work".to_string(),
+            paused: false,
+            remaining_seconds: remaining,
+            elapsed_seconds: elapsed,
+            session_active: true,
+            session_number: 1,
+            cycle_position: 1,
+            cycle_length: 4,
+            tag: None,
+            session_id: Some(session_id),
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn open_session(db: &Database, planned: Option<i32>) -> SessionDetail {
+        let id = db
+            .start_session_with_tag(SessionType::Work, None, planned)
+            .unwrap();
+        db.get_session_detail(id).unwrap().unwrap()
+    }
+
+    #[test]
+    fn test_estimate_from_last_status() {
+        let db = Database::open_in_memory().unwrap();
+        let detail = open_session(&db, Some(1500));
+        let last = status(detail.session.id, 600, 0);
+        let from_status = estimate(&detail, Some(&last), Utc::now()).unwrap();
+        assert_eq!(from_status.seconds, 900);
+        assert_eq!(from_status.ended_at, last.updated_at);
+        assert!(from_status.from_status);
+
+        // Flowtime counts up
+        let flow = open_session(&db, None);
+        let last = status(flow.session.id, 0, 2400);
+        assert_eq!(
+            estimate(&flow, Some(&last), Utc::now()).unwrap().seconds,
+            2400
+        );
+    }
+
+    #[test]
+    fn test_estimate_without_status_uses_plan() {
+        let db = Database::open_in_memory().unwrap();
+        let detail = open_session(&db, Some(1500));
+        let started = detail.session.started_at;
+
+        // A status for another session is ignored
+        let other = status(detail.session.id + 1, 0, 0);
+        let later = estimate(&detail, Some(&other), started + Duration::hours(3)).unwrap();
+        assert_eq!(later.seconds, 1500);
+        assert_eq!(later.ended_at, started + Duration::seconds(1500));
+        assert!(!later.from_status);
+
+        let soon = estimate(&detail, None, started + Duration::seconds(300)).unwrap();
+        assert_eq!(soon.seconds, 300);
+
+        assert_eq!(estimate(&open_session(&db, None), None, Utc::now()), None);
+    }
+
+    #[test]
+    fn test_estimate_without_status_leaves_out_pauses() {
+        let db = Database::open_in_memory().unwrap();
+        let detail = open_session(&db, Some(1500));
+        let id = detail.session.id;
+        let started = detail.session.started_at;
+        db.pause_session(id, started + Duration::minutes(5))
+            .unwrap();
+        db.unpause_session(id, started + Duration::minutes(15))
+            .unwrap();
+        let detail = db.get_session_detail(id).unwrap().unwrap();
+        let at = started + Duration::minutes(20);
+        assert_eq!(estimate(&detail, None, at).unwrap().seconds, 600);
+
+        // Crashed while paused: only the time before the pause counts
+        db.pause_session(id, at).unwrap();
+        let detail = db.get_session_detail(id).unwrap().unwrap();
+        let later = estimate(&detail, None, started + Duration::hours(3)).unwrap();
+        assert_eq!(later.seconds, 600);
+        assert_eq!(later.ended_at, started + Duration::hours(3));
+    }
+
+    #[test]
+    fn test_find_and_resolve() {
+        let db = Database::open_in_memory().unwrap();
+        let crashed = open_session(&db, Some(1500));
+        let interrupted = open_session(&db, Some(1500));
+        let discarded = open_session(&db, Some(1500));
+        let skipped = open_session(&db, Some(1500));
+        db.log_session_event(skipped.session.id, "skipped", None)
+            .unwrap();
+        // Left open by a version that kept no event trail
+        db.connection()
+            .execute(
+                "INSERT INTO sessions (started_at, type, completed) VALUES (?1, 'work', FALSE)",
+                [Utc::now().to_rfc3339()],
+            )
+            .unwrap();
+
+        let open = find(&db, None).unwrap();
+        let ids: Vec<i64> = open.iter().map(|o| o.detail.session.id).collect();
+        assert_eq!(
+            ids,
+            vec![
+                crashed.session.id,
+                interrupted.session.id,
+                discarded.session.id
+            ]
+        );
+
+        resolve(&db, &open[0], RecoveryAction::Complete).unwrap();
+        resolve(&db, &open[1], RecoveryAction::Interrupt).unwrap();
+        resolve(&db, &open[2], RecoveryAction::Discard).unwrap();
+        assert!(find(&db, None).unwrap().is_empty());
+
+        let completed = db.get_session_detail(crashed.session.id).unwrap().unwrap();
+        assert!(completed.session.completed);
+        assert!(completed.session.ended_at.is_some());
+        let kept = db
+            .get_session_detail(interrupted.session.id)
+            .unwrap()
+            .unwrap();
+        assert!(!kept.session.completed);
+        assert!(kept.session.ended_at.is_some());
+        assert!(db
+            .get_session_detail(discarded.session.id)
+            .unwrap()
+            .is_none());
+    }
+}
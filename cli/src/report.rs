@@ -0,0 +1,442 @@
+//! Standalone HTML report
+//!
+//! `sandoro report --html out.html` writes the last 30 days as one HTML
+//! file with no outside requests: summary figures, the heatmap, charts of
+//! daily and hourly focus, time per tag, goal bars and the journal notes
+//! written in those days. Colors come from
+//! `appearance.theme` and `appearance.accent`, so the page looks like the
+//! timer. Figures are the same ones `sandoro stats --json` reports.
+
+use anyhow::Result;
+use chrono::NaiveDate;
+
+use crate::config::Config;
+use crate::db::Database;
+use crate::i18n;
+use crate::stats_json::{self, Goal, HourBucket, Period, StatsDocument};
+use crate::svg::{self, escape, hex};
+use crate::theme::{self, Theme};
+
+/// Days the report covers
+pub const DAYS: usize = 30;
+/// Weeks of context in the heatmap
+const HEATMAP_WEEKS: i32 = 12;
+
+const CHART_WIDTH: u32 = 640;
+const CHART_HEIGHT: u32 = 120;
+
+/// Colors the page is drawn in
+#[derive(Debug, Clone, PartialEq)]
+pub struct Palette {
+    pub background: (u8, u8, u8),
+    pub foreground: (u8, u8, u8),
+    pub muted: (u8, u8, u8),
+    pub accent: (u8, u8, u8),
+    /// Rainbow accent: bars take turns through the rainbow heatmap colors
+    pub rainbow: bool,
+}
+
+impl Palette {
+    pub fn from_config(config: &Config) -> Self {
+        let appearance = &config.appearance;
+        let theme = Theme::by_name(&appearance.theme);
+        Self {
+            background: theme.background.to_rgb(),
+            foreground: theme.foreground.to_rgb(),
+            muted: theme.secondary.to_rgb(),
+            accent: svg::accent_rgb(&appearance.accent),
+            rainbow: appearance.accent == "rainbow",
+        }
+    }
+
+    /// Color of the `index`th bar in a chart
+    fn bar(&self, index: usize) -> String {
+        if self.rainbow {
+            hex(theme::rainbow_heatmap_rgb(index % 4 + 1))
+        } else {
+            hex(self.accent)
+        }
+    }
+}
+
+/// Render the report for `db`
+pub fn build(db: &Database, config: &Config) -> Result<String> {
+    // Whole weeks, so the daily series covers the report's days
+    let doc = stats_json::build(db, config, None, (DAYS as i32 + 6) / 7)?;
+    let heatmap = svg::heatmap(
+        &db.get_heatmap_data(HEATMAP_WEEKS)?,
+        &config.appearance.accent,
+    );
+    let today = db.today()?;
+    let first = today - chrono::Duration::days(DAYS as i64 - 1);
+    let journal = db.get_journal_since(&first.format("%Y-%m-%d").to_string())?;
+    Ok(render(
+        &doc,
+        &heatmap,
+        &journal,
+        &Palette::from_config(config),
+        today,
+    ))
+}
+
+/// Render the report from its figures; `journal` is (date, note), newest
+/// first
+pub fn render(
+    doc: &StatsDocument,
+    heatmap: &str,
+    journal: &[(String, String)],
+    palette: &Palette,
+    today: NaiveDate,
+) -> String {
+    let days = &doc.daily[doc.daily.len().saturating_sub(DAYS)..];
+    let total = &doc.last_30_days;
+    let first = days
+        .first()
+        .and_then(|d| NaiveDate::parse_from_str(&d.label, "%Y-%m-%d").ok())
+        .unwrap_or(today);
+    let average = total.focus_seconds / DAYS as i32;
+    let best = days.iter().max_by_key(|d| d.focus_seconds);
+
+    let mut body = String::new();
+    body.push_str(&format!(
+        "<header><h1>sandoro</h1><p>{} – {}</p></header>\n",
+        i18n::month_day(first),
+        i18n::month_day(today)
+    ));
+
+    body.push_str("<section class=\"figures\">\n");
+    for (label, value) in [
+        (i18n::t("Focus"), i18n::duration(total.focus_seconds)),
+        (i18n::t("Sessions"), total.sessions.to_string()),
+        (i18n::t("Per day"), i18n::duration(average)),
+        (i18n::t("Streak"), i18n::days(doc.streak.current)),
+        (
+            i18n::t("Best day"),
+            best.filter(|d| d.focus_seconds > 0)
+                .map(|d| format!("{} · {}", d.label, i18n::duration(d.focus_seconds)))
+                .unwrap_or_else(|| "–".to_string()),
+        ),
+    ] {
+        body.push_str(&format!(
+            "<div><span>{}</span><strong>{}</strong></div>\n",
+            label,
+            escape(&value)
+        ));
+    }
+    body.push_str("</section>\n");
+
+    section(
+        &mut body,
+        i18n::t("Activity"),
+        &format!("<div class=\"heatmap\">{}</div>", heatmap),
+    );
+    section(
+        &mut body,
+        i18n::t("Daily focus"),
+        &daily_chart(days, palette),
+    );
+    section(
+        &mut body,
+        i18n::t("Time of day"),
+        &hour_chart(&doc.by_hour, palette),
+    );
+
+    let tags = tag_rows(doc, palette);
+    if !tags.is_empty() {
+        section(&mut body, i18n::t("Tags"), &tags);
+    }
+    let goals = goal_rows(doc, palette);
+    if !goals.is_empty() {
+        section(&mut body, i18n::t("Goals"), &goals);
+    }
+    if !journal.is_empty() {
+        section(&mut body, i18n::t("Journal"), &journal_rows(journal));
+    }
+
+    let trend = &doc.trend;
+    body.push_str(&format!(
+        "<footer>{} {}/{} {} · {} {}/{} {}</footer>\n",
+        i18n::t("7-day avg"),
+        i18n::duration((trend.avg_7d * 60.0).round() as i32),
+        i18n::t("day"),
+        trend.trend_7d.arrow(),
+        i18n::t("28-day avg"),
+        i18n::duration((trend.avg_28d * 60.0).round() as i32),
+        i18n::t("day"),
+        trend.trend_28d.arrow(),
+    ));
+
+    format!(
+        r#"<!DOCTYPE html>
+<html lang="{lang}">
+<head>
+<meta charset="utf-8">
+<meta name="viewport" content="width=device-width, initial-scale=1">
+<title>sandoro {title}</title>
+<style>
+body {{ margin: 0 auto; max-width: 720px; padding: 32px 24px; background: {bg}; color: {fg}; font: 15px/1.5 -apple-system, "Segoe UI", Helvetica, Arial, sans-serif; }}
+h1 {{ margin: 0; color: {accent}; font-size: 28px; }}
+h2 {{ margin: 32px 0 12px; font-size: 16px; border-bottom: 1px solid {muted}; padding-bottom: 4px; }}
+header p, footer, .figures span, .row small {{ color: {muted}; }}
+.figures {{ display: flex; flex-wrap: wrap; gap: 12px; margin-top: 24px; }}
+.figures div {{ flex: 1 1 120px; padding: 12px; border: 1px solid {muted}; border-radius: 8px; }}
+.figures span {{ display: block; font-size: 12px; }}
+.figures strong {{ font-size: 18px; }}
+.heatmap {{ overflow-x: auto; }}
+.row {{ display: grid; grid-template-columns: 140px 1fr 120px; gap: 12px; align-items: center; margin: 6px 0; }}
+.bar {{ height: 10px; border-radius: 5px; background: {muted}33; overflow: hidden; }}
+.bar div {{ height: 100%; border-radius: 5px; }}
+.note {{ margin: 6px 0; }}
+.note small {{ display: inline-block; min-width: 100px; color: {muted}; }}
+footer {{ margin-top: 32px; font-size: 13px; }}
+svg {{ max-width: 100%; height: auto; }}
+</style>
+</head>
+<body>
+{body}</body>
+</html>
+"#,
+        lang = i18n::language().code(),
+        title = i18n::t("report"),
+        bg = hex(palette.background),
+        fg = hex(palette.foreground),
+        muted = hex(palette.muted),
+        accent = hex(palette.accent),
+        body = body
+    )
+}
+
+/// One line per journal note, oldest first
+fn journal_rows(journal: &[(String, String)]) -> String {
+    journal
+        .iter()
+        .rev()
+        .map(|(date, note)| {
+            format!(
+                "<p class=\"note\"><small>{}</small>{}</p>",
+                escape(date),
+                escape(note)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn section(body: &mut String, title: &str, content: &str) {
+    body.push_str(&format!(
+        "<section>\n<h2>{}</h2>\n{}\n</section>\n",
+        title, content
+    ));
+}
+
+/// One bar per day, scaled to the busiest day
+fn daily_chart(days: &[Period], palette: &Palette) -> String {
+    let most = days
+        .iter()
+        .map(|d| d.focus_seconds)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    let pitch = CHART_WIDTH / days.len().max(1) as u32;
+    let mut svg = chart_start();
+    for (i, day) in days.iter().enumerate() {
+        let height = (day.focus_seconds.max(0) as u32 * (CHART_HEIGHT - 16)) / most as u32;
+        svg.push_str(&format!(
+            r#"<rect x="{}" y="{}" width="{}" height="{}" rx="2" fill="{}"><title>{}: {}</title></rect>"#,
+            i as u32 * pitch,
+            CHART_HEIGHT - 14 - height,
+            pitch.saturating_sub(2).max(1),
+            height,
+            palette.bar(i),
+            escape(&day.label),
+            i18n::duration(day.focus_seconds)
+        ));
+    }
+    if let (Some(first), Some(last)) = (days.first(), days.last()) {
+        svg.push_str(&format!(
+            r#"<text x="0" y="{y}">{}</text><text x="{}" y="{y}" text-anchor="end">{}</text>"#,
+            escape(&first.label),
+            CHART_WIDTH,
+            escape(&last.label),
+            y = CHART_HEIGHT
+        ));
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+/// One bar per hour of the day
+fn hour_chart(hours: &[HourBucket], palette: &Palette) -> String {
+    let most = hours
+        .iter()
+        .map(|h| h.focus_seconds)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    let pitch = CHART_WIDTH / 24;
+    let mut svg = chart_start();
+    for bucket in hours {
+        let height = (bucket.focus_seconds.max(0) as u32 * (CHART_HEIGHT - 16)) / most as u32;
+        svg.push_str(&format!(
+            r#"<rect x="{}" y="{}" width="{}" height="{}" rx="2" fill="{}"><title>{:02}:00: {}</title></rect>"#,
+            bucket.hour * pitch,
+            CHART_HEIGHT - 14 - height,
+            pitch - 4,
+            height,
+            palette.bar(bucket.hour as usize),
+            bucket.hour,
+            i18n::duration(bucket.focus_seconds)
+        ));
+    }
+    for hour in [0, 6, 12, 18] {
+        svg.push_str(&format!(
+            r#"<text x="{}" y="{}">{:02}:00</text>"#,
+            hour * pitch,
+            CHART_HEIGHT,
+            hour
+        ));
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+fn chart_start() -> String {
+    format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-size="10" fill="currentColor">"#,
+        w = CHART_WIDTH,
+        h = CHART_HEIGHT
+    )
+}
+
+/// A labelled bar, `percent` of the way across
+fn bar_row(label: &str, percent: u32, color: &str, note: &str) -> String {
+    format!(
+        "<div class=\"row\"><span>{}</span><div class=\"bar\"><div style=\"width: {}%; background: {}\"></div></div><small>{}</small></div>\n",
+        escape(label),
+        percent.min(100),
+        color,
+        escape(note)
+    )
+}
+
+/// Time per tag, against the tag with the most
+fn tag_rows(doc: &StatsDocument, palette: &Palette) -> String {
+    let most = doc
+        .by_tag
+        .iter()
+        .map(|t| t.focus_seconds)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    doc.by_tag
+        .iter()
+        .enumerate()
+        .map(|(i, bucket)| {
+            let name = bucket
+                .tag
+                .clone()
+                .unwrap_or_else(|| i18n::t("No tag").to_string());
+            bar_row(
+                &name,
+                (bucket.focus_seconds.max(0) as i64 * 100 / i64::from(most)) as u32,
+                &palette.bar(i),
+                &format!(
+                    "{} · {}",
+                    i18n::duration(bucket.focus_seconds),
+                    i18n::sessions(bucket.sessions)
+                ),
+            )
+        })
+        .collect()
+}
+
+/// Bars for the goals that are set, and how recent weeks went
+fn goal_rows(doc: &StatsDocument, palette: &Palette) -> String {
+    let goals = &doc.goals;
+    let accent = hex(palette.accent);
+    let minutes = |g: &Goal| format!("{}/{}", i18n::minutes(g.current), i18n::minutes(g.target));
+    let sessions = |g: &Goal| format!("{}/{}", g.current, i18n::sessions(g.target));
+    let mut rows = String::new();
+    for (label, goal, note) in [
+        (
+            i18n::t("Today · time"),
+            &goals.daily_minutes,
+            minutes(&goals.daily_minutes),
+        ),
+        (
+            i18n::t("Today · sessions"),
+            &goals.daily_sessions,
+            sessions(&goals.daily_sessions),
+        ),
+        (
+            i18n::t("Week · time"),
+            &goals.weekly_minutes,
+            minutes(&goals.weekly_minutes),
+        ),
+        (
+            i18n::t("Week · sessions"),
+            &goals.weekly_sessions,
+            sessions(&goals.weekly_sessions),
+        ),
+    ] {
+        if let Some(percent) = goal.percent {
+            rows.push_str(&bar_row(label, percent, &accent, &note));
+        }
+    }
+    if !goals.weekly_history.is_empty() {
+        let weeks: Vec<String> = goals
+            .weekly_history
+            .iter()
+            .map(|w| format!("{} {}", if w.met { "✓" } else { "✗" }, w.week_start))
+            .collect();
+        rows.push_str(&format!("<p><small>{}</small></p>\n", weeks.join(" · ")));
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::SessionType;
+
+    #[test]
+    fn test_report_shows_figures_tags_and_goals_in_theme_colors() {
+        let db = Database::open_in_memory().unwrap();
+        let tag = db.create_tag("R&D <lab>", None).unwrap();
+        let id = db
+            .start_session_with_tag(SessionType::Work, Some(tag), Some(1500))
+            .unwrap();
+        db.complete_session(id, 1500).unwrap();
+
+        let mut config = Config::default();
+        config.appearance.accent = "green".to_string();
+        config.goals.daily_minutes = 50;
+        let html = build(&db, &config).unwrap();
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        // Standalone: nothing loaded from elsewhere
+        assert!(!html.contains("<script") && !html.contains("<link") && !html.contains("src="));
+        assert!(html.contains("R&amp;D &lt;lab&gt;"));
+        assert!(html.contains(&hex(theme::heatmap_accent_rgb("green"))));
+        assert!(html.contains("<title>sandoro focus activity</title>"));
+        // Half of the 50-minute daily goal
+        assert!(html.contains("width: 50%"));
+        assert!(html.contains("25m/50m"));
+        assert!(!html.contains("class=\"note\""));
+
+        db.set_journal(&db.today_str().unwrap(), "shipped <the> parser")
+            .unwrap();
+        let html = build(&db, &config).unwrap();
+        assert!(html.contains("shipped &lt;the&gt; parser"));
+    }
+
+    #[test]
+    fn test_rainbow_bars_take_turns() {
+        let palette = Palette {
+            rainbow: true,
+            ..Palette::from_config(&Config::default())
+        };
+        assert_eq!(palette.bar(0), hex(theme::rainbow_heatmap_rgb(1)));
+        assert_eq!(palette.bar(5), hex(theme::rainbow_heatmap_rgb(2)));
+    }
+}
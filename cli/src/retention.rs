@@ -0,0 +1,227 @@
+//! Data retention
+//!
+//! Prunes raw session history older than the configured retention period,
+//! backing up the database first and optionally keeping daily totals.
+//! Ages are counted in the days stats use: the cutoff comes from
+//! `Database::today` and is compared with each session's day.
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Local, NaiveDate, Utc};
+use std::path::PathBuf;
+
+use crate::config::{Config, RetentionMode};
+use crate::db::Database;
+
+/// Metadata key holding the last scheduled prune time
+const LAST_PRUNE_KEY: &str = "last_retention_prune";
+/// Minimum time between scheduled prunes
+const PRUNE_INTERVAL_HOURS: i64 = 24;
+/// Number of pre-prune backups to keep
+const MAX_BACKUPS: usize = 5;
+
+/// Outcome of a prune run
+#[derive(Debug)]
+pub struct PruneReport {
+    /// Sessions that started before this date are pruned
+    pub cutoff: NaiveDate,
+    pub sessions_removed: usize,
+    /// Work days whose totals were kept in daily_stats
+    pub days_aggregated: usize,
+    pub backup: Option<PathBuf>,
+    pub dry_run: bool,
+}
+
+/// Retention period in days (0 or unset = keep everything)
+pub fn effective_days(config: &Config) -> u32 {
+    config.retention.days.unwrap_or(0)
+}
+
+/// Directory holding pre-prune backups
+pub fn backup_dir() -> Result<PathBuf> {
+    Ok(Config::config_dir()?.join("backups"))
+}
+
+/// Prune now, ignoring the schedule. Returns None when retention is disabled.
+pub fn prune(db: &Database, config: &Config, dry_run: bool) -> Result<Option<PruneReport>> {
+    let days = effective_days(config);
+    if days == 0 {
+        return Ok(None);
+    }
+
+    let cutoff = db.today()? - Duration::days(i64::from(days));
+    let cutoff_str = cutoff.format("%Y-%m-%d").to_string();
+    let retention = &config.retention;
+    let keep_totals = retention.mode == RetentionMode::Aggregate;
+
+    let (pending, work_days) = db.count_sessions_before(&cutoff_str)?;
+    let mut report = PruneReport {
+        cutoff,
+        sessions_removed: pending as usize,
+        days_aggregated: if keep_totals { work_days as usize } else { 0 },
+        backup: None,
+        dry_run,
+    };
+
+    if dry_run || pending == 0 {
+        return Ok(Some(report));
+    }
+
+    if retention.backup {
+        report.backup = Some(backup(db)?);
+    }
+    report.sessions_removed = db.prune_sessions_before(&cutoff_str, keep_totals)?;
+
+    Ok(Some(report))
+}
+
+/// Prune if retention is enabled and the last scheduled run was over a day ago
+pub fn run_scheduled(db: &Database, config: &Config) -> Result<Option<PruneReport>> {
+    if effective_days(config) == 0 {
+        return Ok(None);
+    }
+
+    let last_run = db
+        .get_metadata(LAST_PRUNE_KEY)?
+        .and_then(|s| DateTime::parse_from_rfc3339(&s).ok())
+        .map(|dt| dt.with_timezone(&Utc));
+    if let Some(last) = last_run {
+        if Utc::now() - last < Duration::hours(PRUNE_INTERVAL_HOURS) {
+            return Ok(None);
+        }
+    }
+
+    let report = prune(db, config, false)?;
+    db.set_metadata(LAST_PRUNE_KEY, &Utc::now().to_rfc3339())?;
+    Ok(report)
+}
+
+/// Back up the database and drop the oldest backups beyond MAX_BACKUPS
+fn backup(db: &Database) -> Result<PathBuf> {
+    let dir = backup_dir()?;
+    let path = dir.join(format!("data-{}.db", Local::now().format("%Y%m%d-%H%M%S")));
+    db.backup_to(&path)?;
+
+    let mut backups: Vec<PathBuf> = std::fs::read_dir(&dir)?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| {
+            p.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with("data-") && n.ends_with(".db"))
+        })
+        .collect();
+    // Timestamped names sort chronologically
+    backups.sort();
+    let excess = backups.len().saturating_sub(MAX_BACKUPS);
+    for old in backups.into_iter().take(excess) {
+        let _ = std::fs::remove_file(old);
+    }
+
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RetentionConfig;
+    use crate::db::SessionType;
+    use rusqlite::params;
+
+    fn config(days: u32, mode: RetentionMode) -> Config {
+        Config {
+            retention: RetentionConfig {
+                days: Some(days),
+                mode,
+                backup: false,
+            },
+            ..Default::default()
+        }
+    }
+
+    /// A completed 25-minute work session counted towards `days_ago`
+    fn work_days_ago(db: &Database, days_ago: i64) -> String {
+        let day = (db.today().unwrap() - Duration::days(days_ago))
+            .format("%Y-%m-%d")
+            .to_string();
+        let id = db
+            .start_session_with_tag(SessionType::Work, None, Some(1500))
+            .unwrap();
+        db.complete_session(id, 1500).unwrap();
+        db.connection()
+            .execute(
+                "UPDATE sessions SET started_at = ?1, day = ?2 WHERE id = ?3",
+                params![format!("{}T12:00:00+00:00", day), day, id],
+            )
+            .unwrap();
+        day
+    }
+
+    #[test]
+    fn test_prune_keeps_the_last_day_in_the_period() {
+        let db = Database::open_in_memory().unwrap();
+        let kept = work_days_ago(&db, 7);
+        let pruned = work_days_ago(&db, 8);
+        work_days_ago(&db, 0);
+
+        let report = prune(&db, &config(7, RetentionMode::Delete), false)
+            .unwrap()
+            .unwrap();
+        assert_eq!(report.cutoff.format("%Y-%m-%d").to_string(), kept);
+        assert_eq!(report.sessions_removed, 1);
+        assert_eq!(db.get_date_stats(&kept).unwrap().sessions_completed, 1);
+        assert_eq!(db.get_date_stats(&pruned).unwrap().sessions_completed, 0);
+        assert_eq!(db.get_recent_sessions(10).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_aggregate_keeps_the_totals_of_pruned_days() {
+        let db = Database::open_in_memory().unwrap();
+        let old = work_days_ago(&db, 30);
+        work_days_ago(&db, 30);
+
+        let report = prune(&db, &config(7, RetentionMode::Aggregate), false)
+            .unwrap()
+            .unwrap();
+        assert_eq!((report.sessions_removed, report.days_aggregated), (2, 1));
+        assert!(db.get_recent_sessions(10).unwrap().is_empty());
+        let stats = db.get_date_stats(&old).unwrap();
+        assert_eq!(
+            (stats.total_work_seconds, stats.sessions_completed),
+            (3000, 2)
+        );
+    }
+
+    #[test]
+    fn test_dry_run_and_disabled_retention_change_nothing() {
+        let db = Database::open_in_memory().unwrap();
+        work_days_ago(&db, 30);
+
+        let report = prune(&db, &config(7, RetentionMode::Delete), true)
+            .unwrap()
+            .unwrap();
+        assert!(report.dry_run);
+        assert_eq!(report.sessions_removed, 1);
+        assert!(prune(&db, &config(0, RetentionMode::Delete), false)
+            .unwrap()
+            .is_none());
+        assert!(prune(&db, &Config::default(), false).unwrap().is_none());
+        assert_eq!(db.get_recent_sessions(10).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_scheduled_prune_runs_once_a_day() {
+        let db = Database::open_in_memory().unwrap();
+        let config = config(7, RetentionMode::Delete);
+        work_days_ago(&db, 30);
+        assert_eq!(
+            run_scheduled(&db, &config)
+                .unwrap()
+                .unwrap()
+                .sessions_removed,
+            1
+        );
+        work_days_ago(&db, 30);
+        assert!(run_scheduled(&db, &config).unwrap().is_none());
+        assert_eq!(db.get_recent_sessions(10).unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,313 @@
+//! Weekly review
+//!
+//! `sandoro review` steps through the week that just ended: its totals
+//! against the week before, the top tags and the goals it missed. It then
+//! asks for one line of reflection, kept in today's journal note, and for
+//! next week's weekly goals, saved to config.toml. Every prompt can be
+//! skipped with Enter.
+
+use anyhow::Result;
+use chrono::{Duration, NaiveDate};
+use std::io::{BufRead, Write};
+
+use crate::config::Config;
+use crate::db::Database;
+use crate::goals::{self, WeekOutcome};
+use crate::i18n;
+use crate::xp;
+
+/// Tags listed in the review
+const TOP_TAGS: usize = 3;
+/// Largest weekly goals accepted, as in Settings
+const MAX_WEEKLY_SESSIONS: u32 = 100;
+const MAX_WEEKLY_MINUTES: u32 = 2400;
+
+/// The week under review and what it's compared with
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeekReview {
+    /// Last week, with the weekly goals that applied to it
+    pub outcome: WeekOutcome,
+    /// Minutes the week before it
+    pub previous_minutes: i32,
+    /// Largest first: (tag name, minutes, sessions)
+    pub top_tags: Vec<(String, i32, i32)>,
+    /// Days the daily goal was met, when there is one
+    pub daily_goal_days: Option<usize>,
+}
+
+impl WeekReview {
+    /// The week before the one containing `today`
+    pub fn build(db: &Database, config: &Config, today: NaiveDate) -> Result<Self> {
+        let start = goals::week_start(today, config.goals.week_start_day()) - Duration::weeks(1);
+        let day = |d: NaiveDate| d.format("%Y-%m-%d").to_string();
+
+        let week = goals::load_week(db, start)?;
+        let previous_minutes = goals::load_week(db, start - Duration::weeks(1))?.minutes;
+        // Goals recorded for the week, else the ones set now
+        let outcome = goals::history(db, config, today)?
+            .into_iter()
+            .find(|o| o.week.start == start)
+            .unwrap_or(WeekOutcome {
+                week,
+                minutes_goal: config.goals.weekly_minutes,
+                sessions_goal: config.goals.weekly_sessions,
+            });
+
+        let top_tags = db
+            .get_range_stats_by_tag(&day(start), &day(start + Duration::days(7)))?
+            .into_iter()
+            .filter_map(|(tag, seconds, sessions)| Some((tag?.name, seconds / 60, sessions)))
+            .take(TOP_TAGS)
+            .collect();
+
+        let goals = &config.goals;
+        let daily_goal_days = if goals.daily_sessions > 0 || goals.daily_minutes > 0 {
+            let mut met = 0;
+            for offset in 0..7 {
+                let stats = db.get_date_stats(&day(start + Duration::days(offset)))?;
+                if xp::daily_goal_met(config, stats.sessions_completed, stats.total_work_seconds) {
+                    met += 1;
+                }
+            }
+            Some(met)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            outcome,
+            previous_minutes,
+            top_tags,
+            daily_goal_days,
+        })
+    }
+
+    /// One line per goal the week fell short of
+    pub fn missed_goals(&self) -> Vec<String> {
+        let o = &self.outcome;
+        let mut missed = Vec::new();
+        if o.sessions_goal > 0 && o.week.sessions < o.sessions_goal as i32 {
+            missed.push(format!(
+                "Weekly sessions: {} of {}",
+                o.week.sessions, o.sessions_goal
+            ));
+        }
+        if o.minutes_goal > 0 && o.week.minutes < o.minutes_goal as i32 {
+            missed.push(format!(
+                "Weekly focus: {} of {}",
+                i18n::minutes(o.week.minutes),
+                i18n::minutes(o.minutes_goal as i32)
+            ));
+        }
+        if let Some(days) = self.daily_goal_days.filter(|d| *d < 7) {
+            missed.push(format!("Daily goal: met on {} of 7 days", days));
+        }
+        missed
+    }
+}
+
+/// Step through `review`, reading answers from `input`. Returns `config`
+/// with next week's goals as answered; the reflection goes to the journal.
+pub fn run(
+    db: &Database,
+    config: &Config,
+    review: &WeekReview,
+    today: NaiveDate,
+    input: &mut impl BufRead,
+    out: &mut impl Write,
+) -> Result<Config> {
+    let week = &review.outcome.week;
+    writeln!(out)?;
+    writeln!(
+        out,
+        "  🗓  Weekly review: {} – {}",
+        week.start.format("%b %-d"),
+        week.end().format("%b %-d")
+    )?;
+    writeln!(out, "  ─────────────")?;
+
+    // 1. How the week went
+    let change = week.minutes - review.previous_minutes;
+    writeln!(
+        out,
+        "     Focus:     {} in {} sessions",
+        i18n::minutes(week.minutes),
+        week.sessions
+    )?;
+    writeln!(
+        out,
+        "     Week before: {} ({}{})",
+        i18n::minutes(review.previous_minutes),
+        if change >= 0 { "+" } else { "-" },
+        i18n::minutes(change.abs())
+    )?;
+    pause(input, out)?;
+
+    // 2. Where the time went
+    writeln!(out, "  🏷  Top tags")?;
+    if review.top_tags.is_empty() {
+        writeln!(out, "     No tagged sessions.")?;
+    }
+    for (name, minutes, sessions) in &review.top_tags {
+        writeln!(
+            out,
+            "     {:<16} {:>8}  {} sessions",
+            name,
+            i18n::minutes(*minutes),
+            sessions
+        )?;
+    }
+    pause(input, out)?;
+
+    // 3. What fell short
+    writeln!(out, "  🎯 Goals")?;
+    let missed = review.missed_goals();
+    if missed.is_empty() {
+        writeln!(out, "     Every goal met (or none set).")?;
+    }
+    for line in &missed {
+        writeln!(out, "     ✗ {}", line)?;
+    }
+    writeln!(out)?;
+
+    // 4. One line to remember it by
+    let reflection = ask(input, out, "  ✍  One line on the week (Enter to skip): ")?;
+    if !reflection.is_empty() {
+        let date = today.format("%Y-%m-%d").to_string();
+        let line = format!("Review: {}", reflection);
+        let note = match db.get_journal(&date)? {
+            Some(existing) if !existing.is_empty() => format!("{} · {}", existing, line),
+            _ => line,
+        };
+        db.set_journal(&date, &note)?;
+        writeln!(out, "     ✓ Saved to the journal for {}", date)?;
+    }
+
+    // 5. Next week's targets
+    writeln!(out, "  📈 Goals for this week (Enter keeps, 0 turns off)")?;
+    let mut edited = config.clone();
+    edited.goals.weekly_sessions = ask_number(
+        input,
+        out,
+        "     Weekly sessions",
+        config.goals.weekly_sessions,
+        MAX_WEEKLY_SESSIONS,
+    )?;
+    edited.goals.weekly_minutes = ask_number(
+        input,
+        out,
+        "     Weekly minutes",
+        config.goals.weekly_minutes,
+        MAX_WEEKLY_MINUTES,
+    )?;
+    writeln!(out)?;
+    Ok(edited)
+}
+
+/// Wait for Enter before the next step
+fn pause(input: &mut impl BufRead, out: &mut impl Write) -> Result<()> {
+    ask(input, out, "     (Enter to continue)")?;
+    writeln!(out)?;
+    Ok(())
+}
+
+fn ask(input: &mut impl BufRead, out: &mut impl Write, prompt: &str) -> Result<String> {
+    write!(out, "{}", prompt)?;
+    out.flush()?;
+    let mut line = String::new();
+    input.read_line(&mut line)?;
+    Ok(line.trim().to_string())
+}
+
+/// Ask for a number up to `max`, keeping `current` on Enter; asks again
+/// after anything else
+fn ask_number(
+    input: &mut impl BufRead,
+    out: &mut impl Write,
+    label: &str,
+    current: u32,
+    max: u32,
+) -> Result<u32> {
+    let shown = if current == 0 {
+        "off".to_string()
+    } else {
+        current.to_string()
+    };
+    loop {
+        let answer = ask(input, out, &format!("{} [{}]: ", label, shown))?;
+        if answer.is_empty() {
+            return Ok(current);
+        }
+        match answer.parse::<u32>() {
+            Ok(n) if n <= max => return Ok(n),
+            _ => writeln!(out, "     Enter a number from 0 to {}.", max)?,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::SessionType;
+    use rusqlite::params;
+
+    fn work_on(db: &Database, day: &str, tag: Option<i64>, minutes: i32) {
+        let id = db
+            .start_session_with_tag(SessionType::Work, tag, None)
+            .unwrap();
+        db.complete_session(id, minutes * 60).unwrap();
+        db.connection()
+            .execute(
+                "UPDATE sessions SET started_at = ?1, day = ?2 WHERE id = ?3",
+                params![format!("{}T09:00:00+00:00", day), day, id],
+            )
+            .unwrap();
+    }
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    #[test]
+    fn test_review_of_last_week() {
+        let db = Database::open_in_memory().unwrap();
+        let writing = db.create_tag("writing", None).unwrap();
+        let email = db.create_tag("email", None).unwrap();
+        // Weeks start on Monday: 2026-10-05 to 10-11, reviewed on the 13th
+        work_on(&db, "2026-10-05", Some(writing), 50);
+        work_on(&db, "2026-10-06", Some(writing), 25);
+        work_on(&db, "2026-10-07", Some(email), 25);
+        work_on(&db, "2026-09-30", None, 25);
+
+        let mut config = Config::default();
+        config.goals.weekly_sessions = 5;
+        config.goals.daily_sessions = 1;
+        let review = WeekReview::build(&db, &config, date("2026-10-13")).unwrap();
+        assert_eq!(review.outcome.week.start, date("2026-10-05"));
+        assert_eq!(review.outcome.week.minutes, 100);
+        assert_eq!(review.previous_minutes, 25);
+        assert_eq!(
+            review.top_tags,
+            vec![("writing".to_string(), 75, 2), ("email".to_string(), 25, 1)]
+        );
+        assert_eq!(
+            review.missed_goals(),
+            vec!["Weekly sessions: 3 of 5", "Daily goal: met on 3 of 7 days"]
+        );
+
+        // Enter through the steps, a reflection, then new goals
+        let mut input = "\n\nToo many meetings\nx\n8\n\n".as_bytes();
+        let mut out = Vec::new();
+        let today = date("2026-10-13");
+        let edited = run(&db, &config, &review, today, &mut input, &mut out).unwrap();
+        assert_eq!(edited.goals.weekly_sessions, 8);
+        assert_eq!(edited.goals.weekly_minutes, 0);
+        assert_eq!(
+            db.get_journal("2026-10-13").unwrap().as_deref(),
+            Some("Review: Too many meetings")
+        );
+        let out = String::from_utf8(out).unwrap();
+        assert!(out.contains("Enter a number from 0 to 100."));
+        assert!(out.contains("✗ Weekly sessions: 3 of 5"));
+    }
+}
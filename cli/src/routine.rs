@@ -0,0 +1,183 @@
+//! Named routines: a chain of blocks the timer walks through on its own
+//!
+//! A routine is set in config.toml as an ordered list of steps, each a
+//! work block or a break with its own length and, for work, a tag:
+//!
+//! ```toml
+//! [[routines.morning]]
+//! minutes = 25
+//! tag = "writing"
+//! repeat = 2
+//!
+//! [[routines.morning]]
+//! kind = "break"
+//! minutes = 5
+//! ```
+//!
+//! Started with `sandoro routine morning` or from the picker (`o`), each
+//! block starts as soon as the one before it ends and is recorded like any
+//! other session. Breaks only come where the routine lists them.
+
+use anyhow::{bail, Result};
+
+use crate::config::{BlockKind, Config, RoutineStep};
+use crate::timer::TimerState;
+
+/// One timed block of a routine, with repeats spelled out
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Block {
+    pub state: TimerState,
+    pub minutes: u32,
+    pub tag: Option<String>,
+}
+
+/// A routine being walked through
+#[derive(Debug, Clone)]
+pub struct Run {
+    pub name: String,
+    blocks: Vec<Block>,
+    index: usize,
+}
+
+impl Run {
+    /// Start the routine `name` from config, at its first block
+    pub fn start(config: &Config, name: &str) -> Result<Self> {
+        let Some(steps) = config.routines.get(name) else {
+            if config.routines.is_empty() {
+                bail!(
+                    "No routines are set up. Add one under [routines] in ~/.sandoro/config.toml."
+                );
+            }
+            bail!(
+                "No routine named '{}'. Routines: {}",
+                name,
+                names(config).join(", ")
+            );
+        };
+        let blocks = blocks(steps);
+        if blocks.is_empty() {
+            bail!("Routine '{}' has no blocks to run.", name);
+        }
+        Ok(Self {
+            name: name.to_string(),
+            blocks,
+            index: 0,
+        })
+    }
+
+    pub fn current(&self) -> &Block {
+        &self.blocks[self.index]
+    }
+
+    /// Move on to the next block; None once the routine is done
+    pub fn advance(&mut self) -> Option<&Block> {
+        self.index += 1;
+        self.blocks.get(self.index)
+    }
+
+    /// The current block's number (from 1) and how many there are
+    pub fn position(&self) -> (usize, usize) {
+        (self.index + 1, self.blocks.len())
+    }
+}
+
+/// The routines in config, sorted by name
+pub fn names(config: &Config) -> Vec<String> {
+    config.routines.keys().cloned().collect()
+}
+
+/// Spell out a routine's steps as blocks; zero-minute steps are dropped
+fn blocks(steps: &[RoutineStep]) -> Vec<Block> {
+    steps
+        .iter()
+        .filter(|step| step.minutes > 0)
+        .flat_map(|step| {
+            let block = Block {
+                state: match step.kind {
+                    BlockKind::Work => TimerState::Work,
+                    BlockKind::Break => TimerState::ShortBreak,
+                    BlockKind::LongBreak => TimerState::LongBreak,
+                },
+                minutes: step.minutes,
+                tag: step.tag.clone(),
+            };
+            std::iter::repeat_n(block, step.repeat as usize)
+        })
+        .collect()
+}
+
+/// One line per routine for `sandoro routine` without a name
+pub fn describe(config: &Config, name: &str) -> String {
+    let steps = &config.routines[name];
+    let parts: Vec<String> = steps
+        .iter()
+        .map(|step| {
+            let kind = match step.kind {
+                BlockKind::Work => step.tag.clone().unwrap_or_else(|| "work".to_string()),
+                BlockKind::Break => "break".to_string(),
+                BlockKind::LongBreak => "long break".to_string(),
+            };
+            if step.repeat > 1 {
+                format!("{}×{} {}", step.repeat, step.minutes, kind)
+            } else {
+                format!("{} {}", step.minutes, kind)
+            }
+        })
+        .collect();
+    parts.join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn morning() -> Config {
+        let routines = toml::from_str(
+            r#"
+            [[morning]]
+            minutes = 25
+            tag = "writing"
+            repeat = 2
+
+            [[morning]]
+            kind = "break"
+            minutes = 5
+
+            [[morning]]
+            minutes = 50
+            tag = "email"
+            "#,
+        )
+        .unwrap();
+        Config {
+            routines,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_steps_spell_out_repeats_in_order() {
+        let config = morning();
+        let mut run = Run::start(&config, "morning").unwrap();
+        assert_eq!(run.position(), (1, 4));
+        assert_eq!(run.current().tag.as_deref(), Some("writing"));
+
+        assert_eq!(run.advance().unwrap().minutes, 25);
+        assert_eq!(run.advance().unwrap().state, TimerState::ShortBreak);
+        let last = run.advance().unwrap();
+        assert_eq!((last.minutes, last.tag.as_deref()), (50, Some("email")));
+        assert!(run.advance().is_none());
+
+        assert_eq!(
+            describe(&config, "morning"),
+            "2×25 writing, 5 break, 50 email"
+        );
+    }
+
+    #[test]
+    fn test_unknown_routine_lists_the_known_ones() {
+        let err = Run::start(&morning(), "evening").unwrap_err();
+        assert!(err.to_string().contains("Routines: morning"));
+        assert!(Run::start(&Config::default(), "morning").is_err());
+    }
+}
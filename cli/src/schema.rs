@@ -0,0 +1,244 @@
+//! What's on disk, for bug reports and tools reading the database
+//!
+//! `sandoro schema` prints the SQLite schema as the database has it (it is
+//! opened read-only and not migrated), which migrations it has, how many
+//! rows each table holds, and the config sandoro is running with after
+//! defaults are filled in, with secrets redacted. TOML by default, JSON
+//! with `--json`.
+//!
+//! `sandoro db compat` is the part of that a dashboard can rely on across
+//! versions: always JSON, with the schema version, the compatibility level
+//! (see `db::COMPAT_LEVELS`), the changelog of migrations and the columns of
+//! every table and view. A tool that checks `compat_level` can keep reading
+//! through any migration that leaves it unchanged.
+
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::config::Config;
+use crate::db::{self, Database};
+
+#[derive(Debug, Serialize)]
+pub struct SchemaReport {
+    pub database: DatabaseSchema,
+    pub config: Config,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DatabaseSchema {
+    pub path: String,
+    pub exists: bool,
+    /// `PRAGMA user_version`; 0 for a database that doesn't exist yet
+    pub schema_version: i32,
+    /// Newest version this build migrates to
+    pub supported_version: i32,
+    pub migrations: Vec<Migration>,
+    pub objects: Vec<Object>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Migration {
+    pub version: i32,
+    pub description: String,
+    pub applied: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Object {
+    pub kind: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rows: Option<i64>,
+    pub sql: String,
+}
+
+/// Build the report from the database as it is (None when missing)
+pub fn build(db: Option<&Database>, config: &Config) -> Result<SchemaReport> {
+    let schema_version = db.map(|db| db.schema_version()).transpose()?.unwrap_or(0);
+    let objects = match db {
+        Some(db) => db
+            .schema_objects()?
+            .into_iter()
+            .map(|o| Object {
+                kind: o.kind,
+                name: o.name,
+                rows: o.rows,
+                sql: o.sql,
+            })
+            .collect(),
+        None => Vec::new(),
+    };
+    Ok(SchemaReport {
+        database: DatabaseSchema {
+            path: Database::db_path()?.display().to_string(),
+            exists: db.is_some(),
+            schema_version,
+            supported_version: Database::supported_schema_version(),
+            migrations: db::MIGRATIONS
+                .iter()
+                .map(|(version, description)| Migration {
+                    version: *version,
+                    description: description.to_string(),
+                    applied: *version <= schema_version,
+                })
+                .collect(),
+            objects,
+        },
+        config: config.redacted(),
+    })
+}
+
+/// What `sandoro db compat` prints
+#[derive(Debug, Serialize)]
+pub struct CompatReport {
+    pub schema_version: i32,
+    pub supported_version: i32,
+    /// Compatibility level of the database as it is; 0 when it doesn't exist
+    pub compat_level: i32,
+    /// Level this build migrates to
+    pub supported_compat_level: i32,
+    pub changes: Vec<Change>,
+    pub tables: Vec<Table>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Change {
+    pub version: i32,
+    pub description: String,
+    pub compat_level: i32,
+    pub applied: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Table {
+    pub name: String,
+    /// "table" or "view"
+    pub kind: String,
+    pub columns: Vec<Column>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Column {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub sql_type: String,
+    pub not_null: bool,
+    pub default: Option<String>,
+    pub primary_key: bool,
+}
+
+/// Build the compat report from the database as it is (None when missing)
+pub fn build_compat(db: Option<&Database>) -> Result<CompatReport> {
+    let schema_version = db.map(|db| db.schema_version()).transpose()?.unwrap_or(0);
+    let mut tables = Vec::new();
+    if let Some(db) = db {
+        for object in db.schema_objects()? {
+            if object.kind != "table" && object.kind != "view" {
+                continue;
+            }
+            let columns = db
+                .columns(&object.name)?
+                .into_iter()
+                .map(|c| Column {
+                    name: c.name,
+                    sql_type: c.sql_type,
+                    not_null: c.not_null,
+                    default: c.default,
+                    primary_key: c.primary_key,
+                })
+                .collect();
+            tables.push(Table {
+                name: object.name,
+                kind: object.kind,
+                columns,
+            });
+        }
+    }
+    let supported_version = Database::supported_schema_version();
+    Ok(CompatReport {
+        schema_version,
+        supported_version,
+        compat_level: db::compat_level(schema_version),
+        supported_compat_level: db::compat_level(supported_version),
+        changes: db::MIGRATIONS
+            .iter()
+            .map(|(version, description)| Change {
+                version: *version,
+                description: description.to_string(),
+                compat_level: db::compat_level(*version),
+                applied: *version <= schema_version,
+            })
+            .collect(),
+        tables,
+    })
+}
+
+pub fn render(report: &SchemaReport, json: bool) -> Result<String> {
+    if json {
+        Ok(serde_json::to_string_pretty(report)?)
+    } else {
+        Ok(toml::to_string_pretty(report)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_lists_tables_and_redacts_secrets() {
+        let db = Database::open_in_memory().unwrap();
+        let mut config = Config::default();
+        config.time_tracking.api_token = "tt-secret".to_string();
+        config.escalation.token = "push-secret".to_string();
+        config.escalation.topic = "topic-secret".to_string();
+        config.account.license_key = "LIC-secret".to_string();
+        let report = build(Some(&db), &config).unwrap();
+
+        assert!(report.database.exists);
+        assert!(report.database.migrations.iter().all(|m| m.applied));
+        for json in [false, true] {
+            let text = render(&report, json).unwrap();
+            assert!(!text.contains("tt-secret"));
+            assert!(!text.contains("push-secret"));
+            assert!(!text.contains("topic-secret"));
+            assert!(!text.contains("LIC-secret"));
+            assert!(text.contains("<redacted>"));
+            assert!(text.contains("CREATE TABLE"));
+        }
+        // Unset secrets stay empty rather than looking set
+        assert_eq!(report.config.privacy.unlock_sequence, "");
+
+        let missing = build(None, &config).unwrap();
+        assert_eq!(missing.database.schema_version, 0);
+        assert!(missing.database.migrations.iter().all(|m| !m.applied));
+    }
+
+    #[test]
+    fn test_compat_report_describes_columns() {
+        let db = Database::open_in_memory().unwrap();
+        let report = build_compat(Some(&db)).unwrap();
+        assert_eq!(report.compat_level, report.supported_compat_level);
+        assert!(report.compat_level >= 1);
+        assert_eq!(report.changes.len(), db::MIGRATIONS.len());
+
+        let sessions = report.tables.iter().find(|t| t.name == "sessions").unwrap();
+        let id = &sessions.columns[0];
+        assert_eq!((id.name.as_str(), id.primary_key), ("id", true));
+        assert!(sessions.columns.iter().any(|c| c.name == "day"));
+        let work_days = report
+            .tables
+            .iter()
+            .find(|t| t.name == "work_days")
+            .unwrap();
+        assert_eq!(work_days.kind, "view");
+        assert!(!report.tables.iter().any(|t| t.name.starts_with("idx_")));
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert!(json["tables"][0]["columns"][0]["type"].is_string());
+
+        let missing = build_compat(None).unwrap();
+        assert_eq!((missing.schema_version, missing.compat_level), (0, 0));
+        assert!(missing.tables.is_empty());
+    }
+}
@@ -0,0 +1,423 @@
+//! Local HTTP server
+//!
+//! `sandoro serve --badge` serves SVG images rendered from local data so the
+//! focus heatmap and streak can be embedded in a personal site or README.
+//! Only daily totals and the streak are exposed; tags, notes and individual
+//! sessions never leave the machine.
+//!
+//! `sandoro serve --remote` serves a small page for controlling the running
+//! timer from a phone: its state and time left, with start, pause and skip
+//! buttons sent on over the control socket. Every /remote request needs the
+//! random token printed at startup.
+
+use anyhow::{Context, Result};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::net::{IpAddr, UdpSocket};
+use tiny_http::{Header, Method, Request, Response, Server};
+use url::Url;
+
+use crate::clock;
+use crate::config::Config;
+use crate::control::{self, Command};
+use crate::status;
+use crate::storage;
+use crate::svg;
+
+/// The remote-control page, polling /remote/status every two seconds
+const REMOTE_PAGE: &str = include_str!("remote.html");
+
+/// Weeks shown by /heatmap.svg unless `?weeks=` is given
+const DEFAULT_HEATMAP_WEEKS: i32 = 52;
+const MAX_HEATMAP_WEEKS: i32 = 53;
+
+pub struct ServeOptions {
+    pub bind: String,
+    pub port: u16,
+    pub badge: bool,
+    /// Token the remote-control page needs; None leaves it off
+    pub remote_token: Option<String>,
+    /// Cache-Control max-age in seconds
+    pub max_age: u32,
+}
+
+/// A random token for `--remote`
+pub fn new_token() -> String {
+    uuid::Uuid::new_v4().simple().to_string()
+}
+
+/// A rendered response
+#[derive(Debug)]
+struct Reply {
+    status: u16,
+    content_type: &'static str,
+    body: String,
+    /// Badges may be cached; the remote's live state never
+    cacheable: bool,
+}
+
+impl Reply {
+    fn svg(body: String) -> Self {
+        Self {
+            status: 200,
+            content_type: "image/svg+xml; charset=utf-8",
+            body,
+            cacheable: true,
+        }
+    }
+
+    fn text(status: u16, body: &str) -> Self {
+        Self {
+            status,
+            content_type: "text/plain; charset=utf-8",
+            body: body.to_string(),
+            cacheable: true,
+        }
+    }
+
+    fn html(body: &str) -> Self {
+        Self {
+            status: 200,
+            content_type: "text/html; charset=utf-8",
+            body: body.to_string(),
+            cacheable: false,
+        }
+    }
+
+    fn json(status: u16, value: serde_json::Value) -> Self {
+        Self {
+            status,
+            content_type: "application/json",
+            body: value.to_string(),
+            cacheable: false,
+        }
+    }
+}
+
+/// Parse `?weeks=` for the heatmap, clamped to a year
+fn heatmap_weeks(url: &Url) -> i32 {
+    url.query_pairs()
+        .find(|(k, _)| k == "weeks")
+        .and_then(|(_, v)| v.parse::<i32>().ok())
+        .unwrap_or(DEFAULT_HEATMAP_WEEKS)
+        .clamp(1, MAX_HEATMAP_WEEKS)
+}
+
+/// Compare without stopping at the first difference, so response times
+/// don't give the token away
+fn token_matches(given: &str, token: &str) -> bool {
+    given.len() == token.len()
+        && given
+            .bytes()
+            .zip(token.bytes())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+}
+
+/// The running timer's status with the clock caught up to now, or
+/// `running: false`
+fn remote_status() -> Result<serde_json::Value> {
+    let Some(mut live) = status::read()? else {
+        return Ok(serde_json::json!({ "running": false }));
+    };
+    if !live.paused {
+        let passed = (clock::now_utc() - live.updated_at).num_seconds().max(0) as u32;
+        live.remaining_seconds = live.remaining_seconds.saturating_sub(passed);
+        if live.elapsed_seconds > 0 {
+            live.elapsed_seconds += passed;
+        }
+    }
+    let mut value = serde_json::to_value(&live)?;
+    value["running"] = serde_json::Value::Bool(true);
+    Ok(value)
+}
+
+/// Render the remote-control page and its endpoints
+fn route_remote(method: &Method, url: &Url, token: &str) -> Result<Reply> {
+    let given = url
+        .query_pairs()
+        .find(|(k, _)| k == "token")
+        .map(|(_, v)| v.into_owned())
+        .unwrap_or_default();
+    if !token_matches(&given, token) {
+        return Ok(Reply::json(
+            403,
+            serde_json::json!({ "ok": false, "message": "Wrong token" }),
+        ));
+    }
+
+    let command = match url.path() {
+        "/remote" | "/remote/" if *method != Method::Post => return Ok(Reply::html(REMOTE_PAGE)),
+        "/remote/status" if *method != Method::Post => {
+            return Ok(Reply::json(200, remote_status()?))
+        }
+        "/remote/start" => Command::Start,
+        "/remote/pause" => Command::Pause,
+        "/remote/skip" => Command::Skip,
+        _ => return Ok(Reply::text(404, "Not found")),
+    };
+    if *method != Method::Post {
+        return Ok(Reply::text(405, "Method not allowed"));
+    }
+    let (status, ok, message) = match control::send(command) {
+        Ok(message) => (200, true, message),
+        Err(e) => (409, false, e.to_string()),
+    };
+    Ok(Reply::json(
+        status,
+        serde_json::json!({ "ok": ok, "message": message }),
+    ))
+}
+
+/// Render the response for a request path
+fn route(method: &Method, path: &str, options: &ServeOptions) -> Result<Reply> {
+    let url = Url::parse(&format!("http://localhost{}", path))?;
+
+    if url.path() == "/remote" || url.path().starts_with("/remote/") {
+        return match &options.remote_token {
+            Some(token) => route_remote(method, &url, token),
+            None => Ok(Reply::text(404, "Not found")),
+        };
+    }
+    if *method == Method::Post {
+        return Ok(Reply::text(405, "Method not allowed"));
+    }
+    if !options.badge {
+        return Ok(Reply::text(404, "Not found"));
+    }
+
+    match url.path() {
+        "/heatmap.svg" => {
+            let config = Config::load().unwrap_or_default();
+            let db = storage::open_readonly(&config)?;
+            let data = db.get_heatmap_data(heatmap_weeks(&url))?;
+            Ok(Reply::svg(svg::heatmap(&data, &config.appearance.accent)))
+        }
+        "/streak-badge.svg" => {
+            let config = Config::load().unwrap_or_default();
+            let db = storage::open_readonly(&config)?;
+            let streak = db.get_streak()?;
+            Ok(Reply::svg(svg::streak_badge(
+                streak.current,
+                &config.appearance.accent,
+            )))
+        }
+        "/today-badge.svg" => {
+            let config = Config::load().unwrap_or_default();
+            let db = storage::open_readonly(&config)?;
+            let today = db.get_today_stats()?;
+            Ok(Reply::svg(svg::focus_badge(
+                today.total_work_seconds,
+                &config.appearance.accent,
+            )))
+        }
+        "/" => Ok(Reply::text(
+            200,
+            "sandoro badge server\n\n/heatmap.svg?weeks=52\n/streak-badge.svg\n/today-badge.svg\n",
+        )),
+        _ => Ok(Reply::text(404, "Not found")),
+    }
+}
+
+fn etag(body: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    body.hash(&mut hasher);
+    format!("\"{:016x}\"", hasher.finish())
+}
+
+fn header(name: &str, value: &str) -> Header {
+    Header::from_bytes(name.as_bytes(), value.as_bytes()).expect("valid header")
+}
+
+fn handle(request: Request, options: &ServeOptions) {
+    if !matches!(request.method(), Method::Get | Method::Head | Method::Post) {
+        let _ = request.respond(Response::from_string("Method not allowed").with_status_code(405));
+        return;
+    }
+
+    let reply = route(request.method(), request.url(), options).unwrap_or_else(|e| {
+        eprintln!("  ✗ {} {}: {}", request.method(), request.url(), e);
+        Reply::text(500, "Internal error")
+    });
+
+    let tag = etag(&reply.body);
+    let not_modified = reply.status == 200
+        && request
+            .headers()
+            .iter()
+            .any(|h| h.field.equiv("If-None-Match") && h.value.as_str() == tag);
+
+    let cache_control = if reply.status == 200 && reply.cacheable {
+        format!("public, max-age={}", options.max_age)
+    } else {
+        "no-store".to_string()
+    };
+
+    let response = if not_modified {
+        Response::from_string("").with_status_code(304)
+    } else {
+        Response::from_string(reply.body)
+            .with_status_code(reply.status)
+            .with_header(header("Content-Type", reply.content_type))
+    };
+    let _ = request.respond(
+        response
+            .with_header(header("Cache-Control", &cache_control))
+            .with_header(header("ETag", &tag)),
+    );
+}
+
+/// This machine's address on the local network, found by asking which
+/// interface would route to a public address (nothing is sent)
+fn lan_address() -> Option<IpAddr> {
+    let socket = UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("8.8.8.8:80").ok()?;
+    Some(socket.local_addr().ok()?.ip())
+}
+
+/// Serve until interrupted
+pub fn run(options: ServeOptions) -> Result<()> {
+    if !options.badge && options.remote_token.is_none() {
+        anyhow::bail!(
+            "Nothing to serve. Pass --badge to serve the SVG heatmap and streak badge, or --remote for the remote control."
+        );
+    }
+
+    let addr = format!("{}:{}", options.bind, options.port);
+    let server = Server::http(&addr)
+        .map_err(|e| anyhow::anyhow!("{}", e))
+        .with_context(|| format!("Failed to listen on {}", addr))?;
+
+    let loopback = options
+        .bind
+        .parse::<IpAddr>()
+        .map(|ip| ip.is_loopback())
+        .unwrap_or(options.bind == "localhost");
+
+    println!();
+    println!("  🌐 Serving on http://{}", addr);
+    println!("  ──────────────────────────────");
+    if options.badge {
+        println!("     http://{}/heatmap.svg", addr);
+        println!("     http://{}/streak-badge.svg", addr);
+        println!("     http://{}/today-badge.svg", addr);
+        println!();
+        println!("  Privacy: only daily focus totals and your streak are served.");
+        println!("  Tags, notes and individual sessions are never exposed.");
+        if !loopback {
+            println!(
+                "  ⚠ Listening beyond localhost: anyone who can reach this address can see them."
+            );
+        }
+        println!();
+    }
+    if let Some(token) = &options.remote_token {
+        // 0.0.0.0 isn't something a phone can open
+        let host = match options.bind.parse::<IpAddr>() {
+            Ok(ip) if ip.is_unspecified() => lan_address()
+                .map(|ip| ip.to_string())
+                .unwrap_or_else(|| options.bind.clone()),
+            _ => options.bind.clone(),
+        };
+        println!("  📱 Remote control — open on your phone:");
+        println!(
+            "     http://{}:{}/remote?token={}",
+            host, options.port, token
+        );
+        println!();
+        println!("  Anyone with this link can start, pause and skip the timer.");
+        println!("  The token changes every time the server starts.");
+        if loopback {
+            println!(
+                "  ⚠ Listening on localhost only: pass --bind 0.0.0.0 to reach it from your phone."
+            );
+        }
+        println!();
+    }
+    println!("  Press Ctrl+C to stop.");
+
+    for request in server.incoming_requests() {
+        handle(request, &options);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(badge: bool) -> ServeOptions {
+        ServeOptions {
+            bind: "127.0.0.1".to_string(),
+            port: 0,
+            badge,
+            remote_token: None,
+            max_age: 300,
+        }
+    }
+
+    #[test]
+    fn test_heatmap_weeks_parsing() {
+        let url = |q: &str| Url::parse(&format!("http://localhost/heatmap.svg{}", q)).unwrap();
+        assert_eq!(heatmap_weeks(&url("")), DEFAULT_HEATMAP_WEEKS);
+        assert_eq!(heatmap_weeks(&url("?weeks=12")), 12);
+        assert_eq!(heatmap_weeks(&url("?weeks=0")), 1);
+        assert_eq!(heatmap_weeks(&url("?weeks=999")), MAX_HEATMAP_WEEKS);
+        assert_eq!(heatmap_weeks(&url("?weeks=abc")), DEFAULT_HEATMAP_WEEKS);
+    }
+
+    #[test]
+    fn test_unknown_paths_are_not_found() {
+        let get = |path: &str, badge: bool| route(&Method::Get, path, &options(badge)).unwrap();
+        assert_eq!(get("/sessions", true).status, 404);
+        assert_eq!(get("/heatmap.svg", false).status, 404);
+        // The remote is off unless asked for
+        assert_eq!(get("/remote?token=", true).status, 404);
+        assert_eq!(
+            route(&Method::Post, "/heatmap.svg", &options(true))
+                .unwrap()
+                .status,
+            405
+        );
+    }
+
+    #[test]
+    fn test_remote_needs_the_token() {
+        let options = ServeOptions {
+            remote_token: Some("abc123".to_string()),
+            ..options(false)
+        };
+        let page = route(&Method::Get, "/remote?token=abc123", &options).unwrap();
+        assert_eq!(page.status, 200);
+        assert!(!page.cacheable);
+        assert!(page.body.contains("/remote/status"));
+
+        for path in [
+            "/remote",
+            "/remote?token=abc12",
+            "/remote/skip?token=abc1234",
+        ] {
+            assert_eq!(route(&Method::Post, path, &options).unwrap().status, 403);
+        }
+        // Actions change the timer, so they're POST only
+        let skip = route(&Method::Get, "/remote/skip?token=abc123", &options).unwrap();
+        assert_eq!(skip.status, 405);
+        let unknown = route(&Method::Post, "/remote/reset?token=abc123", &options).unwrap();
+        assert_eq!(unknown.status, 404);
+    }
+
+    #[test]
+    fn test_token_matches() {
+        assert!(token_matches("abc", "abc"));
+        assert!(!token_matches("abd", "abc"));
+        assert!(!token_matches("", "abc"));
+        assert_ne!(new_token(), new_token());
+        assert_eq!(new_token().len(), 32);
+    }
+
+    #[test]
+    fn test_etag_changes_with_body() {
+        assert_eq!(etag("a"), etag("a"));
+        assert_ne!(etag("a"), etag("b"));
+    }
+}
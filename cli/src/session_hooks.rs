@@ -0,0 +1,168 @@
+//! Shell commands on session events
+//!
+//! `[hooks]` in config.toml names a command for each event (work or break
+//! starting or completing, a skip). The command runs through the shell on a
+//! background thread so the timer never waits for it, with what happened in
+//! environment variables:
+//!
+//! - `SANDORO_EVENT`: `work_start`, `work_complete`, `break_start`,
+//!   `break_complete` or `skip`
+//! - `SANDORO_STATE`: `work`, `short_break`, `long_break` or `timer`
+//! - `SANDORO_DURATION`: seconds; the planned length when a phase starts,
+//!   the time it ran when it ends
+//! - `SANDORO_TAG`: the tag, empty when there's none
+//!
+//! Its output is discarded, and it's killed if still running after
+//! `timeout_secs`.
+
+use std::process::{Command, Stdio};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use crate::config::HooksConfig;
+
+/// How often the waiting thread checks on the command
+const POLL: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Event {
+    WorkStart,
+    WorkComplete,
+    BreakStart,
+    BreakComplete,
+    Skip,
+}
+
+impl Event {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Event::WorkStart => "work_start",
+            Event::WorkComplete => "work_complete",
+            Event::BreakStart => "break_start",
+            Event::BreakComplete => "break_complete",
+            Event::Skip => "skip",
+        }
+    }
+
+    /// The command configured for this event, if any
+    fn command(self, config: &HooksConfig) -> Option<&str> {
+        let command = match self {
+            Event::WorkStart => &config.on_work_start,
+            Event::WorkComplete => &config.on_work_complete,
+            Event::BreakStart => &config.on_break_start,
+            Event::BreakComplete => &config.on_break_complete,
+            Event::Skip => &config.on_skip,
+        };
+        command.as_deref().filter(|c| !c.trim().is_empty())
+    }
+}
+
+/// What the event was about
+#[derive(Debug, Clone)]
+pub struct Context {
+    /// Session type: "work", "short_break", "long_break" or "timer"
+    pub state: String,
+    /// Seconds planned (start) or run (complete, skip)
+    pub duration: u32,
+    pub tag: Option<String>,
+}
+
+/// `command` run through the shell
+pub fn shell(command: &str) -> Command {
+    if cfg!(windows) {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", command]);
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.args(["-c", command]);
+        cmd
+    }
+}
+
+/// Run the command for `event` in the background. Returns the thread
+/// waiting on it, None when nothing is configured or it couldn't start.
+pub fn fire(config: &HooksConfig, event: Event, context: &Context) -> Option<JoinHandle<()>> {
+    let command = event.command(config)?;
+    let mut child = shell(command)
+        .env("SANDORO_EVENT", event.as_str())
+        .env("SANDORO_STATE", &context.state)
+        .env("SANDORO_DURATION", context.duration.to_string())
+        .env("SANDORO_TAG", context.tag.as_deref().unwrap_or(""))
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+    let timeout = Duration::from_secs(config.timeout_secs);
+    Some(std::thread::spawn(move || {
+        let started = Instant::now();
+        while let Ok(None) = child.try_wait() {
+            if started.elapsed() >= timeout {
+                let _ = child.kill();
+                let _ = child.wait();
+                return;
+            }
+            std::thread::sleep(POLL);
+        }
+    }))
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn context() -> Context {
+        Context {
+            state: "work".to_string(),
+            duration: 1500,
+            tag: Some("writing".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_command_gets_the_event_in_its_environment() {
+        let dir = tempfile::tempdir().unwrap();
+        let out = dir.path().join("out");
+        let config = HooksConfig {
+            on_work_complete: Some(format!(
+                "echo \"$SANDORO_EVENT $SANDORO_STATE $SANDORO_DURATION $SANDORO_TAG\" > {}",
+                out.display()
+            )),
+            ..HooksConfig::default()
+        };
+        fire(&config, Event::WorkComplete, &context())
+            .unwrap()
+            .join()
+            .unwrap();
+        assert_eq!(
+            std::fs::read_to_string(&out).unwrap(),
+            "work_complete work 1500 writing\n"
+        );
+    }
+
+    #[test]
+    fn test_unset_events_run_nothing() {
+        let config = HooksConfig {
+            on_work_start: Some("  ".to_string()),
+            ..HooksConfig::default()
+        };
+        assert!(fire(&config, Event::WorkStart, &context()).is_none());
+        assert!(fire(&config, Event::Skip, &context()).is_none());
+    }
+
+    #[test]
+    fn test_slow_command_is_killed_after_the_timeout() {
+        let config = HooksConfig {
+            on_skip: Some("sleep 30".to_string()),
+            timeout_secs: 1,
+            ..HooksConfig::default()
+        };
+        let started = Instant::now();
+        fire(&config, Event::Skip, &context())
+            .unwrap()
+            .join()
+            .unwrap();
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+}
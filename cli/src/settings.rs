@@ -0,0 +1,375 @@
+//! Saving settings when more than one frontend writes them
+//!
+//! Whatever changes config.toml (the timer's settings screen, and any
+//! frontend added later) saves through here instead of writing the file
+//! outright. A save takes a lock file, rereads the file and merges: only
+//! the keys this frontend changed since it loaded the config are written,
+//! so a change another process made to a different key in the meantime is
+//! kept. When both changed the same key, the later save wins. Each save
+//! bumps `revision` in the file and replaces it with a rename, so a reader
+//! never sees half a file. The version it replaces is kept as
+//! config.toml.bak, to fall back on if config.toml ever stops parsing.
+
+use anyhow::{bail, Result};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+use toml::{Table, Value};
+
+use crate::config::Config;
+
+/// How long to wait for another save to finish
+const LOCK_TIMEOUT: Duration = Duration::from_secs(2);
+const LOCK_RETRY: Duration = Duration::from_millis(20);
+/// A lock file older than this was left by a process that died mid-save
+const STALE_LOCK: Duration = Duration::from_secs(10);
+/// The config.toml before the last save
+const BACKUP: &str = "config.toml.bak";
+/// Where a config.toml that didn't parse is moved when the backup replaces it
+const BROKEN: &str = "config.toml.broken";
+
+/// Held while config.toml is being rewritten
+struct Lock {
+    path: PathBuf,
+}
+
+impl Lock {
+    fn acquire(path: PathBuf) -> Result<Self> {
+        let started = Instant::now();
+        loop {
+            match OpenOptions::new().write(true).create_new(true).open(&path) {
+                Ok(mut file) => {
+                    let _ = write!(file, "{}", std::process::id());
+                    return Ok(Self { path });
+                }
+                Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => {
+                    if is_stale(&path) {
+                        let _ = std::fs::remove_file(&path);
+                        continue;
+                    }
+                    if started.elapsed() > LOCK_TIMEOUT {
+                        bail!(
+                            "Settings are being saved by another sandoro. If none is running, remove {}",
+                            path.display()
+                        );
+                    }
+                    std::thread::sleep(LOCK_RETRY);
+                }
+                Err(err) => return Err(err.into()),
+            }
+        }
+    }
+}
+
+impl Drop for Lock {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.path);
+    }
+}
+
+fn is_stale(path: &Path) -> bool {
+    std::fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .is_some_and(|age| age > STALE_LOCK)
+}
+
+/// Save `edited`, a config loaded as `base` and then changed. Returns the
+/// config as written: these changes on top of what's in the file.
+pub fn save(base: &Config, edited: &Config) -> Result<Config> {
+    let dir = Config::config_dir()?;
+    std::fs::create_dir_all(&dir)?;
+    save_in(&dir, base, edited)
+}
+
+fn save_in(dir: &Path, base: &Config, edited: &Config) -> Result<Config> {
+    let _lock = Lock::acquire(dir.join("config.lock"))?;
+    let path = dir.join("config.toml");
+    let disk: Config = match std::fs::read_to_string(&path) {
+        Ok(content) => {
+            let disk = toml::from_str(&content)?;
+            // Only a file that parses is worth going back to
+            write_atomic(&dir.join(BACKUP), &content)?;
+            disk
+        }
+        // What loading a missing file gives; not `base`, which may have a
+        // project's .sandoro.toml laid over it
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Config::default(),
+        Err(err) => return Err(err.into()),
+    };
+
+    let merged = merge(&table(base)?, &table(edited)?, &table(&disk)?);
+    let mut config: Config = Value::Table(merged).try_into()?;
+    config.revision = disk.revision + 1;
+    write_atomic(&path, &toml::to_string_pretty(&config)?)?;
+    Ok(config)
+}
+
+/// Replace config.toml with `content` as written, e.g. after
+/// `sandoro config edit`, so its comments and layout are kept. The version
+/// it replaces is kept as config.toml.bak, as on a save.
+pub fn replace(content: &str) -> Result<Config> {
+    let dir = Config::config_dir()?;
+    std::fs::create_dir_all(&dir)?;
+    replace_in(&dir, content)
+}
+
+fn replace_in(dir: &Path, content: &str) -> Result<Config> {
+    let config: Config = toml::from_str(content)?;
+    let _lock = Lock::acquire(dir.join("config.lock"))?;
+    let path = dir.join("config.toml");
+    if let Ok(current) = std::fs::read_to_string(&path) {
+        if toml::from_str::<Config>(&current).is_ok() {
+            write_atomic(&dir.join(BACKUP), &current)?;
+        }
+    }
+    write_atomic(&path, content)?;
+    Ok(config)
+}
+
+/// `onto` with the keys that differ between `base` and `edited` set as in
+/// `edited`, e.g. to keep a project's keys over a save's result
+pub fn apply_changes(base: &Config, edited: &Config, onto: &Config) -> Result<Config> {
+    let merged = merge(&table(base)?, &table(edited)?, &table(onto)?);
+    Ok(Value::Table(merged).try_into()?)
+}
+
+/// The backup kept by the last save, if there is one and it parses
+pub fn backup() -> Option<(PathBuf, SystemTime)> {
+    let path = Config::config_dir().ok()?.join(BACKUP);
+    backup_in(&path)
+}
+
+fn backup_in(path: &Path) -> Option<(PathBuf, SystemTime)> {
+    let content = std::fs::read_to_string(path).ok()?;
+    toml::from_str::<Config>(&content).ok()?;
+    let modified = std::fs::metadata(path).and_then(|m| m.modified()).ok()?;
+    Some((path.to_path_buf(), modified))
+}
+
+/// Put the backup back in place of a config.toml that doesn't parse. The
+/// broken file is kept as config.toml.broken.
+pub fn restore_backup() -> Result<Config> {
+    restore_backup_in(&Config::config_dir()?)
+}
+
+fn restore_backup_in(dir: &Path) -> Result<Config> {
+    let _lock = Lock::acquire(dir.join("config.lock"))?;
+    let content = std::fs::read_to_string(dir.join(BACKUP))?;
+    let config: Config = toml::from_str(&content)?;
+    let path = dir.join("config.toml");
+    if path.exists() {
+        std::fs::rename(&path, dir.join(BROKEN))?;
+    }
+    write_atomic(&path, &content)?;
+    Ok(config)
+}
+
+/// `config` as the table config.toml holds
+pub fn table(config: &Config) -> Result<Table> {
+    match Value::try_from(config)? {
+        Value::Table(table) => Ok(table),
+        _ => bail!("config is not a table"),
+    }
+}
+
+/// Write through a temporary file and a rename
+pub fn write_atomic(path: &Path, content: &str) -> Result<()> {
+    let tmp = path.with_extension(format!("toml.{}.tmp", std::process::id()));
+    let mut file = std::fs::File::create(&tmp)?;
+    file.write_all(content.as_bytes())?;
+    file.sync_all()?;
+    drop(file);
+    if let Err(err) = std::fs::rename(&tmp, path) {
+        let _ = std::fs::remove_file(&tmp);
+        return Err(err.into());
+    }
+    Ok(())
+}
+
+/// `disk` with the keys that differ between `base` and `edited` set as in
+/// `edited`
+fn merge(base: &Table, edited: &Table, disk: &Table) -> Table {
+    let mut merged = disk.clone();
+    merge_into(&mut merged, base, edited, disk, true);
+    merged
+}
+
+fn merge_into(merged: &mut Table, base: &Table, edited: &Table, disk: &Table, top: bool) {
+    let keys: Vec<&String> = base
+        .keys()
+        .chain(edited.keys().filter(|k| !base.contains_key(*k)))
+        .collect();
+    for key in keys {
+        // Bumped by every save, so it's never this frontend's change
+        if top && key == "revision" {
+            continue;
+        }
+        let (b, e) = (base.get(key), edited.get(key));
+        if b == e {
+            continue;
+        }
+        if let (Some(Value::Table(b)), Some(Value::Table(e))) = (b, e) {
+            let empty = Table::new();
+            let d = match disk.get(key) {
+                Some(Value::Table(d)) => d,
+                _ => &empty,
+            };
+            let mut sub = d.clone();
+            merge_into(&mut sub, b, e, d, false);
+            merged.insert(key.clone(), Value::Table(sub));
+            continue;
+        }
+        match e {
+            Some(value) => merged.insert(key.clone(), value.clone()),
+            None => merged.remove(key),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_keeps_other_writers_keys() {
+        let base = Config::default();
+        let mut mine = base.clone();
+        mine.appearance.theme = "nord".to_string();
+        mine.goals.daily_minutes = 120;
+        let mut theirs = base.clone();
+        theirs.timer.work_duration = 50;
+        theirs.goals.daily_minutes = 90;
+
+        let merged = merge(
+            &table(&base).unwrap(),
+            &table(&mine).unwrap(),
+            &table(&theirs).unwrap(),
+        );
+        let merged: Config = Value::Table(merged).try_into().unwrap();
+        assert_eq!(merged.appearance.theme, "nord");
+        assert_eq!(merged.timer.work_duration, 50);
+        // Both changed it: the later save wins
+        assert_eq!(merged.goals.daily_minutes, 120);
+    }
+
+    #[test]
+    fn test_saves_bump_revision_and_merge_on_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = Config::default();
+
+        let mut first = base.clone();
+        first.timer.work_duration = 45;
+        assert_eq!(save_in(dir.path(), &base, &first).unwrap().revision, 1);
+
+        // A second frontend still holding the original config
+        let mut second = base.clone();
+        second.notifications.sound = !base.notifications.sound;
+        let saved = save_in(dir.path(), &base, &second).unwrap();
+        assert_eq!(saved.revision, 2);
+        assert_eq!(saved.timer.work_duration, 45);
+
+        let content = std::fs::read_to_string(dir.path().join("config.toml")).unwrap();
+        let on_disk: Config = toml::from_str(&content).unwrap();
+        assert_eq!(on_disk.timer.work_duration, 45);
+        assert_eq!(on_disk.notifications.sound, !base.notifications.sound);
+        assert!(!dir.path().join("config.lock").exists());
+    }
+
+    #[test]
+    fn test_project_keys_stay_on_top_of_a_save() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(crate::config::PROJECT_FILE);
+        std::fs::write(
+            &path,
+            "[timer]\nwork_duration = 50\n[appearance]\ntheme = \"nord\"\n",
+        )
+        .unwrap();
+        let project = crate::config::ProjectConfig::load(&path).unwrap();
+        let base = project.apply(&Config::default()).unwrap();
+
+        let mut edited = base.clone();
+        edited.appearance.theme = "dracula".to_string();
+        edited.timer.short_break = 7;
+        let saved = save_in(dir.path(), &base, &edited).unwrap();
+        // Only the changes reach config.toml
+        assert_eq!(
+            saved.timer.work_duration,
+            Config::default().timer.work_duration
+        );
+        assert_eq!(saved.appearance.theme, "dracula");
+
+        let shown = apply_changes(&base, &edited, &project.apply(&saved).unwrap()).unwrap();
+        assert_eq!(shown.timer.work_duration, 50);
+        assert_eq!(shown.appearance.theme, "dracula");
+        assert_eq!(shown.timer.short_break, 7);
+    }
+
+    #[test]
+    fn test_save_keeps_a_backup_to_restore() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = Config::default();
+        let mut first = base.clone();
+        first.timer.work_duration = 45;
+        save_in(dir.path(), &base, &first).unwrap();
+        // Nothing to back up before the first save
+        assert!(backup_in(&dir.path().join(BACKUP)).is_none());
+
+        let mut second = first.clone();
+        second.timer.short_break = 10;
+        save_in(dir.path(), &first, &second).unwrap();
+        assert!(backup_in(&dir.path().join(BACKUP)).is_some());
+
+        // A save cut short leaves config.toml unreadable: the backup is the
+        // version before the last save
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, "[timer]\nwork_duration = ").unwrap();
+        assert!(save_in(dir.path(), &second, &base).is_err());
+        let restored = restore_backup_in(dir.path()).unwrap();
+        assert_eq!(restored.timer.work_duration, 45);
+        assert_eq!(restored.timer.short_break, base.timer.short_break);
+        let content = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(toml::from_str::<Config>(&content).unwrap().revision, 1);
+        assert!(dir.path().join(BROKEN).exists());
+    }
+
+    #[test]
+    fn test_replace_keeps_the_text_and_a_backup() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        std::fs::write(&path, "[timer]\nwork_duration = 45\n").unwrap();
+
+        let edited = "# Longer blocks\n[timer]\nwork_duration = 50\n";
+        assert_eq!(
+            replace_in(dir.path(), edited).unwrap().timer.work_duration,
+            50
+        );
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), edited);
+        assert!(backup_in(&dir.path().join(BACKUP)).is_some());
+
+        // Text that doesn't parse never reaches config.toml
+        assert!(replace_in(dir.path(), "[timer]\nwork_duration = \"long\"\n").is_err());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), edited);
+    }
+
+    #[test]
+    fn test_live_lock_times_out_and_stale_one_is_broken() {
+        let dir = tempfile::tempdir().unwrap();
+        let lock = dir.path().join("config.lock");
+        let held = Lock::acquire(lock.clone()).unwrap();
+        assert!(Lock::acquire(lock.clone()).is_err());
+        drop(held);
+
+        std::fs::write(&lock, "1").unwrap();
+        let old = SystemTime::now() - STALE_LOCK * 2;
+        std::fs::File::options()
+            .write(true)
+            .open(&lock)
+            .unwrap()
+            .set_modified(old)
+            .unwrap();
+        assert!(Lock::acquire(lock).is_ok());
+    }
+}
@@ -0,0 +1,247 @@
+//! Shareable stats card
+//!
+//! `sandoro share --output card.svg` draws one image for a blog post or a
+//! social feed: focus time, sessions, streak and best day over the last
+//! year, above the heatmap strip. Colors come from `appearance.theme` and
+//! `appearance.accent` like the HTML report. Only per-day totals go into
+//! it, never tags or notes.
+
+use anyhow::Result;
+use chrono::NaiveDate;
+
+use crate::config::Config;
+use crate::db::{DailyStats, StreakInfo};
+use crate::i18n;
+use crate::report::Palette;
+use crate::storage::Storage;
+use crate::svg::{self, escape, hex};
+
+/// Weeks the card covers by default: a year, like GitHub's graph
+pub const DEFAULT_WEEKS: i32 = 53;
+
+const PAD: u32 = 24;
+const TITLE_HEIGHT: u32 = 56;
+const FIGURES_HEIGHT: u32 = 64;
+/// Narrowest card, so the figures fit over a short heatmap
+const MIN_WIDTH: u32 = 560;
+
+/// What the card says, over the days of its heatmap
+#[derive(Debug, Clone, PartialEq)]
+pub struct Figures {
+    pub first: Option<NaiveDate>,
+    pub last: Option<NaiveDate>,
+    pub focus_seconds: i32,
+    pub sessions: i32,
+    pub current_streak: i32,
+    pub longest_streak: i32,
+    /// The day with the most focus, and its total
+    pub best_day: Option<(NaiveDate, i32)>,
+}
+
+impl Figures {
+    /// Figures for `days` (oldest first, as the heatmap has them)
+    pub fn from_days(days: &[DailyStats], streak: &StreakInfo) -> Self {
+        let dates: Vec<(NaiveDate, &DailyStats)> = days
+            .iter()
+            .filter_map(|d| {
+                NaiveDate::parse_from_str(&d.date, "%Y-%m-%d")
+                    .ok()
+                    .map(|date| (date, d))
+            })
+            .collect();
+        let best_day = dates
+            .iter()
+            .filter(|(_, d)| d.total_work_seconds > 0)
+            // The first of equal days, so the card doesn't change on a tie
+            .min_by_key(|(date, d)| (-d.total_work_seconds, *date))
+            .map(|(date, d)| (*date, d.total_work_seconds));
+        Self {
+            first: dates.first().map(|(date, _)| *date),
+            last: dates.last().map(|(date, _)| *date),
+            focus_seconds: days.iter().map(|d| d.total_work_seconds).sum(),
+            sessions: days.iter().map(|d| d.sessions_completed).sum(),
+            current_streak: streak.current,
+            longest_streak: streak.longest,
+            best_day,
+        }
+    }
+}
+
+/// Render the card for the last `weeks` weeks in `storage`
+pub fn build(storage: &dyn Storage, config: &Config, weeks: i32) -> Result<String> {
+    let days = storage.get_heatmap_data(weeks)?;
+    let figures = Figures::from_days(&days, &storage.get_streak()?);
+    Ok(render(
+        &figures,
+        &days,
+        &config.appearance.accent,
+        &Palette::from_config(config),
+    ))
+}
+
+/// Render the card from its figures and the heatmap's days
+pub fn render(figures: &Figures, days: &[DailyStats], accent: &str, palette: &Palette) -> String {
+    let (heatmap_width, heatmap_height) = svg::heatmap_size(days);
+    let width = (heatmap_width + 2 * PAD).max(MIN_WIDTH);
+    let heatmap_top = PAD + TITLE_HEIGHT + FIGURES_HEIGHT;
+    let height = heatmap_top + heatmap_height + PAD;
+    let (fg, muted) = (hex(palette.foreground), hex(palette.muted));
+
+    let mut card = format!(
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="-apple-system,Segoe UI,Helvetica,Arial,sans-serif">"#,
+        w = width,
+        h = height
+    );
+    card.push_str(&format!(
+        "<title>sandoro {}</title>",
+        i18n::t("focus graph")
+    ));
+    card.push_str(&format!(
+        r#"<rect x="0.5" y="0.5" width="{}" height="{}" rx="12" fill="{}" stroke="{}" stroke-opacity="0.4"/>"#,
+        width - 1,
+        height - 1,
+        hex(palette.background),
+        muted
+    ));
+
+    let period = match (figures.first, figures.last) {
+        (Some(first), Some(last)) => format!("{} – {}", first, last),
+        _ => String::new(),
+    };
+    card.push_str(&format!(
+        r#"<text x="{x}" y="{}" font-size="22" font-weight="bold" fill="{}">sandoro</text><text x="{x}" y="{}" font-size="13" fill="{}">{} · {}</text>"#,
+        PAD + 22,
+        hex(palette.accent),
+        PAD + 44,
+        muted,
+        i18n::t("Focus graph"),
+        escape(&period),
+        x = PAD
+    ));
+
+    let best = figures.best_day;
+    let column_width = (width - 2 * PAD) / 4;
+    for (index, (value, label)) in [
+        (
+            i18n::duration(figures.focus_seconds),
+            i18n::t("Focus").to_string(),
+        ),
+        (
+            figures.sessions.to_string(),
+            i18n::t("Sessions").to_string(),
+        ),
+        (
+            i18n::days(figures.current_streak),
+            format!(
+                "{} · {} {}",
+                i18n::t("Streak"),
+                i18n::t("best"),
+                figures.longest_streak
+            ),
+        ),
+        (
+            best.map_or("–".to_string(), |(_, seconds)| i18n::duration(seconds)),
+            match best {
+                Some((date, _)) => format!("{} · {}", i18n::t("Best day"), i18n::month_day(date)),
+                None => i18n::t("Best day").to_string(),
+            },
+        ),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let x = PAD + index as u32 * column_width;
+        let top = PAD + TITLE_HEIGHT;
+        card.push_str(&format!(
+            r#"<text x="{x}" y="{}" font-size="20" font-weight="bold" fill="{}">{}</text><text x="{x}" y="{}" font-size="12" fill="{}">{}</text>"#,
+            top + 22,
+            fg,
+            escape(&value),
+            top + 42,
+            muted,
+            escape(&label),
+            x = x
+        ));
+    }
+
+    card.push_str(&format!(
+        r#"<g transform="translate({}, {})">{}</g>"#,
+        PAD,
+        heatmap_top,
+        svg::heatmap(days, accent)
+    ));
+    card.push_str("</svg>");
+    card
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(date: &str, minutes: i32, sessions: i32) -> DailyStats {
+        DailyStats {
+            date: date.to_string(),
+            total_work_seconds: minutes * 60,
+            sessions_completed: sessions,
+            longest_streak: 0,
+        }
+    }
+
+    fn palette() -> Palette {
+        Palette {
+            background: (0, 0, 0),
+            foreground: (255, 255, 255),
+            muted: (128, 128, 128),
+            accent: (0, 200, 200),
+            rainbow: false,
+        }
+    }
+
+    #[test]
+    fn test_figures_over_the_heatmap_days() {
+        let days = vec![
+            day("2026-10-14", 0, 0),
+            day("2026-10-15", 90, 3),
+            day("2026-10-16", 120, 4),
+            day("2026-10-17", 120, 4),
+        ];
+        let streak = StreakInfo {
+            current: 3,
+            longest: 9,
+        };
+        let figures = Figures::from_days(&days, &streak);
+        assert_eq!(figures.focus_seconds, 330 * 60);
+        assert_eq!(figures.sessions, 11);
+        assert_eq!(figures.first, NaiveDate::from_ymd_opt(2026, 10, 14));
+        // The earlier of two equal days
+        assert_eq!(
+            figures.best_day,
+            Some((NaiveDate::from_ymd_opt(2026, 10, 16).unwrap(), 120 * 60))
+        );
+        assert_eq!(Figures::from_days(&days[..1], &streak).best_day, None);
+    }
+
+    #[test]
+    fn test_card_holds_the_figures_and_the_heatmap() {
+        let days = vec![day("2026-10-16", 0, 0), day("2026-10-17", 50, 2)];
+        let streak = StreakInfo {
+            current: 1,
+            longest: 4,
+        };
+        let card = render(
+            &Figures::from_days(&days, &streak),
+            &days,
+            "cyan",
+            &palette(),
+        );
+        assert!(card.starts_with(&format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}""#,
+            MIN_WIDTH
+        )));
+        assert!(card.contains("2026-10-16 – 2026-10-17"));
+        assert!(card.contains(">50m</text>"));
+        assert!(card.contains(r##"fill="#00c8c8">sandoro</text>"##));
+        assert_eq!(card.matches("<svg").count(), 2);
+        assert!(card.contains("<title>2026-10-17: 50m</title>"));
+    }
+}
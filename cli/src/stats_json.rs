@@ -0,0 +1,243 @@
+//! Machine-readable stats output
+//!
+//! Builds the document printed by `sandoro stats --json`. The schema is
+//! documented in docs/STATS_JSON.md; bump SCHEMA_VERSION on breaking changes.
+
+use anyhow::Result;
+use chrono::Utc;
+use serde::Serialize;
+
+use crate::accountability;
+use crate::analytics::{self, TrendSummary};
+use crate::config::Config;
+use crate::db::{DailyStats, Database};
+use crate::goals::{self, WeekOutcome};
+
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Days covered by the by_tag and by_hour aggregates
+const BREAKDOWN_DAYS: i32 = 30;
+
+#[derive(Debug, Serialize)]
+pub struct StatsDocument {
+    pub schema_version: u32,
+    pub generated_at: String,
+    pub today: Period,
+    /// Only present when `--date` is given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<Period>,
+    pub last_7_days: Period,
+    pub last_30_days: Period,
+    pub previous_7_days: Period,
+    pub previous_30_days: Period,
+    pub streak: Streak,
+    /// One entry per day (oldest first), including days without work
+    pub daily: Vec<Period>,
+    pub by_tag: Vec<TagBucket>,
+    pub by_hour: Vec<HourBucket>,
+    pub goals: Goals,
+    /// Only present when the break skip penalty is on
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accountability: Option<Accountability>,
+    pub trend: TrendSummary,
+}
+
+/// Totals of completed work sessions
+#[derive(Debug, Serialize)]
+pub struct Period {
+    pub label: String,
+    pub focus_seconds: i32,
+    pub sessions: i32,
+}
+
+impl From<DailyStats> for Period {
+    fn from(stats: DailyStats) -> Self {
+        Self {
+            label: stats.date,
+            focus_seconds: stats.total_work_seconds,
+            sessions: stats.sessions_completed,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Streak {
+    pub current: i32,
+    pub longest: i32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TagBucket {
+    /// None for untagged sessions
+    pub tag_id: Option<i64>,
+    pub tag: Option<String>,
+    pub focus_seconds: i32,
+    pub sessions: i32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct HourBucket {
+    /// Local hour of day, 0-23
+    pub hour: u32,
+    pub focus_seconds: i32,
+    pub sessions: i32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Goals {
+    pub daily_minutes: Goal,
+    pub daily_sessions: Goal,
+    /// First day (YYYY-MM-DD) of the calendar week the weekly goals cover
+    pub week_start: String,
+    pub weekly_minutes: Goal,
+    pub weekly_sessions: Goal,
+    /// Recorded past weeks, most recent first
+    pub weekly_history: Vec<GoalWeek>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GoalWeek {
+    pub week_start: String,
+    pub minutes: Goal,
+    pub sessions: Goal,
+    pub met: bool,
+}
+
+impl From<WeekOutcome> for GoalWeek {
+    fn from(outcome: WeekOutcome) -> Self {
+        Self {
+            week_start: outcome.week.start.format("%Y-%m-%d").to_string(),
+            minutes: Goal::new(outcome.minutes_goal, outcome.week.minutes),
+            sessions: Goal::new(outcome.sessions_goal, outcome.week.sessions),
+            met: outcome.met(),
+        }
+    }
+}
+
+/// Today's skipped breaks and the resulting quality focus
+#[derive(Debug, Serialize)]
+pub struct Accountability {
+    pub skipped_breaks: i32,
+    pub free_skips: u32,
+    pub penalty_minutes_per_skip: u32,
+    pub penalty_seconds: i32,
+    pub quality_focus_seconds: i32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Goal {
+    /// 0 when the goal is not set
+    pub target: u32,
+    pub current: i32,
+    /// Progress capped at 100, or null when the goal is not set
+    pub percent: Option<u32>,
+}
+
+impl Goal {
+    fn new(target: u32, current: i32) -> Self {
+        let percent = (target > 0)
+            .then(|| ((current.max(0) as f64 / target as f64) * 100.0).min(100.0) as u32);
+        Self {
+            target,
+            current,
+            percent,
+        }
+    }
+}
+
+/// Build the stats document
+pub fn build(
+    db: &Database,
+    config: &Config,
+    date: Option<&str>,
+    weeks: i32,
+) -> Result<StatsDocument> {
+    let today = db.get_today_stats()?;
+    let week = db.get_week_stats()?;
+    let streak = db.get_streak()?;
+    let heatmap = db.get_heatmap_data(weeks.max(4))?;
+
+    let minutes: Vec<f64> = heatmap
+        .iter()
+        .map(|s| f64::from(s.total_work_seconds) / 60.0)
+        .collect();
+
+    let day = db.today()?;
+    let goal_week = goals::current_week(db, config, day)?;
+    let goals = Goals {
+        daily_minutes: Goal::new(config.goals.daily_minutes, today.total_work_seconds / 60),
+        daily_sessions: Goal::new(config.goals.daily_sessions, today.sessions_completed),
+        week_start: goal_week.start.format("%Y-%m-%d").to_string(),
+        weekly_minutes: Goal::new(config.goals.weekly_minutes, goal_week.minutes),
+        weekly_sessions: Goal::new(config.goals.weekly_sessions, goal_week.sessions),
+        weekly_history: goals::history(db, config, day)?
+            .into_iter()
+            .map(GoalWeek::from)
+            .collect(),
+    };
+
+    let accountability =
+        accountability::today(db, &config.accountability)?.map(|penalty| Accountability {
+            skipped_breaks: penalty.skipped,
+            free_skips: config.accountability.free_skips,
+            penalty_minutes_per_skip: config.accountability.skip_penalty_minutes,
+            penalty_seconds: penalty.penalty_seconds,
+            quality_focus_seconds: penalty.quality_focus(today.total_work_seconds),
+        });
+
+    let by_tag = db
+        .get_stats_by_tag(BREAKDOWN_DAYS)?
+        .into_iter()
+        .map(|(tag, focus_seconds, sessions)| TagBucket {
+            tag_id: tag.as_ref().map(|t| t.id),
+            tag: tag.map(|t| t.name),
+            focus_seconds,
+            sessions,
+        })
+        .collect();
+
+    let by_hour = db
+        .get_stats_by_hour(BREAKDOWN_DAYS)?
+        .into_iter()
+        .map(|(hour, focus_seconds, sessions)| HourBucket {
+            hour,
+            focus_seconds,
+            sessions,
+        })
+        .collect();
+
+    Ok(StatsDocument {
+        schema_version: SCHEMA_VERSION,
+        generated_at: Utc::now().to_rfc3339(),
+        today: today.into(),
+        date: date
+            .map(|d| db.get_date_stats(d))
+            .transpose()?
+            .map(Period::from),
+        last_7_days: week.into(),
+        last_30_days: db.get_month_stats()?.into(),
+        previous_7_days: db.get_previous_week_stats()?.into(),
+        previous_30_days: db.get_previous_month_stats()?.into(),
+        streak: Streak {
+            current: streak.current,
+            longest: streak.longest,
+        },
+        daily: heatmap
+            .into_iter()
+            .rev()
+            .take((weeks.max(1) * 7) as usize)
+            .rev()
+            .map(Period::from)
+            .collect(),
+        by_tag,
+        by_hour,
+        goals,
+        accountability,
+        trend: analytics::summarize_trend(&minutes),
+    })
+}
+
+/// Render the document as pretty-printed JSON
+pub fn render(doc: &StatsDocument) -> Result<String> {
+    Ok(serde_json::to_string_pretty(doc)?)
+}
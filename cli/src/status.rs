@@ -0,0 +1,141 @@
+//! Live timer status
+//!
+//! The TUI publishes its state to ~/.sandoro/status.json so other processes
+//! (git hooks, status lines) can read it without talking to the timer. A file
+//! that hasn't been refreshed recently is treated as "not running", which
+//! covers a timer that was killed before it could clean up.
+
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+use crate::config::Config;
+
+/// The TUI rewrites the file at least this often while running
+pub const REFRESH_SECS: i64 = 5;
+/// Readers ignore a file older than this
+const STALE_AFTER_SECS: i64 = 15;
+
+/// Snapshot of a running timer
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LiveStatus {
+    pub pid: u32,
+    /// "work", "short_break", "long_break" or "timer"
+    pub state: String,
+    pub paused: bool,
+    pub remaining_seconds: u32,
+    /// Time spent in a flowtime work session
+    pub elapsed_seconds: u32,
+    /// Whether a work session has been started and is being recorded
+    pub session_active: bool,
+    /// Today's work session number, counting the current one
+    pub session_number: u32,
+    /// Work session within the cycle, and sessions until the long break
+    /// (both 0 in timer mode, or from a version without them)
+    #[serde(default)]
+    pub cycle_position: u32,
+    #[serde(default)]
+    pub cycle_length: u32,
+    pub tag: Option<String>,
+    /// Database row of the session being recorded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<i64>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl LiveStatus {
+    fn is_fresh(&self, now: DateTime<Utc>) -> bool {
+        now - self.updated_at < Duration::seconds(STALE_AFTER_SECS)
+    }
+
+    /// Whether anything other than the clock changed
+    pub fn differs_from(&self, other: &LiveStatus) -> bool {
+        self.state != other.state
+            || self.paused != other.paused
+            || self.session_active != other.session_active
+            || self.session_number != other.session_number
+            || self.tag != other.tag
+            || self.session_id != other.session_id
+    }
+}
+
+pub fn path() -> Result<PathBuf> {
+    Ok(Config::config_dir()?.join("status.json"))
+}
+
+/// Publish the status, replacing the file atomically
+pub fn write(status: &LiveStatus) -> Result<()> {
+    let path = path()?;
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, serde_json::to_string(status)?)?;
+    std::fs::rename(&tmp, &path)?;
+    Ok(())
+}
+
+/// Read the running timer's status; None when no timer is running
+pub fn read() -> Result<Option<LiveStatus>> {
+    Ok(read_last()?.filter(|status| status.is_fresh(Utc::now())))
+}
+
+/// Read the last status written, even by a timer that has since died
+pub fn read_last() -> Result<Option<LiveStatus>> {
+    let path = path()?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    match serde_json::from_str(&std::fs::read_to_string(path)?) {
+        Ok(status) => Ok(Some(status)),
+        // A file from an incompatible version is as good as none
+        Err(_) => Ok(None),
+    }
+}
+
+/// Remove the status file when the timer exits
+pub fn clear() -> Result<()> {
+    let path = path()?;
+    if path.exists() {
+        std::fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status() -> LiveStatus {
+        LiveStatus {
+            pid: 1,
+            state: "work".to_string(),
+            paused: false,
+            remaining_seconds: 600,
+            elapsed_seconds: 0,
+            session_active: true,
+            session_number: 3,
+            cycle_position: 2,
+            cycle_length: 4,
+            tag: Some("writing".to_string()),
+            session_id: Some(7),
+            updated_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_freshness() {
+        let s = status();
+        assert!(s.is_fresh(s.updated_at + Duration::seconds(REFRESH_SECS)));
+        assert!(!s.is_fresh(s.updated_at + Duration::seconds(STALE_AFTER_SECS)));
+    }
+
+    #[test]
+    fn test_differs_ignores_clock() {
+        let a = status();
+        let mut b = a.clone();
+        b.remaining_seconds -= 1;
+        b.updated_at = a.updated_at + Duration::seconds(1);
+        assert!(!a.differs_from(&b));
+        b.paused = true;
+        assert!(a.differs_from(&b));
+    }
+}
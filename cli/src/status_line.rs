@@ -0,0 +1,261 @@
+//! One-line timer status for status bars
+//!
+//! `sandoro status` reads the live status file the TUI and the daemon keep
+//! up to date (see status.rs) and prints it as a single line for waybar,
+//! polybar, tmux or a shell prompt: `🍅 14:32 WORK (2/4)`. When no timer is
+//! running, the last state written is shown as stopped.
+
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use serde_json::json;
+
+use crate::db::DailyStats;
+use crate::status::LiveStatus;
+
+/// `--format` of `sandoro status`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum Format {
+    /// Plain text, also for polybar and shell prompts
+    #[default]
+    Plain,
+    /// JSON for a waybar custom module (text, tooltip, class)
+    Waybar,
+    /// Text with tmux color markup, for status-right
+    Tmux,
+    /// Everything, with today's totals
+    Json,
+}
+
+/// What `sandoro status` shows
+pub struct Snapshot {
+    /// The last status written, None when there never was one
+    pub status: Option<LiveStatus>,
+    /// Whether the timer that wrote it is still running
+    pub running: bool,
+    /// Today's completed work, when the database could be read
+    pub today: Option<DailyStats>,
+}
+
+impl Snapshot {
+    /// Class for styling: the state, or "paused" / "stopped"
+    fn class(&self) -> &str {
+        match &self.status {
+            Some(_) if !self.running => "stopped",
+            Some(status) if status.paused => "paused",
+            Some(status) => &status.state,
+            None => "stopped",
+        }
+    }
+
+    /// `🍅 14:32 WORK (2/4)`; empty when there's no status at all
+    fn text(&self) -> String {
+        let Some(status) = &self.status else {
+            return String::new();
+        };
+        let glyph = match self.class() {
+            "stopped" => "⏹",
+            "paused" => "⏸",
+            "short_break" => "☕",
+            "long_break" => "🌴",
+            "timer" => "⏱",
+            _ => "🍅",
+        };
+        let label = match status.state.as_str() {
+            "short_break" => "BREAK",
+            "long_break" => "LONG BREAK",
+            "timer" => "TIMER",
+            _ => "WORK",
+        };
+        let cycle = if status.cycle_length > 0 {
+            format!(" ({}/{})", status.cycle_position, status.cycle_length)
+        } else {
+            String::new()
+        };
+        format!(
+            "{} {} {}{}",
+            glyph,
+            clock(shown_seconds(status)),
+            label,
+            cycle
+        )
+    }
+}
+
+/// Move a running timer's clock on to `now`; the file is only rewritten
+/// every few seconds
+pub fn catch_up(status: &mut LiveStatus, now: DateTime<Utc>) {
+    if status.paused {
+        return;
+    }
+    let lag = (now - status.updated_at).num_seconds().max(0) as u32;
+    if status.remaining_seconds == 0 {
+        status.elapsed_seconds += lag;
+    } else {
+        status.remaining_seconds = status.remaining_seconds.saturating_sub(lag);
+    }
+}
+
+/// Counting up (flowtime, stopwatch) shows the time in, otherwise the time
+/// left
+fn shown_seconds(status: &LiveStatus) -> u32 {
+    if status.remaining_seconds == 0 && status.elapsed_seconds > 0 {
+        status.elapsed_seconds
+    } else {
+        status.remaining_seconds
+    }
+}
+
+/// `14:32`, or `92:00` for a long timer
+fn clock(seconds: u32) -> String {
+    format!("{:02}:{:02}", seconds / 60, seconds % 60)
+}
+
+/// tmux color for each class
+fn tmux_color(class: &str) -> &'static str {
+    match class {
+        "work" => "red",
+        "short_break" => "green",
+        "long_break" => "blue",
+        "timer" => "magenta",
+        "paused" => "yellow",
+        _ => "default",
+    }
+}
+
+/// The snapshot as one line in `format`
+pub fn render(snapshot: &Snapshot, format: Format) -> Result<String> {
+    let text = snapshot.text();
+    Ok(match format {
+        Format::Plain => text,
+        Format::Tmux if text.is_empty() => text,
+        Format::Tmux => format!("#[fg={}]{}#[default]", tmux_color(snapshot.class()), text),
+        Format::Waybar => {
+            let tooltip = match &snapshot.status {
+                Some(status) => {
+                    let tag = status.tag.as_deref().unwrap_or("no tag");
+                    format!("{} · session {} today", tag, status.session_number)
+                }
+                None => "sandoro isn't running".to_string(),
+            };
+            serde_json::to_string(&json!({
+                "text": text,
+                "tooltip": tooltip,
+                "class": snapshot.class(),
+                "alt": snapshot.class(),
+            }))?
+        }
+        Format::Json => {
+            let status = snapshot.status.as_ref();
+            serde_json::to_string(&json!({
+                "running": snapshot.running,
+                "state": status.map(|s| s.state.as_str()),
+                "paused": status.map(|s| s.paused),
+                "remaining_seconds": status.map(|s| s.remaining_seconds),
+                "elapsed_seconds": status.map(|s| s.elapsed_seconds),
+                "session_number": status.map(|s| s.session_number),
+                "cycle_position": status.map(|s| s.cycle_position),
+                "cycle_length": status.map(|s| s.cycle_length),
+                "tag": status.and_then(|s| s.tag.as_deref()),
+                "today": snapshot.today.as_ref().map(|t| json!({
+                    "work_seconds": t.total_work_seconds,
+                    "sessions": t.sessions_completed,
+                })),
+            }))?
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn snapshot(state: &str, paused: bool, running: bool) -> Snapshot {
+        Snapshot {
+            status: Some(LiveStatus {
+                pid: 1,
+                state: state.to_string(),
+                paused,
+                remaining_seconds: 14 * 60 + 32,
+                elapsed_seconds: 10 * 60 + 28,
+                session_active: true,
+                session_number: 3,
+                cycle_position: 2,
+                cycle_length: 4,
+                tag: Some("writing".to_string()),
+                session_id: Some(7),
+                updated_at: Utc::now(),
+            }),
+            running,
+            today: Some(DailyStats {
+                date: "2026-10-17".to_string(),
+                total_work_seconds: 3000,
+                sessions_completed: 2,
+                longest_streak: 0,
+            }),
+        }
+    }
+
+    #[test]
+    fn test_plain_and_tmux_lines() {
+        let work = snapshot("work", false, true);
+        assert_eq!(render(&work, Format::Plain).unwrap(), "🍅 14:32 WORK (2/4)");
+        assert_eq!(
+            render(&work, Format::Tmux).unwrap(),
+            "#[fg=red]🍅 14:32 WORK (2/4)#[default]"
+        );
+        let paused = snapshot("short_break", true, true);
+        assert_eq!(
+            render(&paused, Format::Plain).unwrap(),
+            "⏸ 14:32 BREAK (2/4)"
+        );
+        let stopped = snapshot("work", false, false);
+        assert!(render(&stopped, Format::Plain).unwrap().starts_with("⏹ "));
+
+        let none = Snapshot {
+            status: None,
+            running: false,
+            today: None,
+        };
+        assert_eq!(render(&none, Format::Tmux).unwrap(), "");
+    }
+
+    #[test]
+    fn test_stopwatch_shows_time_in_without_a_cycle() {
+        let mut timer = snapshot("timer", false, true);
+        if let Some(status) = timer.status.as_mut() {
+            status.remaining_seconds = 0;
+            status.cycle_length = 0;
+        }
+        assert_eq!(render(&timer, Format::Plain).unwrap(), "⏱ 10:28 TIMER");
+    }
+
+    #[test]
+    fn test_catch_up_moves_a_running_clock() {
+        let mut status = snapshot("work", false, true).status.unwrap();
+        let now = status.updated_at + Duration::seconds(4);
+        catch_up(&mut status, now);
+        assert_eq!(status.remaining_seconds, 14 * 60 + 28);
+
+        status.paused = true;
+        catch_up(&mut status, now + Duration::seconds(4));
+        assert_eq!(status.remaining_seconds, 14 * 60 + 28);
+    }
+
+    #[test]
+    fn test_waybar_and_json() {
+        let work = snapshot("work", false, true);
+        let waybar: serde_json::Value =
+            serde_json::from_str(&render(&work, Format::Waybar).unwrap()).unwrap();
+        assert_eq!(waybar["class"], "work");
+        assert_eq!(waybar["tooltip"], "writing · session 3 today");
+
+        let doc: serde_json::Value =
+            serde_json::from_str(&render(&work, Format::Json).unwrap()).unwrap();
+        assert_eq!(doc["state"], "work");
+        assert_eq!(doc["remaining_seconds"], 872);
+        assert_eq!(doc["cycle_position"], 2);
+        assert_eq!(doc["today"]["work_seconds"], 3000);
+        assert_eq!(doc["today"]["sessions"], 2);
+    }
+}
@@ -0,0 +1,331 @@
+//! Storage backends
+//!
+//! `Storage` is the part of the database API the timer and the summary
+//! stats need: recording sessions, tags, daily totals and streaks. SQLite
+//! (`Database`) is the default; a Postgres backend is available with the
+//! `postgres` feature and `[storage] backend = "postgres"`.
+//!
+//! Everything else (the today board, recovery, search, journal, goals,
+//! retention) still needs SQLite and is reached through `Storage::sqlite`.
+//! With another backend those features are simply unavailable.
+
+use anyhow::Result;
+use chrono::{DateTime, NaiveDate, Utc};
+
+use crate::config::{Config, StorageBackend};
+use crate::db::{DailyStats, Database, Session, SessionMode, SessionType, StreakInfo, Tag};
+
+/// `Send` so the timer can open it on a background thread at startup
+pub trait Storage: Send {
+    /// Start a session timed in `mode`, returning its id
+    fn start_session_in_mode(
+        &self,
+        session_type: SessionType,
+        tag_id: Option<i64>,
+        planned_seconds: Option<i32>,
+        mode: SessionMode,
+    ) -> Result<i64>;
+    fn complete_session(&self, session_id: i64, duration_seconds: i32) -> Result<()>;
+    /// Close a session that stopped before its end; it doesn't count
+    fn interrupt_session(
+        &self,
+        session_id: i64,
+        ended_at: DateTime<Utc>,
+        duration_seconds: i32,
+    ) -> Result<()>;
+    /// Close a session another device took over; it counts there, not here
+    fn hand_off_session(
+        &self,
+        session_id: i64,
+        ended_at: DateTime<Utc>,
+        duration_seconds: i32,
+        to: &str,
+    ) -> Result<()>;
+    fn log_session_event(&self, session_id: i64, event: &str, detail: Option<&str>) -> Result<()>;
+    fn delete_session(&self, session_id: i64) -> Result<()>;
+    fn update_session_tag(&self, session_id: i64, tag_id: Option<i64>) -> Result<()>;
+    /// Latest completed work sessions with their tags
+    fn get_recent_sessions(&self, limit: i32) -> Result<Vec<(Session, Option<Tag>)>>;
+
+    fn get_all_tags(&self) -> Result<Vec<Tag>>;
+    fn create_tag(&self, name: &str, color: Option<&str>) -> Result<i64>;
+    fn delete_tag(&self, tag_id: i64) -> Result<()>;
+
+    /// Totals for one UTC date (YYYY-MM-DD)
+    fn get_date_stats(&self, date: &str) -> Result<DailyStats>;
+    /// Totals for dates in `[start, end)`
+    fn get_range_stats(&self, start: &str, end: &str) -> Result<DailyStats>;
+    /// Totals per day with work over the last N days, newest first
+    fn get_daily_stats(&self, days: i32) -> Result<Vec<DailyStats>>;
+    /// Today's completed work by tag, largest first: (tag, seconds)
+    fn get_today_stats_by_tag(&self) -> Result<Vec<(Option<Tag>, i32)>>;
+    fn get_streak(&self) -> Result<StreakInfo>;
+
+    fn get_metadata(&self, key: &str) -> Result<Option<String>>;
+    fn set_metadata(&self, key: &str, value: &str) -> Result<()>;
+
+    /// The SQLite database, for features only it supports
+    fn sqlite(&self) -> Option<&Database> {
+        None
+    }
+
+    /// The day a session started now would count towards; the UTC date
+    /// unless the backend follows `[days] policy`
+    fn today(&self) -> Result<NaiveDate> {
+        Ok(Utc::now().date_naive())
+    }
+
+    fn get_today_stats(&self) -> Result<DailyStats> {
+        self.get_date_stats(&utc_date(0))
+    }
+
+    /// Last 7 days
+    fn get_week_stats(&self) -> Result<DailyStats> {
+        labeled_range(self, 7, "Last 7 days")
+    }
+
+    /// Last 30 days
+    fn get_month_stats(&self) -> Result<DailyStats> {
+        labeled_range(self, 30, "Last 30 days")
+    }
+
+    /// Every day of the last N weeks up to today, oldest first, including
+    /// days without work
+    fn get_heatmap_data(&self, weeks: i32) -> Result<Vec<DailyStats>> {
+        let days = weeks * 7;
+        let totals: std::collections::HashMap<String, DailyStats> = self
+            .get_daily_stats(days)?
+            .into_iter()
+            .map(|d| (d.date.clone(), d))
+            .collect();
+        let today = self.today()?;
+        Ok((0..days)
+            .rev()
+            .map(|i| {
+                let date = (today - chrono::Duration::days(i64::from(i)))
+                    .format("%Y-%m-%d")
+                    .to_string();
+                totals.get(&date).cloned().unwrap_or(DailyStats {
+                    date,
+                    total_work_seconds: 0,
+                    sessions_completed: 0,
+                    longest_streak: 0,
+                })
+            })
+            .collect())
+    }
+}
+
+/// Totals since `days` days ago, labeled for display
+fn labeled_range<S: Storage + ?Sized>(storage: &S, days: i64, label: &str) -> Result<DailyStats> {
+    let mut stats = storage.get_range_stats(&utc_date(days), "9999-12-31")?;
+    stats.date = label.to_string();
+    Ok(stats)
+}
+
+/// The UTC date `days_ago` days back, as stored dates are grouped
+fn utc_date(days_ago: i64) -> String {
+    (Utc::now() - chrono::Duration::days(days_ago))
+        .format("%Y-%m-%d")
+        .to_string()
+}
+
+impl Storage for Database {
+    fn start_session_in_mode(
+        &self,
+        session_type: SessionType,
+        tag_id: Option<i64>,
+        planned_seconds: Option<i32>,
+        mode: SessionMode,
+    ) -> Result<i64> {
+        Database::start_session_in_mode(self, session_type, tag_id, planned_seconds, mode)
+    }
+
+    fn complete_session(&self, session_id: i64, duration_seconds: i32) -> Result<()> {
+        Database::complete_session(self, session_id, duration_seconds)
+    }
+
+    fn interrupt_session(
+        &self,
+        session_id: i64,
+        ended_at: DateTime<Utc>,
+        duration_seconds: i32,
+    ) -> Result<()> {
+        Database::interrupt_session(self, session_id, ended_at, duration_seconds)
+    }
+
+    fn hand_off_session(
+        &self,
+        session_id: i64,
+        ended_at: DateTime<Utc>,
+        duration_seconds: i32,
+        to: &str,
+    ) -> Result<()> {
+        Database::hand_off_session(self, session_id, ended_at, duration_seconds, to)
+    }
+
+    fn log_session_event(&self, session_id: i64, event: &str, detail: Option<&str>) -> Result<()> {
+        Database::log_session_event(self, session_id, event, detail)
+    }
+
+    fn delete_session(&self, session_id: i64) -> Result<()> {
+        Database::delete_session(self, session_id)
+    }
+
+    fn update_session_tag(&self, session_id: i64, tag_id: Option<i64>) -> Result<()> {
+        Database::update_session_tag(self, session_id, tag_id)
+    }
+
+    fn get_recent_sessions(&self, limit: i32) -> Result<Vec<(Session, Option<Tag>)>> {
+        Database::get_recent_sessions(self, limit)
+    }
+
+    fn get_all_tags(&self) -> Result<Vec<Tag>> {
+        Database::get_all_tags(self)
+    }
+
+    fn create_tag(&self, name: &str, color: Option<&str>) -> Result<i64> {
+        Database::create_tag(self, name, color)
+    }
+
+    fn delete_tag(&self, tag_id: i64) -> Result<()> {
+        Database::delete_tag(self, tag_id)
+    }
+
+    fn get_date_stats(&self, date: &str) -> Result<DailyStats> {
+        Database::get_date_stats(self, date)
+    }
+
+    fn get_range_stats(&self, start: &str, end: &str) -> Result<DailyStats> {
+        Database::get_range_stats(self, start, end)
+    }
+
+    fn get_daily_stats(&self, days: i32) -> Result<Vec<DailyStats>> {
+        Database::get_daily_stats(self, days)
+    }
+
+    fn get_today_stats_by_tag(&self) -> Result<Vec<(Option<Tag>, i32)>> {
+        Database::get_today_stats_by_tag(self)
+    }
+
+    fn get_streak(&self) -> Result<StreakInfo> {
+        Database::get_streak(self)
+    }
+
+    fn get_metadata(&self, key: &str) -> Result<Option<String>> {
+        Database::get_metadata(self, key)
+    }
+
+    fn set_metadata(&self, key: &str, value: &str) -> Result<()> {
+        Database::set_metadata(self, key, value)
+    }
+
+    fn sqlite(&self) -> Option<&Database> {
+        Some(self)
+    }
+
+    fn today(&self) -> Result<NaiveDate> {
+        Database::today(self)
+    }
+
+    // SQLite has its own queries for these; keep using them
+
+    fn get_today_stats(&self) -> Result<DailyStats> {
+        Database::get_today_stats(self)
+    }
+
+    fn get_week_stats(&self) -> Result<DailyStats> {
+        Database::get_week_stats(self)
+    }
+
+    fn get_month_stats(&self) -> Result<DailyStats> {
+        Database::get_month_stats(self)
+    }
+
+    fn get_heatmap_data(&self, weeks: i32) -> Result<Vec<DailyStats>> {
+        Database::get_heatmap_data(self, weeks)
+    }
+}
+
+/// Open the configured backend
+pub fn open(config: &Config) -> Result<Box<dyn Storage>> {
+    match config.storage.backend {
+        StorageBackend::Sqlite => Ok(Box::new(Database::open()?)),
+        StorageBackend::Postgres => open_postgres(config),
+    }
+}
+
+/// Open the configured backend for reading only (stats, serve). SQLite
+/// opens read-only so it never blocks a running timer.
+pub fn open_readonly(config: &Config) -> Result<Box<dyn Storage>> {
+    match config.storage.backend {
+        StorageBackend::Sqlite => Ok(Box::new(Database::open_readonly()?)),
+        StorageBackend::Postgres => open_postgres(config),
+    }
+}
+
+#[cfg(feature = "postgres")]
+fn open_postgres(config: &Config) -> Result<Box<dyn Storage>> {
+    Ok(Box::new(crate::pg_storage::PgStorage::connect(
+        &config.storage.url,
+    )?))
+}
+
+#[cfg(not(feature = "postgres"))]
+fn open_postgres(_config: &Config) -> Result<Box<dyn Storage>> {
+    anyhow::bail!(
+        "This build of sandoro has no Postgres support. \
+         Rebuild with `cargo install sandoro --features postgres`, \
+         or set [storage] backend = \"sqlite\"."
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sqlite_through_the_trait() {
+        let storage: Box<dyn Storage> = Box::new(Database::open_in_memory().unwrap());
+        let tag = storage.create_tag("writing", None).unwrap();
+        let id = storage
+            .start_session_in_mode(
+                SessionType::Work,
+                Some(tag),
+                Some(1500),
+                SessionMode::Classic,
+            )
+            .unwrap();
+        storage.complete_session(id, 1500).unwrap();
+
+        assert_eq!(storage.get_today_stats().unwrap().total_work_seconds, 1500);
+        assert_eq!(storage.get_week_stats().unwrap().sessions_completed, 1);
+        assert_eq!(storage.get_streak().unwrap().current, 1);
+        assert_eq!(storage.get_heatmap_data(1).unwrap().len(), 7);
+        assert_eq!(
+            storage.get_recent_sessions(5).unwrap()[0]
+                .1
+                .as_ref()
+                .map(|t| t.id),
+            Some(tag)
+        );
+        assert!(storage.sqlite().is_some());
+        // Today follows the database's day policy, not the UTC default
+        let today = storage.sqlite().unwrap().today().unwrap();
+        assert_eq!(storage.today().unwrap(), today);
+        let heatmap = storage.get_heatmap_data(1).unwrap();
+        assert_eq!(
+            heatmap.last().unwrap().date,
+            today.format("%Y-%m-%d").to_string()
+        );
+    }
+
+    #[test]
+    #[cfg(not(feature = "postgres"))]
+    fn test_postgres_needs_the_feature() {
+        let mut config = Config::default();
+        config.storage.backend = StorageBackend::Postgres;
+        let err = open(&config).err().unwrap().to_string();
+        assert!(err.contains("no Postgres support"));
+    }
+}
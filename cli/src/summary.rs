@@ -0,0 +1,491 @@
+//! Weekly and monthly summary for `sandoro report --week|--month`
+//!
+//! A plain-text report of the last 7 or 30 days: focus time and sessions
+//! against the period before, time per tag, average session length,
+//! completion rate, best day, a sparkline of daily totals, the journal
+//! notes written in the period and, for a week, the grade of last week's
+//! plan and how this week's is going; for a month, the average day for
+//! each weekday.
+
+use anyhow::Result;
+use chrono::{Duration, NaiveDate, Weekday};
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use crate::analytics;
+use crate::color;
+use crate::config::Config;
+use crate::db::Database;
+use crate::i18n;
+use crate::plan::{self, WeekPlan};
+
+/// Width of the label column
+const LABEL_WIDTH: usize = 14;
+
+/// How far back the report looks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Period {
+    Week,
+    Month,
+}
+
+impl Period {
+    /// Days covered, today included; the same spans as `stats --week` and
+    /// `stats --month`
+    pub fn days(&self) -> i64 {
+        match self {
+            Period::Week => 7,
+            Period::Month => 30,
+        }
+    }
+
+    fn title(&self) -> &'static str {
+        match self {
+            Period::Week => i18n::t("Weekly report"),
+            Period::Month => i18n::t("Monthly report"),
+        }
+    }
+}
+
+/// Time on one tag, now and in the period before
+#[derive(Debug, Clone, PartialEq)]
+pub struct TagRow {
+    /// None for untagged work
+    pub name: Option<String>,
+    pub seconds: i32,
+    pub sessions: i32,
+    pub previous_seconds: i32,
+}
+
+/// Figures for one report
+#[derive(Debug, Clone, PartialEq)]
+pub struct Summary {
+    pub period: Period,
+    pub first_day: NaiveDate,
+    pub last_day: NaiveDate,
+    pub seconds: i32,
+    pub sessions: i32,
+    pub previous_seconds: i32,
+    pub previous_sessions: i32,
+    /// Every day in the period, oldest first, with its focus in seconds
+    pub daily: Vec<(NaiveDate, i32)>,
+    /// Largest first
+    pub tags: Vec<TagRow>,
+    pub average_session: Option<i32>,
+    pub completed: i32,
+    pub skipped: i32,
+    /// (weekday, average seconds, days with focus), Monday first
+    pub weekdays: Vec<(Weekday, i32, i32)>,
+    /// Journal notes in the period, oldest first
+    pub journal: Vec<(NaiveDate, String)>,
+    /// For a week: (last week's plan, this week's plan), the ones that
+    /// aren't empty
+    pub plans: (Option<WeekPlan>, Option<WeekPlan>),
+}
+
+impl Summary {
+    /// Completed share of work sessions that were completed or skipped
+    pub fn completion_percent(&self) -> Option<u32> {
+        let finished = self.completed + self.skipped;
+        (finished > 0).then(|| (self.completed as f64 * 100.0 / finished as f64).round() as u32)
+    }
+
+    /// The day with the most focus; the earliest of equal days
+    pub fn best_day(&self) -> Option<(NaiveDate, i32)> {
+        self.daily
+            .iter()
+            .copied()
+            .filter(|(_, seconds)| *seconds > 0)
+            .fold(None, |best, day| match best {
+                Some((_, most)) if most >= day.1 => best,
+                _ => Some(day),
+            })
+    }
+}
+
+/// Gather the report for the period ending `today`
+pub fn build(db: &Database, config: &Config, period: Period, today: NaiveDate) -> Result<Summary> {
+    let first_day = today - Duration::days(period.days() - 1);
+    let previous_first = first_day - Duration::days(period.days());
+    let day = |date: NaiveDate| date.format("%Y-%m-%d").to_string();
+    let (start, end, previous_start) = (
+        day(first_day),
+        day(today + Duration::days(1)),
+        day(previous_first),
+    );
+
+    let current = db.get_range_stats(&start, &end)?;
+    let previous = db.get_range_stats(&previous_start, &start)?;
+
+    let daily = first_day
+        .iter_days()
+        .take_while(|date| *date <= today)
+        .map(|date| Ok((date, db.get_date_stats(&day(date))?.total_work_seconds)))
+        .collect::<Result<_>>()?;
+
+    let previous_tags: HashMap<Option<i64>, i32> = db
+        .get_range_stats_by_tag(&previous_start, &start)?
+        .into_iter()
+        .map(|(tag, seconds, _)| (tag.map(|t| t.id), seconds))
+        .collect();
+    let tags = db
+        .get_range_stats_by_tag(&start, &end)?
+        .into_iter()
+        .map(|(tag, seconds, sessions)| TagRow {
+            previous_seconds: previous_tags
+                .get(&tag.as_ref().map(|t| t.id))
+                .copied()
+                .unwrap_or(0),
+            name: tag.map(|t| t.name),
+            seconds,
+            sessions,
+        })
+        .collect();
+
+    let (completed, skipped) = db.get_range_completion(&start, &end)?;
+    let mut journal = db
+        .get_journal_since(&start)?
+        .into_iter()
+        .filter_map(|(date, note)| {
+            let date = NaiveDate::parse_from_str(&date, "%Y-%m-%d").ok()?;
+            (date <= today).then_some((date, note))
+        })
+        .collect::<Vec<_>>();
+    journal.reverse();
+
+    let plans = match period {
+        Period::Week => {
+            let load = |weeks_ago| -> Result<Option<WeekPlan>> {
+                let plan = plan::load(db, plan::week_of(config, today, weeks_ago))?;
+                Ok((!plan.is_empty()).then_some(plan))
+            };
+            (load(1)?, load(0)?)
+        }
+        Period::Month => (None, None),
+    };
+
+    Ok(Summary {
+        period,
+        first_day,
+        last_day: today,
+        seconds: current.total_work_seconds,
+        sessions: current.sessions_completed,
+        previous_seconds: previous.total_work_seconds,
+        previous_sessions: previous.sessions_completed,
+        daily,
+        tags,
+        average_session: db.get_range_average_session(&start, &end)?,
+        completed,
+        skipped,
+        weekdays: db.get_range_weekday_averages(&start, &end)?,
+        journal,
+        plans,
+    })
+}
+
+/// "↑ 15%", "↓ 8%", "new" or "" against the period before
+pub fn change(current: i32, previous: i32) -> String {
+    if previous <= 0 {
+        return if current > 0 {
+            i18n::t("new").to_string()
+        } else {
+            String::new()
+        };
+    }
+    let percent = ((current - previous) as f64 * 100.0 / previous as f64).round() as i64;
+    match percent {
+        0 => "→ 0%".to_string(),
+        p if p > 0 => color::up(&format!("↑ {}%", p)),
+        p => color::down(&format!("↓ {}%", -p)),
+    }
+}
+
+/// The report as text, ready to print
+pub fn render(summary: &Summary) -> String {
+    let mut out = String::new();
+    let label = |text: &str| i18n::pad(text, LABEL_WIDTH);
+    let vs = match summary.period {
+        Period::Week => i18n::t("vs previous 7 days"),
+        Period::Month => i18n::t("vs previous 30 days"),
+    };
+
+    let _ = writeln!(
+        out,
+        "  📋 {} · {} – {}",
+        color::heading(summary.period.title()),
+        i18n::month_day(summary.first_day),
+        i18n::month_day(summary.last_day)
+    );
+    let _ = writeln!(out, "  ─────────────────────────────");
+
+    if summary.sessions == 0 && summary.completed + summary.skipped == 0 {
+        let _ = writeln!(out, "     {}", i18n::t("No work sessions in this period."));
+        return out;
+    }
+
+    let mut trend = change(summary.seconds, summary.previous_seconds);
+    if summary.previous_seconds > 0 {
+        trend = format!("{} {}", trend, vs);
+    }
+    let _ = writeln!(
+        out,
+        "     {} │ {:<10} {}",
+        label(i18n::t("Focus")),
+        i18n::duration(summary.seconds),
+        trend
+    );
+    let _ = writeln!(
+        out,
+        "     {} │ {:<10} {}",
+        label(i18n::t("Sessions")),
+        summary.sessions,
+        change(summary.sessions, summary.previous_sessions)
+    );
+    if let Some(average) = summary.average_session {
+        let _ = writeln!(
+            out,
+            "     {} │ {}",
+            label(i18n::t("Avg session")),
+            i18n::duration(average)
+        );
+    }
+    if let Some(percent) = summary.completion_percent() {
+        let detail = i18n::fill(
+            "{0}% ({1} completed, {2} skipped)",
+            &[&percent, &summary.completed, &summary.skipped],
+        );
+        let _ = writeln!(out, "     {} │ {}", label(i18n::t("Completion")), detail);
+    }
+    if let Some((date, seconds)) = summary.best_day() {
+        let _ = writeln!(
+            out,
+            "     {} │ {} · {}",
+            label(i18n::t("Best day")),
+            i18n::weekday_month_day(date),
+            i18n::duration(seconds)
+        );
+    }
+    if let Some(last) = &summary.plans.0 {
+        let grade = i18n::fill(
+            "Last week: {0} ({1}% of {2} planned sessions)",
+            &[&last.grade().letter(), &last.percent(), &last.planned()],
+        );
+        let _ = writeln!(out, "     {} │ {}", label(i18n::t("Plan")), grade);
+    }
+    if let Some(current) = &summary.plans.1 {
+        let progress = i18n::fill(
+            "{0} of {1} planned sessions done ({2}%)",
+            &[&current.done(), &current.planned(), &current.percent()],
+        );
+        let first = if summary.plans.0.is_some() {
+            ""
+        } else {
+            i18n::t("Plan")
+        };
+        let _ = writeln!(out, "     {} │ {}", label(first), progress);
+    }
+    let totals: Vec<f64> = summary.daily.iter().map(|(_, s)| *s as f64).collect();
+    let _ = writeln!(
+        out,
+        "     {} │{}│",
+        label(i18n::t("Daily")),
+        analytics::sparkline(&totals)
+    );
+
+    if !summary.tags.is_empty() {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "     {}", i18n::t("By tag"));
+        for tag in &summary.tags {
+            let name = tag.name.as_deref().unwrap_or(i18n::t("(untagged)"));
+            let _ = writeln!(
+                out,
+                "     {} │ {:>8} │ {:>3} │ {}",
+                label(name),
+                i18n::duration(tag.seconds),
+                tag.sessions,
+                change(tag.seconds, tag.previous_seconds)
+            );
+        }
+    }
+
+    if !summary.journal.is_empty() {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "     {}", i18n::t("Journal"));
+        for (date, note) in &summary.journal {
+            let _ = writeln!(
+                out,
+                "     {} │ {}",
+                label(&i18n::weekday_month_day(*date)),
+                note
+            );
+        }
+    }
+
+    if summary.period == Period::Month && !summary.weekdays.is_empty() {
+        let _ = writeln!(out);
+        let _ = writeln!(
+            out,
+            "     {}",
+            i18n::t("By weekday (average on days with focus)")
+        );
+        for (weekday, seconds, days) in &summary.weekdays {
+            let _ = writeln!(
+                out,
+                "     {} │ {:>8} │ {}",
+                label(&i18n::weekday(*weekday)),
+                i18n::duration(*seconds),
+                i18n::days(days)
+            );
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::SessionType;
+    use rusqlite::params;
+
+    fn date(s: &str) -> NaiveDate {
+        NaiveDate::parse_from_str(s, "%Y-%m-%d").unwrap()
+    }
+
+    fn work_on(db: &Database, day: &str, tag: Option<i64>, seconds: i32) -> i64 {
+        let id = db
+            .start_session_with_tag(SessionType::Work, tag, Some(1500))
+            .unwrap();
+        db.complete_session(id, seconds).unwrap();
+        db.connection()
+            .execute(
+                "UPDATE sessions SET started_at = ?1, day = ?2 WHERE id = ?3",
+                params![format!("{}T09:00:00+00:00", day), day, id],
+            )
+            .unwrap();
+        id
+    }
+
+    #[test]
+    fn test_change() {
+        assert_eq!(change(0, 0), "");
+        assert_eq!(change(600, 0), "new");
+        assert_eq!(change(1150, 1000), "↑ 15%");
+        assert_eq!(change(920, 1000), "↓ 8%");
+        assert_eq!(change(1000, 1000), "→ 0%");
+    }
+
+    #[test]
+    fn test_build_week() {
+        let db = Database::open_in_memory().unwrap();
+        let writing = db.create_tag("writing", None).unwrap();
+        // The week is Oct 11 – Oct 17; Oct 4 is the week before
+        work_on(&db, "2026-10-04", Some(writing), 1500);
+        work_on(&db, "2026-10-12", Some(writing), 1500);
+        work_on(&db, "2026-10-14", Some(writing), 1500);
+        work_on(&db, "2026-10-14", None, 900);
+        let skipped = db
+            .start_session_with_tag(SessionType::Work, None, Some(1500))
+            .unwrap();
+        db.log_session_event(skipped, "skipped", None).unwrap();
+        db.connection()
+            .execute(
+                "UPDATE sessions SET ended_at = started_at, day = '2026-10-15' WHERE id = ?1",
+                [skipped],
+            )
+            .unwrap();
+
+        let summary = build(&db, &Config::default(), Period::Week, date("2026-10-17")).unwrap();
+        assert_eq!(summary.first_day, date("2026-10-11"));
+        assert_eq!(summary.seconds, 3900);
+        assert_eq!(summary.sessions, 3);
+        assert_eq!(summary.previous_seconds, 1500);
+        assert_eq!(summary.daily.len(), 7);
+        assert_eq!(summary.daily[3], (date("2026-10-14"), 2400));
+        assert_eq!(summary.best_day(), Some((date("2026-10-14"), 2400)));
+        assert_eq!(summary.average_session, Some(1300));
+        assert_eq!((summary.completed, summary.skipped), (3, 1));
+        assert_eq!(summary.completion_percent(), Some(75));
+        assert_eq!(
+            summary.tags[0],
+            TagRow {
+                name: Some("writing".to_string()),
+                seconds: 3000,
+                sessions: 2,
+                previous_seconds: 1500,
+            }
+        );
+        assert_eq!(summary.tags[1].name, None);
+        assert_eq!(
+            summary.weekdays,
+            vec![(Weekday::Mon, 1500, 1), (Weekday::Wed, 2400, 1)]
+        );
+        assert!(summary.journal.is_empty());
+        assert_eq!(summary.plans, (None, None));
+    }
+
+    #[test]
+    fn test_week_grades_last_weeks_plan() {
+        let db = Database::open_in_memory().unwrap();
+        let writing = db.create_tag("writing", None).unwrap();
+        let config = Config::default();
+        // Sat Oct 17; weeks start on Monday, so last week is Oct 5 – Oct 11
+        let today = date("2026-10-17");
+        plan::set(&db, date("2026-10-05"), "writing", 4).unwrap();
+        plan::set(&db, date("2026-10-12"), "writing", 2).unwrap();
+        for day in ["2026-10-06", "2026-10-07", "2026-10-08", "2026-10-14"] {
+            work_on(&db, day, Some(writing), 1500);
+        }
+
+        let summary = build(&db, &config, Period::Week, today).unwrap();
+        let (last, current) = &summary.plans;
+        assert_eq!(last.as_ref().map(|p| p.grade()), Some(plan::Grade::B));
+        assert_eq!(current.as_ref().map(|p| p.percent()), Some(50));
+        let text = render(&summary);
+        assert!(text.contains("Last week: B (75% of 4 planned sessions)"));
+        assert!(text.contains("1 of 2 planned sessions done (50%)"));
+
+        let month = build(&db, &config, Period::Month, today).unwrap();
+        assert_eq!(month.plans, (None, None));
+        assert!(!render(&month).contains("Last week"));
+    }
+
+    #[test]
+    fn test_render() {
+        let db = Database::open_in_memory().unwrap();
+        let summary = build(&db, &Config::default(), Period::Month, date("2026-10-17")).unwrap();
+        assert!(render(&summary).contains("No work sessions in this period."));
+
+        work_on(&db, "2026-10-12", None, 1500);
+        work_on(&db, "2026-10-13", None, 3000);
+        let text =
+            render(&build(&db, &Config::default(), Period::Month, date("2026-10-17")).unwrap());
+        assert!(text.contains("Monthly report · Sep 18 – Oct 17"));
+        assert!(text.contains("75m") || text.contains("1h 15m"));
+        assert!(text.contains("Tue Oct 13"));
+        assert!(text.contains("(untagged)"));
+        assert!(text.contains("By weekday"));
+        let week =
+            render(&build(&db, &Config::default(), Period::Week, date("2026-10-17")).unwrap());
+        assert!(!week.contains("By weekday"));
+        assert!(!week.contains("Journal"));
+
+        // Notes in the period show oldest first; older ones stay out
+        db.set_journal("2026-10-01", "kickoff").unwrap();
+        db.set_journal("2026-10-14", "reviews").unwrap();
+        db.set_journal("2026-10-12", "shipped the parser").unwrap();
+        let summary = build(&db, &Config::default(), Period::Week, date("2026-10-17")).unwrap();
+        assert_eq!(
+            summary.journal,
+            vec![
+                (date("2026-10-12"), "shipped the parser".to_string()),
+                (date("2026-10-14"), "reviews".to_string()),
+            ]
+        );
+        let week = render(&summary);
+        assert!(week.contains("Journal"));
+        assert!(week.contains("Mon Oct 12"));
+        assert!(week.contains("shipped the parser"));
+        assert!(!week.contains("kickoff"));
+    }
+}
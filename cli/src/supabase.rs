@@ -7,6 +7,7 @@ use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 use crate::auth;
+use crate::http::StatusError;
 
 /// Supabase configuration
 const SUPABASE_URL: &str = "https://ukjsssbpfvkumflzcfrd.supabase.co";
@@ -47,6 +48,24 @@ pub struct CloudSettings {
     pub break_snooze_enabled: Option<bool>,
 }
 
+/// Work session running on one of the user's devices (`active_sessions`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActiveSession {
+    pub user_id: String,
+    pub device_id: String,
+    pub device_name: String,
+    pub session_type: String,
+    pub paused: bool,
+    pub remaining_seconds: i32,
+    pub work_minutes: i32,
+    pub tag: Option<String>,
+    pub heartbeat_at: DateTime<Utc>,
+    /// Name of the device that took the session over
+    pub handed_off_to: Option<String>,
+    /// Device id the session was taken over from
+    pub resumed_from: Option<String>,
+}
+
 /// Supabase API client
 pub struct SupabaseClient {
     client: reqwest::blocking::Client,
@@ -95,7 +114,8 @@ impl SupabaseClient {
         Ok(sessions)
     }
 
-    /// Upload a session to Supabase
+    /// Upload a session to Supabase, replacing the one with the same id, so
+    /// sending a change twice is harmless
     pub fn upload_session(&self, session: &CloudSession) -> Result<()> {
         let url = format!("{}/rest/v1/sessions", SUPABASE_URL);
 
@@ -103,14 +123,18 @@ impl SupabaseClient {
         for (key, value) in self.auth_headers() {
             request = request.header(key, value);
         }
-        request = request.header("Prefer", "return=minimal");
+        request = request.header("Prefer", "return=minimal,resolution=merge-duplicates");
 
         let response = request.json(session).send()?;
 
         if !response.status().is_success() {
             let status = response.status();
             let body = response.text().unwrap_or_default();
-            anyhow::bail!("Failed to upload session: {} - {}", status, body);
+            return Err(StatusError::new(
+                status,
+                format!("Failed to upload session: {} - {}", status, body),
+            )
+            .into());
         }
 
         Ok(())
@@ -191,7 +215,6 @@ impl SupabaseClient {
     }
 
     /// Delete a session from Supabase
-    #[allow(dead_code)]
     pub fn delete_session(&self, session_id: &str) -> Result<()> {
         let url = format!("{}/rest/v1/sessions?id=eq.{}", SUPABASE_URL, session_id);
 
@@ -205,7 +228,111 @@ impl SupabaseClient {
         if !response.status().is_success() {
             let status = response.status();
             let body = response.text().unwrap_or_default();
-            anyhow::bail!("Failed to delete session: {} - {}", status, body);
+            return Err(StatusError::new(
+                status,
+                format!("Failed to delete session: {} - {}", status, body),
+            )
+            .into());
+        }
+
+        Ok(())
+    }
+
+    /// Get the session running on another device, if any
+    pub fn get_active_session(&self) -> Result<Option<ActiveSession>> {
+        let url = format!("{}/rest/v1/active_sessions?select=*", SUPABASE_URL);
+
+        let mut request = self.client.get(&url);
+        for (key, value) in self.auth_headers() {
+            request = request.header(key, value);
+        }
+
+        let response = request.send()?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().unwrap_or_default();
+            anyhow::bail!("Failed to fetch active session: {} - {}", status, body);
+        }
+
+        let sessions: Vec<ActiveSession> = response.json()?;
+        Ok(sessions.into_iter().next())
+    }
+
+    /// Publish this device's running session, replacing any other
+    pub fn publish_active_session(&self, session: &ActiveSession) -> Result<()> {
+        let url = format!("{}/rest/v1/active_sessions", SUPABASE_URL);
+
+        let mut request = self.client.post(&url);
+        for (key, value) in self.auth_headers() {
+            request = request.header(key, value);
+        }
+        request = request.header("Prefer", "resolution=merge-duplicates,return=minimal");
+
+        let response = request.json(session).send()?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().unwrap_or_default();
+            anyhow::bail!("Failed to publish active session: {} - {}", status, body);
+        }
+
+        Ok(())
+    }
+
+    /// Mark the session running on `device_id` as taken over by `to_name`
+    pub fn hand_off_active_session(&self, device_id: &str, to_name: &str) -> Result<()> {
+        let url = format!(
+            "{}/rest/v1/active_sessions?device_id=eq.{}&handed_off_to=is.null",
+            SUPABASE_URL,
+            urlencoding::encode(device_id)
+        );
+
+        let mut request = self.client.patch(&url);
+        for (key, value) in self.auth_headers() {
+            request = request.header(key, value);
+        }
+        // Only a session nobody has taken yet is updated; the rows that
+        // changed come back, so two devices can't both take it over
+        request = request.header("Prefer", "return=representation");
+
+        let response = request
+            .json(&serde_json::json!({ "handed_off_to": to_name }))
+            .send()?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().unwrap_or_default();
+            anyhow::bail!("Failed to hand off session: {} - {}", status, body);
+        }
+
+        let changed: Vec<serde_json::Value> = response.json()?;
+        if changed.len() != 1 {
+            anyhow::bail!("The session was already taken over by another device.");
+        }
+
+        Ok(())
+    }
+
+    /// Remove the session published by `device_id` (another device's stays)
+    pub fn clear_active_session(&self, device_id: &str) -> Result<()> {
+        let url = format!(
+            "{}/rest/v1/active_sessions?device_id=eq.{}",
+            SUPABASE_URL,
+            urlencoding::encode(device_id)
+        );
+
+        let mut request = self.client.delete(&url);
+        for (key, value) in self.auth_headers() {
+            request = request.header(key, value);
+        }
+
+        let response = request.send()?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().unwrap_or_default();
+            anyhow::bail!("Failed to clear active session: {} - {}", status, body);
         }
 
         Ok(())
@@ -0,0 +1,363 @@
+//! SVG rendering
+//!
+//! Renders the activity heatmap and shields.io-style badges as standalone
+//! SVG documents for embedding in web pages and READMEs. With the `png`
+//! feature, a document can also be rasterized to PNG.
+
+use chrono::{Datelike, NaiveDate};
+
+use crate::db::DailyStats;
+use crate::theme;
+
+const CELL: u32 = 10;
+const CELL_GAP: u32 = 3;
+const LEFT_MARGIN: u32 = 28;
+const TOP_MARGIN: u32 = 18;
+/// Empty cells are translucent gray so they work on light and dark pages
+const EMPTY_FILL: &str = r##"fill="#8b8b8b" fill-opacity="0.2""##;
+
+/// Escape text for use in SVG content and attributes
+pub fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+pub fn hex(rgb: (u8, u8, u8)) -> String {
+    format!("#{:02x}{:02x}{:02x}", rgb.0, rgb.1, rgb.2)
+}
+
+/// Fill attributes for a heatmap cell at the given activity level
+fn cell_fill(accent: &str, level: usize) -> String {
+    if level == 0 {
+        EMPTY_FILL.to_string()
+    } else if accent == "rainbow" {
+        format!(r#"fill="{}""#, hex(theme::rainbow_heatmap_rgb(level)))
+    } else {
+        format!(
+            r#"fill="{}" fill-opacity="{}""#,
+            hex(theme::heatmap_accent_rgb(accent)),
+            theme::heatmap_opacity(level)
+        )
+    }
+}
+
+fn describe_minutes(seconds: i32) -> String {
+    let minutes = seconds / 60;
+    if minutes >= 60 {
+        format!("{}h {}m", minutes / 60, minutes % 60)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+fn heatmap_days(data: &[DailyStats]) -> Vec<(NaiveDate, i32)> {
+    data.iter()
+        .filter_map(|s| {
+            NaiveDate::parse_from_str(&s.date, "%Y-%m-%d")
+                .ok()
+                .map(|d| (d, s.total_work_seconds))
+        })
+        .collect()
+}
+
+/// (column, row) of `date` in a graph starting at `first`
+fn grid_position(first: NaiveDate, date: NaiveDate) -> (u32, u32) {
+    let lead = first.weekday().num_days_from_sunday();
+    let offset = (date - first).num_days() as u32 + lead;
+    (offset / 7, date.weekday().num_days_from_sunday())
+}
+
+fn columns(days: &[(NaiveDate, i32)]) -> u32 {
+    match (days.first(), days.last()) {
+        (Some((first, _)), Some((last, _))) => grid_position(*first, *last).0 + 1,
+        _ => 0,
+    }
+}
+
+fn size_for(columns: u32) -> (u32, u32) {
+    let pitch = CELL + CELL_GAP;
+    (
+        LEFT_MARGIN + columns * pitch + CELL_GAP,
+        TOP_MARGIN + 7 * pitch,
+    )
+}
+
+/// Width and height of the graph `heatmap` draws for `data`
+pub fn heatmap_size(data: &[DailyStats]) -> (u32, u32) {
+    size_for(columns(&heatmap_days(data)))
+}
+
+/// Render daily stats (oldest first) as a GitHub-style contribution graph.
+///
+/// Columns are weeks starting on Sunday, rows are days of the week.
+pub fn heatmap(data: &[DailyStats], accent: &str) -> String {
+    let days = heatmap_days(data);
+    let pitch = CELL + CELL_GAP;
+    let first = days.first().map(|(d, _)| *d);
+    let position = |date: NaiveDate| grid_position(first.unwrap_or(date), date);
+
+    let columns = columns(&days);
+    let (width, height) = size_for(columns);
+
+    let mut svg = format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="-apple-system,Segoe UI,Helvetica,Arial,sans-serif" font-size="9" fill="#767676">"##,
+        w = width,
+        h = height
+    );
+    svg.push_str("<title>sandoro focus activity</title>");
+
+    for (row, label) in [(1, "Mon"), (3, "Wed"), (5, "Fri")] {
+        svg.push_str(&format!(
+            r#"<text x="0" y="{}">{}</text>"#,
+            TOP_MARGIN + row * pitch + CELL - 1,
+            label
+        ));
+    }
+
+    // Label a column when its first day starts a new month
+    let mut last_month = None;
+    for (date, _) in &days {
+        let (column, row) = position(*date);
+        if row != 0 && Some(*date) != first {
+            continue;
+        }
+        if last_month != Some(date.month()) {
+            last_month = Some(date.month());
+            // Skip a label that would be cut off at the right edge
+            if column + 2 < columns {
+                svg.push_str(&format!(
+                    r#"<text x="{}" y="{}">{}</text>"#,
+                    LEFT_MARGIN + column * pitch,
+                    TOP_MARGIN - 6,
+                    date.format("%b")
+                ));
+            }
+        }
+    }
+
+    for (date, seconds) in &days {
+        let (column, row) = position(*date);
+        svg.push_str(&format!(
+            r#"<rect x="{}" y="{}" width="{c}" height="{c}" rx="2" {}><title>{}: {}</title></rect>"#,
+            LEFT_MARGIN + column * pitch,
+            TOP_MARGIN + row * pitch,
+            cell_fill(accent, theme::activity_level(*seconds)),
+            date.format("%Y-%m-%d"),
+            describe_minutes(*seconds),
+            c = CELL
+        ));
+    }
+
+    svg.push_str("</svg>");
+    svg
+}
+
+/// Approximate text width in pixels for 11px Verdana
+fn text_width(text: &str) -> u32 {
+    text.chars()
+        .map(|c| if c.is_ascii() { 7 } else { 12 })
+        .sum::<u32>()
+}
+
+/// Render a flat shields.io-style badge, e.g. `streak | 12 days`
+pub fn badge(label: &str, message: &str, color: (u8, u8, u8)) -> String {
+    let label_width = text_width(label) + 10;
+    let message_width = text_width(message) + 10;
+    let width = label_width + message_width;
+    let (label, message) = (escape(label), escape(message));
+
+    format!(
+        concat!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="20" role="img" aria-label="{l}: {m}">"#,
+            r#"<title>{l}: {m}</title>"#,
+            r##"<linearGradient id="s" x2="0" y2="100%"><stop offset="0" stop-color="#bbb" stop-opacity=".1"/><stop offset="1" stop-opacity=".1"/></linearGradient>"##,
+            r##"<clipPath id="r"><rect width="{w}" height="20" rx="3" fill="#fff"/></clipPath>"##,
+            r##"<g clip-path="url(#r)"><rect width="{lw}" height="20" fill="#555"/><rect x="{lw}" width="{mw}" height="20" fill="{c}"/><rect width="{w}" height="20" fill="url(#s)"/></g>"##,
+            r##"<g fill="#fff" text-anchor="middle" font-family="Verdana,Geneva,DejaVu Sans,sans-serif" font-size="11">"##,
+            r##"<text x="{lx}" y="15" fill="#010101" fill-opacity=".3">{l}</text><text x="{lx}" y="14">{l}</text>"##,
+            r##"<text x="{mx}" y="15" fill="#010101" fill-opacity=".3">{m}</text><text x="{mx}" y="14">{m}</text>"##,
+            r#"</g></svg>"#
+        ),
+        w = width,
+        lw = label_width,
+        mw = message_width,
+        lx = label_width / 2,
+        mx = label_width + message_width / 2,
+        c = hex(color),
+        l = label,
+        m = message
+    )
+}
+
+/// Badge color when there is nothing to show yet
+const INACTIVE_COLOR: (u8, u8, u8) = (159, 159, 159);
+
+/// Solid color for an accent; rainbow uses its strongest level
+pub fn accent_rgb(accent: &str) -> (u8, u8, u8) {
+    if accent == "rainbow" {
+        theme::rainbow_heatmap_rgb(4)
+    } else {
+        theme::heatmap_accent_rgb(accent)
+    }
+}
+
+fn streak_message(days: i32) -> String {
+    if days == 1 {
+        "1 day".to_string()
+    } else {
+        format!("{} days", days)
+    }
+}
+
+/// `focus streak | 12 days`, grey while there is no streak
+pub fn streak_badge(days: i32, accent: &str) -> String {
+    let color = if days > 0 {
+        accent_rgb(accent)
+    } else {
+        INACTIVE_COLOR
+    };
+    badge("focus streak", &streak_message(days), color)
+}
+
+/// `focus today | 2h 5m`, grey until the first session is done
+pub fn focus_badge(seconds: i32, accent: &str) -> String {
+    let color = if seconds > 0 {
+        accent_rgb(accent)
+    } else {
+        INACTIVE_COLOR
+    };
+    badge("focus today", &crate::format_duration(seconds), color)
+}
+
+/// Rasterize an SVG document to PNG at `scale` times its size. Text uses
+/// the system's fonts.
+#[cfg(feature = "png")]
+pub fn to_png(svg: &str, scale: f32) -> anyhow::Result<Vec<u8>> {
+    use resvg::{tiny_skia, usvg};
+
+    let mut options = usvg::Options::default();
+    let fonts = options.fontdb_mut();
+    fonts.load_system_fonts();
+    // usvg takes "sans-serif" to mean Arial; use a sans font that's installed
+    if let Some(family) = [
+        "Helvetica",
+        "Arial",
+        "DejaVu Sans",
+        "Liberation Sans",
+        "Noto Sans",
+    ]
+    .into_iter()
+    .find(|name| {
+        fonts
+            .faces()
+            .any(|face| face.families.iter().any(|(family, _)| family == name))
+    }) {
+        fonts.set_sans_serif_family(family);
+    }
+    let tree = usvg::Tree::from_str(svg, &options)?;
+    let size = tree
+        .size()
+        .to_int_size()
+        .scale_by(scale)
+        .ok_or_else(|| anyhow::anyhow!("The image is too large to rasterize"))?;
+    let mut pixmap = tiny_skia::Pixmap::new(size.width(), size.height())
+        .ok_or_else(|| anyhow::anyhow!("The image is empty"))?;
+    resvg::render(
+        &tree,
+        tiny_skia::Transform::from_scale(scale, scale),
+        &mut pixmap.as_mut(),
+    );
+    Ok(pixmap.encode_png()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(date: &str, seconds: i32) -> DailyStats {
+        DailyStats {
+            date: date.to_string(),
+            total_work_seconds: seconds,
+            sessions_completed: 0,
+            longest_streak: 0,
+        }
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape(r#"<a & "b">"#), "&lt;a &amp; &quot;b&quot;&gt;");
+    }
+
+    #[test]
+    fn test_heatmap_cells_and_layout() {
+        // 2026-10-03 is a Saturday, so the Sunday after starts a new column
+        let data = vec![day("2026-10-03", 0), day("2026-10-04", 3600)];
+        let svg = heatmap(&data, "cyan");
+        assert_eq!(svg.matches("<rect").count(), 2);
+        let pitch = CELL + CELL_GAP;
+        assert!(svg.contains(&format!(
+            r#"<rect x="{}" y="{}""#,
+            LEFT_MARGIN,
+            TOP_MARGIN + 6 * pitch
+        )));
+        assert!(svg.contains(&format!(
+            r#"<rect x="{}" y="{}""#,
+            LEFT_MARGIN + pitch,
+            TOP_MARGIN
+        )));
+        assert!(svg.contains("<title>2026-10-04: 1h 0m</title>"));
+        assert!(svg.contains(EMPTY_FILL));
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn test_heatmap_png_is_scaled() {
+        let svg = heatmap(&[day("2026-10-03", 0), day("2026-10-04", 3600)], "cyan");
+        let png = to_png(&svg, 2.0).unwrap();
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+        // Width and height from the IHDR chunk
+        let size = |at: usize| u32::from_be_bytes(png[at..at + 4].try_into().unwrap());
+        let pitch = CELL + CELL_GAP;
+        assert_eq!(size(16), 2 * (LEFT_MARGIN + 2 * pitch + CELL_GAP));
+        assert_eq!(size(20), 2 * (TOP_MARGIN + 7 * pitch));
+    }
+
+    #[test]
+    fn test_heatmap_rainbow_uses_level_colors() {
+        let svg = heatmap(&[day("2026-10-04", 3 * 3600)], "rainbow");
+        assert!(svg.contains(&hex(theme::rainbow_heatmap_rgb(4))));
+    }
+
+    #[test]
+    fn test_badge_escapes_and_sizes() {
+        let svg = badge("focus", "<1h>", (0, 128, 0));
+        assert!(svg.contains("focus: &lt;1h&gt;"));
+        assert!(svg.contains(r##"fill="#008000""##));
+        let width = text_width("focus") + text_width("<1h>") + 20;
+        assert!(svg.starts_with(&format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}""#,
+            width
+        )));
+    }
+
+    #[test]
+    fn test_streak_message() {
+        assert_eq!(streak_message(0), "0 days");
+        assert_eq!(streak_message(1), "1 day");
+        assert_eq!(streak_message(12), "12 days");
+    }
+
+    #[test]
+    fn test_stats_badges() {
+        let streak = streak_badge(12, "green");
+        assert!(streak.contains("focus streak: 12 days"));
+        assert!(streak.contains(&hex(theme::heatmap_accent_rgb("green"))));
+        assert!(streak_badge(0, "green").contains(&hex(INACTIVE_COLOR)));
+
+        let today = focus_badge(7500, "green");
+        assert!(today.contains("focus today: 2h 5m"));
+        assert!(focus_badge(0, "green").contains(&hex(INACTIVE_COLOR)));
+    }
+}
@@ -1,13 +1,25 @@
 //! Sync module for SQLite <-> Supabase synchronization
 //!
 //! Handles bidirectional sync of sessions between local SQLite and cloud.
+//!
+//! Changes to sessions go through an outbox (`sync_queue`): completed
+//! sessions are queued for upload, retags and merges of uploaded ones for an
+//! update, deletions for a delete. The outbox is sent when a session
+//! completes, on startup and by `sandoro sync`; a change that fails waits
+//! longer before each retry, and the rest stay queued behind it. One the
+//! cloud turns down for good is set aside instead, so it can't hold up the
+//! rest.
 
 use anyhow::Result;
 use chrono::{DateTime, Utc};
-use rusqlite::Connection;
+use rusqlite::{Connection, OptionalExtension};
 
 use crate::auth;
+use crate::config::ConflictPolicy;
+use crate::db::{self, record_session_event, SyncOp};
+use crate::http;
 use crate::supabase::{CloudSession, SupabaseClient};
+use crate::time_tracking::backoff;
 
 /// Sync metadata stored in SQLite
 const SYNC_METADATA_TABLE: &str = r#"
@@ -24,7 +36,7 @@ pub fn ensure_sync_table(conn: &Connection) -> Result<()> {
 }
 
 /// Get last sync timestamp
-fn get_last_sync(conn: &Connection) -> Result<Option<DateTime<Utc>>> {
+pub fn get_last_sync(conn: &Connection) -> Result<Option<DateTime<Utc>>> {
     let result: Option<String> = conn
         .query_row(
             "SELECT value FROM sync_metadata WHERE key = 'last_sync'",
@@ -54,7 +66,6 @@ fn set_last_sync(conn: &Connection, timestamp: &DateTime<Utc>) -> Result<()> {
 }
 
 /// Get cloud session ID if this local session was synced
-#[allow(dead_code)]
 fn get_cloud_id(conn: &Connection, local_id: i64) -> Result<Option<String>> {
     let result: Option<String> = conn
         .query_row(
@@ -85,6 +96,7 @@ fn mark_synced(conn: &Connection, local_id: i64, cloud_id: &str) -> Result<()> {
         "UPDATE sessions SET cloud_id = ? WHERE id = ?",
         rusqlite::params![cloud_id, local_id],
     )?;
+    let _ = record_session_event(conn, local_id, "synced", Some(cloud_id));
     Ok(())
 }
 
@@ -96,11 +108,28 @@ struct LocalSession {
     duration_seconds: i32,
     completed_at: String,
     tag: Option<String>,
-    #[allow(dead_code)]
     cloud_id: Option<String>,
+    /// Its id in the cloud once uploaded
+    uuid: String,
+}
+
+impl LocalSession {
+    fn to_cloud(&self, user_id: &str, cloud_id: &str) -> CloudSession {
+        CloudSession {
+            id: Some(cloud_id.to_string()),
+            user_id: user_id.to_string(),
+            session_type: self.session_type.clone(),
+            duration_seconds: self.duration_seconds,
+            completed_at: self.completed_at.clone(),
+            tag: self.tag.clone(),
+            // Also the time of the last change, for latest-wins conflicts
+            created_at: Some(Utc::now().to_rfc3339()),
+            synced_from_cli: Some(true),
+        }
+    }
 }
 
-/// Get unsynced local sessions
+/// Get completed local sessions never uploaded nor queued
 fn get_unsynced_sessions(conn: &Connection) -> Result<Vec<LocalSession>> {
     // Ensure cloud_id column exists
     let has_cloud_id: bool = conn
@@ -116,10 +145,11 @@ fn get_unsynced_sessions(conn: &Connection) -> Result<Vec<LocalSession>> {
     }
 
     let mut stmt = conn.prepare(
-        "SELECT s.id, s.type, s.duration_seconds, s.ended_at, t.name, s.cloud_id
+        "SELECT s.id, s.type, s.duration_seconds, s.ended_at, t.name, s.cloud_id, s.uuid
          FROM sessions s
          LEFT JOIN tags t ON s.tag_id = t.id
          WHERE s.cloud_id IS NULL AND s.completed = 1
+           AND s.id NOT IN (SELECT session_id FROM sync_queue)
          ORDER BY s.ended_at ASC",
     )?;
 
@@ -132,6 +162,7 @@ fn get_unsynced_sessions(conn: &Connection) -> Result<Vec<LocalSession>> {
                 completed_at: row.get(3)?,
                 tag: row.get(4)?,
                 cloud_id: row.get(5)?,
+                uuid: row.get(6)?,
             })
         })?
         .filter_map(|r| r.ok())
@@ -140,29 +171,124 @@ fn get_unsynced_sessions(conn: &Connection) -> Result<Vec<LocalSession>> {
     Ok(sessions)
 }
 
-/// Insert cloud session into local SQLite
-fn insert_cloud_session(conn: &Connection, session: &CloudSession) -> Result<()> {
-    // Check if already exists (by cloud_id)
-    if let Some(id) = &session.id {
-        let exists: bool = conn
-            .query_row(
-                "SELECT COUNT(*) > 0 FROM sessions WHERE cloud_id = ?",
-                [id],
-                |row| row.get(0),
-            )
-            .unwrap_or(false);
+/// A completed local session by ID
+fn get_local_session(conn: &Connection, session_id: i64) -> Result<Option<LocalSession>> {
+    let session = conn
+        .query_row(
+            "SELECT s.id, s.type, s.duration_seconds, s.ended_at, t.name, s.cloud_id, s.uuid
+             FROM sessions s
+             LEFT JOIN tags t ON s.tag_id = t.id
+             WHERE s.id = ? AND s.completed = 1",
+            [session_id],
+            |row| {
+                Ok(LocalSession {
+                    id: row.get(0)?,
+                    session_type: row.get(1)?,
+                    duration_seconds: row.get(2)?,
+                    completed_at: row.get(3)?,
+                    tag: row.get(4)?,
+                    cloud_id: row.get(5)?,
+                    uuid: row.get(6)?,
+                })
+            },
+        )
+        .optional()?;
+    Ok(session)
+}
+
+/// Queue completed sessions that were never uploaded, e.g. ones finished
+/// while logged out. Returns how many were queued.
+fn queue_unsynced(conn: &Connection) -> Result<usize> {
+    let unsynced = get_unsynced_sessions(conn)?;
+    for local in &unsynced {
+        db::queue_sync(conn, local.id, SyncOp::Insert, &local.uuid)?;
+    }
+    Ok(unsynced.len())
+}
+
+/// Send the outbox in the order changes were queued. With `due`, only
+/// changes whose retry time has come. Returns how many were sent and the
+/// errors on the way.
+fn flush_queue(
+    conn: &Connection,
+    client: &SupabaseClient,
+    user_id: &str,
+    due: Option<DateTime<Utc>>,
+) -> Result<(usize, Vec<String>)> {
+    flush_with(conn, due, |entry| match entry.op {
+        SyncOp::Delete => client.delete_session(&entry.cloud_id).map(|()| true),
+        SyncOp::Insert | SyncOp::Update => match get_local_session(conn, entry.session_id)? {
+            Some(local) => client
+                .upload_session(&local.to_cloud(user_id, &entry.cloud_id))
+                .map(|()| true),
+            // Removed or reopened since it was queued
+            None => Ok(false),
+        },
+    })
+}
 
-        if exists {
-            return Ok(());
+/// The outbox pass behind `flush_queue`. `send` returns false when there's
+/// nothing left to send for an entry. A failure that would come back the
+/// same (the cloud turning the row down, or a local row that can't be
+/// read) sets that entry aside and the pass goes on; any other stops it,
+/// since being offline fails every entry alike.
+fn flush_with(
+    conn: &Connection,
+    due: Option<DateTime<Utc>>,
+    mut send: impl FnMut(&db::QueuedSync) -> Result<bool>,
+) -> Result<(usize, Vec<String>)> {
+    let mut sent = 0;
+    let mut errors = Vec::new();
+    for entry in db::get_sync_queue(conn, due)? {
+        match send(&entry) {
+            Ok(false) => db::sync_done(conn, entry.session_id)?,
+            Ok(true) => {
+                if entry.op == SyncOp::Delete {
+                    let _ = record_session_event(
+                        conn,
+                        entry.session_id,
+                        "sync_deleted",
+                        Some(&entry.cloud_id),
+                    );
+                } else {
+                    mark_synced(conn, entry.session_id, &entry.cloud_id)?;
+                }
+                db::sync_done(conn, entry.session_id)?;
+                sent += 1;
+            }
+            Err(e) => {
+                let error = e.to_string();
+                let _ = record_session_event(conn, entry.session_id, "sync_failed", Some(&error));
+                errors.push(format!(
+                    "Failed to sync session {}: {}",
+                    entry.session_id, error
+                ));
+                if http::is_permanent(&e) || e.downcast_ref::<rusqlite::Error>().is_some() {
+                    db::sync_dead(conn, entry.session_id, &error)?;
+                    continue;
+                }
+                let retry_at = Utc::now() + backoff(entry.attempts);
+                db::sync_failed(conn, entry.session_id, &error, retry_at)?;
+                break;
+            }
         }
     }
+    Ok((sent, errors))
+}
+
+/// Insert cloud session into local SQLite
+fn insert_cloud_session(conn: &Connection, session: &CloudSession) -> Result<()> {
+    if is_downloaded(conn, session) {
+        return Ok(());
+    }
 
     // Parse completed_at to get started_at (subtract duration)
     let ended_at = &session.completed_at;
 
     conn.execute(
-        "INSERT INTO sessions (type, duration_seconds, ended_at, started_at, completed, cloud_id)
-         VALUES (?, ?, ?, datetime(?, '-' || ? || ' seconds'), 1, ?)",
+        "INSERT INTO sessions
+             (type, duration_seconds, ended_at, started_at, completed, cloud_id, uuid, origin)
+         VALUES (?, ?, ?, datetime(?, '-' || ? || ' seconds'), 1, ?, ?, 'cloud')",
         rusqlite::params![
             session.session_type,
             session.duration_seconds,
@@ -170,22 +296,83 @@ fn insert_cloud_session(conn: &Connection, session: &CloudSession) -> Result<()>
             ended_at,
             session.duration_seconds,
             session.id,
+            session.id.clone().unwrap_or_else(db::new_uuid),
         ],
     )?;
 
     Ok(())
 }
 
+/// Which way a sync moves sessions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SyncDirection {
+    /// Upload local sessions, then download cloud ones
+    #[default]
+    Both,
+    /// Upload only
+    Push,
+    /// Download only
+    Pull,
+}
+
+/// Options for `sync`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SyncOptions {
+    pub direction: SyncDirection,
+    /// Count what would change without writing anything locally or uploading
+    pub dry_run: bool,
+    pub conflicts: ConflictPolicy,
+}
+
+impl SyncOptions {
+    fn pushes(&self) -> bool {
+        self.direction != SyncDirection::Pull
+    }
+
+    fn pulls(&self) -> bool {
+        self.direction != SyncDirection::Push
+    }
+}
+
+/// A cloud session that looks like one already recorded locally: same
+/// type, finished within `CONFLICT_WINDOW_SECS` of it. These aren't
+/// downloaded, so the session isn't counted twice
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conflict {
+    pub local_id: i64,
+    pub cloud_id: Option<String>,
+    pub session_type: String,
+    pub completed_at: String,
+    /// None when the local session is already uploaded as another cloud
+    /// session; both are left as they are
+    pub resolution: Option<Resolution>,
+}
+
+/// How a conflict with a local session not uploaded yet was settled; the
+/// two are linked either way
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution {
+    /// The local session goes up over the cloud one
+    KeptLocal,
+    /// The local session was overwritten with the cloud one
+    TookCloud,
+}
+
+/// How close two completion times are to count as the same session
+const CONFLICT_WINDOW_SECS: i64 = 60;
+
 /// Sync result
 #[derive(Debug, Default)]
 pub struct SyncResult {
     pub uploaded: usize,
     pub downloaded: usize,
+    pub conflicts: Vec<Conflict>,
     pub errors: Vec<String>,
 }
 
-/// Perform full sync
-pub fn sync(conn: &Connection) -> Result<SyncResult> {
+/// Perform a sync in one or both directions; with `dry_run` the counts
+/// are what a real sync would upload and download
+pub fn sync(conn: &Connection, options: &SyncOptions) -> Result<SyncResult> {
     let mut result = SyncResult::default();
 
     // Check if logged in
@@ -213,78 +400,216 @@ pub fn sync(conn: &Connection) -> Result<SyncResult> {
         }
     };
 
-    // 1. Upload unsynced local sessions
-    let unsynced = get_unsynced_sessions(conn)?;
-    if !unsynced.is_empty() {
-        println!("Uploading {} local sessions...", unsynced.len());
-
-        for local in &unsynced {
-            let cloud_session = CloudSession {
-                id: Some(uuid::Uuid::new_v4().to_string()),
-                user_id: creds.user_id.clone(),
-                session_type: local.session_type.clone(),
-                duration_seconds: local.duration_seconds,
-                completed_at: local.completed_at.clone(),
-                tag: local.tag.clone(),
-                created_at: Some(Utc::now().to_rfc3339()),
-                synced_from_cli: Some(true),
-            };
+    // 1. Download new cloud sessions, settling conflicts first so a local
+    // session isn't then uploaded a second time
+    if options.pulls() {
+        let last_sync = get_last_sync(conn)?;
+        let cloud_sessions = if let Some(after) = last_sync {
+            println!("Fetching sessions since {}...", after);
+            client.get_sessions_after(&after)?
+        } else {
+            println!("Fetching all cloud sessions...");
+            client.get_sessions()?
+        };
 
-            match client.upload_session(&cloud_session) {
-                Ok(_) => {
-                    if let Some(cloud_id) = &cloud_session.id {
-                        let _ = mark_synced(conn, local.id, cloud_id);
-                    }
-                    result.uploaded += 1;
+        // Filter out sessions that were synced from CLI (avoid duplicates)
+        let mut new_sessions = Vec::new();
+        for session in cloud_sessions {
+            if session.synced_from_cli.unwrap_or(false) || is_downloaded(conn, &session) {
+                continue;
+            }
+            match find_conflict(conn, &session)? {
+                Some(mut conflict) => {
+                    conflict.resolution = resolve_conflict(conn, &session, &conflict, options)?;
+                    result.conflicts.push(conflict);
                 }
-                Err(e) => {
-                    result
-                        .errors
-                        .push(format!("Failed to upload session {}: {}", local.id, e));
+                None => new_sessions.push(session),
+            }
+        }
+
+        if options.dry_run {
+            result.downloaded = new_sessions.len();
+        } else {
+            if !new_sessions.is_empty() {
+                println!("Downloading {} cloud sessions...", new_sessions.len());
+            }
+            for session in &new_sessions {
+                match insert_cloud_session(conn, session) {
+                    Ok(_) => result.downloaded += 1,
+                    Err(e) => {
+                        result
+                            .errors
+                            .push(format!("Failed to insert session: {}", e));
+                    }
                 }
             }
+            set_last_sync(conn, &Utc::now())?;
         }
     }
 
-    // 2. Download new cloud sessions
-    let last_sync = get_last_sync(conn)?;
-    let cloud_sessions = if let Some(after) = last_sync {
-        println!("Fetching sessions since {}...", after);
-        client.get_sessions_after(&after)?
-    } else {
-        println!("Fetching all cloud sessions...");
-        client.get_sessions()?
-    };
+    // 2. Send the outbox, with every completed session not uploaded yet
+    if options.pushes() {
+        if options.dry_run {
+            result.uploaded =
+                get_unsynced_sessions(conn)?.len() + db::get_sync_queue(conn, None)?.len();
+        } else {
+            queue_unsynced(conn)?;
+            let queued = db::get_sync_queue(conn, None)?.len();
+            if queued > 0 {
+                println!("Sending {} queued changes...", queued);
+            }
+            let (sent, errors) = flush_queue(conn, &client, &creds.user_id, None)?;
+            result.uploaded = sent;
+            result.errors.extend(errors);
+        }
+    }
 
-    // Filter out sessions that were synced from CLI (avoid duplicates)
-    let new_sessions: Vec<_> = cloud_sessions
-        .into_iter()
-        .filter(|s| !s.synced_from_cli.unwrap_or(false))
-        .collect();
+    Ok(result)
+}
 
-    if !new_sessions.is_empty() {
-        println!("Downloading {} cloud sessions...", new_sessions.len());
+/// Whether a cloud session is already stored locally: downloaded before,
+/// or uploaded from here, even if the upload was never confirmed (its id is
+/// the local session's uuid)
+fn is_downloaded(conn: &Connection, session: &CloudSession) -> bool {
+    let Some(id) = &session.id else {
+        return false;
+    };
+    conn.query_row(
+        "SELECT COUNT(*) > 0 FROM sessions WHERE uuid = ?1 OR cloud_id = ?1",
+        [id],
+        |row| row.get(0),
+    )
+    .unwrap_or(false)
+}
 
-        for session in &new_sessions {
-            match insert_cloud_session(conn, session) {
-                Ok(_) => result.downloaded += 1,
-                Err(e) => {
-                    result
-                        .errors
-                        .push(format!("Failed to insert session: {}", e));
-                }
+/// The local session a cloud session would duplicate, if any
+fn find_conflict(conn: &Connection, session: &CloudSession) -> Result<Option<Conflict>> {
+    let local_id: Option<i64> = conn
+        .query_row(
+            "SELECT id FROM sessions
+             WHERE completed = 1 AND type = ?1 AND ended_at IS NOT NULL
+               AND ABS(strftime('%s', ended_at) - strftime('%s', ?2)) < ?3
+             ORDER BY ABS(strftime('%s', ended_at) - strftime('%s', ?2))
+             LIMIT 1",
+            rusqlite::params![
+                session.session_type,
+                session.completed_at,
+                CONFLICT_WINDOW_SECS
+            ],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(local_id.map(|local_id| Conflict {
+        local_id,
+        cloud_id: session.id.clone(),
+        session_type: session.session_type.clone(),
+        completed_at: session.completed_at.clone(),
+        resolution: None,
+    }))
+}
+
+/// Settle a conflict by `options.conflicts` when the local session isn't
+/// uploaded yet: link it to the cloud session, then either queue it to go up
+/// over that one or overwrite it with the cloud copy. A dry run only says
+/// which it would be.
+fn resolve_conflict(
+    conn: &Connection,
+    cloud: &CloudSession,
+    conflict: &Conflict,
+    options: &SyncOptions,
+) -> Result<Option<Resolution>> {
+    let Some(cloud_id) = &cloud.id else {
+        return Ok(None);
+    };
+    if get_cloud_id(conn, conflict.local_id)?.is_some() {
+        return Ok(None);
+    }
+    let resolution = match options.conflicts {
+        ConflictPolicy::LocalWins => Resolution::KeptLocal,
+        ConflictPolicy::LatestWins => {
+            let cloud_changed = cloud.created_at.as_deref().unwrap_or(&cloud.completed_at);
+            match (
+                parse_time(cloud_changed),
+                local_changed_at(conn, conflict.local_id)?,
+            ) {
+                (Some(cloud), Some(local)) if cloud > local => Resolution::TookCloud,
+                _ => Resolution::KeptLocal,
             }
         }
+    };
+    if options.dry_run {
+        return Ok(Some(resolution));
     }
 
-    // Update last sync time
-    set_last_sync(conn, &Utc::now())?;
+    // The local session takes the cloud session's id; whatever was queued
+    // for it would make a second copy
+    conn.execute(
+        "UPDATE sessions SET uuid = ?1 WHERE id = ?2",
+        rusqlite::params![cloud_id, conflict.local_id],
+    )?;
+    db::sync_done(conn, conflict.local_id)?;
+    match resolution {
+        Resolution::KeptLocal => {
+            db::queue_sync(conn, conflict.local_id, SyncOp::Update, cloud_id)?;
+            let _ =
+                record_session_event(conn, conflict.local_id, "sync_conflict", Some("kept local"));
+        }
+        Resolution::TookCloud => {
+            take_cloud_copy(conn, conflict.local_id, cloud)?;
+            let _ =
+                record_session_event(conn, conflict.local_id, "sync_conflict", Some("took cloud"));
+            mark_synced(conn, conflict.local_id, cloud_id)?;
+        }
+    }
+    Ok(Some(resolution))
+}
 
-    Ok(result)
+fn parse_time(s: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(s)
+        .ok()
+        .map(|t| t.with_timezone(&Utc))
+}
+
+/// When a local session last changed: its latest event, or when it ended
+fn local_changed_at(conn: &Connection, session_id: i64) -> Result<Option<DateTime<Utc>>> {
+    let changed: Option<String> = conn.query_row(
+        "SELECT COALESCE(
+             (SELECT MAX(created_at) FROM session_events WHERE session_id = ?1),
+             (SELECT ended_at FROM sessions WHERE id = ?1))",
+        [session_id],
+        |row| row.get(0),
+    )?;
+    Ok(changed.as_deref().and_then(parse_time))
+}
+
+/// Overwrite a local session with its cloud copy: length, end and tag
+fn take_cloud_copy(conn: &Connection, session_id: i64, cloud: &CloudSession) -> Result<()> {
+    let Some(ended_at) = parse_time(&cloud.completed_at) else {
+        anyhow::bail!("Cloud session ends at '{}'", cloud.completed_at);
+    };
+    let started_at = ended_at - chrono::Duration::seconds(i64::from(cloud.duration_seconds));
+    if let Some(tag) = &cloud.tag {
+        conn.execute("INSERT OR IGNORE INTO tags (name) VALUES (?1)", [tag])?;
+    }
+    conn.execute(
+        "UPDATE sessions
+         SET duration_seconds = ?1, started_at = ?2, ended_at = ?3,
+             tag_id = (SELECT id FROM tags WHERE name = ?4)
+         WHERE id = ?5",
+        rusqlite::params![
+            cloud.duration_seconds,
+            started_at.to_rfc3339(),
+            ended_at.to_rfc3339(),
+            cloud.tag,
+            session_id
+        ],
+    )?;
+    Ok(())
 }
 
-/// Try to sync a single session immediately after completion
-/// Returns Ok(true) if synced, Ok(false) if not logged in or offline, Err on error
+/// Queue a session just completed and send the outbox's due changes.
+/// Returns Ok(true) if the session is in the cloud, Ok(false) if not logged
+/// in or offline; it then stays queued for a retry.
 pub fn try_sync_session(conn: &Connection, session_id: i64) -> Result<bool> {
     // Check if logged in
     let creds = match auth::load_credentials()? {
@@ -295,41 +620,7 @@ pub fn try_sync_session(conn: &Connection, session_id: i64) -> Result<bool> {
     // Ensure sync table exists
     ensure_sync_table(conn)?;
 
-    // Ensure cloud_id column exists
-    let has_cloud_id: bool = conn
-        .query_row(
-            "SELECT COUNT(*) > 0 FROM pragma_table_info('sessions') WHERE name = 'cloud_id'",
-            [],
-            |row| row.get(0),
-        )
-        .unwrap_or(false);
-
-    if !has_cloud_id {
-        conn.execute("ALTER TABLE sessions ADD COLUMN cloud_id TEXT", [])?;
-    }
-
-    // Get the session details
-    let session: Option<LocalSession> = conn
-        .query_row(
-            "SELECT s.id, s.type, s.duration_seconds, s.ended_at, t.name, s.cloud_id
-             FROM sessions s
-             LEFT JOIN tags t ON s.tag_id = t.id
-             WHERE s.id = ? AND s.completed = 1",
-            [session_id],
-            |row| {
-                Ok(LocalSession {
-                    id: row.get(0)?,
-                    session_type: row.get(1)?,
-                    duration_seconds: row.get(2)?,
-                    completed_at: row.get(3)?,
-                    tag: row.get(4)?,
-                    cloud_id: row.get(5)?,
-                })
-            },
-        )
-        .ok();
-
-    let session = match session {
+    let session = match get_local_session(conn, session_id)? {
         Some(s) => s,
         None => return Ok(false), // Session not found or not completed
     };
@@ -338,37 +629,19 @@ pub fn try_sync_session(conn: &Connection, session_id: i64) -> Result<bool> {
     if session.cloud_id.is_some() {
         return Ok(true);
     }
+    db::queue_sync(conn, session_id, SyncOp::Insert, &session.uuid)?;
 
     // Create Supabase client
     let client = match SupabaseClient::new()? {
         Some(c) => c,
         None => return Ok(false), // No valid credentials
     };
-
-    // Upload the session
-    let cloud_session = CloudSession {
-        id: Some(uuid::Uuid::new_v4().to_string()),
-        user_id: creds.user_id,
-        session_type: session.session_type,
-        duration_seconds: session.duration_seconds,
-        completed_at: session.completed_at,
-        tag: session.tag,
-        created_at: Some(Utc::now().to_rfc3339()),
-        synced_from_cli: Some(true),
-    };
-
-    match client.upload_session(&cloud_session) {
-        Ok(_) => {
-            if let Some(cloud_id) = &cloud_session.id {
-                let _ = mark_synced(conn, session.id, cloud_id);
-            }
-            Ok(true)
-        }
-        Err(_) => Ok(false), // Network error, will retry later
-    }
+    flush_queue(conn, &client, &creds.user_id, Some(Utc::now()))?;
+    Ok(get_cloud_id(conn, session_id)?.is_some())
 }
 
-/// Try to sync all unsynced sessions (called on startup)
+/// Send the outbox's due changes, queueing completed sessions never
+/// uploaded first (called on startup). Returns how many were sent.
 pub fn try_sync_pending(conn: &Connection) -> Result<usize> {
     // Check if logged in
     let creds = match auth::load_credentials()? {
@@ -385,33 +658,9 @@ pub fn try_sync_pending(conn: &Connection) -> Result<usize> {
         None => return Ok(0),
     };
 
-    let unsynced = get_unsynced_sessions(conn)?;
-    let mut synced_count = 0;
-
-    for local in &unsynced {
-        let cloud_session = CloudSession {
-            id: Some(uuid::Uuid::new_v4().to_string()),
-            user_id: creds.user_id.clone(),
-            session_type: local.session_type.clone(),
-            duration_seconds: local.duration_seconds,
-            completed_at: local.completed_at.clone(),
-            tag: local.tag.clone(),
-            created_at: Some(Utc::now().to_rfc3339()),
-            synced_from_cli: Some(true),
-        };
-
-        if client.upload_session(&cloud_session).is_ok() {
-            if let Some(cloud_id) = &cloud_session.id {
-                let _ = mark_synced(conn, local.id, cloud_id);
-            }
-            synced_count += 1;
-        } else {
-            // Stop on first failure (likely offline)
-            break;
-        }
-    }
-
-    Ok(synced_count)
+    queue_unsynced(conn)?;
+    let (sent, _) = flush_queue(conn, &client, &creds.user_id, Some(Utc::now()))?;
+    Ok(sent)
 }
 
 /// Get sync status
@@ -470,6 +719,30 @@ pub fn get_sync_status(conn: &Connection) -> Result<String> {
 
     status.push_str(&format!("Unsynced sessions: {}\n", unsynced_count));
 
+    let queue = db::get_sync_queue(conn, None)?;
+    if !queue.is_empty() {
+        status.push_str(&format!("Queued changes: {}\n", queue.len()));
+    }
+    let dead: Vec<_> = queue.iter().filter(|q| q.dead).collect();
+    if let Some(last) = dead.last() {
+        status.push_str(&format!(
+            "Turned down by the cloud: {} ({}); `sandoro sync` tries them again\n",
+            dead.len(),
+            last.last_error.as_deref().unwrap_or("unknown error")
+        ));
+    }
+    if let Some(failed) = queue.iter().find(|q| q.attempts > 0 && !q.dead) {
+        status.push_str(&format!(
+            "Next retry: {} after {} attempts ({})\n",
+            failed
+                .next_attempt_at
+                .with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M"),
+            failed.attempts,
+            failed.last_error.as_deref().unwrap_or("unknown error")
+        ));
+    }
+
     Ok(status)
 }
 
@@ -527,4 +800,173 @@ mod tests {
         let sync_time = get_last_sync(&conn).unwrap().unwrap();
         assert!((sync_time - now).num_seconds().abs() < 1);
     }
+
+    #[test]
+    fn test_permanent_failures_are_set_aside() {
+        use crate::http::StatusError;
+        use reqwest::StatusCode;
+
+        let db = crate::db::Database::open_in_memory().unwrap();
+        let conn = db.connection();
+        let ids: Vec<i64> = (0..3)
+            .map(|_| {
+                let id = db
+                    .start_session_with_tag(crate::db::SessionType::Work, None, None)
+                    .unwrap();
+                db.complete_session(id, 1500).unwrap();
+                db::queue_sync(conn, id, SyncOp::Insert, &format!("cloud-{}", id)).unwrap();
+                id
+            })
+            .collect();
+
+        // The first is turned down for good; the others still go
+        let (sent, errors) = flush_with(conn, Some(Utc::now()), |entry| {
+            if entry.session_id == ids[0] {
+                Err(StatusError::new(StatusCode::UNPROCESSABLE_ENTITY, "bad row").into())
+            } else {
+                Ok(true)
+            }
+        })
+        .unwrap();
+        assert_eq!(sent, 2);
+        assert_eq!(errors.len(), 1);
+        let queue = db::get_sync_queue(conn, None).unwrap();
+        assert_eq!(queue.len(), 1);
+        assert!(queue[0].dead);
+        assert_eq!(queue[0].attempts, 1);
+        // Left out of the background passes
+        assert!(db::get_sync_queue(conn, Some(Utc::now()))
+            .unwrap()
+            .is_empty());
+
+        // Offline: the pass stops at the first failure and retries later
+        for &id in &ids[1..] {
+            db::queue_sync(conn, id, SyncOp::Delete, "cloud").unwrap();
+        }
+        let mut tried = 0;
+        let (sent, _) = flush_with(conn, Some(Utc::now()), |_| {
+            tried += 1;
+            Err(anyhow::anyhow!("connection refused"))
+        })
+        .unwrap();
+        assert_eq!((sent, tried), (0, 1));
+        let queue = db::get_sync_queue(conn, None).unwrap();
+        assert_eq!(queue.iter().filter(|q| !q.dead).count(), 2);
+    }
+
+    #[test]
+    fn test_find_conflict() {
+        let db = crate::db::Database::open_in_memory().unwrap();
+        let id = db
+            .start_session_with_tag(crate::db::SessionType::Work, None, None)
+            .unwrap();
+        db.complete_session(id, 1500).unwrap();
+        db.connection()
+            .execute(
+                "UPDATE sessions SET ended_at = '2026-10-17T09:25:00+00:00' WHERE id = ?1",
+                [id],
+            )
+            .unwrap();
+
+        let cloud = |session_type: &str, completed_at: &str| CloudSession {
+            id: Some("cloud-1".to_string()),
+            user_id: "user".to_string(),
+            session_type: session_type.to_string(),
+            duration_seconds: 1500,
+            completed_at: completed_at.to_string(),
+            tag: None,
+            created_at: None,
+            synced_from_cli: None,
+        };
+
+        // The same session recorded elsewhere, a few seconds apart
+        let conflict = find_conflict(db.connection(), &cloud("work", "2026-10-17T09:25:30Z"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(conflict.local_id, id);
+        assert_eq!(conflict.cloud_id.as_deref(), Some("cloud-1"));
+
+        let later = cloud("work", "2026-10-17T09:40:00Z");
+        assert_eq!(find_conflict(db.connection(), &later).unwrap(), None);
+        let other_type = cloud("shortBreak", "2026-10-17T09:25:00Z");
+        assert_eq!(find_conflict(db.connection(), &other_type).unwrap(), None);
+    }
+
+    #[test]
+    fn test_conflict_policies() {
+        let db = crate::db::Database::open_in_memory().unwrap();
+        let conn = db.connection();
+        let finished = || {
+            let id = db
+                .start_session_with_tag(crate::db::SessionType::Work, None, None)
+                .unwrap();
+            db.complete_session(id, 1500).unwrap();
+            id
+        };
+        let cloud = |created_at: &str| CloudSession {
+            id: Some("cloud-1".to_string()),
+            user_id: "user".to_string(),
+            session_type: "work".to_string(),
+            duration_seconds: 1200,
+            completed_at: "2026-10-17T09:25:00+00:00".to_string(),
+            tag: Some("reading".to_string()),
+            created_at: Some(created_at.to_string()),
+            synced_from_cli: None,
+        };
+        let options = |conflicts, dry_run| SyncOptions {
+            conflicts,
+            dry_run,
+            ..Default::default()
+        };
+        let settle = |id, session: &CloudSession, options: &SyncOptions| {
+            let conflict = Conflict {
+                local_id: id,
+                cloud_id: session.id.clone(),
+                session_type: "work".to_string(),
+                completed_at: session.completed_at.clone(),
+                resolution: None,
+            };
+            resolve_conflict(conn, session, &conflict, options).unwrap()
+        };
+
+        // Local wins: linked, and queued to go up over the cloud copy
+        let local = finished();
+        queue_unsynced(conn).unwrap();
+        let uuid = db.get_session_detail(local).unwrap().unwrap().uuid.unwrap();
+        assert_eq!(db::get_sync_queue(conn, None).unwrap()[0].cloud_id, uuid);
+        assert!(!is_downloaded(conn, &cloud("2020-01-01T00:00:00Z")));
+        let older = cloud("2020-01-01T00:00:00Z");
+        let kept = settle(local, &older, &options(ConflictPolicy::LocalWins, false));
+        assert_eq!(kept, Some(Resolution::KeptLocal));
+        let queue = db::get_sync_queue(conn, None).unwrap();
+        assert_eq!(queue.len(), 1);
+        assert_eq!(
+            (queue[0].op, queue[0].cloud_id.as_str()),
+            (SyncOp::Update, "cloud-1")
+        );
+        // and takes its id, so the cloud copy counts as this session
+        assert!(is_downloaded(conn, &older));
+
+        // Latest wins: the cloud copy changed after the local session
+        let newer = cloud("2999-01-01T00:00:00Z");
+        let policy = options(ConflictPolicy::LatestWins, true);
+        assert_eq!(settle(local, &older, &policy), Some(Resolution::KeptLocal));
+        assert_eq!(settle(local, &newer, &policy), Some(Resolution::TookCloud));
+        // A dry run changes nothing
+        assert_eq!(db::get_sync_queue(conn, None).unwrap().len(), 1);
+
+        let policy = options(ConflictPolicy::LatestWins, false);
+        assert_eq!(settle(local, &newer, &policy), Some(Resolution::TookCloud));
+        assert!(db::get_sync_queue(conn, None).unwrap().is_empty());
+        let taken = db.get_session_detail(local).unwrap().unwrap();
+        assert_eq!(taken.session.duration_seconds, Some(1200));
+        assert_eq!(taken.tag.unwrap().name, "reading");
+        assert_eq!(
+            get_cloud_id(conn, local).unwrap().as_deref(),
+            Some("cloud-1")
+        );
+
+        // Once uploaded, a matching cloud session is left alone
+        assert_eq!(settle(local, &newer, &policy), None);
+    }
 }
@@ -0,0 +1,142 @@
+//! Goal bars split into per-tag segments
+//!
+//! Each tag's share of today's focus gets its own colored stretch of the
+//! bar, so the daily minutes goal shows where the time went.
+
+use crate::db::Tag;
+use crate::theme::{parse_color, ThemeColor};
+
+/// Colors handed out to tags without one of their own, by tag id
+const PALETTE: [&str; 8] = [
+    "cyan", "orange", "green", "purple", "yellow", "pink", "blue", "red",
+];
+/// Color for untagged focus time
+const UNTAGGED_RGB: (u8, u8, u8) = (128, 128, 128);
+
+/// One tag's share of the bar
+#[derive(Debug, Clone, PartialEq)]
+pub struct Segment {
+    pub label: String,
+    pub seconds: i32,
+    pub rgb: (u8, u8, u8),
+}
+
+/// A tag's color: its own ("#rrggbb" or an accent name), else one from the
+/// palette picked by id so it stays the same from day to day
+pub fn tag_rgb(tag: &Tag) -> (u8, u8, u8) {
+    if let Some(rgb) = tag.color.as_deref().and_then(parse_color) {
+        return rgb;
+    }
+    let index = tag.id.rem_euclid(PALETTE.len() as i64) as usize;
+    ThemeColor::from_accent_name(PALETTE[index]).to_rgb()
+}
+
+/// Segments for today's focus by tag, largest first. None when nothing is
+/// tagged, since a single grey segment says less than the plain bar.
+pub fn segments(by_tag: &[(Option<Tag>, i32)]) -> Option<Vec<Segment>> {
+    if !by_tag
+        .iter()
+        .any(|(tag, seconds)| tag.is_some() && *seconds > 0)
+    {
+        return None;
+    }
+    let mut segments: Vec<Segment> = by_tag
+        .iter()
+        .filter(|(_, seconds)| *seconds > 0)
+        .map(|(tag, seconds)| Segment {
+            label: tag
+                .as_ref()
+                .map(|t| t.name.clone())
+                .unwrap_or_else(|| "No tag".to_string()),
+            seconds: *seconds,
+            rgb: tag.as_ref().map(tag_rgb).unwrap_or(UNTAGGED_RGB),
+        })
+        .collect();
+    segments.sort_by_key(|s| std::cmp::Reverse(s.seconds));
+    Some(segments)
+}
+
+/// Cells of a `width`-wide bar for each segment, proportional to its share
+/// of `goal_seconds`. The filled part matches the plain bar; rounding is
+/// settled by largest remainder so the cells add up exactly.
+pub fn cells(segments: &[Segment], goal_seconds: i32, width: usize) -> Vec<usize> {
+    let total: i64 = segments.iter().map(|s| s.seconds.max(0) as i64).sum();
+    if total == 0 || goal_seconds <= 0 {
+        return vec![0; segments.len()];
+    }
+    let filled = ((total * width as i64 / goal_seconds as i64) as usize).min(width);
+
+    let exact: Vec<f64> = segments
+        .iter()
+        .map(|s| s.seconds.max(0) as f64 * filled as f64 / total as f64)
+        .collect();
+    let mut cells: Vec<usize> = exact.iter().map(|e| e.floor() as usize).collect();
+    let mut order: Vec<usize> = (0..segments.len()).collect();
+    order.sort_by(|&a, &b| {
+        let ra = exact[a] - exact[a].floor();
+        let rb = exact[b] - exact[b].floor();
+        rb.total_cmp(&ra).then(a.cmp(&b))
+    });
+    let short = filled - cells.iter().sum::<usize>();
+    for &i in order.iter().take(short) {
+        cells[i] += 1;
+    }
+    cells
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tag(id: i64, name: &str, color: Option<&str>) -> Tag {
+        Tag {
+            id,
+            name: name.to_string(),
+            color: color.map(str::to_string),
+        }
+    }
+
+    fn segment(seconds: i32) -> Segment {
+        Segment {
+            label: String::new(),
+            seconds,
+            rgb: (0, 0, 0),
+        }
+    }
+
+    #[test]
+    fn test_cells_follow_shares_of_the_goal() {
+        // 90 of 120 minutes: 15 of 20 cells, split 2:1
+        let parts = [segment(3600), segment(1800)];
+        assert_eq!(cells(&parts, 7200, 20), vec![10, 5]);
+
+        // Over the goal: the whole bar, still split by share
+        assert_eq!(cells(&parts, 1800, 6), vec![4, 2]);
+
+        // Rounding never loses or adds cells
+        let thirds = [segment(100), segment(100), segment(100)];
+        assert_eq!(cells(&thirds, 300, 20).iter().sum::<usize>(), 20);
+        assert_eq!(cells(&thirds, 0, 20), vec![0, 0, 0]);
+    }
+
+    #[test]
+    fn test_segments_need_a_tag() {
+        assert_eq!(segments(&[(None, 600)]), None);
+        let writing = tag(1, "writing", Some("#ff8000"));
+        let parts = segments(&[(None, 600), (Some(writing), 1200)]).unwrap();
+        assert_eq!(parts[0].label, "writing");
+        assert_eq!(parts[0].rgb, (255, 128, 0));
+        assert_eq!(parts[1].label, "No tag");
+        assert_eq!(parts[1].rgb, UNTAGGED_RGB);
+    }
+
+    #[test]
+    fn test_tag_colors() {
+        let blue = ThemeColor::from_accent_name("blue").to_rgb();
+        assert_eq!(tag_rgb(&tag(3, "a", Some("blue"))), blue);
+        // Not a hex color: falls back to the palette
+        assert_eq!(tag_rgb(&tag(6, "a", Some("#12"))), blue);
+        assert_eq!(tag_rgb(&tag(6, "a", None)), blue);
+        assert_ne!(tag_rgb(&tag(1, "a", None)), tag_rgb(&tag(2, "b", None)));
+    }
+}
@@ -0,0 +1,47 @@
+//! Taskbar progress through the terminal
+//!
+//! With `[appearance] taskbar_progress`, the timer sends the OSC 9;4
+//! escape sequence, which Windows Terminal, ConEmu, WezTerm, Ghostty and
+//! others show as progress on the tab or taskbar button. Terminals that
+//! don't know it ignore it.
+
+/// What the terminal's progress indicator shows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Progress {
+    /// Percent done of a running countdown
+    Running(u8),
+    /// Percent done of a paused one, shown as a warning (yellow)
+    Paused(u8),
+    /// Counting up with no end, shown as busy
+    Open,
+}
+
+/// The escape sequence that shows `progress`, or clears it for None
+pub fn osc_progress(progress: Option<Progress>) -> String {
+    let (state, percent) = match progress {
+        None => (0, 0),
+        Some(Progress::Running(percent)) => (1, percent.min(100)),
+        Some(Progress::Open) => (3, 0),
+        Some(Progress::Paused(percent)) => (4, percent.min(100)),
+    };
+    format!("\x1b]9;4;{};{}\x07", state, percent)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_progress_sequences() {
+        assert_eq!(
+            osc_progress(Some(Progress::Running(40))),
+            "\x1b]9;4;1;40\x07"
+        );
+        assert_eq!(
+            osc_progress(Some(Progress::Paused(120))),
+            "\x1b]9;4;4;100\x07"
+        );
+        assert_eq!(osc_progress(Some(Progress::Open)), "\x1b]9;4;3;0\x07");
+        assert_eq!(osc_progress(None), "\x1b]9;4;0;0\x07");
+    }
+}
@@ -8,6 +8,8 @@
 use ratatui::style::Color;
 use serde::{Deserialize, Serialize};
 
+use crate::config::RainbowConfig;
+
 /// Theme definition
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Theme {
@@ -122,6 +124,10 @@ impl ThemeColor {
                 // Rainbow returns a default color (actual animation handled elsewhere)
                 ThemeColor::Rgb { r: 255, g: 0, b: 0 }
             }
+            // Terminal color names without an accent shade of their own
+            "magenta" | "white" | "black" | "gray" | "grey" | "darkgray" | "darkgrey" => {
+                ThemeColor::Named(name.to_lowercase())
+            }
             _ => ThemeColor::Rgb {
                 r: 34,
                 g: 211,
@@ -149,11 +155,184 @@ pub const RAINBOW_COLORS: [(u8, u8, u8); 7] = [
     (148, 0, 211), // Violet
 ];
 
-/// Get rainbow color at a given time offset (cycles through 7 colors)
-pub fn get_rainbow_color(time_offset_ms: u64) -> (u8, u8, u8) {
-    // Cycle through colors every 1 second (1000ms per color)
-    let color_index = ((time_offset_ms / 1000) % 7) as usize;
-    RAINBOW_COLORS[color_index]
+/// A palette's name and colors
+pub type NamedPalette = (&'static str, &'static [(u8, u8, u8)]);
+
+/// Built-in rainbow palettes, by the name used for `[appearance.rainbow]
+/// palette`
+pub const RAINBOW_PALETTES: [NamedPalette; 5] = [
+    ("classic", &RAINBOW_COLORS),
+    (
+        "pastel",
+        &[
+            (255, 179, 186),
+            (255, 223, 186),
+            (255, 255, 186),
+            (186, 255, 201),
+            (186, 225, 255),
+            (218, 198, 255),
+        ],
+    ),
+    (
+        "trans",
+        &[
+            (91, 206, 250),
+            (245, 169, 184),
+            (255, 255, 255),
+            (245, 169, 184),
+        ],
+    ),
+    (
+        "sunset",
+        &[
+            (255, 94, 77),
+            (255, 154, 0),
+            (255, 206, 84),
+            (237, 85, 101),
+            (150, 70, 160),
+        ],
+    ),
+    (
+        "ocean",
+        &[(0, 119, 182), (0, 180, 216), (144, 224, 239), (0, 150, 136)],
+    ),
+];
+
+/// Palette name for the colors listed in `[appearance.rainbow] colors`
+pub const CUSTOM_RAINBOW: &str = "custom";
+/// Milliseconds per color offered in Settings
+pub const RAINBOW_SPEEDS: [u32; 4] = [250, 500, 1000, 2000];
+/// Fastest and slowest accepted milliseconds per color
+const RAINBOW_SPEED_RANGE: (u32, u32) = (100, 5000);
+const MAX_RAINBOW_COLORS: usize = 16;
+
+/// A "#rrggbb" color or an accent name ("rainbow" isn't a single color)
+pub fn parse_color(color: &str) -> Option<(u8, u8, u8)> {
+    let color = color.trim().to_lowercase();
+    if color != "rainbow" && available_accent_colors().contains(&color.as_str()) {
+        return Some(ThemeColor::from_accent_name(&color).to_rgb());
+    }
+    let hex = color.strip_prefix('#')?;
+    if hex.len() != 6 {
+        return None;
+    }
+    let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+    Some((channel(0)?, channel(2)?, channel(4)?))
+}
+
+/// The colors the rainbow accent goes through and how fast, resolved from
+/// config. Anything in the config that can't be used falls back to the
+/// classic palette or the nearest speed, and is listed in `problems`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rainbow {
+    /// Palette name, or "classic" when the configured one was unusable
+    pub name: String,
+    pub colors: Vec<(u8, u8, u8)>,
+    /// Ticks (100ms each) per color
+    pub ticks_per_color: u8,
+    pub problems: Vec<String>,
+}
+
+impl Rainbow {
+    pub fn from_config(config: &RainbowConfig) -> Self {
+        let mut problems = Vec::new();
+        let name = config.palette.trim().to_lowercase();
+        let colors = if name == CUSTOM_RAINBOW {
+            let mut colors = Vec::new();
+            for color in &config.colors {
+                match parse_color(color) {
+                    Some(rgb) => colors.push(rgb),
+                    None => problems.push(format!("'{}' is not a #rrggbb color", color)),
+                }
+            }
+            if colors.len() > MAX_RAINBOW_COLORS {
+                problems.push(format!(
+                    "only the first {} colors are used",
+                    MAX_RAINBOW_COLORS
+                ));
+                colors.truncate(MAX_RAINBOW_COLORS);
+            }
+            if colors.len() < 2 {
+                problems.push("a custom palette needs at least 2 colors".to_string());
+                None
+            } else {
+                Some(colors)
+            }
+        } else {
+            let preset = RAINBOW_PALETTES.iter().find(|(n, _)| *n == name);
+            if preset.is_none() {
+                problems.push(format!("unknown palette '{}'", config.palette));
+            }
+            preset.map(|(_, colors)| colors.to_vec())
+        };
+        let (name, colors) = match colors {
+            Some(colors) => (name, colors),
+            None => ("classic".to_string(), RAINBOW_COLORS.to_vec()),
+        };
+
+        let (fastest, slowest) = RAINBOW_SPEED_RANGE;
+        if !(fastest..=slowest).contains(&config.speed_ms) {
+            problems.push(format!("speed_ms must be {}-{}", fastest, slowest));
+        }
+        let speed_ms = config.speed_ms.clamp(fastest, slowest);
+
+        Self {
+            name,
+            colors,
+            ticks_per_color: ((speed_ms + 50) / 100) as u8,
+            problems,
+        }
+    }
+
+    /// Palette name, with the first problem when there is one
+    pub fn summary(&self) -> String {
+        match self.problems.first() {
+            Some(problem) => format!("{} ({})", self.name, problem),
+            None => self.name.clone(),
+        }
+    }
+
+    /// Color for an animation frame; frames step through the palette
+    pub fn color(&self, frame: u8) -> (u8, u8, u8) {
+        self.colors[frame as usize % self.colors.len()]
+    }
+
+    /// Color for a line of a vertical gradient over the whole palette,
+    /// scrolled by `frame`
+    pub fn gradient(&self, line_index: usize, total_lines: usize, frame: u8) -> (u8, u8, u8) {
+        if total_lines == 0 {
+            return self.colors[0];
+        }
+        let len = self.colors.len();
+        // Add frame offset to create scrolling animation
+        let frame_offset = (frame as usize % len) as f32 / len as f32;
+        let position = (line_index as f32 / total_lines as f32 + frame_offset) % 1.0;
+
+        let scaled = position * len as f32;
+        let color_index = scaled.floor() as usize % len;
+        let next_index = (color_index + 1) % len;
+        lerp_color(
+            self.colors[color_index],
+            self.colors[next_index],
+            scaled.fract(),
+        )
+    }
+
+    /// Color for cell `index` of a bar `width` cells wide, spreading the
+    /// palette over the bar
+    pub fn bar(&self, index: usize, width: usize) -> (u8, u8, u8) {
+        self.colors[(index * self.colors.len() / width.max(1)) % self.colors.len()]
+    }
+
+    /// Heatmap color for an activity level (1-4). Classic keeps its own
+    /// distinct heatmap colors; other palettes spread the levels over theirs.
+    pub fn heatmap(&self, level: usize) -> (u8, u8, u8) {
+        if self.name == "classic" {
+            return rainbow_heatmap_rgb(level);
+        }
+        let level = level.clamp(1, 4);
+        self.colors[(level - 1) * (self.colors.len() - 1) / 3]
+    }
 }
 
 /// Interpolate between two colors
@@ -164,33 +343,54 @@ fn lerp_color(c1: (u8, u8, u8), c2: (u8, u8, u8), t: f32) -> (u8, u8, u8) {
     (r, g, b)
 }
 
-/// Get rainbow gradient color for a specific line
-/// This creates a smooth vertical gradient effect like the web version
-/// - line_index: current line (0-based)
-/// - total_lines: total number of lines
-/// - frame: animation frame (0-6) to scroll the gradient
-pub fn get_rainbow_gradient_color(
-    line_index: usize,
-    total_lines: usize,
-    frame: u8,
-) -> (u8, u8, u8) {
-    if total_lines == 0 {
-        return RAINBOW_COLORS[0];
+/// Heatmap activity level (0-4) for a day's focus time
+pub fn activity_level(total_seconds: i32) -> usize {
+    if total_seconds <= 0 {
+        0
+    } else if total_seconds < 30 * 60 {
+        1 // < 30min
+    } else if total_seconds < 60 * 60 {
+        2 // < 1h
+    } else if total_seconds < 120 * 60 {
+        3 // < 2h
+    } else {
+        4 // 2h+
     }
+}
 
-    // Calculate position in the gradient (0.0 to 1.0)
-    // Add frame offset to create scrolling animation
-    let frame_offset = (frame as f32) / 7.0;
-    let position = (line_index as f32 / total_lines as f32 + frame_offset) % 1.0;
+/// Opacity of the accent color for each heatmap level (1-4)
+pub fn heatmap_opacity(level: usize) -> f32 {
+    match level {
+        0 => 0.0,
+        1 => 0.4,
+        2 => 0.6,
+        3 => 0.8,
+        _ => 1.0,
+    }
+}
+
+/// Base heatmap color for an accent name, named colors included
+pub fn heatmap_accent_rgb(accent: &str) -> (u8, u8, u8) {
+    ThemeColor::from_accent_name(accent).to_rgb()
+}
 
-    // Map position to color index with interpolation
-    // Spread all 7 colors across the gradient
-    let scaled = position * 7.0;
-    let color_index = scaled.floor() as usize % 7;
-    let next_index = (color_index + 1) % 7;
-    let t = scaled.fract();
+/// Heatmap color for an accent at an activity level: the accent scaled by
+/// the level's opacity (black at level 0)
+pub fn heatmap_level_rgb(accent: &str, level: usize) -> (u8, u8, u8) {
+    let (r, g, b) = heatmap_accent_rgb(accent);
+    let opacity = heatmap_opacity(level);
+    let scale = |c: u8| (c as f32 * opacity) as u8;
+    (scale(r), scale(g), scale(b))
+}
 
-    lerp_color(RAINBOW_COLORS[color_index], RAINBOW_COLORS[next_index], t)
+/// Distinct heatmap color per activity level (1-4) for the rainbow accent
+pub fn rainbow_heatmap_rgb(level: usize) -> (u8, u8, u8) {
+    match level {
+        1 => (80, 200, 220), // Cyan (low activity)
+        2 => (80, 220, 120), // Green (medium)
+        3 => (255, 200, 60), // Yellow-orange (high)
+        _ => (255, 80, 180), // Magenta-pink (very high)
+    }
 }
 
 impl Theme {
@@ -336,3 +536,80 @@ impl Theme {
         ]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_heatmap_level_rgb_for_every_accent() {
+        let accents = available_accent_colors()
+            .into_iter()
+            .chain(["magenta", "white", "gray"]);
+        for accent in accents {
+            let base = ThemeColor::from_accent_name(accent).to_rgb();
+            assert_eq!(heatmap_accent_rgb(accent), base, "{}", accent);
+            assert_eq!(heatmap_level_rgb(accent, 0), (0, 0, 0), "{}", accent);
+            assert_eq!(heatmap_level_rgb(accent, 4), base, "{}", accent);
+            // Each level is at least as bright as the one below it
+            for level in 1..=4 {
+                let (r, g, b) = heatmap_level_rgb(accent, level);
+                let (pr, pg, pb) = heatmap_level_rgb(accent, level - 1);
+                assert!(r >= pr && g >= pg && b >= pb, "{} level {}", accent, level);
+                let opacity = heatmap_opacity(level);
+                assert_eq!(
+                    r,
+                    (base.0 as f32 * opacity) as u8,
+                    "{} level {}",
+                    accent,
+                    level
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_named_accents_keep_their_hue() {
+        assert_eq!(heatmap_accent_rgb("magenta"), (255, 0, 255));
+        assert_eq!(heatmap_accent_rgb("Grey"), (128, 128, 128));
+        assert_eq!(heatmap_level_rgb("magenta", 2), (153, 0, 153));
+        // Names that aren't colors still fall back to cyan
+        assert_eq!(heatmap_accent_rgb("sparkly"), (34, 211, 238));
+    }
+
+    #[test]
+    fn test_rainbow_from_config() {
+        let mut config = RainbowConfig::default();
+        let classic = Rainbow::from_config(&config);
+        assert_eq!(classic.colors, RAINBOW_COLORS.to_vec());
+        assert_eq!(classic.ticks_per_color, 5);
+        assert_eq!(classic.heatmap(4), rainbow_heatmap_rgb(4));
+        assert!(classic.problems.is_empty());
+
+        // Custom colors, with a bad one skipped and the speed clamped
+        config.palette = "custom".to_string();
+        config.colors = vec!["#5bcefa".into(), "pink".into(), "#zzz".into()];
+        config.speed_ms = 50;
+        let custom = Rainbow::from_config(&config);
+        assert_eq!(custom.name, "custom");
+        assert_eq!(custom.colors, vec![(91, 206, 250), (236, 72, 153)]);
+        assert_eq!(custom.ticks_per_color, 1);
+        assert_eq!(
+            custom.problems,
+            vec!["'#zzz' is not a #rrggbb color", "speed_ms must be 100-5000"]
+        );
+        assert_eq!(custom.color(3), (236, 72, 153));
+        assert_eq!(custom.heatmap(1), (91, 206, 250));
+        assert_eq!(custom.heatmap(4), (236, 72, 153));
+        assert_eq!(custom.gradient(0, 4, 0), (91, 206, 250));
+        assert_eq!(custom.bar(19, 20), (236, 72, 153));
+
+        // Too few usable colors, or an unknown name: classic
+        config.colors = vec!["#5bcefa".into()];
+        assert_eq!(Rainbow::from_config(&config).name, "classic");
+        config.palette = "neon".to_string();
+        let unknown = Rainbow::from_config(&config);
+        assert_eq!(unknown.colors, RAINBOW_COLORS.to_vec());
+        assert_eq!(unknown.summary(), "classic (unknown palette 'neon')");
+    }
+}
@@ -0,0 +1,451 @@
+//! Time entries in Toggl or Clockify for completed work sessions
+//!
+//! Completed work sessions are queued in SQLite and sent from a background
+//! thread, so the timer never waits on the network. Failed uploads stay
+//! queued and are retried with exponential backoff, including on the next
+//! start after a run that was offline. An entry the tracker turns down for
+//! good is set aside so the rest still go. Each entry is claimed before it's
+//! sent, so flushes running at once (the timer and `sandoro track flush`)
+//! never send it twice.
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::config::{Config, TimeTrackingConfig, TrackerProvider};
+use crate::db::{Database, SessionDetail};
+use crate::http::{self, StatusError};
+
+const TOGGL_API: &str = "https://api.track.toggl.com/api/v9";
+const CLOCKIFY_API: &str = "https://api.clockify.me/api/v1";
+const REQUEST_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+/// Wait before the first retry; doubles with each failure
+const RETRY_BASE_SECS: i64 = 60;
+const RETRY_MAX_SECS: i64 = 6 * 60 * 60;
+/// How long a flush holds an entry it's sending; well past REQUEST_TIMEOUT,
+/// so a flush that died lets go eventually
+const CLAIM_SECS: i64 = 5 * 60;
+
+/// Set while this process has a background flush going
+static FLUSHING: AtomicBool = AtomicBool::new(false);
+
+/// What gets sent for one session
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimeEntry {
+    pub description: String,
+    pub start: DateTime<Utc>,
+    pub stop: DateTime<Utc>,
+    pub project: Option<String>,
+    pub tag: Option<String>,
+    pub billable: bool,
+}
+
+/// Outcome of one pass over the queue
+#[derive(Debug, Default, PartialEq)]
+pub struct FlushResult {
+    pub sent: usize,
+    pub failed: usize,
+}
+
+/// Build the entry for a completed work session or named timer
+pub fn entry_for(config: &TimeTrackingConfig, detail: &SessionDetail) -> Option<TimeEntry> {
+    let session = &detail.session;
+    if !matches!(session.session_type.as_str(), "work" | "timer") || !session.completed {
+        return None;
+    }
+    let seconds = session.duration_seconds?;
+    let tag = detail.tag.as_ref().map(|t| t.name.clone());
+    let project = tag
+        .as_ref()
+        .and_then(|t| config.projects.get(t))
+        .or(config.default_project.as_ref())
+        .cloned();
+    Some(TimeEntry {
+        description: config.description.clone(),
+        start: session.started_at,
+        stop: session.started_at + Duration::seconds(seconds as i64),
+        project,
+        tag,
+        billable: config.billable,
+    })
+}
+
+fn timestamp(dt: DateTime<Utc>) -> String {
+    dt.format("%Y-%m-%dT%H:%M:%SZ").to_string()
+}
+
+fn numeric_id(kind: &str, id: &str) -> Result<i64> {
+    id.trim()
+        .parse()
+        .with_context(|| format!("Toggl {} ID must be a number, got '{}'", kind, id))
+}
+
+/// Endpoint and JSON body for creating an entry
+pub fn request(
+    provider: TrackerProvider,
+    workspace_id: &str,
+    entry: &TimeEntry,
+) -> Result<(String, Value)> {
+    match provider {
+        TrackerProvider::Toggl => {
+            let workspace = numeric_id("workspace", workspace_id)?;
+            let mut body = json!({
+                "created_with": "sandoro",
+                "description": entry.description,
+                "start": timestamp(entry.start),
+                "stop": timestamp(entry.stop),
+                "duration": (entry.stop - entry.start).num_seconds(),
+                "workspace_id": workspace,
+                "billable": entry.billable,
+                "tags": entry.tag.iter().collect::<Vec<_>>(),
+            });
+            if let Some(project) = &entry.project {
+                body["project_id"] = json!(numeric_id("project", project)?);
+            }
+            let url = format!("{}/workspaces/{}/time_entries", TOGGL_API, workspace);
+            Ok((url, body))
+        }
+        TrackerProvider::Clockify => {
+            // Clockify tags need IDs, so the tag goes into the description
+            let description = match &entry.tag {
+                Some(tag) => format!("{} ({})", entry.description, tag),
+                None => entry.description.clone(),
+            };
+            let mut body = json!({
+                "description": description,
+                "start": timestamp(entry.start),
+                "end": timestamp(entry.stop),
+                "billable": entry.billable,
+            });
+            if let Some(project) = &entry.project {
+                body["projectId"] = json!(project);
+            }
+            let url = format!(
+                "{}/workspaces/{}/time-entries",
+                CLOCKIFY_API,
+                workspace_id.trim()
+            );
+            Ok((url, body))
+        }
+    }
+}
+
+/// Create the entry and return the tracker's ID for it
+fn send(
+    config: &TimeTrackingConfig,
+    provider: TrackerProvider,
+    entry: &TimeEntry,
+) -> Result<String> {
+    let (url, body) = request(provider, &config.workspace_id, entry)?;
+    let client = reqwest::blocking::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()?;
+    let request = client.post(&url).json(&body);
+    let request = match provider {
+        TrackerProvider::Toggl => request.basic_auth(&config.api_token, Some("api_token")),
+        TrackerProvider::Clockify => request.header("X-Api-Key", &config.api_token),
+    };
+
+    let response = request.send()?;
+    let status = response.status();
+    if !status.is_success() {
+        let text = response.text().unwrap_or_default();
+        let message = format!("{} returned {}: {}", provider.as_str(), status, text.trim());
+        return Err(StatusError::new(status, message).into());
+    }
+    let created: Value = response.json()?;
+    Ok(match &created["id"] {
+        Value::String(id) => id.clone(),
+        id => id.to_string(),
+    })
+}
+
+/// How long to wait after the given number of failed attempts
+pub fn backoff(attempts: u32) -> Duration {
+    let secs = RETRY_BASE_SECS.saturating_mul(1 << attempts.min(16));
+    Duration::seconds(secs.min(RETRY_MAX_SECS))
+}
+
+/// Queue a session if time tracking is set up. Returns whether it was queued.
+pub fn enqueue(db: &Database, config: &Config, session_id: i64) -> Result<bool> {
+    match config.time_tracking.enabled() {
+        Some(provider) => {
+            db.queue_time_entry(session_id, provider.as_str())?;
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+/// Send queued entries that are due. With `force`, ignore the backoff.
+pub fn flush(db: &Database, config: &Config, force: bool) -> Result<FlushResult> {
+    let tracking = &config.time_tracking;
+    flush_with(db, tracking, Utc::now(), force, |provider, entry| {
+        send(tracking, provider, entry)
+    })
+}
+
+fn flush_with(
+    db: &Database,
+    config: &TimeTrackingConfig,
+    now: DateTime<Utc>,
+    force: bool,
+    mut send: impl FnMut(TrackerProvider, &TimeEntry) -> Result<String>,
+) -> Result<FlushResult> {
+    let mut result = FlushResult::default();
+    let Some(provider) = config.enabled() else {
+        return Ok(result);
+    };
+
+    let due = if force { None } else { Some(now) };
+    for queued in db.get_time_entry_queue(due)? {
+        if !db.claim_time_entry(queued.session_id, now, now + Duration::seconds(CLAIM_SECS))? {
+            continue;
+        }
+        // Sessions deleted or pruned since they were queued have nothing to send
+        let entry = db
+            .get_session_detail(queued.session_id)?
+            .and_then(|detail| entry_for(config, &detail));
+        let Some(entry) = entry else {
+            db.time_entry_done(queued.session_id, None)?;
+            continue;
+        };
+
+        match send(provider, &entry) {
+            Ok(id) => {
+                let detail = format!("{}:{}", provider.as_str(), id);
+                db.time_entry_done(queued.session_id, Some(&detail))?;
+                result.sent += 1;
+            }
+            Err(e) if http::is_permanent(&e) => {
+                db.time_entry_dead(queued.session_id, &e.to_string())?;
+                result.failed += 1;
+            }
+            Err(e) => {
+                let next = now + backoff(queued.attempts);
+                db.time_entry_failed(queued.session_id, &e.to_string(), next)?;
+                result.failed += 1;
+                // Most failures (offline, bad token) hit every entry alike
+                break;
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// Flush the queue from a background thread with its own connection. Does
+/// nothing while an earlier one is still going; that one picks up whatever
+/// was queued since.
+pub fn flush_in_background(config: &Config) {
+    if config.time_tracking.enabled().is_none() || FLUSHING.swap(true, Ordering::AcqRel) {
+        return;
+    }
+    let config = config.clone();
+    std::thread::spawn(move || {
+        if let Ok(db) = Database::open() {
+            let _ = flush(&db, &config, false);
+        }
+        FLUSHING.store(false, Ordering::Release);
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::SessionType;
+
+    fn tracking(provider: TrackerProvider) -> TimeTrackingConfig {
+        TimeTrackingConfig {
+            provider: Some(provider),
+            api_token: "token".to_string(),
+            workspace_id: "42".to_string(),
+            default_project: Some("7".to_string()),
+            projects: [("writing".to_string(), "99".to_string())].into(),
+            billable: true,
+            ..Default::default()
+        }
+    }
+
+    fn completed_work(db: &Database, tag: Option<&str>) -> i64 {
+        let tag_id = tag.map(|t| db.create_tag(t, None).unwrap());
+        let id = db
+            .start_session_with_tag(SessionType::Work, tag_id, Some(1500))
+            .unwrap();
+        db.complete_session(id, 1500).unwrap();
+        id
+    }
+
+    #[test]
+    fn test_entry_maps_tag_to_project() {
+        let db = Database::open_in_memory().unwrap();
+        let config = tracking(TrackerProvider::Toggl);
+
+        let writing = completed_work(&db, Some("writing"));
+        let detail = db.get_session_detail(writing).unwrap().unwrap();
+        let entry = entry_for(&config, &detail).unwrap();
+        assert_eq!(entry.project.as_deref(), Some("99"));
+        assert_eq!(entry.tag.as_deref(), Some("writing"));
+        assert_eq!((entry.stop - entry.start).num_seconds(), 1500);
+
+        let untagged = completed_work(&db, None);
+        let detail = db.get_session_detail(untagged).unwrap().unwrap();
+        assert_eq!(
+            entry_for(&config, &detail).unwrap().project.as_deref(),
+            Some("7")
+        );
+
+        let open = db
+            .start_session_with_tag(SessionType::Work, None, Some(1500))
+            .unwrap();
+        let detail = db.get_session_detail(open).unwrap().unwrap();
+        assert_eq!(entry_for(&config, &detail), None);
+    }
+
+    #[test]
+    fn test_request_bodies() {
+        let start = DateTime::parse_from_rfc3339("2026-10-17T09:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let entry = TimeEntry {
+            description: "Focus session".to_string(),
+            start,
+            stop: start + Duration::minutes(25),
+            project: Some("99".to_string()),
+            tag: Some("writing".to_string()),
+            billable: true,
+        };
+
+        let (url, body) = request(TrackerProvider::Toggl, "42", &entry).unwrap();
+        assert_eq!(
+            url,
+            "https://api.track.toggl.com/api/v9/workspaces/42/time_entries"
+        );
+        assert_eq!(body["workspace_id"], 42);
+        assert_eq!(body["project_id"], 99);
+        assert_eq!(body["duration"], 1500);
+        assert_eq!(body["stop"], "2026-10-17T09:25:00Z");
+        assert_eq!(body["tags"], json!(["writing"]));
+        assert!(request(TrackerProvider::Toggl, "abc", &entry).is_err());
+
+        let (url, body) = request(TrackerProvider::Clockify, "ws1", &entry).unwrap();
+        assert_eq!(
+            url,
+            "https://api.clockify.me/api/v1/workspaces/ws1/time-entries"
+        );
+        assert_eq!(body["projectId"], "99");
+        assert_eq!(body["end"], "2026-10-17T09:25:00Z");
+        assert_eq!(body["description"], "Focus session (writing)");
+    }
+
+    #[test]
+    fn test_backoff_doubles_and_caps() {
+        assert_eq!(backoff(0), Duration::minutes(1));
+        assert_eq!(backoff(3), Duration::minutes(8));
+        assert_eq!(backoff(20), Duration::hours(6));
+    }
+
+    #[test]
+    fn test_flush_retries_failures_later() {
+        let db = Database::open_in_memory().unwrap();
+        let config = tracking(TrackerProvider::Toggl);
+        let first = completed_work(&db, Some("writing"));
+        let second = completed_work(&db, None);
+        db.queue_time_entry(first, "toggl").unwrap();
+        db.queue_time_entry(second, "toggl").unwrap();
+        let now = Utc::now();
+
+        // Offline: the first failure stops the pass
+        let offline =
+            flush_with(&db, &config, now, false, |_, _| anyhow::bail!("offline")).unwrap();
+        assert_eq!(offline, FlushResult { sent: 0, failed: 1 });
+        let queue = db.get_time_entry_queue(None).unwrap();
+        assert_eq!(queue[0].attempts, 1);
+        assert_eq!(queue[0].last_error.as_deref(), Some("offline"));
+
+        // The failed entry waits out its backoff; the other is still due
+        let mut sent = Vec::new();
+        let result = flush_with(&db, &config, now, false, |_, entry| {
+            sent.push(entry.tag.clone());
+            Ok("1".to_string())
+        })
+        .unwrap();
+        assert_eq!(result, FlushResult { sent: 1, failed: 0 });
+        assert_eq!(sent, vec![None]);
+
+        let later = now + backoff(0) + Duration::seconds(1);
+        flush_with(&db, &config, later, false, |_, _| Ok("2".to_string())).unwrap();
+        assert!(db.get_time_entry_queue(None).unwrap().is_empty());
+        let events = db.get_session_events(first).unwrap();
+        assert!(events
+            .iter()
+            .any(|e| e.event == "time_entry" && e.detail.as_deref() == Some("toggl:2")));
+    }
+
+    #[test]
+    fn test_flush_sets_aside_entries_turned_down() {
+        use reqwest::StatusCode;
+
+        let db = Database::open_in_memory().unwrap();
+        let config = tracking(TrackerProvider::Clockify);
+        let rejected = completed_work(&db, Some("writing"));
+        let fine = completed_work(&db, None);
+        db.queue_time_entry(rejected, "clockify").unwrap();
+        db.queue_time_entry(fine, "clockify").unwrap();
+
+        let now = Utc::now();
+        let result = flush_with(&db, &config, now, false, |_, entry| {
+            if entry.tag.is_some() {
+                Err(StatusError::new(StatusCode::BAD_REQUEST, "project archived").into())
+            } else {
+                Ok("1".to_string())
+            }
+        })
+        .unwrap();
+        assert_eq!(result, FlushResult { sent: 1, failed: 1 });
+        let queue = db.get_time_entry_queue(None).unwrap();
+        assert_eq!(queue.len(), 1);
+        assert!(queue[0].dead);
+        assert_eq!(queue[0].attempts, 1);
+        // Background passes leave it alone
+        let later = now + Duration::hours(7);
+        assert!(db.get_time_entry_queue(Some(later)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_claimed_entries_are_sent_once() {
+        let db = Database::open_in_memory().unwrap();
+        let config = tracking(TrackerProvider::Toggl);
+        let id = completed_work(&db, None);
+        db.queue_time_entry(id, "toggl").unwrap();
+        let now = Utc::now();
+
+        // Another flush has it
+        assert!(db
+            .claim_time_entry(id, now, now + Duration::seconds(CLAIM_SECS))
+            .unwrap());
+        let result = flush_with(&db, &config, now, true, |_, _| panic!("sent twice")).unwrap();
+        assert_eq!(result, FlushResult::default());
+
+        // A claim left by a flush that died runs out
+        let later = now + Duration::seconds(CLAIM_SECS + 1);
+        let result = flush_with(&db, &config, later, true, |_, _| Ok("1".to_string())).unwrap();
+        assert_eq!(result.sent, 1);
+    }
+
+    #[test]
+    fn test_flush_drops_deleted_sessions() {
+        let db = Database::open_in_memory().unwrap();
+        let id = completed_work(&db, None);
+        db.queue_time_entry(id, "toggl").unwrap();
+        db.delete_session(id).unwrap();
+
+        let config = tracking(TrackerProvider::Toggl);
+        let result = flush_with(&db, &config, Utc::now(), false, |_, _| {
+            panic!("nothing to send")
+        })
+        .unwrap();
+        assert_eq!(result, FlushResult::default());
+        assert!(db.get_time_entry_queue(None).unwrap().is_empty());
+    }
+}
@@ -1,6 +1,9 @@
 //! Timer logic and state management
 
-use std::time::{Duration, Instant};
+use std::time::Duration;
+
+use crate::clock::{self, Clock};
+use crate::i18n;
 
 /// Timer states
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -8,39 +11,29 @@ pub enum TimerState {
     Work,
     ShortBreak,
     LongBreak,
+    /// A named timer (timer focus mode), counting down or, with no length,
+    /// up like a stopwatch
+    Timer,
 }
 
 impl TimerState {
-    #[allow(dead_code)]
+    /// Work or a named timer: time that counts toward focus stats
+    pub fn is_focus(&self) -> bool {
+        matches!(self, TimerState::Work | TimerState::Timer)
+    }
+
     pub fn label(&self) -> &'static str {
         self.label_with_lang("en")
     }
 
     pub fn label_with_lang(&self, lang: &str) -> &'static str {
-        let is_ja = lang == "ja";
-        match self {
-            TimerState::Work => {
-                if is_ja {
-                    "作業中"
-                } else {
-                    "WORKING"
-                }
-            }
-            TimerState::ShortBreak => {
-                if is_ja {
-                    "短い休憩"
-                } else {
-                    "SHORT BREAK"
-                }
-            }
-            TimerState::LongBreak => {
-                if is_ja {
-                    "長い休憩"
-                } else {
-                    "LONG BREAK"
-                }
-            }
-        }
+        let label = match self {
+            TimerState::Work => "WORKING",
+            TimerState::ShortBreak => "SHORT BREAK",
+            TimerState::LongBreak => "LONG BREAK",
+            TimerState::Timer => "TIMER",
+        };
+        i18n::t_in(lang, label)
     }
 }
 
@@ -64,14 +57,21 @@ pub struct Timer {
     pub sessions_until_long_break: u32,
     /// Current session count (1-based)
     pub session_count: u32,
-    /// Last tick time
-    last_tick: Instant,
+    /// Clock the timer counts on
+    clock: Clock,
+    /// Clock reading at the last tick
+    last_tick: Duration,
     /// Accumulated time since last second
     accumulated: Duration,
     /// Whether we're in flowtime mode
     pub is_flowtime: bool,
     /// Calculated flowtime break duration in seconds
     pub flowtime_break_seconds: u32,
+    /// Timer seconds per real second (sped up by `sandoro demo`)
+    pub speed: u32,
+    /// Length in minutes of the named timer in timer focus mode (0 for a
+    /// stopwatch); None in the other modes
+    pub timer_minutes: Option<u32>,
 }
 
 impl Timer {
@@ -86,6 +86,7 @@ impl Timer {
         long_break_minutes: u32,
         sessions_until_long_break: u32,
     ) -> Self {
+        let clock = clock::global();
         Self {
             state: TimerState::Work,
             remaining_seconds: work_minutes * 60,
@@ -96,13 +97,23 @@ impl Timer {
             long_break_duration: long_break_minutes,
             sessions_until_long_break,
             session_count: 1,
-            last_tick: Instant::now(),
+            last_tick: clock.elapsed(),
+            clock,
             accumulated: Duration::ZERO,
             is_flowtime: false,
             flowtime_break_seconds: 0,
+            speed: 1,
+            timer_minutes: None,
         }
     }
 
+    /// Count on `clock` instead of the process's clock
+    #[allow(dead_code)]
+    pub fn set_clock(&mut self, clock: Clock) {
+        self.last_tick = clock.elapsed();
+        self.clock = clock;
+    }
+
     /// Set flowtime mode
     pub fn set_flowtime(&mut self, is_flowtime: bool) {
         self.is_flowtime = is_flowtime;
@@ -112,24 +123,87 @@ impl Timer {
         }
     }
 
+    /// Switch to a named timer of `minutes` (0 counts up), paused at its
+    /// start; None goes back to the work/break cycle
+    pub fn set_named_timer(&mut self, minutes: Option<u32>) {
+        self.timer_minutes = minutes;
+        match minutes {
+            Some(minutes) => {
+                self.state = TimerState::Timer;
+                self.remaining_seconds = minutes * 60;
+                self.elapsed_seconds = 0;
+            }
+            None if self.state == TimerState::Timer => {
+                self.state = TimerState::Work;
+                self.remaining_seconds = self.work_duration * 60;
+                self.elapsed_seconds = 0;
+            }
+            None => return,
+        }
+        self.is_paused = true;
+        self.accumulated = Duration::ZERO;
+    }
+
+    /// Change the work and short break lengths. A phase that hasn't started
+    /// (paused at its full length) takes the new length; one under way keeps
+    /// the time it has.
+    pub fn set_lengths(&mut self, work_minutes: u32, short_break_minutes: u32) {
+        let fresh = self.is_paused
+            && !self.is_counting_up()
+            && self.remaining_seconds == self.phase_seconds();
+        self.work_duration = work_minutes;
+        self.short_break_duration = short_break_minutes;
+        if fresh {
+            self.remaining_seconds = self.phase_seconds();
+        }
+    }
+
+    /// Whether the current phase counts up: flowtime work, or a named timer
+    /// with no length
+    pub fn is_counting_up(&self) -> bool {
+        match self.state {
+            TimerState::Work => self.is_flowtime,
+            TimerState::Timer => self.timer_minutes == Some(0),
+            TimerState::ShortBreak | TimerState::LongBreak => false,
+        }
+    }
+
+    /// Full length of the current phase in seconds (0 when counting up)
+    pub fn phase_seconds(&self) -> u32 {
+        match self.state {
+            _ if self.is_counting_up() => 0,
+            TimerState::ShortBreak if self.is_flowtime => self.flowtime_break_seconds,
+            state => self.duration_for_state(state) * 60,
+        }
+    }
+
+    /// Time the current phase has run
+    pub fn seconds_done(&self) -> u32 {
+        if self.is_counting_up() {
+            self.elapsed_seconds
+        } else {
+            self.phase_seconds().saturating_sub(self.remaining_seconds)
+        }
+    }
+
     /// Tick the timer (call this every frame)
     pub fn tick(&mut self) {
         if self.is_paused {
-            self.last_tick = Instant::now();
+            self.last_tick = self.clock.elapsed();
             return;
         }
 
-        let now = Instant::now();
-        let elapsed = now - self.last_tick;
+        let now = self.clock.elapsed();
+        let elapsed = now.saturating_sub(self.last_tick);
         self.last_tick = now;
-        self.accumulated += elapsed;
+        self.accumulated += elapsed * self.speed;
 
         // Handle time changes
         while self.accumulated >= Duration::from_secs(1) {
             self.accumulated -= Duration::from_secs(1);
 
-            if self.is_flowtime && self.state == TimerState::Work {
-                // Flowtime work mode: count up (no auto-transition)
+            if self.is_counting_up() {
+                // Flowtime work or a stopwatch: count up (no auto-transition)
                 self.elapsed_seconds += 1;
             } else {
                 // Classic mode or flowtime break: count down
@@ -140,7 +214,7 @@ impl Timer {
         }
 
         // Check for completion (only in countdown mode)
-        if (!self.is_flowtime || self.state != TimerState::Work) && self.remaining_seconds == 0 {
+        if !self.is_counting_up() && self.remaining_seconds == 0 {
             self.transition_to_next_state();
         }
     }
@@ -149,13 +223,13 @@ impl Timer {
     pub fn toggle_pause(&mut self) {
         self.is_paused = !self.is_paused;
         if !self.is_paused {
-            self.last_tick = Instant::now();
+            self.last_tick = self.clock.elapsed();
         }
     }
 
     /// Reset current timer
     pub fn reset(&mut self) {
-        if self.is_flowtime && self.state == TimerState::Work {
+        if self.is_counting_up() {
             self.elapsed_seconds = 0;
         } else {
             self.remaining_seconds = self.duration_for_state(self.state) * 60;
@@ -164,7 +238,8 @@ impl Timer {
         self.accumulated = Duration::ZERO;
     }
 
-    /// Skip to next state (for classic mode or flowtime break)
+    /// Skip to next state (for classic mode or flowtime break); a named
+    /// timer ends and starts over
     pub fn skip(&mut self) {
         if self.is_flowtime && self.state == TimerState::Work {
             // In flowtime work mode, use end_work instead
@@ -193,15 +268,15 @@ impl Timer {
 
     /// Get progress percentage (0.0 - 100.0)
     pub fn progress_percent(&self) -> f32 {
-        // In flowtime work mode, there's no progress (infinite)
-        if self.is_flowtime && self.state == TimerState::Work {
+        // In flowtime work mode or a stopwatch, there's no progress (infinite)
+        if self.is_counting_up() {
             return 0.0;
         }
         let total = self.duration_for_state(self.state) * 60;
         if total == 0 {
             return 0.0;
         }
-        ((total - self.remaining_seconds) as f32 / total as f32) * 100.0
+        (total.saturating_sub(self.remaining_seconds) as f32 / total as f32) * 100.0
     }
 
     /// Get remaining time as (minutes, seconds)
@@ -214,15 +289,55 @@ impl Timer {
         (self.elapsed_seconds / 60, self.elapsed_seconds % 60)
     }
 
-    /// Get display time - elapsed for flowtime work, remaining otherwise
+    /// Get display time - elapsed when counting up, remaining otherwise
     pub fn display_time(&self) -> (u32, u32) {
-        if self.is_flowtime && self.state == TimerState::Work {
+        if self.is_counting_up() {
             self.elapsed_time()
         } else {
             self.remaining_time()
         }
     }
 
+    /// Work blocks done in the current cycle, out of
+    /// `sessions_until_long_break`; the one under way doesn't count yet
+    pub fn cycle_done(&self) -> u32 {
+        let done = match self.state {
+            TimerState::Work => self.session_count.saturating_sub(1),
+            TimerState::ShortBreak | TimerState::LongBreak => self.session_count,
+            // Named timers aren't part of a cycle
+            TimerState::Timer => return 0,
+        };
+        done.min(self.sessions_until_long_break)
+    }
+
+    /// Work block within the cycle, the current one or the one a break
+    /// follows: 2 in "2/4". 0 for a named timer.
+    pub fn cycle_position(&self) -> u32 {
+        match self.state {
+            TimerState::Timer => 0,
+            _ => self.session_count.min(self.sessions_until_long_break),
+        }
+    }
+
+    /// Work blocks before the long break; 0 for a named timer
+    pub fn cycle_length(&self) -> u32 {
+        match self.state {
+            TimerState::Timer => 0,
+            _ => self.sessions_until_long_break,
+        }
+    }
+
+    /// The cycle as dots, one per work block before the long break, filled
+    /// for the ones done: ●●○○
+    pub fn cycle_dots(&self) -> String {
+        if self.state == TimerState::Timer {
+            return String::new();
+        }
+        let done = self.cycle_done() as usize;
+        let left = (self.sessions_until_long_break as usize).saturating_sub(done);
+        format!("{}{}", "●".repeat(done), "○".repeat(left))
+    }
+
     /// Get formatted display time string
     /// Format: MM:SS for <100 min, or M...M:SS for longer sessions (e.g., 100:00, 150:30)
     pub fn formatted_display_time(&self) -> String {
@@ -246,6 +361,7 @@ impl Timer {
             TimerState::Work => self.work_duration,
             TimerState::ShortBreak => self.short_break_duration,
             TimerState::LongBreak => self.long_break_duration,
+            TimerState::Timer => self.timer_minutes.unwrap_or(0),
         }
     }
 
@@ -269,10 +385,12 @@ impl Timer {
                 self.session_count = 1;
                 TimerState::Work
             }
+            // A named timer starts over
+            TimerState::Timer => TimerState::Timer,
         };
 
         // Reset timers based on new state
-        if self.is_flowtime && self.state == TimerState::Work {
+        if self.is_counting_up() {
             // Flowtime work: reset elapsed time for count-up
             self.elapsed_seconds = 0;
         } else {
@@ -289,12 +407,18 @@ impl Timer {
         self.transition_to_next_state();
         if auto_start {
             self.is_paused = false;
-            self.last_tick = Instant::now();
+            self.last_tick = self.clock.elapsed();
         }
     }
 
     /// Full reset - back to session 1 and Work state
     pub fn full_reset(&mut self) {
+        self.session_count = 1;
+        self.flowtime_break_seconds = 0;
+        if self.timer_minutes.is_some() {
+            self.set_named_timer(self.timer_minutes);
+            return;
+        }
         self.state = TimerState::Work;
         self.remaining_seconds = self.work_duration * 60;
         self.elapsed_seconds = 0;
@@ -308,6 +432,33 @@ impl Timer {
     pub fn add_time(&mut self, seconds: u32) {
         self.remaining_seconds += seconds;
     }
+
+    /// Switch to a block of `minutes` in `state`, paused at its start (for
+    /// routines, whose blocks set their own lengths)
+    pub fn start_block(&mut self, state: TimerState, minutes: u32) {
+        match state {
+            TimerState::Work => self.work_duration = minutes,
+            TimerState::ShortBreak => self.short_break_duration = minutes,
+            TimerState::LongBreak => self.long_break_duration = minutes,
+            TimerState::Timer => self.timer_minutes = Some(minutes),
+        }
+        self.state = state;
+        self.remaining_seconds = minutes * 60;
+        self.elapsed_seconds = 0;
+        self.is_paused = true;
+        self.accumulated = Duration::ZERO;
+    }
+
+    /// Go back to a break that just ended, with `seconds` left, running.
+    /// `session_count` is the count during that break.
+    pub fn reopen_break(&mut self, state: TimerState, session_count: u32, seconds: u32) {
+        self.state = state;
+        self.session_count = session_count;
+        self.remaining_seconds = seconds;
+        self.accumulated = Duration::ZERO;
+        self.is_paused = false;
+        self.last_tick = self.clock.elapsed();
+    }
 }
 
 #[cfg(test)]
@@ -322,6 +473,24 @@ mod tests {
         assert!(timer.is_paused);
     }
 
+    #[test]
+    fn test_counts_on_its_clock() {
+        let clock = Clock::frozen();
+        let mut timer = Timer::new(25, 5, 15);
+        timer.set_clock(clock.clone());
+        timer.toggle_pause();
+
+        clock.advance(Duration::from_secs(90));
+        timer.tick();
+        assert_eq!(timer.remaining_seconds, 25 * 60 - 90);
+
+        // A whole session goes by in one tick
+        clock.advance(Duration::from_secs(25 * 60));
+        timer.tick();
+        assert_eq!(timer.state, TimerState::ShortBreak);
+        assert_eq!(timer.session_count, 1);
+    }
+
     #[test]
     fn test_toggle_pause() {
         let mut timer = Timer::new(25, 5, 15);
@@ -383,6 +552,23 @@ mod tests {
         assert_eq!(timer.remaining_seconds, 25 * 60);
     }
 
+    #[test]
+    fn test_cycle_dots_follow_the_cycle() {
+        let mut timer = Timer::with_sessions(25, 5, 15, 4);
+        assert_eq!(timer.cycle_dots(), "○○○○");
+        timer.skip();
+        assert_eq!(timer.cycle_dots(), "●○○○");
+        timer.skip();
+        assert_eq!(timer.cycle_dots(), "●○○○");
+        for _ in 0..5 {
+            timer.skip();
+        }
+        assert_eq!(timer.state, TimerState::LongBreak);
+        assert_eq!(timer.cycle_dots(), "●●●●");
+        timer.skip();
+        assert_eq!(timer.cycle_dots(), "○○○○");
+    }
+
     #[test]
     fn test_progress_percent() {
         let mut timer = Timer::new(25, 5, 15);
@@ -425,6 +611,64 @@ mod tests {
         assert_eq!(TimerState::LongBreak.label(), "LONG BREAK");
     }
 
+    #[test]
+    fn test_set_lengths_only_resets_a_phase_not_yet_started() {
+        let mut timer = Timer::new(25, 5, 15);
+        timer.set_lengths(50, 10);
+        assert_eq!(timer.remaining_seconds, 50 * 60);
+
+        timer.toggle_pause();
+        timer.remaining_seconds = 30 * 60;
+        timer.set_lengths(25, 5);
+        assert_eq!(timer.remaining_seconds, 30 * 60);
+        timer.remaining_seconds = 0;
+        timer.tick();
+        assert_eq!(timer.state, TimerState::ShortBreak);
+        assert_eq!(timer.remaining_seconds, 5 * 60);
+    }
+
+    #[test]
+    fn test_named_timer_counts_down_and_starts_over() {
+        let clock = Clock::frozen();
+        let mut timer = Timer::new(25, 5, 15);
+        timer.set_clock(clock.clone());
+        timer.set_named_timer(Some(90));
+        assert_eq!(timer.state, TimerState::Timer);
+        assert_eq!(timer.phase_seconds(), 90 * 60);
+        assert_eq!(timer.cycle_dots(), "");
+        timer.toggle_pause();
+
+        clock.advance(Duration::from_secs(30 * 60));
+        timer.tick();
+        assert_eq!(timer.seconds_done(), 30 * 60);
+        clock.advance(Duration::from_secs(60 * 60));
+        timer.tick();
+        assert_eq!(timer.state, TimerState::Timer);
+        assert_eq!(timer.remaining_seconds, 90 * 60);
+        assert!(timer.is_paused);
+
+        timer.set_named_timer(None);
+        assert_eq!(timer.state, TimerState::Work);
+        assert_eq!(timer.remaining_seconds, 25 * 60);
+    }
+
+    #[test]
+    fn test_stopwatch_counts_up() {
+        let clock = Clock::frozen();
+        let mut timer = Timer::new(25, 5, 15);
+        timer.set_clock(clock.clone());
+        timer.set_named_timer(Some(0));
+        assert!(timer.is_counting_up());
+        timer.toggle_pause();
+
+        clock.advance(Duration::from_secs(3 * 3600));
+        timer.tick();
+        assert_eq!(timer.state, TimerState::Timer);
+        assert_eq!(timer.seconds_done(), 3 * 3600);
+        assert_eq!(timer.formatted_display_time(), "180:00");
+        assert_eq!(timer.progress_percent(), 0.0);
+    }
+
     #[test]
     fn test_full_reset() {
         let mut timer = Timer::new(25, 5, 15);
@@ -0,0 +1,233 @@
+//! Focus lock in tmux
+//!
+//! With `[tmux] focus_lock = true` and sandoro running inside tmux, the
+//! `focus` commands run when a work session starts and the `release`
+//! commands when it gives way to a break or ends, including when sandoro
+//! quits mid-session. The defaults dim the window's other panes, rename it
+//! to 🍅 and turn off activity alerts, then undo all three.
+//!
+//! Each entry is one tmux command, split like a shell would (quotes group
+//! words) and run as `tmux <command>`. `{pane}` is sandoro's pane
+//! ($TMUX_PANE), `{tag}` the session's tag (empty when untagged) and
+//! `{minutes}` the work length. They're filled in after splitting, so a tag
+//! with spaces or quotes stays within its word. Outside tmux nothing runs.
+
+use std::process::Command;
+
+use crate::config::TmuxConfig;
+
+/// What the command templates are filled in with
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Vars {
+    pub tag: Option<String>,
+    pub minutes: u32,
+}
+
+/// Runs the focus commands as work sessions start and stop
+#[derive(Debug)]
+pub struct FocusLock {
+    config: TmuxConfig,
+    pane: String,
+    /// The focus commands ran and haven't been released yet
+    focused: bool,
+}
+
+impl FocusLock {
+    /// None when it's off or sandoro isn't running inside tmux
+    pub fn new(config: &TmuxConfig) -> Option<Self> {
+        if !config.focus_lock || std::env::var_os("TMUX").is_none() {
+            return None;
+        }
+        Some(Self {
+            config: config.clone(),
+            pane: std::env::var("TMUX_PANE").unwrap_or_default(),
+            focused: false,
+        })
+    }
+
+    /// Call on every loop with whether a work session is on. Runs the focus
+    /// or release commands when that changes; Err names the first command
+    /// that failed (the rest still run).
+    pub fn update(&mut self, working: bool, vars: &Vars) -> Result<(), String> {
+        if working == self.focused {
+            return Ok(());
+        }
+        self.focused = working;
+        let commands = if working {
+            &self.config.focus
+        } else {
+            &self.config.release
+        };
+        let mut result = Ok(());
+        for template in commands {
+            if let Err(err) = expand(template, &self.pane, vars).and_then(|args| run(&args)) {
+                if result.is_ok() {
+                    result = Err(format!("`{}`: {}", template, err));
+                }
+            }
+        }
+        result
+    }
+
+    /// Undo the focus commands if they're in effect, e.g. when quitting
+    pub fn release(&mut self) {
+        let _ = self.update(false, &Vars::default());
+    }
+}
+
+/// Split `template` into words, then fill in `{pane}`, `{tag}` and
+/// `{minutes}` in each
+fn expand(template: &str, pane: &str, vars: &Vars) -> Result<Vec<String>, String> {
+    let minutes = vars.minutes.to_string();
+    let values = [
+        ("{pane}", pane),
+        ("{tag}", vars.tag.as_deref().unwrap_or("")),
+        ("{minutes}", minutes.as_str()),
+    ];
+    Ok(split_args(template)?
+        .iter()
+        .map(|arg| fill(arg, &values))
+        .collect())
+}
+
+/// Replace each placeholder in one pass, so a value that looks like a
+/// placeholder stays as it is
+fn fill(arg: &str, values: &[(&str, &str)]) -> String {
+    let mut out = String::new();
+    let mut rest = arg;
+    while !rest.is_empty() {
+        match values.iter().find(|(key, _)| rest.starts_with(key)) {
+            Some((key, value)) => {
+                out.push_str(value);
+                rest = &rest[key.len()..];
+            }
+            None => {
+                let c = rest.chars().next().unwrap_or_default();
+                out.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    out
+}
+
+/// Split a command into words: whitespace separates, single or double
+/// quotes group, a backslash escapes the next character
+fn split_args(command: &str) -> Result<Vec<String>, String> {
+    let mut args = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut quote: Option<char> = None;
+    let mut chars = command.chars();
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (None, '\'' | '"') => {
+                quote = Some(c);
+                in_word = true;
+            }
+            (q, '\\') if q != Some('\'') => {
+                word.push(chars.next().ok_or("ends with a backslash")?);
+                in_word = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    args.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            (_, c) => {
+                word.push(c);
+                in_word = true;
+            }
+        }
+    }
+    if quote.is_some() {
+        return Err("unclosed quote".to_string());
+    }
+    if in_word {
+        args.push(word);
+    }
+    if args.is_empty() {
+        return Err("empty command".to_string());
+    }
+    Ok(args)
+}
+
+fn run(args: &[String]) -> Result<(), String> {
+    let output = Command::new("tmux")
+        .args(args)
+        .output()
+        .map_err(|err| format!("can't run tmux: {}", err))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Err(stderr.trim().to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_templates() {
+        let vars = Vars {
+            tag: Some("writing".to_string()),
+            minutes: 50,
+        };
+        assert_eq!(
+            expand("rename-window -t {pane} '🍅 {tag} {minutes}m'", "%3", &vars).unwrap(),
+            vec!["rename-window", "-t", "%3", "🍅 writing 50m"]
+        );
+        assert_eq!(
+            expand("rename-window {tag}", "", &Vars::default()).unwrap(),
+            vec!["rename-window", ""]
+        );
+        // A tag is one word whatever it holds, and isn't expanded itself
+        let odd = Vars {
+            tag: Some("it's {pane} \\ \"x\"".to_string()),
+            minutes: 25,
+        };
+        assert_eq!(
+            expand("rename-window {tag}", "%3", &odd).unwrap(),
+            vec!["rename-window", "it's {pane} \\ \"x\""]
+        );
+    }
+
+    #[test]
+    fn test_split_args() {
+        assert_eq!(
+            split_args("setw -t %3  window-style 'fg=colour244,bg=black'").unwrap(),
+            vec!["setw", "-t", "%3", "window-style", "fg=colour244,bg=black"]
+        );
+        assert_eq!(
+            split_args(r#"rename-window "🍅 deep work" a\ b ''"#).unwrap(),
+            vec!["rename-window", "🍅 deep work", "a b", ""]
+        );
+        assert!(split_args("rename-window 'oops").is_err());
+        assert!(split_args("   ").is_err());
+    }
+
+    #[test]
+    fn test_only_runs_on_a_change() {
+        // Commands that would fail if they ran
+        let config = TmuxConfig {
+            focus_lock: true,
+            focus: vec!["'unclosed".to_string()],
+            release: vec![],
+        };
+        let mut lock = FocusLock {
+            config,
+            pane: "%1".to_string(),
+            focused: false,
+        };
+        assert!(lock.update(false, &Vars::default()).is_ok());
+        let err = lock.update(true, &Vars::default()).unwrap_err();
+        assert_eq!(err, "`'unclosed`: unclosed quote");
+        // Still focused: nothing runs again until the session stops
+        assert!(lock.update(true, &Vars::default()).is_ok());
+        assert!(lock.update(false, &Vars::default()).is_ok());
+    }
+}
@@ -0,0 +1,1736 @@
+//! TUI test harness
+//!
+//! Drives the real `App` and `ui::draw` on ratatui's TestBackend with
+//! scripted key presses, so whole flows can be asserted without a terminal.
+//! The app runs on an in-memory database and never writes config.toml,
+//! syncs or plays sounds.
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::{backend::TestBackend, Terminal};
+
+use crate::app::{App, AppView};
+use crate::config::Config;
+use crate::db::Database;
+use crate::ui;
+
+const WIDTH: u16 = 90;
+const HEIGHT: u16 = 32;
+
+pub struct Harness {
+    pub app: App,
+    terminal: Terminal<TestBackend>,
+}
+
+impl Harness {
+    pub fn new() -> Self {
+        Self::with_config(Config::default())
+    }
+
+    /// Default config with these tags already in the database
+    pub fn with_tags(tags: &[&str]) -> Self {
+        let db = Database::open_in_memory().expect("in-memory database");
+        for tag in tags {
+            db.create_tag(tag, None).expect("create tag");
+        }
+        Self::with_db(db)
+    }
+
+    /// Default config on a database prepared by the test
+    pub fn with_db(db: Database) -> Self {
+        Self::build(Config::default(), db)
+    }
+
+    /// A config and a database both prepared by the test
+    pub fn with_config_and_db(config: Config, db: Database) -> Self {
+        Self::build(config, db)
+    }
+
+    pub fn with_config(config: Config) -> Self {
+        Self::build(
+            config,
+            Database::open_in_memory().expect("in-memory database"),
+        )
+    }
+
+    /// The app as `sandoro --ephemeral` runs it
+    pub fn ephemeral() -> Self {
+        let app = App::ephemeral(Self::quiet(Config::default())).expect("ephemeral app");
+        Self::from_app(app)
+    }
+
+    /// The app as `sandoro demo` runs it
+    pub fn demo(demo: crate::demo::Demo) -> Self {
+        let app = App::demo(Self::quiet(Config::default()), demo).expect("demo app");
+        Self::from_app(app)
+    }
+
+    fn build(config: Config, db: Database) -> Self {
+        Self::from_app(App::headless(Self::quiet(config), db))
+    }
+
+    /// No sounds or notifications, English text
+    fn quiet(mut config: Config) -> Config {
+        config.notifications.sound = false;
+        config.notifications.desktop = false;
+        config.appearance.language = "en".to_string();
+        config
+    }
+
+    fn from_app(app: App) -> Self {
+        let terminal = Terminal::new(TestBackend::new(WIDTH, HEIGHT)).expect("test terminal");
+        let mut harness = Self { app, terminal };
+        harness.draw();
+        harness
+    }
+
+    /// Resize the terminal and redraw
+    pub fn resize(&mut self, width: u16, height: u16) -> &mut Self {
+        self.terminal.backend_mut().resize(width, height);
+        self.draw();
+        self
+    }
+
+    /// Redraw after changing app state directly
+    pub fn draw(&mut self) {
+        self.terminal
+            .draw(|f| ui::draw(f, &self.app))
+            .expect("draw");
+    }
+
+    /// Press a key, then tick and redraw like the main loop does
+    pub fn press(&mut self, code: KeyCode) -> &mut Self {
+        self.app.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+        self.app.tick();
+        self.draw();
+        self
+    }
+
+    /// Press each character in turn, e.g. `keys("tt ")`
+    pub fn keys(&mut self, keys: &str) -> &mut Self {
+        for c in keys.chars() {
+            self.press(KeyCode::Char(c));
+        }
+        self
+    }
+
+    /// Run the current timer phase to the end without waiting for it
+    pub fn finish_phase(&mut self) -> &mut Self {
+        assert!(
+            !self.app.timer.is_paused,
+            "start the timer before finishing a phase"
+        );
+        self.app.timer.remaining_seconds = 0;
+        self.app.tick();
+        self.draw();
+        self
+    }
+
+    pub fn db(&self) -> &Database {
+        self.app.database().expect("harness always has a database")
+    }
+
+    /// The rendered screen as text, one line per row
+    pub fn screen(&self) -> String {
+        let buffer = self.terminal.backend().buffer();
+        buffer
+            .content()
+            .chunks(buffer.area.width as usize)
+            .map(|row| row.iter().map(|cell| cell.symbol()).collect::<String>())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Foreground color of the first cell of `text` on screen, searching
+    /// row by row
+    pub fn color_of(&self, text: &str) -> Option<ratatui::style::Color> {
+        let buffer = self.terminal.backend().buffer();
+        buffer
+            .content()
+            .chunks(buffer.area.width as usize)
+            .find_map(|row| {
+                (0..row.len()).find_map(|start| {
+                    let rest: String = row[start..].iter().map(|cell| cell.symbol()).collect();
+                    rest.starts_with(text).then(|| row[start].fg)
+                })
+            })
+    }
+
+    #[track_caller]
+    pub fn assert_screen_contains(&self, text: &str) -> &Self {
+        let screen = self.screen();
+        assert!(
+            screen.contains(text),
+            "expected {:?} on screen:\n{}",
+            text,
+            screen
+        );
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::app::SettingsItem;
+    use crate::config::{AmbientSound, AnimationSpeed, FocusMode, Route, StrictMode};
+    use crate::db::{SessionMode, SessionType, TagDefaults, TaskStatus};
+    use crate::icons::IconType;
+    use crate::plan;
+    use crate::terminal::Progress;
+    use crate::timer::TimerState;
+    use chrono::{Duration, Local};
+
+    #[test]
+    fn test_change_theme_in_settings() {
+        let mut h = Harness::new();
+        let before = h.app.config.appearance.theme.clone();
+
+        // Settings opens on Theme: edit it, move to the next theme, apply
+        h.press(KeyCode::Tab)
+            .press(KeyCode::Enter)
+            .press(KeyCode::Down)
+            .press(KeyCode::Enter);
+
+        let theme = h.app.config.appearance.theme.clone();
+        assert_ne!(theme, before);
+        assert_eq!(h.app.theme.name, theme);
+        h.assert_screen_contains(&theme);
+
+        h.press(KeyCode::Tab);
+        assert_eq!(h.app.view, AppView::Timer);
+        h.assert_screen_contains("WORKING");
+    }
+
+    #[test]
+    fn test_animation_speed_changes_live_and_off_holds_still() {
+        let mut h = Harness::new();
+        h.press(KeyCode::Char(' '));
+
+        // Fast from the settings screen: a new frame every other tick
+        h.press(KeyCode::Tab)
+            .press(KeyCode::Down)
+            .press(KeyCode::Down)
+            .press(KeyCode::Down)
+            .press(KeyCode::Enter);
+        assert_eq!(
+            h.app.config.appearance.animation_speed,
+            AnimationSpeed::Fast
+        );
+        h.assert_screen_contains("Animation Speed");
+        h.press(KeyCode::Tab);
+        let frame = h.app.animation_frame;
+        h.app.tick();
+        h.app.tick();
+        assert_ne!(h.app.animation_frame, frame);
+
+        // Off: the icon stays on its first frame while the timer runs
+        h.press(KeyCode::Tab)
+            .press(KeyCode::Enter)
+            .press(KeyCode::Tab);
+        assert_eq!(h.app.config.appearance.animation_speed, AnimationSpeed::Off);
+        for _ in 0..20 {
+            h.app.tick();
+            assert_eq!(h.app.animation_frame, 0);
+        }
+        assert!(!h.app.timer.is_paused);
+    }
+
+    #[test]
+    fn test_rainbow_palette_and_speed_from_settings() {
+        let mut h = Harness::new();
+        h.press(KeyCode::Tab);
+        let palette = SettingsItem::all()
+            .iter()
+            .position(|item| *item == SettingsItem::RainbowPalette)
+            .unwrap();
+        while h.app.settings_index < palette {
+            h.press(KeyCode::Down);
+        }
+
+        // Classic → pastel, previewed right away
+        h.press(KeyCode::Enter);
+        assert_eq!(h.app.config.appearance.rainbow.palette, "pastel");
+        h.assert_screen_contains("Rainbow Palette: pastel ██");
+
+        // 500 ms → 1000 ms: the color holds for ten ticks
+        h.press(KeyCode::Down).press(KeyCode::Enter);
+        assert_eq!(h.app.config.appearance.rainbow.speed_ms, 1000);
+        h.assert_screen_contains("Rainbow Speed: 1000 ms");
+        // The key press already ticked once
+        let frame = h.app.rainbow_frame;
+        for _ in 0..8 {
+            h.app.tick();
+        }
+        assert_eq!(h.app.rainbow_frame, frame);
+        h.app.tick();
+        assert_eq!(h.app.rainbow_frame, (frame + 1) % 6);
+    }
+
+    #[test]
+    fn test_notification_routes_edited_per_event() {
+        let mut h = Harness::new();
+        h.press(KeyCode::Tab);
+        let routing = SettingsItem::all()
+            .iter()
+            .position(|item| *item == SettingsItem::NotificationRouting)
+            .unwrap();
+        while h.app.settings_index < routing {
+            h.press(KeyCode::Down);
+        }
+        h.assert_screen_contains("Default");
+
+        // Break end: from sound + desktop to sound only, then desktop only
+        h.press(KeyCode::Enter)
+            .press(KeyCode::Down)
+            .press(KeyCode::Enter)
+            .press(KeyCode::Enter);
+        let routes = &h.app.config.notifications.routes;
+        assert_eq!(routes.break_end, Route::Desktop);
+        assert_eq!(routes.work_end, Route::Both);
+        h.assert_screen_contains("Notification Routing")
+            .assert_screen_contains("Desktop only");
+
+        // Esc closes the editor, not the settings screen
+        h.press(KeyCode::Esc);
+        assert_eq!(h.app.routes_editor, None);
+        assert_eq!(h.app.view, AppView::Settings);
+        h.assert_screen_contains("Custom");
+    }
+
+    #[test]
+    fn test_ambience_plays_only_while_working() {
+        let mut h = Harness::new();
+        h.press(KeyCode::Tab);
+        let ambience = SettingsItem::all()
+            .iter()
+            .position(|item| *item == SettingsItem::Ambience)
+            .unwrap();
+        while h.app.settings_index < ambience {
+            h.press(KeyCode::Down);
+        }
+        h.press(KeyCode::Enter);
+        assert_eq!(h.app.config.ambience.sound, AmbientSound::Ticking);
+        h.assert_screen_contains("Ticking clock");
+
+        // Volume goes up in steps of 10 while editing
+        h.press(KeyCode::Down)
+            .press(KeyCode::Enter)
+            .press(KeyCode::Up)
+            .press(KeyCode::Enter);
+        assert_eq!(h.app.config.ambience.volume, 40);
+        h.assert_screen_contains("40%");
+
+        h.press(KeyCode::Tab);
+        h.app.config.notifications.sound = true;
+        assert_eq!(h.app.ambience(), None);
+        h.press(KeyCode::Char(' '));
+        assert_eq!(h.app.ambience(), Some((AmbientSound::Ticking, 40)));
+        // Paused: silent
+        h.press(KeyCode::Char(' '));
+        assert_eq!(h.app.ambience(), None);
+        h.press(KeyCode::Char(' '));
+        h.app.config.notifications.sound = false;
+        assert_eq!(h.app.ambience(), None);
+    }
+
+    #[test]
+    fn test_do_not_disturb_follows_work_sessions() {
+        let mut h = Harness::new();
+        h.press(KeyCode::Tab);
+        let dnd = SettingsItem::all()
+            .iter()
+            .position(|item| *item == SettingsItem::DoNotDisturb)
+            .unwrap();
+        while h.app.settings_index < dnd {
+            h.press(KeyCode::Down);
+        }
+        assert!(!h.app.dnd_focus());
+        h.press(KeyCode::Enter);
+        assert!(h.app.config.dnd.enabled);
+        h.assert_screen_contains("Do Not Disturb");
+
+        h.press(KeyCode::Tab);
+        assert!(!h.app.dnd_focus());
+        h.press(KeyCode::Char(' '));
+        assert!(h.app.dnd_focus());
+        // Still on while paused, off on the break
+        h.press(KeyCode::Char(' '));
+        assert!(h.app.dnd_focus());
+        h.press(KeyCode::Char('s'));
+        assert!(!h.app.dnd_focus());
+    }
+
+    #[test]
+    fn test_interruptions_during_work() {
+        let mut h = Harness::new();
+        // Nothing to interrupt before work starts
+        h.press(KeyCode::Char('i'));
+        assert!(h.app.interruption_at.is_none());
+
+        h.press(KeyCode::Char(' ')).press(KeyCode::Char('i'));
+        h.assert_screen_contains("Interruption");
+        // The category key doesn't reach the timer
+        h.press(KeyCode::Char('s'));
+        assert!(h.app.interruption_at.is_none());
+        assert_eq!(h.app.timer.state, TimerState::Work);
+        h.press(KeyCode::Char('i')).press(KeyCode::Enter);
+        h.press(KeyCode::Char('i')).press(KeyCode::Esc);
+        assert_eq!(h.app.interruptions, 2);
+        // ⚡ is two cells wide on the test backend, so skip the cell it covers
+        let screen = h.screen();
+        let count = screen
+            .split('⚡')
+            .nth(1)
+            .and_then(|rest| rest.chars().nth(1));
+        assert_eq!(count, Some('2'));
+
+        h.finish_phase();
+        let id = h.db().get_recent_sessions(1).unwrap()[0].0.id;
+        let categories: Vec<_> = h
+            .db()
+            .get_session_interruptions(id)
+            .unwrap()
+            .iter()
+            .map(|i| i.category)
+            .collect();
+        assert_eq!(categories, vec![Some('s'), None]);
+
+        // The history is loaded at startup and after edits
+        h.app.refresh_recent_sessions();
+        h.press(KeyCode::Tab);
+        let delete = SettingsItem::all()
+            .iter()
+            .position(|item| *item == SettingsItem::DeleteSession)
+            .unwrap();
+        while h.app.settings_index < delete {
+            h.press(KeyCode::Down);
+        }
+        h.assert_screen_contains("25m ⚡");
+        assert_eq!(h.app.history_interruptions.get(&id), Some(&2));
+    }
+
+    #[test]
+    fn test_note_prompt_and_editing_a_note() {
+        let mut config = Config::default();
+        config.timer.note_prompt = true;
+        let mut h = Harness::with_config(config);
+        h.press(KeyCode::Char(' ')).finish_phase();
+        assert!(h.app.note_draft.is_some());
+        h.assert_screen_contains("Session note");
+        // Keys go to the note, not the timer
+        h.keys("fixed s bug").press(KeyCode::Enter);
+        assert!(h.app.note_draft.is_none());
+        let sessions = h.db().get_recent_sessions(1).unwrap();
+        assert_eq!(sessions[0].0.notes.as_deref(), Some("fixed s bug"));
+
+        h.press(KeyCode::Tab);
+        let edit = SettingsItem::all()
+            .iter()
+            .position(|item| *item == SettingsItem::EditSessionNote)
+            .unwrap();
+        while h.app.settings_index < edit {
+            h.press(KeyCode::Down);
+        }
+        h.assert_screen_contains("fixed s bug");
+        h.press(KeyCode::Enter).press(KeyCode::Enter);
+        assert_eq!(h.app.note_draft.as_ref().unwrap().text, "fixed s bug");
+        h.press(KeyCode::Backspace).press(KeyCode::Esc);
+        let sessions = h.db().get_recent_sessions(1).unwrap();
+        assert_eq!(sessions[0].0.notes.as_deref(), Some("fixed s bug"));
+    }
+
+    #[test]
+    fn test_routine_walks_through_its_blocks() {
+        let routines = toml::from_str(
+            r#"
+            [[morning]]
+            minutes = 25
+            tag = "writing"
+            repeat = 2
+
+            [[morning]]
+            kind = "break"
+            minutes = 5
+
+            [[morning]]
+            minutes = 50
+            tag = "email"
+            "#,
+        )
+        .unwrap();
+        let mut h = Harness::with_config(Config {
+            routines,
+            ..Default::default()
+        });
+
+        h.press(KeyCode::Char('o'));
+        h.assert_screen_contains("Routines")
+            .assert_screen_contains("2×25 writing, 5 break, 50 email");
+        h.press(KeyCode::Enter);
+        assert!(!h.app.timer.is_paused);
+        h.assert_screen_contains("Routine: morning 1/4")
+            .assert_screen_contains("Tag: writing");
+
+        // Each block starts as soon as the last one ends
+        h.finish_phase();
+        assert!(!h.app.timer.is_paused);
+        h.assert_screen_contains("Routine: morning 2/4");
+        h.finish_phase();
+        assert_eq!(h.app.timer.state, TimerState::ShortBreak);
+        assert_eq!(h.app.timer.remaining_seconds, 5 * 60);
+        h.finish_phase();
+        assert_eq!(h.app.timer.remaining_seconds, 50 * 60);
+        h.assert_screen_contains("Tag: email");
+
+        // After the last block the timer goes back to the settings
+        h.finish_phase();
+        assert!(h.app.routine.is_none());
+        assert!(h.app.timer.is_paused);
+        assert_eq!(h.app.timer.remaining_seconds, 25 * 60);
+        h.draw();
+        h.assert_screen_contains("Round: 1/4");
+
+        let sessions = h.db().get_recent_sessions(5).unwrap();
+        let recorded: Vec<(Option<i32>, Option<&str>)> = sessions
+            .iter()
+            .rev()
+            .map(|(s, tag)| (s.duration_seconds, tag.as_ref().map(|t| t.name.as_str())))
+            .collect();
+        assert_eq!(
+            recorded,
+            [
+                (Some(1500), Some("writing")),
+                (Some(1500), Some("writing")),
+                (Some(3000), Some("email")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tag_over_budget_is_flagged_while_working() {
+        let db = Database::open_in_memory().unwrap();
+        db.create_tag("meetings-prep", None).unwrap();
+        let mut config = Config::default();
+        config.budgets.insert("meetings-prep".to_string(), 20);
+        let mut h = Harness::with_config_and_db(config, db);
+
+        h.keys("t ");
+        assert!(h.app.budget_usage().is_some_and(|u| !u.is_over()));
+        // 21 minutes into the session
+        h.app.timer.remaining_seconds = 4 * 60;
+        h.app.tick();
+        h.draw();
+        h.assert_screen_contains("meetings-prep over budget: 21m of 20m today");
+    }
+
+    #[test]
+    fn test_complete_session_records_db_row() {
+        let mut h = Harness::with_tags(&["writing"]);
+        h.keys("t ").finish_phase();
+
+        assert_eq!(h.app.timer.state, TimerState::ShortBreak);
+        assert_eq!(h.app.today_sessions, 1);
+        h.assert_screen_contains("SHORT BREAK")
+            .assert_screen_contains("(1 sessions)");
+
+        let sessions = h.db().get_recent_sessions(5).unwrap();
+        assert_eq!(sessions.len(), 1);
+        let (session, tag) = &sessions[0];
+        assert!(session.completed);
+        assert_eq!(session.session_type, "work");
+        assert_eq!(session.duration_seconds, Some(25 * 60));
+        assert_eq!(tag.as_ref().map(|t| t.name.as_str()), Some("writing"));
+    }
+
+    #[test]
+    fn test_flowtime_work_records_the_time_it_ran() {
+        let mut config = Config::default();
+        config.focus.mode = FocusMode::Flowtime;
+        let mut h = Harness::with_config(config);
+        h.press(KeyCode::Char(' '));
+        // 42 minutes counted up, then ended
+        h.app.timer.elapsed_seconds = 42 * 60;
+        h.press(KeyCode::Char('s'));
+        assert_eq!(h.app.timer.state, TimerState::ShortBreak);
+        assert_eq!(h.app.today_sessions, 1);
+
+        let sessions = h.db().get_recent_sessions(5).unwrap();
+        let (session, _) = &sessions[0];
+        assert!(session.completed);
+        assert_eq!(session.duration_seconds, Some(42 * 60));
+        let flow = h.db().get_mode_stats(SessionMode::Flowtime, 7).unwrap();
+        assert_eq!(
+            (flow.total_work_seconds, flow.sessions_completed),
+            (42 * 60, 1)
+        );
+        let classic = h.db().get_mode_stats(SessionMode::Classic, 7).unwrap();
+        assert_eq!(classic.sessions_completed, 0);
+    }
+
+    #[test]
+    fn test_named_timer_records_timer_sessions() {
+        let mut config = Config::default();
+        config.focus.mode = FocusMode::Timer;
+        config.focus.timer = "meeting".to_string();
+        let mut h = Harness::with_config(config);
+        h.assert_screen_contains("TIMER · meeting");
+        h.assert_screen_contains("30:00");
+
+        // Runs out and starts over, paused, rather than going to a break
+        h.press(KeyCode::Char(' ')).finish_phase();
+        assert_eq!(h.app.timer.state, TimerState::Timer);
+        assert!(h.app.timer.is_paused);
+        assert_eq!(h.app.timer.remaining_seconds, 30 * 60);
+        assert_eq!(h.app.today_sessions, 1);
+
+        // Ending it early with `s` records the time it ran
+        h.press(KeyCode::Char(' '));
+        h.app.timer.remaining_seconds = 20 * 60;
+        h.press(KeyCode::Char('s'));
+        assert_eq!(h.app.today_sessions, 2);
+
+        let sessions = h.db().get_recent_sessions(5).unwrap();
+        let durations: Vec<_> = sessions
+            .iter()
+            .map(|(s, _)| (s.session_type.as_str(), s.completed, s.duration_seconds))
+            .collect();
+        assert!(durations.contains(&("timer", true, Some(30 * 60))));
+        assert!(durations.contains(&("timer", true, Some(10 * 60))));
+        assert_eq!(
+            h.db().get_today_stats().unwrap().total_work_seconds,
+            40 * 60
+        );
+    }
+
+    #[test]
+    fn test_tag_defaults_reconfigure_the_timer() {
+        let db = Database::open_in_memory().unwrap();
+        let writing = db.create_tag("writing", None).unwrap();
+        db.set_tag_defaults(
+            writing,
+            &TagDefaults {
+                work_minutes: Some(50),
+                break_minutes: Some(10),
+                icon: Some("coffee".to_string()),
+            },
+        )
+        .unwrap();
+        let mut h = Harness::with_db(db);
+
+        h.press(KeyCode::Char('t'));
+        h.assert_screen_contains("50:00");
+        assert_eq!(h.app.current_icon(), IconType::Coffee);
+        h.press(KeyCode::Char(' ')).finish_phase();
+        assert_eq!(h.app.timer.state, TimerState::ShortBreak);
+        assert_eq!(h.app.timer.remaining_seconds, 10 * 60);
+
+        // Back to no tag: the break under way keeps its time, work that
+        // follows takes the setting
+        h.press(KeyCode::Char(' ')).press(KeyCode::Char('t'));
+        assert_eq!(h.app.timer.remaining_seconds, 10 * 60);
+        assert_eq!(h.app.timer.work_duration, 25);
+        assert_eq!(h.app.current_icon(), IconType::Hourglass);
+    }
+
+    #[test]
+    fn test_edit_tag_defaults_in_settings() {
+        let mut h = Harness::with_tags(&["writing"]);
+        h.press(KeyCode::Char('t'));
+        h.press(KeyCode::Tab);
+        let tag_work = SettingsItem::all()
+            .iter()
+            .position(|item| *item == SettingsItem::TagWork)
+            .unwrap();
+        while h.app.settings_index < tag_work {
+            h.press(KeyCode::Down);
+        }
+        h.assert_screen_contains("Tag Work: -");
+
+        // Up from the setting, then one more step
+        h.press(KeyCode::Enter)
+            .press(KeyCode::Up)
+            .press(KeyCode::Up);
+        h.press(KeyCode::Enter);
+        h.assert_screen_contains("Tag Work: 30 min");
+        let tag = h.app.selected_tag().unwrap().id;
+        assert_eq!(h.db().get_tag_defaults(tag).unwrap().work_minutes, Some(30));
+        // The selected tag takes it straight away
+        assert_eq!(h.app.timer.remaining_seconds, 30 * 60);
+
+        // Icon cycles from the setting through the icons
+        h.press(KeyCode::Down)
+            .press(KeyCode::Down)
+            .press(KeyCode::Enter);
+        assert_eq!(h.app.current_icon(), IconType::None);
+        h.press(KeyCode::Enter);
+        assert_eq!(h.app.current_icon(), IconType::Progress);
+    }
+
+    #[test]
+    fn test_tag_color_in_settings_and_timer() {
+        use ratatui::style::Color;
+        let mut h = Harness::with_tags(&["writing"]);
+        h.press(KeyCode::Char('t'));
+        let tag = h.app.selected_tag().unwrap().id;
+        h.press(KeyCode::Tab);
+        let tag_color = SettingsItem::all()
+            .iter()
+            .position(|item| *item == SettingsItem::TagColor)
+            .unwrap();
+        while h.app.settings_index < tag_color {
+            h.press(KeyCode::Down);
+        }
+        h.assert_screen_contains("Tag Color: - ■");
+
+        // From none through the accents
+        h.press(KeyCode::Enter);
+        h.assert_screen_contains("Tag Color: red ■");
+        assert_eq!(
+            h.db().get_tag(tag).unwrap().unwrap().color.as_deref(),
+            Some("red")
+        );
+        h.press(KeyCode::Enter);
+        h.assert_screen_contains("Tag Color: orange ■");
+        let orange = crate::theme::ThemeColor::from_accent_name("orange").to_rgb();
+        let orange = Color::Rgb(orange.0, orange.1, orange.2);
+        assert_eq!(h.color_of("■"), Some(orange));
+
+        // The timer shows the tag in it
+        h.press(KeyCode::Esc);
+        assert_eq!(h.app.view, AppView::Timer);
+        h.assert_screen_contains("Tag: writing");
+        assert_eq!(h.color_of("writing"), Some(orange));
+    }
+
+    #[test]
+    fn test_state_accent_tints_the_whole_settings_frame() {
+        use ratatui::style::Color;
+
+        let mut config = Config::default();
+        config.appearance.state_accents.work = Some("red".to_string());
+        let mut h = Harness::with_config(config);
+        let red = crate::theme::ThemeColor::from_accent_name("red").to_rgb();
+        let red = Color::Rgb(red.0, red.1, red.2);
+        assert_eq!(h.color_of("└"), Some(red));
+
+        h.press(KeyCode::Tab);
+        assert_eq!(h.app.view, AppView::Settings);
+        // The header's corner, the list's sides and the footer's corner
+        assert_eq!(h.color_of("┌"), Some(red));
+        assert_eq!(h.color_of("│"), Some(red));
+        assert_eq!(h.color_of("└"), Some(red));
+    }
+
+    #[test]
+    fn test_new_tag_goes_to_the_tag_defaults() {
+        let mut h = Harness::with_tags(&["admin"]);
+        h.press(KeyCode::Tab);
+        let add_tag = SettingsItem::all()
+            .iter()
+            .position(|item| *item == SettingsItem::AddTag)
+            .unwrap();
+        while h.app.settings_index < add_tag {
+            h.press(KeyCode::Down);
+        }
+        h.press(KeyCode::Enter)
+            .keys("reading")
+            .press(KeyCode::Enter);
+        h.assert_screen_contains("Tag Defaults: reading");
+    }
+
+    #[test]
+    fn test_tag_cycling() {
+        let mut h = Harness::with_tags(&["deep", "admin"]);
+        h.assert_screen_contains("Tag: -");
+
+        // Tags are listed by name
+        h.press(KeyCode::Char('t'));
+        h.assert_screen_contains("Tag: admin");
+        h.press(KeyCode::Char('t'));
+        h.assert_screen_contains("Tag: deep");
+        h.press(KeyCode::Char('t'));
+        h.assert_screen_contains("Tag: -");
+    }
+
+    #[test]
+    fn test_recovery_prompt_completes_crashed_session() {
+        let db = Database::open_in_memory().unwrap();
+        let id = db
+            .start_session_with_tag(SessionType::Work, None, Some(25 * 60))
+            .unwrap();
+        let mut h = Harness::with_db(db);
+        h.app.load_recovery(None);
+        h.draw();
+        h.assert_screen_contains("Unfinished session")
+            .assert_screen_contains("[i] Interrupted  [d] Discard");
+
+        // Other keys are ignored while the prompt is up
+        h.press(KeyCode::Char(' '));
+        assert!(h.app.timer.is_paused);
+
+        h.press(KeyCode::Char('c'));
+        assert!(h.app.recovery.is_empty());
+        assert!(!h.screen().contains("Unfinished session"));
+        let detail = h.db().get_session_detail(id).unwrap().unwrap();
+        assert!(detail.session.completed);
+    }
+
+    #[test]
+    fn test_quit_interrupts_running_session() {
+        let mut h = Harness::new();
+        h.press(KeyCode::Char(' '));
+        let id = h.app.live_status().session_id.expect("session recording");
+        h.press(KeyCode::Char('q'));
+        h.app.interrupt_current_session();
+
+        let detail = h.db().get_session_detail(id).unwrap().unwrap();
+        assert!(!detail.session.completed);
+        assert!(detail.session.ended_at.is_some());
+        assert!(h.db().get_open_sessions().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_search_session_history() {
+        let db = Database::open_in_memory().unwrap();
+        for notes in ["billing refactor", "parser tests", "billing emails"] {
+            let id = db
+                .start_session_with_tag(SessionType::Work, None, Some(1500))
+                .unwrap();
+            db.complete_session(id, 1500).unwrap();
+            db.connection()
+                .execute(
+                    "UPDATE sessions SET notes = ?1 WHERE id = ?2",
+                    rusqlite::params![notes, id],
+                )
+                .unwrap();
+        }
+        let mut h = Harness::with_db(db);
+        h.app.refresh_recent_sessions();
+        h.press(KeyCode::Tab);
+        // Scroll down to the history the way a user would, so it's on screen
+        let history = SettingsItem::all()
+            .iter()
+            .position(|item| *item == SettingsItem::DeleteSession)
+            .unwrap();
+        while h.app.settings_index < history {
+            h.press(KeyCode::Down);
+        }
+
+        h.keys("/bil");
+        assert!(h.app.session_search_mode);
+        assert_eq!(h.app.recent_sessions.len(), 2);
+        h.assert_screen_contains("/bil|");
+
+        h.keys("ling r").press(KeyCode::Enter);
+        assert!(!h.app.session_search_mode);
+        assert_eq!(h.app.recent_sessions.len(), 1);
+        h.assert_screen_contains("(1 matching \"billing r\")");
+
+        h.keys("/x");
+        h.assert_screen_contains("(no matches)");
+        h.press(KeyCode::Esc);
+        assert!(h.app.session_search.is_empty());
+        assert_eq!(h.app.recent_sessions.len(), 3);
+        assert_eq!(h.app.view, AppView::Settings);
+    }
+
+    #[test]
+    fn test_merge_sessions_from_history() {
+        let mut h = Harness::new();
+        h.press(KeyCode::Char(' ')).finish_phase();
+        h.press(KeyCode::Char('s'));
+        h.press(KeyCode::Char(' ')).finish_phase();
+        assert_eq!(h.app.today_sessions, 2);
+        h.app.refresh_recent_sessions();
+
+        h.press(KeyCode::Tab);
+        let merge = SettingsItem::all()
+            .iter()
+            .position(|item| *item == SettingsItem::MergeSession)
+            .unwrap();
+        while h.app.settings_index < merge {
+            h.press(KeyCode::Down);
+        }
+        h.press(KeyCode::Enter);
+        h.assert_screen_contains("[↑↓ select, Enter merge]");
+        h.press(KeyCode::Enter);
+
+        assert_eq!(h.app.recent_sessions.len(), 1);
+        assert_eq!(h.app.recent_sessions[0].0.duration_seconds, Some(3000));
+        assert_eq!(h.app.today_sessions, 1);
+        assert_eq!(h.app.today_work_seconds, 3000);
+    }
+
+    #[test]
+    fn test_xp_is_awarded_and_shown_when_enabled() {
+        let mut h = Harness::new();
+        h.press(KeyCode::Char(' ')).finish_phase();
+        assert_eq!(h.app.xp, None);
+        assert!(!h.screen().contains("Lv 1"));
+
+        let mut config = Config::default();
+        config.xp.enabled = true;
+        config.goals.daily_sessions = 1;
+        let mut h = Harness::with_config(config);
+        h.assert_screen_contains("★ Lv 1 Seedling");
+        // 15 for 25 minutes, 2 for a one-day streak, 25 for the daily goal
+        h.press(KeyCode::Char(' ')).finish_phase();
+        assert_eq!(h.app.xp, Some(42));
+        // Taking the break in full
+        h.press(KeyCode::Char(' ')).finish_phase();
+        assert_eq!(h.app.xp, Some(47));
+        assert_eq!(h.db().xp_log().unwrap().len(), 4);
+    }
+
+    #[test]
+    fn test_ignored_break_end_escalates_until_work_starts() {
+        let mut config = Config::default();
+        config.escalation.provider = Some(crate::config::PushProvider::Ntfy);
+        config.escalation.topic = "sandoro-test".to_string();
+        let mut h = Harness::with_config(config);
+        h.press(KeyCode::Char(' ')).finish_phase();
+        // Nothing to escalate after work, only after a break
+        assert_eq!(
+            h.app.check_escalation(Local::now() + Duration::hours(1)),
+            None
+        );
+
+        h.press(KeyCode::Char(' ')).finish_phase();
+        let now = Local::now();
+        assert_eq!(h.app.check_escalation(now), None);
+        let first = h.app.check_escalation(now + Duration::minutes(6)).unwrap();
+        assert_eq!((first.step, first.minutes), (0, 5));
+        assert_eq!(h.app.check_escalation(now + Duration::minutes(7)), None);
+        let second = h.app.check_escalation(now + Duration::minutes(16)).unwrap();
+        assert_eq!(second.step, 1);
+
+        // Starting work ends it
+        h.press(KeyCode::Char(' '));
+        assert_eq!(h.app.check_escalation(now + Duration::minutes(31)), None);
+    }
+
+    #[test]
+    fn test_progress_cues_fire_once_per_threshold() {
+        let mut config = Config::default();
+        config.notifications.progress_cues = vec![25, 50, 75];
+        let mut h = Harness::with_config(config);
+        // Nothing until a work phase runs
+        assert_eq!(h.app.tick_progress_cues(), None);
+        h.press(KeyCode::Char(' '));
+        let total = h.app.timer.remaining_seconds;
+        let mut at = |percent: u32| {
+            h.app.timer.remaining_seconds = total - total * percent / 100;
+            h.app.tick_progress_cues()
+        };
+
+        assert_eq!(at(10), None);
+        assert_eq!(at(25), Some(0));
+        assert_eq!(at(30), None);
+        // Jumping past two thresholds plays only the higher one, once
+        assert_eq!(at(80), Some(2));
+        assert_eq!(at(95), None);
+    }
+
+    #[test]
+    fn test_progress_cues_start_over_with_the_next_session() {
+        let mut config = Config::default();
+        config.notifications.progress_cues = vec![50];
+        let mut h = Harness::with_config(config);
+        h.press(KeyCode::Char(' '));
+        let total = h.app.timer.remaining_seconds;
+        h.app.timer.remaining_seconds = total / 2;
+        assert_eq!(h.app.tick_progress_cues(), Some(0));
+        assert_eq!(h.app.tick_progress_cues(), None);
+
+        // Reset: the same session crosses the threshold again
+        h.app.timer.remaining_seconds = total;
+        assert_eq!(h.app.tick_progress_cues(), None);
+        h.app.timer.remaining_seconds = total / 2;
+        assert_eq!(h.app.tick_progress_cues(), Some(0));
+
+        // Through a break into the next work session
+        h.finish_phase();
+        assert_ne!(h.app.timer.state, TimerState::Work);
+        assert_eq!(h.app.tick_progress_cues(), None);
+        h.press(KeyCode::Char(' ')).finish_phase();
+        h.press(KeyCode::Char(' '));
+        assert_eq!(h.app.timer.state, TimerState::Work);
+        h.app.timer.remaining_seconds = total / 2;
+        assert_eq!(h.app.tick_progress_cues(), Some(0));
+    }
+
+    #[test]
+    fn test_goal_reminder_fires_once_while_the_goal_is_unmet() {
+        let mut config = Config::default();
+        config.goals.daily_sessions = 2;
+        config.goals.reminder = true;
+        let mut h = Harness::with_config(config);
+        let today = Local::now().date_naive();
+        let at = |hour| {
+            today
+                .and_hms_opt(hour, 0, 0)
+                .unwrap()
+                .and_local_timezone(Local)
+                .unwrap()
+        };
+        h.press(KeyCode::Char(' ')).finish_phase();
+        assert_eq!(h.app.check_goal_reminder(at(19)), None);
+        assert_eq!(
+            h.app.check_goal_reminder(at(20)).as_deref(),
+            Some("1 session")
+        );
+        assert_eq!(h.app.check_goal_reminder(at(21)), None);
+    }
+
+    #[test]
+    fn test_goal_reminder_stays_quiet_once_the_goal_is_met() {
+        let mut config = Config::default();
+        config.goals.daily_sessions = 1;
+        config.goals.reminder = true;
+        let mut h = Harness::with_config(config);
+        h.press(KeyCode::Char(' ')).finish_phase();
+        let late = Local::now()
+            .date_naive()
+            .and_hms_opt(23, 0, 0)
+            .unwrap()
+            .and_local_timezone(Local)
+            .unwrap();
+        assert_eq!(h.app.check_goal_reminder(late), None);
+    }
+
+    #[test]
+    fn test_routine_that_cannot_start_says_why() {
+        // Blocks of zero minutes are dropped, leaving nothing to run
+        let routines = toml::from_str(
+            r#"
+            [[idle]]
+            minutes = 0
+            "#,
+        )
+        .unwrap();
+        let mut h = Harness::with_config(Config {
+            routines,
+            ..Default::default()
+        });
+
+        h.press(KeyCode::Char('o')).press(KeyCode::Enter);
+        assert!(h.app.routine.is_none());
+        assert!(h.app.timer.is_paused);
+        h.assert_screen_contains("⚠ Routine 'idle' has no blocks to run.");
+    }
+
+    #[test]
+    fn test_journal_prompt_asks_once_for_todays_note() {
+        let mut config = Config::default();
+        config.goals.journal_prompt = true;
+        config.goals.reminder_time = "23:59".to_string();
+        let mut h = Harness::with_config(config);
+        let at = |hour, minute| {
+            Local::now()
+                .date_naive()
+                .and_hms_opt(hour, minute, 0)
+                .unwrap()
+                .and_local_timezone(Local)
+                .unwrap()
+        };
+        assert!(!h.app.check_journal_prompt(at(23, 0)));
+        assert!(h.app.check_journal_prompt(at(23, 59)));
+        h.draw();
+        h.assert_screen_contains("Journal");
+        h.keys("shipped the parser").press(KeyCode::Enter);
+        assert!(h.app.note_draft.is_none());
+        let today = h.db().today_str().unwrap();
+        assert_eq!(
+            h.db().get_journal(&today).unwrap().as_deref(),
+            Some("shipped the parser")
+        );
+        // Once a day
+        assert!(!h.app.check_journal_prompt(at(23, 59)));
+    }
+
+    #[test]
+    fn test_week_plan_shows_planned_against_done() {
+        let db = Database::open_in_memory().unwrap();
+        db.create_tag("writing", None).unwrap();
+        let week = plan::week_of(&Config::default(), db.today().unwrap(), 0);
+        plan::set(&db, week, "writing", 4).unwrap();
+        let mut h = Harness::with_db(db);
+        h.resize(90, 40);
+        h.assert_screen_contains("🗓 Plan  writing 0/4");
+
+        h.keys("t ").finish_phase();
+        h.assert_screen_contains("🗓 Plan  writing 1/4");
+    }
+
+    #[test]
+    fn test_private_mode_hides_tags_until_unlocked() {
+        let db = Database::open_in_memory().unwrap();
+        db.create_tag("client-x", None).unwrap();
+        let week = plan::week_of(&Config::default(), db.today().unwrap(), 0);
+        plan::set(&db, week, "client-x", 4).unwrap();
+        let mut h = Harness::with_db(db);
+        h.resize(90, 40);
+        h.press(KeyCode::Char('t'));
+        h.assert_screen_contains("Tag: client-x");
+
+        h.press(KeyCode::Char('p'));
+        assert!(h.app.locked);
+        assert!(!h.screen().contains("client-x"));
+        h.assert_screen_contains("Tag: *****");
+        h.assert_screen_contains("🗓 Plan  0/4");
+        h.assert_screen_contains("type the unlock sequence");
+        // Settings (and the history in them) stay closed; Space still works
+        h.press(KeyCode::Tab).press(KeyCode::Char(' '));
+        assert_eq!(h.app.view, AppView::Timer);
+        assert!(!h.app.timer.is_paused);
+        assert_eq!(h.app.live_status().tag.as_deref(), Some("*****"));
+
+        h.keys("unlok");
+        assert!(h.app.locked);
+        h.press(KeyCode::Esc).keys("unlock");
+        assert!(!h.app.locked);
+        h.assert_screen_contains("Tag: client-x");
+    }
+
+    #[test]
+    fn test_session_handed_over_between_devices() {
+        use crate::handoff::{Adoption, Device};
+
+        // The laptop picks up the desktop's session with 10 minutes left
+        let mut laptop = Harness::new();
+        laptop.app.adopt_remote(&Adoption {
+            remaining_seconds: 600,
+            work_minutes: 30,
+            tag: Some("writing".to_string()),
+            from_device: Device {
+                id: "desktop".to_string(),
+                name: "desktop-host".to_string(),
+            },
+        });
+        assert!(!laptop.app.timer.is_paused);
+        assert_eq!(laptop.app.timer.remaining_seconds, 600);
+        let beat = laptop.app.handoff_beat().expect("work is running");
+        assert_eq!(
+            (beat.work_minutes, beat.tag.as_deref()),
+            (30, Some("writing"))
+        );
+        laptop.finish_phase();
+        let (session, tag) = laptop.db().get_recent_sessions(1).unwrap().remove(0);
+        assert!(session.completed);
+        assert_eq!(tag.unwrap().name, "writing");
+        let events = laptop.db().get_session_events(session.id).unwrap();
+        assert!(events.iter().any(
+            |e| e.event == "resumed_remote" && e.detail.as_deref() == Some("from desktop-host")
+        ));
+
+        // The desktop drops its copy without counting it
+        let mut desktop = Harness::new();
+        desktop.press(KeyCode::Char(' '));
+        assert!(desktop.app.handoff_beat().is_some());
+        let id = desktop.app.live_status().session_id.unwrap();
+        desktop.app.handed_off("laptop-host");
+        assert!(desktop.app.timer.is_paused);
+        assert_eq!(desktop.app.handoff_beat(), None);
+        assert!(desktop.db().get_recent_sessions(1).unwrap().is_empty());
+        let events = desktop.db().get_session_events(id).unwrap();
+        assert!(events.iter().any(|e| e.event == "handed_off"));
+        assert_eq!(
+            desktop.db().get_today_stats().unwrap().sessions_completed,
+            0
+        );
+        desktop.resize(90, 40);
+        desktop.assert_screen_contains("Continued on laptop-host");
+
+        // Starting again here clears the notice
+        desktop.press(KeyCode::Char(' '));
+        assert_eq!(desktop.app.handoff_notice, None);
+    }
+
+    #[test]
+    fn test_skipping_break_shows_penalty() {
+        let mut config = Config::default();
+        config.accountability.skip_penalty_minutes = 10;
+        let mut h = Harness::with_config(config);
+        h.press(KeyCode::Char(' ')).finish_phase();
+        assert_eq!(h.app.timer.state, TimerState::ShortBreak);
+
+        // The break hasn't been started; skipping it still counts
+        h.press(KeyCode::Char('s'));
+        assert_eq!(h.app.timer.state, TimerState::Work);
+        let penalty = h.app.skip_penalty.expect("penalty enabled");
+        assert_eq!(penalty.skipped, 1);
+        h.assert_screen_contains("1 break skipped today · quality focus 15m");
+    }
+
+    #[test]
+    fn test_work_length_tip_until_work_starts() {
+        let db = Database::open_in_memory().unwrap();
+        for _ in 0..5 {
+            let id = db
+                .start_session_with_tag(SessionType::Work, None, Some(1500))
+                .unwrap();
+            db.complete_session(id, 1500).unwrap();
+        }
+        let mut config = Config::default();
+        config.timer.suggest_work_length = true;
+        let mut h = Harness::build(config, db);
+        h.assert_screen_contains("You finish 100% of 25m sessions — try 30m");
+
+        h.press(KeyCode::Char(' ')).press(KeyCode::Char(' '));
+        assert!(h.app.timer.is_paused);
+        assert_eq!(h.app.work_length_tip, None);
+        assert!(!h.screen().contains("try 30m"));
+    }
+
+    #[test]
+    fn test_capacity_warning_until_work_starts() {
+        let db = Database::open_in_memory().unwrap();
+        let today = db.today().unwrap();
+        for week in 1..=4 {
+            let day = (today - chrono::Duration::weeks(week))
+                .format("%Y-%m-%d")
+                .to_string();
+            let id = db
+                .start_session_with_tag(SessionType::Work, None, None)
+                .unwrap();
+            db.complete_session(id, 1500).unwrap();
+            db.connection()
+                .execute(
+                    "UPDATE sessions SET started_at = ?1, day = ?2 WHERE id = ?3",
+                    rusqlite::params![format!("{}T09:00:00+00:00", day), day, id],
+                )
+                .unwrap();
+        }
+        let mut config = Config::default();
+        config.goals.daily_sessions = 4;
+        let mut h = Harness::build(config, db);
+        h.assert_screen_contains("You've never done 4 sessions on a");
+
+        h.press(KeyCode::Char(' ')).press(KeyCode::Char(' '));
+        assert_eq!(h.app.capacity_warning, None);
+        assert!(!h.screen().contains("You've never done"));
+    }
+
+    #[test]
+    fn test_quit_mid_session_offers_resume() {
+        let mut h = Harness::with_tags(&["writing"]);
+        h.keys("t ");
+        h.app.timer.remaining_seconds = 600;
+        let id = h.app.live_status().session_id.expect("work being recorded");
+        h.app.save_session_on_exit();
+        assert_eq!(h.app.live_status().session_id, None);
+        assert_eq!(h.db().get_open_sessions().unwrap().len(), 0);
+
+        // Next launch: a fresh timer and the question
+        h.app.timer.full_reset();
+        h.app.selected_tag_index = None;
+        h.app.load_saved_session();
+        h.draw();
+        h.assert_screen_contains("Resume previous session?");
+        h.assert_screen_contains("Work · writing");
+        h.assert_screen_contains("10m left");
+
+        h.press(KeyCode::Char('r'));
+        assert_eq!(h.app.saved_session, None);
+        assert_eq!(h.app.timer.remaining_seconds, 600);
+        assert!(h.app.timer.is_paused);
+        assert_eq!(h.app.selected_tag().unwrap().name, "writing");
+        assert_eq!(h.app.live_status().session_id, Some(id));
+        let events = h.db().get_session_events(id).unwrap();
+        assert_eq!(events.last().unwrap().event, "resumed");
+
+        // Offered once
+        h.app.load_saved_session();
+        assert_eq!(h.app.saved_session, None);
+
+        // The time away counts as paused
+        let detail = h.db().get_session_detail(id).unwrap().unwrap();
+        assert!(detail.paused_at.is_some());
+        h.press(KeyCode::Char(' '));
+        let detail = h.db().get_session_detail(id).unwrap().unwrap();
+        assert_eq!(detail.paused_at, None);
+        let events = h.db().get_session_events(id).unwrap();
+        assert_eq!(events.last().unwrap().event, "unpaused");
+    }
+
+    #[test]
+    fn test_pausing_is_recorded_on_the_session() {
+        let mut h = Harness::new();
+        h.press(KeyCode::Char(' '));
+        let id = h.app.live_status().session_id.expect("work being recorded");
+        h.press(KeyCode::Char(' '));
+        let detail = h.db().get_session_detail(id).unwrap().unwrap();
+        assert!(detail.paused_at.is_some());
+        h.press(KeyCode::Char(' '));
+        h.finish_phase();
+
+        let detail = h.db().get_session_detail(id).unwrap().unwrap();
+        assert!(detail.session.completed);
+        assert_eq!(detail.paused_at, None);
+        let events: Vec<String> = h
+            .db()
+            .get_session_events(id)
+            .unwrap()
+            .into_iter()
+            .map(|e| e.event)
+            .collect();
+        assert_eq!(events, ["started", "paused", "unpaused", "completed"]);
+    }
+
+    #[test]
+    fn test_fresh_timer_saves_nothing_on_quit() {
+        let mut h = Harness::new();
+        h.app.save_session_on_exit();
+        h.app.load_saved_session();
+        assert_eq!(h.app.saved_session, None);
+
+        // Declining starts fresh and leaves the session interrupted
+        h.press(KeyCode::Char(' '));
+        h.app.timer.remaining_seconds = 600;
+        h.app.save_session_on_exit();
+        h.app.timer.full_reset();
+        h.app.load_saved_session();
+        assert!(h.app.saved_session.is_some());
+        h.press(KeyCode::Char('n'));
+        assert_eq!(h.app.saved_session, None);
+        assert_eq!(h.app.live_status().session_id, None);
+        assert_eq!(h.app.timer.remaining_seconds, 25 * 60);
+    }
+
+    #[test]
+    fn test_last_tag_restored_on_startup() {
+        let mut h = Harness::with_tags(&["reading", "writing"]);
+        h.keys("tt");
+        let writing = h.app.selected_tag().unwrap().id;
+        assert_eq!(
+            h.db().get_metadata("last_tag_id").unwrap(),
+            Some(writing.to_string())
+        );
+
+        let launch = |remember_tag: bool| {
+            let db = Database::open_in_memory().unwrap();
+            db.create_tag("reading", None).unwrap();
+            db.create_tag("writing", None).unwrap();
+            db.set_metadata("last_tag_id", &writing.to_string())
+                .unwrap();
+            let mut config = Config::default();
+            config.focus.remember_tag = remember_tag;
+            Harness::build(config, db)
+        };
+        let h = launch(true);
+        assert_eq!(
+            h.app.selected_tag().map(|t| t.name.as_str()),
+            Some("writing")
+        );
+        h.assert_screen_contains("writing");
+        assert_eq!(launch(false).app.selected_tag_index, None);
+    }
+
+    #[test]
+    fn test_board_task_follows_sessions() {
+        let mut h = Harness::new();
+        h.press(KeyCode::Char('b'))
+            .assert_screen_contains("[a] Add a task");
+        h.keys("aWrite report").press(KeyCode::Enter);
+        h.assert_screen_contains("Planned (1)")
+            .assert_screen_contains("· Write repo…");
+
+        // Estimate one session and bind the task to the timer
+        h.press(KeyCode::Char('+')).press(KeyCode::Enter);
+        h.assert_screen_contains("▶ 0/1 Write")
+            .assert_screen_contains("▶ Write report");
+
+        h.press(KeyCode::Char(' '));
+        h.assert_screen_contains("Doing (1)");
+        h.finish_phase();
+        h.assert_screen_contains("Done (1)")
+            .assert_screen_contains("· 1/1 Write");
+        assert_eq!(h.app.bound_task, None);
+
+        let task = &h.db().get_board_tasks().unwrap()[0];
+        assert_eq!(task.status, TaskStatus::Done);
+
+        h.press(KeyCode::Char('b'));
+        assert!(!h.screen().contains("Write report"));
+    }
+
+    #[test]
+    fn test_goal_bar_split_by_tag() {
+        let db = Database::open_in_memory().unwrap();
+        let writing = db.create_tag("writing", Some("#ff8000")).unwrap();
+        for tag in [Some(writing), Some(writing), None] {
+            let id = db
+                .start_session_with_tag(SessionType::Work, tag, Some(1500))
+                .unwrap();
+            db.complete_session(id, 1500).unwrap();
+        }
+        let mut config = Config::default();
+        config.goals.daily_minutes = 150;
+        let h = Harness::build(config, db);
+
+        // 75 of 150 minutes: 10 of 20 cells, two thirds of them writing
+        h.assert_screen_contains("Goal ██████████░░░░░░░░░░ 75/150m  ■ writing  ■ No tag");
+        let row = h
+            .screen()
+            .lines()
+            .position(|line| line.contains("Goal "))
+            .unwrap();
+        let buffer = h.terminal.backend().buffer();
+        let orange = (0..buffer.area.width)
+            .map(|x| &buffer[(x, row as u16)])
+            .filter(|cell| cell.symbol() != " ")
+            .filter(|cell| cell.fg == ratatui::style::Color::Rgb(255, 128, 0))
+            .count();
+        // Seven bar cells plus the legend square
+        assert_eq!(orange, 8);
+    }
+
+    #[test]
+    fn test_ephemeral_run_is_marked_and_kept_in_memory() {
+        let mut h = Harness::ephemeral();
+        h.assert_screen_contains("◌ ephemeral");
+
+        h.press(KeyCode::Char(' ')).finish_phase();
+        assert_eq!(h.db().get_today_stats().unwrap().sessions_completed, 1);
+
+        let plain = Harness::new();
+        assert!(!plain.screen().contains("ephemeral"));
+    }
+
+    #[test]
+    fn test_demo_runs_sped_up_on_made_up_history() {
+        let mut h = Harness::demo(crate::demo::Demo::new(60, true));
+        assert!(!h.app.timer.is_paused);
+        assert_eq!(h.app.timer.speed, 60);
+        assert!(h.db().get_streak().unwrap().current >= 8);
+        assert!(!h.screen().contains("ephemeral"));
+
+        // Finishing a phase keeps going on its own
+        h.finish_phase();
+        assert!(!h.app.timer.is_paused);
+        assert_ne!(h.app.timer.state, TimerState::Work);
+    }
+
+    #[test]
+    fn test_compact_mode_fits_three_lines() {
+        let mut h = Harness::with_tags(&["writing"]);
+        h.app.compact = true;
+        h.keys("t").draw();
+
+        let screen = h.screen();
+        let lines: Vec<&str> = screen.lines().collect();
+        assert!(lines[0].contains("WORKING") && lines[0].contains("25:00"));
+        assert!(lines[0].trim_end().ends_with("1/4"));
+        assert!(lines[1].contains("Today 0m · 0 sessions · writing"));
+        assert!(lines[2].contains("[Space] Pause"));
+        assert!(lines[3..].iter().all(|l| l.trim().is_empty()));
+        assert!(!screen.contains("sandoro v0.1.0"));
+
+        // Keys still work
+        h.press(KeyCode::Char(' '));
+        assert!(!h.app.timer.is_paused);
+        assert!(h.screen().contains("▶ WORKING"));
+    }
+
+    #[test]
+    fn test_short_terminal_switches_to_compact() {
+        let mut h = Harness::new();
+        h.assert_screen_contains("sandoro v0.1.0");
+        h.resize(60, 3);
+        h.assert_screen_contains("25:00");
+        assert!(!h.screen().contains("sandoro v0.1.0"));
+        h.resize(90, 32);
+        h.assert_screen_contains("sandoro v0.1.0");
+    }
+
+    #[test]
+    fn test_icon_shrinks_to_fit_the_terminal() {
+        let mut h = Harness::new();
+        h.resize(90, 40);
+        h.assert_screen_contains("╔══════════╗");
+        h.resize(90, 32);
+        h.assert_screen_contains("╔══════╗");
+        assert!(!h.screen().contains("╔══════════╗"));
+        h.resize(90, 20);
+        h.assert_screen_contains("╲▓▓▓╱");
+        // Too short for even the small hourglass
+        h.resize(90, 18);
+        h.assert_screen_contains("[░░░░░░░░░░░░░░░░░░░░]");
+        // Narrow terminals shrink it too
+        h.resize(16, 40);
+        h.assert_screen_contains("╔══════╗");
+    }
+
+    #[test]
+    fn test_snooze_from_outside_extends_or_reopens_the_break() {
+        let mut h = Harness::new();
+        assert!(h.app.snooze_break_by(Some(5)).is_err());
+
+        // Work done; snoozing the short break adds to it
+        h.press(KeyCode::Char(' ')).finish_phase();
+        assert_eq!(h.app.timer.state, TimerState::ShortBreak);
+        let reply = h.app.snooze_break_by(Some(5)).unwrap();
+        assert_eq!(reply, "Break extended by 5m, 10:00 left");
+
+        // The break ends; a snooze right after brings it back, running
+        h.press(KeyCode::Char(' ')).finish_phase();
+        assert_eq!(h.app.timer.state, TimerState::Work);
+        assert_eq!(h.app.snooze_break_by(None).unwrap(), "Break resumed for 5m");
+        assert_eq!(h.app.timer.state, TimerState::ShortBreak);
+        assert_eq!(h.app.timer.remaining_seconds, 300);
+        assert_eq!(h.app.timer.session_count, 1);
+        assert!(!h.app.timer.is_paused);
+
+        // Once work has started there's no break to snooze
+        h.finish_phase().press(KeyCode::Char(' '));
+        assert!(h.app.snooze_break_by(Some(5)).is_err());
+    }
+
+    #[test]
+    fn test_notification_buttons_act_on_the_timer() {
+        use crate::control::Command;
+        use crate::notification::Clicked;
+
+        let mut h = Harness::new();
+        let clicks = h.app.notification_clicks();
+        h.press(KeyCode::Char(' ')).finish_phase();
+        assert_eq!(h.app.timer.state, TimerState::ShortBreak);
+        assert!(h.app.timer.is_paused);
+
+        // "Start break" on the work-done notification
+        let click = |command| Clicked {
+            command,
+            phase: TimerState::ShortBreak,
+        };
+        clicks.send(click(Command::Start)).unwrap();
+        assert!(h.app.handle_notification_clicks());
+        assert!(!h.app.timer.is_paused);
+        clicks.send(click(Command::Snooze(Some(5)))).unwrap();
+        h.app.handle_notification_clicks();
+        assert_eq!(h.app.timer.remaining_seconds, 600);
+
+        // A late "Skip break" after work has started again does nothing
+        h.finish_phase().press(KeyCode::Char(' '));
+        assert_eq!(h.app.timer.state, TimerState::Work);
+        clicks.send(click(Command::Skip)).unwrap();
+        assert!(h.app.handle_notification_clicks());
+        assert_eq!(h.app.timer.state, TimerState::Work);
+        assert!(!h.app.handle_notification_clicks());
+    }
+
+    #[test]
+    fn test_break_and_work_now_from_outside() {
+        let mut config = Config::default();
+        config.accountability.skip_penalty_minutes = 10;
+        let mut h = Harness::with_config(config);
+        assert!(h.app.break_now().is_ok());
+        assert!(h.app.break_now().is_err());
+        assert_eq!(h.app.work_now().unwrap(), "Work started, 25:00 left");
+        let id = h.app.live_status().session_id.expect("session recording");
+        assert!(h.app.work_now().is_err());
+
+        // A meeting starts: the work block is kept as interrupted
+        assert_eq!(h.app.break_now().unwrap(), "Break started, 05:00 left");
+        assert_eq!(h.app.timer.state, TimerState::ShortBreak);
+        assert!(!h.app.timer.is_paused);
+        let detail = h.db().get_session_detail(id).unwrap().unwrap();
+        assert!(!detail.session.completed);
+        assert!(detail.session.ended_at.is_some());
+
+        // Back early: the break counts as skipped, and work runs
+        h.app.work_now().unwrap();
+        assert_eq!(h.app.timer.state, TimerState::Work);
+        assert!(!h.app.timer.is_paused);
+        assert_eq!(h.app.skip_penalty.expect("penalty enabled").skipped, 2);
+    }
+
+    #[test]
+    fn test_cycle_dots_on_screen_and_in_title() {
+        let mut config = Config::default();
+        config.appearance.terminal_title = true;
+        let mut h = Harness::with_config(config);
+        h.assert_screen_contains("Round: 1/4 ○○○○");
+        assert_eq!(h.app.terminal_title().unwrap(), "○○○○ WORKING · sandoro");
+
+        h.press(KeyCode::Char(' ')).finish_phase();
+        h.assert_screen_contains("Round: 1/4 ●○○○");
+        assert!(h.app.terminal_title().unwrap().starts_with("●○○○"));
+
+        h.app.config.appearance.terminal_title = false;
+        assert!(h.app.terminal_title().is_none());
+    }
+
+    #[test]
+    fn test_strict_confirm_needs_three_presses_in_a_row() {
+        let mut config = Config::default();
+        config.focus.strict = StrictMode::Confirm;
+        let mut h = Harness::with_config(config);
+        // Nothing to hold back before work starts
+        assert!(!h.app.strict_active());
+        h.press(KeyCode::Char(' '));
+        h.assert_screen_contains("⛓ strict");
+        h.assert_screen_contains("[qqq] Quit");
+
+        h.keys("qq");
+        h.assert_screen_contains("press q once more to quit");
+        // Another key starts the count over
+        h.press(KeyCode::Char('t')).keys("qq");
+        assert!(!h.app.should_quit);
+        h.press(KeyCode::Char('q'));
+        assert!(h.app.should_quit);
+    }
+
+    #[test]
+    fn test_strict_lock_holds_keys_until_the_break() {
+        let mut config = Config::default();
+        config.focus.strict = StrictMode::Lock;
+        let mut h = Harness::with_config(config);
+        h.press(KeyCode::Char(' ')).keys("sssrrr");
+        assert_eq!(h.app.timer.state, TimerState::Work);
+        // Pausing a session under way doesn't get around it
+        h.app.timer.remaining_seconds -= 60;
+        h.press(KeyCode::Char(' ')).keys("qqq");
+        assert!(!h.app.should_quit);
+        h.assert_screen_contains("quit waits for the break");
+
+        h.press(KeyCode::Char(' ')).finish_phase();
+        assert!(!h.app.strict_active());
+        h.press(KeyCode::Char('s'));
+        assert_eq!(h.app.timer.state, TimerState::Work);
+    }
+
+    #[test]
+    fn test_remote_skip_is_refused_in_strict_mode() {
+        use crate::control::Command;
+
+        let mut config = Config::default();
+        config.focus.strict = StrictMode::Lock;
+        let mut h = Harness::with_config(config);
+        h.press(KeyCode::Char(' '));
+        // What the control socket (break now, the remote page) hands over
+        for command in [Command::Skip, Command::BreakNow] {
+            let err = h.app.handle_control(command).unwrap_err();
+            assert!(err.to_string().starts_with("Strict mode"));
+            assert_eq!(h.app.timer.state, TimerState::Work);
+        }
+        // On the break it goes through again
+        h.finish_phase();
+        assert!(h.app.handle_control(Command::Skip).is_ok());
+        assert_eq!(h.app.timer.state, TimerState::Work);
+    }
+
+    #[test]
+    fn test_strict_mode_holds_back_break_now_and_notification_skips() {
+        use crate::control::Command;
+        use crate::notification::Clicked;
+
+        let mut config = Config::default();
+        config.focus.strict = StrictMode::Confirm;
+        let mut h = Harness::with_config(config);
+        let clicks = h.app.notification_clicks();
+        h.press(KeyCode::Char(' '));
+        let err = h.app.handle_control(Command::BreakNow).unwrap_err();
+        assert!(err.to_string().contains("Strict mode"));
+        clicks
+            .send(Clicked {
+                command: Command::Skip,
+                phase: TimerState::Work,
+            })
+            .unwrap();
+        h.app.handle_notification_clicks();
+        assert_eq!(h.app.timer.state, TimerState::Work);
+        // Pausing and resuming aren't held back
+        assert!(h.app.handle_control(Command::Pause).is_ok());
+    }
+
+    #[test]
+    fn test_settings_show_the_project_config_in_use() {
+        let mut h = Harness::new();
+        h.press(KeyCode::Tab);
+        h.assert_screen_contains("Settings  · config.toml");
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(crate::config::PROJECT_FILE);
+        std::fs::write(&path, "tag = \"work\"\n").unwrap();
+        h.app.project = crate::config::ProjectConfig::find(dir.path()).unwrap();
+        h.draw();
+        h.assert_screen_contains("config.toml + ");
+        h.assert_screen_contains(".sandoro.toml");
+    }
+
+    #[test]
+    fn test_title_time_and_taskbar_progress() {
+        let mut config = Config::default();
+        config.appearance.terminal_title = true;
+        config.appearance.title_time = true;
+        config.appearance.taskbar_progress = true;
+        let mut h = Harness::with_config(config);
+        assert_eq!(
+            h.app.terminal_title().unwrap(),
+            "⏸ 25:00 · ○○○○ WORKING · sandoro"
+        );
+        // Nothing to show before the phase starts
+        assert_eq!(h.app.taskbar_progress(), None);
+
+        h.press(KeyCode::Char(' '));
+        h.app.timer.remaining_seconds = 15 * 60;
+        assert_eq!(
+            h.app.terminal_title().unwrap(),
+            "15:00 · ○○○○ WORKING · sandoro"
+        );
+        assert_eq!(h.app.taskbar_progress(), Some(Progress::Running(40)));
+        h.press(KeyCode::Char(' '));
+        assert_eq!(h.app.taskbar_progress(), Some(Progress::Paused(40)));
+
+        h.app.config.appearance.taskbar_progress = false;
+        assert_eq!(h.app.taskbar_progress(), None);
+    }
+
+    #[test]
+    fn test_quit_key() {
+        let mut h = Harness::new();
+        h.press(KeyCode::Char('q'));
+        assert!(h.app.should_quit);
+    }
+
+    #[test]
+    fn test_keys_wait_for_storage_without_blocking() {
+        use crate::app::{Loaded, Startup};
+        use crate::storage::Storage;
+
+        let config = Harness::quiet(Config::default());
+        let (app, startup) = App::deferred(config.clone());
+        let mut h = Harness::from_app(app);
+        assert!(h.screen().contains("loading"));
+
+        // Space is held back, not acted on or waited for
+        h.press(KeyCode::Char(' '));
+        assert!(h.app.is_loading());
+        assert!(h.app.timer.is_paused);
+
+        let db: Box<dyn Storage> = Box::new(Database::open_in_memory().unwrap());
+        let loaded = Loaded::load(Some(db.as_ref()), &config);
+        startup
+            .send(Startup {
+                db: Some(db),
+                loaded,
+            })
+            .unwrap();
+        h.app.tick();
+        h.draw();
+        assert!(!h.app.is_loading());
+        assert!(!h.screen().contains("loading"));
+        // The held-back press started the timer and its session
+        assert!(!h.app.timer.is_paused);
+        assert_eq!(h.db().get_open_sessions().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_ctrl_c_quits_while_loading() {
+        let (app, _startup) = App::deferred(Harness::quiet(Config::default()));
+        let mut h = Harness::from_app(app);
+        h.app
+            .handle_key(KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL));
+        assert!(h.app.should_quit);
+    }
+}
@@ -5,22 +5,536 @@ use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, List, ListItem, Paragraph},
+    widgets::{Block, Borders, Clear, List, ListItem, Paragraph},
     Frame,
 };
 
-use crate::app::{App, AppView, SettingsItem};
-use crate::icons::{IconState, IconType};
-use crate::messages::{get_context_message, Language, UserStats};
-use crate::theme::{get_rainbow_color, get_rainbow_gradient_color, ThemeColor};
+use crate::app::{
+    format_progress_cues, routes_summary, App, AppView, NoteDraft, NoteTarget, SettingsItem,
+};
+use crate::clock;
+use crate::config::{AnimationSpeed, NotifyEvent, StrictMode};
+use crate::db::{SavedSession, Tag, TaskStatus};
+use crate::i18n::{self, pad};
+use crate::icons::{IconSize, IconState, IconType};
+use crate::messages::{get_break_activity, get_context_message, Language, UserStats};
+use crate::privacy;
+use crate::routine;
+use crate::tag_bar;
+use crate::theme::ThemeColor;
 use crate::timer::TimerState;
+use crate::xp;
+
+/// Terminals shorter than this get the compact timer
+pub const COMPACT_HEIGHT: u16 = 12;
+
+/// Keys while private mode is on
+const LOCKED_KEYS: &str = "  🔒 Private · type the unlock sequence  [Space] Pause  [Ctrl+C] Quit";
+/// Timer keys while strict mode holds back quit, skip and reset
+const STRICT_CONFIRM_KEYS: &str =
+    "  [Space] Pause  [rrr] Reset  [sss] Skip  [Tab] Settings  [qqq] Quit";
+const STRICT_LOCK_KEYS: &str =
+    "  [Space] Pause  [Tab] Settings · quit, skip and reset wait for the break";
+
+/// `id` in the timer's language
+fn t(app: &App, id: &'static str) -> &'static str {
+    i18n::t_in(&app.config.appearance.language, id)
+}
+
+/// The template `id` in the timer's language, filled in with `args`
+fn fill(app: &App, id: &'static str, args: &[&dyn std::fmt::Display]) -> String {
+    i18n::fill_in(&app.config.appearance.language, id, args)
+}
+
+/// Tag names and notes as shown: masked in private mode
+fn shown(app: &App, text: &str) -> String {
+    privacy::mask(text, app.locked)
+}
+
+/// A tag's own color, or the one picked for it by id
+fn tag_color(tag: &Tag) -> Color {
+    let (r, g, b) = tag_bar::tag_rgb(tag);
+    Color::Rgb(r, g, b)
+}
+
+/// `text` with the last `part` of it in `color`, e.g. a tag name in a
+/// settings row
+fn with_colored(text: String, part: &str, color: Color) -> Line<'static> {
+    match text.rfind(part).filter(|_| !part.is_empty()) {
+        Some(at) => {
+            let end = at + part.len();
+            Line::from(vec![
+                Span::raw(text[..at].to_string()),
+                Span::styled(text[at..end].to_string(), Style::default().fg(color)),
+                Span::raw(text[end..].to_string()),
+            ])
+        }
+        None => Line::from(text),
+    }
+}
 
 /// Draw the main UI
 pub fn draw(f: &mut Frame, app: &App) {
     match app.view {
+        AppView::Timer if app.compact || f.area().height < COMPACT_HEIGHT => {
+            draw_compact_view(f, app)
+        }
         AppView::Timer => draw_timer_view(f, app),
         AppView::Settings => draw_settings_view(f, app),
     }
+    if !app.recovery.is_empty() {
+        draw_recovery_prompt(f, app);
+    } else if let Some(saved) = &app.saved_session {
+        draw_resume_prompt(f, app, saved);
+    } else if let Some(selected) = app.routine_picker {
+        draw_routine_picker(f, app, selected);
+    } else if let Some(selected) = app.routes_editor {
+        draw_routes_editor(f, app, selected);
+    } else if let Some(draft) = &app.note_draft {
+        draw_note_prompt(f, app, draft);
+    } else if let Some(at) = app.interruption_at {
+        draw_interruption_prompt(f, app, at);
+    }
+}
+
+/// Note being typed for a session, after it completes or from the history,
+/// or for the day's journal
+fn draw_note_prompt(f: &mut Frame, app: &App, draft: &NoteDraft) {
+    let fg = app.theme.foreground.to_color();
+    let secondary = app.theme.secondary.to_color();
+    let accent = app.theme.accent.to_color();
+
+    let area = f.area();
+    let width = 58.min(area.width);
+    // Room for the text inside the borders and padding
+    let room = width.saturating_sub(8) as usize;
+    let typed: Vec<char> = draft.text.chars().collect();
+    let visible: String = typed[typed.len().saturating_sub(room.saturating_sub(1))..]
+        .iter()
+        .collect();
+    let lines = vec![
+        Line::from(Span::styled(
+            draft.label.clone(),
+            Style::default().fg(secondary),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            format!("{}|", visible),
+            Style::default().fg(fg).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            t(app, "[Enter] Save  [Esc] Skip"),
+            Style::default().fg(secondary),
+        )),
+    ];
+
+    let height = (lines.len() as u16 + 4).min(area.height);
+    let rect = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    );
+    let prompt = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(accent))
+            .title(Span::styled(
+                format!(
+                    " {} ",
+                    match draft.target {
+                        NoteTarget::Session(_) => t(app, "Session note"),
+                        NoteTarget::Day(_) => t(app, "Journal"),
+                    }
+                ),
+                Style::default().fg(accent),
+            ))
+            .title_alignment(Alignment::Center)
+            .padding(ratatui::widgets::Padding::new(2, 2, 1, 1)),
+    );
+    f.render_widget(Clear, rect);
+    f.render_widget(prompt, rect);
+}
+
+/// Waiting for the category of an interruption noted with `i`
+fn draw_interruption_prompt(f: &mut Frame, app: &App, at: chrono::DateTime<chrono::Utc>) {
+    let fg = app.theme.foreground.to_color();
+    let secondary = app.theme.secondary.to_color();
+    let accent = app.theme.accent.to_color();
+
+    let lines = vec![
+        Line::from(Span::styled(
+            fill(
+                app,
+                "Interrupted at {0} (#{1} this session)",
+                &[
+                    &at.with_timezone(&chrono::Local).format("%H:%M"),
+                    &(app.interruptions + 1),
+                ],
+            ),
+            Style::default().fg(secondary),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            t(app, "[']  internal   [-]  external"),
+            Style::default().fg(fg).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(Span::styled(
+            t(app, "or any other key as the category"),
+            Style::default().fg(fg),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            t(app, "[Enter] No category  [Esc] Cancel"),
+            Style::default().fg(secondary),
+        )),
+    ];
+
+    let area = f.area();
+    let width = 46.min(area.width);
+    let height = (lines.len() as u16 + 4).min(area.height);
+    let rect = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    );
+    let prompt = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(accent))
+            .title(Span::styled(
+                format!(" {} ", t(app, "Interruption")),
+                Style::default().fg(accent),
+            ))
+            .title_alignment(Alignment::Center)
+            .padding(ratatui::widgets::Padding::new(2, 2, 1, 1)),
+    );
+    f.render_widget(Clear, rect);
+    f.render_widget(prompt, rect);
+}
+
+/// " ⚡2" after a history session that was interrupted twice, or nothing
+fn interruption_mark(app: &App, session_id: i64) -> String {
+    match app.history_interruptions.get(&session_id) {
+        Some(count) => format!(" ⚡{}", count),
+        None => String::new(),
+    }
+}
+
+/// Routine being walked through and how far along, e.g. "morning 2/4"
+fn routine_progress(app: &App) -> Option<String> {
+    let run = app.routine.as_ref()?;
+    let (block, total) = run.position();
+    Some(format!("{} {}/{}", run.name, block, total))
+}
+
+/// List of routines from config to start one from
+fn draw_routine_picker(f: &mut Frame, app: &App, selected: usize) {
+    let fg = app.theme.foreground.to_color();
+    let secondary = app.theme.secondary.to_color();
+    let accent = app.theme.accent.to_color();
+
+    let mut lines: Vec<Line> = routine::names(&app.config)
+        .iter()
+        .enumerate()
+        .flat_map(|(i, name)| {
+            let (marker, style) = if i == selected {
+                (
+                    "▶ ",
+                    Style::default().fg(accent).add_modifier(Modifier::BOLD),
+                )
+            } else {
+                ("  ", Style::default().fg(fg))
+            };
+            [
+                Line::from(Span::styled(format!("{}{}", marker, name), style)),
+                Line::from(Span::styled(
+                    format!("  {}", shown(app, &routine::describe(&app.config, name))),
+                    Style::default().fg(secondary),
+                )),
+            ]
+        })
+        .collect();
+    lines.push(Line::from(""));
+    lines.push(Line::from(Span::styled(
+        t(app, "[↑↓/jk] Choose  [Enter] Start  [Esc] Close"),
+        Style::default().fg(secondary),
+    )));
+
+    let area = f.area();
+    let width = 58.min(area.width);
+    let height = (lines.len() as u16 + 4).min(area.height);
+    let rect = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    );
+    let picker = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(accent))
+            .title(Span::styled(
+                format!(" {} ", t(app, "Routines")),
+                Style::default().fg(accent),
+            ))
+            .title_alignment(Alignment::Center)
+            .padding(ratatui::widgets::Padding::new(2, 2, 1, 1)),
+    );
+    f.render_widget(Clear, rect);
+    f.render_widget(picker, rect);
+}
+
+/// Where each event's notification goes, edited from Settings
+fn draw_routes_editor(f: &mut Frame, app: &App, selected: usize) {
+    let fg = app.theme.foreground.to_color();
+    let secondary = app.theme.secondary.to_color();
+    let accent = app.theme.accent.to_color();
+    let lang = &app.config.appearance.language;
+
+    let mut lines: Vec<Line> = NotifyEvent::ALL
+        .iter()
+        .enumerate()
+        .map(|(i, event)| {
+            let (marker, style) = if i == selected {
+                (
+                    "▶ ",
+                    Style::default().fg(accent).add_modifier(Modifier::BOLD),
+                )
+            } else {
+                ("  ", Style::default().fg(fg))
+            };
+            let route = app.config.notifications.routes.get(*event);
+            Line::from(vec![
+                Span::styled(
+                    format!("{}{}", marker, pad(event.label_with_lang(lang), 20)),
+                    style,
+                ),
+                Span::styled(route.label_with_lang(lang), style),
+            ])
+        })
+        .collect();
+    lines.push(Line::from(""));
+    if !app.config.notifications.sound || !app.config.notifications.desktop {
+        lines.push(Line::from(Span::styled(
+            t(app, "Sound or desktop is OFF in Settings for every event"),
+            Style::default().fg(secondary),
+        )));
+    }
+    lines.push(Line::from(Span::styled(
+        t(app, "[↑↓/jk] Choose  [Enter/→] Change  [Esc] Close"),
+        Style::default().fg(secondary),
+    )));
+
+    let area = f.area();
+    let width = 58.min(area.width);
+    let height = (lines.len() as u16 + 4).min(area.height);
+    let rect = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    );
+    let editor = Paragraph::new(lines).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(accent))
+            .title(Span::styled(
+                format!(" {} ", t(app, "Notification Routing")),
+                Style::default().fg(accent),
+            ))
+            .title_alignment(Alignment::Center)
+            .padding(ratatui::widgets::Padding::new(2, 2, 1, 1)),
+    );
+    f.render_widget(Clear, rect);
+    f.render_widget(editor, rect);
+}
+
+/// "Short break" for "short_break" and so on, as a message id
+fn session_kind(session_type: &str) -> &'static str {
+    match session_type {
+        "short_break" => "Short break",
+        "long_break" => "Long break",
+        "timer" => "Timer",
+        _ => "Work",
+    }
+}
+
+/// Offer to pick up the session the last run was quit in
+fn draw_resume_prompt(f: &mut Frame, app: &App, saved: &SavedSession) {
+    let fg = app.theme.foreground.to_color();
+    let secondary = app.theme.secondary.to_color();
+    let accent = app.theme.accent.to_color();
+
+    let kind = t(app, session_kind(&saved.state));
+    let tag = saved
+        .tag_id
+        .and_then(|id| app.available_tags.iter().find(|t| t.id == id));
+    let title = match tag {
+        Some(tag) => format!("{} · {}", kind, shown(app, &tag.name)),
+        None => kind.to_string(),
+    };
+    let time = if saved.remaining_seconds == 0 && saved.elapsed_seconds > 0 {
+        fill(
+            app,
+            "{0} in",
+            &[&crate::format_duration(saved.elapsed_seconds as i32)],
+        )
+    } else {
+        fill(
+            app,
+            "{0} left",
+            &[&crate::format_duration(saved.remaining_seconds as i32)],
+        )
+    };
+    let quit = saved
+        .saved_at
+        .with_timezone(&Local)
+        .format("%m/%d %H:%M")
+        .to_string();
+
+    let lines = vec![
+        Line::from(Span::styled(
+            title,
+            Style::default().fg(fg).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(Span::styled(
+            fill(app, "{0}, quit {1}", &[&time, &quit]),
+            Style::default().fg(secondary),
+        )),
+        Line::from(""),
+        Line::from(Span::styled(
+            t(app, "[r] Resume  [n] Start fresh"),
+            Style::default().fg(accent),
+        )),
+    ];
+
+    let area = f.area();
+    let width = 50.min(area.width);
+    let height = (lines.len() as u16 + 4).min(area.height);
+    let rect = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    );
+
+    let prompt = Paragraph::new(lines).alignment(Alignment::Center).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(accent))
+            .title(Span::styled(
+                format!(" {} ", t(app, "Resume previous session?")),
+                Style::default().fg(accent),
+            ))
+            .title_alignment(Alignment::Center)
+            .padding(ratatui::widgets::Padding::vertical(1)),
+    );
+    f.render_widget(Clear, rect);
+    f.render_widget(prompt, rect);
+}
+
+/// Prompt for the first session a crashed run left open
+fn draw_recovery_prompt(f: &mut Frame, app: &App) {
+    let fg = app.theme.foreground.to_color();
+    let secondary = app.theme.secondary.to_color();
+    let accent = app.theme.accent.to_color();
+    let open = &app.recovery[0];
+    let session = &open.detail.session;
+
+    let kind = t(app, session_kind(&session.session_type));
+    let title = match &open.detail.tag {
+        Some(tag) => format!("{} · {}", kind, shown(app, &tag.name)),
+        None => kind.to_string(),
+    };
+    let started = session
+        .started_at
+        .with_timezone(&Local)
+        .format("%m/%d %H:%M")
+        .to_string();
+    let planned = match open.detail.planned_seconds {
+        Some(p) => fill(
+            app,
+            "Started {0}, planned {1}",
+            &[&started, &crate::format_duration(p)],
+        ),
+        None => fill(app, "Started {0}, open-ended", &[&started]),
+    };
+    let estimate = match &open.estimate {
+        Some(e) if e.from_status => fill(
+            app,
+            "About {0} done when the timer stopped",
+            &[&crate::format_duration(e.seconds)],
+        ),
+        Some(e) => fill(
+            app,
+            "Up to {0} (no record of when it stopped)",
+            &[&crate::format_duration(e.seconds)],
+        ),
+        None => t(app, "No record of how long it ran").to_string(),
+    };
+    let complete = match &open.estimate {
+        Some(e) => fill(
+            app,
+            "[c] Complete ({0})  ",
+            &[&crate::format_duration(e.seconds)],
+        ),
+        None => String::new(),
+    };
+    let all = if app.recovery.len() > 1 {
+        fill(app, "[C/I/D] All {0}  ", &[&app.recovery.len()])
+    } else {
+        String::new()
+    };
+
+    let lines = vec![
+        Line::from(Span::styled(
+            title,
+            Style::default().fg(fg).add_modifier(Modifier::BOLD),
+        )),
+        Line::from(Span::styled(planned, Style::default().fg(secondary))),
+        Line::from(Span::styled(estimate, Style::default().fg(secondary))),
+        Line::from(""),
+        Line::from(Span::styled(
+            format!("{}{}", complete, t(app, "[i] Interrupted  [d] Discard")),
+            Style::default().fg(accent),
+        )),
+        Line::from(Span::styled(
+            format!("{}{}", all, t(app, "[Esc] Later")),
+            Style::default().fg(secondary),
+        )),
+    ];
+
+    let counter = if app.recovery.len() > 1 {
+        format!(
+            " {} ",
+            fill(app, "Unfinished session (1 of {0})", &[&app.recovery.len()])
+        )
+    } else {
+        format!(" {} ", t(app, "Unfinished session"))
+    };
+    let area = f.area();
+    let width = 58.min(area.width);
+    let height = (lines.len() as u16 + 4).min(area.height);
+    let rect = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    );
+
+    let prompt = Paragraph::new(lines).alignment(Alignment::Center).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(accent))
+            .title(Span::styled(counter, Style::default().fg(accent)))
+            .title_alignment(Alignment::Center)
+            .padding(ratatui::widgets::Padding::vertical(1)),
+    );
+    f.render_widget(Clear, rect);
+    f.render_widget(prompt, rect);
 }
 
 fn draw_timer_view(f: &mut Frame, app: &App) {
@@ -35,18 +549,316 @@ fn draw_timer_view(f: &mut Frame, app: &App) {
         .split(f.area());
 
     draw_header(f, chunks[0], app);
-    draw_main_content(f, chunks[1], app);
+    if app.board_open && !app.locked {
+        let board_width = (chunks[1].width / 2).min(54);
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Min(30), Constraint::Length(board_width)])
+            .split(chunks[1]);
+        draw_main_content(f, columns[0], app);
+        draw_board(f, columns[1], app);
+    } else {
+        draw_main_content(f, chunks[1], app);
+    }
     draw_footer(f, chunks[2], app, false);
 }
 
+/// The timer on three borderless lines, for small tmux panes:
+/// state, time, progress and round; today's totals; keys
+fn draw_compact_view(f: &mut Frame, app: &App) {
+    let fg = app.theme.foreground.to_color();
+    let secondary = app.theme.secondary.to_color();
+    let lang = &app.config.appearance.language;
+    let area = f.area();
+
+    let (glyph, state_color) = if app.timer.is_paused {
+        ("⏸", Color::Yellow)
+    } else {
+        let color = match app.timer.state {
+            TimerState::Work | TimerState::Timer => app.theme.work.to_color(),
+            TimerState::ShortBreak => app.theme.short_break.to_color(),
+            TimerState::LongBreak => app.theme.long_break.to_color(),
+        };
+        ("▶", color)
+    };
+    let state = format!(" {} {} ", glyph, app.timer.state.label_with_lang(lang));
+    let time = app.timer.formatted_display_time();
+    let round = match routine_progress(app) {
+        Some(progress) => format!("  {}", progress),
+        None => format!(
+            "  {} {}/{}",
+            app.timer.cycle_dots(),
+            app.timer.session_count,
+            app.timer.sessions_until_long_break
+        ),
+    };
+    // The bar takes whatever width is left, within reason
+    let used = state.chars().count() + time.chars().count() + round.chars().count() + 2;
+    let bar_width = (area.width as usize).saturating_sub(used + 1).min(40);
+    let filled = ((app.timer.progress_percent() / 100.0) * bar_width as f32).round() as usize;
+    let filled = filled.min(bar_width);
+
+    let mut first = vec![
+        Span::styled(
+            state,
+            Style::default()
+                .fg(state_color)
+                .add_modifier(Modifier::BOLD),
+        ),
+        Span::styled(time, Style::default().fg(fg).add_modifier(Modifier::BOLD)),
+    ];
+    if bar_width > 0 {
+        first.push(Span::raw("  "));
+        first.push(Span::styled(
+            "█".repeat(filled),
+            Style::default().fg(state_accent_color(app)),
+        ));
+        first.push(Span::styled(
+            "░".repeat(bar_width - filled),
+            Style::default().fg(secondary),
+        ));
+    }
+    first.push(Span::styled(round, Style::default().fg(secondary)));
+
+    let summary = if app.is_loading() {
+        format!(" {}", t(app, "Today … · loading"))
+    } else {
+        format!(
+            " {}",
+            fill(
+                app,
+                "Today {0} · {1} sessions",
+                &[
+                    &crate::format_duration(app.today_work_seconds),
+                    &app.today_sessions
+                ],
+            )
+        )
+    };
+    let mut today = vec![Span::styled(summary, Style::default().fg(fg))];
+    if let Some(tag) = app.selected_tag() {
+        today.push(Span::styled(" · ", Style::default().fg(fg)));
+        today.push(Span::styled(
+            shown(app, &tag.name),
+            Style::default().fg(tag_color(tag)),
+        ));
+    }
+    if app.ephemeral && app.demo.is_none() {
+        today.push(Span::styled(
+            format!(" · ◌ {}", t(app, "ephemeral")),
+            Style::default().fg(fg),
+        ));
+    }
+    let keys = if app.locked {
+        t(app, LOCKED_KEYS).trim_start()
+    } else if let Some(keys) = strict_keys(app) {
+        t(app, keys).trim_start()
+    } else if app.available_tags.is_empty() {
+        t(
+            app,
+            " [Space] Pause  [r] Reset  [s] Skip  [Tab] Settings  [q] Quit",
+        )
+    } else {
+        t(
+            app,
+            " [Space] Pause  [r] Reset  [s] Skip  [t] Tag  [Tab] Settings  [q] Quit",
+        )
+    };
+
+    let lines = vec![
+        Line::from(first),
+        Line::from(today),
+        Line::from(Span::styled(keys, Style::default().fg(secondary))),
+    ];
+    f.render_widget(Paragraph::new(lines), area);
+}
+
+/// Width of the TUI daily goal bar in cells
+const GOAL_BAR_WIDTH: usize = 20;
+
+/// Daily minutes goal as a bar split by tag, with a legend when it fits in
+/// `width`; None without a daily minutes goal
+fn goal_bar_line(app: &App, width: usize) -> Option<Line<'static>> {
+    let goal_minutes = app.config.goals.daily_minutes;
+    if goal_minutes == 0 {
+        return None;
+    }
+    let secondary = app.theme.secondary.to_color();
+    let goal_seconds = goal_minutes as i32 * 60;
+    let segments = tag_bar::segments(&app.today_by_tag).unwrap_or_else(|| {
+        // Nothing tagged: one stretch in the accent color
+        vec![tag_bar::Segment {
+            label: String::new(),
+            seconds: app.today_work_seconds,
+            rgb: ThemeColor::from_accent_name(app.current_accent()).to_rgb(),
+        }]
+    });
+    let cells = tag_bar::cells(&segments, goal_seconds, GOAL_BAR_WIDTH);
+
+    let mut spans = vec![Span::styled(
+        format!("{} ", t(app, "Goal")),
+        Style::default().fg(secondary),
+    )];
+    for (segment, count) in segments.iter().zip(&cells) {
+        let (r, g, b) = segment.rgb;
+        spans.push(Span::styled(
+            "█".repeat(*count),
+            Style::default().fg(Color::Rgb(r, g, b)),
+        ));
+    }
+    let filled: usize = cells.iter().sum();
+    spans.push(Span::styled(
+        "░".repeat(GOAL_BAR_WIDTH - filled),
+        Style::default().fg(secondary),
+    ));
+    spans.push(Span::styled(
+        format!(" {}/{}m", app.today_work_seconds / 60, goal_minutes),
+        Style::default().fg(secondary),
+    ));
+
+    // Legend, only for tagged time and only while it fits
+    for segment in segments.iter().filter(|s| !s.label.is_empty()) {
+        let label = shown(app, &segment.label);
+        let entry = format!("  ■ {}", label);
+        let used: usize = spans.iter().map(|s| s.width()).sum();
+        if used + entry.chars().count() > width {
+            break;
+        }
+        let (r, g, b) = segment.rgb;
+        spans.push(Span::styled(
+            "  ■",
+            Style::default().fg(Color::Rgb(r, g, b)),
+        ));
+        spans.push(Span::styled(
+            format!(" {}", label),
+            Style::default().fg(secondary),
+        ));
+    }
+    Some(Line::from(spans))
+}
+
+/// Cut `text` to `width` characters, ending in "…" when shortened
+pub fn truncate(text: &str, width: usize) -> String {
+    if text.chars().count() <= width {
+        text.to_string()
+    } else {
+        let mut cut: String = text.chars().take(width.saturating_sub(1)).collect();
+        cut.push('…');
+        cut
+    }
+}
+
+/// "Today" board pane: Planned / Doing / Done
+fn draw_board(f: &mut Frame, area: Rect, app: &App) {
+    let fg = app.theme.foreground.to_color();
+    let secondary = app.theme.secondary.to_color();
+    let accent = ThemeColor::from_accent_name(app.current_accent()).to_color();
+    let border = frame_border_style(app);
+
+    // The timer's right border doubles as the divider
+    let block = Block::default()
+        .borders(Borders::TOP | Borders::RIGHT)
+        .border_style(border)
+        .title(Span::styled(
+            format!(" {} ", t(app, "Today")),
+            Style::default().fg(fg),
+        ))
+        .padding(ratatui::widgets::Padding::left(1));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let rows = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .split(inner);
+    let columns = Layout::default()
+        .direction(Direction::Horizontal)
+        .constraints([Constraint::Ratio(1, 3); 3])
+        .split(rows[0]);
+    let title_width = (columns[0].width as usize).saturating_sub(3);
+
+    for (i, status) in TaskStatus::ALL.iter().enumerate() {
+        let tasks = app.board.column_tasks(*status);
+        let label = t(
+            app,
+            match status {
+                TaskStatus::Planned => "Planned",
+                TaskStatus::Doing => "Doing",
+                TaskStatus::Done => "Done",
+            },
+        );
+        let header_style = if i == app.board.column {
+            Style::default().fg(accent).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(fg)
+        };
+        let mut items =
+            vec![ListItem::new(format!("{} ({})", label, tasks.len())).style(header_style)];
+        items.extend(tasks.iter().enumerate().map(|(row, task)| {
+            let marker = if app.bound_task == Some(task.id) {
+                "▶"
+            } else {
+                "·"
+            };
+            let estimate = match task.estimate {
+                Some(n) => format!("{}/{} ", task.sessions_done, n),
+                None if task.sessions_done > 0 => format!("{} ", task.sessions_done),
+                None => String::new(),
+            };
+            let label = truncate(&format!("{}{}", estimate, task.title), title_width);
+            let style = if i == app.board.column && row == app.board.row {
+                Style::default().fg(accent).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(secondary)
+            };
+            ListItem::new(format!("{} {}", marker, label)).style(style)
+        }));
+        f.render_widget(List::new(items), columns[i]);
+    }
+
+    let prompt = match &app.board.input {
+        Some(input) => Line::from(Span::styled(
+            format!("+ {}|", input),
+            Style::default().fg(accent),
+        )),
+        None => match app.bound_task() {
+            Some(task) => Line::from(Span::styled(
+                format!("▶ {}", task.title),
+                Style::default().fg(accent),
+            )),
+            None if app.board.tasks.is_empty() => Line::from(Span::styled(
+                t(app, "[a] Add a task"),
+                Style::default().fg(secondary),
+            )),
+            None => Line::from(Span::styled(
+                t(app, "[Enter] Bind a task to the timer"),
+                Style::default().fg(secondary),
+            )),
+        },
+    };
+    f.render_widget(Paragraph::new(prompt), rows[1]);
+}
+
+/// Where the settings in use come from, e.g. "config.toml + ~/work/api/.sandoro.toml"
+fn config_source(app: &App) -> String {
+    let Some(project) = &app.project else {
+        return "config.toml".to_string();
+    };
+    let path = match dirs::home_dir().and_then(|home| project.path.strip_prefix(home).ok()) {
+        Some(rest) => format!("~/{}", rest.display()),
+        None => project.path.display().to_string(),
+    };
+    format!("config.toml + {}", path)
+}
+
 fn draw_settings_view(f: &mut Frame, app: &App) {
     let secondary = app.theme.secondary.to_color();
     let fg = app.theme.foreground.to_color();
 
     // Determine accent color (rainbow or selected accent)
+    let rainbow = app.rainbow();
     let accent = if app.is_rainbow_mode() {
-        let time_offset = (app.rainbow_frame as u64) * 1000;
-        let (r, g, b) = get_rainbow_color(time_offset);
+        let (r, g, b) = rainbow.color(app.rainbow_frame);
         Color::Rgb(r, g, b)
     } else {
         ThemeColor::from_accent_name(app.current_accent()).to_color()
@@ -66,24 +878,34 @@ fn draw_settings_view(f: &mut Frame, app: &App) {
         .split(f.area());
 
     // Header
-    let header_text = if app.config.appearance.language == "ja" {
-        "設定"
-    } else {
-        "Settings"
-    };
+    let header_text = t(app, "Settings");
     let header = Paragraph::new(Line::from(vec![
         Span::styled("  ", Style::default()),
         Span::styled(
             header_text,
             Style::default().add_modifier(Modifier::BOLD).fg(fg),
         ),
+        Span::styled(
+            format!("  · {}", config_source(app)),
+            Style::default().fg(secondary),
+        ),
     ]))
-    .block(Block::default().borders(Borders::TOP | Borders::LEFT | Borders::RIGHT));
+    .block(
+        Block::default()
+            .borders(Borders::TOP | Borders::LEFT | Borders::RIGHT)
+            .border_style(frame_border_style(app)),
+    );
     f.render_widget(header, chunks[0]);
 
     // Calculate visible area height (subtract borders)
     let visible_height = chunks[1].height.saturating_sub(2) as usize;
 
+    let no_sessions = if app.session_search.is_empty() {
+        t(app, "(no sessions)")
+    } else {
+        t(app, "(no matches)")
+    };
+
     // Settings list with scroll support
     let all_items = SettingsItem::all();
     let items: Vec<ListItem> = all_items
@@ -102,76 +924,133 @@ fn draw_settings_view(f: &mut Frame, app: &App) {
                     let icon = &app.available_icons[app.icon_index];
                     format!("{} {}", icon.emoji(), icon.label())
                 }
-                SettingsItem::WorkDuration => format!("{} min", app.config.timer.work_duration),
-                SettingsItem::ShortBreak => format!("{} min", app.config.timer.short_break),
-                SettingsItem::LongBreak => format!("{} min", app.config.timer.long_break),
+                SettingsItem::WorkDuration => {
+                    fill(app, "{0} min", &[&app.config.timer.work_duration])
+                }
+                SettingsItem::ShortBreak => fill(app, "{0} min", &[&app.config.timer.short_break]),
+                SettingsItem::LongBreak => fill(app, "{0} min", &[&app.config.timer.long_break]),
                 SettingsItem::AutoStart => {
                     if app.config.timer.auto_start {
-                        "ON".to_string()
+                        t(app, "ON").to_string()
                     } else {
-                        "OFF".to_string()
+                        t(app, "OFF").to_string()
+                    }
+                }
+                SettingsItem::NotePrompt => {
+                    if app.config.timer.note_prompt {
+                        t(app, "ON").to_string()
+                    } else {
+                        t(app, "OFF").to_string()
                     }
                 }
                 SettingsItem::FocusMode => {
                     let modes = [
                         "🍅 Classic (fixed intervals)",
                         "🌊 Flowtime (work as long as you want)",
+                        "⏱ Timer (one countdown or stopwatch)",
                     ];
-                    modes[app.focus_mode_index].to_string()
+                    t(app, modes[app.focus_mode_index]).to_string()
                 }
                 SettingsItem::BreakSnooze => {
                     if app.config.focus.break_snooze_enabled {
-                        "ON".to_string()
+                        t(app, "ON").to_string()
                     } else {
-                        "OFF".to_string()
+                        t(app, "OFF").to_string()
                     }
                 }
+                SettingsItem::AnimationSpeed => app
+                    .config
+                    .appearance
+                    .animation_speed
+                    .label_with_lang(&app.config.appearance.language)
+                    .to_string(),
+                SettingsItem::RainbowPalette => rainbow.summary(),
+                SettingsItem::RainbowSpeed => {
+                    format!("{} ms", app.config.appearance.rainbow.speed_ms)
+                }
+                SettingsItem::NamedTimer => app
+                    .config
+                    .focus
+                    .named_timer()
+                    .map(|t| t.summary(&app.config.appearance.language))
+                    .unwrap_or_default(),
+                SettingsItem::BreakActivity => app
+                    .config
+                    .focus
+                    .break_activity
+                    .label_with_lang(&app.config.appearance.language)
+                    .to_string(),
                 SettingsItem::SoundEnabled => {
                     if app.config.notifications.sound {
-                        "ON".to_string()
+                        t(app, "ON").to_string()
                     } else {
-                        "OFF".to_string()
+                        t(app, "OFF").to_string()
                     }
                 }
+                SettingsItem::SoundChoice => app
+                    .config
+                    .notifications
+                    .sound_name
+                    .label_with_lang(&app.config.appearance.language),
+                SettingsItem::Ambience => app
+                    .config
+                    .ambience
+                    .sound
+                    .label_with_lang(&app.config.appearance.language)
+                    .to_string(),
+                SettingsItem::AmbienceVolume => format!("{}%", app.config.ambience.volume),
+                SettingsItem::ProgressCues => {
+                    format_progress_cues(&app.config.notifications.progress_cues)
+                }
                 SettingsItem::DesktopNotification => {
                     if app.config.notifications.desktop {
-                        "ON".to_string()
+                        t(app, "ON").to_string()
                     } else {
-                        "OFF".to_string()
+                        t(app, "OFF").to_string()
+                    }
+                }
+                SettingsItem::NotificationRouting => {
+                    routes_summary(&app.config.notifications.routes).to_string()
+                }
+                SettingsItem::DoNotDisturb => {
+                    if app.config.dnd.enabled {
+                        t(app, "ON").to_string()
+                    } else {
+                        t(app, "OFF").to_string()
                     }
                 }
                 SettingsItem::DailySessionsGoal => {
                     if app.config.goals.daily_sessions == 0 {
-                        "Not set".to_string()
+                        t(app, "Not set").to_string()
                     } else {
-                        format!("{} sessions", app.config.goals.daily_sessions)
+                        fill(app, "{0} sessions", &[&app.config.goals.daily_sessions])
                     }
                 }
                 SettingsItem::DailyMinutesGoal => {
                     if app.config.goals.daily_minutes == 0 {
-                        "Not set".to_string()
+                        t(app, "Not set").to_string()
                     } else {
-                        format!("{} min", app.config.goals.daily_minutes)
+                        fill(app, "{0} min", &[&app.config.goals.daily_minutes])
                     }
                 }
                 SettingsItem::WeeklySessionsGoal => {
                     if app.config.goals.weekly_sessions == 0 {
-                        "Not set".to_string()
+                        t(app, "Not set").to_string()
                     } else {
-                        format!("{} sessions", app.config.goals.weekly_sessions)
+                        fill(app, "{0} sessions", &[&app.config.goals.weekly_sessions])
                     }
                 }
                 SettingsItem::WeeklyMinutesGoal => {
                     if app.config.goals.weekly_minutes == 0 {
-                        "Not set".to_string()
+                        t(app, "Not set").to_string()
                     } else {
-                        format!("{} min", app.config.goals.weekly_minutes)
+                        fill(app, "{0} min", &[&app.config.goals.weekly_minutes])
                     }
                 }
                 SettingsItem::TagsHeader => {
                     // Show existing tags as a summary
                     if app.available_tags.is_empty() {
-                        "(no tags)".to_string()
+                        t(app, "(no tags)").to_string()
                     } else {
                         app.available_tags
                             .iter()
@@ -189,64 +1068,154 @@ fn draw_settings_view(f: &mut Frame, app: &App) {
                 }
                 SettingsItem::DeleteTag => {
                     if app.available_tags.is_empty() {
-                        "(no tags)".to_string()
+                        t(app, "(no tags)").to_string()
                     } else if is_editing {
                         let tag_name = &app.available_tags[app.delete_tag_index].name;
-                        format!("→ {} [↑↓ select, Enter delete]", tag_name)
+                        format!("→ {} {}", tag_name, t(app, "[↑↓ select, Enter delete]"))
                     } else {
                         let tag_name = &app.available_tags[app.delete_tag_index].name;
                         tag_name.clone()
                     }
                 }
+                SettingsItem::TagDefaults if is_editing => {
+                    format!(
+                        "→ {} {}",
+                        app.tag_setting_value(item),
+                        t(app, "[↑↓ select]")
+                    )
+                }
+                SettingsItem::TagDefaults
+                | SettingsItem::TagWork
+                | SettingsItem::TagBreak
+                | SettingsItem::TagIcon
+                | SettingsItem::TagColor => app.tag_setting_value(item),
                 SettingsItem::SessionsHeader => {
                     // Show count of recent sessions
-                    if app.recent_sessions.is_empty() {
-                        "(no sessions)".to_string()
+                    if app.session_search_mode {
+                        format!("/{}|", app.session_search)
+                    } else if !app.session_search.is_empty() {
+                        fill(
+                            app,
+                            "({0} matching \"{1}\")",
+                            &[&app.recent_sessions.len(), &app.session_search],
+                        )
+                    } else if app.recent_sessions.is_empty() {
+                        t(app, "(no sessions)").to_string()
                     } else {
-                        format!("({} recent)", app.recent_sessions.len())
+                        fill(app, "({0} recent)", &[&app.recent_sessions.len()])
                     }
                 }
                 SettingsItem::EditSessionTag => {
                     if app.recent_sessions.is_empty() {
-                        "(no sessions)".to_string()
+                        no_sessions.to_string()
                     } else if is_editing {
                         let (session, tag) = &app.recent_sessions[app.session_edit_index];
-                        let tag_name = tag.as_ref().map(|t| t.name.as_str()).unwrap_or("No tag");
+                        let tag_name = tag
+                            .as_ref()
+                            .map(|t| t.name.as_str())
+                            .unwrap_or(t(app, "No tag"));
                         let new_tag = app
                             .session_tag_edit_index
                             .and_then(|i| app.available_tags.get(i))
                             .map(|t| t.name.as_str())
-                            .unwrap_or("No tag");
+                            .unwrap_or(t(app, "No tag"));
                         let date = session.started_at.format("%m/%d %H:%M").to_string();
                         format!(
-                            "→ {} {} → {} [↑↓ select, Enter confirm]",
-                            date, tag_name, new_tag
+                            "→ {} {} → {} {}",
+                            date,
+                            tag_name,
+                            new_tag,
+                            t(app, "[↑↓ select, Enter confirm]")
                         )
                     } else {
                         let (session, tag) = &app.recent_sessions[app.session_edit_index];
-                        let tag_name = tag.as_ref().map(|t| t.name.as_str()).unwrap_or("No tag");
+                        let tag_name = tag
+                            .as_ref()
+                            .map(|t| t.name.as_str())
+                            .unwrap_or(t(app, "No tag"));
                         let date = session.started_at.format("%m/%d %H:%M").to_string();
                         format!("{} - {}", date, tag_name)
                     }
                 }
+                SettingsItem::EditSessionNote => {
+                    if app.recent_sessions.is_empty() {
+                        no_sessions.to_string()
+                    } else {
+                        let (session, _) = &app.recent_sessions[app.session_edit_index];
+                        let date = session.started_at.format("%m/%d %H:%M").to_string();
+                        let note = session
+                            .notes
+                            .as_deref()
+                            .map(|n| truncate(&shown(app, n), 30))
+                            .unwrap_or_else(|| t(app, "(no note)").to_string());
+                        if is_editing {
+                            format!("→ {} {} {}", date, note, t(app, "[↑↓ select, Enter edit]"))
+                        } else {
+                            format!("{} - {}", date, note)
+                        }
+                    }
+                }
+                SettingsItem::MergeSession => {
+                    let index = app.session_edit_index;
+                    if index + 1 >= app.recent_sessions.len() {
+                        no_sessions.to_string()
+                    } else {
+                        // The selected session and the one before it
+                        let (newer, _) = &app.recent_sessions[index];
+                        let (older, tag) = &app.recent_sessions[index + 1];
+                        let tag_name = tag
+                            .as_ref()
+                            .map(|t| t.name.as_str())
+                            .unwrap_or(t(app, "No tag"));
+                        let date = older.started_at.format("%m/%d %H:%M").to_string();
+                        let minutes = |s: &crate::db::Session| s.duration_seconds.unwrap_or(0) / 60;
+                        let pair = format!("{} {}m + {}m", date, minutes(older), minutes(newer));
+                        if is_editing {
+                            format!(
+                                "→ {} {} {}",
+                                pair,
+                                tag_name,
+                                t(app, "[↑↓ select, Enter merge]")
+                            )
+                        } else {
+                            format!("{} - {}", pair, tag_name)
+                        }
+                    }
+                }
                 SettingsItem::DeleteSession => {
                     if app.recent_sessions.is_empty() {
-                        "(no sessions)".to_string()
+                        no_sessions.to_string()
                     } else if is_editing {
                         let (session, tag) = &app.recent_sessions[app.session_edit_index];
-                        let tag_name = tag.as_ref().map(|t| t.name.as_str()).unwrap_or("No tag");
+                        let tag_name = tag
+                            .as_ref()
+                            .map(|t| t.name.as_str())
+                            .unwrap_or(t(app, "No tag"));
                         let date = session.started_at.format("%m/%d %H:%M").to_string();
                         let duration = session.duration_seconds.unwrap_or(0) / 60;
                         format!(
-                            "→ {} {}m {} [↑↓ select, Enter delete]",
-                            date, duration, tag_name
+                            "→ {} {}m{} {} {}",
+                            date,
+                            duration,
+                            interruption_mark(app, session.id),
+                            tag_name,
+                            t(app, "[↑↓ select, Enter delete]")
                         )
                     } else {
                         let (session, tag) = &app.recent_sessions[app.session_edit_index];
-                        let tag_name = tag.as_ref().map(|t| t.name.as_str()).unwrap_or("No tag");
+                        let tag_name = tag
+                            .as_ref()
+                            .map(|t| t.name.as_str())
+                            .unwrap_or(t(app, "No tag"));
                         let date = session.started_at.format("%m/%d %H:%M").to_string();
                         let duration = session.duration_seconds.unwrap_or(0) / 60;
-                        format!("{} {}m - {}", date, duration, tag_name)
+                        format!(
+                            "{} {}m{} - {}",
+                            date,
+                            duration,
+                            interruption_mark(app, session.id),
+                            tag_name
+                        )
                     }
                 }
                 SettingsItem::Back => String::new(),
@@ -258,7 +1227,7 @@ fn draw_settings_view(f: &mut Frame, app: &App) {
 
             let prefix = if is_selected { "► " } else { "  " };
             let edit_indicator = if is_editing && !is_input_mode {
-                " [editing ↑↓]"
+                t(app, " [editing ↑↓]")
             } else {
                 ""
             };
@@ -292,30 +1261,151 @@ fn draw_settings_view(f: &mut Frame, app: &App) {
                 Style::default().fg(secondary)
             };
 
-            ListItem::new(content).style(style)
+            if matches!(item, SettingsItem::RainbowPalette) {
+                // Preview: the palette as a strip of blocks, scrolling with
+                // the animation
+                let mut spans = vec![Span::raw(content), Span::raw(" ")];
+                spans.extend((0..rainbow.colors.len()).map(|i| {
+                    let (r, g, b) = rainbow.color(app.rainbow_frame.wrapping_add(i as u8));
+                    Span::styled("██", Style::default().fg(Color::Rgb(r, g, b)))
+                }));
+                return ListItem::new(Line::from(spans)).style(style);
+            }
+            // Tag names in their colors, and a swatch for the tag's color
+            let tag_defaults = app.available_tags.get(app.tag_defaults_index);
+            let session_tag = |index: usize| {
+                app.recent_sessions
+                    .get(index)
+                    .and_then(|(_, tag)| tag.as_ref())
+            };
+            let row_tag = match item {
+                SettingsItem::TagDefaults | SettingsItem::TagColor => tag_defaults,
+                SettingsItem::DeleteTag => app.available_tags.get(app.delete_tag_index),
+                SettingsItem::EditSessionTag | SettingsItem::DeleteSession => {
+                    session_tag(app.session_edit_index)
+                }
+                SettingsItem::MergeSession => session_tag(app.session_edit_index + 1),
+                _ => None,
+            };
+            match (item, row_tag) {
+                (SettingsItem::TagColor, Some(tag)) => {
+                    let mut line = Line::from(content);
+                    line.push_span(Span::styled(" ■", Style::default().fg(tag_color(tag))));
+                    ListItem::new(line).style(style)
+                }
+                (_, Some(tag)) => {
+                    ListItem::new(with_colored(content, &tag.name, tag_color(tag))).style(style)
+                }
+                _ => ListItem::new(content).style(style),
+            }
         })
         .collect();
 
-    let list = List::new(items).block(Block::default().borders(Borders::LEFT | Borders::RIGHT));
+    let list = List::new(items).block(
+        Block::default()
+            .borders(Borders::LEFT | Borders::RIGHT)
+            .border_style(frame_border_style(app)),
+    );
     f.render_widget(list, chunks[1]);
 
     draw_footer(f, chunks[2], app, true);
 }
 
+/// Accent color for the current timer state (rainbow cycles through colors)
+fn state_accent_color(app: &App) -> Color {
+    match app.state_accent() {
+        "rainbow" => {
+            let (r, g, b) = app.rainbow().color(app.rainbow_frame);
+            Color::Rgb(r, g, b)
+        }
+        accent => ThemeColor::from_accent_name(accent).to_color(),
+    }
+}
+
+/// Border style of the timer and Settings frames, tinted only when per-state
+/// accents are configured
+fn frame_border_style(app: &App) -> Style {
+    if app.config.appearance.state_accents.is_empty() {
+        Style::default()
+    } else {
+        Style::default().fg(state_accent_color(app))
+    }
+}
+
 fn draw_header(f: &mut Frame, area: Rect, app: &App) {
     let fg = app.theme.foreground.to_color();
     let secondary = app.theme.secondary.to_color();
 
-    // Get current time
-    let current_time = Local::now().format("%H:%M:%S").to_string();
+    // Get current time; low-power mode drops the seconds so idle screens stay still
+    let current_time = if app.low_power {
+        clock::now().format("🔋 %H:%M").to_string()
+    } else {
+        clock::now().format("%H:%M:%S").to_string()
+    };
 
     // Calculate padding to right-align the time
     // Area width - borders (2) - left content (~15) - time (~8) - right padding (2)
     let left_content_width = 15; // "  sandoro v0.1.0"
-    let time_width = 8; // "HH:MM:SS"
-    let padding_width = area
-        .width
-        .saturating_sub(2 + left_content_width + time_width + 2) as usize;
+    let time_width = 8; // "HH:MM:SS" or "🔋 HH:MM"
+
+    // Detected workspace, e.g. "  ⌂ clientA" (dimmed once the tag is changed by hand)
+    let workspace = app.workspace.as_ref().map(|ws| {
+        let (label, color) = if app.workspace_overridden() {
+            (
+                format!("  ⌂ {} {}", shown(app, &ws.tag), t(app, "(overridden)")),
+                secondary,
+            )
+        } else {
+            (
+                format!("  ⌂ {}", shown(app, &ws.tag)),
+                state_accent_color(app),
+            )
+        };
+        Span::styled(label, Style::default().fg(color))
+    });
+    let workspace_width = workspace.as_ref().map(|s| s.width() as u16).unwrap_or(0);
+
+    // --ephemeral: nothing from this run is saved. The demo is ephemeral
+    // too but stays unmarked for screenshots.
+    let ephemeral = (app.ephemeral && app.demo.is_none()).then(|| {
+        Span::styled(
+            format!("  ◌ {}", t(app, "ephemeral")),
+            Style::default()
+                .fg(app.theme.short_break.to_color())
+                .add_modifier(Modifier::BOLD),
+        )
+    });
+    let ephemeral_width = ephemeral.as_ref().map(|s| s.width() as u16).unwrap_or(0);
+
+    // "  ★ Lv 3 Apprentice" when XP is on
+    let level = app.xp.map(|total| {
+        Span::styled(
+            format!("  ★ {}", xp::level_for(total).badge()),
+            Style::default().fg(secondary),
+        )
+    });
+    let level_width = level.as_ref().map(|s| s.width() as u16).unwrap_or(0);
+
+    // Strict mode holding back quit, skip and reset for this work session
+    let strict = app.strict_active().then(|| {
+        Span::styled(
+            format!("  ⛓ {}", t(app, "strict")),
+            Style::default()
+                .fg(state_accent_color(app))
+                .add_modifier(Modifier::BOLD),
+        )
+    });
+    let strict_width = strict.as_ref().map(|s| s.width() as u16).unwrap_or(0);
+
+    let padding_width = area.width.saturating_sub(
+        2 + left_content_width
+            + workspace_width
+            + ephemeral_width
+            + level_width
+            + strict_width
+            + time_width
+            + 2,
+    ) as usize;
     let padding = " ".repeat(padding_width);
 
     let header = Paragraph::new(Line::from(vec![
@@ -325,6 +1415,10 @@ fn draw_header(f: &mut Frame, area: Rect, app: &App) {
             Style::default().add_modifier(Modifier::BOLD).fg(fg),
         ),
         Span::styled(" v0.1.0", Style::default().fg(secondary)),
+        workspace.unwrap_or_default(),
+        ephemeral.unwrap_or_default(),
+        level.unwrap_or_default(),
+        strict.unwrap_or_default(),
         Span::styled(padding, Style::default()),
         Span::styled(current_time, Style::default().fg(secondary)),
         Span::styled("  ", Style::default()),
@@ -334,17 +1428,46 @@ fn draw_header(f: &mut Frame, area: Rect, app: &App) {
     f.render_widget(header, area);
 }
 
+/// Rows `draw_main_content` draws under the icon
+const MAIN_CONTENT_ROWS: u16 = 8;
+
+/// Render the icon at the largest size that fits in `width` × `height`. The
+/// progress bar stands in when even the small icon doesn't fit, and nothing
+/// is drawn when the progress bar doesn't either.
+fn fit_icon(icon_state: &mut IconState, is_break: bool, width: u16, height: u16) -> Vec<String> {
+    let fits = |lines: &[String]| {
+        lines.len() <= height as usize
+            && lines
+                .iter()
+                .all(|line| Span::raw(line.as_str()).width() <= width as usize)
+    };
+    for icon_type in [icon_state.icon_type, IconType::Progress] {
+        icon_state.icon_type = icon_type;
+        for size in IconSize::ALL {
+            icon_state.size = size;
+            let lines = icon_state.render_with_direction(is_break);
+            if fits(&lines) {
+                return lines;
+            }
+        }
+    }
+    vec![]
+}
+
 fn draw_main_content(f: &mut Frame, area: Rect, app: &App) {
     let secondary = app.theme.secondary.to_color();
     let work_color = app.theme.work.to_color();
     let short_break_color = app.theme.short_break.to_color();
     let long_break_color = app.theme.long_break.to_color();
 
-    // Determine if rainbow mode is enabled
-    let is_rainbow = app.is_rainbow_mode();
+    let border = frame_border_style(app);
+
+    // Determine if rainbow mode is enabled for the current state
+    let is_rainbow = app.state_accent() == "rainbow";
+    let rainbow = app.rainbow();
     let accent_color = if !is_rainbow {
-        // Use accent color from theme
-        ThemeColor::from_accent_name(app.current_accent()).to_color()
+        // Use accent color for the current state
+        ThemeColor::from_accent_name(app.state_accent()).to_color()
     } else {
         // Placeholder - will be replaced per line for rainbow
         Color::White
@@ -352,19 +1475,25 @@ fn draw_main_content(f: &mut Frame, area: Rect, app: &App) {
 
     // Draw icon based on selected icon type
     let progress = app.timer.progress_percent();
-    let is_break = !matches!(app.timer.state, TimerState::Work);
+    let is_break = !app.timer.state.is_focus();
     let current_icon = app.current_icon();
 
-    let icon_lines = if current_icon == IconType::None {
+    let icon_lines = if let Some(lines) = app.break_activity_lines() {
+        lines
+    } else if current_icon == IconType::None {
         vec![]
     } else {
         let mut icon_state = IconState::new(current_icon);
         icon_state.percent = progress;
         icon_state.animation_frame = app.animation_frame;
-        icon_state.is_animating = !app.timer.is_paused;
+        icon_state.is_animating =
+            !app.timer.is_paused && app.config.appearance.animation_speed != AnimationSpeed::Off;
         // Flowtime work mode: timer is flowtime and in work state
         icon_state.is_flowtime_work = app.timer.is_flowtime && !is_break;
-        icon_state.render_with_direction(is_break)
+        // Leave room for the rows under the icon and the side borders
+        let height = area.height.saturating_sub(MAIN_CONTENT_ROWS);
+        let width = area.width.saturating_sub(2);
+        fit_icon(&mut icon_state, is_break, width, height)
     };
 
     // Calculate icon height dynamically
@@ -395,7 +1524,7 @@ fn draw_main_content(f: &mut Frame, area: Rect, app: &App) {
         .map(|(i, s)| {
             let line_color = if is_rainbow {
                 // Rainbow gradient: each line gets a different color
-                let (r, g, b) = get_rainbow_gradient_color(i, total_lines, app.rainbow_frame);
+                let (r, g, b) = rainbow.gradient(i, total_lines, app.rainbow_frame);
                 Color::Rgb(r, g, b)
             } else {
                 accent_color
@@ -406,27 +1535,35 @@ fn draw_main_content(f: &mut Frame, area: Rect, app: &App) {
 
     let icon_widget = Paragraph::new(icon_text)
         .alignment(Alignment::Center)
-        .block(Block::default().borders(Borders::LEFT | Borders::RIGHT));
+        .block(
+            Block::default()
+                .borders(Borders::LEFT | Borders::RIGHT)
+                .border_style(border),
+        );
     f.render_widget(icon_widget, chunks[0]);
 
     // Spacer (chunks[1]) - just draw borders
-    let spacer = Paragraph::new("").block(Block::default().borders(Borders::LEFT | Borders::RIGHT));
+    let spacer = Paragraph::new("").block(
+        Block::default()
+            .borders(Borders::LEFT | Borders::RIGHT)
+            .border_style(border),
+    );
     f.render_widget(spacer, chunks[1]);
 
     // Draw timer (chunks[2])
     let timer_text = Paragraph::new(app.timer.formatted_display_time())
         .style(Style::default().add_modifier(Modifier::BOLD))
         .alignment(Alignment::Center)
-        .block(Block::default().borders(Borders::LEFT | Borders::RIGHT));
+        .block(
+            Block::default()
+                .borders(Borders::LEFT | Borders::RIGHT)
+                .border_style(border),
+        );
     f.render_widget(timer_text, chunks[2]);
 
     // Draw status (chunks[3])
     let lang = &app.config.appearance.language;
-    let paused_text = if lang == "ja" {
-        "一時停止"
-    } else {
-        "PAUSED"
-    };
+    let paused_text = i18n::t_in(lang, "PAUSED");
     let (status_color, status_text) = match app.timer.state {
         TimerState::Work => {
             let color = if app.timer.is_paused {
@@ -445,6 +1582,26 @@ fn draw_main_content(f: &mut Frame, area: Rect, app: &App) {
             };
             (color, text)
         }
+        TimerState::Timer => {
+            let color = if app.timer.is_paused {
+                Color::Yellow
+            } else {
+                work_color
+            };
+            // Which timer is running, e.g. "TIMER · deep work"
+            let label = match app.config.focus.named_timer() {
+                Some(named) => {
+                    format!("{} · {}", app.timer.state.label_with_lang(lang), named.name)
+                }
+                None => app.timer.state.label_with_lang(lang).to_string(),
+            };
+            let text = if app.timer.is_paused {
+                format!("[ {} - {} ]", label, paused_text)
+            } else {
+                format!("[ {} ]", label)
+            };
+            (color, text)
+        }
         TimerState::ShortBreak => {
             let color = if app.timer.is_paused {
                 Color::Yellow
@@ -481,40 +1638,74 @@ fn draw_main_content(f: &mut Frame, area: Rect, app: &App) {
         }
     };
 
-    let status = Paragraph::new(status_text)
+    let mut status_lines = vec![Line::from(status_text)];
+    if let Some(goal) = goal_bar_line(app, area.width.saturating_sub(4) as usize) {
+        status_lines.push(goal);
+    }
+    let status = Paragraph::new(status_lines)
         .style(Style::default().fg(status_color))
         .alignment(Alignment::Center)
-        .block(Block::default().borders(Borders::LEFT | Borders::RIGHT));
+        .block(
+            Block::default()
+                .borders(Borders::LEFT | Borders::RIGHT)
+                .border_style(border),
+        );
     f.render_widget(status, chunks[3]);
 
     // Draw session info (chunks[4]) - prioritize time display
-    let hours = app.today_work_seconds / 3600;
-    let minutes = (app.today_work_seconds % 3600) / 60;
-    let today_display = if hours > 0 {
-        format!("{}h {}m", hours, minutes)
-    } else {
-        format!("{}m", minutes)
-    };
-    // Show tag if selected
+    let today_display = crate::format_duration(app.today_work_seconds);
+    // Show tag if selected, in its color
     let tag_display = if let Some(tag) = app.selected_tag() {
-        format!("  Tag: {}", tag.name)
+        vec![
+            Span::raw(format!("  {} ", t(app, "Tag:"))),
+            Span::styled(shown(app, &tag.name), Style::default().fg(tag_color(tag))),
+        ]
     } else if !app.available_tags.is_empty() {
-        "  Tag: -".to_string()
+        vec![Span::raw(format!("  {} -", t(app, "Tag:")))]
+    } else {
+        Vec::new()
+    };
+    // A routine's progress takes the place of the round
+    let round = match routine_progress(app) {
+        Some(progress) => fill(app, "Routine: {0}", &[&progress]),
+        None => fill(
+            app,
+            "Round: {0}/{1} {2}",
+            &[
+                &app.timer.session_count,
+                &app.timer.sessions_until_long_break,
+                &app.timer.cycle_dots(),
+            ],
+        ),
+    };
+    // Time-first layout: Today's time prominently, then session count.
+    // Placeholders until the stats have loaded.
+    let today_info = if app.is_loading() {
+        t(app, "Today: …  (loading)").to_string()
+    } else {
+        fill(
+            app,
+            "Today: {0}  ({1} sessions)",
+            &[&today_display, &app.today_sessions],
+        )
+    };
+    // Interruptions so far in this work session
+    let interruptions = if app.timer.state.is_focus() && app.interruptions > 0 {
+        format!("  ⚡{}", app.interruptions)
     } else {
         String::new()
     };
-    // Time-first layout: Today's time prominently, then session count
-    let session_info = Paragraph::new(format!(
-        "Today: {}  ({} sessions)    Round: {}/{}{}",
-        today_display,
-        app.today_sessions,
-        app.timer.session_count,
-        app.timer.sessions_until_long_break,
-        tag_display
-    ))
-    .style(Style::default().fg(secondary))
-    .alignment(Alignment::Center)
-    .block(Block::default().borders(Borders::LEFT | Borders::RIGHT));
+    let mut session_spans = vec![Span::raw(format!("{}    {}", today_info, round))];
+    session_spans.extend(tag_display);
+    session_spans.push(Span::raw(interruptions));
+    let session_info = Paragraph::new(Line::from(session_spans))
+        .style(Style::default().fg(secondary))
+        .alignment(Alignment::Center)
+        .block(
+            Block::default()
+                .borders(Borders::LEFT | Borders::RIGHT)
+                .border_style(border),
+        );
     f.render_widget(session_info, chunks[4]);
 
     // Draw context message (chunks[5])
@@ -528,54 +1719,168 @@ fn draw_main_content(f: &mut Frame, area: Rect, app: &App) {
         yesterday_seconds: app.yesterday_seconds,
         total_sessions: app.total_sessions,
     };
-    let context_msg =
-        get_context_message(app.timer.state, !app.timer.is_paused, lang, Some(&stats));
-    let context_widget = Paragraph::new(context_msg)
-        .style(
-            Style::default()
-                .fg(secondary)
-                .add_modifier(Modifier::ITALIC),
-        )
+    // A gentle nudge replaces the usual message once breaks are being skipped
+    let skip_warning = app.skip_penalty.and_then(|penalty| {
+        let warning = penalty.warning()?;
+        let quality = penalty.quality_focus(app.today_work_seconds);
+        let quality = crate::format_duration(quality);
+        Some(format!(
+            "🧘 {} · {}",
+            warning,
+            fill(app, "quality focus {0}", &[&quality])
+        ))
+    });
+    // The work-length tip waits for a paused work phase, so it never
+    // interrupts a running session
+    let tip = app
+        .work_length_tip
+        .as_ref()
+        .filter(|_| app.timer.state == TimerState::Work && app.timer.is_paused)
+        .map(|tip| format!("💡 {}", tip));
+    let capacity = app
+        .capacity_warning
+        .as_ref()
+        .filter(|_| app.timer.state == TimerState::Work && app.timer.is_paused)
+        .map(|warning| format!("📉 {}", warning));
+    let handoff = app
+        .handoff_notice
+        .as_ref()
+        .map(|notice| format!("📡 {}", notice));
+    // Stays up while the selected tag is past its daily budget
+    let over_budget = app
+        .budget_usage()
+        .filter(|usage| usage.is_over())
+        .map(|usage| {
+            fill(
+                app,
+                "⏳ {0} over budget: {1} of {2} today",
+                &[
+                    &shown(app, &usage.tag),
+                    &crate::format_duration(usage.seconds),
+                    &crate::format_duration(usage.budget_minutes as i32 * 60),
+                ],
+            )
+        });
+    let tmux = app.tmux_error.as_ref().map(|err| format!("⚠ tmux {}", err));
+    let dnd = app
+        .dnd_error
+        .as_ref()
+        .map(|err| fill(app, "⚠ do not disturb: {0}", &[err]));
+    let action = app.action_error.as_ref().map(|err| format!("⚠ {}", err));
+    let strict = app.strict_notice().map(|notice| format!("⛓ {}", notice));
+    let context_msg = strict
+        .or(handoff)
+        .or(tmux)
+        .or(dnd)
+        .or(action)
+        .or(over_budget)
+        .or(tip)
+        .or(capacity)
+        .or(skip_warning)
+        .unwrap_or_else(|| {
+            let on_break = matches!(
+                app.timer.state,
+                TimerState::ShortBreak | TimerState::LongBreak
+            );
+            if on_break && app.config.focus.break_suggestions {
+                get_break_activity(lang, &app.config.focus.custom_break_suggestions)
+            } else {
+                get_context_message(app.timer.state, !app.timer.is_paused, lang, Some(&stats))
+            }
+        });
+    let mut context_lines = vec![Line::from(Span::styled(
+        context_msg,
+        Style::default().add_modifier(Modifier::ITALIC),
+    ))];
+    // The week's plan sits on the line under the message
+    if let Some(plan) = &app.week_plan {
+        let summary = if app.locked {
+            format!("{}/{}", plan.done(), plan.planned())
+        } else {
+            plan.summary()
+        };
+        context_lines.push(Line::from(format!("🗓 {}  {}", t(app, "Plan"), summary)));
+    }
+    let context_widget = Paragraph::new(context_lines)
+        .style(Style::default().fg(secondary))
         .alignment(Alignment::Center)
-        .block(Block::default().borders(Borders::LEFT | Borders::RIGHT));
+        .block(
+            Block::default()
+                .borders(Borders::LEFT | Borders::RIGHT)
+                .border_style(border),
+        );
     f.render_widget(context_widget, chunks[5]);
 
     // Fill remaining space with borders (chunks[6])
-    let filler = Paragraph::new("").block(Block::default().borders(Borders::LEFT | Borders::RIGHT));
+    let filler = Paragraph::new("").block(
+        Block::default()
+            .borders(Borders::LEFT | Borders::RIGHT)
+            .border_style(border),
+    );
     f.render_widget(filler, chunks[6]);
 }
 
+/// Timer keys for strict mode, while it holds back quit, skip and reset
+fn strict_keys(app: &App) -> Option<&'static str> {
+    if !app.strict_active() {
+        return None;
+    }
+    match app.config.focus.strict {
+        StrictMode::Confirm => Some(STRICT_CONFIRM_KEYS),
+        _ => Some(STRICT_LOCK_KEYS),
+    }
+}
+
 fn draw_footer(f: &mut Frame, area: Rect, app: &App, is_settings: bool) {
     let secondary = app.theme.secondary.to_color();
 
-    let help_text = if is_settings {
-        if app.tag_input_mode {
-            "  Type tag name  [Enter] Add  [Esc] Cancel"
-        } else if app.editing {
-            "  [↑↓] Change  [Enter] Confirm  [Esc] Cancel"
+    let help_text = t(
+        app,
+        if app.locked {
+            LOCKED_KEYS
+        } else if is_settings {
+            if app.session_search_mode {
+                "  Search tags and notes  [Enter] Keep filter  [Esc] Clear"
+            } else if app.tag_input_mode {
+                "  Type tag name  [Enter] Add  [Esc] Cancel"
+            } else if app.session_history_selected() && !app.editing {
+                "  [↑↓/jk] Navigate  [Enter] Select  [/] Search  [Tab] Back  [q] Quit"
+            } else if app.editing {
+                "  [↑↓] Change  [Enter] Confirm  [Esc] Cancel"
+            } else {
+                "  [↑↓/jk] Navigate  [Enter] Select  [Tab] Back  [q] Quit"
+            }
+        } else if app.board_open && app.board.input.is_some() {
+            "  Type task title  [Enter] Add  [Esc] Cancel"
+        } else if app.board_open {
+            "  [←→↑↓] Move  [a] Add  [Enter] Bind  [<>] Column  [+-] Est.  [x] Del  [b] Close"
         } else {
-            "  [↑↓/jk] Navigate  [Enter] Select  [Tab] Back  [q] Quit"
-        }
-    } else {
-        // Timer view - show different help based on state
-        let is_break = matches!(
-            app.timer.state,
-            TimerState::ShortBreak | TimerState::LongBreak
-        );
-        let snooze_enabled = app.config.focus.break_snooze_enabled;
+            // Timer view - show different help based on state
+            let is_break = matches!(
+                app.timer.state,
+                TimerState::ShortBreak | TimerState::LongBreak
+            );
+            let snooze_enabled = app.config.focus.break_snooze_enabled;
 
-        if is_break && snooze_enabled {
-            "  [Space] Pause  [r] Reset  [s] Skip  [m] Mode  [z] Snooze  [Tab] Settings  [q] Quit"
-        } else if !app.available_tags.is_empty() {
-            "  [Space] Pause  [r] Reset  [s] Skip  [t] Tag  [m] Mode  [Tab] Settings  [q] Quit"
-        } else {
-            "  [Space] Pause  [r] Reset  [s] Skip  [m] Mode  [Tab] Settings  [q] Quit"
-        }
-    };
+            if let Some(keys) = strict_keys(app) {
+                keys
+            } else if is_break && snooze_enabled {
+                "  [Space] Pause  [r] Reset  [s] Skip  [m] Mode  [z] Snooze  [Tab] Settings  [q] Quit"
+            } else if !app.available_tags.is_empty() {
+                "  [Space] Pause  [r] Reset  [s] Skip  [t] Tag  [m] Mode  [Tab] Settings  [q] Quit"
+            } else {
+                "  [Space] Pause  [r] Reset  [s] Skip  [m] Mode  [Tab] Settings  [q] Quit"
+            }
+        },
+    );
 
     let footer = Paragraph::new(help_text)
         .style(Style::default().fg(secondary))
-        .block(Block::default().borders(Borders::BOTTOM | Borders::LEFT | Borders::RIGHT));
+        .block(
+            Block::default()
+                .borders(Borders::BOTTOM | Borders::LEFT | Borders::RIGHT)
+                .border_style(frame_border_style(app)),
+        );
 
     f.render_widget(footer, area);
 }
@@ -0,0 +1,194 @@
+//! Workspace detection
+//!
+//! Picks a tag from the current directory or tmux session name using the
+//! `[[workspaces]]` rules in config.toml, or from the `tag` of a project's
+//! `.sandoro.toml`
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::config::{ProjectConfig, WorkspaceRule};
+
+/// What matched a workspace rule
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WorkspaceSource {
+    /// Directory prefix of the rule that matched
+    Path(PathBuf),
+    /// tmux session name
+    Tmux(String),
+    /// `.sandoro.toml` that set the tag
+    Project(PathBuf),
+}
+
+/// A workspace detected at startup
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DetectedWorkspace {
+    pub tag: String,
+    pub source: WorkspaceSource,
+}
+
+/// Expand a leading `~` to the home directory
+fn expand_home(path: &str, home: Option<&Path>) -> PathBuf {
+    match (path.strip_prefix('~'), home) {
+        (Some(rest), Some(home)) => home.join(rest.trim_start_matches(['/', '\\'])),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Resolve the workspace for a directory and optional tmux session.
+///
+/// The longest matching path prefix wins; tmux rules are only used when no
+/// path rule matches.
+pub fn resolve(
+    rules: &[WorkspaceRule],
+    cwd: &Path,
+    tmux_session: Option<&str>,
+    home: Option<&Path>,
+) -> Option<DetectedWorkspace> {
+    let by_path = rules
+        .iter()
+        .filter_map(|rule| {
+            let prefix = expand_home(rule.path.as_deref()?, home);
+            cwd.starts_with(&prefix).then_some((rule, prefix))
+        })
+        .max_by_key(|(_, prefix)| prefix.components().count());
+
+    if let Some((rule, prefix)) = by_path {
+        return Some(DetectedWorkspace {
+            tag: rule.tag.clone(),
+            source: WorkspaceSource::Path(prefix),
+        });
+    }
+
+    let session = tmux_session?;
+    rules
+        .iter()
+        .find(|rule| rule.tmux.as_deref() == Some(session))
+        .map(|rule| DetectedWorkspace {
+            tag: rule.tag.clone(),
+            source: WorkspaceSource::Tmux(session.to_string()),
+        })
+}
+
+/// Name of the tmux session we're running in, if any
+fn tmux_session_name() -> Option<String> {
+    std::env::var_os("TMUX")?;
+    let output = Command::new("tmux")
+        .args(["display-message", "-p", "#S"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let name = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!name.is_empty()).then_some(name)
+}
+
+/// The tag a project's `.sandoro.toml` sets, if any
+pub fn from_project(project: &ProjectConfig) -> Option<DetectedWorkspace> {
+    Some(DetectedWorkspace {
+        tag: project.tag.clone()?,
+        source: WorkspaceSource::Project(project.path.clone()),
+    })
+}
+
+/// Detect the workspace for the current process
+pub fn detect(rules: &[WorkspaceRule]) -> Option<DetectedWorkspace> {
+    if rules.is_empty() {
+        return None;
+    }
+    let cwd = std::env::current_dir().ok()?;
+    let home = dirs::home_dir();
+    // Only ask tmux when a rule needs it
+    let tmux = if rules.iter().any(|r| r.tmux.is_some()) {
+        tmux_session_name()
+    } else {
+        None
+    };
+    resolve(rules, &cwd, tmux.as_deref(), home.as_deref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn path_rule(path: &str, tag: &str) -> WorkspaceRule {
+        WorkspaceRule {
+            path: Some(path.to_string()),
+            tmux: None,
+            tag: tag.to_string(),
+        }
+    }
+
+    fn tmux_rule(session: &str, tag: &str) -> WorkspaceRule {
+        WorkspaceRule {
+            path: None,
+            tmux: Some(session.to_string()),
+            tag: tag.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_path_prefix_with_home() {
+        let rules = vec![path_rule("~/work/clientA", "clientA")];
+        let home = Path::new("/home/me");
+        let detected = resolve(
+            &rules,
+            Path::new("/home/me/work/clientA/api"),
+            None,
+            Some(home),
+        )
+        .unwrap();
+        assert_eq!(detected.tag, "clientA");
+        assert_eq!(
+            detected.source,
+            WorkspaceSource::Path(PathBuf::from("/home/me/work/clientA"))
+        );
+    }
+
+    #[test]
+    fn test_home_with_backslash() {
+        let home = Path::new("/home/me");
+        assert_eq!(expand_home(r"~\work", Some(home)), home.join("work"));
+    }
+
+    #[test]
+    fn test_longest_prefix_wins() {
+        let rules = vec![
+            path_rule("/src", "code"),
+            path_rule("/src/clientA", "clientA"),
+        ];
+        let detected = resolve(&rules, Path::new("/src/clientA/web"), None, None).unwrap();
+        assert_eq!(detected.tag, "clientA");
+    }
+
+    #[test]
+    fn test_prefix_matches_whole_components() {
+        let rules = vec![path_rule("/src/client", "client")];
+        assert!(resolve(&rules, Path::new("/src/clientA"), None, None).is_none());
+    }
+
+    #[test]
+    fn test_tmux_session() {
+        let rules = vec![tmux_rule("clientB", "clientB")];
+        let detected = resolve(&rules, Path::new("/tmp"), Some("clientB"), None).unwrap();
+        assert_eq!(
+            detected.source,
+            WorkspaceSource::Tmux("clientB".to_string())
+        );
+        assert!(resolve(&rules, Path::new("/tmp"), Some("other"), None).is_none());
+    }
+
+    #[test]
+    fn test_path_beats_tmux() {
+        let rules = vec![tmux_rule("main", "general"), path_rule("/src/a", "a")];
+        let detected = resolve(&rules, Path::new("/src/a"), Some("main"), None).unwrap();
+        assert_eq!(detected.tag, "a");
+    }
+
+    #[test]
+    fn test_no_rules_match() {
+        let rules = vec![path_rule("/src/a", "a")];
+        assert!(resolve(&rules, Path::new("/home"), None, None).is_none());
+    }
+}
@@ -0,0 +1,236 @@
+//! Experience points and levels, for those who like a game on top
+//!
+//! Off unless `[xp] enabled = true`. Completed work earns XP, with bonuses
+//! for keeping a streak, reaching the daily goal and taking breaks in full.
+//! Every award is a row in `xp_events`, so the total can be rebuilt and
+//! exported with `sandoro level --json`.
+
+use anyhow::Result;
+
+use crate::config::Config;
+use crate::db::Database;
+
+/// XP for a completed work session, plus one per five minutes of it
+const SESSION_XP: i64 = 10;
+/// Per day of the current streak, up to `STREAK_BONUS_MAX`
+const STREAK_BONUS_PER_DAY: i64 = 2;
+const STREAK_BONUS_MAX: i64 = 20;
+/// Once a day, for the session that reaches the daily goal
+const GOAL_BONUS: i64 = 25;
+/// For a break taken in full rather than skipped
+const BREAK_XP: i64 = 5;
+
+/// Level titles; past the last one the title stays
+const TITLES: [&str; 10] = [
+    "Seedling",
+    "Sprout",
+    "Apprentice",
+    "Focused",
+    "Steady Hand",
+    "Deep Worker",
+    "Flow Adept",
+    "Time Sculptor",
+    "Zen Master",
+    "Sandoro Sage",
+];
+
+/// Why XP was awarded, as stored in `xp_events.reason`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reason {
+    Session,
+    Streak,
+    Goal,
+    Break,
+}
+
+impl Reason {
+    pub const ALL: [Reason; 4] = [Reason::Session, Reason::Streak, Reason::Goal, Reason::Break];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Reason::Session => "session",
+            Reason::Streak => "streak",
+            Reason::Goal => "goal",
+            Reason::Break => "break",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Reason::Session => "Sessions",
+            Reason::Streak => "Streak bonus",
+            Reason::Goal => "Daily goals",
+            Reason::Break => "Breaks taken",
+        }
+    }
+}
+
+/// Where a total of XP stands
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Level {
+    pub level: u32,
+    pub xp: i64,
+    /// XP earned since reaching this level
+    pub into_level: i64,
+    /// XP between this level and the next
+    pub level_span: i64,
+}
+
+impl Level {
+    pub fn title(&self) -> &'static str {
+        TITLES[(self.level as usize - 1).min(TITLES.len() - 1)]
+    }
+
+    /// XP left to the next level
+    pub fn remaining(&self) -> i64 {
+        self.level_span - self.into_level
+    }
+
+    pub fn percent(&self) -> u32 {
+        (self.into_level * 100 / self.level_span.max(1)) as u32
+    }
+
+    /// "Lv 3 Apprentice", for the header
+    pub fn badge(&self) -> String {
+        format!("Lv {} {}", self.level, self.title())
+    }
+}
+
+/// Total XP needed to reach `level`: 0, 100, 300, 600, 1000, ...
+pub fn threshold(level: u32) -> i64 {
+    let n = i64::from(level.max(1));
+    50 * n * (n - 1)
+}
+
+pub fn level_for(xp: i64) -> Level {
+    let xp = xp.max(0);
+    let mut level = 1;
+    while threshold(level + 1) <= xp {
+        level += 1;
+    }
+    Level {
+        level,
+        xp,
+        into_level: xp - threshold(level),
+        level_span: threshold(level + 1) - threshold(level),
+    }
+}
+
+/// XP for a completed work session: (reason, xp) for each part earned
+pub fn for_work(minutes: u32, streak: i32, reached_goal: bool) -> Vec<(Reason, i64)> {
+    let mut parts = vec![(Reason::Session, SESSION_XP + i64::from(minutes / 5))];
+    let streak_bonus = (i64::from(streak.max(0)) * STREAK_BONUS_PER_DAY).min(STREAK_BONUS_MAX);
+    if streak_bonus > 0 {
+        parts.push((Reason::Streak, streak_bonus));
+    }
+    if reached_goal {
+        parts.push((Reason::Goal, GOAL_BONUS));
+    }
+    parts
+}
+
+/// Whether today's totals meet the daily goal set in config
+pub fn daily_goal_met(config: &Config, sessions: i32, seconds: i32) -> bool {
+    let goals = &config.goals;
+    let any = goals.daily_sessions > 0 || goals.daily_minutes > 0;
+    any && (goals.daily_sessions == 0 || sessions >= goals.daily_sessions as i32)
+        && (goals.daily_minutes == 0 || seconds >= goals.daily_minutes as i32 * 60)
+}
+
+/// Record XP for a completed work session; returns the new total
+pub fn award_work(
+    db: &Database,
+    session_id: i64,
+    minutes: u32,
+    streak: i32,
+    reached_goal: bool,
+) -> Result<i64> {
+    for (reason, xp) in for_work(minutes, streak, reached_goal) {
+        db.add_xp(Some(session_id), reason.as_str(), xp)?;
+    }
+    db.total_xp()
+}
+
+/// Record XP for a break taken in full; returns the new total
+pub fn award_break(db: &Database) -> Result<i64> {
+    db.add_xp(None, Reason::Break.as_str(), BREAK_XP)?;
+    db.total_xp()
+}
+
+/// XP earned per reason, in `Reason::ALL` order
+pub fn breakdown(db: &Database) -> Result<Vec<(Reason, i64)>> {
+    let by_reason = db.xp_by_reason()?;
+    Ok(Reason::ALL
+        .into_iter()
+        .map(|reason| {
+            let xp = by_reason
+                .iter()
+                .find(|(r, _)| r == reason.as_str())
+                .map(|(_, xp)| *xp)
+                .unwrap_or(0);
+            (reason, xp)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_levels_and_titles() {
+        assert_eq!(level_for(0).level, 1);
+        assert_eq!(level_for(0).title(), "Seedling");
+        assert_eq!(level_for(99).remaining(), 1);
+        let third = level_for(350);
+        assert_eq!(
+            (third.level, third.into_level, third.level_span),
+            (3, 50, 300)
+        );
+        assert_eq!(third.badge(), "Lv 3 Apprentice");
+        assert_eq!(third.percent(), 16);
+        // Titles run out before levels do
+        assert_eq!(level_for(threshold(25)).title(), "Sandoro Sage");
+    }
+
+    #[test]
+    fn test_work_xp_bonuses() {
+        assert_eq!(for_work(25, 0, false), vec![(Reason::Session, 15)]);
+        assert_eq!(
+            for_work(50, 3, true),
+            vec![
+                (Reason::Session, 20),
+                (Reason::Streak, 6),
+                (Reason::Goal, GOAL_BONUS)
+            ]
+        );
+        assert_eq!(
+            for_work(25, 100, false)[1],
+            (Reason::Streak, STREAK_BONUS_MAX)
+        );
+    }
+
+    #[test]
+    fn test_daily_goal() {
+        let mut config = Config::default();
+        assert!(!daily_goal_met(&config, 10, 36_000));
+        config.goals.daily_sessions = 4;
+        assert!(!daily_goal_met(&config, 3, 36_000));
+        assert!(daily_goal_met(&config, 4, 0));
+        config.goals.daily_minutes = 120;
+        assert!(!daily_goal_met(&config, 4, 3600));
+        assert!(daily_goal_met(&config, 4, 7200));
+    }
+
+    #[test]
+    fn test_awards_are_stored() {
+        let db = Database::open_in_memory().unwrap();
+        assert_eq!(award_work(&db, 1, 25, 2, false).unwrap(), 19);
+        assert_eq!(award_break(&db).unwrap(), 19 + BREAK_XP);
+        let breakdown = breakdown(&db).unwrap();
+        assert_eq!(breakdown[0], (Reason::Session, 15));
+        assert_eq!(breakdown[1], (Reason::Streak, 4));
+        assert_eq!(breakdown[2], (Reason::Goal, 0));
+        assert_eq!(db.xp_log().unwrap().len(), 3);
+    }
+}
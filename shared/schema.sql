@@ -11,7 +11,7 @@ CREATE TABLE IF NOT EXISTS sessions (
     started_at DATETIME NOT NULL,
     ended_at DATETIME,
     duration_seconds INTEGER,
-    type TEXT NOT NULL CHECK (type IN ('work', 'short_break', 'long_break')),
+    type TEXT NOT NULL CHECK (type IN ('work', 'short_break', 'long_break', 'timer')),
     completed BOOLEAN DEFAULT FALSE
 );
 
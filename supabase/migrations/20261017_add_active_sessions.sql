@@ -0,0 +1,42 @@
+-- ==========================================
+-- Active sessions, for handing a session over between devices
+-- ==========================================
+
+-- One row per user: the work session running on one of their devices.
+-- The device running it refreshes heartbeat_at while it runs and deletes
+-- the row when it ends. `sandoro start --resume-remote` on another device
+-- sets handed_off_to, then replaces the row with its own (resumed_from
+-- pointing back at the original device).
+CREATE TABLE IF NOT EXISTS public.active_sessions (
+    user_id UUID PRIMARY KEY REFERENCES auth.users(id) ON DELETE CASCADE,
+    device_id TEXT NOT NULL,
+    device_name TEXT NOT NULL,
+    session_type TEXT NOT NULL CHECK (session_type IN ('work', 'short_break', 'long_break')),
+    paused BOOLEAN NOT NULL DEFAULT false,
+    remaining_seconds INTEGER NOT NULL,
+    work_minutes INTEGER NOT NULL,
+    tag TEXT,
+    heartbeat_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    -- Name of the device that took the session over
+    handed_off_to TEXT,
+    -- Device the session was taken over from
+    resumed_from TEXT
+);
+
+ALTER TABLE public.active_sessions ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY "Users can view their own active session"
+    ON public.active_sessions FOR SELECT
+    USING (auth.uid() = user_id);
+
+CREATE POLICY "Users can insert their own active session"
+    ON public.active_sessions FOR INSERT
+    WITH CHECK (auth.uid() = user_id);
+
+CREATE POLICY "Users can update their own active session"
+    ON public.active_sessions FOR UPDATE
+    USING (auth.uid() = user_id);
+
+CREATE POLICY "Users can delete their own active session"
+    ON public.active_sessions FOR DELETE
+    USING (auth.uid() = user_id);
@@ -0,0 +1,12 @@
+-- ==========================================
+-- Allow 'timer' sessions (timer focus mode)
+-- ==========================================
+
+-- Named countdowns and stopwatches are recorded with their own session
+-- type; they count as focused time like work sessions
+ALTER TABLE public.sessions
+DROP CONSTRAINT IF EXISTS sessions_session_type_check;
+
+ALTER TABLE public.sessions
+ADD CONSTRAINT sessions_session_type_check
+CHECK (session_type IN ('work', 'short_break', 'long_break', 'timer'));